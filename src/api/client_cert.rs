@@ -0,0 +1,120 @@
+use std::path::Path;
+
+/// The curl options needed to present a TLS client certificate.
+pub(super) trait ClientCertHandle {
+    fn ssl_cert(&mut self, cert: &Path) -> Result<(), curl::Error>;
+    fn ssl_key(&mut self, key: &Path) -> Result<(), curl::Error>;
+    fn key_password(&mut self, password: &str) -> Result<(), curl::Error>;
+}
+
+impl ClientCertHandle for curl::easy::Easy {
+    fn ssl_cert(&mut self, cert: &Path) -> Result<(), curl::Error> {
+        curl::easy::Easy::ssl_cert(self, cert)
+    }
+
+    fn ssl_key(&mut self, key: &Path) -> Result<(), curl::Error> {
+        curl::easy::Easy::ssl_key(self, key)
+    }
+
+    fn key_password(&mut self, password: &str) -> Result<(), curl::Error> {
+        curl::easy::Easy::key_password(self, password)
+    }
+}
+
+/// Configures the handle to present the given client certificate.
+///
+/// Without a key, curl reads the private key from the certificate file.
+pub(super) fn set_client_cert<H: ClientCertHandle>(
+    handle: &mut H,
+    cert: &Path,
+    key: Option<&Path>,
+    key_password: Option<&str>,
+) -> Result<(), curl::Error> {
+    handle.ssl_cert(cert)?;
+    if let Some(key) = key {
+        handle.ssl_key(key)?;
+    }
+    if let Some(key_password) = key_password {
+        handle.key_password(key_password)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::api::errors::{ApiError, ApiErrorKind};
+
+    #[derive(Default)]
+    struct RecordingHandle {
+        cert: Option<PathBuf>,
+        key: Option<PathBuf>,
+        key_password: Option<String>,
+    }
+
+    impl ClientCertHandle for RecordingHandle {
+        fn ssl_cert(&mut self, cert: &Path) -> Result<(), curl::Error> {
+            self.cert = Some(cert.to_owned());
+            Ok(())
+        }
+
+        fn ssl_key(&mut self, key: &Path) -> Result<(), curl::Error> {
+            self.key = Some(key.to_owned());
+            Ok(())
+        }
+
+        fn key_password(&mut self, password: &str) -> Result<(), curl::Error> {
+            self.key_password = Some(password.to_owned());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_set_client_cert_only() {
+        let mut handle = RecordingHandle::default();
+        set_client_cert(&mut handle, Path::new("client.pem"), None, None).unwrap();
+
+        assert_eq!(handle.cert, Some(PathBuf::from("client.pem")));
+        assert_eq!(handle.key, None);
+        assert_eq!(handle.key_password, None);
+    }
+
+    #[test]
+    fn test_set_client_cert_with_key() {
+        let mut handle = RecordingHandle::default();
+        set_client_cert(
+            &mut handle,
+            Path::new("client.crt"),
+            Some(Path::new("client.key")),
+            Some("secret"),
+        )
+        .unwrap();
+
+        assert_eq!(handle.cert, Some(PathBuf::from("client.crt")));
+        assert_eq!(handle.key, Some(PathBuf::from("client.key")));
+        assert_eq!(handle.key_password.as_deref(), Some("secret"));
+    }
+
+    #[test]
+    fn test_set_client_cert_on_curl_handle() {
+        let mut handle = curl::easy::Easy::new();
+        set_client_cert(
+            &mut handle,
+            Path::new("client.crt"),
+            Some(Path::new("client.key")),
+            Some("secret"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_certificate_problem_is_not_a_generic_failure() {
+        // CURLE_SSL_CERTPROBLEM
+        let err = curl::Error::new(58);
+        let err = ApiError::from(err);
+        assert_eq!(err.kind(), ApiErrorKind::ClientCertificateFailed);
+        assert!(format!("{:?}", anyhow::Error::from(err)).contains("SSL certificate"));
+    }
+}
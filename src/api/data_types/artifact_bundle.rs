@@ -0,0 +1,32 @@
+//! Data types for the artifact bundle endpoints.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An artifact bundle uploaded to a project, as listed by the server.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactBundle {
+    pub bundle_id: String,
+    pub date: DateTime<Utc>,
+    pub file_count: u64,
+    #[serde(default)]
+    pub associations: Vec<ArtifactBundleAssociation>,
+}
+
+/// A release and distribution an artifact bundle is associated with.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ArtifactBundleAssociation {
+    pub release: String,
+    pub dist: Option<String>,
+}
+
+/// An artifact the server would use to process an event, as returned by the
+/// artifact lookup endpoint.
+#[derive(Deserialize, Debug)]
+pub struct ArtifactLookupEntry {
+    pub id: String,
+    /// The kind of artifact, `bundle` for artifact bundles.
+    #[serde(rename = "type")]
+    pub ty: String,
+}
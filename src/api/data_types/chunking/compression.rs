@@ -1,5 +1,7 @@
 use std::fmt;
+use std::str::FromStr;
 
+use anyhow::{bail, Error};
 use serde::{Deserialize, Deserializer};
 
 #[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Default)]
@@ -11,6 +13,8 @@ pub enum ChunkCompression {
     Gzip = 10,
     /// Brotli compression
     Brotli = 20,
+    /// Zstandard compression
+    Zstd = 30,
 }
 
 impl ChunkCompression {
@@ -19,6 +23,7 @@ impl ChunkCompression {
             ChunkCompression::Uncompressed => "file",
             ChunkCompression::Gzip => "file_gzip",
             ChunkCompression::Brotli => "file_brotli",
+            ChunkCompression::Zstd => "file_zstd",
         }
     }
 }
@@ -29,10 +34,26 @@ impl fmt::Display for ChunkCompression {
             ChunkCompression::Uncompressed => write!(f, "uncompressed"),
             ChunkCompression::Gzip => write!(f, "gzip"),
             ChunkCompression::Brotli => write!(f, "brotli"),
+            ChunkCompression::Zstd => write!(f, "zstd"),
         }
     }
 }
 
+impl FromStr for ChunkCompression {
+    type Err = Error;
+
+    /// Parses the compression names accepted by the `--compression` argument.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "none" => ChunkCompression::Uncompressed,
+            "gzip" => ChunkCompression::Gzip,
+            "brotli" => ChunkCompression::Brotli,
+            "zstd" => ChunkCompression::Zstd,
+            _ => bail!("unknown compression '{}'", s),
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for ChunkCompression {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -41,6 +62,7 @@ impl<'de> Deserialize<'de> for ChunkCompression {
         Ok(match String::deserialize(deserializer)?.as_str() {
             "gzip" => ChunkCompression::Gzip,
             "brotli" => ChunkCompression::Brotli,
+            "zstd" => ChunkCompression::Zstd,
             // We do not know this compression, so we assume no compression
             _ => ChunkCompression::Uncompressed,
         })
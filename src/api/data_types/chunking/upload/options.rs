@@ -2,13 +2,22 @@ use serde::Deserialize;
 
 use super::{ChunkCompression, ChunkHashAlgorithm, ChunkUploadCapability};
 
+/// The number of chunks per request used if the server does not advertise it.
+const DEFAULT_CHUNKS_PER_REQUEST: u64 = 64;
+
+/// The maximum request size used if the server does not advertise it.
+const DEFAULT_MAX_REQUEST_SIZE: u64 = 32 * 1024 * 1024;
+
+/// The number of concurrent requests used if the server does not advertise it.
+const DEFAULT_CONCURRENCY: u8 = 4;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChunkUploadOptions {
     pub url: String,
-    #[serde(rename = "chunksPerRequest")]
+    #[serde(rename = "chunksPerRequest", default)]
     pub max_chunks: u64,
-    #[serde(rename = "maxRequestSize")]
+    #[serde(rename = "maxRequestSize", default)]
     pub max_size: u64,
     #[serde(default)]
     pub max_file_size: u64,
@@ -17,6 +26,7 @@ pub struct ChunkUploadOptions {
     #[expect(dead_code)]
     pub hash_algorithm: ChunkHashAlgorithm,
     pub chunk_size: u64,
+    #[serde(default)]
     pub concurrency: u8,
     #[serde(default)]
     pub compression: Vec<ChunkCompression>,
@@ -29,6 +39,37 @@ impl ChunkUploadOptions {
     pub fn supports(&self, capability: ChunkUploadCapability) -> bool {
         self.accept.contains(&capability)
     }
+
+    /// Returns the maximum number of chunks to send in one request.
+    ///
+    /// Like the other limits, this falls back to a default if the server does
+    /// not advertise it or advertises zero.
+    pub fn chunks_per_request(&self) -> u64 {
+        non_zero_or(self.max_chunks, DEFAULT_CHUNKS_PER_REQUEST)
+    }
+
+    /// Returns the maximum combined size of the chunks sent in one request.
+    pub fn max_request_size(&self) -> u64 {
+        non_zero_or(self.max_size, DEFAULT_MAX_REQUEST_SIZE)
+    }
+
+    /// Returns the maximum number of requests to send concurrently.
+    pub fn concurrency(&self) -> usize {
+        non_zero_or(self.concurrency, DEFAULT_CONCURRENCY).into()
+    }
+
+    /// Returns the maximum size of a single file, if the server limits it.
+    pub fn max_file_size(&self) -> Option<u64> {
+        (self.max_file_size > 0).then_some(self.max_file_size)
+    }
+}
+
+fn non_zero_or<T: Default + PartialEq>(value: T, default: T) -> T {
+    if value == T::default() {
+        default
+    } else {
+        value
+    }
 }
 
 fn default_chunk_upload_accept() -> Vec<ChunkUploadCapability> {
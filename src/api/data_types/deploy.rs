@@ -6,6 +6,8 @@ use std::borrow::Cow;
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Deploy<'d> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "environment")]
     pub env: Cow<'d, str>,
     pub name: Option<Cow<'d, str>>,
@@ -1,7 +1,15 @@
 //! Data types used in the api module
 
+mod artifact_bundle;
 mod chunking;
 mod deploy;
+mod monitor;
+mod oauth;
+mod org_auth_token;
 
+pub use self::artifact_bundle::*;
 pub use self::chunking::*;
 pub use self::deploy::*;
+pub use self::monitor::*;
+pub use self::oauth::*;
+pub use self::org_auth_token::*;
@@ -0,0 +1,79 @@
+//! Data types for cron monitors.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Monitor {
+    pub id: String,
+    pub slug: String,
+    pub name: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<MonitorSettings>,
+    #[serde(default)]
+    pub environments: Vec<MonitorEnvironment>,
+}
+
+impl Monitor {
+    /// Returns the most recent check-in across all environments of this monitor.
+    pub fn last_check_in(&self) -> Option<DateTime<Utc>> {
+        self.environments
+            .iter()
+            .filter_map(|env| env.last_check_in)
+            .max()
+    }
+}
+
+/// The schedule configuration of a monitor, as stored on the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorSettings {
+    pub schedule_type: String,
+    pub schedule: MonitorScheduleValue,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkin_margin: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runtime: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+/// A monitor schedule, either a crontab expression or an interval
+/// given as `[value, unit]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MonitorScheduleValue {
+    Crontab(String),
+    Interval(u64, String),
+}
+
+impl fmt::Display for MonitorScheduleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorScheduleValue::Crontab(crontab) => write!(f, "{crontab}"),
+            MonitorScheduleValue::Interval(1, unit) => write!(f, "every {unit}"),
+            MonitorScheduleValue::Interval(value, unit) => write!(f, "every {value} {unit}s"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MonitorEnvironment {
+    pub name: String,
+    pub status: String,
+    #[serde(rename = "lastCheckIn")]
+    pub last_check_in: Option<DateTime<Utc>>,
+}
+
+/// The payload used to create or update a monitor.
+#[derive(Debug, Serialize)]
+pub struct NewMonitor<'a> {
+    pub project: &'a str,
+    pub name: &'a str,
+    pub slug: &'a str,
+    #[serde(rename = "type")]
+    pub ty: &'a str,
+    pub config: MonitorSettings,
+}
@@ -0,0 +1,43 @@
+//! Data types for the OAuth device authorization flow.
+
+use serde::Deserialize;
+
+/// The response to a device authorization request.
+#[derive(Deserialize, Debug)]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: Option<String>,
+    pub expires_in: u64,
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+/// Clients must poll every five seconds if the server does not specify an interval.
+fn default_interval() -> u64 {
+    5
+}
+
+/// The state of a device authorization, as returned by the token endpoint.
+#[derive(Debug)]
+pub enum DeviceTokenStatus {
+    /// The user has not yet completed the authorization.
+    Pending,
+    /// The client polls too often and must increase its interval.
+    SlowDown,
+    /// The user declined the authorization.
+    Denied,
+    /// The device code expired before the user completed the authorization.
+    Expired,
+    /// The user approved the authorization and a token was issued.
+    Token(String),
+}
+
+/// The raw response of the token endpoint, which is either a token or an error.
+#[derive(Deserialize, Debug)]
+pub(in crate::api) struct DeviceTokenResponse {
+    pub access_token: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
@@ -0,0 +1,35 @@
+//! Data types for the organization auth token endpoints.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An organization auth token, as listed by the server.
+///
+/// The server never returns the value of existing tokens, only their last characters.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OrgAuthToken {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub token_last_characters: Option<String>,
+    pub date_created: DateTime<Utc>,
+    pub date_last_used: Option<DateTime<Utc>>,
+}
+
+/// A newly created organization auth token, which includes the token value.
+#[derive(Deserialize, Debug)]
+pub struct CreatedOrgAuthToken {
+    #[serde(flatten)]
+    pub info: OrgAuthToken,
+    pub token: String,
+}
+
+/// The request to create a new organization auth token.
+#[derive(Serialize, Debug)]
+pub struct CreateOrgAuthToken<'a> {
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<&'a str>>,
+}
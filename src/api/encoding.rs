@@ -24,7 +24,7 @@ const PATH_SEGMENT_ENCODE_SET: AsciiSet = QUERY_ENCODE_SET
 pub(super) struct PathArg<A: Display>(pub(super) A);
 
 /// Wrapper that escapes arguments for URL query segments.
-pub(super) struct QueryArg<A: Display>(pub(super) A);
+pub(crate) struct QueryArg<A: Display>(pub(crate) A);
 
 impl<A: Display> Display for PathArg<A> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
@@ -4,7 +4,13 @@ use super::{
 };
 use crate::{api::errors::ApiError, constants::USER_AGENT};
 use log::debug;
-use sentry::{types::Dsn, Envelope};
+use sentry::{
+    protocol::{Attachment, EnvelopeItem},
+    types::Dsn,
+    Envelope,
+};
+use std::fs::File;
+use std::path::Path;
 use std::sync::Arc;
 
 pub struct EnvelopesApi {
@@ -22,11 +28,24 @@ impl EnvelopesApi {
     }
 
     pub fn send_envelope(&self, envelope: impl Into<Envelope>) -> ApiResult<ApiResponse> {
-        let mut body = vec![];
-        envelope
-            .into()
-            .to_writer(&mut body)
-            .map_err(|e| ApiError::with_source(ApiErrorKind::CannotSerializeEnvelope, e))?;
+        let envelope = envelope.into();
+        let categories = rate_limit_categories(&envelope);
+        self.send_serialized_envelope(serialize_envelope(envelope)?, categories)
+    }
+
+    /// Sends an envelope that has already been serialized.
+    ///
+    /// Since the items of the envelope are not known, it is only retried after
+    /// the rate limits that apply to all data categories.
+    pub fn send_envelope_bytes(&self, body: Vec<u8>) -> ApiResult<ApiResponse> {
+        self.send_serialized_envelope(body, vec![])
+    }
+
+    fn send_serialized_envelope(
+        &self,
+        body: Vec<u8>,
+        categories: Vec<&'static str>,
+    ) -> ApiResult<ApiResponse> {
         let url = self.dsn.envelope_api_url();
         let auth = self.dsn.to_auth(Some(USER_AGENT));
         debug!("Sending envelope:\n{}", String::from_utf8_lossy(&body));
@@ -34,7 +53,90 @@ impl EnvelopesApi {
             .request(Method::Post, url.as_str(), None)?
             .with_header("X-Sentry-Auth", &auth.to_string())?
             .with_body(body)?
+            .with_timeout(self.api.config.get_transfer_timeout())?
+            .with_rate_limit_categories(categories)
+            .send()?
+            .into_result()
+    }
+
+    /// Sends the envelope stored in the given file as is, streaming it to the
+    /// server without reading it into memory.
+    pub fn send_envelope_file(&self, file: File) -> ApiResult<ApiResponse> {
+        let url = self.dsn.envelope_api_url();
+        let auth = self.dsn.to_auth(Some(USER_AGENT));
+        self.api
+            .request(Method::Post, url.as_str(), None)?
+            .with_header("X-Sentry-Auth", &auth.to_string())?
+            .with_header("Content-Type", "application/x-sentry-envelope")?
+            .with_file_body(file)?
+            .with_timeout(self.api.config.get_transfer_timeout())?
             .send()?
             .into_result()
     }
+
+    /// Sends a minidump to the minidump endpoint as multipart form data.
+    ///
+    /// The event payload is sent in the `sentry` field and is merged into the
+    /// event created from the minidump. Attachments are sent as additional
+    /// file fields named after their file names.
+    pub fn send_minidump(
+        &self,
+        minidump: &Path,
+        event: &[u8],
+        attachments: &[Attachment],
+    ) -> ApiResult<ApiResponse> {
+        let mut url = self.dsn.envelope_api_url();
+        url.set_path(&url.path().replace("/envelope/", "/minidump/"));
+        let auth = self.dsn.to_auth(Some(USER_AGENT));
+
+        let mut form = curl::easy::Form::new();
+        form.part("upload_file_minidump").file(minidump).add()?;
+        form.part("sentry")
+            .contents(event)
+            .content_type("application/json")
+            .add()?;
+        for attachment in attachments {
+            let mut part = form.part(&attachment.filename);
+            part.buffer(&attachment.filename, attachment.buffer.clone());
+            if let Some(content_type) = &attachment.content_type {
+                part.content_type(content_type);
+            }
+            part.add()?;
+        }
+
+        debug!("Sending minidump {}", minidump.display());
+        self.api
+            .request(Method::Post, url.as_str(), None)?
+            .with_header("X-Sentry-Auth", &auth.to_string())?
+            .with_form_data(form)?
+            .with_timeout(self.api.config.get_transfer_timeout())?
+            .send()?
+            .into_result()
+    }
+}
+
+/// Returns the rate limit data categories of the items in the envelope.
+fn rate_limit_categories(envelope: &Envelope) -> Vec<&'static str> {
+    envelope
+        .items()
+        .filter_map(|item| match item {
+            EnvelopeItem::Event(_) => Some("error"),
+            EnvelopeItem::Transaction(_) => Some("transaction"),
+            EnvelopeItem::SessionUpdate(_) | EnvelopeItem::SessionAggregates(_) => Some("session"),
+            EnvelopeItem::Attachment(_) => Some("attachment"),
+            EnvelopeItem::MonitorCheckIn(_) => Some("monitor"),
+            EnvelopeItem::Statsd(_) => Some("metric_bucket"),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Serializes an envelope into the format it is sent and stored in.
+pub fn serialize_envelope(envelope: impl Into<Envelope>) -> ApiResult<Vec<u8>> {
+    let mut body = vec![];
+    envelope
+        .into()
+        .to_writer(&mut body)
+        .map_err(|e| ApiError::with_source(ApiErrorKind::CannotSerializeEnvelope, e))?;
+    Ok(body)
 }
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use super::SentryError;
+
 #[derive(Debug, thiserror::Error)]
 pub struct ApiError {
     inner: ApiErrorKind,
@@ -26,12 +28,24 @@ pub(in crate::api) enum ApiErrorKind {
     ResourceNotFound,
     #[error("Project not found. Ensure that you configured the correct project and organization.")]
     ProjectNotFound,
+    #[error("Team not found. Ensure that you configured the correct team and organization.")]
+    TeamNotFound,
     #[error("Release not found. Ensure that you configured the correct release, project, and organization.")]
     ReleaseNotFound,
     #[error("chunk upload endpoint not supported by sentry server")]
     ChunkUploadNotSupported,
+    #[error("artifact bundle upload not supported by sentry server")]
+    ArtifactBundlesNotSupported,
     #[error("API request failed")]
     RequestFailed,
+    #[error("API request timed out")]
+    RequestTimedOut,
+    #[error("could not use the TLS client certificate")]
+    ClientCertificateFailed,
+    #[error(
+        "Managing organization auth tokens requires an auth token with the `org:admin` scope."
+    )]
+    OrgAdminScopeRequired,
     #[error("could not compress data")]
     CompressionFailed,
     #[error("region overrides cannot be applied to absolute urls")]
@@ -40,6 +54,8 @@ pub(in crate::api) enum ApiErrorKind {
         "Auth token is required for this request. Please run `sentry-cli login` and try again!"
     )]
     AuthMissing,
+    #[error("Could not get an auth token from auth.token_cmd or SENTRY_AUTH_TOKEN_CMD.")]
+    AuthTokenCommandFailed,
     #[error(
         "DSN missing. Please set the `SENTRY_DSN` environment variable to your project's DSN."
     )]
@@ -48,6 +64,25 @@ pub(in crate::api) enum ApiErrorKind {
     ErrorPreparingRequest,
 }
 
+/// The class of a failed API request, which determines the exit code of the command.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ApiErrorClass {
+    /// The request was not authenticated or not permitted.
+    Auth,
+    /// The request failed on the server or the network.
+    Server,
+}
+
+impl ApiErrorClass {
+    /// Returns the exit code for commands failing with this class of errors.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ApiErrorClass::Auth => 3,
+            ApiErrorClass::Server => 1,
+        }
+    }
+}
+
 impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(&self.inner, f)
@@ -69,6 +104,49 @@ impl ApiError {
         self.inner
     }
 
+    /// Returns the HTTP status of the response, if the request failed on the server.
+    fn status(&self) -> Option<u32> {
+        self.source
+            .as_ref()
+            .and_then(|source| source.downcast_ref::<SentryError>())
+            .map(|err| err.status)
+    }
+
+    /// Classifies this error as an authentication or a server error.
+    pub fn class(&self) -> ApiErrorClass {
+        match (self.inner, self.status()) {
+            (ApiErrorKind::AuthMissing | ApiErrorKind::AuthTokenCommandFailed, _)
+            | (_, Some(401 | 403)) => ApiErrorClass::Auth,
+            _ => ApiErrorClass::Server,
+        }
+    }
+
+    /// Indicates that the server rejected the request because of a rate limit.
+    pub fn is_rate_limited(&self) -> bool {
+        self.status() == Some(429)
+    }
+
+    /// Indicates that the request did not make it to the server or got no
+    /// response, for instance because the network is down.
+    pub fn is_network_error(&self) -> bool {
+        match self.inner {
+            ApiErrorKind::RequestTimedOut => true,
+            ApiErrorKind::RequestFailed => self
+                .source
+                .as_ref()
+                .map_or(false, |source| source.is::<curl::Error>()),
+            _ => false,
+        }
+    }
+
+    /// Indicates that the request was aborted because it exceeded a timeout.
+    pub(in crate::api) fn is_timeout(&self) -> bool {
+        self.source
+            .as_ref()
+            .and_then(|source| source.downcast_ref::<curl::Error>())
+            .map_or(false, curl::Error::is_operation_timedout)
+    }
+
     fn set_source<E: Into<anyhow::Error>>(mut self, source: E) -> ApiError {
         self.source = Some(source.into());
         self
@@ -86,7 +164,13 @@ impl From<ApiErrorKind> for ApiError {
 
 impl From<curl::Error> for ApiError {
     fn from(err: curl::Error) -> ApiError {
-        ApiError::from(ApiErrorKind::RequestFailed).set_source(err)
+        // Problems with the local certificate or key are not worth retrying.
+        let kind = if err.is_ssl_certproblem() {
+            ApiErrorKind::ClientCertificateFailed
+        } else {
+            ApiErrorKind::RequestFailed
+        };
+        ApiError::from(kind).set_source(err)
     }
 }
 
@@ -1,7 +1,8 @@
 mod api_error;
 mod sentry_error;
 
-pub(super) use api_error::{ApiError, ApiErrorKind};
+pub use api_error::ApiError;
+pub(super) use api_error::ApiErrorKind;
 pub(super) use sentry_error::SentryError;
 
 #[derive(Clone, Debug, thiserror::Error)]
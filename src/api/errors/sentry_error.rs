@@ -1,10 +1,119 @@
 use std::fmt;
 
+use serde_json::Value;
+
+/// The longest plain text body that is shown as the detail of an error.
+const MAX_TEXT_DETAIL_LEN: usize = 200;
+
+/// The keys under which Sentry puts the message of an error response.
+const DETAIL_KEYS: &[&str] = &["detail", "message", "error"];
+
 #[derive(Debug, thiserror::Error)]
 pub(in crate::api) struct SentryError {
     pub(in crate::api) status: u32,
     pub(in crate::api) detail: Option<String>,
-    pub(in crate::api) extra: Option<serde_json::Value>,
+    /// Field-level validation errors, formatted as `field: message`.
+    pub(in crate::api) fields: Vec<String>,
+}
+
+impl SentryError {
+    /// Creates an error from the body of a failed response.
+    ///
+    /// JSON bodies may contain a message, field-level validation errors as
+    /// reported by Django REST framework, or both. Other bodies are only used
+    /// if they are a short line of plain text; HTML error pages are dropped.
+    pub(in crate::api) fn from_body(status: u32, body: &[u8]) -> SentryError {
+        let mut error = SentryError {
+            status,
+            detail: None,
+            fields: vec![],
+        };
+
+        match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Object(map)) => {
+                for (key, value) in &map {
+                    let is_detail = DETAIL_KEYS.contains(&key.as_str());
+                    if is_detail && error.detail.is_none() {
+                        error.detail = message(value);
+                        if error.detail.is_some() {
+                            continue;
+                        }
+                    }
+                    match value {
+                        // Validation errors are sometimes wrapped in the detail
+                        Value::Object(fields) if is_detail => {
+                            for (key, value) in fields {
+                                collect_fields(key, value, &mut error.fields);
+                            }
+                        }
+                        _ => collect_fields(key, value, &mut error.fields),
+                    }
+                }
+            }
+            Ok(value) => error.detail = message(&value),
+            Err(_) => error.detail = text_detail(body),
+        }
+
+        error
+    }
+}
+
+/// Returns the message contained in a JSON value.
+///
+/// Objects are searched for a message under the usual keys. Lists of messages
+/// are joined into a single line.
+fn message(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) if s.is_empty() => None,
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::Array(values) => {
+            let messages = values.iter().filter_map(message).collect::<Vec<_>>();
+            (!messages.is_empty()).then(|| messages.join(" "))
+        }
+        Value::Object(map) => DETAIL_KEYS
+            .iter()
+            .find_map(|key| map.get(*key).and_then(message)),
+    }
+}
+
+/// Flattens nested validation errors into `field: message` lines.
+///
+/// Nested fields are joined with dots, for example `refs.0.commit`.
+fn collect_fields(path: &str, value: &Value, fields: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                collect_fields(&format!("{path}.{key}"), value, fields);
+            }
+        }
+        Value::Array(values) if values.iter().any(|v| v.is_object() || v.is_array()) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_fields(&format!("{path}.{index}"), value, fields);
+            }
+        }
+        _ => {
+            if let Some(message) = message(value) {
+                fields.push(format!("{path}: {message}"));
+            }
+        }
+    }
+}
+
+/// Returns the body as detail if it is a short line of plain text.
+fn text_detail(body: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(body);
+    let text = text.trim();
+    if text.is_empty()
+        || text.starts_with('<')
+        || text.contains('\n')
+        || text.len() > MAX_TEXT_DETAIL_LEN
+    {
+        None
+    } else {
+        Some(text.to_owned())
+    }
 }
 
 impl fmt::Display for SentryError {
@@ -17,9 +126,14 @@ impl fmt::Display for SentryError {
                 match self.status {
                     400 => "bad request",
                     401 => "unauthorized",
+                    403 => "forbidden",
                     404 => "not found",
+                    409 => "conflict",
+                    413 => "request too large",
+                    429 => "too many requests",
                     500 => "internal server error",
                     502 => "bad gateway",
+                    503 => "service unavailable",
                     504 => "gateway timeout",
                     _ => "unknown error",
                 }
@@ -28,9 +142,96 @@ impl fmt::Display for SentryError {
             },
             self.status
         )?;
-        if let Some(ref extra) = self.extra {
-            write!(f, "\n  {extra:?}")?;
+        for field in &self.fields {
+            write!(f, "\n  {field}")?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use super::*;
+
+    fn format_body(status: u32, body: &str) -> String {
+        SentryError::from_body(status, body.as_bytes()).to_string()
+    }
+
+    #[test]
+    fn test_string_detail() {
+        assert_snapshot!(format_body(
+            409,
+            r#"{"detail": "An event with the same ID already exists (abc123)"}"#
+        ));
+    }
+
+    #[test]
+    fn test_error_key() {
+        assert_snapshot!(format_body(400, r#"{"error": "Invalid release name"}"#));
+    }
+
+    #[test]
+    fn test_nested_detail() {
+        assert_snapshot!(format_body(
+            429,
+            r#"{"detail": {"code": "quota_exceeded", "message": "Monthly quota exceeded", "extra": {}}}"#
+        ));
+    }
+
+    #[test]
+    fn test_nested_validation_errors() {
+        assert_snapshot!(format_body(
+            400,
+            r#"{"detail": {"name": ["This field is required."]}}"#
+        ));
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        assert_snapshot!(format_body(
+            400,
+            r#"{
+                "version": ["Ensure this field has no more than 250 characters."],
+                "projects": ["This field is required.", "Invalid project slug."],
+                "refs": [{}, {"commit": ["This field may not be blank."]}]
+            }"#
+        ));
+    }
+
+    #[test]
+    fn test_detail_with_validation_errors() {
+        assert_snapshot!(format_body(
+            400,
+            r#"{"detail": "Invalid dsym", "file": {"name": "Too long"}}"#
+        ));
+    }
+
+    #[test]
+    fn test_list_of_messages() {
+        assert_snapshot!(format_body(
+            400,
+            r#"["Release with this version already exists."]"#
+        ));
+    }
+
+    #[test]
+    fn test_html_error_page() {
+        assert_snapshot!(format_body(
+            413,
+            "<html>\r\n<head><title>413 Request Entity Too Large</title></head>\r\n\
+             <body><center><h1>413 Request Entity Too Large</h1></center></body>\r\n</html>\r\n"
+        ));
+    }
+
+    #[test]
+    fn test_plain_text() {
+        assert_snapshot!(format_body(502, "upstream connect error\n"));
+    }
+
+    #[test]
+    fn test_empty_body() {
+        assert_snapshot!(format_body(500, ""));
+    }
+}
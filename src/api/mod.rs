@@ -5,6 +5,7 @@
 
 pub mod envelopes_api;
 
+mod client_cert;
 mod connection_manager;
 mod data_types;
 mod encoding;
@@ -17,12 +18,12 @@ use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fmt;
 use std::fs::File;
-use std::io::{self, Read, Write};
+use std::io::{self, Read, Seek, Write};
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{format_err, Context, Result};
 use backoff::backoff::Backoff;
 use brotli2::write::BrotliEncoder;
 #[cfg(target_os = "macos")]
@@ -42,24 +43,32 @@ use serde::{Deserialize, Serialize};
 use sha1_smol::Digest;
 use symbolic::common::DebugId;
 use symbolic::debuginfo::ObjectKind;
+use url::Url;
 use uuid::Uuid;
 
 use crate::api::errors::ProjectRenamedError;
-use crate::config::{Auth, Config};
-use crate::constants::{ARCH, DEFAULT_URL, EXT, PLATFORM, RELEASE_REGISTRY_LATEST_URL, VERSION};
+use crate::config::{header_name, Auth, Config};
+use crate::constants::{
+    ARCH, DEFAULT_API_TIMEOUT, DEFAULT_CONNECT_TIMEOUT, DEFAULT_URL, EXT, MAX_RATE_LIMIT_DELAY,
+    OAUTH_CLIENT_ID, OAUTH_SCOPES, PLATFORM, REQUEST_COMPRESSION_THRESHOLD, VERSION,
+};
+use crate::utils::chunks::{load_cached_chunk_upload_options, store_cached_chunk_upload_options};
 use crate::utils::file_upload::UploadContext;
 use crate::utils::http::{self, is_absolute_url};
 use crate::utils::progress::{ProgressBar, ProgressBarMode};
-use crate::utils::retry::{get_default_backoff, DurationAsMilliseconds};
+use crate::utils::retry::{get_default_backoff, with_jitter, DurationAsMilliseconds};
 use crate::utils::sourcemaps::get_sourcemap_reference_from_headers;
 use crate::utils::ui::{capitalize_string, make_byte_progress_bar};
+use crate::utils::update::UpdateChannel;
 
 use self::pagination::Pagination;
 use connection_manager::CurlConnectionManager;
-use encoding::{PathArg, QueryArg};
-use errors::{ApiError, ApiErrorKind, ApiResult, SentryError};
+use encoding::PathArg;
+pub(crate) use encoding::QueryArg;
+use errors::{ApiErrorKind, ApiResult, SentryError};
 
 pub use self::data_types::*;
+pub use self::errors::ApiError;
 
 lazy_static! {
     static ref API: Mutex<Option<Arc<Api>>> = Mutex::new(None);
@@ -110,11 +119,19 @@ impl fmt::Display for Method {
 pub struct ApiRequest {
     handle: r2d2::PooledConnection<CurlConnectionManager>,
     headers: curl::easy::List,
+    global_headers: Vec<String>,
     is_authenticated: bool,
     body: Option<Vec<u8>>,
+    body_file: Option<File>,
     progress_bar_mode: ProgressBarMode,
     max_retries: u32,
     retry_on_statuses: &'static [u32],
+    max_rate_limit_retries: u32,
+    rate_limit_categories: Vec<&'static str>,
+    url: String,
+    timeout: std::time::Duration,
+    connect_timeout: std::time::Duration,
+    compress_json: bool,
 }
 
 /// Represents an API response.
@@ -129,9 +146,13 @@ impl<'a> TryFrom<&'a Api> for AuthenticatedApi<'a> {
     type Error = ApiError;
 
     fn try_from(api: &'a Api) -> ApiResult<AuthenticatedApi<'a>> {
-        match api.config.get_auth() {
-            Some(_) => Ok(AuthenticatedApi { api }),
-            None => Err(ApiErrorKind::AuthMissing.into()),
+        match api.config.resolve_auth() {
+            Ok(Some(_)) => Ok(AuthenticatedApi { api }),
+            Ok(None) => Err(ApiErrorKind::AuthMissing.into()),
+            Err(err) => Err(ApiError::with_source(
+                ApiErrorKind::AuthTokenCommandFailed,
+                err,
+            )),
         }
     }
 }
@@ -210,6 +231,21 @@ impl Api {
         Ok((url.into_owned(), auth))
     }
 
+    /// Checks whether the host of the given URL is excluded from proxying.
+    fn should_bypass_proxy(&self, url: &str) -> bool {
+        let Some(no_proxy) = self.config.get_no_proxy() else {
+            return false;
+        };
+
+        match Url::parse(url) {
+            Ok(url) => match url.host_str() {
+                Some(host) => http::is_no_proxy_host(&no_proxy, host, url.port_or_known_default()),
+                None => false,
+            },
+            Err(_) => false,
+        }
+    }
+
     fn construct_api_request(
         &self,
         method: Method,
@@ -227,17 +263,35 @@ impl Api {
         }
         handle.ssl_options(&ssl_opts)?;
 
-        if let Some(proxy_url) = self.config.get_proxy_url() {
-            handle.proxy(&proxy_url)?;
-        }
-        if let Some(proxy_username) = self.config.get_proxy_username() {
-            handle.proxy_username(proxy_username)?;
-        }
-        if let Some(proxy_password) = self.config.get_proxy_password() {
-            handle.proxy_password(proxy_password)?;
+        if self.should_bypass_proxy(url) {
+            handle.noproxy("*")?;
+        } else if let Some(proxy) = self.config.get_proxy() {
+            debug!("using proxy {proxy}");
+            handle.proxy(&proxy.url)?;
+            set_proxy_credentials(
+                &mut handle,
+                proxy.username.as_deref(),
+                proxy.password.as_deref(),
+            )?;
+        } else {
+            // curl may still pick up a proxy from the environment, such as `https_proxy`.
+            set_proxy_credentials(
+                &mut handle,
+                self.config.get_proxy_username().as_deref(),
+                self.config.get_proxy_password().as_deref(),
+            )?;
         }
         handle.ssl_verify_host(self.config.should_verify_ssl())?;
         handle.ssl_verify_peer(self.config.should_verify_ssl())?;
+        if let Some(cert) = self.config.get_client_cert() {
+            debug!("using client certificate {}", cert.display());
+            client_cert::set_client_cert(
+                &mut *handle,
+                &cert,
+                self.config.get_client_key().as_deref(),
+                self.config.get_client_key_password().as_deref(),
+            )?;
+        }
 
         // This toggles gzipping, useful for uploading large files
         handle.transfer_encoding(self.config.allow_transfer_encoding())?;
@@ -245,7 +299,15 @@ impl Api {
         let env = self.config.get_pipeline_env();
         let headers = self.config.get_headers();
 
-        ApiRequest::create(handle, &method, url, auth, env, headers)
+        let mut request = ApiRequest::create(handle, &method, url, auth, env, headers)?;
+        request.max_rate_limit_retries = self
+            .config
+            .get_max_retry_count()
+            .map_err(|err| ApiError::with_source(ApiErrorKind::ErrorPreparingRequest, err))?;
+        request.timeout = self.config.get_api_timeout();
+        request.connect_timeout = self.config.get_connect_timeout();
+        request.compress_json = self.config.allow_request_compression();
+        Ok(request)
     }
 
     /// Convenience method that performs a `GET` request.
@@ -276,6 +338,7 @@ impl Api {
     pub fn download(&self, url: &str, dst: &mut File) -> ApiResult<ApiResponse> {
         self.request(Method::Get, url, None)?
             .follow_location(true)?
+            .with_timeout(self.config.get_transfer_timeout())?
             .send_into(dst)
     }
 
@@ -285,6 +348,7 @@ impl Api {
     pub fn download_with_progress(&self, url: &str, dst: &mut File) -> ApiResult<ApiResponse> {
         self.request(Method::Get, url, None)?
             .follow_location(true)?
+            .with_timeout(self.config.get_transfer_timeout())?
             .progress_bar_mode(ProgressBarMode::Response)?
             .send_into(dst)
     }
@@ -312,9 +376,17 @@ impl Api {
 
     // High Level Methods
 
-    /// Finds the latest release for sentry-cli on GitHub.
-    pub fn get_latest_sentrycli_release(&self) -> ApiResult<Option<SentryCliRelease>> {
-        let resp = self.get(RELEASE_REGISTRY_LATEST_URL)?;
+    /// Finds the latest release for sentry-cli in the feed of the given channel.
+    pub fn get_latest_sentrycli_release(
+        &self,
+        channel: UpdateChannel,
+    ) -> ApiResult<Option<SentryCliRelease>> {
+        let url = format!(
+            "{}/{}",
+            self.config.get_release_registry_url(),
+            channel.registry_feed()
+        );
+        let resp = self.get(&url)?;
 
         // Prefer universal binary on macOS
         let arch = match PLATFORM {
@@ -345,6 +417,67 @@ impl Api {
         }
     }
 
+    /// Starts the OAuth device authorization flow against the configured Sentry server.
+    pub fn request_device_authorization(&self) -> ApiResult<DeviceAuthorization> {
+        let url = self.oauth_url("device/code/")?;
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("client_id", OAUTH_CLIENT_ID)
+            .append_pair("scope", OAUTH_SCOPES)
+            .finish();
+
+        self.request(Method::Post, &url, None)?
+            .with_header("Content-Type", "application/x-www-form-urlencoded")?
+            .with_body(body.into_bytes())?
+            .send()?
+            .convert()
+    }
+
+    /// Polls the token endpoint for the state of a device authorization.
+    pub fn poll_device_token(&self, device_code: &str) -> ApiResult<DeviceTokenStatus> {
+        let url = self.oauth_url("token/")?;
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("grant_type", "urn:ietf:params:oauth:grant-type:device_code")
+            .append_pair("device_code", device_code)
+            .append_pair("client_id", OAUTH_CLIENT_ID)
+            .finish();
+
+        let resp = self
+            .request(Method::Post, &url, None)?
+            .with_header("Content-Type", "application/x-www-form-urlencoded")?
+            .with_body(body.into_bytes())?
+            .send()?;
+
+        // Pending and failed authorizations are reported as errors with a 400 status.
+        let token = match resp.deserialize::<DeviceTokenResponse>() {
+            Ok(token) => token,
+            Err(err) => return resp.into_result().and(Err(err)),
+        };
+
+        match (token.access_token, token.error.as_deref()) {
+            (Some(access_token), _) if resp.ok() => Ok(DeviceTokenStatus::Token(access_token)),
+            (_, Some("authorization_pending")) => Ok(DeviceTokenStatus::Pending),
+            (_, Some("slow_down")) => Ok(DeviceTokenStatus::SlowDown),
+            (_, Some("access_denied")) => Ok(DeviceTokenStatus::Denied),
+            (_, Some("expired_token")) => Ok(DeviceTokenStatus::Expired),
+            (_, error) => Err(ApiError::with_source(
+                ApiErrorKind::RequestFailed,
+                SentryError {
+                    status: resp.status(),
+                    detail: token.error_description.or(error.map(str::to_owned)),
+                    fields: vec![],
+                },
+            )),
+        }
+    }
+
+    /// Returns the absolute URL of an OAuth endpoint on the configured Sentry server.
+    fn oauth_url(&self, path: &str) -> ApiResult<String> {
+        match self.config.get_base_url() {
+            Ok(base_url) => Ok(format!("{base_url}/oauth/{path}")),
+            Err(err) => Err(ApiError::with_source(ApiErrorKind::BadApiUrl, err)),
+        }
+    }
+
     /// Compresses a file with the given compression.
     fn compress(data: &[u8], compression: ChunkCompression) -> Result<Vec<u8>, io::Error> {
         Ok(match compression {
@@ -360,6 +493,8 @@ impl Api {
                 encoder.finish()?
             }
 
+            ChunkCompression::Zstd => zstd::encode_all(data, 0)?,
+
             ChunkCompression::Uncompressed => data.into(),
         })
     }
@@ -396,6 +531,7 @@ impl Api {
         let request = self
             .request(Method::Post, url, None)?
             .with_form_data(form)?
+            .with_timeout(self.config.get_transfer_timeout())?
             .with_retry(
                 self.config.get_max_retry_count().unwrap(),
                 &[
@@ -465,6 +601,30 @@ impl<'a> AuthenticatedApi<'a> {
         self.get("/")?.convert()
     }
 
+    /// Like `get_auth_info` but returns `None` if the server rejected the token.
+    pub fn check_auth_token(&self) -> ApiResult<Option<AuthInfo>> {
+        let resp = self.get("/")?;
+        if resp.status() == 401 {
+            return Ok(None);
+        }
+        resp.convert().map(Some)
+    }
+
+    /// Performs a request to an arbitrary endpoint, given relative to `/api/0/`,
+    /// and returns the response.  Failed requests are converted into errors.
+    pub fn send_raw_request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> ApiResult<ApiResponse> {
+        let mut request = self.request(method, path)?;
+        if let Some(body) = body {
+            request = request.with_json_body(body)?;
+        }
+        request.send()?.into_result()
+    }
+
     /// Lists release files for the given `release`, filtered by a set of checksums.
     /// When empty checksums list is provided, fetches all possible artifacts.
     pub fn list_release_files_by_checksum(
@@ -602,6 +762,170 @@ impl<'a> AuthenticatedApi<'a> {
         }
     }
 
+    /// Lists all the artifact bundles uploaded to a project.
+    pub fn list_artifact_bundles(
+        &self,
+        org: &str,
+        project: &str,
+    ) -> ApiResult<Vec<ArtifactBundle>> {
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let path = format!(
+                "/projects/{}/{}/files/artifact-bundles/?cursor={}",
+                PathArg(org),
+                PathArg(project),
+                QueryArg(&cursor),
+            );
+
+            let resp = self.get(&path)?;
+            if resp.status() == 404 || (resp.status() == 400 && !cursor.is_empty()) {
+                if rv.is_empty() {
+                    return Err(ApiErrorKind::ProjectNotFound.into());
+                } else {
+                    break;
+                }
+            }
+
+            let pagination = resp.pagination();
+            rv.extend(resp.convert::<Vec<ArtifactBundle>>()?);
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
+        }
+        Ok(rv)
+    }
+
+    /// Deletes the artifact bundle with the given `bundle_id`.  Returns `true`
+    /// if the bundle was deleted or `false` if it does not exist.
+    pub fn delete_artifact_bundle(
+        &self,
+        org: &str,
+        project: &str,
+        bundle_id: &str,
+    ) -> ApiResult<bool> {
+        let path = format!(
+            "/projects/{}/{}/files/artifact-bundles/?bundleId={}",
+            PathArg(org),
+            PathArg(project),
+            QueryArg(bundle_id)
+        );
+
+        let resp = self.delete(&path)?;
+        if resp.status() == 404 {
+            Ok(false)
+        } else {
+            resp.into_result().map(|_| true)
+        }
+    }
+
+    /// Lists all the files contained in the artifact bundle with the given `bundle_id`.
+    pub fn list_artifact_bundle_files(
+        &self,
+        org: &str,
+        project: &str,
+        bundle_id: &str,
+    ) -> ApiResult<Vec<ArtifactBundleFile>> {
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let path = format!(
+                "/projects/{}/{}/artifact-bundles/{}/files/?cursor={}",
+                PathArg(org),
+                PathArg(project),
+                PathArg(bundle_id),
+                QueryArg(&cursor),
+            );
+
+            let resp = self.get(&path)?;
+            if resp.status() == 404 || (resp.status() == 400 && !cursor.is_empty()) {
+                if rv.is_empty() {
+                    return Err(ApiErrorKind::ResourceNotFound.into());
+                } else {
+                    break;
+                }
+            }
+
+            let pagination = resp.pagination();
+            rv.extend(resp.convert::<ArtifactBundleFiles>()?.files);
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
+        }
+        Ok(rv)
+    }
+
+    /// Get a single file of an artifact bundle and store it inside provided descriptor.
+    pub fn get_artifact_bundle_file(
+        &self,
+        org: &str,
+        project: &str,
+        bundle_id: &str,
+        file_id: &str,
+        file_desc: &mut File,
+    ) -> Result<(), ApiError> {
+        let path = format!(
+            "/projects/{}/{}/artifact-bundles/{}/files/{}/",
+            PathArg(org),
+            PathArg(project),
+            PathArg(bundle_id),
+            PathArg(file_id)
+        );
+
+        let resp = self.api.download(&path, file_desc)?;
+        if resp.status() == 404 {
+            resp.convert_rnf(ApiErrorKind::ResourceNotFound)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Looks up the artifacts the server uses to process frames with the
+    /// given debug ID.
+    pub fn lookup_artifacts_by_debug_id(
+        &self,
+        org: &str,
+        project: &str,
+        debug_id: DebugId,
+    ) -> ApiResult<Vec<ArtifactLookupEntry>> {
+        let path = format!(
+            "/projects/{}/{}/artifact-lookup/?debug_id={}",
+            PathArg(org),
+            PathArg(project),
+            QueryArg(&debug_id.to_string())
+        );
+
+        self.get(&path)?.convert_rnf(ApiErrorKind::ResourceNotFound)
+    }
+
+    /// Downloads an artifact found with the artifact lookup endpoint and
+    /// stores it inside provided descriptor.
+    pub fn get_artifact_lookup_file(
+        &self,
+        org: &str,
+        project: &str,
+        id: &str,
+        file_desc: &mut File,
+    ) -> Result<(), ApiError> {
+        let path = format!(
+            "/projects/{}/{}/artifact-lookup/?download={}",
+            PathArg(org),
+            PathArg(project),
+            QueryArg(id)
+        );
+
+        let resp = self.api.download(&path, file_desc)?;
+        if resp.status() == 404 {
+            resp.convert_rnf(ApiErrorKind::ResourceNotFound)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Deletes a single release file.  Returns `true` if the file was
     /// deleted or `false` otherwise.
     pub fn delete_release_file(
@@ -733,15 +1057,15 @@ impl<'a> AuthenticatedApi<'a> {
             .convert_rnf(ApiErrorKind::ReleaseNotFound)
     }
 
-    /// Deletes an already existing release.  Returns `true` if it was deleted
-    /// or `false` if not.  The project is needed to support the old deletion
-    /// API.
+    /// Deletes an already existing release.  Returns whether it was deleted,
+    /// did not exist, or was rejected by the server.  The project is needed to
+    /// support the old deletion API.
     pub fn delete_release(
         &self,
         org: &str,
         project: Option<&str>,
         version: &str,
-    ) -> ApiResult<bool> {
+    ) -> ApiResult<ReleaseDeletion> {
         let resp = if let Some(project) = project {
             self.delete(&format!(
                 "/projects/{}/{}/releases/{}/",
@@ -756,10 +1080,14 @@ impl<'a> AuthenticatedApi<'a> {
                 PathArg(version)
             ))?
         };
-        if resp.status() == 404 {
-            Ok(false)
-        } else {
-            resp.into_result().map(|_| true)
+
+        match resp.status() {
+            404 => Ok(ReleaseDeletion::NotFound),
+            400 => Ok(resp
+                .into_result()
+                .err()
+                .map_or(ReleaseDeletion::Deleted, ReleaseDeletion::Rejected)),
+            _ => resp.into_result().map(|_| ReleaseDeletion::Deleted),
         }
     }
 
@@ -793,29 +1121,45 @@ impl<'a> AuthenticatedApi<'a> {
         }
     }
 
-    /// Returns a list of releases for a given project.  This is currently a
-    /// capped list by what the server deems an acceptable default limit.
-    pub fn list_releases(&self, org: &str, project: Option<&str>) -> ApiResult<Vec<ReleaseInfo>> {
+    /// Returns a list of releases for a given organization or project.  This is
+    /// currently a capped list by what the server deems an acceptable default
+    /// limit.  Without a status, the server only returns open releases.
+    pub fn list_releases(
+        &self,
+        org: &str,
+        project: Option<&str>,
+        status: Option<ReleaseStatus>,
+    ) -> ApiResult<Vec<ReleaseInfo>> {
+        let query = match status {
+            Some(status) => format!("?status={}", status.as_str()),
+            None => String::new(),
+        };
         if let Some(project) = project {
-            let path = format!("/projects/{}/{}/releases/", PathArg(org), PathArg(project));
+            let path = format!(
+                "/projects/{}/{}/releases/{query}",
+                PathArg(org),
+                PathArg(project)
+            );
             self.get(&path)?
                 .convert_rnf::<Vec<ReleaseInfo>>(ApiErrorKind::ProjectNotFound)
         } else {
-            let path = format!("/organizations/{}/releases/", PathArg(org));
+            let path = format!("/organizations/{}/releases/{query}", PathArg(org));
             self.get(&path)?
                 .convert_rnf::<Vec<ReleaseInfo>>(ApiErrorKind::OrganizationNotFound)
         }
     }
 
-    /// Looks up a release commits and returns it.  If it does not exist `None`
-    /// will be returned.
+    /// Looks up the commits of a release, following the pagination until at
+    /// most `max_commits` commits are found.  If the release does not exist
+    /// `None` will be returned.
     pub fn get_release_commits(
         &self,
         org: &str,
         project: Option<&str>,
         version: &str,
+        max_commits: usize,
     ) -> ApiResult<Option<Vec<ReleaseCommit>>> {
-        let path = if let Some(project) = project {
+        let base_path = if let Some(project) = project {
             format!(
                 "/projects/{}/{}/releases/{}/commits/",
                 PathArg(org),
@@ -829,12 +1173,34 @@ impl<'a> AuthenticatedApi<'a> {
                 PathArg(version)
             )
         };
-        let resp = self.get(&path)?;
-        if resp.status() == 404 {
-            Ok(None)
-        } else {
-            resp.convert()
+
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        while rv.len() < max_commits {
+            let path = if cursor.is_empty() {
+                base_path.clone()
+            } else {
+                format!("{base_path}?cursor={}", QueryArg(&cursor))
+            };
+
+            let resp = self.get(&path)?;
+            if resp.status() == 404 {
+                if cursor.is_empty() {
+                    return Ok(None);
+                }
+                break;
+            }
+
+            let pagination = resp.pagination();
+            rv.extend(resp.convert::<Vec<ReleaseCommit>>()?);
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
         }
+        rv.truncate(max_commits);
+        Ok(Some(rv))
     }
 
     // Finds the most recent release with commits and returns it.
@@ -858,17 +1224,61 @@ impl<'a> AuthenticatedApi<'a> {
     }
 
     /// Creates a new deploy for a release.
-    pub fn create_deploy(&self, org: &str, version: &str, deploy: &Deploy) -> ApiResult<Deploy> {
+    ///
+    /// An idempotency key is sent along in the `Idempotency-Key` header.
+    pub fn create_deploy(
+        &self,
+        org: &str,
+        version: &str,
+        deploy: &Deploy,
+        idempotency_key: Option<&str>,
+    ) -> ApiResult<Deploy> {
         let path = format!(
             "/organizations/{}/releases/{}/deploys/",
             PathArg(org),
             PathArg(version)
         );
 
-        self.post(&path, deploy)?
+        let mut request = self.request(Method::Post, &path)?;
+        if let Some(key) = idempotency_key {
+            request = request.with_header("Idempotency-Key", key)?;
+        }
+        request
+            .with_json_body(deploy)?
+            .send()?
             .convert_rnf(ApiErrorKind::ReleaseNotFound)
     }
 
+    /// Lists the auth tokens of an organization.
+    pub fn list_org_auth_tokens(&self, org: &str) -> ApiResult<Vec<OrgAuthToken>> {
+        let path = format!("/organizations/{}/org-auth-tokens/", PathArg(org));
+        check_org_auth_token_response(self.get(&path)?)?.deserialize()
+    }
+
+    /// Creates a new auth token for an organization.
+    ///
+    /// The response contains the value of the token, so it is not written to
+    /// the debug log like other responses.
+    pub fn create_org_auth_token(
+        &self,
+        org: &str,
+        token: &CreateOrgAuthToken,
+    ) -> ApiResult<CreatedOrgAuthToken> {
+        let path = format!("/organizations/{}/org-auth-tokens/", PathArg(org));
+        check_org_auth_token_response(self.post(&path, token)?)?.deserialize()
+    }
+
+    /// Revokes an auth token of an organization.
+    pub fn revoke_org_auth_token(&self, org: &str, id: &str) -> ApiResult<()> {
+        let path = format!(
+            "/organizations/{}/org-auth-tokens/{}/",
+            PathArg(org),
+            PathArg(id)
+        );
+        check_org_auth_token_response(self.delete(&path)?)?;
+        Ok(())
+    }
+
     /// Lists all deploys for a release
     pub fn list_deploys(&self, org: &str, version: &str) -> ApiResult<Vec<Deploy>> {
         let path = format!(
@@ -935,14 +1345,68 @@ impl<'a> AuthenticatedApi<'a> {
         Ok(state.missing)
     }
 
+    /// Lists the debug information files of a project with the given debug id.
+    pub fn list_difs_by_debug_id(
+        &self,
+        org: &str,
+        project: &str,
+        debug_id: DebugId,
+    ) -> ApiResult<Vec<DebugInfoFile>> {
+        let url = format!(
+            "/projects/{}/{}/files/dsyms/?debug_id={}",
+            PathArg(org),
+            PathArg(project),
+            QueryArg(&debug_id.to_string())
+        );
+        self.get(&url)?.convert_rnf(ApiErrorKind::ProjectNotFound)
+    }
+
+    /// Deletes the debug information file with the given server ID.  Returns
+    /// `true` if the file was deleted or `false` if it does not exist.
+    pub fn delete_dif(&self, org: &str, project: &str, file_id: &str) -> ApiResult<bool> {
+        let path = format!(
+            "/projects/{}/{}/files/dsyms/?id={}",
+            PathArg(org),
+            PathArg(project),
+            QueryArg(file_id)
+        );
+
+        let resp = self.delete(&path)?;
+        if resp.status() == 404 {
+            Ok(false)
+        } else {
+            resp.into_result().map(|_| true)
+        }
+    }
+
     /// Get the server configuration for chunked file uploads.
+    ///
+    /// The response is cached for a few minutes, unless caching is disabled.
     pub fn get_chunk_upload_options(&self, org: &str) -> ApiResult<Option<ChunkUploadOptions>> {
+        let cache = self
+            .api
+            .config
+            .get_cache_dir()
+            .zip(self.api.config.get_base_url().ok());
+        if let Some((cache_dir, base_url)) = &cache {
+            if let Some(options) = load_cached_chunk_upload_options(cache_dir, base_url, org) {
+                return Ok(Some(options));
+            }
+        }
+
         let url = format!("/organizations/{}/chunk-upload/", PathArg(org));
         match self
             .get(&url)?
-            .convert_rnf::<ChunkUploadOptions>(ApiErrorKind::ChunkUploadNotSupported)
+            .convert_rnf::<serde_json::Value>(ApiErrorKind::ChunkUploadNotSupported)
         {
-            Ok(options) => Ok(Some(options)),
+            Ok(response) => {
+                let options = ChunkUploadOptions::deserialize(&response)
+                    .map_err(|err| ApiError::with_source(ApiErrorKind::BadJson, err))?;
+                if let Some((cache_dir, base_url)) = cache {
+                    store_cached_chunk_upload_options(&cache_dir, base_url, org, &response);
+                }
+                Ok(Some(options))
+            }
             Err(error) => {
                 if error.kind() == ApiErrorKind::ChunkUploadNotSupported {
                     Ok(None)
@@ -1013,6 +1477,9 @@ impl<'a> AuthenticatedApi<'a> {
             .convert_rnf(ApiErrorKind::ReleaseNotFound)
     }
 
+    /// Request assembling of an artifact bundle from chunks.
+    ///
+    /// Returns `None` if the server does not support artifact bundles.
     pub fn assemble_artifact_bundle(
         &self,
         org: &str,
@@ -1021,10 +1488,11 @@ impl<'a> AuthenticatedApi<'a> {
         chunks: &[Digest],
         version: Option<&str>,
         dist: Option<&str>,
-    ) -> ApiResult<AssembleArtifactsResponse> {
+    ) -> ApiResult<Option<AssembleArtifactsResponse>> {
         let url = format!("/organizations/{}/artifactbundle/assemble/", PathArg(org));
 
-        self.request(Method::Post, &url)?
+        let response = self
+            .request(Method::Post, &url)?
             .with_json_body(&ChunkedArtifactRequest {
                 checksum,
                 chunks,
@@ -1040,8 +1508,14 @@ impl<'a> AuthenticatedApi<'a> {
                     http::HTTP_STATUS_504_GATEWAY_TIMEOUT,
                 ],
             )?
-            .send()?
-            .convert_rnf(ApiErrorKind::ReleaseNotFound)
+            .send()?;
+
+        // Servers before artifact bundles do not know the endpoint.
+        match response.convert_rnf(ApiErrorKind::ArtifactBundlesNotSupported) {
+            Ok(response) => Ok(Some(response)),
+            Err(error) if error.kind() == ApiErrorKind::ArtifactBundlesNotSupported => Ok(None),
+            Err(error) => Err(error),
+        }
     }
 
     pub fn associate_proguard_mappings(
@@ -1124,6 +1598,24 @@ impl<'a> AuthenticatedApi<'a> {
         Ok(region_response.regions)
     }
 
+    /// List all organizations available to the current token across all regions.
+    pub fn list_organizations_in_all_regions(&self) -> ApiResult<Vec<Organization>> {
+        // Self-hosted instances won't have a region instance or prefix, so we
+        // need to check before fanning out.
+        let regions = self.list_available_regions()?;
+        debug!("Available regions: {:?}", regions);
+
+        if regions.is_empty() {
+            return self.list_organizations(None);
+        }
+
+        let mut organizations = vec![];
+        for region in regions {
+            organizations.append(&mut self.list_organizations(Some(&region))?);
+        }
+        Ok(organizations)
+    }
+
     /// List all monitors associated with an organization
     pub fn list_organization_monitors(&self, org: &str) -> ApiResult<Vec<Monitor>> {
         let mut rv = vec![];
@@ -1152,6 +1644,76 @@ impl<'a> AuthenticatedApi<'a> {
         Ok(rv)
     }
 
+    /// Creates a monitor or updates the configuration of an existing monitor
+    /// with the same slug.
+    pub fn upsert_organization_monitor(
+        &self,
+        org: &str,
+        monitor: &NewMonitor,
+    ) -> ApiResult<Monitor> {
+        let path = format!(
+            "/organizations/{}/monitors/{}/",
+            PathArg(org),
+            PathArg(monitor.slug)
+        );
+        let resp = self.put(&path, monitor)?;
+        if resp.status() != 404 {
+            return resp.convert();
+        }
+
+        let path = format!("/organizations/{}/monitors/", PathArg(org));
+        self.post(&path, monitor)?
+            .convert_rnf(ApiErrorKind::OrganizationNotFound)
+    }
+
+    /// Creates a new project owned by the given team.
+    pub fn create_project(
+        &self,
+        org: &str,
+        team: &str,
+        project: &NewProject,
+    ) -> ApiResult<Project> {
+        let path = format!("/teams/{}/{}/projects/", PathArg(org), PathArg(team));
+        self.post(&path, project)?
+            .convert_rnf(ApiErrorKind::TeamNotFound)
+    }
+
+    /// Lists the client keys of a project, which hold its DSNs.
+    pub fn list_project_keys(&self, org: &str, project: &str) -> ApiResult<Vec<ProjectKey>> {
+        let path = format!("/projects/{}/{}/keys/", PathArg(org), PathArg(project));
+        self.get(&path)?.convert_rnf(ApiErrorKind::ProjectNotFound)
+    }
+
+    /// Creates a new client key for a project.
+    pub fn create_project_key(
+        &self,
+        org: &str,
+        project: &str,
+        key: &NewProjectKey,
+    ) -> ApiResult<ProjectKey> {
+        let path = format!("/projects/{}/{}/keys/", PathArg(org), PathArg(project));
+        self.post(&path, key)?
+            .convert_rnf(ApiErrorKind::ProjectNotFound)
+    }
+
+    /// Updates a client key of a project.
+    pub fn update_project_key(
+        &self,
+        org: &str,
+        project: &str,
+        key_id: &str,
+        key: &UpdatedProjectKey,
+    ) -> ApiResult<ProjectKey> {
+        let path = format!(
+            "/projects/{}/{}/keys/{}/",
+            PathArg(org),
+            PathArg(project),
+            PathArg(key_id)
+        );
+        self.put(&path, key)?
+            .convert_rnf(ApiErrorKind::ResourceNotFound)
+    }
+
     /// List all projects associated with an organization
     pub fn list_organization_projects(&self, org: &str) -> ApiResult<Vec<Project>> {
         let mut rv = vec![];
@@ -1180,6 +1742,26 @@ impl<'a> AuthenticatedApi<'a> {
         Ok(rv)
     }
 
+    /// List all projects the authenticated user has access to, across all organizations.
+    pub fn list_projects(&self) -> ApiResult<Vec<ProjectWithOrganization>> {
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let resp = self.get(&format!("/projects/?cursor={}", QueryArg(&cursor)))?;
+            if resp.status() == 400 && !cursor.is_empty() {
+                break;
+            }
+            let pagination = resp.pagination();
+            rv.extend(resp.convert::<Vec<ProjectWithOrganization>>()?);
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
+        }
+        Ok(rv)
+    }
+
     /// List all events associated with an organization and a project
     pub fn list_organization_project_events(
         &self,
@@ -1226,12 +1808,16 @@ impl<'a> AuthenticatedApi<'a> {
         Ok(rv)
     }
 
-    /// List all issues associated with an organization and a project
+    /// List all issues associated with an organization and a project.
+    ///
+    /// Stops following pagination once `max_pages` pages or at least
+    /// `max_rows` issues have been fetched.
     pub fn list_organization_project_issues(
         &self,
         org: &str,
         project: &str,
         max_pages: usize,
+        max_rows: usize,
         query: Option<String>,
     ) -> ApiResult<Vec<Issue>> {
         let mut rv = vec![];
@@ -1265,7 +1851,7 @@ impl<'a> AuthenticatedApi<'a> {
             let pagination = resp.pagination();
             rv.extend(resp.convert::<Vec<Issue>>()?);
 
-            if requests_no == max_pages {
+            if requests_no == max_pages || rv.len() >= max_rows {
                 break;
             }
 
@@ -1305,6 +1891,34 @@ impl<'a> AuthenticatedApi<'a> {
         Ok(rv)
     }
 
+    /// Creates a new repository in an organization, linking it to the given
+    /// provider.  If the repository already exists `None` is returned.
+    pub fn create_organization_repo(&self, org: &str, repo: &NewRepo) -> ApiResult<Option<Repo>> {
+        let path = format!("/organizations/{}/repos/", PathArg(org));
+        let resp = self.post(&path, repo)?;
+        if resp.status() == 409 {
+            Ok(None)
+        } else {
+            resp.convert_rnf(ApiErrorKind::OrganizationNotFound)
+                .map(Some)
+        }
+    }
+
+    /// Deletes a repository from an organization.  Returns `false` if the
+    /// repository does not exist.
+    pub fn delete_organization_repo(&self, org: &str, repo_id: &str) -> ApiResult<bool> {
+        let resp = self.delete(&format!(
+            "/organizations/{}/repos/{}/",
+            PathArg(org),
+            PathArg(repo_id)
+        ))?;
+        if resp.status() == 404 {
+            Ok(false)
+        } else {
+            resp.into_result().map(|_| true)
+        }
+    }
+
     /// Looks up an event, which was already processed by Sentry and returns it.
     /// If it does not exist `None` will be returned.
     pub fn get_event(
@@ -1408,6 +2022,7 @@ impl<'a> RegionSpecificApi<'a> {
         form.part("file").file(file).add()?;
         self.request(Method::Post, &path)?
             .with_form_data(form)?
+            .with_timeout(self.api.api.config.get_transfer_timeout())?
             .with_retry(
                 self.api.api.config.get_max_retry_count().map_err(|e| {
                     ApiError::with_source(
@@ -1475,6 +2090,7 @@ impl<'a> RegionSpecificApi<'a> {
         let resp = self
             .request(Method::Post, &path)?
             .with_form_data(form)?
+            .with_timeout(self.api.api.config.get_transfer_timeout())?
             .with_retry(
                 self.api.api.config.get_max_retry_count().unwrap(),
                 &[
@@ -1496,18 +2112,23 @@ impl<'a> RegionSpecificApi<'a> {
 fn send_req<W: Write>(
     handle: &mut curl::easy::Easy,
     out: &mut W,
-    body: Option<&[u8]>,
+    body: Option<(&mut dyn Read, u64)>,
     progress_bar_mode: ProgressBarMode,
+    redacted_headers: &[&str],
 ) -> ApiResult<(u32, Vec<String>)> {
     match body {
-        Some(mut body) => {
+        Some((body, len)) => {
             handle.upload(true)?;
-            handle.in_filesize(body.len() as u64)?;
-            handle_req(handle, out, progress_bar_mode, &mut |buf| {
-                body.read(buf).unwrap_or(0)
-            })
+            handle.in_filesize(len)?;
+            handle_req(
+                handle,
+                out,
+                progress_bar_mode,
+                redacted_headers,
+                &mut |buf| body.read(buf).unwrap_or(0),
+            )
         }
-        None => handle_req(handle, out, progress_bar_mode, &mut |_| 0),
+        None => handle_req(handle, out, progress_bar_mode, redacted_headers, &mut |_| 0),
     }
 }
 
@@ -1515,6 +2136,7 @@ fn handle_req<W: Write>(
     handle: &mut curl::easy::Easy,
     out: &mut W,
     progress_bar_mode: ProgressBarMode,
+    redacted_headers: &[&str],
     read: &mut dyn FnMut(&mut [u8]) -> usize,
 ) -> ApiResult<(u32, Vec<String>)> {
     if progress_bar_mode.active() {
@@ -1578,10 +2200,10 @@ fn handle_req<W: Write>(
 
         handle.debug_function(move |info, data| match info {
             curl::easy::InfoType::HeaderIn => {
-                log_headers(false, data);
+                log_headers(false, data, &[]);
             }
             curl::easy::InfoType::HeaderOut => {
-                log_headers(true, data);
+                log_headers(true, data, redacted_headers);
             }
             _ => {}
         })?;
@@ -1635,12 +2257,6 @@ impl ApiRequest {
         let mut headers = curl::easy::List::new();
         headers.append("Expect:").ok();
 
-        if let Some(global_headers) = global_headers {
-            for header in global_headers {
-                headers.append(&header).ok();
-            }
-        }
-
         match pipeline_env {
             Some(env) => {
                 debug!("pipeline: {}", env);
@@ -1667,11 +2283,19 @@ impl ApiRequest {
         let request = ApiRequest {
             handle,
             headers,
+            global_headers: global_headers.unwrap_or_default(),
             is_authenticated: false,
             body: None,
+            body_file: None,
             progress_bar_mode: ProgressBarMode::Disabled,
             max_retries: 0,
             retry_on_statuses: &[],
+            max_rate_limit_retries: 0,
+            rate_limit_categories: vec![],
+            url: url.to_owned(),
+            timeout: DEFAULT_API_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            compress_json: false,
         };
 
         let request = match auth {
@@ -1709,11 +2333,28 @@ impl ApiRequest {
     }
 
     /// sets the JSON request body for the request.
+    ///
+    /// Bodies larger than `REQUEST_COMPRESSION_THRESHOLD` are gzip compressed,
+    /// unless request compression is disabled with `http.compress_requests`.
     pub fn with_json_body<S: Serialize>(mut self, body: &S) -> ApiResult<Self> {
         let mut body_bytes: Vec<u8> = vec![];
         serde_json::to_writer(&mut body_bytes, &body)
             .map_err(|err| ApiError::with_source(ApiErrorKind::CannotSerializeAsJson, err))?;
         debug!("json body: {}", String::from_utf8_lossy(&body_bytes));
+        if self.compress_json && body_bytes.len() > REQUEST_COMPRESSION_THRESHOLD {
+            let mut encoder = GzEncoder::new(Vec::new(), Default::default());
+            let compressed = encoder
+                .write_all(&body_bytes)
+                .and_then(|()| encoder.finish())
+                .map_err(|err| ApiError::with_source(ApiErrorKind::CompressionFailed, err))?;
+            debug!(
+                "compressed json body from {} to {} bytes",
+                body_bytes.len(),
+                compressed.len()
+            );
+            body_bytes = compressed;
+            self.headers.append("Content-Encoding: gzip")?;
+        }
         self.body = Some(body_bytes);
         self.headers.append("Content-Type: application/json")?;
         Ok(self)
@@ -1724,6 +2365,14 @@ impl ApiRequest {
         Ok(self)
     }
 
+    /// sets a file as request body, which is streamed instead of being read
+    /// into memory.  The file is sent from the start, also when retrying.
+    pub fn with_file_body(mut self, file: File) -> ApiResult<Self> {
+        self.body = None;
+        self.body_file = Some(file);
+        Ok(self)
+    }
+
     /// attaches some form data to the request.
     pub fn with_form_data(mut self, form: curl::easy::Form) -> ApiResult<Self> {
         debug!("sending form data");
@@ -1745,6 +2394,22 @@ impl ApiRequest {
         Ok(self)
     }
 
+    /// sets the total timeout of the request.
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> ApiResult<Self> {
+        self.timeout = timeout;
+        Ok(self)
+    }
+
+    /// Sets the data categories of the request, such as `error` for an envelope
+    /// containing an event.
+    ///
+    /// If the request is rate limited, only the quotas of these categories and the
+    /// quotas applying to all categories are considered for the wait time.
+    pub fn with_rate_limit_categories(mut self, categories: Vec<&'static str>) -> Self {
+        self.rate_limit_categories = categories;
+        self
+    }
+
     pub fn with_retry(
         mut self,
         max_retries: u32,
@@ -1756,11 +2421,30 @@ impl ApiRequest {
     }
 
     /// Get a copy of the header list
+    ///
+    /// Global headers are added last and replace any header of the same name, with
+    /// later global headers taking precedence over earlier ones.
     fn get_headers(&self) -> curl::easy::List {
+        let is_overridden = |header: &str, global_headers: &[String]| {
+            header_name(header).map_or(false, |name| {
+                global_headers
+                    .iter()
+                    .filter_map(|global| header_name(global))
+                    .any(|global| global.eq_ignore_ascii_case(name))
+            })
+        };
+
         let mut result = curl::easy::List::new();
         for header_bytes in self.headers.iter() {
             let header = String::from_utf8(header_bytes.to_vec()).unwrap();
-            result.append(&header).ok();
+            if !is_overridden(&header, &self.global_headers) {
+                result.append(&header).ok();
+            }
+        }
+        for (idx, header) in self.global_headers.iter().enumerate() {
+            if !is_overridden(header, &self.global_headers[idx + 1..]) {
+                result.append(header).ok();
+            }
         }
         result
     }
@@ -1770,9 +2454,38 @@ impl ApiRequest {
     pub fn send_into<W: Write>(&mut self, out: &mut W) -> ApiResult<ApiResponse> {
         let headers = self.get_headers();
         self.handle.http_headers(headers)?;
-        let body = self.body.as_deref();
-        let (status, headers) =
-            send_req(&mut self.handle, out, body, self.progress_bar_mode.clone())?;
+        self.handle.timeout(self.timeout)?;
+        self.handle.connect_timeout(self.connect_timeout)?;
+        let mut body_bytes = self.body.as_deref();
+        let body: Option<(&mut dyn Read, u64)> = match (&mut self.body_file, &mut body_bytes) {
+            (Some(file), _) => {
+                let len = file
+                    .rewind()
+                    .and_then(|_| file.metadata())
+                    .map_err(|e| ApiError::with_source(ApiErrorKind::ErrorPreparingRequest, e))?
+                    .len();
+                Some((file, len))
+            }
+            (None, Some(bytes)) => {
+                let len = bytes.len() as u64;
+                Some((bytes, len))
+            }
+            (None, None) => None,
+        };
+        // the values of custom headers may be credentials, so they are kept out of logs
+        let redacted_headers: Vec<&str> = self
+            .global_headers
+            .iter()
+            .filter_map(|header| header_name(header))
+            .collect();
+        let (status, headers) = send_req(
+            &mut self.handle,
+            out,
+            body,
+            self.progress_bar_mode.clone(),
+            &redacted_headers,
+        )
+        .map_err(|err| self.timeout_error(err))?;
         debug!("response status: {}", status);
         Ok(ApiResponse {
             status,
@@ -1781,10 +2494,41 @@ impl ApiRequest {
         })
     }
 
+    /// Converts a timeout of the transfer into an error naming the endpoint and
+    /// the exceeded limit.  Other errors are returned unchanged.
+    fn timeout_error(&mut self, err: ApiError) -> ApiError {
+        if !err.is_timeout() {
+            return err;
+        }
+
+        // curl reports both limits with the same error, but only the connect
+        // timeout can fire before the connection is established.
+        let connected = self
+            .handle
+            .connect_time()
+            .map_or(false, |time| !time.is_zero());
+        let (limit, timeout) = if !connected && self.connect_timeout <= self.timeout {
+            ("connect", self.connect_timeout)
+        } else {
+            ("total", self.timeout)
+        };
+
+        ApiError::with_source(
+            ApiErrorKind::RequestTimedOut,
+            format_err!(
+                "request to {} exceeded the {} timeout of {}s",
+                self.url,
+                limit,
+                timeout.as_secs()
+            ),
+        )
+    }
+
     /// Sends the request and reads the response body into the response object.
     pub fn send(mut self) -> ApiResult<ApiResponse> {
         let mut backoff = get_default_backoff();
         let mut retry_number = 0;
+        let mut rate_limit_retry_number = 0;
 
         loop {
             let mut out = vec![];
@@ -1793,7 +2537,45 @@ impl ApiRequest {
                 retry_number, self.max_retries,
             );
 
-            let mut rv = self.send_into(&mut out)?;
+            let mut rv = match self.send_into(&mut out) {
+                Ok(rv) => rv,
+                // Transport errors (e.g. a dropped connection) are retried just like
+                // retryable status codes, as long as retries have been configured.
+                Err(err)
+                    if retry_number < self.max_retries
+                        && err.kind() == ApiErrorKind::RequestFailed =>
+                {
+                    let backoff_timeout = backoff.next_backoff().unwrap();
+                    warn!(
+                        "request failed ({:#}), retrying again in {} ms",
+                        anyhow::Error::from(err),
+                        backoff_timeout.as_milliseconds()
+                    );
+                    std::thread::sleep(backoff_timeout);
+                    retry_number += 1;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            // A 429 response means the server rejected the request without processing it,
+            // so this is safe to retry even for non-idempotent requests like event submission.
+            if rv.status == http::HTTP_STATUS_429_TOO_MANY_REQUESTS
+                && rate_limit_retry_number < self.max_rate_limit_retries
+            {
+                let delay = rv
+                    .rate_limit_delay(&self.rate_limit_categories)
+                    .unwrap_or_else(|| backoff.next_backoff().unwrap());
+                let delay = with_jitter(delay);
+                warn!(
+                    "rate limited by the server, retrying again in {} ms",
+                    delay.as_milliseconds()
+                );
+                std::thread::sleep(delay);
+                rate_limit_retry_number += 1;
+                continue;
+            }
+
             if retry_number >= self.max_retries || !self.retry_on_statuses.contains(&rv.status) {
                 rv.body = Some(out);
                 return Ok(rv);
@@ -1819,6 +2601,11 @@ impl ApiResponse {
         self.status
     }
 
+    /// Returns the body of the response
+    pub fn body(&self) -> &[u8] {
+        self.body.as_deref().unwrap_or_default()
+    }
+
     /// Indicates that the request failed
     pub fn failed(&self) -> bool {
         self.status >= 400 && self.status <= 600
@@ -1839,37 +2626,10 @@ impl ApiResponse {
         if self.ok() {
             return Ok(self);
         }
-        if let Ok(err) = self.deserialize::<ErrorInfo>() {
-            Err(ApiError::with_source(
-                ApiErrorKind::RequestFailed,
-                SentryError {
-                    status: self.status(),
-                    detail: Some(match err {
-                        ErrorInfo::Detail(val) => val,
-                        ErrorInfo::Error(val) => val,
-                    }),
-                    extra: None,
-                },
-            ))
-        } else if let Ok(value) = self.deserialize::<serde_json::Value>() {
-            Err(ApiError::with_source(
-                ApiErrorKind::RequestFailed,
-                SentryError {
-                    status: self.status(),
-                    detail: Some("request failure".into()),
-                    extra: Some(value),
-                },
-            ))
-        } else {
-            Err(ApiError::with_source(
-                ApiErrorKind::RequestFailed,
-                SentryError {
-                    status: self.status(),
-                    detail: None,
-                    extra: None,
-                },
-            ))
-        }
+        Err(ApiError::with_source(
+            ApiErrorKind::RequestFailed,
+            SentryError::from_body(self.status(), self.body()),
+        ))
     }
 
     /// Deserializes the response body into the given type
@@ -1937,6 +2697,21 @@ impl ApiResponse {
         None
     }
 
+    /// Returns how long the server asked us to wait before retrying a rate limited
+    /// request, based on the `X-Sentry-Rate-Limits` or `Retry-After` headers.
+    ///
+    /// Only the rate limits of the given data `categories` are considered, and the
+    /// wait time is capped at `MAX_RATE_LIMIT_DELAY`.
+    pub fn rate_limit_delay(&self, categories: &[&str]) -> Option<std::time::Duration> {
+        self.get_header("x-sentry-rate-limits")
+            .and_then(|value| http::parse_sentry_rate_limits(value, categories))
+            .or_else(|| {
+                self.get_header("retry-after")
+                    .and_then(|value| http::parse_retry_after(value, Utc::now()))
+            })
+            .map(|delay| delay.min(MAX_RATE_LIMIT_DELAY))
+    }
+
     /// Returns the pagination info
     pub fn pagination(&self) -> Pagination {
         self.get_header("link")
@@ -1953,7 +2728,29 @@ impl ApiResponse {
     }
 }
 
-fn log_headers(is_response: bool, data: &[u8]) {
+/// Sets the credentials for the proxy, letting curl negotiate Basic or NTLM
+/// authentication with the proxy.
+fn set_proxy_credentials(
+    handle: &mut curl::easy::Easy,
+    username: Option<&str>,
+    password: Option<&str>,
+) -> ApiResult<()> {
+    if username.is_none() && password.is_none() {
+        return Ok(());
+    }
+    if let Some(username) = username {
+        handle.proxy_username(username)?;
+    }
+    if let Some(password) = password {
+        handle.proxy_password(password)?;
+    }
+    let mut auth = curl::easy::Auth::new();
+    auth.basic(true).ntlm(true);
+    handle.proxy_auth(&auth)?;
+    Ok(())
+}
+
+fn log_headers(is_response: bool, data: &[u8], redacted_headers: &[&str]) {
     lazy_static! {
         static ref AUTH_RE: Regex = Regex::new(r"(?i)(authorization):\s*([\w]+)\s+(.*)").unwrap();
     }
@@ -1963,6 +2760,22 @@ fn log_headers(is_response: bool, data: &[u8]) {
                 continue;
             }
 
+            if let Some(name) = header_name(line) {
+                // the proxy credentials are only base64 encoded, so they are never logged
+                if name.eq_ignore_ascii_case("proxy-authorization")
+                    || redacted_headers
+                        .iter()
+                        .any(|redacted| redacted.eq_ignore_ascii_case(name))
+                {
+                    debug!(
+                        "{} {}: [REDACTED]",
+                        if is_response { ">" } else { "<" },
+                        name
+                    );
+                    continue;
+                }
+            }
+
             let replaced = AUTH_RE.replace_all(line, |caps: &Captures<'_>| {
                 let info = if &caps[1].to_lowercase() == "basic" {
                     caps[3].split(':').next().unwrap().to_string()
@@ -1983,12 +2796,82 @@ enum ErrorInfo {
     Error(String),
 }
 
+/// Converts failed responses of the organization auth token endpoints into errors.
+///
+/// Successful responses are returned as they are, which keeps token values out
+/// of the debug log. Rejected requests are reported as a missing `org:admin` scope,
+/// which these endpoints require.
+fn check_org_auth_token_response(resp: ApiResponse) -> ApiResult<ApiResponse> {
+    if resp.status() == 403 {
+        let detail = resp.deserialize::<ErrorInfo>().ok().map(|err| match err {
+            ErrorInfo::Detail(val) | ErrorInfo::Error(val) => val,
+        });
+        return Err(ApiError::with_source(
+            ApiErrorKind::OrgAdminScopeRequired,
+            SentryError {
+                status: resp.status(),
+                detail,
+                fields: vec![],
+            },
+        ));
+    }
+    if resp.ok() {
+        Ok(resp)
+    } else {
+        resp.into_result()
+    }
+}
+
+/// The outcome of deleting a release.
+#[derive(Debug)]
+pub enum ReleaseDeletion {
+    Deleted,
+    NotFound,
+    /// The server refused to delete the release, for example because files or
+    /// artifact bundles are still attached to it.
+    Rejected(ApiError),
+}
+
 /// Provides the auth details (access scopes)
 #[derive(Deserialize, Debug)]
 pub struct AuthDetails {
     pub scopes: Vec<String>,
 }
 
+impl AuthDetails {
+    /// Checks whether the token grants the given scope, either directly or
+    /// through a higher access level on the same resource.
+    ///
+    /// For instance, `project:admin` implies `project:write`, which in turn implies
+    /// `project:read`.  Organization tokens with `org:ci` may read the organization
+    /// and manage its releases.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        const ACCESS_LEVELS: &[&str] = &["read", "write", "admin"];
+
+        self.scopes.iter().any(|granted| {
+            if granted == scope {
+                return true;
+            }
+            if granted == "org:ci" && (scope == "project:releases" || scope == "org:read") {
+                return true;
+            }
+
+            match (granted.split_once(':'), scope.split_once(':')) {
+                (Some((granted_resource, granted_level)), Some((resource, level)))
+                    if granted_resource == resource =>
+                {
+                    let rank = |level| ACCESS_LEVELS.iter().position(|&l| l == level);
+                    matches!(
+                        (rank(granted_level), rank(level)),
+                        (Some(granted_rank), Some(rank)) if granted_rank >= rank
+                    )
+                }
+                _ => false,
+            }
+        })
+    }
+}
+
 /// Indicates which user signed in
 #[derive(Deserialize, Debug)]
 pub struct User {
@@ -2021,6 +2904,19 @@ impl Artifact {
     }
 }
 
+/// A file contained in an artifact bundle
+#[derive(Clone, Deserialize, Debug)]
+pub struct ArtifactBundleFile {
+    pub id: String,
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ArtifactBundleFiles {
+    files: Vec<ArtifactBundleFile>,
+}
+
 /// Information for new releases
 #[derive(Debug, Serialize, Default)]
 pub struct NewRelease {
@@ -2035,7 +2931,7 @@ pub struct NewRelease {
 }
 
 /// A head commit on a release
-#[derive(Debug, Serialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
 pub struct Ref {
     #[serde(rename = "repository")]
     pub repo: String,
@@ -2045,13 +2941,22 @@ pub struct Ref {
     pub prev_rev: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Copy, Debug, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ReleaseStatus {
     Open,
     Archived,
 }
 
+impl ReleaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseStatus::Open => "open",
+            ReleaseStatus::Archived => "archived",
+        }
+    }
+}
+
 /// Changes to a release
 #[derive(Debug, Serialize, Default)]
 pub struct UpdatedRelease {
@@ -2074,10 +2979,9 @@ pub struct UpdatedRelease {
 }
 
 /// Provides all release information from already existing releases
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReleaseInfo {
     pub version: String,
-    #[expect(dead_code)]
     pub url: Option<String>,
     #[serde(rename = "dateCreated")]
     pub date_created: DateTime<Utc>,
@@ -2095,22 +2999,57 @@ pub struct ReleaseInfo {
         skip_serializing_if = "Option::is_none"
     )]
     pub last_commit: Option<ReleaseCommit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum OptionalReleaseInfo {
     None(NoneReleaseInfo),
-    Some(ReleaseInfo),
+    Some(Box<ReleaseInfo>),
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct NoneReleaseInfo {}
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReleaseCommit {
     pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<CommitAuthor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(
+        default,
+        rename = "dateCreated",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub date_created: Option<DateTime<Utc>>,
+}
+
+impl ReleaseCommit {
+    /// Returns the abbreviated commit SHA.
+    pub fn short_id(&self) -> &str {
+        self.id.get(..12).unwrap_or(&self.id)
+    }
+
+    /// Returns the first line of the commit message.
+    pub fn summary(&self) -> &str {
+        self.message
+            .as_deref()
+            .and_then(|message| message.lines().next())
+            .unwrap_or("")
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitAuthor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -2143,7 +3082,6 @@ pub struct DebugInfoData {
     #[serde(default, rename = "type")]
     pub kind: Option<ObjectKind>,
     #[serde(default)]
-    #[expect(dead_code)]
     pub features: Vec<String>,
 }
 
@@ -2151,6 +3089,9 @@ pub struct DebugInfoData {
 /// Can be dSYMs, ELF debug infos, Breakpad symbols, etc...
 #[derive(Debug, Deserialize)]
 pub struct DebugInfoFile {
+    /// The ID of the file on the server, which is not included in all responses.
+    #[serde(rename = "id", default)]
+    pub file_id: String,
     #[serde(rename = "uuid")]
     uuid: Option<DebugId>,
     #[serde(rename = "debugId")]
@@ -2159,6 +3100,8 @@ pub struct DebugInfoFile {
     pub object_name: String,
     #[serde(rename = "cpuName")]
     pub cpu_name: String,
+    #[serde(default)]
+    pub size: Option<u64>,
     #[serde(rename = "sha1")]
     #[expect(dead_code)]
     pub checksum: String,
@@ -2183,12 +3126,16 @@ struct MissingChecksumsResponse {
     missing: HashSet<Digest>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Issue {
     pub id: String,
     pub short_id: String,
     pub title: String,
+    #[serde(default)]
+    pub culprit: String,
+    #[serde(default)]
+    pub count: String,
     pub last_seen: String,
     pub status: String,
     pub level: String,
@@ -2260,7 +3207,7 @@ impl IssueFilter {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Organization {
     pub id: String,
     pub slug: String,
@@ -2272,63 +3219,123 @@ pub struct Organization {
     #[serde(rename = "require2FA")]
     pub require_2fa: bool,
     #[serde(rename = "requireEmailVerification")]
-    #[expect(dead_code)]
     pub require_email_verification: bool,
-    #[expect(dead_code)]
     pub features: Vec<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Team {
-    #[expect(dead_code)]
     pub id: String,
-    #[expect(dead_code)]
     pub slug: String,
     pub name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ProjectSlugAndName {
     pub slug: String,
-    #[expect(dead_code)]
     pub name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Project {
     pub id: String,
     pub slug: String,
     pub name: String,
+    #[serde(default)]
+    pub platform: Option<String>,
     pub team: Option<Team>,
+    #[serde(default)]
+    pub teams: Vec<Team>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct Monitor {
+/// The payload used to create a project.
+#[derive(Debug, Serialize)]
+pub struct NewProject<'a> {
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slug: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<&'a str>,
+}
+
+/// A client key of a project.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectKey {
     pub id: String,
-    pub slug: String,
+    #[serde(default)]
     pub name: String,
-    pub status: String,
+    #[serde(default, rename = "isActive")]
+    pub is_active: bool,
+    #[serde(default, rename = "rateLimit")]
+    pub rate_limit: Option<ProjectKeyRateLimit>,
+    pub dsn: ProjectKeyDsn,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectKeyDsn {
+    pub public: String,
+}
+
+/// The number of events a client key accepts within a window of seconds.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectKeyRateLimit {
+    pub window: u64,
+    pub count: u64,
+}
+
+/// The payload used to create a client key.
+#[derive(Debug, Serialize)]
+pub struct NewProjectKey<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'a str>,
+}
+
+/// The payload used to update a client key.
+#[derive(Debug, Default, Serialize)]
+pub struct UpdatedProjectKey {
+    #[serde(rename = "isActive", skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+}
+
+/// A project as returned by the project index, along with its organization.
+#[derive(Deserialize, Debug)]
+pub struct ProjectWithOrganization {
+    pub id: String,
+    pub slug: String,
+    pub organization: ProjectOrganization,
 }
 
 #[derive(Deserialize, Debug)]
+pub struct ProjectOrganization {
+    pub slug: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RepoProvider {
     pub id: String,
     pub name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Repo {
     pub id: String,
     pub name: String,
     pub url: Option<String>,
     pub provider: RepoProvider,
-    #[expect(dead_code)]
     pub status: String,
     #[serde(rename = "dateCreated")]
-    #[expect(dead_code)]
     pub date_created: DateTime<Utc>,
 }
 
+/// The payload used to create a repository.
+#[derive(Debug, Serialize)]
+pub struct NewRepo<'a> {
+    pub provider: &'a str,
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<&'a str>,
+}
+
 impl fmt::Display for Repo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}:{}", &self.provider.id, &self.id)?;
@@ -2379,6 +3386,23 @@ pub struct ProcessedEvent {
     pub user: Option<ProcessedEventUser>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<ProcessedEventTag>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug_meta: Option<ProcessedEventDebugMeta>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProcessedEventDebugMeta {
+    #[serde(default)]
+    pub images: Vec<ProcessedEventDebugImage>,
+}
+
+/// A debug image of an event, such as a source map referenced by debug ID.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProcessedEventDebugImage {
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub code_file: Option<String>,
+    pub debug_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
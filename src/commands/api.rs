@@ -0,0 +1,181 @@
+use std::fs;
+use std::io::{self, Read};
+
+use anyhow::{bail, Context, Result};
+use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
+use percent_encoding::percent_decode_str;
+
+use crate::api::{Api, Method, QueryArg};
+
+/// The prefix that all paths requested with this command must start with.
+const API_PREFIX: &str = "/api/0/";
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Send an authenticated request to the Sentry API.")
+        .long_about(
+            "Send an authenticated request to the Sentry API.{n}{n}\
+             The request is sent to the configured server with the configured auth token, \
+             and the response body is printed. Use this for endpoints that have no dedicated \
+             command. Paths must start with `/api/0/`, for example:{n}{n}    \
+             sentry-cli api GET /api/0/organizations/my-org/projects/ --paginate",
+        )
+        .arg(
+            Arg::new("method")
+                .value_name("METHOD")
+                .required(true)
+                .value_parser(PossibleValuesParser::new(["GET", "POST", "PUT", "DELETE"]))
+                .ignore_case(true)
+                .help("The HTTP method of the request."),
+        )
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .required(true)
+                .help("The path of the endpoint, starting with `/api/0/`."),
+        )
+        .arg(
+            Arg::new("data")
+                .long("data")
+                .short('d')
+                .value_name("JSON")
+                .help(
+                    "The JSON body of the request. Use `@path` to read it from a file, \
+                     or `-` to read it from stdin.",
+                ),
+        )
+        .arg(
+            Arg::new("paginate")
+                .long("paginate")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Follow the pagination of the response and print the results of all \
+                     pages as a single JSON array. Only supported for GET requests.",
+                ),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["raw", "json"])
+                .default_value("raw")
+                .help("Print the response body as received, or as pretty-printed JSON."),
+        )
+}
+
+/// Checks that `path` refers to an endpoint of the API.
+///
+/// This prevents sending the auth token to other hosts or to paths outside of
+/// the API with absolute URLs or `..` components.
+fn validate_path(path: &str) -> Result<()> {
+    if !path.starts_with(API_PREFIX) {
+        bail!("The path must start with {API_PREFIX}, got `{path}`.");
+    }
+
+    let path_only = path.split(['?', '#']).next().unwrap_or_default();
+    let decoded = percent_decode_str(path_only).decode_utf8_lossy();
+    if decoded.contains('\\')
+        || decoded
+            .split('/')
+            .any(|component| component == "." || component == "..")
+    {
+        bail!("The path must not contain `.` or `..` components, got `{path}`.");
+    }
+
+    Ok(())
+}
+
+/// Reads the request body given with `--data`.
+fn read_body(data: &str) -> Result<serde_json::Value> {
+    let body = if data == "-" {
+        let mut body = String::new();
+        io::stdin().read_to_string(&mut body)?;
+        body
+    } else if let Some(path) = data.strip_prefix('@') {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read request body from {path}"))?
+    } else {
+        data.to_owned()
+    };
+
+    serde_json::from_str(&body).context("The request body is not valid JSON")
+}
+
+/// Sets the pagination cursor in the query string of `path`, replacing a
+/// cursor the path already has.
+fn with_cursor(path: &str, cursor: &str) -> String {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let mut params: Vec<_> = query
+        .split('&')
+        .filter(|param| !param.is_empty() && param.split('=').next() != Some("cursor"))
+        .map(str::to_owned)
+        .collect();
+    params.push(format!("cursor={}", QueryArg(cursor)));
+    format!("{path}?{}", params.join("&"))
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let method = match matches
+        .get_one::<String>("method")
+        .unwrap()
+        .to_ascii_uppercase()
+        .as_str()
+    {
+        "POST" => Method::Post,
+        "PUT" => Method::Put,
+        "DELETE" => Method::Delete,
+        _ => Method::Get,
+    };
+    let path = matches.get_one::<String>("path").unwrap();
+    validate_path(path)?;
+
+    let paginate = matches.get_flag("paginate");
+    if paginate && !matches!(method, Method::Get) {
+        bail!("Pagination is only supported for GET requests.");
+    }
+
+    let body = matches
+        .get_one::<String>("data")
+        .map(|data| read_body(data))
+        .transpose()?;
+    let pretty = matches.get_one::<String>("format").map(String::as_str) == Some("json");
+
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+
+    if !paginate {
+        let response = authenticated_api.send_raw_request(method, path, body.as_ref())?;
+        if pretty {
+            let value: serde_json::Value = serde_json::from_slice(response.body())
+                .context("The response body is not valid JSON")?;
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        } else {
+            println!("{}", String::from_utf8_lossy(response.body()));
+        }
+        return Ok(());
+    }
+
+    let mut results = Vec::new();
+    let mut page_path = path.to_owned();
+    loop {
+        let response = authenticated_api.send_raw_request(Method::Get, &page_path, None)?;
+        match serde_json::from_slice(response.body()) {
+            Ok(serde_json::Value::Array(page)) => results.extend(page),
+            _ => bail!("Cannot paginate {page_path}, the response is not a JSON array."),
+        }
+
+        match response.pagination().into_next_cursor() {
+            Some(cursor) => page_path = with_cursor(path, &cursor),
+            None => break,
+        }
+    }
+
+    let results = serde_json::Value::Array(results);
+    if pretty {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        println!("{results}");
+    }
+
+    Ok(())
+}
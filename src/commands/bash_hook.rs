@@ -79,13 +79,13 @@ pub fn make_command(command: Command) -> Command {
         .arg(Arg::new("log").long("log").value_name("PATH").hide(true))
 }
 
-fn send_event(
-    traceback: &str,
-    logfile: &str,
+/// Creates the event reported by the shell hooks, with the given tags,
+/// release and optionally the environment variables attached.
+pub(super) fn hook_event(
     tags: &[&String],
     release: Option<String>,
     environ: bool,
-) -> Result<()> {
+) -> Result<Event<'static>> {
     let config = Config::current();
 
     let mut event = Event {
@@ -116,6 +116,53 @@ fn send_event(
         );
     }
 
+    Ok(event)
+}
+
+/// Fills in the source lines around each frame from the files on disk.
+pub(super) fn add_source_context(frames: &mut [Frame]) {
+    let mut source_caches = HashMap::new();
+    for frame in frames {
+        let lineno = match frame.lineno {
+            Some(line) => line as usize,
+            None => continue,
+        };
+
+        let Some(filename) = frame.filename.clone() else {
+            continue;
+        };
+
+        let source = source_caches
+            .entry(filename)
+            .or_insert_with_key(|filename| {
+                if let Ok(f) = fs::File::open(filename) {
+                    BufReader::new(f)
+                        .lines()
+                        .map(|x| x.unwrap_or_else(|_| "".to_string()))
+                        .collect()
+                } else {
+                    vec![]
+                }
+            });
+        frame.context_line = source.get(lineno.saturating_sub(1)).cloned();
+        if let Some(slice) = source.get(lineno.saturating_sub(5)..lineno.saturating_sub(1)) {
+            frame.pre_context = slice.to_vec();
+        };
+        if let Some(slice) = source.get(lineno..min(lineno + 5, source.len())) {
+            frame.post_context = slice.to_vec();
+        };
+    }
+}
+
+fn send_event(
+    traceback: &str,
+    logfile: &str,
+    tags: &[&String],
+    release: Option<String>,
+    environ: bool,
+) -> Result<()> {
+    let mut event = hook_event(tags, release, environ)?;
+
     let mut cmd = "unknown".to_string();
     let mut exit_code = 1;
     let mut frames = vec![];
@@ -155,37 +202,7 @@ fn send_event(
         }
     }
 
-    {
-        let mut source_caches = HashMap::new();
-        for frame in &mut frames {
-            let lineno = match frame.lineno {
-                Some(line) => line as usize,
-                None => continue,
-            };
-
-            let filename = frame.filename.as_deref().expect("frame without location");
-
-            if !source_caches.contains_key(filename) {
-                if let Ok(f) = fs::File::open(filename) {
-                    let lines: Vec<_> = BufReader::new(f)
-                        .lines()
-                        .map(|x| x.unwrap_or_else(|_| "".to_string()))
-                        .collect();
-                    source_caches.insert(filename, lines);
-                } else {
-                    source_caches.insert(filename, vec![]);
-                }
-            }
-            let source = &source_caches[filename];
-            frame.context_line = source.get(lineno.saturating_sub(1)).cloned();
-            if let Some(slice) = source.get(lineno.saturating_sub(5)..lineno.saturating_sub(1)) {
-                frame.pre_context = slice.to_vec();
-            };
-            if let Some(slice) = source.get(lineno..min(lineno + 5, source.len())) {
-                frame.post_context = slice.to_vec();
-            };
-        }
-    }
+    add_source_context(&mut frames);
 
     attach_logfile(&mut event, logfile, true)?;
 
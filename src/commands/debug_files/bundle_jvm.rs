@@ -1,10 +1,10 @@
-use crate::api::Api;
 use crate::config::Config;
 use crate::constants::DEFAULT_MAX_WAIT;
 use crate::utils::args::ArgExt;
 use crate::utils::file_search::ReleaseFileSearch;
-use crate::utils::file_upload::{FileUpload, SourceFile, UploadContext};
+use crate::utils::file_upload::{FileUpload, SourceFile, SourceFiles, UploadContext};
 use crate::utils::fs::path_as_url;
+use crate::utils::logging::is_quiet_mode;
 use anyhow::{bail, Context, Result};
 use clap::{Arg, ArgMatches, Command};
 use sentry::types::DebugId;
@@ -13,21 +13,33 @@ use std::fs;
 use std::path::PathBuf;
 use std::str::FromStr;
 use symbolic::debuginfo::sourcebundle::SourceFileType;
+use uuid::Uuid;
 
 pub fn make_command(command: Command) -> Command {
     command
-        .hide(true) // experimental for now
         .about(
             "Create a source bundle for the given JVM based source files (e.g. Java, Kotlin, ...)",
         )
+        .long_about(
+            "Create a source bundle for the given JVM based source files (e.g. Java, Kotlin, ...){n}{n}\
+             The bundle is written to `<output>/<debug-id>.zip` and can be uploaded with \
+             `sentry-cli debug-files upload --type jvm <output>`. To associate it with events, \
+             the bundle ID has to be added to the `io.sentry.bundle-ids` entry of the \
+             application's manifest or properties. With `--quiet`, only the bundle ID is \
+             printed, so that build tools can capture it.",
+        )
         .org_arg()
         .project_arg(false)
         .arg(
             Arg::new("path")
                 .value_name("PATH")
                 .required(true)
+                .num_args(1..)
                 .value_parser(clap::builder::PathBufValueParser::new())
-                .help("The directory containing source files to bundle."),
+                .help(
+                    "The source roots, i.e. the directories containing the packages of the \
+                     source files to bundle.",
+                ),
         )
         .arg(
             Arg::new("output")
@@ -41,9 +53,11 @@ pub fn make_command(command: Command) -> Command {
             Arg::new("debug_id")
                 .long("debug-id")
                 .value_name("UUID")
-                .required(true)
                 .value_parser(DebugId::from_str)
-                .help("Debug ID (UUID) to use for the source bundle."),
+                .help(
+                    "Debug ID (UUID) to use for the source bundle. \
+                     [default: a randomly generated UUID]",
+                ),
         )
 }
 
@@ -51,31 +65,35 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let org = config.get_org(matches)?;
     let project = config.get_project(matches).ok();
-    let api = Api::current();
-    let chunk_upload_options = api.authenticated()?.get_chunk_upload_options(&org)?;
 
     let context = &UploadContext {
         org: &org,
         project: project.as_deref(),
         release: None,
         dist: None,
-        note: None,
+        metadata: None,
         wait: true,
         max_wait: DEFAULT_MAX_WAIT,
         dedupe: false,
-        chunk_upload_options: chunk_upload_options.as_ref(),
+        chunk_upload_options: None,
+        compression: None,
     };
-    let path = matches.get_one::<PathBuf>("path").unwrap();
+    let paths = matches.get_many::<PathBuf>("path").unwrap();
     let output_path = matches.get_one::<PathBuf>("output").unwrap();
-    let debug_id = matches.get_one::<DebugId>("debug_id").unwrap();
+    let debug_id = matches
+        .get_one::<DebugId>("debug_id")
+        .copied()
+        .unwrap_or_else(|| DebugId::from_uuid(Uuid::new_v4()));
     let out = output_path.join(format!("{debug_id}.zip"));
 
-    if !path.exists() {
-        bail!("Given path does not exist: {}", path.display())
-    }
+    for path in paths.clone() {
+        if !path.exists() {
+            bail!("Given path does not exist: {}", path.display())
+        }
 
-    if !path.is_dir() {
-        bail!("Given path is not a directory: {}", path.display())
+        if !path.is_dir() {
+            bail!("Given path is not a directory: {}", path.display())
+        }
     }
 
     if !output_path.exists() {
@@ -85,35 +103,47 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         ))?;
     }
 
-    let sources = ReleaseFileSearch::new(path.to_path_buf()).collect_files()?;
-    let files = sources
-        .iter()
-        .map(|source| {
+    let mut files = SourceFiles::new();
+    for path in paths {
+        for source in ReleaseFileSearch::new(path.to_path_buf()).collect_files()? {
             let local_path = source.path.strip_prefix(&source.base_path).unwrap();
             let local_path_jvm_ext = local_path.with_extension("jvm");
             let url = format!("~/{}", path_as_url(&local_path_jvm_ext));
-            (
-                url.to_string(),
+            if let Some(existing) = files.get(&url).map(|file| &file.path) {
+                bail!(
+                    "Both {} and {} would be bundled as {}, source roots must not contain \
+                     the same file",
+                    existing.display(),
+                    source.path.display(),
+                    url
+                );
+            }
+            files.insert(
+                url.clone(),
                 SourceFile {
                     url,
-                    path: source.path.clone(),
-                    contents: source.contents.clone(),
+                    path: source.path,
+                    contents: source.contents,
                     ty: SourceFileType::Source,
                     headers: BTreeMap::new(),
                     messages: vec![],
                     already_uploaded: false,
                 },
-            )
-        })
-        .collect();
+            );
+        }
+    }
 
     let tempfile = FileUpload::new(context)
         .files(&files)
-        .build_jvm_bundle(Some(*debug_id))
+        .build_jvm_bundle(Some(debug_id))
         .context("Unable to create source bundle")?;
 
     fs::copy(tempfile.path(), &out).context("Unable to write source bundle")?;
-    println!("Created {}", out.display());
+    if is_quiet_mode() {
+        println!("{debug_id}");
+    } else {
+        println!("Created {}", out.display());
+    }
 
     Ok(())
 }
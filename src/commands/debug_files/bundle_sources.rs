@@ -1,13 +1,14 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
-use log::{info, warn};
+use glob::Pattern;
+use log::warn;
 use symbolic::debuginfo::sourcebundle::SourceBundleWriter;
 
-use crate::utils::dif::DifFile;
-use crate::utils::dif_upload::filter_bad_sources;
+use crate::config::Config;
+use crate::utils::dif::{DifFile, SourceBundleOptions};
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -29,6 +30,32 @@ pub fn make_command(command: Command) -> Command {
                      file is placed next to the input file.",
                 ),
         )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .value_name("GLOB")
+                .action(ArgAction::Append)
+                .value_parser(|value: &str| Pattern::new(value))
+                .help("Skip source files whose path matches the given glob pattern."),
+        )
+        .arg(
+            Arg::new("prefix_map")
+                .long("prefix-map")
+                .value_name("OLD=NEW")
+                .action(ArgAction::Append)
+                .value_parser(parse_prefix_map)
+                .help(
+                    "Resolve source files of objects built with `-fdebug-prefix-map=OLD=NEW`. \
+                     Referenced paths starting with NEW are read from OLD on disk.",
+                ),
+        )
+}
+
+fn parse_prefix_map(value: &str) -> Result<(String, String)> {
+    value
+        .split_once('=')
+        .map(|(old, new)| (old.to_owned(), new.to_owned()))
+        .ok_or_else(|| format_err!("expected a prefix map in the form OLD=NEW"))
 }
 
 fn is_dsym(path: &Path) -> bool {
@@ -69,6 +96,20 @@ fn get_canonical_path<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let output_path = matches.get_one::<String>("output").map(Path::new);
+    let options = SourceBundleOptions {
+        exclude: matches
+            .get_many::<Pattern>("exclude")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        prefix_map: matches
+            .get_many::<(String, String)>("prefix_map")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        max_source_size: Some(Config::current().get_max_dif_item_size()),
+        include_il2cpp_sources: false,
+    };
 
     for orig_path in matches.get_many::<String>("paths").unwrap() {
         let canonical_path = get_canonical_path(orig_path)?;
@@ -100,15 +141,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             // Resolve source files from the object and write their contents into the archive. Skip to
             // upload this bundle if no source could be written. This can happen if there is no file or
             // line information in the object file, or if none of the files could be resolved.
-            let written = writer
-                .with_skipped_file_callback(|skipped_info| info!("{skipped_info}"))
-                .write_object_with_filter(
-                    &object,
-                    &filename.to_string_lossy(),
-                    filter_bad_sources,
-                )?;
-
-            if !written {
+            let summary = options.write_object(writer, &object, &filename.to_string_lossy())?;
+
+            if summary.written == 0 {
                 eprintln!("skipped {orig_path} (no files found)");
                 fs::remove_file(&out)?;
                 continue;
@@ -1,11 +1,17 @@
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
 use console::style;
+use serde::Serialize;
+use symbolic::common::{CodeId, DebugId};
+use symbolic::debuginfo::ObjectKind;
 
-use crate::utils::dif::{DifFile, DifType};
+use crate::utils::dif::{DifFile, DifType, DifVariant, ObjectDifFeatures};
+use crate::utils::dif_upload::{
+    DART_SYMBOLS_EXTENSION, DART_SYMBOL_MAP_NAME, IL2CPP_LINE_MAPPING_NAME,
+};
 use crate::utils::logging::is_quiet_mode;
 use crate::utils::system::QuietExit;
 
@@ -40,6 +46,119 @@ pub fn make_command(command: Command) -> Command {
         )
 }
 
+/// The result of checking a debug information file.
+#[derive(Serialize)]
+struct DifCheck<'a> {
+    path: &'a Path,
+    #[serde(rename = "type")]
+    ty: DifType,
+    kind: String,
+    #[serde(skip)]
+    object_kind: Option<ObjectKind>,
+    arch: Option<String>,
+    debug_id: Option<DebugId>,
+    code_id: Option<CodeId>,
+    variants: Vec<DifVariant>,
+    features: String,
+    feature_flags: ObjectDifFeatures,
+    il2cpp_line_mapping: Option<PathBuf>,
+    dart_symbol_map: Option<PathBuf>,
+    is_usable: bool,
+    problem: Option<&'static str>,
+    note: Option<&'static str>,
+    problems: Vec<&'static str>,
+}
+
+impl<'a> DifCheck<'a> {
+    fn new(path: &'a Path, dif: &DifFile) -> Self {
+        let variants = dif.variants();
+        let first_variant = variants.first();
+        let line_mapping = path.with_file_name(IL2CPP_LINE_MAPPING_NAME);
+        let dart_symbol_map = path.with_file_name(DART_SYMBOL_MAP_NAME);
+        let is_dart_symbols = path
+            .extension()
+            .map_or(false, |e| e == DART_SYMBOLS_EXTENSION);
+        let note = dif.get_note();
+        let problem = dif.get_problem();
+        let features = dif.features();
+
+        DifCheck {
+            path,
+            ty: dif.ty(),
+            kind: dif.format_name(),
+            object_kind: dif.kind(),
+            arch: first_variant.and_then(|v| v.arch.clone()),
+            debug_id: first_variant.map(|v| v.debug_id),
+            code_id: first_variant.and_then(|v| v.code_id.clone()),
+            variants,
+            features: features.to_string(),
+            feature_flags: features,
+            il2cpp_line_mapping: line_mapping.is_file().then_some(line_mapping),
+            dart_symbol_map: (is_dart_symbols && dart_symbol_map.is_file())
+                .then_some(dart_symbol_map),
+            is_usable: dif.is_usable(),
+            problem,
+            note,
+            problems: note.into_iter().chain(problem).collect(),
+        }
+    }
+
+    fn print(&self) {
+        println!("{}", style("Debug Info File Check").dim().bold());
+        match self.object_kind {
+            Some(class) => println!(
+                "  Type: {} {:#}",
+                style(self.ty).cyan(),
+                style(class).cyan()
+            ),
+            None => println!("  Type: {}", style(self.ty).cyan()),
+        }
+
+        // ProGuard mappings are identified by the UUID that `upload-proguard` reports
+        let id_label = match self.ty {
+            DifType::Proguard => "UUID:    ",
+            _ => "Debug ID:",
+        };
+        println!("  Contained debug identifiers:");
+        for variant in &self.variants {
+            println!("    > {id_label} {}", style(variant.debug_id).dim());
+            if let Some(ref code_id) = variant.code_id {
+                println!("      Code ID:  {}", style(code_id).dim());
+            }
+            if let Some(ref arch) = variant.arch {
+                println!("      Arch:     {}", style(arch).dim());
+            }
+        }
+
+        println!("  Contained debug information:");
+        println!("    > {}", self.features);
+
+        if let Some(ref line_mapping) = self.il2cpp_line_mapping {
+            println!(
+                "  Associated il2cpp line mapping: {}",
+                style(line_mapping.display()).dim()
+            );
+        }
+
+        if let Some(ref symbol_map) = self.dart_symbol_map {
+            println!(
+                "  Associated Dart symbol map: {}",
+                style(symbol_map.display()).dim()
+            );
+        }
+
+        if let Some(msg) = self.note {
+            println!("  Note: {msg}");
+        }
+
+        if let Some(prob) = self.problem {
+            println!("  Usable: {} ({})", style("no").red(), prob);
+        } else {
+            println!("  Usable: {}", style("yes").green());
+        }
+    }
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let path = Path::new(matches.get_one::<String>("path").unwrap());
 
@@ -56,53 +175,18 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .get_one::<String>("type")
         .map(|t| t.parse().unwrap());
     let dif = DifFile::open_path(path, ty)?;
+    let check = DifCheck::new(path, &dif);
 
     if matches.get_flag("json") {
-        serde_json::to_writer_pretty(&mut io::stdout(), &dif)?;
+        serde_json::to_writer_pretty(&mut io::stdout(), &check)?;
         println!();
+    } else if !is_quiet_mode() {
+        check.print();
     }
 
-    if matches.get_flag("json") || is_quiet_mode() {
-        return if dif.is_usable() {
-            Ok(())
-        } else {
-            Err(QuietExit(1).into())
-        };
-    }
-
-    println!("{}", style("Debug Info File Check").dim().bold());
-    match dif.kind() {
-        Some(class) => println!(
-            "  Type: {} {:#}",
-            style(dif.ty()).cyan(),
-            style(class).cyan()
-        ),
-        None => println!("  Type: {}", style(dif.ty()).cyan()),
-    }
-
-    println!("  Contained debug identifiers:");
-    for variant in dif.variants() {
-        println!("    > Debug ID: {}", style(variant.debug_id).dim());
-        if let Some(code_id) = variant.code_id {
-            println!("      Code ID:  {}", style(code_id).dim());
-        }
-        if let Some(arch) = variant.arch {
-            println!("      Arch:     {}", style(arch).dim());
-        }
-    }
-
-    println!("  Contained debug information:");
-    println!("    > {}", dif.features());
-
-    if let Some(msg) = dif.get_note() {
-        println!("  Note: {msg}");
-    }
-
-    if let Some(prob) = dif.get_problem() {
-        println!("  Usable: {} ({})", style("no").red(), prob);
-        Err(QuietExit(1).into())
-    } else {
-        println!("  Usable: {}", style("yes").green());
+    if check.is_usable {
         Ok(())
+    } else {
+        Err(QuietExit(1).into())
     }
 }
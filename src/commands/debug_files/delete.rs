@@ -0,0 +1,95 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use symbolic::common::DebugId;
+
+use crate::api::Api;
+use crate::commands::debug_files::list::print_difs;
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::ui::prompt_to_continue;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Delete debug information files of a project.")
+        .org_arg()
+        .project_arg(false)
+        .arg(
+            Arg::new("id")
+                .value_name("DIF_ID_OR_DEBUG_ID")
+                .required(true)
+                .help(
+                    "The ID of the file on the server, as shown by `debug-files list`, \
+                     or the debug identifier of the file.",
+                ),
+        )
+        .arg(
+            Arg::new("all_matching")
+                .long("all-matching")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Delete all files with the given debug identifier.  Without this, \
+                     deleting by debug identifier fails if multiple files have it.",
+                ),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .action(ArgAction::SetTrue)
+                .help("Delete the files without asking for confirmation."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let id = matches.get_one::<String>("id").unwrap();
+    let api = Api::current();
+    let authenticated = api.authenticated()?;
+
+    // Debug identifiers can consist of digits only, so they take precedence over
+    // server IDs, which are much shorter.
+    let file_ids = if let Ok(debug_id) = DebugId::from_str(id) {
+        let difs = authenticated.list_difs_by_debug_id(&org, &project, debug_id)?;
+        if difs.is_empty() {
+            bail!("No debug information files found with debug ID {debug_id}.");
+        }
+        if difs.len() > 1 && !matches.get_flag("all_matching") {
+            print_difs(&difs);
+            bail!(
+                "{} debug information files have debug ID {debug_id}. Delete one of them \
+                 by its ID, or all of them with --all-matching.",
+                difs.len()
+            );
+        }
+        print_difs(&difs);
+        difs.into_iter().map(|dif| dif.file_id).collect()
+    } else if id.bytes().all(|b| b.is_ascii_digit()) {
+        vec![id.clone()]
+    } else {
+        bail!("{id} is neither a file ID nor a debug identifier.");
+    };
+
+    let message = match file_ids.as_slice() {
+        [file_id] => format!("Do you really want to delete debug information file {file_id}?"),
+        _ => format!(
+            "Do you really want to delete {} debug information files?",
+            file_ids.len()
+        ),
+    };
+    if !matches.get_flag("yes") && !prompt_to_continue(&message)? {
+        println!("Aborted!");
+        return Ok(());
+    }
+
+    for file_id in &file_ids {
+        if !authenticated.delete_dif(&org, &project, file_id)? {
+            bail!("Debug information file {file_id} does not exist.");
+        }
+        println!("Deleted debug information file {file_id}");
+    }
+
+    Ok(())
+}
@@ -3,20 +3,24 @@ use std::env;
 use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
 use console::style;
 use if_chain::if_chain;
 use proguard::ProguardMapping;
 use serde::Serialize;
 use symbolic::common::{ByteView, DebugId};
+use symbolic::debuginfo::ObjectKind;
 use uuid::{Uuid, Version as UuidVersion};
 use walkdir::{DirEntry, WalkDir};
 
-use crate::utils::dif::{DifFile, DifType};
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::dif::{DifFile, DifType, ObjectDifFeatures};
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 use crate::utils::system::QuietExit;
 
@@ -30,16 +34,44 @@ struct DifMatch {
     pub ty: DifType,
     pub id: DebugId,
     pub path: PathBuf,
+    pub kind: Option<&'static str>,
+    pub arch: Option<String>,
+    pub features: ObjectDifFeatures,
+}
+
+/// A debug information file with a searched debug identifier stored on the server.
+#[derive(Serialize, Debug)]
+struct ServerDif {
+    pub id: DebugId,
+    pub object_name: String,
+    pub arch: String,
+    pub kind: Option<&'static str>,
+    pub features: Vec<String>,
+}
+
+/// The output of `--check-server` in JSON format.
+#[derive(Serialize)]
+struct FindOutput<'a> {
+    files: &'a [DifMatch],
+    server: &'a [ServerDif],
 }
 
 pub fn make_command(command: Command) -> Command {
     command
         .about("Locate debug information files for given debug identifiers.")
+        .org_arg()
+        .project_arg(false)
+        // `-p` is the short form of `--path` here.
+        .mut_arg("project", |arg| arg.short(None))
         .arg(
             Arg::new("ids")
-                .value_name("ID")
-                .help("The debug identifiers of the files to search for.")
-                .value_parser(DebugId::from_str)
+                .value_name("ID_OR_PATH")
+                .help(
+                    "The debug identifiers of the files to search for. Other values are \
+                     treated as paths to search recursively, like `--path`. Values that \
+                     look like debug identifiers but are invalid and no existing path are \
+                     rejected.",
+                )
                 .num_args(1..)
                 .action(ArgAction::Append),
         )
@@ -59,7 +91,11 @@ pub fn make_command(command: Command) -> Command {
             Arg::new("no_well_known")
                 .long("no-well-known")
                 .action(ArgAction::SetTrue)
-                .help("Do not look for debug symbols in well known locations."),
+                .help(
+                    "Do not look for debug symbols in well known locations, such as \
+                     Xcode's derived data, archives and device support folders, and the \
+                     Breakpad symbol tree in `~/.breakpad/symbols`.",
+                ),
         )
         .arg(
             Arg::new("no_cwd")
@@ -75,14 +111,38 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::Append)
                 .help("Add a path to search recursively for debug info files."),
         )
+        .arg(
+            Arg::new("check_server")
+                .long("check-server")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Also look up the debug identifiers on Sentry and report whether the \
+                     project already has matching files, and with which features. The JSON \
+                     output then is an object with the local `files` and the `server` files.",
+                ),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .help("The output format."),
+        )
         .arg(
             Arg::new("json")
                 .long("json")
                 .action(ArgAction::SetTrue)
-                .help("Format outputs as JSON."),
+                .help("Format outputs as JSON. Alias for `--format json`."),
         )
 }
 
+/// Checks whether a value only consists of the characters of a debug identifier, so
+/// that it was likely meant to be one.
+fn looks_like_debug_id(value: &str) -> bool {
+    value.len() >= 8 && value.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
 fn id_hint(id: &DebugId) -> &'static str {
     if id.appendix() > 0 {
         return "likely PDB";
@@ -100,10 +160,8 @@ fn find_ids(
     paths: &HashSet<PathBuf>,
     types: &HashSet<DifType>,
     ids: &HashSet<DebugId>,
-    as_json: bool,
-) -> Result<bool> {
+) -> Vec<DifMatch> {
     let mut remaining = ids.clone();
-    let mut breakpad_found = HashSet::new();
     let mut proguard_uuids: HashSet<_> = ids
         .iter()
         .map(DebugId::uuid)
@@ -141,63 +199,136 @@ fn find_ids(
         let found: Vec<_> = types
             .iter()
             .filter_map(|t| match t {
-                DifType::Dsym => find_ids_for_dsym(&dirent, &remaining),
-                DifType::Elf => find_ids_for_elf(&dirent, &remaining),
-                DifType::Pe => find_ids_for_pe(&dirent, &remaining),
-                DifType::Pdb => find_ids_for_pdb(&dirent, &remaining),
-                DifType::PortablePdb => find_ids_for_portablepdb(&dirent, &remaining),
-                DifType::SourceBundle => find_ids_for_sourcebundle(&dirent, &remaining),
-                DifType::Breakpad => find_ids_for_breakpad(&dirent, &remaining),
+                DifType::Dsym => find_ids_for_dsym(&dirent),
+                DifType::Elf => find_ids_for_elf(&dirent),
+                DifType::Pe => find_ids_for_pe(&dirent),
+                DifType::Pdb => find_ids_for_pdb(&dirent),
+                DifType::PortablePdb => find_ids_for_portablepdb(&dirent),
+                DifType::SourceBundle => find_ids_for_sourcebundle(&dirent),
+                DifType::Breakpad => find_ids_for_breakpad(&dirent),
                 DifType::Proguard => find_ids_for_proguard(&dirent, &proguard_uuids),
-                DifType::Jvm => find_ids_for_sourcebundle(&dirent, &remaining),
+                DifType::Jvm => find_ids_for_sourcebundle(&dirent),
                 DifType::Wasm => None,
+                // Dart symbol files are ELF files, so only search for them once.
+                DifType::Dart if !types.contains(&DifType::Elf) => find_ids_for_elf(&dirent),
+                DifType::Dart => None,
             })
-            .flatten()
+            .flat_map(|dif| extract_remaining_ids(&dif, &remaining, dirent.path()))
             .collect();
 
-        for (id, ty) in found {
-            let path = dirent.path().to_path_buf();
-            found_files.push(DifMatch { ty, id, path });
-            if ty == DifType::Breakpad {
-                breakpad_found.insert(id);
-            } else {
-                remaining.remove(&id);
+        for m in found {
+            // Breakpad symbols are often converted from another file with
+            // the same identifier, so keep looking for that one as well.
+            if m.ty != DifType::Breakpad {
+                remaining.remove(&m.id);
             }
-            proguard_uuids.remove(&id.uuid());
+            proguard_uuids.remove(&m.id.uuid());
+            found_files.push(m);
         }
     }
 
     pb.finish_and_clear();
+    found_files
+}
 
-    if as_json {
-        serde_json::to_writer_pretty(&mut io::stdout(), &found_files)?;
-        println!();
-    } else {
-        for m in found_files {
-            println!(
-                "{} {} [{}]",
-                style(m.id).dim(),
-                m.path.display(),
-                style(m.ty).yellow()
-            );
+/// Looks up the debug identifiers in the project on the server.
+fn find_server_ids(matches: &ArgMatches, ids: &HashSet<DebugId>) -> Result<Vec<ServerDif>> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+
+    let mut ids: Vec<_> = ids.iter().collect();
+    ids.sort();
+
+    let mut found = vec![];
+    for id in ids {
+        for dif in authenticated_api.list_difs_by_debug_id(&org, &project, *id)? {
+            found.push(ServerDif {
+                id: dif.id(),
+                object_name: dif.object_name,
+                arch: dif.cpu_name,
+                kind: dif.data.kind.map(ObjectKind::human_name),
+                features: dif.data.features,
+            });
         }
-        remaining.extend(breakpad_found);
-        if !remaining.is_empty() {
-            eprintln!();
-            eprintln!("missing debug information files:");
-            for id in &remaining {
-                eprintln!("  {} ({})", id, id_hint(id),);
-            }
+    }
+    Ok(found)
+}
+
+fn print_matches(
+    found_files: &[DifMatch],
+    server_files: Option<&[ServerDif]>,
+    ids: &HashSet<DebugId>,
+) {
+    for m in found_files {
+        println!(
+            "{} {} [{}]",
+            style(m.id).dim(),
+            m.path.display(),
+            style(m.ty).yellow()
+        );
+        println!(
+            "  Kind: {}, Arch: {}, Features: {}",
+            m.kind.unwrap_or("unknown"),
+            m.arch.as_deref().unwrap_or("unknown"),
+            m.features
+        );
+    }
+
+    let mut ids: Vec<_> = ids.iter().collect();
+    ids.sort();
+
+    let remaining: Vec<_> = ids
+        .iter()
+        .filter(|&&id| {
+            !found_files
+                .iter()
+                .any(|m| m.id == *id && m.ty != DifType::Breakpad)
+        })
+        .collect();
+    if !remaining.is_empty() {
+        eprintln!();
+        eprintln!("missing debug information files:");
+        for id in remaining {
+            eprintln!("  {} ({})", id, id_hint(id),);
         }
     }
 
-    Ok(remaining.is_empty())
+    if let Some(server_files) = server_files {
+        println!();
+        println!(
+            "{}",
+            style("Debug information files on Sentry:").dim().bold()
+        );
+        for id in ids {
+            let mut files = server_files.iter().filter(|dif| dif.id == *id).peekable();
+            if files.peek().is_none() {
+                println!("  {} {}", style(id).dim(), style("not uploaded").red());
+            }
+            for dif in files {
+                println!(
+                    "  {} {} [{}]\n    Kind: {}, Arch: {}, Features: {}",
+                    style(id).dim(),
+                    dif.object_name,
+                    style("uploaded").green(),
+                    dif.kind.unwrap_or("unknown"),
+                    dif.arch,
+                    if dif.features.is_empty() {
+                        "none".to_owned()
+                    } else {
+                        dif.features.join(", ")
+                    }
+                );
+            }
+        }
+    }
 }
 
 fn find_ids_for_proguard(
     dirent: &DirEntry,
     proguard_uuids: &HashSet<Uuid>,
-) -> Option<Vec<(DebugId, DifType)>> {
+) -> Option<DifFile<'static>> {
     if_chain! {
         if !proguard_uuids.is_empty();
         if dirent.path().extension() == Some(OsStr::new("txt"));
@@ -208,123 +339,81 @@ fn find_ids_for_proguard(
         if mapping.is_valid();
         if proguard_uuids.contains(&mapping.uuid());
         then {
-            return Some(vec![(mapping.uuid().into(), DifType::Proguard)]);
+            return DifFile::open_path(dirent.path(), Some(DifType::Proguard)).ok();
         }
     }
     None
 }
 
-fn find_ids_for_dsym(
-    dirent: &DirEntry,
-    remaining: &HashSet<DebugId>,
-) -> Option<Vec<(DebugId, DifType)>> {
-    if_chain! {
-        // we regularly match on .class files but the will never be
-        // dsyms, so we can quickly skip them here
-        if dirent.path().extension() != Some(OsStr::new("class"));
-        if let Ok(dif) = DifFile::open_path(dirent.path(), Some(DifType::Dsym));
-        then {
-            return Some(extract_remaining_ids(&dif.ids(), remaining, DifType::Dsym))
-        }
+fn find_ids_for_dsym(dirent: &DirEntry) -> Option<DifFile<'static>> {
+    // we regularly match on .class files but the will never be
+    // dsyms, so we can quickly skip them here
+    if dirent.path().extension() == Some(OsStr::new("class")) {
+        return None;
     }
-    None
+    DifFile::open_path(dirent.path(), Some(DifType::Dsym)).ok()
 }
 
-fn find_ids_for_elf(
-    dirent: &DirEntry,
-    remaining: &HashSet<DebugId>,
-) -> Option<Vec<(DebugId, DifType)>> {
-    if_chain! {
-        if let Ok(dif) = DifFile::open_path(dirent.path(), Some(DifType::Elf));
-        then {
-            return Some(extract_remaining_ids(&dif.ids(), remaining, DifType::Elf))
-        }
-    }
-    None
+fn find_ids_for_elf(dirent: &DirEntry) -> Option<DifFile<'static>> {
+    DifFile::open_path(dirent.path(), Some(DifType::Elf)).ok()
 }
 
-fn find_ids_for_pe(
-    dirent: &DirEntry,
-    remaining: &HashSet<DebugId>,
-) -> Option<Vec<(DebugId, DifType)>> {
+fn find_ids_for_pe(dirent: &DirEntry) -> Option<DifFile<'static>> {
     if_chain! {
         if dirent.path().extension() == Some(OsStr::new("exe")) ||
         dirent.path().extension() == Some(OsStr::new("dll"));
-        if let Ok(dif) = DifFile::open_path(dirent.path(), Some(DifType::Pe));
         then {
-            return Some(extract_remaining_ids(&dif.ids(), remaining, DifType::Pe))
+            return DifFile::open_path(dirent.path(), Some(DifType::Pe)).ok();
         }
     }
     None
 }
 
-fn find_ids_for_pdb(
-    dirent: &DirEntry,
-    remaining: &HashSet<DebugId>,
-) -> Option<Vec<(DebugId, DifType)>> {
-    if_chain! {
-        if dirent.path().extension() == Some(OsStr::new("pdb"));
-        if let Ok(dif) = DifFile::open_path(dirent.path(), Some(DifType::Pdb));
-        then {
-            return Some(extract_remaining_ids(&dif.ids(), remaining, DifType::Pdb))
-        }
+fn find_ids_for_pdb(dirent: &DirEntry) -> Option<DifFile<'static>> {
+    if dirent.path().extension() != Some(OsStr::new("pdb")) {
+        return None;
     }
-    None
+    DifFile::open_path(dirent.path(), Some(DifType::Pdb)).ok()
 }
 
-fn find_ids_for_portablepdb(
-    dirent: &DirEntry,
-    remaining: &HashSet<DebugId>,
-) -> Option<Vec<(DebugId, DifType)>> {
-    if_chain! {
-        if dirent.path().extension() == Some(OsStr::new("pdb"));
-        if let Ok(dif) = DifFile::open_path(dirent.path(), Some(DifType::PortablePdb));
-        then {
-            return Some(extract_remaining_ids(&dif.ids(), remaining, DifType::PortablePdb))
-        }
+fn find_ids_for_portablepdb(dirent: &DirEntry) -> Option<DifFile<'static>> {
+    if dirent.path().extension() != Some(OsStr::new("pdb")) {
+        return None;
     }
-    None
+    DifFile::open_path(dirent.path(), Some(DifType::PortablePdb)).ok()
 }
 
-fn find_ids_for_sourcebundle(
-    dirent: &DirEntry,
-    remaining: &HashSet<DebugId>,
-) -> Option<Vec<(DebugId, DifType)>> {
-    if_chain! {
-        if dirent.path().extension() == Some(OsStr::new("zip"));
-        if let Ok(dif) = DifFile::open_path(dirent.path(), Some(DifType::SourceBundle));
-        then {
-            return Some(extract_remaining_ids(&dif.ids(), remaining, DifType::SourceBundle))
-        }
+fn find_ids_for_sourcebundle(dirent: &DirEntry) -> Option<DifFile<'static>> {
+    if dirent.path().extension() != Some(OsStr::new("zip")) {
+        return None;
     }
-    None
+    DifFile::open_path(dirent.path(), Some(DifType::SourceBundle)).ok()
 }
 
-fn find_ids_for_breakpad(
-    dirent: &DirEntry,
-    remaining: &HashSet<DebugId>,
-) -> Option<Vec<(DebugId, DifType)>> {
-    if_chain! {
-        if dirent.path().extension() == Some(OsStr::new("sym"));
-        if let Ok(dif) = DifFile::open_path(dirent.path(), Some(DifType::Breakpad));
-        then {
-            return Some(extract_remaining_ids(&dif.ids(), remaining, DifType::Breakpad))
-        }
+fn find_ids_for_breakpad(dirent: &DirEntry) -> Option<DifFile<'static>> {
+    if dirent.path().extension() != Some(OsStr::new("sym")) {
+        return None;
     }
-    None
+    DifFile::open_path(dirent.path(), Some(DifType::Breakpad)).ok()
 }
 
 fn extract_remaining_ids(
-    ids: &[DebugId],
+    dif: &DifFile<'_>,
     remaining: &HashSet<DebugId>,
-    t: DifType,
-) -> Vec<(DebugId, DifType)> {
-    ids.iter()
-        .filter_map(|id| {
-            if remaining.contains(id) {
-                return Some((id.to_owned(), t));
-            }
-            None
+    path: &Path,
+) -> Vec<DifMatch> {
+    let kind = dif.kind().map(ObjectKind::human_name);
+    let features = dif.features();
+    dif.variants()
+        .into_iter()
+        .filter(|variant| remaining.contains(&variant.debug_id))
+        .map(|variant| DifMatch {
+            ty: dif.ty(),
+            id: variant.debug_id,
+            path: path.to_path_buf(),
+            kind,
+            arch: variant.arch,
+            features,
         })
         .collect()
 }
@@ -347,13 +436,23 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let with_cwd = !matches.get_flag("no_cwd");
 
     // start adding well known locations
-    if_chain! {
-        if with_well_known;
-        if types.contains(&DifType::Dsym);
-        if let Some(path) = dirs::home_dir().map(|x| x.join("Library/Developer/Xcode/DerivedData"));
-        if path.is_dir();
-        then {
-            paths.insert(path);
+    if with_well_known && types.contains(&DifType::Dsym) {
+        if let Some(xcode) = dirs::home_dir().map(|x| x.join("Library/Developer/Xcode")) {
+            // Device support folders contain the system symbols in `<version>/Symbols`.
+            for folder in ["DerivedData", "Archives", "iOS DeviceSupport"] {
+                let path = xcode.join(folder);
+                if path.is_dir() {
+                    paths.insert(path);
+                }
+            }
+        }
+    }
+    if with_well_known && types.contains(&DifType::Breakpad) {
+        // The symbol tree used by `minidump_stackwalk` and the Breakpad tools.
+        if let Some(path) = dirs::home_dir().map(|x| x.join(".breakpad/symbols")) {
+            if path.is_dir() {
+                paths.insert(path);
+            }
         }
     }
 
@@ -373,16 +472,58 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
-    // which ids are we looking for?
-    if let Some(i) = matches.get_many::<DebugId>("ids") {
-        for id in i {
-            ids.insert(*id);
+    // which ids are we looking for? Positional values that are no debug
+    // identifiers are additional search paths.
+    if let Some(values) = matches.get_many::<String>("ids") {
+        for value in values {
+            match DebugId::from_str(value) {
+                Ok(id) => {
+                    ids.insert(id);
+                }
+                Err(_) if looks_like_debug_id(value) && !Path::new(value).exists() => {
+                    bail!("Invalid debug identifier: {value}");
+                }
+                Err(_) => {
+                    paths.insert(PathBuf::from(value));
+                }
+            }
         }
-    } else {
+    }
+    if ids.is_empty() {
         return Ok(());
     }
 
-    if !find_ids(&paths, &types, &ids, matches.get_flag("json"))? {
+    let found_files = find_ids(&paths, &types, &ids);
+    let server_files = if matches.get_flag("check_server") {
+        Some(find_server_ids(matches, &ids)?)
+    } else {
+        None
+    };
+
+    let as_json = matches.get_flag("json")
+        || matches.get_one::<String>("format").map(String::as_str) == Some("json");
+    if as_json {
+        match server_files {
+            Some(ref server_files) => serde_json::to_writer_pretty(
+                &mut io::stdout(),
+                &FindOutput {
+                    files: &found_files,
+                    server: server_files,
+                },
+            )?,
+            None => serde_json::to_writer_pretty(&mut io::stdout(), &found_files)?,
+        }
+        println!();
+    } else {
+        print_matches(&found_files, server_files.as_deref(), &ids);
+    }
+
+    let all_found = ids.iter().all(|id| {
+        found_files
+            .iter()
+            .any(|m| m.id == *id && m.ty != DifType::Breakpad)
+    });
+    if !all_found {
         return Err(QuietExit(1).into());
     }
 
@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use indicatif::HumanBytes;
+use symbolic::common::DebugId;
+
+use crate::api::{Api, DebugInfoFile};
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::formatting::Table;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("List the debug information files of a project with a debug identifier.")
+        .org_arg()
+        .project_arg(false)
+        .arg(
+            Arg::new("debug_id")
+                .long("debug-id")
+                .value_name("DEBUG_ID")
+                .value_parser(DebugId::from_str)
+                .required(true)
+                .help("The debug identifier of the files to list."),
+        )
+}
+
+/// Prints a table of debug information files stored on the server.
+pub(super) fn print_difs(difs: &[DebugInfoFile]) {
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("ID")
+        .add("Name")
+        .add("Arch")
+        .add("Size")
+        .add("Features");
+
+    for dif in difs {
+        table
+            .add_row()
+            .add(&dif.file_id)
+            .add(&dif.object_name)
+            .add(&dif.cpu_name)
+            .add(
+                dif.size
+                    .map_or_else(|| "-".into(), |size| HumanBytes(size).to_string()),
+            )
+            .add(if dif.data.features.is_empty() {
+                "-".into()
+            } else {
+                dif.data.features.join(", ")
+            });
+    }
+
+    table.print();
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let debug_id = *matches.get_one::<DebugId>("debug_id").unwrap();
+
+    let difs = Api::current()
+        .authenticated()?
+        .list_difs_by_debug_id(&org, &project, debug_id)?;
+
+    if difs.is_empty() {
+        println!("No debug information files found with debug ID {debug_id}");
+    } else {
+        print_difs(&difs);
+    }
+
+    Ok(())
+}
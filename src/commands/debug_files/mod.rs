@@ -4,7 +4,9 @@ use clap::{ArgMatches, Command};
 pub mod bundle_jvm;
 pub mod bundle_sources;
 pub mod check;
+pub mod delete;
 pub mod find;
+pub mod list;
 pub mod print_sources;
 pub mod upload;
 
@@ -14,6 +16,8 @@ macro_rules! each_subcommand {
         $mac!(check);
         $mac!(bundle_jvm);
         $mac!(find);
+        $mac!(list);
+        $mac!(delete);
         $mac!(print_sources);
         $mac!(upload);
     };
@@ -1,37 +1,71 @@
+use std::collections::HashSet;
 use std::fmt;
+use std::io;
 use std::path::Path;
 
 use anyhow::Result;
 use clap::{Arg, ArgMatches, Command};
-use symbolic::common::ByteView;
-use symbolic::debuginfo::{Archive, Object};
+use serde::Serialize;
+use symbolic::common::{ByteView, DebugId};
+use symbolic::debuginfo::{Archive, FileFormat, Object};
 
 pub fn make_command(command: Command) -> Command {
     command
         .about("Print source files linked by the given debug info file.")
+        .long_about(
+            "Print source files linked by the given debug info file.{n}{n}\
+             For source bundles, this lists the contained files along with their size. \
+             For other debug info files, this lists the referenced source files and whether \
+             they are embedded or available on the local disk.",
+        )
         .arg(
             Arg::new("path")
                 .required(true)
                 .help("The path to the debug info file."),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .help("The output format."),
+        )
+}
+
+/// The source files of a single object in the debug info file.
+#[derive(Serialize)]
+struct ObjectSources {
+    format: FileFormat,
+    debug_id: DebugId,
+    sources: Vec<SourceInfo>,
+}
+
+/// A source file referenced by or contained in an object.
+#[derive(Serialize)]
+struct SourceInfo {
+    path: String,
+    embedded: bool,
+    size: Option<usize>,
+    url: Option<String>,
+    debug_id: Option<DebugId>,
+    source_mapping_url: Option<String>,
+    available_locally: bool,
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let path = Path::new(matches.get_one::<String>("path").unwrap());
+    let json = matches.get_one::<String>("format").map(String::as_str) == Some("json");
 
     // which types should we consider?
     let data = ByteView::open(path)?;
     let archive = Archive::parse(&data)?;
 
-    if archive.object_count() == 0 {
-        println!("No objects found in the given debug info file");
-        return Ok(());
-    }
-
+    let mut objects = vec![];
     for object in archive.objects() {
         let object = object?;
 
-        print_object_sources(&object)?;
+        objects.push(collect_object_sources(&object)?);
 
         // In case of a PE file with an embedded PDB, handle the PPDB separately.
         if let Object::Pe(pe) = &object {
@@ -39,56 +73,109 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 let mut buf = Vec::new();
                 ppdb_data.decompress_to(&mut buf)?;
                 let ppdb = Object::parse(&buf)?;
-                print_object_sources(&ppdb)?;
+                objects.push(collect_object_sources(&ppdb)?);
             }
         }
     }
 
+    if json {
+        serde_json::to_writer_pretty(&mut io::stdout(), &objects)?;
+        println!();
+        return Ok(());
+    }
+
+    if objects.is_empty() {
+        println!("No objects found in the given debug info file");
+        return Ok(());
+    }
+
+    for object in &objects {
+        print_object_sources(object);
+    }
+
     Ok(())
 }
 
-fn print_object_sources(object: &Object) -> Result<()> {
+fn collect_object_sources(object: &Object) -> Result<ObjectSources> {
     let debug_session = object.debug_session()?;
 
     // We're not using object.has_sources() because it only reports on embedded sources, not referenced files.
-    if debug_session.files().next().is_none() {
+    // The same file may be referenced by multiple compilation units, so only list it once.
+    let mut seen = HashSet::new();
+    let mut sources = vec![];
+    for file in debug_session.files() {
+        let abs_path = file?.abs_path_str();
+        if !seen.insert(abs_path.clone()) {
+            continue;
+        }
+
+        let source = debug_session.source_by_path(abs_path.as_str())?;
+        let available_locally = Path::new(&abs_path).exists();
+        sources.push(match source {
+            Some(source) => SourceInfo {
+                embedded: source.contents().is_some(),
+                size: source.contents().map(str::len),
+                url: source.url().map(str::to_owned),
+                debug_id: source.debug_id(),
+                source_mapping_url: source.source_mapping_url().map(str::to_owned),
+                available_locally,
+                path: abs_path,
+            },
+            None => SourceInfo {
+                path: abs_path,
+                embedded: false,
+                size: None,
+                url: None,
+                debug_id: None,
+                source_mapping_url: None,
+                available_locally,
+            },
+        });
+    }
+
+    Ok(ObjectSources {
+        format: object.file_format(),
+        debug_id: object.debug_id(),
+        sources,
+    })
+}
+
+fn print_object_sources(object: &ObjectSources) {
+    if object.sources.is_empty() {
+        println!("{} {} has no sources.", object.format, object.debug_id);
+        return;
+    }
+
+    if object.format == FileFormat::SourceBundle {
         println!(
-            "{} {} has no sources.",
-            object.file_format(),
-            object.debug_id()
+            "{} {} contains {} source files:",
+            object.format,
+            object.debug_id,
+            object.sources.len()
         );
     } else {
-        println!(
-            "{} {} references sources:",
-            object.file_format(),
-            object.debug_id()
-        );
-        for file in debug_session.files() {
-            let file = file?;
-            let abs_path = file.abs_path_str();
-            println!("  {}", &abs_path);
-            let source = debug_session.source_by_path(abs_path.as_str())?;
-            match source {
-                Some(source) => {
-                    print_file_descriptor_detail(
-                        "Embedded",
-                        source.contents().map(|v| format!("{} bytes", v.len())),
-                    );
-                    print_file_descriptor_detail("Url", source.url());
-                    print_file_descriptor_detail("DebugId", source.debug_id());
-                    print_file_descriptor_detail("SourceMap Url", source.source_mapping_url());
-                }
-                None => {
-                    if Path::new(&abs_path).exists() {
-                        println!("    Not embedded, but available on the local disk.");
-                    } else {
-                        println!("    Not embedded nor available locally at the referenced path.");
-                    }
-                }
+        println!("{} {} references sources:", object.format, object.debug_id);
+    }
+
+    for source in &object.sources {
+        println!("  {}", source.path);
+        if source.embedded {
+            print_file_descriptor_detail(
+                "Embedded",
+                source.size.map(|size| format!("{size} bytes")),
+            );
+        }
+        print_file_descriptor_detail("Url", source.url.as_ref());
+        print_file_descriptor_detail("DebugId", source.debug_id.as_ref());
+        print_file_descriptor_detail("SourceMap Url", source.source_mapping_url.as_ref());
+        if !source.embedded && source.url.is_none() {
+            if source.available_locally {
+                println!("    Not embedded, but available on the local disk.");
+            } else {
+                println!("    Not embedded nor available locally at the referenced path.");
             }
         }
     }
-    Ok(())
 }
 
 fn print_file_descriptor_detail<T: fmt::Display>(name: &str, value: Option<T>) {
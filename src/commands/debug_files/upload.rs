@@ -1,22 +1,35 @@
 use std::collections::BTreeSet;
+use std::io;
+use std::path::Path;
+use std::process;
 use std::str::{self, FromStr};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use anyhow::{bail, format_err, Result};
+use anyhow::{bail, Result};
 use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
 use console::style;
+use indicatif::HumanBytes;
 use itertools::Itertools;
 use log::info;
 use symbolic::common::DebugId;
 use symbolic::debuginfo::FileFormat;
 
+use crate::api::Api;
 use crate::config::Config;
-use crate::constants::DEFAULT_MAX_WAIT;
+use crate::constants::{DEFAULT_MAX_DIF_SIZE, DEFAULT_MAX_WAIT};
 use crate::utils::args::ArgExt;
 use crate::utils::dif::{DifType, ObjectDifFeatures};
-use crate::utils::dif_upload::{DifFormat, DifUpload};
+use crate::utils::dif_upload::{DifFormat, DifUpload, DART_SYMBOLS_EXTENSION};
+use crate::utils::formatting::Table;
+use crate::utils::logging::{quiet_println, set_quiet_mode};
+use crate::utils::releases::detect_release_name_from_git;
 use crate::utils::system::QuietExit;
-use crate::utils::xcode::InfoPlist;
+use crate::utils::upload_metadata::UploadMetadata;
+use crate::utils::upload_report::UploadReport;
+use crate::utils::watch::FileWatcher;
+use crate::utils::xcode::{self, InfoPlist};
 
 static DERIVED_DATA_FOLDER: &str = "Library/Developer/Xcode/DerivedData";
 
@@ -116,18 +129,41 @@ pub fn make_command(command: Command) -> Command {
                     they are found in the PATH searched for symbol files.",
                 ),
         )
+        .arg(
+            Arg::new("require_symbol_maps")
+                .long("require-symbol-maps")
+                .action(ArgAction::SetTrue)
+                .requires("symbol_maps")
+                .help(
+                    "Fail before uploading if a dSYM with hidden symbols has no \
+                    BCSymbolMap in the path given with --symbol-maps, instead of \
+                    uploading it with unresolved symbols.",
+                ),
+        )
         .arg(
             Arg::new("derived_data")
                 .long("derived-data")
                 .action(ArgAction::SetTrue)
                 .help("Search for debug symbols in Xcode's derived data."),
         )
+        .arg(
+            Arg::new("no_auto_discover")
+                .long("no-auto-discover")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Do not search the dSYM folders of the current Xcode build or \
+                    of Xcode archives in addition to the given paths.{n}By default, \
+                    DWARF_DSYM_FOLDER_PATH, framework dSYMs in BUILT_PRODUCTS_DIR and \
+                    the dSYMs folder of .xcarchive paths are searched.",
+                ),
+        )
         .arg(
             Arg::new("no_zips")
                 .long("no-zips")
                 .action(ArgAction::SetTrue)
                 .help("Do not search in ZIP files."),
         )
+        .ignore_file_args()
         .arg(
             Arg::new("info_plist")
                 .long("info-plist")
@@ -150,6 +186,58 @@ pub fn make_command(command: Command) -> Command {
                     just want to verify the setup or skip the upload in tests.",
                 ),
         )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("no_upload")
+                .help(
+                    "Print the debug information files that would be uploaded, \
+                    without uploading them.{n}This runs all steps for the processing \
+                    and prints their debug ids, formats, sizes and checksums instead.",
+                ),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .requires("dry_run")
+                .help("The output format of the dry run manifest."),
+        )
+        .arg(
+            Arg::new("report")
+                .long("report")
+                .value_name("PATH")
+                .conflicts_with_all(["dry_run", "no_upload"])
+                .help(
+                    "Write a JSON report to the given path.{n}The report lists every \
+                    debug information file with its debug id, type, size, checksum and \
+                    whether it was uploaded, skipped or failed. It is also written if \
+                    the upload fails.",
+                ),
+        )
+        .upload_metadata_args()
+        .release_arg()
+        .mut_arg("release", |arg| {
+            arg.help(
+                "The release slug.{n}It is stored in the manifest of source bundles \
+                created with --include-sources and recorded in the upload report.",
+            )
+        })
+        .release_from_git_arg()
+        .arg(
+            Arg::new("skip_known")
+                .long("skip-known")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Skip debug files which the server already has for their debug id, \
+                    before computing their checksums.{n}This saves reading and hashing \
+                    large files which were uploaded before.  Files are only skipped if the \
+                    known file provides all of their features.",
+                ),
+        )
         .arg(
             Arg::new("force_foreground")
                 .hide(true)
@@ -168,6 +256,18 @@ pub fn make_command(command: Command) -> Command {
                     "Include sources from the local file system and upload them as source bundles.",
                 ),
         )
+        .arg(
+            Arg::new("max_source_size")
+                .long("max-source-size")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(u64))
+                .requires("include_sources")
+                .help(
+                    "Skip source files larger than this many bytes when including sources. \
+                    Files that are not UTF-8 are transcoded before they are bundled. \
+                    [default: 1048576, or the `dif.max_item_size` config value]",
+                ),
+        )
         .arg(
             Arg::new("wait")
                 .long("wait")
@@ -192,6 +292,49 @@ pub fn make_command(command: Command) -> Command {
                     significantly slow down the upload process.",
                 ),
         )
+        .arg(
+            Arg::new("no_wait")
+                .long("no-wait")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["wait", "wait_for"])
+                .help(
+                    "Do not wait for the server to process uploaded files. This is the \
+                    default behavior.",
+                ),
+        )
+        .arg(
+            Arg::new("compression")
+                .long("compression")
+                .value_name("ALGORITHM")
+                .value_parser(["gzip", "brotli", "zstd", "none"])
+                .help(
+                    "The compression used to upload files. If the server does not support \
+                    the given compression, gzip is used instead. [defaults to the best \
+                    compression supported by the server]",
+                ),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["dry_run", "no_upload", "report", "require_all"])
+                .help(
+                    "Keep running after the upload, and upload new or modified debug \
+                    information files in the search paths until interrupted with Ctrl-C.{n}\
+                    Files are uploaded once no further changes happened for a second.",
+                ),
+        )
+        .arg(
+            Arg::new("poll_interval")
+                .long("poll-interval")
+                .value_name("MILLIS")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .requires("watch")
+                .help(
+                    "Poll the search paths for changes at this interval instead of using \
+                    filesystem notifications, for example on network drives.",
+                ),
+        )
         // Legacy flag that has no effect, left hidden for backward compatibility
         .arg(
             Arg::new("upload_symbol_maps")
@@ -203,13 +346,62 @@ pub fn make_command(command: Command) -> Command {
             Arg::new("il2cpp_mapping")
                 .long("il2cpp-mapping")
                 .action(ArgAction::SetTrue)
-                .help("Compute il2cpp line mappings and upload them along with sources."),
+                .help(
+                    "Compute il2cpp line mappings and upload them along with sources.{n}\
+                     A LineNumberMappings.json next to a debug file is uploaded instead, \
+                     which also happens without this flag for Unity builds containing a \
+                     global-metadata.dat.",
+                ),
+        )
+        .arg(
+            Arg::new("dart_symbol_map")
+                .long("dart-symbol-map")
+                .value_name("PATH")
+                .help(
+                    "Path to the symbol map of an obfuscated Flutter build, which is \
+                     uploaded along with the Dart symbol files (*.symbols) written by \
+                     `--split-debug-info`.  By default, an obfuscation.map.json found \
+                     in the searched paths is used.",
+                ),
+        )
+        .arg(
+            Arg::new("symbol_servers")
+                .long("symbol-server")
+                .value_name("URL")
+                .action(ArgAction::Append)
+                .help(
+                    "Fetch the PDBs of PE files found in the searched paths from this \
+                     symbol server and upload them along with the other files.  The \
+                     server must use the SymStore layout.  Can be given multiple times, \
+                     servers are queried in order.  PDBs that cannot be fetched are \
+                     reported as warnings.",
+                ),
+        )
+        .arg(
+            Arg::new("print_limits")
+                .long("print-limits")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print the upload limits advertised by the server, such as the \
+                     maximum file and request sizes, and exit without uploading.",
+                ),
         )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let start = Instant::now();
     let config = Config::current();
+    if matches.get_flag("print_limits") {
+        return print_upload_limits(&config.get_org(matches)?);
+    }
+
     let (org, project) = config.get_org_and_project(matches)?;
+    let metadata = UploadMetadata::from_matches(matches)?;
+    let release = if matches.get_flag("release_from_git") {
+        Some(config.check_release(matches, &detect_release_name_from_git()?)?)
+    } else {
+        config.find_release(matches)?
+    };
 
     let ids = matches
         .get_many::<DebugId>("ids")
@@ -222,7 +414,8 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     );
 
     let wait_for_secs = matches.get_one::<u64>("wait_for").copied();
-    let wait = matches.get_flag("wait") || wait_for_secs.is_some();
+    let wait =
+        !matches.get_flag("no_wait") && (matches.get_flag("wait") || wait_for_secs.is_some());
     let max_wait = wait_for_secs.map_or(DEFAULT_MAX_WAIT, Duration::from_secs);
 
     // Build generic upload parameters
@@ -232,8 +425,20 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .max_wait(max_wait)
         .search_paths(matches.get_many::<String>("paths").unwrap_or_default())
         .allow_zips(!matches.get_flag("no_zips"))
+        .use_gitignore(matches.get_flag("use_gitignore"))
         .filter_ids(ids);
 
+    for ignore_file in matches
+        .get_many::<String>("ignore_file")
+        .unwrap_or_default()
+    {
+        upload.ignore_file(ignore_file);
+    }
+
+    if let Some(compression) = matches.get_one::<String>("compression") {
+        upload.compression(compression.parse()?);
+    }
+
     // Restrict symbol types, if specified by the user
     for ty in matches
         .get_many::<String>("types")
@@ -250,6 +455,11 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             "portablepdb" => upload.filter_format(DifFormat::Object(FileFormat::PortablePdb)),
             "jvm" => upload.filter_format(DifFormat::Object(FileFormat::SourceBundle)),
             "wasm" => upload.filter_format(DifFormat::Object(FileFormat::Wasm)),
+            "dart" => {
+                upload.filter_format(DifFormat::Object(FileFormat::Elf));
+                upload.filter_format(DifFormat::DartSymbolMap);
+                upload.filter_extension(DART_SYMBOLS_EXTENSION)
+            }
             "bcsymbolmap" => {
                 upload.filter_format(DifFormat::BcSymbolMap);
                 upload.filter_format(DifFormat::PList)
@@ -271,14 +481,30 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         sources: !matches.get_flag("no_sources"),
     });
 
-    upload.include_sources(matches.get_flag("include_sources"));
+    upload
+        .include_sources(matches.get_flag("include_sources"))
+        .metadata(metadata.clone())
+        .release(release.clone());
+    if let Some(max_source_size) = matches.get_one::<u64>("max_source_size") {
+        upload.max_source_size(*max_source_size);
+    }
     upload.il2cpp_mapping(matches.get_flag("il2cpp_mapping"));
+    if let Some(dart_symbol_map) = matches.get_one::<String>("dart_symbol_map") {
+        upload.dart_symbol_map(dart_symbol_map);
+    }
+    upload.skip_known(matches.get_flag("skip_known"));
+    for symbol_server in matches
+        .get_many::<String>("symbol_servers")
+        .unwrap_or_default()
+    {
+        upload.symbol_server(symbol_server);
+    }
 
     // Configure BCSymbolMap resolution, if possible
     if let Some(symbol_map) = matches.get_one::<String>("symbol_maps") {
         upload
             .symbol_map(symbol_map)
-            .map_err(|_| format_err!("--symbol-maps requires Apple dsymutil to be available."))?;
+            .require_symbol_maps(matches.get_flag("require_symbol_maps"));
     }
 
     // Add a path to XCode's DerivedData, if configured
@@ -291,6 +517,18 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
+    // Add the dSYM folders of the current Xcode build or archive
+    if !matches.get_flag("no_auto_discover") {
+        let paths: Vec<_> = matches
+            .get_many::<String>("paths")
+            .unwrap_or_default()
+            .collect();
+        for folder in xcode::discover_dsym_folders(&paths) {
+            info!("Searching for dSYMs in {}", folder.display());
+            upload.search_path(folder);
+        }
+    }
+
     // Try to resolve the Info.plist either by path or from Xcode
     // TODO: maybe remove this completely?
     let _info_plist = match matches.get_one::<String>("info_plist") {
@@ -299,12 +537,26 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     };
 
     if matches.get_flag("no_upload") {
-        println!("{} skipping upload.", style(">").dim());
+        quiet_println!("{} skipping upload.", style(">").dim());
         return Ok(());
     }
 
-    // Execute the upload
-    let (uploaded, has_processing_errors) = upload.upload()?;
+    if matches.get_flag("dry_run") {
+        return print_dry_run(
+            &mut upload,
+            matches.get_one::<String>("format").map(String::as_str) == Some("json"),
+        );
+    }
+
+    // Execute the upload, and write the report even if it fails
+    let result = upload.upload();
+    if let Some(path) = matches.get_one::<String>("report") {
+        UploadReport::new(upload.report(), start, result.as_ref().err())
+            .with_release(release.as_deref())
+            .with_metadata(&metadata)
+            .write(Path::new(path))?;
+    }
+    let (uploaded, has_processing_errors) = result?;
 
     // Did we miss explicitly requested symbols?
     if matches.get_flag("require_all") {
@@ -329,6 +581,16 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
+    if matches.get_flag("watch") {
+        if has_processing_errors {
+            eprintln!("{}", style("Error: some symbols did not process correctly"));
+        }
+        let poll_interval = matches
+            .get_one::<u64>("poll_interval")
+            .map(|millis| Duration::from_millis(*millis));
+        return watch_for_changes(&mut upload, poll_interval);
+    }
+
     // report a non 0 status code if the server encountered issues.
     if has_processing_errors {
         eprintln!();
@@ -338,3 +600,162 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     Ok(())
 }
+
+/// Uploads new and modified debug information files in the search paths until
+/// interrupted with Ctrl-C.
+///
+/// The first Ctrl-C lets an upload in progress finish, a second one exits
+/// immediately. Failed uploads are reported without exiting, since the next
+/// build may fix them.
+fn watch_for_changes(upload: &mut DifUpload, poll_interval: Option<Duration>) -> Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let stop = stop.clone();
+        ctrlc::set_handler(move || {
+            if stop.swap(true, Ordering::SeqCst) {
+                process::exit(130);
+            }
+        })?;
+    }
+
+    let paths = upload
+        .get_search_paths()
+        .iter()
+        .filter(|path| path.exists())
+        .cloned()
+        .collect::<Vec<_>>();
+    let watcher = FileWatcher::new(&paths, poll_interval)?;
+
+    loop {
+        quiet_println!(
+            "{} Watching for changes (press Ctrl-C to stop)",
+            style(">").dim()
+        );
+        let Some(changed) = watcher.wait_for_changes(&stop)? else {
+            break;
+        };
+
+        quiet_println!(
+            "{} Detected changes in {}",
+            style(">").dim(),
+            changed.iter().map(|path| path.display()).join(", ")
+        );
+        match upload.upload_paths(changed.into_iter().collect()) {
+            Ok((_, true)) => {
+                eprintln!("{}", style("Error: some symbols did not process correctly"))
+            }
+            Ok(_) => (),
+            Err(err) => eprintln!("{} {:?}", style("error:").red(), err),
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the limits of chunked uploads advertised by the server.
+fn print_upload_limits(org: &str) -> Result<()> {
+    let Some(options) = Api::current()
+        .authenticated()?
+        .get_chunk_upload_options(org)?
+    else {
+        println!("The server does not support chunked uploads.");
+        return Ok(());
+    };
+
+    let mut table = Table::new();
+    table.title_row().add("Limit").add("Value");
+    table
+        .add_row()
+        .add("Chunk size")
+        .add(HumanBytes(options.chunk_size));
+    table
+        .add_row()
+        .add("Chunks per request")
+        .add(options.chunks_per_request());
+    table
+        .add_row()
+        .add("Max request size")
+        .add(HumanBytes(options.max_request_size()));
+    table.add_row().add("Max file size").add(
+        options
+            .max_file_size()
+            .map_or_else(|| HumanBytes(DEFAULT_MAX_DIF_SIZE), HumanBytes),
+    );
+    table
+        .add_row()
+        .add("Concurrent requests")
+        .add(options.concurrency());
+    table.add_row().add("Max wait").add(match options.max_wait {
+        0 => "-".to_owned(),
+        secs => format!("{secs}s"),
+    });
+    table
+        .add_row()
+        .add("Compression")
+        .add(match options.compression.iter().join(", ").as_str() {
+            "" => "-".to_owned(),
+            compression => compression.to_owned(),
+        });
+    table.print();
+
+    Ok(())
+}
+
+fn print_dry_run(upload: &mut DifUpload, json: bool) -> Result<()> {
+    if json {
+        set_quiet_mode(true);
+    }
+
+    let files = upload.dry_run()?;
+    let total_size = files.iter().map(|file| file.size).sum::<u64>();
+
+    if json {
+        serde_json::to_writer_pretty(
+            &mut io::stdout(),
+            &serde_json::json!({ "files": files, "total_size": total_size }),
+        )?;
+        println!();
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", style("Dry Run Manifest").dim().bold());
+
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("Debug ID")
+        .add("Format")
+        .add("Arch")
+        .add("Size")
+        .add("Checksum")
+        .add("Path");
+
+    for file in &files {
+        table
+            .add_row()
+            .add(
+                file.debug_id
+                    .map_or_else(|| "-".into(), |id| id.to_string()),
+            )
+            .add(&file.format)
+            .add(file.arch.as_deref().unwrap_or("-"))
+            .add(HumanBytes(file.size))
+            .add(&file.checksum)
+            .add(&file.name);
+    }
+
+    table.print();
+    println!(
+        "{} {} {} ({}) would be uploaded.",
+        style(">").dim(),
+        style(files.len()).yellow(),
+        match files.len() {
+            1 => "file",
+            _ => "files",
+        },
+        HumanBytes(total_size)
+    );
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::io;
+
 use anyhow::Result;
 use chrono::Utc;
 use clap::{Arg, ArgMatches, Command};
@@ -11,12 +13,29 @@ pub fn make_command(command: Command) -> Command {
         .about("List all deployments of a release.")
         // Backward compatibility with `releases deploys <VERSION>` commands.
         .arg(Arg::new("version").long("version").hide(true))
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of deploys."),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
     let version = config.get_release_with_legacy_fallback(matches)?;
+    let authenticated_api = api.authenticated()?;
+    let deploys = authenticated_api.list_deploys(&config.get_org(matches)?, &version)?;
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &deploys)?;
+        println!();
+        return Ok(());
+    }
+
     let mut table = Table::new();
     table
         .title_row()
@@ -24,10 +43,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .add("Name")
         .add("Finished");
 
-    for deploy in api
-        .authenticated()?
-        .list_deploys(&config.get_org(matches)?, &version)?
-    {
+    for deploy in deploys {
         table
             .add_row()
             .add(&deploy.env)
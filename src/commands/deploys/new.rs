@@ -1,10 +1,13 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use chrono::{DateTime, Duration, Utc};
-use clap::{Arg, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 
 use crate::api::{Api, Deploy};
 use crate::config::Config;
-use crate::utils::args::get_timestamp;
+use crate::utils::args::{get_timestamp, ArgExt};
+use crate::utils::logging::quiet_println;
+use crate::utils::releases::detect_release_name_from_git;
+use crate::utils::vcs::find_head_commit_time;
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -41,14 +44,27 @@ pub fn make_command(command: Command) -> Command {
                 .long("started")
                 .value_name("TIMESTAMP")
                 .value_parser(get_timestamp)
-                .help("Optional unix timestamp when the deployment started."),
+                .help(
+                    "Optional timestamp when the deployment started.{n}\
+                     Accepts a unix timestamp, an RFC 3339 date or `now`.",
+                ),
+        )
+        .arg(
+            Arg::new("started_from_commit")
+                .long("started-from-commit")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("started")
+                .help("Use the committer date of the current HEAD commit as the start time."),
         )
         .arg(
             Arg::new("finished")
                 .long("finished")
                 .value_name("TIMESTAMP")
                 .value_parser(get_timestamp)
-                .help("Optional unix timestamp when the deployment finished."),
+                .help(
+                    "Optional timestamp when the deployment finished.{n}\
+                     Accepts a unix timestamp, an RFC 3339 date or `now`.",
+                ),
         )
         .arg(
             Arg::new("time")
@@ -56,17 +72,35 @@ pub fn make_command(command: Command) -> Command {
                 .short('t')
                 .value_name("SECONDS")
                 .value_parser(clap::value_parser!(i64))
+                .conflicts_with_all(["started", "finished", "started_from_commit"])
                 .help(
                     "Optional deployment duration in seconds.{n}\
                             This can be specified alternatively to `--started` and `--finished`.",
                 ),
         )
+        .arg(
+            Arg::new("idempotency_key")
+                .long("idempotency-key")
+                .value_name("KEY")
+                .help(
+                    "Do not create the deploy if the release already has a deploy to the \
+                     environment with the same name and URL, for instance when a deploy step \
+                     is retried.{n}The key is sent along in the `Idempotency-Key` header of \
+                     the request that creates the deploy.",
+                ),
+        )
+        .release_from_git_arg()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
-    let version = config.get_release_with_legacy_fallback(matches)?;
+    let version = if matches.get_flag("release_from_git") {
+        config.check_release(matches, &detect_release_name_from_git()?)?
+    } else {
+        config.get_release_with_legacy_fallback(matches)?
+    };
+    let idempotency_key = matches.get_one::<String>("idempotency_key");
     let mut deploy = Deploy {
         env: matches.get_one::<String>("env").unwrap().into(),
         name: matches.get_one::<String>("name").map(|n| n.into()),
@@ -87,16 +121,52 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
         if let Some(started) = matches.get_one::<DateTime<Utc>>("started") {
             deploy.started = Some(*started);
+        } else if matches.get_flag("started_from_commit") {
+            deploy.started = Some(find_head_commit_time()?.with_timezone(&Utc));
+        }
+    }
+
+    if let (Some(started), Some(finished)) = (deploy.started, deploy.finished) {
+        if finished < started {
+            bail!(
+                "The deploy cannot finish ({}) before it started ({}).",
+                finished.to_rfc3339(),
+                started.to_rfc3339()
+            );
         }
     }
 
     let org = config.get_org(matches)?;
     let authenticated_api = api.authenticated()?;
 
-    let created_deploy = authenticated_api
-        .create_deploy(&org, &version, &deploy)?;
+    if idempotency_key.is_some() {
+        let existing = authenticated_api
+            .list_deploys(&org, &version)?
+            .into_iter()
+            .find(|existing| {
+                existing.env == deploy.env
+                    && existing.name == deploy.name
+                    && existing.url == deploy.url
+            });
+        if let Some(existing) = existing {
+            quiet_println!(
+                "Deploy {} for '{}' already exists (id: {})",
+                existing.name(),
+                existing.env,
+                existing.id.as_deref().unwrap_or("unknown")
+            );
+            return Ok(());
+        }
+    }
+
+    let created_deploy = authenticated_api.create_deploy(
+        &org,
+        &version,
+        &deploy,
+        idempotency_key.map(String::as_str),
+    )?;
 
-    println!(
+    quiet_println!(
         "Created new deploy {} for '{}'",
         created_deploy.name(),
         created_deploy.env
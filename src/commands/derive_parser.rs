@@ -13,6 +13,10 @@ pub(super) struct SentryCLI {
     #[arg(help = "Custom headers that should be attached to all requests{n}in key:value format")]
     pub(super) headers: Vec<(String, String)>,
 
+    #[arg(global = true, long, value_name = "PROFILE")]
+    #[arg(help = "Use the values of the `[profile.<PROFILE>]` section of the config file")]
+    pub(super) profile: Option<String>,
+
     #[arg(global=true, long, value_parser=auth_token_parser)]
     #[arg(help = "Use the given Sentry auth token")]
     pub(super) auth_token: Option<AuthToken>,
@@ -21,13 +25,24 @@ pub(super) struct SentryCLI {
     #[arg(long, help = "Set the log output verbosity")]
     pub(super) log_level: Option<String>,
 
-    #[arg(global=true, action=SetTrue, visible_alias="silent", long)]
-    #[arg(help = "Do not print any output while preserving correct exit code. \
-        This flag is currently implemented only for selected subcommands")]
+    #[arg(global=true, action=SetTrue, visible_alias="silent", long, short='q')]
+    #[arg(
+        help = "Do not print informational output or progress bars while preserving \
+        correct exit code"
+    )]
     pub(super) quiet: bool,
 
-    #[arg(global=true, action=SetTrue, long, hide=true, help="Always return 0 exit code")]
+    #[arg(global=true, action=SetTrue, long)]
+    #[arg(help = "Print errors but exit with 0. Invalid arguments still exit with 2")]
     pub(super) allow_failure: bool,
+
+    #[arg(global=true, action=SetTrue, long)]
+    #[arg(help = "Do not use or update the on-disk cache of server options")]
+    pub(super) no_cache: bool,
+
+    #[arg(global = true, long, value_name = "SECS")]
+    #[arg(help = "The maximum number of seconds a single request may take")]
+    pub(super) timeout: Option<u64>,
 }
 
 #[derive(Subcommand)]
@@ -1,10 +1,29 @@
-use anyhow::Result;
+use std::io;
+
+use anyhow::{bail, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use serde::ser::{Serialize, SerializeMap, Serializer};
 
-use crate::api::Api;
+use crate::api::{Api, ProcessedEvent};
 use crate::config::Config;
 use crate::utils::formatting::Table;
 
+/// The fields of an event which can be selected with `--fields`, along with
+/// the title of their table column.
+const EVENT_FIELDS: &[(&str, &str)] = &[
+    ("event_id", "Event ID"),
+    ("date_created", "Date"),
+    ("title", "Title"),
+    ("release", "Release"),
+    ("dist", "Dist"),
+    ("user", "User"),
+    ("user.id", "User ID"),
+    ("user.username", "Username"),
+    ("user.email", "User Email"),
+    ("user.ip_address", "User IP"),
+    ("tags", "Tags"),
+];
+
 pub fn make_command(command: Command) -> Command {
     command
         .about("List all events in your organization.")
@@ -13,6 +32,7 @@ pub fn make_command(command: Command) -> Command {
                 .long("show-user")
                 .short('U')
                 .action(ArgAction::SetTrue)
+                .conflicts_with("fields")
                 .help("Display the Users column."),
         )
         .arg(
@@ -20,8 +40,29 @@ pub fn make_command(command: Command) -> Command {
                 .long("show-tags")
                 .short('T')
                 .action(ArgAction::SetTrue)
+                .conflicts_with("fields")
                 .help("Display the Tags column."),
         )
+        .arg(
+            Arg::new("fields")
+                .long("fields")
+                .value_name("FIELDS")
+                .value_delimiter(',')
+                .help(
+                    "Comma separated list of event fields to print. Supported fields are \
+                     event_id, date_created, title, release, dist, user, user.id, user.username, \
+                     user.email, user.ip_address, tags and tags.<KEY> for the value of a single \
+                     tag.{n}[default: event_id,date_created,title]",
+                ),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json", "csv"])
+                .default_value("table")
+                .help("The output format of the list of events."),
+        )
         .arg(
             Arg::new("max_rows")
                 .long("max-rows")
@@ -39,64 +80,152 @@ pub fn make_command(command: Command) -> Command {
         )
 }
 
+/// Returns the table column title of a field, or an error if the field is unknown.
+fn field_title(field: &str) -> Result<&str> {
+    if let Some(key) = field.strip_prefix("tags.") {
+        if !key.is_empty() {
+            return Ok(key);
+        }
+    }
+
+    match EVENT_FIELDS.iter().find(|(name, _)| *name == field) {
+        Some((_, title)) => Ok(title),
+        None => bail!(
+            "Unknown event field '{}'. Supported fields are: {}, tags.<KEY>",
+            field,
+            EVENT_FIELDS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Returns the value of a field of the event, if it is set.
+fn field_value(event: &ProcessedEvent, field: &str) -> Option<String> {
+    let user = event.user.as_ref();
+    match field {
+        "event_id" => Some(event.event_id.to_string()),
+        "date_created" => Some(event.date_created.clone()),
+        "title" => Some(event.title.clone()),
+        "release" => event.release.clone(),
+        "dist" => event.dist.clone(),
+        "user" => user.map(|user| user.to_string()),
+        "user.id" => user.and_then(|user| user.id.clone()),
+        "user.username" => user.and_then(|user| user.username.clone()),
+        "user.email" => user.and_then(|user| user.email.clone()),
+        "user.ip_address" => user.and_then(|user| user.ip_address.clone()),
+        "tags" => event.tags.as_ref().map(|tags| {
+            tags.iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }),
+        _ => {
+            let key = field.strip_prefix("tags.")?;
+            event
+                .tags
+                .as_ref()?
+                .iter()
+                .find(|tag| tag.key == key)
+                .map(|tag| tag.value.clone())
+        }
+    }
+}
+
+/// Serializes the selected fields of an event as a JSON object, in the order
+/// in which the fields were requested.
+struct EventFields<'a> {
+    fields: &'a [&'a str],
+    event: &'a ProcessedEvent,
+}
+
+impl Serialize for EventFields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in self.fields {
+            map.serialize_entry(field, &field_value(self.event, field))?;
+        }
+        map.end()
+    }
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let org = config.get_org(matches)?;
     let project = config.get_project(matches)?;
     let pages = *matches.get_one("pages").unwrap();
-    let api = Api::current();
+    let format = matches.get_one::<String>("format").unwrap();
 
+    let fields: Vec<&str> = match matches.get_many::<String>("fields") {
+        Some(fields) => fields.map(String::as_str).collect(),
+        None => {
+            let mut fields = vec!["event_id", "date_created", "title"];
+            if matches.get_flag("show_user") {
+                fields.push("user");
+            }
+            if matches.get_flag("show_tags") {
+                fields.push("tags");
+            }
+            fields
+        }
+    };
+    let titles = fields
+        .iter()
+        .map(|field| field_title(field))
+        .collect::<Result<Vec<_>>>()?;
+
+    let api = Api::current();
     let events = api
         .authenticated()?
         .list_organization_project_events(&org, &project, pages)?;
 
-    let mut table = Table::new();
-    let title_row = table.title_row().add("Event ID").add("Date").add("Title");
-
-    if matches.get_flag("show_user") {
-        title_row.add("User");
-    }
-
-    if matches.get_flag("show_tags") {
-        title_row.add("Tags");
-    }
-
     let max_rows = std::cmp::min(
         events.len(),
         *matches.get_one("max_rows").unwrap_or(&usize::MAX),
     );
+    let events = &events[..max_rows];
 
-    if let Some(events) = events.get(..max_rows) {
-        for event in events {
-            let row = table.add_row();
-            row.add(event.event_id)
-                .add(&event.date_created)
-                .add(&event.title);
+    if format == "json" {
+        let events: Vec<_> = events
+            .iter()
+            .map(|event| EventFields {
+                fields: &fields,
+                event,
+            })
+            .collect();
+        serde_json::to_writer_pretty(&mut io::stdout(), &events)?;
+        println!();
+        return Ok(());
+    }
 
-            if matches.get_flag("show_user") {
-                if let Some(user) = &event.user {
-                    row.add(user);
-                } else {
-                    row.add("-");
-                }
-            }
+    let mut table = Table::new();
+    let title_row = table.title_row();
+    if format == "csv" {
+        for field in &fields {
+            title_row.add(field);
+        }
+    } else {
+        for title in &titles {
+            title_row.add(title);
+        }
+    }
 
-            if matches.get_flag("show_tags") {
-                if let Some(tags) = &event.tags {
-                    row.add(
-                        tags.iter()
-                            .map(|t| t.to_string())
-                            .collect::<Vec<_>>()
-                            .join("\n"),
-                    );
-                } else {
-                    row.add("-");
-                }
-            }
+    for event in events {
+        let row = table.add_row();
+        for field in &fields {
+            match field_value(event, field) {
+                Some(value) => row.add(value),
+                None if format == "csv" => row.add(""),
+                None => row.add("-"),
+            };
         }
     }
 
-    if table.is_empty() {
+    if format == "csv" {
+        table.print_csv()?;
+    } else if table.is_empty() {
         println!("No events found");
     } else {
         table.print();
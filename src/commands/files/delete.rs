@@ -5,6 +5,7 @@ use clap::{Arg, ArgAction, ArgMatches, Command};
 
 use crate::api::Api;
 use crate::config::Config;
+use crate::utils::logging::quiet_println;
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -37,7 +38,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     if matches.get_flag("all") {
         authenticated_api.delete_release_files(&org, project.as_deref(), &release)?;
-        println!("All files deleted.");
+        quiet_println!("All files deleted.");
         return Ok(());
     }
 
@@ -50,7 +51,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             continue;
         }
         if authenticated_api.delete_release_file(&org, project.as_deref(), &release, &file.id)? {
-            println!("D {}", file.name);
+            quiet_println!("D {}", file.name);
         }
     }
     Ok(())
@@ -1,24 +1,28 @@
 use std::collections::BTreeMap;
 use std::ffi::OsStr;
+use std::fmt;
 use std::fs;
 use std::io::Read;
 use std::path::Path;
 use std::time::Duration;
 
-use anyhow::{bail, format_err, Result};
+use anyhow::{bail, format_err, Context, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use glob::Pattern;
 use log::warn;
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
 use symbolic::debuginfo::sourcebundle::SourceFileType;
 
 use crate::api::Api;
 use crate::config::Config;
 use crate::constants::DEFAULT_MAX_WAIT;
-use crate::utils::args::validate_distribution;
+use crate::utils::args::{validate_distribution, ArgExt};
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::{
     initialize_legacy_release_upload, FileUpload, SourceFile, UploadContext,
 };
 use crate::utils::fs::{decompress_gzip_content, is_gzip_compressed, path_as_url};
+use crate::utils::logging::quiet_println;
 use crate::utils::progress::ProgressBarMode;
 
 pub fn make_command(command: Command) -> Command {
@@ -73,9 +77,25 @@ pub fn make_command(command: Command) -> Command {
             Arg::new("file-headers")
                 .long("file-header")
                 .short('H')
-                .value_name("KEY VALUE")
+                .value_name("KEY:VALUE")
                 .action(ArgAction::Append)
-                .help("Store a header with this file."),
+                .help(
+                    "Store a header with this file, e.g. `Content-Type: text/html`. \
+                     For directory uploads, the header is stored with every file. \
+                     Can be given multiple times.",
+                ),
+        )
+        .arg(
+            Arg::new("header_manifest")
+                .long("header-manifest")
+                .value_name("PATH")
+                .help(
+                    "For directory uploads, the path to a JSON file mapping URL glob patterns \
+                     to the headers to store with the matching files, e.g. \
+                     `{\"~/templates/*.html\": {\"Content-Type\": \"text/html\"}}`. \
+                     These take precedence over --file-header, and later patterns over \
+                     earlier ones.",
+                ),
         )
         .arg(
             Arg::new("url_prefix")
@@ -98,16 +118,8 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::Append)
                 .help("Ignores all files and folders matching the given glob"),
         )
-        .arg(
-            Arg::new("ignore_file")
-                .long("ignore-file")
-                .short('I')
-                .value_name("IGNORE_FILE")
-                .help(
-                    "Ignore all files and folders specified in the given \
-                    ignore file, e.g. .gitignore.",
-                ),
-        )
+        .ignore_file_args()
+        .follow_symlinks_args()
         .arg(
             Arg::new("extensions")
                 .long("ext")
@@ -122,6 +134,56 @@ pub fn make_command(command: Command) -> Command {
         )
 }
 
+/// The entries of a `--header-manifest` file, in the order they are listed.
+struct HeaderManifest(Vec<(String, BTreeMap<String, String>)>);
+
+impl<'de> Deserialize<'de> for HeaderManifest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ManifestVisitor;
+
+        impl<'de> Visitor<'de> for ManifestVisitor {
+            type Value = HeaderManifest;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an object mapping URL patterns to headers")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = vec![];
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(HeaderManifest(entries))
+            }
+        }
+
+        deserializer.deserialize_map(ManifestVisitor)
+    }
+}
+
+/// Reads a `--header-manifest` file into a list of patterns and their headers.
+fn read_header_manifest(path: &Path) -> Result<Vec<(Pattern, BTreeMap<String, String>)>> {
+    let contents = fs::read(path)
+        .with_context(|| format!("Failed to read header manifest {}", path.display()))?;
+    let HeaderManifest(entries) = serde_json::from_slice(&contents)
+        .with_context(|| format!("Invalid header manifest {}", path.display()))?;
+
+    entries
+        .into_iter()
+        .map(|(pattern, headers)| {
+            if let Some(key) = headers
+                .keys()
+                .find(|key| key.is_empty() || key.contains(':'))
+            {
+                bail!("Invalid header name `{key}` for `{pattern}` in the header manifest");
+            }
+            let pattern = Pattern::new(&pattern)
+                .map_err(|err| format_err!("Invalid URL pattern `{pattern}`: {err}"))?;
+            Ok((pattern, headers))
+        })
+        .collect()
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let release = config.get_release_with_legacy_fallback(matches)?;
@@ -152,20 +214,27 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         project: project.as_deref(),
         release: Some(&release),
         dist,
-        note: None,
+        metadata: None,
         wait,
         max_wait,
         dedupe: false,
         chunk_upload_options: chunk_upload_options.as_ref(),
+        compression: None,
     };
 
     let path = Path::new(matches.get_one::<String>("path").unwrap());
+    let header_manifest = matches
+        .get_one::<String>("header_manifest")
+        .map(|manifest| read_header_manifest(Path::new(manifest)))
+        .transpose()?
+        .unwrap_or_default();
+
     // Batch files upload
     if path.is_dir() {
-        let ignore_file = matches
-            .get_one::<String>("ignore_file")
-            .map(String::as_str)
-            .unwrap_or_default();
+        let ignore_files: Vec<_> = matches
+            .get_many::<String>("ignore_file")
+            .unwrap_or_default()
+            .collect();
         let ignores: Vec<_> = matches
             .get_many::<String>("ignore")
             .map(|ignores| ignores.map(|i| format!("!{i}")).collect())
@@ -176,10 +245,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             .unwrap_or_default();
 
         let sources = ReleaseFileSearch::new(path.to_path_buf())
-            .ignore_file(ignore_file)
+            .ignore_files(ignore_files.clone())
+            .use_gitignore(matches.get_flag("use_gitignore"))
             .ignores(ignores)
             .extensions(extensions)
             .decompress(matches.get_flag("decompress"))
+            .follow_symlinks(!matches.get_flag("no_follow_symlinks"))
             .collect_files()?;
 
         let url_suffix = matches
@@ -200,6 +271,13 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 let local_path = source.path.strip_prefix(&source.base_path).unwrap();
                 let url = format!("{}/{}{}", url_prefix, path_as_url(local_path), url_suffix);
 
+                let mut headers = headers.clone();
+                for (pattern, manifest_headers) in &header_manifest {
+                    if pattern.matches(&url) {
+                        headers.extend(manifest_headers.clone());
+                    }
+                }
+
                 (
                     url.to_string(),
                     SourceFile {
@@ -207,7 +285,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                         path: source.path.clone(),
                         contents: source.contents.clone(),
                         ty: SourceFileType::Source,
-                        headers: headers.clone(),
+                        headers,
                         messages: vec![],
                         already_uploaded: false,
                     },
@@ -219,6 +297,10 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     }
     // Single file upload
     else {
+        if matches.contains_id("header_manifest") {
+            bail!("--header-manifest is only supported when uploading a directory.");
+        }
+
         initialize_legacy_release_upload(context)?;
 
         let name = match matches.get_one::<String>("name") {
@@ -256,7 +338,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 ProgressBarMode::Request,
             )?
         {
-            println!("A {}  ({} bytes)", artifact.sha1, artifact.size);
+            quiet_println!("A {}  ({} bytes)", artifact.sha1, artifact.size);
         } else {
             bail!("File already present!");
         }
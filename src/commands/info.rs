@@ -2,9 +2,10 @@ use std::io;
 
 use anyhow::Result;
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
 use serde::Serialize;
 
-use crate::api::Api;
+use crate::api::{Api, AuthDetails};
 use crate::config::{Auth, Config};
 use crate::utils::logging::is_quiet_mode;
 use crate::utils::system::QuietExit;
@@ -30,6 +31,33 @@ pub struct Status {
     have_dsn: bool,
 }
 
+/// Common operations and the scopes they require, for the permissions matrix.
+const OPERATIONS: &[(&str, &[&str])] = &[
+    ("Create releases", &["project:releases"]),
+    ("Upload files", &["project:releases", "org:read"]),
+    ("Read organization", &["org:read"]),
+    ("Write projects", &["project:write"]),
+];
+
+#[derive(Serialize)]
+struct Permission {
+    operation: &'static str,
+    required_scopes: &'static [&'static str],
+    missing_scopes: Vec<&'static str>,
+    ok: bool,
+}
+
+#[derive(Serialize)]
+struct TokenInfo<'a> {
+    url: Option<&'a str>,
+    org: Option<String>,
+    project: Option<String>,
+    method: &'a str,
+    user: Option<String>,
+    scopes: Vec<String>,
+    permissions: Vec<Permission>,
+}
+
 pub fn make_command(command: Command) -> Command {
     command
         .about("Print information about the configuration and verify authentication.")
@@ -43,6 +71,15 @@ pub fn make_command(command: Command) -> Command {
                      the user towards configuration.",
                 ),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .conflicts_with("config_status_json")
+                .help("The output format of the configuration and token information."),
+        )
         .arg(
             Arg::new("no_defaults")
                 .long("no-defaults")
@@ -63,6 +100,26 @@ fn describe_auth(auth: Option<&Auth>) -> &str {
     }
 }
 
+/// Checks the scopes of the token against the scopes required by common operations.
+fn get_permissions(auth: &AuthDetails) -> Vec<Permission> {
+    OPERATIONS
+        .iter()
+        .map(|&(operation, required_scopes)| {
+            let missing_scopes: Vec<_> = required_scopes
+                .iter()
+                .copied()
+                .filter(|scope| !auth.has_scope(scope))
+                .collect();
+            Permission {
+                operation,
+                required_scopes,
+                ok: missing_scopes.is_empty(),
+                missing_scopes,
+            }
+        })
+        .collect()
+}
+
 fn get_config_status_json() -> Result<()> {
     let config = Config::current();
     let mut rv = Status::default();
@@ -72,12 +129,12 @@ fn get_config_status_json() -> Result<()> {
     rv.config.project = project;
     rv.config.url = Some(config.get_base_url()?.to_string());
 
-    rv.auth.auth_type = config.get_auth().map(|val| match val {
+    let auth = config.resolve_auth()?;
+    rv.auth.auth_type = auth.map(|val| match val {
         Auth::Token(_) => "token".into(),
         Auth::Key(_) => "api_key".into(),
     });
-    rv.auth.successful =
-        config.get_auth().is_some() && Api::current().authenticated()?.get_auth_info().is_ok();
+    rv.auth.successful = auth.is_some() && Api::current().authenticated()?.get_auth_info().is_ok();
     rv.have_dsn = config.get_dsn().is_ok();
 
     serde_json::to_writer_pretty(&mut io::stdout(), &rv)?;
@@ -112,6 +169,27 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         };
     }
 
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        let info = info_rv?;
+        let no_defaults = matches.get_flag("no_defaults");
+        let rv = TokenInfo {
+            url: config.get_base_url().ok(),
+            org: org.filter(|_| !no_defaults),
+            project: project.filter(|_| !no_defaults),
+            method: describe_auth(config.get_auth()),
+            user: info.user.map(|user| user.email),
+            scopes: info
+                .auth
+                .as_ref()
+                .map(|auth| auth.scopes.clone())
+                .unwrap_or_default(),
+            permissions: info.auth.as_ref().map(get_permissions).unwrap_or_default(),
+        };
+        serde_json::to_writer_pretty(&mut io::stdout(), &rv)?;
+        println!();
+        return Ok(());
+    }
+
     println!("Sentry Server: {}", config.get_base_url().unwrap_or("-"));
 
     if !matches.get_flag("no_defaults") {
@@ -137,6 +215,21 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         for scope in &auth.scopes {
             println!("    - {scope}");
         }
+
+        println!();
+        println!("Permissions:");
+        for permission in get_permissions(auth) {
+            if permission.ok {
+                println!("  {:<20} {}", permission.operation, style("ok").green());
+            } else {
+                println!(
+                    "  {:<20} {} (requires {})",
+                    permission.operation,
+                    style("missing").red(),
+                    permission.missing_scopes.join(", ")
+                );
+            }
+        }
     }
 
     Ok(())
@@ -1,3 +1,5 @@
+use std::io;
+
 use anyhow::Result;
 use clap::{Arg, ArgMatches, Command};
 
@@ -30,6 +32,14 @@ pub fn make_command(command: Command) -> Command {
                 .default_value("")
                 .help("Query to pass at the request. An example is \"is:unresolved\""),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of issues."),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -37,12 +47,31 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let org = config.get_org(matches)?;
     let project = config.get_project(matches)?;
     let pages = *matches.get_one("pages").unwrap();
-    let query = matches.get_one::<String>("query").cloned();
+    let max_rows = *matches.get_one("max_rows").unwrap_or(&usize::MAX);
+    let mut query = matches.get_one::<String>("query").cloned();
+
+    // The status selected on `issues` narrows down the query, using the
+    // search syntax of the issue stream.
+    if let Some(status) = matches.get_one::<String>("status") {
+        let status = if status == "muted" { "ignored" } else { status };
+        query = Some(match query {
+            Some(query) if !query.is_empty() => format!("{query} is:{status}"),
+            _ => format!("is:{status}"),
+        });
+    }
+
     let api = Api::current();
 
-    let issues = api
+    let mut issues = api
         .authenticated()?
-        .list_organization_project_issues(&org, &project, pages, query)?;
+        .list_organization_project_issues(&org, &project, pages, max_rows, query)?;
+    issues.truncate(max_rows);
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &issues)?;
+        println!();
+        return Ok(());
+    }
 
     let mut table = Table::new();
     table
@@ -50,25 +79,22 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .add("Issue ID")
         .add("Short ID")
         .add("Title")
+        .add("Culprit")
+        .add("Events")
         .add("Last seen")
         .add("Status")
         .add("Level");
 
-    let max_rows = std::cmp::min(
-        issues.len(),
-        *matches.get_one("max_rows").unwrap_or(&usize::MAX),
-    );
-
-    if let Some(issues) = issues.get(..max_rows) {
-        for issue in issues {
-            let row = table.add_row();
-            row.add(&issue.id)
-                .add(&issue.short_id)
-                .add(&issue.title)
-                .add(&issue.last_seen)
-                .add(&issue.status)
-                .add(&issue.level);
-        }
+    for issue in &issues {
+        let row = table.add_row();
+        row.add(&issue.id)
+            .add(&issue.short_id)
+            .add(&issue.title)
+            .add(&issue.culprit)
+            .add(&issue.count)
+            .add(&issue.last_seen)
+            .add(&issue.status)
+            .add(&issue.level);
     }
 
     if table.is_empty() {
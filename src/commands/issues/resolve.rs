@@ -4,6 +4,7 @@ use log::info;
 
 use crate::api::{Api, IssueChanges, IssueFilter};
 use crate::config::Config;
+use crate::utils::logging::quiet_println;
 
 pub fn make_command(command: Command) -> Command {
     command.about("Bulk resolve all selected issues.").arg(
@@ -36,12 +37,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .authenticated()?
         .bulk_update_issue(&org, &project, &filter, &changes)?
     {
-        println!("Updated matching issues.");
+        quiet_println!("Updated matching issues.");
         if let Some(status) = changes.new_status.as_ref() {
-            println!("  new status: {status}");
+            quiet_println!("  new status: {status}");
         }
     } else {
-        println!("No changes requested.");
+        quiet_println!("No changes requested.");
     }
     Ok(())
 }
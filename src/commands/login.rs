@@ -1,22 +1,57 @@
 use std::env;
+use std::io::{self, Read};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
 use url::Url;
 
-use crate::api::Api;
+use crate::api::{Api, DeviceTokenStatus};
 use crate::config::{Auth, Config};
 use crate::utils::auth_token::AuthToken;
 use crate::utils::ui::{prompt, prompt_to_continue};
 
 pub fn make_command(command: Command) -> Command {
-    command.about("Authenticate with the Sentry server.").arg(
-        Arg::new("global")
-            .short('g')
-            .long("global")
-            .action(ArgAction::SetTrue)
-            .help("Store authentication token globally rather than locally."),
-    )
+    command
+        .about("Authenticate with the Sentry server.")
+        .arg(
+            Arg::new("global")
+                .short('g')
+                .long("global")
+                .action(ArgAction::SetTrue)
+                .help("Store authentication token globally rather than locally."),
+        )
+        .arg(
+            Arg::new("token_from_stdin")
+                .long("token-from-stdin")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("auth_token")
+                .help("Read the authentication token from stdin instead of prompting."),
+        )
+        .arg(
+            Arg::new("device")
+                .long("device")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["auth_token", "token_from_stdin", "validate_only"])
+                .help(
+                    "Sign in through the OAuth device authorization flow.{n}\
+                     Prints a code to enter in the browser, on any device, and stores \
+                     the issued token once the authorization is approved.",
+                ),
+        )
+        .arg(
+            Arg::new("validate_only")
+                .long("validate-only")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("global")
+                .help(
+                    "Only validate the authentication token without storing it.{n}\
+                     Unless read from stdin, the token is taken from --auth-token, \
+                     SENTRY_AUTH_TOKEN or the config file.",
+                ),
+        )
 }
 
 fn update_config(config: &Config, token: AuthToken) -> Result<()> {
@@ -26,8 +61,156 @@ fn update_config(config: &Config, token: AuthToken) -> Result<()> {
     Ok(())
 }
 
+/// Validates the token non-interactively and stores it unless `--validate-only` is set.
+fn execute_non_interactive(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let token = if matches.get_flag("token_from_stdin") {
+        let mut token = String::new();
+        io::stdin()
+            .read_to_string(&mut token)
+            .context("Failed to read the auth token from stdin")?;
+        let token = token.trim();
+        if token.is_empty() {
+            bail!("No auth token was passed on stdin.");
+        }
+        AuthToken::from(token)
+    } else if let Some(Auth::Token(token)) = config.resolve_auth()? {
+        token.clone()
+    } else {
+        bail!(
+            "No auth token to validate. Pass one with --auth-token, SENTRY_AUTH_TOKEN \
+             or --token-from-stdin."
+        );
+    };
+
+    let base_url = config.get_base_url()?.to_owned();
+    let test_cfg = config.make_copy(|cfg| {
+        cfg.set_auth(Auth::Token(token.clone()))?;
+        Ok(())
+    })?;
+    let api = Api::with_config(test_cfg);
+    let authenticated_api = api.authenticated()?;
+
+    let info = match authenticated_api.check_auth_token() {
+        Ok(Some(info)) => info,
+        Ok(None) => bail!("The auth token was rejected by the Sentry server at {base_url}."),
+        Err(err) => {
+            return Err(err).context(format!(
+                "Could not validate the auth token with the Sentry server at {base_url}."
+            ))
+        }
+    };
+
+    match info.user {
+        Some(user) => println!("Valid token for user {}", user.email),
+        None => println!("Valid org token"),
+    }
+
+    let orgs = match token.payload() {
+        Some(payload) => vec![payload.org.clone()],
+        None => authenticated_api
+            .list_organizations_in_all_regions()?
+            .into_iter()
+            .map(|org| org.slug)
+            .collect(),
+    };
+    if orgs.is_empty() {
+        println!("The token does not have access to any organizations.");
+    } else {
+        println!("Accessible organizations: {}", orgs.join(", "));
+    }
+
+    if matches.get_flag("validate_only") {
+        return Ok(());
+    }
+
+    let config_to_update = if matches.get_flag("global") {
+        Config::global()?
+    } else {
+        Config::from_cli_config()?
+    };
+
+    update_config(&config_to_update, token)?;
+    println!(
+        "Stored token in {}",
+        config_to_update.get_filename().display()
+    );
+
+    Ok(())
+}
+
+/// Signs in through the OAuth device authorization flow and stores the issued token.
+fn execute_device_flow(matches: &ArgMatches, config: &Config) -> Result<()> {
+    let base_url = config.get_base_url()?.to_owned();
+    let api = Api::current();
+
+    let authorization = api.request_device_authorization().context(format!(
+        "Could not start the device authorization with the Sentry server at {base_url}."
+    ))?;
+
+    println!(
+        "To sign in, open {} in your browser and enter the code:",
+        authorization.verification_uri
+    );
+    println!();
+    println!("    {}", style(&authorization.user_code).bold());
+    println!();
+    if let Some(ref uri) = authorization.verification_uri_complete {
+        println!("Alternatively, open {uri} to skip entering the code.");
+    }
+    println!("Waiting for authorization...");
+
+    let deadline = Instant::now() + Duration::from_secs(authorization.expires_in);
+    let mut interval = Duration::from_secs(authorization.interval);
+
+    let token = loop {
+        if Instant::now() + interval > deadline {
+            bail!(
+                "The device code expired before the sign in was approved. \
+                 Run `sentry-cli login --device` to try again."
+            );
+        }
+        thread::sleep(interval);
+
+        match api.poll_device_token(&authorization.device_code)? {
+            DeviceTokenStatus::Pending => {}
+            DeviceTokenStatus::SlowDown => interval += Duration::from_secs(5),
+            DeviceTokenStatus::Denied => {
+                bail!("The sign in was denied in the browser. No token was stored.")
+            }
+            DeviceTokenStatus::Expired => bail!(
+                "The device code expired before the sign in was approved. \
+                 Run `sentry-cli login --device` to try again."
+            ),
+            DeviceTokenStatus::Token(token) => break AuthToken::from(token),
+        }
+    };
+
+    println!("Authorization complete");
+
+    let config_to_update = if matches.get_flag("global") {
+        Config::global()?
+    } else {
+        Config::from_cli_config()?
+    };
+
+    update_config(&config_to_update, token)?;
+    println!(
+        "Stored token in {}",
+        config_to_update.get_filename().display()
+    );
+
+    Ok(())
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
+    if matches.get_flag("token_from_stdin") || matches.get_flag("validate_only") {
+        return execute_non_interactive(matches, &config);
+    }
+    if matches.get_flag("device") {
+        return execute_device_flow(matches, &config);
+    }
+
     let token_url = format!(
         "{}/orgredirect/organizations/:orgslug/settings/auth-tokens/",
         config.get_base_url()?
@@ -1,20 +1,24 @@
 //! This module implements the root command of the CLI tool.
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Error, Result};
 use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
 use clap_complete::{generate, Generator, Shell};
-use log::{debug, info, set_logger, set_max_level, LevelFilter};
+use log::{debug, info, set_logger, LevelFilter};
 use std::borrow::Cow;
-use std::io;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::Duration;
 use std::{env, iter};
 
-use crate::api::Api;
-use crate::config::{Auth, Config};
+use crate::api::{Api, ApiError};
+use crate::config::{header_name, Auth, Config};
 use crate::constants::{ARCH, PLATFORM, VERSION};
 use crate::utils::auth_token::{redact_token_from_string, AuthToken};
 use crate::utils::logging::set_quiet_mode;
-use crate::utils::logging::Logger;
+use crate::utils::logging::{set_log_file, set_max_level, Logger};
+use crate::utils::progress::set_progress_format;
 use crate::utils::system::{init_backtrace, load_dotenv, print_error, QuietExit};
 use crate::utils::update::run_sentrycli_update_nagger;
 use crate::utils::value_parsers::auth_token_parser;
@@ -23,6 +27,7 @@ mod derive_parser;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
+        $mac!(api);
         $mac!(bash_hook);
         $mac!(debug_files);
         $mac!(deploys);
@@ -33,6 +38,7 @@ macro_rules! each_subcommand {
         $mac!(login);
         $mac!(monitors);
         $mac!(organizations);
+        $mac!(powershell_hook);
         $mac!(projects);
         $mac!(react_native);
         $mac!(releases);
@@ -40,6 +46,7 @@ macro_rules! each_subcommand {
         $mac!(send_event);
         $mac!(send_envelope);
         $mac!(send_metric);
+        $mac!(send_queued);
         $mac!(sourcemaps);
         #[cfg(not(feature = "managed"))]
         $mac!(uninstall);
@@ -85,8 +92,29 @@ const UPDATE_NAGGER_CMDS: &[&str] = &[
 /// The long auth token argument (--auth-token).
 const AUTH_TOKEN_ARG: &str = "auth-token";
 
-fn print_completions<G: Generator>(gen: G, cmd: &mut Command) {
-    generate(gen, cmd, cmd.get_name().to_string(), &mut io::stdout());
+fn print_completions<G: Generator>(gen: G, cmd: &mut Command, out: &mut dyn Write) {
+    generate(gen, cmd, cmd.get_name().to_string(), out);
+}
+
+/// Copies the command for completions, leaving out hidden subcommands such as
+/// deprecated aliases and experimental commands.
+fn visible_command(cmd: &Command) -> Command {
+    let mut visible = Command::new(cmd.get_name().to_owned())
+        .visible_aliases(cmd.get_visible_aliases().map(str::to_owned))
+        .args(cmd.get_arguments().cloned())
+        .groups(cmd.get_groups().cloned())
+        .subcommands(
+            cmd.get_subcommands()
+                .filter(|subcommand| !subcommand.is_hide_set())
+                .map(visible_command),
+        );
+    if let Some(about) = cmd.get_about() {
+        visible = visible.about(about.clone());
+    }
+    if let Some(version) = cmd.get_version() {
+        visible = visible.version(version.to_owned());
+    }
+    visible
 }
 
 fn preexecute_hooks() -> Result<bool> {
@@ -110,6 +138,14 @@ fn preexecute_hooks() -> Result<bool> {
 }
 
 fn configure_args(config: &mut Config, matches: &ArgMatches) -> Result<()> {
+    if let Some(profile) = matches
+        .get_one::<String>("profile")
+        .cloned()
+        .or_else(|| env::var("SENTRY_PROFILE").ok().filter(|p| !p.is_empty()))
+    {
+        config.select_profile(&profile)?;
+    }
+
     if let Some(api_key) = matches.get_one::<String>("api_key") {
         config.set_auth(Auth::Key(api_key.to_owned()))?;
     }
@@ -122,10 +158,32 @@ fn configure_args(config: &mut Config, matches: &ArgMatches) -> Result<()> {
         config.set_base_url(url);
     }
 
+    if let Some(&max_retries) = matches.get_one::<u32>("max_retries") {
+        config.set_max_retry_count(max_retries);
+    }
+
+    if let Some(&timeout) = matches.get_one::<u64>("timeout") {
+        config.set_timeout(Duration::from_secs(timeout));
+    }
+
+    if let Some(path) = matches.get_one::<String>("client_cert") {
+        config.set_client_cert(PathBuf::from(path));
+    }
+
+    if let Some(path) = matches.get_one::<String>("client_key") {
+        config.set_client_key(PathBuf::from(path));
+    }
+    config.validate_client_cert()?;
+
+    if matches.get_flag("no_cache") {
+        config.disable_cache();
+    }
+
     if let Some(headers) = matches.get_many::<String>("headers") {
         let headers = headers.map(|h| h.to_owned()).collect();
         config.set_headers(headers);
     }
+    config.validate_headers(matches.get_flag("allow_header_override"))?;
 
     Ok(())
 }
@@ -163,13 +221,37 @@ fn app() -> Command {
                     "Custom headers that should be attached to all requests{n}in key:value format.",
                 ),
         )
+        .arg(
+            Arg::new("allow_header_override")
+                .long("allow-header-override")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Allow custom headers to replace the Authorization and Content-Type \
+                     headers sent by sentry-cli.",
+                ),
+        )
+        .arg(
+            Arg::new("profile")
+                .value_name("PROFILE")
+                .long("profile")
+                .global(true)
+                .help(
+                    "Use the values of the `[profile.<PROFILE>]` section of the config file.{n}\
+                     [default: SENTRY_PROFILE if set]",
+                ),
+        )
         .arg(
             Arg::new("auth_token")
                 .value_name("AUTH_TOKEN")
                 .long(AUTH_TOKEN_ARG)
                 .global(true)
                 .value_parser(auth_token_parser)
-                .help("Use the given Sentry auth token."),
+                .help(
+                    "Use the given Sentry auth token, instead of SENTRY_AUTH_TOKEN or the \
+                     config file. Tokens passed on the command line may be kept in the \
+                     shell history, so prefer SENTRY_AUTH_TOKEN where possible.",
+                ),
         )
         .arg(
             Arg::new("api_key")
@@ -186,31 +268,120 @@ fn app() -> Command {
                 .global(true)
                 .help("Set the log output verbosity."),
         )
+        .arg(
+            Arg::new("log_file")
+                .value_name("PATH")
+                .long("log-file")
+                .global(true)
+                .help(
+                    "Append the full debug log, including API request traces, to the given file \
+                     regardless of the log level.{n}[default: SENTRY_LOG_FILE if set]",
+                ),
+        )
+        .arg(
+            Arg::new("progress")
+                .value_name("FORMAT")
+                .long("progress")
+                .value_parser(["bar", "json"])
+                .global(true)
+                .help(
+                    "Set the progress output format. `json` emits newline-delimited JSON \
+                     progress events of uploads to stderr instead of drawing progress bars.{n}\
+                     [default: SENTRY_PROGRESS_FORMAT if set, otherwise bar]",
+                ),
+        )
         .arg(
             Arg::new("quiet")
                 .long("quiet")
+                .short('q')
                 .visible_alias("silent")
                 .action(ArgAction::SetTrue)
                 .global(true)
-                .help("Do not print any output while preserving correct exit code. This flag is currently implemented only for selected subcommands."),
+                .help(
+                    "Do not print informational output or progress bars while preserving \
+                     correct exit code.  Errors are still printed to stderr, and results such \
+                     as `--format json` output or created identifiers are printed as usual.{n}\
+                     [default: SENTRY_QUIET if set]",
+                ),
         )
         .arg(
-          Arg::new("allow_failure")
-              .long("allow-failure")
-              .action(ArgAction::SetTrue)
-              .global(true)
-              .hide(true)
-              .help("Always return 0 exit code."),
+            Arg::new("allow_failure")
+                .long("allow-failure")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Print errors but exit with 0, for instance so that a Sentry outage does \
+                     not fail a deploy. Invalid arguments still exit with 2.{n}\
+                     [default: SENTRY_ALLOW_FAILURE if set]",
+                ),
+        )
+        .arg(
+            Arg::new("no_cache")
+                .long("no-cache")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Do not use or update the on-disk cache of server options, such as the \
+                     chunk upload configuration, which is otherwise reused for 5 minutes.",
+                ),
+        )
+        .arg(
+            Arg::new("max_retries")
+                .value_name("MAX_RETRIES")
+                .long("max-retries")
+                .value_parser(value_parser!(u32))
+                .help(
+                    "The maximum number of times a failed request is retried.{n}\
+                     [default: 5, or SENTRY_HTTP_MAX_RETRIES if set]",
+                ),
+        )
+        .arg(
+            Arg::new("timeout")
+                .value_name("SECS")
+                .long("timeout")
+                .value_parser(value_parser!(u64))
+                .global(true)
+                .help(
+                    "The maximum number of seconds a single request may take.{n}\
+                     [default: 30 for API requests and 300 for file transfers, \
+                     or http.timeout if set]",
+                ),
+        )
+        .arg(
+            Arg::new("client_cert")
+                .value_name("PATH")
+                .long("client-cert")
+                .global(true)
+                .help(
+                    "Present the given PEM client certificate to servers that require \
+                     mutual TLS.{n}[default: http.client_cert if set]",
+                ),
+        )
+        .arg(
+            Arg::new("client_key")
+                .value_name("PATH")
+                .long("client-key")
+                .global(true)
+                .help(
+                    "The PEM private key of the client certificate, if it is not contained \
+                     in the certificate file.{n}[default: http.client_key if set]",
+                ),
         )
         .subcommand(
             Command::new("completions")
-            .about("Generate completions for the specified shell.")
-            .arg_required_else_help(true)
-            .arg(
-                Arg::new("shell")
-                    .help("The shell to print completions for.")
-                    .value_parser(value_parser!(Shell)),
-            )
+                .about("Generate completions for the specified shell.")
+                .arg_required_else_help(true)
+                .arg(
+                    Arg::new("shell")
+                        .help("The shell to print completions for.")
+                        .value_parser(value_parser!(Shell)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("PATH")
+                        .help("Write the completions to this file instead of stdout."),
+                ),
         )
 }
 
@@ -258,9 +429,24 @@ pub fn execute() -> Result<()> {
     if let Some(log_level) = log_level {
         set_max_level(log_level);
     }
+    if let Some(log_file) = matches
+        .get_one::<String>("log_file")
+        .cloned()
+        .or_else(|| env::var("SENTRY_LOG_FILE").ok())
+    {
+        set_log_file(Path::new(&log_file))
+            .with_context(|| format!("Failed to open log file {log_file}"))?;
+    }
+    if let Some(progress) = matches
+        .get_one::<String>("progress")
+        .cloned()
+        .or_else(|| env::var("SENTRY_PROGRESS_FORMAT").ok())
+    {
+        set_progress_format(progress.parse()?);
+    }
     let mut config = Config::from_cli_config()?;
     configure_args(&mut config, &matches)?;
-    set_quiet_mode(matches.get_flag("quiet"));
+    set_quiet_mode(config.get_quiet(&matches));
 
     if let Some(log_level) = log_level {
         config.set_log_level(log_level);
@@ -274,6 +460,9 @@ pub fn execute() -> Result<()> {
             Config::current().get_filename().display()
         );
     }
+    if let Some(profile) = Config::current().get_profile() {
+        info!("Using config profile {profile}");
+    }
 
     debug!(
         "sentry-cli version: {}, platform: \"{}\", architecture: \"{}\"",
@@ -283,19 +472,25 @@ pub fn execute() -> Result<()> {
     info!(
         "sentry-cli was invoked with the following command line: {}",
         env::args()
-            // Check whether the previous argument is "--auth-token"
-            .zip(
-                iter::once(false)
-                    .chain(env::args().map(|arg| arg == format!("--{AUTH_TOKEN_ARG}")))
-            )
-            .map(|(a, is_auth_token_arg)| {
+            // Pair each argument with the previous one, to find values of "--auth-token"
+            // and "--header"
+            .zip(iter::once(None).chain(env::args().map(Some)))
+            .map(|(a, prev)| {
                 let redact_replacement = "[REDACTED]";
+                let redact_header = |header: &str| match header_name(header) {
+                    Some(name) => format!("{name}: {redact_replacement}"),
+                    None => redact_replacement.to_owned(),
+                };
 
-                // Redact anything that comes after --auth-token
-                let redacted = if is_auth_token_arg {
+                // Redact anything that comes after --auth-token, and header values
+                let redacted = if prev.as_deref() == Some(&format!("--{AUTH_TOKEN_ARG}")) {
                     Cow::Borrowed(redact_replacement)
                 } else if a.starts_with(&format!("--{AUTH_TOKEN_ARG}=")) {
                     Cow::Owned(format!("--{AUTH_TOKEN_ARG}={redact_replacement}"))
+                } else if prev.as_deref() == Some("--header") {
+                    Cow::Owned(redact_header(&a))
+                } else if let Some(header) = a.strip_prefix("--header=") {
+                    Cow::Owned(format!("--header={}", redact_header(header)))
                 } else {
                     redact_token_from_string(&a, redact_replacement)
                 };
@@ -307,11 +502,17 @@ pub fn execute() -> Result<()> {
     );
 
     if let Some(argmatches) = matches.subcommand_matches("completions") {
-        let mut cmd = app();
-        cmd = add_commands(cmd);
+        let mut cmd = visible_command(&add_commands(app()));
         if let Some(generator) = argmatches.get_one::<Shell>("shell") {
             eprintln!("Generating completion file for {generator}...");
-            print_completions(*generator, &mut cmd);
+            match argmatches.get_one::<String>("output") {
+                Some(path) => {
+                    let mut file = File::create(path)
+                        .with_context(|| format!("Failed to create completion file {path}"))?;
+                    print_completions(*generator, &mut cmd, &mut file);
+                }
+                None => print_completions(*generator, &mut cmd, &mut io::stdout()),
+            }
             return Ok(());
         }
     }
@@ -348,6 +549,23 @@ fn setup() {
     }
 }
 
+/// Returns the exit code of a failed command.
+///
+/// Usage errors exit with 2. Failed API requests exit with 3 if they were not
+/// authenticated or permitted, and with 1 otherwise, like all other errors.
+fn exit_code(err: &Error) -> i32 {
+    if let Some(&QuietExit(code)) = err.downcast_ref() {
+        return code;
+    }
+    if let Some(clap_err) = err.downcast_ref::<clap::Error>() {
+        return clap_err.exit_code();
+    }
+
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<ApiError>())
+        .map_or(1, |api_err| api_err.class().exit_code())
+}
+
 /// Executes the command line application and exits the process.
 pub fn main() -> ! {
     setup();
@@ -355,12 +573,10 @@ pub fn main() -> ! {
     let exit_code = match execute() {
         Ok(()) => 0,
         Err(err) => {
-            let code = if let Some(&QuietExit(code)) = err.downcast_ref() {
-                code
-            } else {
+            if err.downcast_ref::<QuietExit>().is_none() {
                 print_error(&err);
-                1
-            };
+            }
+            let code = exit_code(&err);
 
             // if the user hit an error, it might be time to run the update
             // nagger because maybe they tried to do something only newer
@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use uuid::Uuid;
+
+use sentry::protocol::{MonitorCheckIn, MonitorCheckInStatus};
+
+use crate::api::envelopes_api::EnvelopesApi;
+use crate::utils::value_parsers::auth_token_parser;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Send a single check-in for a monitor.")
+        .long_about(
+            "Send a single check-in for a monitor.{n}{n}\
+             This is useful for jobs which can't be wrapped with `monitors run`. \
+             Send an `in_progress` check-in when the job starts and pass the printed \
+             check-in ID with `--check-in-id` when sending the final `ok` or `error` \
+             check-in.",
+        )
+        .arg(
+            Arg::new("monitor_slug")
+                .value_name("monitor-slug")
+                .help("The monitor slug.")
+                .required(true),
+        )
+        .arg(
+            Arg::new("status")
+                .long("status")
+                .value_name("STATUS")
+                .value_parser(["in_progress", "ok", "error"])
+                .required(true)
+                .help("The status of the check-in."),
+        )
+        .arg(
+            Arg::new("check_in_id")
+                .long("check-in-id")
+                .visible_alias("checkin-id")
+                .value_name("ID")
+                .value_parser(clap::value_parser!(Uuid))
+                .help(
+                    "The ID of the check-in to update, as printed when sending the \
+                     in-progress check-in. Defaults to a new check-in.",
+                ),
+        )
+        .arg(
+            Arg::new("duration")
+                .long("duration")
+                .value_name("SECS")
+                .value_parser(clap::value_parser!(f64))
+                .help("The duration of the job in seconds."),
+        )
+        .arg(
+            Arg::new("environment")
+                .short('e')
+                .long("environment")
+                .default_value("production")
+                .help("Specify the environment of the monitor."),
+        )
+        // Hide auth token from --help output
+        .arg(
+            Arg::new("auth_token")
+                .long("auth-token")
+                .value_parser(auth_token_parser)
+                .hide(true),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let monitor_slug = matches.get_one::<String>("monitor_slug").unwrap();
+    let environment = matches.get_one::<String>("environment").unwrap();
+    let check_in_id = matches
+        .get_one::<Uuid>("check_in_id")
+        .copied()
+        .unwrap_or_else(Uuid::new_v4);
+    let status = match matches.get_one::<String>("status").unwrap().as_str() {
+        "in_progress" => MonitorCheckInStatus::InProgress,
+        "ok" => MonitorCheckInStatus::Ok,
+        _ => MonitorCheckInStatus::Error,
+    };
+
+    let check_in = MonitorCheckIn {
+        check_in_id,
+        monitor_slug: monitor_slug.to_string(),
+        status,
+        duration: matches.get_one::<f64>("duration").copied(),
+        environment: Some(environment.to_string()),
+        monitor_config: None,
+    };
+
+    EnvelopesApi::try_new()?
+        .send_envelope(check_in)
+        .with_context(|| {
+            format!(
+                "Failed to send check-in {} for {monitor_slug}",
+                check_in_id.as_simple()
+            )
+        })?;
+
+    println!("{}", check_in_id.as_simple());
+
+    Ok(())
+}
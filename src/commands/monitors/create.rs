@@ -0,0 +1,156 @@
+use std::io;
+
+use anyhow::{bail, Result};
+use chrono_tz::Tz;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, MonitorScheduleValue, MonitorSettings, NewMonitor};
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::logging::quiet_println;
+
+const INTERVAL_UNITS: &[&str] = &["minute", "hour", "day", "week", "month", "year"];
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Create a monitor or update the configuration of an existing one.")
+        .org_arg()
+        .project_arg(false)
+        .arg(
+            Arg::new("slug")
+                .long("slug")
+                .value_name("SLUG")
+                .required(true)
+                .help("The slug of the monitor to create or update."),
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("NAME")
+                .help("The human readable name of the monitor.{n}Defaults to the slug."),
+        )
+        .arg(
+            Arg::new("schedule")
+                .short('s')
+                .long("schedule")
+                .value_name("SCHEDULE")
+                .required(true)
+                .help(
+                    "The schedule of the monitor. This is a crontab expression \
+                     (e.g. \"0 * * * *\") or, with `--schedule-type interval`, an interval \
+                     such as \"10 minutes\".",
+                ),
+        )
+        .arg(
+            Arg::new("schedule_type")
+                .long("schedule-type")
+                .value_name("TYPE")
+                .value_parser(["crontab", "interval"])
+                .default_value("crontab")
+                .help("The type of the schedule given with --schedule."),
+        )
+        .arg(
+            Arg::new("checkin_margin")
+                .long("check-in-margin")
+                .visible_alias("checkin-margin")
+                .value_name("MINUTES")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help(
+                    "The allowed margin of minutes after the expected check-in time that the \
+                     monitor will not be considered missed for.",
+                ),
+        )
+        .arg(
+            Arg::new("max_runtime")
+                .long("max-runtime")
+                .value_name("MINUTES")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help(
+                    "The allowed duration in minutes that the monitor may be in progress for \
+                     before being considered failed due to timeout.",
+                ),
+        )
+        .arg(
+            Arg::new("timezone")
+                .long("timezone")
+                .value_name("TIMEZONE")
+                .value_parser(|value: &str| value.parse::<Tz>().map(|_| value.to_owned()))
+                .help(
+                    "A tz database string (e.g. \"Europe/Vienna\") representing the monitor's \
+                     execution schedule's timezone.",
+                ),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the created monitor."),
+        )
+}
+
+/// Parses an interval schedule of the form `<value> <unit>`, e.g. `10 minutes`.
+pub fn parse_interval(schedule: &str) -> Result<MonitorScheduleValue> {
+    let mut parts = schedule.split_whitespace();
+    if let (Some(value), Some(unit), None) = (parts.next(), parts.next(), parts.next()) {
+        let unit = unit.strip_suffix('s').unwrap_or(unit);
+        if let (Ok(value), true) = (value.parse::<u64>(), INTERVAL_UNITS.contains(&unit)) {
+            if value > 0 {
+                return Ok(MonitorScheduleValue::Interval(value, unit.to_owned()));
+            }
+        }
+    }
+    bail!(
+        "Invalid interval schedule '{}'. Expected '<value> <unit>' where unit is one of: {}",
+        schedule,
+        INTERVAL_UNITS.join(", ")
+    );
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let api = Api::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+
+    let slug = matches.get_one::<String>("slug").unwrap();
+    let name = matches.get_one::<String>("name").unwrap_or(slug);
+    let schedule_type = matches.get_one::<String>("schedule_type").unwrap();
+    let schedule = matches.get_one::<String>("schedule").unwrap();
+
+    let schedule = if schedule_type == "interval" {
+        parse_interval(schedule)?
+    } else {
+        MonitorScheduleValue::Crontab(schedule.to_owned())
+    };
+
+    let monitor = api.authenticated()?.upsert_organization_monitor(
+        &org,
+        &NewMonitor {
+            project: &project,
+            name,
+            slug,
+            ty: "cron_job",
+            config: MonitorSettings {
+                schedule_type: schedule_type.to_owned(),
+                schedule,
+                checkin_margin: matches.get_one::<u64>("checkin_margin").copied(),
+                max_runtime: matches.get_one::<u64>("max_runtime").copied(),
+                timezone: matches.get_one::<String>("timezone").cloned(),
+            },
+        },
+    )?;
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &monitor)?;
+        println!();
+        return Ok(());
+    }
+
+    match monitor.config {
+        Some(ref config) => quiet_println!("Saved monitor {} ({})", monitor.slug, config.schedule),
+        None => quiet_println!("Saved monitor {}", monitor.slug),
+    }
+
+    Ok(())
+}
@@ -1,15 +1,26 @@
+use std::io;
+
 use anyhow::Result;
-use clap::{ArgMatches, Command};
+use chrono::Utc;
+use clap::{Arg, ArgMatches, Command};
 
 use crate::api::Api;
 use crate::config::Config;
 use crate::utils::args::ArgExt;
-use crate::utils::formatting::Table;
+use crate::utils::formatting::{HumanDuration, Table};
 
 pub fn make_command(command: Command) -> Command {
     command
         .about("List all monitors for an organization.")
         .org_arg()
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of monitors."),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -19,21 +30,41 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let mut monitors = api.authenticated()?.list_organization_monitors(&org)?;
     monitors.sort_by_key(|p| (p.name.clone()));
 
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &monitors)?;
+        println!();
+        return Ok(());
+    }
+
     let mut table = Table::new();
     table
         .title_row()
         .add("ID")
         .add("Slug")
         .add("Name")
-        .add("Status");
+        .add("Status")
+        .add("Schedule")
+        .add("Last Check-In");
 
     for monitor in &monitors {
-        table
-            .add_row()
-            .add(&monitor.id)
+        let row = table.add_row();
+        row.add(&monitor.id)
             .add(&monitor.slug)
             .add(&monitor.name)
             .add(&monitor.status);
+        if let Some(ref config) = monitor.config {
+            row.add(&config.schedule);
+        } else {
+            row.add("-");
+        }
+        if let Some(date) = monitor.last_check_in() {
+            row.add(format!(
+                "{} ago",
+                HumanDuration(Utc::now().signed_duration_since(date))
+            ));
+        } else {
+            row.add("-");
+        }
     }
 
     table.print();
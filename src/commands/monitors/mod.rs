@@ -1,11 +1,15 @@
 use anyhow::Result;
 use clap::{ArgMatches, Command};
 
+pub mod check_in;
+pub mod create;
 pub mod list;
 pub mod run;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
+        $mac!(check_in);
+        $mac!(create);
         $mac!(list);
         $mac!(run);
     };
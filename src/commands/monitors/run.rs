@@ -1,18 +1,34 @@
+use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
-use std::process;
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::{self, ExitStatus, Stdio};
+use std::thread;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use anyhow::Result;
-use clap::{Arg, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use console::style;
+use parking_lot::Mutex;
 
-use sentry::protocol::{MonitorCheckIn, MonitorCheckInStatus, MonitorConfig, MonitorSchedule};
+use sentry::protocol::{
+    Breadcrumb, Context, Event, Level, MonitorCheckIn, MonitorCheckInStatus, MonitorConfig,
+    MonitorIntervalUnit, MonitorSchedule, Value,
+};
 
+use super::create::parse_interval;
 use crate::api::envelopes_api::EnvelopesApi;
+use crate::api::MonitorScheduleValue;
+use crate::utils::env_file::{parse_env_file, parse_env_var};
+use crate::utils::event::get_sdk_info;
 use crate::utils::system::QuietExit;
 use crate::utils::value_parsers::auth_token_parser;
 
+/// The number of output lines attached to the error event by default.
+const DEFAULT_ATTACH_LOG_LINES: usize = 100;
+
 pub fn make_command(command: Command) -> Command {
     command
         .about("Wraps a command")
@@ -96,6 +112,70 @@ pub fn make_command(command: Command) -> Command {
                      issue. Requires --schedule.",
                 ),
         )
+        .arg(
+            Arg::new("attach_log")
+                .long("attach-log")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Capture the output of the command and, if it fails, send the last lines \
+                     of its combined stdout and stderr to Sentry as an error event linked to \
+                     the monitor.",
+                ),
+        )
+        .arg(
+            Arg::new("attach_log_lines")
+                .long("attach-log-lines")
+                .value_name("LINES")
+                .value_parser(clap::builder::RangedU64ValueParser::<usize>::new().range(1..))
+                .help(format!(
+                    "The number of output lines to send with --attach-log. Implies \
+                     --attach-log. [default: {DEFAULT_ATTACH_LOG_LINES}]"
+                )),
+        )
+        .arg(
+            Arg::new("env_files")
+                .long("env-file")
+                .value_name("PATH")
+                .action(ArgAction::Append)
+                .help(
+                    "Set the environment variables in this dotenv-style file for the command. \
+                     They are not used by sentry-cli itself. Can be given multiple times.",
+                ),
+        )
+        .arg(
+            Arg::new("env_vars")
+                .long("env")
+                .value_name("KEY=VALUE")
+                .value_parser(parse_env_var)
+                .action(ArgAction::Append)
+                .help(
+                    "Set an environment variable for the command. Takes precedence over \
+                     --env-file. Can be given multiple times.",
+                ),
+        )
+        .arg(
+            Arg::new("schedule_type")
+                .long("schedule-type")
+                .value_name("TYPE")
+                .value_parser(["crontab", "interval"])
+                .default_value("crontab")
+                .requires("schedule")
+                .help(
+                    "The type of the schedule given with --schedule. Interval schedules are \
+                     given as \"<value> <unit>\", such as \"10 minutes\".",
+                ),
+        )
+        .arg(
+            Arg::new("create_if_missing")
+                .long("create-if-missing")
+                .action(ArgAction::SetTrue)
+                .requires("schedule")
+                .help(
+                    "Create the monitor on its first run if it does not exist yet. The monitor \
+                     configuration is sent along with the check-in, which also updates the \
+                     configuration of an existing monitor. Requires --schedule.",
+                ),
+        )
         // Hide auth token from --help output
         .arg(
             Arg::new("auth_token")
@@ -105,14 +185,141 @@ pub fn make_command(command: Command) -> Command {
         )
 }
 
-fn run_program(args: Vec<&String>, monitor_slug: &str) -> (bool, Option<i32>, Duration) {
+/// The last lines printed by the wrapped program to stdout or stderr.
+struct OutputTail {
+    lines: VecDeque<(DateTime<Utc>, String)>,
+    capacity: usize,
+}
+
+impl OutputTail {
+    fn new(capacity: usize) -> Self {
+        OutputTail {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, line: &[u8]) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        let line = String::from_utf8_lossy(line);
+        self.lines
+            .push_back((Utc::now(), line.trim_end_matches(['\r', '\n']).to_string()));
+    }
+}
+
+/// Copies everything from `reader` to `writer` as soon as it is read, while
+/// recording complete lines in `tail`.
+fn forward_output(
+    mut reader: impl Read,
+    mut writer: impl Write,
+    tail: &Mutex<OutputTail>,
+) -> io::Result<()> {
+    let mut buf = [0; 8192];
+    let mut line = vec![];
+    loop {
+        let read = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        writer.write_all(&buf[..read])?;
+        writer.flush()?;
+
+        for chunk in buf[..read].split_inclusive(|b| *b == b'\n') {
+            line.extend_from_slice(chunk);
+            if line.ends_with(b"\n") {
+                tail.lock().push(&line);
+                line.clear();
+            }
+        }
+    }
+
+    if !line.is_empty() {
+        tail.lock().push(&line);
+    }
+    Ok(())
+}
+
+/// Runs the program, passing its output through while keeping the last
+/// `capacity` lines of it.
+fn run_with_output_tail(
+    mut p: process::Command,
+    capacity: usize,
+) -> io::Result<(ExitStatus, OutputTail)> {
+    let mut child = p.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let tail = Mutex::new(OutputTail::new(capacity));
+
+    thread::scope(|scope| {
+        let tail = &tail;
+        let stdout = scope.spawn(move || forward_output(stdout, io::stdout(), tail));
+        let stderr = scope.spawn(move || forward_output(stderr, io::stderr(), tail));
+        for handle in [stdout, stderr] {
+            if let Err(err) = handle.join().expect("output thread panicked") {
+                log::warn!("Failed to forward the output of the program: {err}");
+            }
+        }
+    });
+
+    Ok((child.wait()?, tail.into_inner()))
+}
+
+/// Describes how the program exited, e.g. `exited with status 1`.
+fn describe_exit_status(status: &ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("exited with status {code}");
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("was terminated by signal {signal}");
+        }
+    }
+
+    "exited abnormally".to_string()
+}
+
+struct RunResult {
+    success: bool,
+    code: Option<i32>,
+    elapsed: Duration,
+    /// How the program failed and its last output lines, when requested
+    /// with `--attach-log`.
+    failure_log: Option<(String, OutputTail)>,
+}
+
+fn run_program(
+    args: &[&String],
+    monitor_slug: &str,
+    env_vars: &[(String, String)],
+    attach_log_lines: Option<usize>,
+) -> RunResult {
     let started = Instant::now();
     let mut p = process::Command::new(args[0]);
     p.args(&args[1..]);
+    p.envs(env_vars.iter().map(|(key, value)| (key, value)));
     p.env("SENTRY_MONITOR_SLUG", monitor_slug);
 
-    let (success, code) = match p.status() {
-        Ok(status) => (status.success(), status.code()),
+    let result = match attach_log_lines {
+        Some(capacity) => {
+            run_with_output_tail(p, capacity).map(|(status, tail)| (status, Some(tail)))
+        }
+        None => p.status().map(|status| (status, None)),
+    };
+
+    let (success, code, failure_log) = match result {
+        Ok((status, tail)) => (
+            status.success(),
+            status.code(),
+            tail.filter(|_| !status.success())
+                .map(|tail| (describe_exit_status(&status), tail)),
+        ),
         Err(err) => {
             eprintln!(
                 "{} could not invoke program '{}': {}",
@@ -120,12 +327,80 @@ fn run_program(args: Vec<&String>, monitor_slug: &str) -> (bool, Option<i32>, Du
                 args[0],
                 err
             );
-            (false, None)
+            (false, None, None)
         }
     };
 
-    let elapsed = started.elapsed();
-    (success, code, elapsed)
+    RunResult {
+        success,
+        code,
+        elapsed: started.elapsed(),
+        failure_log,
+    }
+}
+
+/// Creates an error event for a failed run of the program, linked to the check-in.
+fn failure_event(
+    args: &[&String],
+    monitor_slug: &str,
+    environment: &str,
+    check_in_id: Uuid,
+    exit: &str,
+    tail: OutputTail,
+) -> Event<'static> {
+    let command = args
+        .iter()
+        .map(|arg| arg.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut event = Event {
+        level: Level::Error,
+        message: Some(format!("Command `{command}` {exit}")),
+        environment: Some(environment.to_string().into()),
+        sdk: Some(get_sdk_info()),
+        ..Event::default()
+    };
+
+    event
+        .tags
+        .insert("monitor.slug".into(), monitor_slug.to_string());
+    event.contexts.insert(
+        "monitor".into(),
+        Context::Other(
+            [
+                ("slug".to_string(), Value::from(monitor_slug)),
+                (
+                    "check_in_id".to_string(),
+                    Value::from(check_in_id.simple().to_string()),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+    );
+    event.extra.insert(
+        "output".into(),
+        Value::from(
+            tail.lines
+                .iter()
+                .map(|(_, line)| line.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+    );
+    event.breadcrumbs.values = tail
+        .lines
+        .into_iter()
+        .map(|(timestamp, line)| Breadcrumb {
+            timestamp: timestamp.into(),
+            message: Some(line),
+            category: Some("output".into()),
+            ..Default::default()
+        })
+        .collect();
+
+    event
 }
 
 fn execute_checkin(
@@ -133,6 +408,8 @@ fn execute_checkin(
     monitor_slug: &str,
     environment: &str,
     monitor_config: Option<MonitorConfig>,
+    env_vars: &[(String, String)],
+    attach_log_lines: Option<usize>,
 ) -> Result<(bool, Option<i32>)> {
     let check_in_id = Uuid::new_v4();
 
@@ -152,15 +429,15 @@ fn execute_checkin(
         log::info!("Continuing to run program...");
     }
 
-    let (success, code, elapsed) = run_program(args, monitor_slug);
+    let result = run_program(&args, monitor_slug, env_vars, attach_log_lines);
 
-    let status = if success {
+    let status = if result.success {
         MonitorCheckInStatus::Ok
     } else {
         MonitorCheckInStatus::Error
     };
 
-    let duration = Some(elapsed.as_secs_f64());
+    let duration = Some(result.elapsed.as_secs_f64());
 
     let close_checkin = MonitorCheckIn {
         check_in_id,
@@ -176,14 +453,49 @@ fn execute_checkin(
         log::info!("Continuing to exit with program's exit code...");
     }
 
-    Ok((success, code))
+    if let Some((exit, tail)) = result.failure_log {
+        let event = failure_event(&args, monitor_slug, environment, check_in_id, &exit, tail);
+        if let Err(e) = envelopes_api.send_envelope(event) {
+            log::error!("Failed to send error event with the program's output: {e}");
+        }
+    }
+
+    Ok((result.success, result.code))
+}
+
+/// Builds the schedule of the monitor config from an interval given with
+/// `--schedule-type interval`.
+fn interval_schedule(schedule: &str) -> Result<MonitorSchedule> {
+    let MonitorScheduleValue::Interval(value, unit) = parse_interval(schedule)? else {
+        unreachable!("intervals are parsed into interval schedules");
+    };
+    let unit = match unit.as_str() {
+        "minute" => MonitorIntervalUnit::Minute,
+        "hour" => MonitorIntervalUnit::Hour,
+        "day" => MonitorIntervalUnit::Day,
+        "week" => MonitorIntervalUnit::Week,
+        "month" => MonitorIntervalUnit::Month,
+        "year" => MonitorIntervalUnit::Year,
+        _ => unreachable!("intervals are parsed with a known unit"),
+    };
+    Ok(MonitorSchedule::Interval { value, unit })
 }
 
 fn parse_monitor_config_args(matches: &ArgMatches) -> Result<Option<MonitorConfig>> {
     let Some(schedule) = matches.get_one::<String>("schedule") else {
         return Ok(None);
     };
-    let schedule = MonitorSchedule::from_crontab(schedule)?;
+    if !matches.get_flag("create_if_missing") {
+        log::warn!("Not sending the monitor configuration without --create-if-missing.");
+        return Ok(None);
+    }
+    let schedule = match matches
+        .get_one::<String>("schedule_type")
+        .map(String::as_str)
+    {
+        Some("interval") => interval_schedule(schedule)?,
+        _ => MonitorSchedule::from_crontab(schedule)?,
+    };
     Ok(Some(MonitorConfig {
         schedule,
         checkin_margin: matches.get_one("checkin_margin").copied(),
@@ -194,13 +506,43 @@ fn parse_monitor_config_args(matches: &ArgMatches) -> Result<Option<MonitorConfi
     }))
 }
 
+/// Collects the variables to set for the program, with `--env` taking
+/// precedence over `--env-file`, and later files over earlier ones.
+fn parse_env_args(matches: &ArgMatches) -> Result<Vec<(String, String)>> {
+    let mut env_vars = vec![];
+    for path in matches.get_many::<String>("env_files").unwrap_or_default() {
+        env_vars.extend(parse_env_file(Path::new(path))?);
+    }
+    env_vars.extend(
+        matches
+            .get_many::<(String, String)>("env_vars")
+            .unwrap_or_default()
+            .cloned(),
+    );
+    Ok(env_vars)
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let args: Vec<_> = matches.get_many::<String>("args").unwrap().collect();
     let monitor_slug = matches.get_one::<String>("monitor_slug").unwrap();
     let environment = matches.get_one::<String>("environment").unwrap();
     let monitor_config = parse_monitor_config_args(matches)?;
+    let env_vars = parse_env_args(matches)?;
+    let attach_log_lines = matches
+        .get_one::<usize>("attach_log_lines")
+        .copied()
+        .or(matches
+            .get_flag("attach_log")
+            .then_some(DEFAULT_ATTACH_LOG_LINES));
 
-    let (success, code) = execute_checkin(args, monitor_slug, environment, monitor_config)?;
+    let (success, code) = execute_checkin(
+        args,
+        monitor_slug,
+        environment,
+        monitor_config,
+        &env_vars,
+        attach_log_lines,
+    )?;
 
     if !success {
         return Err(QuietExit(code.unwrap_or(1)).into());
@@ -1,36 +1,36 @@
+use std::io;
+
 use anyhow::Result;
-use clap::{ArgMatches, Command};
-use log::debug;
+use clap::{Arg, ArgMatches, Command};
 
-use crate::api::{Api, Organization};
+use crate::api::Api;
 use crate::utils::formatting::Table;
 
 pub fn make_command(command: Command) -> Command {
-    command.about("List all organizations available to the authenticated token.")
+    command
+        .about("List all organizations available to the authenticated token.")
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of organizations."),
+        )
 }
 
-pub fn execute(_matches: &ArgMatches) -> Result<()> {
+pub fn execute(matches: &ArgMatches) -> Result<()> {
     let api = Api::current();
-    let authenticated_api = api.authenticated()?;
-
-    // Query regions available to the current CLI user
-    let regions = authenticated_api.list_available_regions()?;
-
-    let mut organizations: Vec<Organization> = vec![];
-    debug!("Available regions: {:?}", regions);
-
-    // Self-hosted instances won't have a region instance or prefix, so we
-    // need to check before fanning out.
-    if !regions.is_empty() {
-        for region in regions {
-            organizations.append(&mut authenticated_api.list_organizations(Some(&region))?)
-        }
-    } else {
-        organizations.append(&mut authenticated_api.list_organizations(None)?)
-    }
+    let mut organizations = api.authenticated()?.list_organizations_in_all_regions()?;
 
     organizations.sort_by_key(|o| o.name.clone().to_lowercase());
 
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &organizations)?;
+        println!();
+        return Ok(());
+    }
+
     let mut table = Table::new();
     table
         .title_row()
@@ -2,10 +2,12 @@ use anyhow::Result;
 use clap::{ArgMatches, Command};
 
 pub mod list;
+pub mod tokens;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
         $mac!(list);
+        $mac!(tokens);
     };
 }
 
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
+
+use crate::api::{Api, CreateOrgAuthToken};
+use crate::config::Config;
+use crate::utils::logging::quiet_println;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Create a new auth token for an organization.")
+        .long_about(
+            "Create a new auth token for an organization.{n}{n}\
+             The value of the token is printed to stdout. It cannot be retrieved again \
+             later, so store it right away.",
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("NAME")
+                .required(true)
+                .help("The name of the auth token."),
+        )
+        .arg(
+            Arg::new("scopes")
+                .long("scopes")
+                .value_name("SCOPE")
+                .num_args(1..)
+                .action(ArgAction::Append)
+                .help("The scopes of the auth token. [default: the scopes set by the server]"),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let api = Api::current();
+
+    let created = api.authenticated()?.create_org_auth_token(
+        &org,
+        &CreateOrgAuthToken {
+            name: matches.get_one::<String>("name").unwrap(),
+            scopes: matches
+                .get_many::<String>("scopes")
+                .map(|scopes| scopes.map(String::as_str).collect()),
+        },
+    )?;
+
+    quiet_println!(
+        "Created auth token {} (ID {}) with scopes: {}",
+        created.info.name,
+        created.info.id,
+        created.info.scopes.join(", ")
+    );
+    quiet_println!(
+        "{}",
+        style("This is the only time the token is shown, store it in a safe place.").yellow()
+    );
+    println!("{}", created.token);
+
+    Ok(())
+}
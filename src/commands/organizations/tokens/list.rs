@@ -0,0 +1,80 @@
+use std::io;
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::formatting::Table;
+
+/// The prefix of organization auth tokens.
+const ORG_AUTH_TOKEN_PREFIX: &str = "sntrys_";
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("List the auth tokens of an organization.")
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of auth tokens."),
+        )
+}
+
+/// Returns the masked form of a token, which only shows its last characters.
+fn mask_token(last_characters: Option<&str>) -> String {
+    format!(
+        "{ORG_AUTH_TOKEN_PREFIX}****{}",
+        last_characters.unwrap_or_default()
+    )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let api = Api::current();
+    let mut tokens = api.authenticated()?.list_org_auth_tokens(&org)?;
+
+    tokens.sort_by_key(|token| token.date_created);
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &tokens)?;
+        println!();
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("ID")
+        .add("Name")
+        .add("Token")
+        .add("Scopes")
+        .add("Date Created")
+        .add("Last Used");
+
+    for token in &tokens {
+        table
+            .add_row()
+            .add(&token.id)
+            .add(&token.name)
+            .add(mask_token(token.token_last_characters.as_deref()))
+            .add(token.scopes.join(", "))
+            .add(token.date_created.format("%F"))
+            .add(
+                token
+                    .date_last_used
+                    .map_or_else(|| "-".to_owned(), |date| date.format("%F").to_string()),
+            );
+    }
+
+    if table.is_empty() {
+        println!("No auth tokens found");
+    } else {
+        table.print();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+use crate::utils::args::ArgExt;
+
+pub mod create;
+pub mod list;
+pub mod revoke;
+
+macro_rules! each_subcommand {
+    ($mac:ident) => {
+        $mac!(create);
+        $mac!(list);
+        $mac!(revoke);
+    };
+}
+
+pub fn make_command(mut command: Command) -> Command {
+    macro_rules! add_subcommand {
+        ($name:ident) => {{
+            command =
+                command.subcommand(crate::commands::organizations::tokens::$name::make_command(
+                    Command::new(stringify!($name).replace('_', "-")),
+                ));
+        }};
+    }
+
+    command = command
+        .about("Manage the auth tokens of an organization.")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .org_arg();
+    each_subcommand!(add_subcommand);
+    command
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    macro_rules! execute_subcommand {
+        ($name:ident) => {{
+            if let Some(sub_matches) =
+                matches.subcommand_matches(&stringify!($name).replace('_', "-"))
+            {
+                return crate::commands::organizations::tokens::$name::execute(&sub_matches);
+            }
+        }};
+    }
+    each_subcommand!(execute_subcommand);
+    unreachable!();
+}
@@ -0,0 +1,28 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::Api;
+use crate::config::Config;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Revoke an auth token of an organization.")
+        .arg(
+            Arg::new("id")
+                .value_name("ID")
+                .required(true)
+                .help("The ID of the auth token, as shown by `tokens list`."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let id = matches.get_one::<String>("id").unwrap();
+    let api = Api::current();
+
+    api.authenticated()?.revoke_org_auth_token(&org, id)?;
+    println!("Revoked auth token {id}");
+
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use anyhow::Result;
+use clap::{builder::ArgPredicate, Arg, ArgAction, ArgMatches, Command};
+use lazy_static::lazy_static;
+use regex::Regex;
+use sentry::protocol::{Exception, Frame, Stacktrace, Value};
+use uuid::Uuid;
+
+use crate::commands::bash_hook::{add_source_context, hook_event};
+use crate::commands::send_event;
+use crate::config::Config;
+use crate::utils::event::attach_logfile;
+
+const POWERSHELL_SCRIPT: &str = include_str!("../powershellsupport.ps1");
+lazy_static! {
+    static ref FRAME_RE: Regex = Regex::new(r"^at (.*?), (.*): line (\d+)$").unwrap();
+}
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Prints out a PowerShell script that does error handling.")
+        .long_about(
+            "Prints out a PowerShell script that does error handling.{n}{n}\
+             Load the script and install the trap at the top of your script:{n}{n}    \
+             . ([scriptblock]::Create((sentry-cli powershell-hook | Out-String))){n}    \
+             trap { _sentry_err_trap $_; break }{n}{n}\
+             Terminating errors are then reported to Sentry together with the script \
+             stack trace and the recent transcript output.",
+        )
+        .arg(
+            Arg::new("no_exit")
+                .long("no-exit")
+                .action(ArgAction::SetTrue)
+                .help("Do not set $ErrorActionPreference to 'Stop' automatically"),
+        )
+        .arg(
+            Arg::new("no_environ")
+                .long("no-environ")
+                .action(ArgAction::SetTrue)
+                .help("Do not send environment variables along"),
+        )
+        .arg(
+            Arg::new("cli")
+                .long("cli")
+                .value_name("CMD")
+                .help("Explicitly set/override the sentry-cli command"),
+        )
+        .arg(
+            Arg::new("send_event")
+                .long("send-event")
+                .action(ArgAction::SetTrue)
+                .requires_ifs([
+                    (ArgPredicate::IsPresent, "traceback"),
+                    (ArgPredicate::IsPresent, "log"),
+                ])
+                .hide(true),
+        )
+        .arg(
+            Arg::new("traceback")
+                .long("traceback")
+                .value_name("PATH")
+                .hide(true),
+        )
+        .arg(
+            Arg::new("tags")
+                .value_name("KEY:VALUE")
+                .long("tag")
+                .action(ArgAction::Append)
+                .help("Add tags (key:value) to the event."),
+        )
+        .arg(
+            Arg::new("release")
+                .value_name("RELEASE")
+                .long("release")
+                .action(ArgAction::Set)
+                .help("Define release version for the event."),
+        )
+        .arg(Arg::new("log").long("log").value_name("PATH").hide(true))
+}
+
+/// Quotes a value as a single-quoted PowerShell string literal.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn send_event(
+    traceback: &str,
+    logfile: &str,
+    tags: &[&String],
+    release: Option<String>,
+    environ: bool,
+) -> Result<()> {
+    let mut event = hook_event(tags, release, environ)?;
+
+    let mut cmd = "unknown".to_string();
+    let mut exit_code = 1;
+    let mut script = None;
+    let mut error_type = None;
+    let mut error_message = None;
+    let mut frames = vec![];
+
+    if let Ok(f) = fs::File::open(traceback) {
+        let f = BufReader::new(f);
+        for line in f.lines() {
+            let line = line?;
+
+            // meta info
+            if line.starts_with('@') {
+                if let Some(rest) = line.strip_prefix("@command:") {
+                    cmd = rest.to_string();
+                } else if let Some(rest) = line.strip_prefix("@exit_code:") {
+                    exit_code = rest.parse().unwrap_or(exit_code);
+                } else if let Some(rest) = line.strip_prefix("@script:") {
+                    script = Some(rest.to_string()).filter(|s| !s.is_empty());
+                } else if let Some(rest) = line.strip_prefix("@error_type:") {
+                    error_type = Some(rest.to_string()).filter(|s| !s.is_empty());
+                } else if let Some(rest) = line.strip_prefix("@error_message:") {
+                    error_message = Some(rest.to_string()).filter(|s| !s.is_empty());
+                }
+                continue;
+            }
+
+            if let Some(cap) = FRAME_RE.captures(line.trim()) {
+                let filename = Some(cap[2].to_string()).filter(|f| f != "<No file>");
+                frames.push(Frame {
+                    abs_path: filename.as_deref().and_then(|f| {
+                        Path::new(f)
+                            .canonicalize()
+                            .map(|x| x.display().to_string())
+                            .ok()
+                    }),
+                    filename,
+                    lineno: cap[3].parse().ok(),
+                    function: Some(cap[1].to_string()),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    // PowerShell lists the innermost frame first, Sentry expects it last.
+    frames.reverse();
+    add_source_context(&mut frames);
+
+    attach_logfile(&mut event, logfile, false)?;
+
+    if let Some(script) = script {
+        event.extra.insert("script".into(), Value::String(script));
+    }
+    event
+        .extra
+        .insert("command".into(), Value::String(cmd.clone()));
+
+    event.exception.values.push(Exception {
+        ty: error_type.unwrap_or_else(|| "PowerShellError".into()),
+        value: Some(
+            error_message
+                .unwrap_or_else(|| format!("command {cmd} exited with status {exit_code}")),
+        ),
+        stacktrace: Some(Stacktrace {
+            frames,
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let id = send_event::send_raw_event(event)?;
+    println!("{id}");
+
+    Ok(())
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let release = Config::current().get_release(matches).ok();
+
+    let tags: Vec<_> = matches
+        .get_many::<String>("tags")
+        .map(|v| v.collect())
+        .unwrap_or_default();
+
+    if matches.get_flag("send_event") {
+        return send_event(
+            matches.get_one::<String>("traceback").unwrap(),
+            matches.get_one::<String>("log").unwrap(),
+            &tags,
+            release,
+            !matches.get_flag("no_environ"),
+        );
+    }
+
+    let path = env::temp_dir();
+    let id = Uuid::new_v4();
+    let file = |ext: &str| {
+        path.join(format!(".sentry-{}.{ext}", id.as_hyphenated()))
+            .display()
+            .to_string()
+    };
+    let mut script = POWERSHELL_SCRIPT
+        .replace("___SENTRY_TRACEBACK_FILE___", &file("traceback"))
+        .replace("___SENTRY_LOG_FILE___", &file("out"))
+        .replace("___SENTRY_TRANSCRIPT_FILE___", &file("transcript"));
+
+    script = script.replace(
+        " ___SENTRY_TAGS___",
+        &tags
+            .iter()
+            .map(|tag| format!(" --tag {}", quote(tag)))
+            .collect::<Vec<_>>()
+            .join(""),
+    );
+
+    script = match release {
+        Some(release) => script.replace(
+            " ___SENTRY_RELEASE___",
+            &format!(" --release {}", quote(&release)),
+        ),
+        None => script.replace(" ___SENTRY_RELEASE___", ""),
+    };
+
+    script = script.replace(
+        "___SENTRY_CLI___",
+        &quote(&matches.get_one::<String>("cli").map_or_else(
+            || env::current_exe().unwrap().display().to_string(),
+            String::clone,
+        )),
+    );
+
+    if matches.get_flag("no_environ") {
+        script = script.replace(" ___SENTRY_NO_ENVIRON___", " --no-environ");
+    } else {
+        script = script.replace(" ___SENTRY_NO_ENVIRON___", "");
+    }
+
+    if !matches.get_flag("no_exit") {
+        script.insert_str(0, "$ErrorActionPreference = 'Stop'\n\n");
+    }
+    println!("{script}");
+    Ok(())
+}
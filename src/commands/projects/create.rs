@@ -0,0 +1,68 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, NewProject};
+use crate::config::Config;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Create a new project for a team.")
+        .arg(
+            Arg::new("team")
+                .long("team")
+                .short('t')
+                .value_name("TEAM")
+                .required(true)
+                .help("The slug of the team that owns the project."),
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("NAME")
+                .required(true)
+                .help("The name of the project."),
+        )
+        .arg(
+            Arg::new("slug")
+                .long("slug")
+                .value_name("SLUG")
+                .help("The slug of the project. [defaults to a slug derived from the name]"),
+        )
+        .arg(
+            Arg::new("platform")
+                .long("platform")
+                .value_name("PLATFORM")
+                .help("The platform of the project, e.g. `python` or `javascript-react`."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let team = matches.get_one::<String>("team").unwrap();
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+
+    let project = authenticated_api.create_project(
+        &org,
+        team,
+        &NewProject {
+            name: matches.get_one::<String>("name").unwrap(),
+            slug: matches.get_one::<String>("slug").map(String::as_str),
+            platform: matches.get_one::<String>("platform").map(String::as_str),
+        },
+    )?;
+
+    // Sentry creates a default key along with the project.
+    let keys = authenticated_api.list_project_keys(&org, &project.slug)?;
+    let key = keys
+        .iter()
+        .find(|key| key.is_active)
+        .or_else(|| keys.first());
+
+    println!("Created project {}", project.slug);
+    println!("  ID:  {}", project.id);
+    println!("  DSN: {}", key.map_or("-", |key| key.dsn.public.as_str()));
+
+    Ok(())
+}
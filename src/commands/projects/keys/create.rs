@@ -0,0 +1,41 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, NewProjectKey};
+use crate::config::Config;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Create a new client key for a project.")
+        .arg(
+            Arg::new("project")
+                .value_name("PROJECT")
+                .help("The slug of the project. [defaults to the configured project]"),
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("NAME")
+                .help("The label of the key."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let project = config.get_project(matches)?;
+    let api = Api::current();
+
+    let key = api.authenticated()?.create_project_key(
+        &org,
+        &project,
+        &NewProjectKey {
+            name: matches.get_one::<String>("name").map(String::as_str),
+        },
+    )?;
+
+    println!("Created key {} for project {}", key.id, project);
+    println!("  DSN: {}", key.dsn.public);
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, UpdatedProjectKey};
+use crate::config::Config;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Disable a client key of a project, so that its DSN is no longer accepted.")
+        .arg(
+            Arg::new("key")
+                .value_name("KEY_ID")
+                .required(true)
+                .help("The ID of the key, as shown by `projects keys`."),
+        )
+        .arg(
+            Arg::new("project")
+                .value_name("PROJECT")
+                .help("The slug of the project. [defaults to the configured project]"),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let project = config.get_project(matches)?;
+    let key_id = matches.get_one::<String>("key").unwrap();
+    let api = Api::current();
+
+    let key = api.authenticated()?.update_project_key(
+        &org,
+        &project,
+        key_id,
+        &UpdatedProjectKey {
+            is_active: Some(false),
+        },
+    )?;
+
+    println!("Disabled key {} of project {}", key.id, project);
+    println!("  DSN: {}", key.dsn.public);
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use std::io;
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, ProjectKey};
+use crate::config::Config;
+use crate::utils::formatting::Table;
+
+pub mod create;
+pub mod disable;
+
+macro_rules! each_subcommand {
+    ($mac:ident) => {
+        $mac!(create);
+        $mac!(disable);
+    };
+}
+
+pub fn make_command(mut command: Command) -> Command {
+    macro_rules! add_subcommand {
+        ($name:ident) => {{
+            command = command.subcommand(crate::commands::projects::keys::$name::make_command(
+                Command::new(stringify!($name).replace('_', "-")),
+            ));
+        }};
+    }
+
+    command = command
+        .about("List and manage the client keys of a project, which hold its DSNs.")
+        .args_conflicts_with_subcommands(true)
+        .arg(
+            Arg::new("project")
+                .value_name("PROJECT")
+                .help("The slug of the project. [defaults to the configured project]"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of keys."),
+        );
+    each_subcommand!(add_subcommand);
+    command
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    macro_rules! execute_subcommand {
+        ($name:ident) => {{
+            if let Some(sub_matches) =
+                matches.subcommand_matches(&stringify!($name).replace('_', "-"))
+            {
+                return crate::commands::projects::keys::$name::execute(&sub_matches);
+            }
+        }};
+    }
+    each_subcommand!(execute_subcommand);
+    list_keys(matches)
+}
+
+/// Formats the rate limit of a key, such as `1000 per 60s`.
+fn format_rate_limit(key: &ProjectKey) -> String {
+    key.rate_limit.as_ref().map_or_else(
+        || "-".into(),
+        |limit| format!("{} per {}s", limit.count, limit.window),
+    )
+}
+
+fn list_keys(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let project = config.get_project(matches)?;
+    let api = Api::current();
+    let keys = api.authenticated()?.list_project_keys(&org, &project)?;
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &keys)?;
+        println!();
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("ID")
+        .add("Label")
+        .add("DSN")
+        .add("Rate Limit")
+        .add("Active");
+
+    for key in &keys {
+        table
+            .add_row()
+            .add(&key.id)
+            .add(&key.name)
+            .add(&key.dsn.public)
+            .add(format_rate_limit(key))
+            .add(if key.is_active { "yes" } else { "no" });
+    }
+
+    table.print();
+
+    Ok(())
+}
@@ -1,46 +1,133 @@
+use std::io;
+
 use anyhow::Result;
-use clap::{ArgMatches, Command};
+use clap::{Arg, ArgMatches, Command};
+use serde::Serialize;
 
-use crate::api::Api;
+use crate::api::{Api, Project};
 use crate::config::Config;
 use crate::utils::formatting::Table;
 
+/// A project together with the slug of the organization it belongs to.
+#[derive(Serialize)]
+struct OrganizationProject {
+    organization: String,
+    #[serde(flatten)]
+    project: Project,
+}
+
 pub fn make_command(command: Command) -> Command {
-    command.about("List all projects for an organization.")
+    command
+        .about("List all projects for an organization, or for all available organizations.")
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of projects."),
+        )
+        .arg(
+            Arg::new("team")
+                .long("team")
+                .short('t')
+                .value_name("TEAM")
+                .help("Only list the projects of the team with this slug."),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
-    let org = config.get_org(matches)?;
-    let mut projects = api.authenticated()?.list_organization_projects(&org)?;
+    let authenticated_api = api.authenticated()?;
+
+    let all_orgs;
+    let orgs = match config.get_org(matches) {
+        Ok(org) if !org.is_empty() => {
+            all_orgs = false;
+            vec![org]
+        }
+        _ => {
+            all_orgs = true;
+            authenticated_api
+                .list_organizations_in_all_regions()?
+                .into_iter()
+                .map(|o| o.slug)
+                .collect()
+        }
+    };
+
+    let team = matches.get_one::<String>("team");
+    let mut projects = vec![];
+    for org in orgs {
+        projects.extend(
+            authenticated_api
+                .list_organization_projects(&org)?
+                .into_iter()
+                .filter(|project| {
+                    team.map_or(true, |team| {
+                        // `team` is the legacy field holding only the first team.
+                        project
+                            .teams
+                            .iter()
+                            .chain(&project.team)
+                            .any(|t| &t.slug == team)
+                    })
+                })
+                .map(|project| OrganizationProject {
+                    organization: org.clone(),
+                    project,
+                }),
+        );
+    }
     projects.sort_by_key(|p| {
         (
-            p.team.as_ref().map_or(String::new(), |t| t.name.clone()),
-            p.name.clone(),
+            p.organization.clone(),
+            p.project
+                .team
+                .as_ref()
+                .map_or(String::new(), |t| t.name.clone()),
+            p.project.name.clone(),
         )
     });
 
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &projects)?;
+        println!();
+        return Ok(());
+    }
+
     let mut table = Table::new();
-    table
-        .title_row()
+    let title_row = table.title_row();
+    if all_orgs {
+        title_row.add("Organization");
+    }
+    title_row
         .add("ID")
         .add("Slug")
         .add("Team")
-        .add("Name");
+        .add("Name")
+        .add("Platform");
 
-    for project in &projects {
+    for OrganizationProject {
+        organization,
+        project,
+    } in &projects
+    {
         let team_name = &project
             .team
             .as_ref()
             .map_or(String::from("-"), |t| t.name.clone());
 
-        table
-            .add_row()
-            .add(&project.id)
+        let row = table.add_row();
+        if all_orgs {
+            row.add(organization);
+        }
+        row.add(&project.id)
             .add(&project.slug)
             .add(team_name)
-            .add(&project.name);
+            .add(&project.name)
+            .add(project.platform.as_deref().unwrap_or("-"));
     }
 
     table.print();
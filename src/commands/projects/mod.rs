@@ -3,10 +3,14 @@ use clap::{ArgMatches, Command};
 
 use crate::utils::args::ArgExt;
 
+pub mod create;
+pub mod keys;
 pub mod list;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
+        $mac!(create);
+        $mac!(keys);
         $mac!(list);
     };
 }
@@ -16,6 +16,7 @@ use crate::utils::appcenter::{get_appcenter_package, get_react_native_appcenter_
 use crate::utils::args::{validate_distribution, ArgExt};
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::UploadContext;
+use crate::utils::logging::quiet_println;
 use crate::utils::sourcemaps::SourceMapProcessor;
 
 pub fn make_command(command: Command) -> Command {
@@ -128,7 +129,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     );
 
     if !print_release_name {
-        println!(
+        quiet_println!(
             "{} Fetching latest AppCenter deployment info",
             style(">").dim()
         );
@@ -151,7 +152,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         return Ok(());
     }
 
-    println!(
+    quiet_println!(
         "{} Processing react-native AppCenter sourcemaps",
         style(">").dim()
     );
@@ -188,7 +189,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     match matches.get_many::<String>("dist") {
         None => {
-            println!(
+            quiet_println!(
                 "Uploading sourcemaps for release {} (no distribution value given; use --dist to set distribution value)",
                 &release
             );
@@ -198,18 +199,20 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 project: Some(&project),
                 release: Some(&release),
                 dist: None,
-                note: None,
+                metadata: None,
                 wait,
                 max_wait,
                 dedupe: false,
                 chunk_upload_options: chunk_upload_options.as_ref(),
+                compression: None,
             })?;
         }
         Some(dists) => {
             for dist in dists {
-                println!(
+                quiet_println!(
                     "Uploading sourcemaps for release {} distribution {}",
-                    &release, dist
+                    &release,
+                    dist
                 );
 
                 processor.upload(&UploadContext {
@@ -217,11 +220,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                     project: Some(&project),
                     release: Some(&release),
                     dist: Some(dist),
-                    note: None,
+                    metadata: None,
                     wait,
                     max_wait,
                     dedupe: false,
                     chunk_upload_options: chunk_upload_options.as_ref(),
+                    compression: None,
                 })?;
             }
         }
@@ -0,0 +1,212 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use log::{debug, info};
+use sourcemap::ram_bundle::RamBundle;
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::constants::DEFAULT_MAX_WAIT;
+use crate::utils::args::{validate_distribution, ArgExt};
+use crate::utils::file_search::ReleaseFileSearch;
+use crate::utils::file_upload::UploadContext;
+use crate::utils::logging::quiet_println;
+use crate::utils::sourcemaps::{is_hermes_bytecode, validate_bundle_sourcemap, SourceMapProcessor};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Upload a react-native bundle and its sourcemap built by a custom packager step.")
+        .long_about(
+            "Upload a react-native bundle and its sourcemap built by a custom packager step.{n}{n}\
+             This injects debug IDs into the bundle and sourcemap, and uploads them as an \
+             artifact bundle, like the xcode and gradle integrations do after the packager \
+             step. Use this for bundles built outside of Xcode and Gradle, for instance for \
+             over-the-air updates. Hermes bytecode bundles are not modified; they reference \
+             the debug ID of their sourcemap instead.",
+        )
+        .org_arg()
+        .project_arg(false)
+        .arg(
+            Arg::new("bundle")
+                .long("bundle")
+                .value_name("PATH")
+                .required(true)
+                .help("The path to the bundle that should be uploaded."),
+        )
+        .arg(
+            Arg::new("sourcemap")
+                .long("sourcemap")
+                .value_name("PATH")
+                .required(true)
+                .help("The path to the sourcemap of the bundle."),
+        )
+        .arg(
+            Arg::new("release")
+                .long("release")
+                .value_name("RELEASE")
+                .help("The name of the release to associate the bundle with."),
+        )
+        .arg(
+            Arg::new("dist")
+                .long("dist")
+                .value_name("DISTRIBUTION")
+                .requires("release")
+                .value_parser(validate_distribution)
+                .help("The name of the distribution to associate the bundle with."),
+        )
+        .arg(
+            Arg::new("platform")
+                .long("platform")
+                .value_name("PLATFORM")
+                .value_parser(["ios", "android"])
+                .help(
+                    "Upload the bundle under the name react-native loads it from on this \
+                     platform, `main.jsbundle` or `index.android.bundle`. \
+                     [default: the file name of the bundle]",
+                ),
+        )
+        .arg(
+            Arg::new("wait")
+                .long("wait")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("wait_for")
+                .help("Wait for the server to fully process uploaded files."),
+        )
+        .arg(
+            Arg::new("wait_for")
+                .long("wait-for")
+                .value_name("SECS")
+                .value_parser(clap::value_parser!(u64))
+                .conflicts_with("wait")
+                .help(
+                    "Wait for the server to fully process uploaded files, \
+                     but at most for the given number of seconds.",
+                ),
+        )
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into()
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let api = Api::current();
+    let base = env::current_dir()?;
+
+    let bundle_path = PathBuf::from(matches.get_one::<String>("bundle").unwrap());
+    let sourcemap_path = PathBuf::from(matches.get_one::<String>("sourcemap").unwrap());
+    let bundle_name = match matches.get_one::<String>("platform").map(String::as_str) {
+        Some("ios") => "main.jsbundle".to_owned(),
+        Some("android") => "index.android.bundle".to_owned(),
+        _ => file_name(&bundle_path),
+    };
+    let bundle_url = format!("~/{bundle_name}");
+    let sourcemap_url = format!("~/{}", file_name(&sourcemap_path));
+
+    info!(
+        "Issuing a command for Organization: {} Project: {}",
+        org, project
+    );
+
+    quiet_println!("Processing react-native bundle for Sentry upload.");
+    info!("  bundle path: {}", bundle_path.display());
+    info!("  sourcemap path: {}", sourcemap_path.display());
+
+    let bundle = fs::read(&bundle_path)
+        .with_context(|| format!("Failed to read bundle {}", bundle_path.display()))?;
+    let sourcemap = fs::read(&sourcemap_path)
+        .with_context(|| format!("Failed to read sourcemap {}", sourcemap_path.display()))?;
+
+    // Refuse to upload a sourcemap of a different or stale bundle, since it
+    // would be associated with the bundle through the injected debug ID.
+    let findings = validate_bundle_sourcemap(&file_name(&bundle_path), &bundle, &sourcemap)
+        .with_context(|| format!("Failed to parse sourcemap {}", sourcemap_path.display()))?;
+    if !findings.is_empty() {
+        bail!(
+            "The sourcemap {} does not belong to the bundle {}:\n  - {}",
+            sourcemap_path.display(),
+            bundle_path.display(),
+            findings.join("\n  - ")
+        );
+    }
+
+    if is_hermes_bytecode(&bundle) {
+        debug!("Hermes bytecode bundle found, skipping debug ID injection");
+    } else {
+        // Inject under the file names, so that a `sourceMappingURL` comment
+        // in the bundle resolves to the sourcemap.
+        let mut processor = SourceMapProcessor::new();
+        processor.add(
+            &format!("~/{}", file_name(&bundle_path)),
+            ReleaseFileSearch::collect_file(bundle_path.clone())?,
+        )?;
+        processor.add(
+            &sourcemap_url,
+            ReleaseFileSearch::collect_file(sourcemap_path.clone())?,
+        )?;
+        processor.inject_debug_ids(false, false, &[])?;
+    }
+
+    let mut processor = SourceMapProcessor::new();
+    processor.add(
+        &bundle_url,
+        ReleaseFileSearch::collect_file(bundle_path.clone())?,
+    )?;
+    processor.add(
+        &sourcemap_url,
+        ReleaseFileSearch::collect_file(sourcemap_path)?,
+    )?;
+
+    if let Ok(ram_bundle) = RamBundle::parse_unbundle_from_path(&bundle_path) {
+        debug!("File RAM bundle found, extracting its contents...");
+        processor.unpack_ram_bundle(&ram_bundle, &bundle_url)?;
+    } else {
+        debug!("Non-file bundle found");
+    }
+
+    processor.rewrite(&[base.to_str().unwrap()])?;
+    processor.add_sourcemap_references()?;
+    processor.add_debug_id_references()?;
+
+    let release = matches.get_one::<String>("release");
+    let dist = matches.get_one::<String>("dist");
+    let chunk_upload_options = api.authenticated()?.get_chunk_upload_options(&org)?;
+
+    let wait_for_secs = matches.get_one::<u64>("wait_for").copied();
+    let wait = matches.get_flag("wait") || wait_for_secs.is_some();
+    let max_wait = wait_for_secs.map_or(DEFAULT_MAX_WAIT, Duration::from_secs);
+
+    match (release, dist) {
+        (Some(release), Some(dist)) => quiet_println!(
+            "Uploading bundle for release {} distribution {}",
+            release,
+            dist
+        ),
+        (Some(release), None) => quiet_println!("Uploading bundle for release {}", release),
+        _ => quiet_println!("Uploading bundle"),
+    }
+
+    processor.upload(&UploadContext {
+        org: &org,
+        project: Some(&project),
+        release: release.map(String::as_str),
+        dist: dist.map(String::as_str),
+        metadata: None,
+        wait,
+        max_wait,
+        dedupe: false,
+        chunk_upload_options: chunk_upload_options.as_ref(),
+        compression: None,
+    })?;
+
+    Ok(())
+}
@@ -13,6 +13,7 @@ use crate::constants::DEFAULT_MAX_WAIT;
 use crate::utils::args::{validate_distribution, ArgExt};
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::UploadContext;
+use crate::utils::logging::quiet_println;
 use crate::utils::sourcemaps::SourceMapProcessor;
 
 pub fn make_command(command: Command) -> Command {
@@ -87,7 +88,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         org, project
     );
 
-    println!("Processing react-native sourcemaps for Sentry upload.");
+    quiet_println!("Processing react-native sourcemaps for Sentry upload.");
     info!("  bundle path: {}", bundle_path.display());
     info!("  sourcemap path: {}", sourcemap_path.display());
 
@@ -121,9 +122,10 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     if let Some(version) = version {
         for dist in matches.get_many::<String>("dist").unwrap() {
-            println!(
+            quiet_println!(
                 "Uploading sourcemaps for release {} distribution {}",
-                version, dist
+                version,
+                dist
             );
 
             processor.upload(&UploadContext {
@@ -131,11 +133,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 project: Some(&project),
                 release: Some(version),
                 dist: Some(dist),
-                note: None,
+                metadata: None,
                 wait,
                 max_wait,
                 dedupe: false,
                 chunk_upload_options: chunk_upload_options.as_ref(),
+                compression: None,
             })?;
         }
     } else {
@@ -145,11 +148,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             project: Some(&project),
             release: None,
             dist: None,
-            note: None,
+            metadata: None,
             wait,
             max_wait,
             dedupe: false,
             chunk_upload_options: chunk_upload_options.as_ref(),
+            compression: None,
         })?;
     }
 
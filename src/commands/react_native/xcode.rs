@@ -20,6 +20,7 @@ use crate::utils::args::{validate_distribution, ArgExt};
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::UploadContext;
 use crate::utils::fs::TempFile;
+use crate::utils::logging::quiet_println;
 use crate::utils::sourcemaps::SourceMapProcessor;
 use crate::utils::system::propagate_exit_status;
 use crate::utils::xcode::InfoPlist;
@@ -30,6 +31,8 @@ struct SourceMapReport {
     packager_sourcemap_path: Option<PathBuf>,
     hermes_bundle_path: Option<PathBuf>,
     hermes_sourcemap_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hermes_compiler_sourcemap_path: Option<PathBuf>,
 }
 
 pub fn make_command(command: Command) -> Command {
@@ -119,6 +122,22 @@ pub fn make_command(command: Command) -> Command {
                      but at most for the given number of seconds.",
                 ),
         )
+        .arg(
+            Arg::new("hermes")
+                .long("hermes")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("no_hermes")
+                .help(
+                    "Treat the build as a Hermes build even if hermesc was not detected.{n}\
+                     The Hermes bundle is uploaded together with the composed source map.",
+                ),
+        )
+        .arg(
+            Arg::new("no_hermes")
+                .long("no-hermes")
+                .action(ArgAction::SetTrue)
+                .help("Upload the React Native Packager bundle and source map even if Hermes is enabled."),
+        )
         .arg(
             Arg::new("no_auto_release")
                 .long("no-auto-release")
@@ -154,6 +173,43 @@ fn is_hermes_enabled(hermesc: &String) -> bool {
     return Path::new(hermesc).exists();
 }
 
+fn find_compose_source_maps_script(base: &Path) -> PathBuf {
+    if let Ok(path) = env::var("COMPOSE_SOURCEMAP_PATH") {
+        if !path.is_empty() {
+            return base.join(path);
+        }
+    }
+    base.join("../node_modules/react-native/scripts/compose-source-maps.js")
+}
+
+/// Composes the packager and the Hermes compiler source maps into the final
+/// source map of the Hermes bundle, the same way the React Native build script
+/// does when `SOURCEMAP_FILE` is set.
+fn compose_hermes_source_maps(
+    node: &str,
+    script: &Path,
+    packager_sourcemap_path: &Path,
+    hermes_compiler_sourcemap_path: &Path,
+    output_path: &Path,
+) -> Result<()> {
+    info!("Composing Hermes source maps using {}", script.display());
+    let rv = process::Command::new(node)
+        .arg(script)
+        .arg(packager_sourcemap_path)
+        .arg(hermes_compiler_sourcemap_path)
+        .arg("-o")
+        .arg(output_path)
+        .spawn()?
+        .wait()?;
+    if !rv.success() {
+        bail!(
+            "Failed to compose Hermes source maps using {}",
+            script.display()
+        );
+    }
+    Ok(())
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let (org, project) = config.get_org_and_project(matches)?;
@@ -206,6 +262,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     info!("Using node interpreter '{}'", &node);
     let hermesc = find_hermesc();
     info!("Using hermesc interpreter '{}'", &hermesc);
+    let hermes = !matches.get_flag("no_hermes")
+        && (matches.get_flag("hermes") || is_hermes_enabled(&hermesc));
+    info!("Hermes enabled: {}", hermes);
 
     let bundle_path;
     let sourcemap_path;
@@ -271,7 +330,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             )
             .env("__SENTRY_RN_WRAP_XCODE_CALL", "1");
 
-        if is_hermes_enabled(&hermesc) {
+        if hermes {
             command
                 .env("HERMES_CLI_PATH", env::current_exe()?.to_str().unwrap())
                 .env("SENTRY_RN_REAL_HERMES_CLI_PATH", &hermesc);
@@ -291,23 +350,59 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         let (Some(packager_bundle_path), Some(packager_sourcemap_path)) =
             (report.packager_bundle_path, report.packager_sourcemap_path)
         else {
-            println!("Warning: build produced no packager sourcemaps.");
+            quiet_println!("Warning: build produced no packager sourcemaps.");
             return Ok(());
         };
 
+        // If the build script did not compose the Hermes source map itself
+        // (it only does so when `SOURCEMAP_FILE` is set), we compose it from
+        // the packager and the Hermes compiler source maps.
+        let hermes_sourcemap_path = match (
+            hermes,
+            &report.hermes_bundle_path,
+            report.hermes_sourcemap_path,
+            report.hermes_compiler_sourcemap_path,
+        ) {
+            (false, _, _, _) => None,
+            (true, _, Some(path), _) => Some(path),
+            (true, Some(hermes_bundle_path), None, Some(compiler_sourcemap_path))
+                if compiler_sourcemap_path.exists() =>
+            {
+                let output_path = match env::var("SOURCEMAP_FILE") {
+                    Ok(path) if !path.is_empty() => base.join(path),
+                    _ => env::temp_dir().join(format!(
+                        "{}.map",
+                        hermes_bundle_path.file_name().unwrap().to_string_lossy()
+                    )),
+                };
+                compose_hermes_source_maps(
+                    &node,
+                    &find_compose_source_maps_script(&base),
+                    &packager_sourcemap_path,
+                    &compiler_sourcemap_path,
+                    &output_path,
+                )?;
+                if env::var("SENTRY_RN_NO_DEBUG_ID").unwrap_or("0".to_string()) != "1" {
+                    copy_debug_id(&packager_sourcemap_path, &output_path)?;
+                }
+                Some(output_path)
+            }
+            _ => None,
+        };
+
         // If Hermes emitted source map we have to use it
         if let (Some(hermes_bundle_path), Some(hermes_sourcemap_path)) =
-            (report.hermes_bundle_path, report.hermes_sourcemap_path)
+            (report.hermes_bundle_path, hermes_sourcemap_path)
         {
-            bundle_path = hermes_bundle_path.clone();
-            sourcemap_path = hermes_sourcemap_path.clone();
-            println!("Using Hermes bundle and combined source map.");
+            bundle_path = hermes_bundle_path;
+            sourcemap_path = hermes_sourcemap_path;
+            quiet_println!("Using Hermes bundle and combined source map.");
 
         // If Hermes emitted only bundle or Hermes was disabled use packager bundle and source map
         } else {
             bundle_path = packager_bundle_path;
             sourcemap_path = packager_sourcemap_path;
-            println!("Using React Native Packager bundle and source map.");
+            quiet_println!("Using React Native Packager bundle and source map.");
         }
         bundle_url = format!("~/{}", bundle_path.file_name().unwrap().to_string_lossy());
         sourcemap_url = format!(
@@ -318,7 +413,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     // now that we have all the data, we can now process and upload the
     // sourcemaps.
-    println!("Processing react-native sourcemaps for Sentry upload.");
+    quiet_println!("Processing react-native sourcemaps for Sentry upload.");
     info!("  bundle path: {}", bundle_path.display());
     info!("  sourcemap path: {}", sourcemap_path.display());
 
@@ -348,11 +443,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             project: Some(&project),
             release: None,
             dist: None,
-            note: None,
+            metadata: None,
             wait,
             max_wait,
             dedupe: false,
             chunk_upload_options: chunk_upload_options.as_ref(),
+            compression: None,
         })?;
     } else {
         let (dist, release_name) = match (&dist_from_env, &release_from_env) {
@@ -384,11 +480,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                     project: Some(&project),
                     release: release_name.as_deref(),
                     dist: dist.as_deref(),
-                    note: None,
+                    metadata: None,
                     wait,
                     max_wait,
                     dedupe: false,
                     chunk_upload_options: chunk_upload_options.as_ref(),
+                    compression: None,
                 })?;
             }
             Some(dists) => {
@@ -398,11 +495,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                         project: Some(&project),
                         release: release_name.as_deref(),
                         dist: Some(dist),
-                        note: None,
+                        metadata: None,
                         wait,
                         max_wait,
                         dedupe: false,
                         chunk_upload_options: chunk_upload_options.as_ref(),
+                        compression: None,
                     })?;
                 }
             }
@@ -472,11 +570,24 @@ pub fn wrap_call() -> Result<()> {
     // and users can't change it
     } else if args.len() > 1 && args[0] == "-emit-binary" {
         execute_hermes_compiler = true;
+        let mut emit_sourcemap = false;
         let mut iter = args.iter().fuse();
         while let Some(item) = iter.next() {
             if item == "-out" {
                 bundle_path = iter.next().cloned();
+            } else if item == "-output-source-map" {
+                emit_sourcemap = true;
+            }
+        }
+
+        // hermesc writes its source map next to the bytecode bundle.  Without
+        // it the Hermes bundle can't be symbolicated so we always request it.
+        if let Some(ref path) = bundle_path {
+            if !emit_sourcemap {
+                args.push("-output-source-map".into());
             }
+            sourcemap_report.hermes_compiler_sourcemap_path =
+                Some(PathBuf::from(format!("{path}.map")));
         }
 
         sourcemap_report.hermes_bundle_path = bundle_path.map(PathBuf::from);
@@ -520,58 +631,64 @@ pub fn wrap_call() -> Result<()> {
         // because the combine source map doesn't copy it over
         // We have to do it while pretending being the script because of the clean up afterwards
         if let Some(ref packager_sourcemap_path) = sourcemap_report.packager_sourcemap_path {
-            let mut packager_sourcemap_file = fs::File::open(packager_sourcemap_path)?;
-            let packager_sourcemap_result: Result<HashMap<String, Value>, serde_json::Error> =
-                serde_json::from_reader(&mut packager_sourcemap_file);
-
             let hermes_sourcemap_path = sourcemap_report.hermes_sourcemap_path.as_ref().unwrap();
-            let mut hermes_sourcemap_file = fs::File::open(hermes_sourcemap_path)?;
-            let hermes_sourcemap_result: Result<HashMap<String, Value>, serde_json::Error> =
-                serde_json::from_reader(&mut hermes_sourcemap_file);
-
-            if packager_sourcemap_result.is_err() {
-                println!(
-                    "React Native Packager source map {} doesn't contain a valid JSON data, skipping copy of debug id to Hermes combined source map.",
-                    packager_sourcemap_path.as_path().display(),
-                );
-            }
+            copy_debug_id(packager_sourcemap_path, hermes_sourcemap_path)?;
+        } else {
+            quiet_println!("No packager source map found in source map report, skipping copy of debug id to Hermes combined source map.");
+        }
+    }
 
-            if hermes_sourcemap_result.is_err() {
-                println!(
-                    "Hermes combined source map {} doesn't contain a valid JSON data, skipping copy of debug id to Hermes combined source map.",
-                    hermes_sourcemap_path.as_path().display(),
-                );
-            }
+    let mut f = fs::File::create(&report_file_path)?;
+    serde_json::to_writer(&mut f, &sourcemap_report)?;
+
+    Ok(())
+}
 
-            if let (Ok(packager_sourcemap), Ok(mut hermes_sourcemap)) =
-                (packager_sourcemap_result, hermes_sourcemap_result)
+/// Copies the debug id from the packager source map to the Hermes combined
+/// source map, unless the latter already has one.
+fn copy_debug_id(packager_sourcemap_path: &Path, hermes_sourcemap_path: &Path) -> Result<()> {
+    let mut packager_sourcemap_file = fs::File::open(packager_sourcemap_path)?;
+    let packager_sourcemap_result: Result<HashMap<String, Value>, serde_json::Error> =
+        serde_json::from_reader(&mut packager_sourcemap_file);
+
+    let mut hermes_sourcemap_file = fs::File::open(hermes_sourcemap_path)?;
+    let hermes_sourcemap_result: Result<HashMap<String, Value>, serde_json::Error> =
+        serde_json::from_reader(&mut hermes_sourcemap_file);
+
+    if packager_sourcemap_result.is_err() {
+        quiet_println!(
+            "React Native Packager source map {} doesn't contain a valid JSON data, skipping copy of debug id to Hermes combined source map.",
+            packager_sourcemap_path.display(),
+        );
+    }
+
+    if hermes_sourcemap_result.is_err() {
+        quiet_println!(
+            "Hermes combined source map {} doesn't contain a valid JSON data, skipping copy of debug id to Hermes combined source map.",
+            hermes_sourcemap_path.display(),
+        );
+    }
+
+    if let (Ok(packager_sourcemap), Ok(mut hermes_sourcemap)) =
+        (packager_sourcemap_result, hermes_sourcemap_result)
+    {
+        if !hermes_sourcemap.contains_key("debugId") && !hermes_sourcemap.contains_key("debug_id") {
+            if let Some(debug_id) = packager_sourcemap
+                .get("debugId")
+                .or_else(|| packager_sourcemap.get("debug_id"))
             {
-                if !hermes_sourcemap.contains_key("debugId")
-                    && !hermes_sourcemap.contains_key("debug_id")
-                {
-                    if let Some(debug_id) = packager_sourcemap
-                        .get("debugId")
-                        .or_else(|| packager_sourcemap.get("debug_id"))
-                    {
-                        hermes_sourcemap.insert("debugId".to_string(), debug_id.clone());
-                        hermes_sourcemap.insert("debug_id".to_string(), debug_id.clone());
-
-                        hermes_sourcemap_file = fs::File::create(hermes_sourcemap_path)?;
-                        serde_json::to_writer(&mut hermes_sourcemap_file, &hermes_sourcemap)?;
-                    } else {
-                        println!("No debug id found in packager source map, skipping copy to Hermes combined source map.");
-                    }
-                } else {
-                    println!("Hermes combined source map already contains a debug id, skipping copy from packager source map.");
-                }
+                hermes_sourcemap.insert("debugId".to_string(), debug_id.clone());
+                hermes_sourcemap.insert("debug_id".to_string(), debug_id.clone());
+
+                hermes_sourcemap_file = fs::File::create(hermes_sourcemap_path)?;
+                serde_json::to_writer(&mut hermes_sourcemap_file, &hermes_sourcemap)?;
+            } else {
+                quiet_println!("No debug id found in packager source map, skipping copy to Hermes combined source map.");
             }
         } else {
-            println!("No packager source map found in source map report, skipping copy of debug id to Hermes combined source map.");
+            quiet_println!("Hermes combined source map already contains a debug id, skipping copy from packager source map.");
         }
     }
 
-    let mut f = fs::File::create(&report_file_path)?;
-    serde_json::to_writer(&mut f, &sourcemap_report)?;
-
     Ok(())
 }
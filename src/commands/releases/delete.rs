@@ -1,31 +1,78 @@
-use anyhow::Result;
-use clap::{ArgMatches, Command};
+use anyhow::{bail, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 
-use crate::api::Api;
+use crate::api::{Api, ReleaseDeletion};
 use crate::config::Config;
 use crate::utils::args::ArgExt;
+use crate::utils::logging::quiet_println;
 
 pub fn make_command(command: Command) -> Command {
     command
         .about("Delete a release.")
         .allow_hyphen_values(true)
         .version_arg(false)
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .short('f')
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Delete the files and artifact bundles still attached to the \
+                     release before deleting it.",
+                ),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
-    let version = matches.get_one::<String>("version").unwrap();
+    let authenticated_api = api.authenticated()?;
+    let version = &config.get_release_version(matches)?;
+    let org = config.get_org(matches)?;
     let project = config.get_project(matches).ok();
 
-    if api.authenticated()?.delete_release(
-        &config.get_org(matches)?,
-        project.as_deref(),
-        version,
-    )? {
-        println!("Deleted release {version}!");
-    } else {
-        println!("Did nothing. Release with this version ({version}) does not exist.");
+    let mut deletion = authenticated_api.delete_release(&org, project.as_deref(), version)?;
+
+    if let ReleaseDeletion::Rejected(err) = deletion {
+        // Releases with files attached cannot be deleted, but the server also
+        // rejects deleting releases for other reasons.
+        let files = authenticated_api
+            .list_release_files(&org, project.as_deref(), version)
+            .unwrap_or_default();
+
+        if !matches.get_flag("force") {
+            if files.is_empty() {
+                return Err(err.into());
+            }
+            bail!(
+                "Release {version} cannot be deleted because {} {} still attached to it.\n\
+                 Run `sentry-cli releases delete --force {version}` to delete them together \
+                 with the release.",
+                files.len(),
+                match files.len() {
+                    1 => "file is",
+                    _ => "files are",
+                }
+            );
+        }
+
+        for file in &files {
+            if authenticated_api.delete_release_file(&org, project.as_deref(), version, &file.id)? {
+                quiet_println!("D {}", file.name);
+            }
+        }
+        // Artifact bundles are not listed as release files, but are associated by name.
+        authenticated_api.delete_release_files(&org, project.as_deref(), version)?;
+
+        deletion = authenticated_api.delete_release(&org, project.as_deref(), version)?;
+    }
+
+    match deletion {
+        ReleaseDeletion::Deleted => quiet_println!("Deleted release {version}!"),
+        ReleaseDeletion::NotFound => {
+            quiet_println!("Did nothing. Release with this version ({version}) does not exist.")
+        }
+        ReleaseDeletion::Rejected(err) => return Err(err.into()),
     }
 
     Ok(())
@@ -5,9 +5,12 @@ use clap::{Arg, ArgMatches, Command};
 use crate::api::{Api, UpdatedRelease};
 use crate::config::Config;
 use crate::utils::args::{get_timestamp, ArgExt};
+use crate::utils::logging::quiet_println;
+
+use super::set_commits::{commit_args, find_release_heads, set_release_heads, CommitOptions};
 
 pub fn make_command(command: Command) -> Command {
-    command
+    let command = command
         .about("Mark a release as finalized and released.")
         .allow_hyphen_values(true)
         .version_arg(false)
@@ -30,16 +33,26 @@ pub fn make_command(command: Command) -> Command {
                 .value_parser(get_timestamp)
                 .value_name("TIMESTAMP")
                 .help("Set the release time. [defaults to the current time]"),
-        )
+        );
+    commit_args(command)
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
-    let version = matches.get_one::<String>("version").unwrap();
+    let authenticated_api = api.authenticated()?;
+    let version = &config.get_release_version(matches)?;
+    let org = config.get_org(matches)?;
+
+    // Commits are set before finalizing, so that the release is never
+    // marked as released without its commits.
+    if let Some(options) = CommitOptions::from_commit_args(matches) {
+        let heads = find_release_heads(&authenticated_api, &org, &options)?;
+        set_release_heads(&authenticated_api, &org, version, heads, &options)?;
+    }
 
-    api.authenticated()?.update_release(
-        &config.get_org(matches)?,
+    authenticated_api.update_release(
+        &org,
         version,
         &UpdatedRelease {
             projects: config.get_projects(matches).ok(),
@@ -54,6 +67,6 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         },
     )?;
 
-    println!("Finalized release {version}");
+    quiet_println!("Finalized release {version}");
     Ok(())
 }
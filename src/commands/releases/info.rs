@@ -1,7 +1,11 @@
+use std::io;
+
 use anyhow::Result;
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
+use serde::Serialize;
 
-use crate::api::Api;
+use crate::api::{Api, Deploy, ReleaseCommit, ReleaseInfo};
 use crate::config::Config;
 use crate::utils::args::ArgExt;
 use crate::utils::formatting::Table;
@@ -25,15 +29,98 @@ pub fn make_command(command: Command) -> Command {
                 .short('C')
                 .long("show-commits")
                 .action(ArgAction::SetTrue)
-                .help("Display the Commits column"),
+                .help("Display the commits of the release."),
+        )
+        .arg(
+            Arg::new("max_commits")
+                .long("max-commits")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("100")
+                .help("The maximum number of commits to display with --show-commits."),
+        )
+        .arg(
+            Arg::new("show_deploys")
+                .short('D')
+                .long("show-deploys")
+                .action(ArgAction::SetTrue)
+                .help("Display the deploys of the release."),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the release information."),
+        )
+}
+
+/// The release information printed with `--format json`.
+#[derive(Serialize)]
+struct ReleaseDetails<'a> {
+    #[serde(flatten)]
+    release: &'a ReleaseInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commits: Option<&'a [ReleaseCommit]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deploys: Option<&'a [Deploy<'a>]>,
+}
+
+fn print_commits(commits: &[ReleaseCommit]) {
+    println!();
+    println!("{}", style("Commits").bold());
+    if commits.is_empty() {
+        println!("No commits found");
+        return;
+    }
+
+    let mut tbl = Table::new();
+    tbl.title_row().add("Commit").add("Author").add("Message");
+    for commit in commits {
+        let author = commit
+            .author
+            .as_ref()
+            .and_then(|author| author.name.as_deref().or(author.email.as_deref()))
+            .unwrap_or("-");
+        tbl.add_row()
+            .add(commit.short_id())
+            .add(author)
+            .add(commit.summary());
+    }
+    tbl.print();
+}
+
+fn print_deploys(deploys: &[Deploy]) {
+    println!();
+    println!("{}", style("Deploys").bold());
+    if deploys.is_empty() {
+        println!("No deploys found");
+        return;
+    }
+
+    let mut tbl = Table::new();
+    tbl.title_row()
+        .add("Environment")
+        .add("Name")
+        .add("Finished");
+    for deploy in deploys {
+        let row = tbl.add_row();
+        row.add(&deploy.env).add(deploy.name());
+        if let Some(finished) = deploy.finished {
+            row.add(finished);
+        } else {
+            row.add("-");
+        }
+    }
+    tbl.print();
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let api = Api::current();
     let authenticated_api = api.authenticated()?;
-    let version = matches.get_one::<String>("version").unwrap();
     let config = Config::current();
+    let version = &config.get_release_version(matches)?;
     let org = config.get_org(matches)?;
     let project = config.get_project(matches).ok();
     let release = authenticated_api.get_release(&org, project.as_deref(), version)?;
@@ -45,67 +132,90 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         return Ok(());
     }
 
-    if let Some(release) = release {
-        let mut tbl = Table::new();
-        let title_row = tbl.title_row().add("Version").add("Date created");
+    let Some(release) = release else {
+        return Err(QuietExit(1).into());
+    };
 
-        if release.last_event.is_some() {
-            title_row.add("Last event");
-        }
+    let commits = if matches.get_flag("show_commits") {
+        let max_commits = *matches.get_one::<usize>("max_commits").unwrap();
+        let commits = authenticated_api.get_release_commits(
+            &org,
+            project.as_deref(),
+            version,
+            max_commits,
+        )?;
+        Some(commits.unwrap_or_default())
+    } else {
+        None
+    };
 
-        if matches.get_flag("show_projects") {
-            title_row.add("Projects");
-        }
+    let deploys = if matches.get_flag("show_deploys") {
+        Some(authenticated_api.list_deploys(&org, version)?)
+    } else {
+        None
+    };
 
-        if matches.get_flag("show_commits") {
-            title_row.add("Commits");
-        }
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        let details = ReleaseDetails {
+            release: &release,
+            commits: commits.as_deref(),
+            deploys: deploys.as_deref(),
+        };
+        serde_json::to_writer_pretty(&mut io::stdout(), &details)?;
+        println!();
+        return Ok(());
+    }
 
-        let data_row = tbl
-            .add_row()
-            .add(&release.version)
-            .add(release.date_created);
+    let mut tbl = Table::new();
+    let title_row = tbl.title_row().add("Version").add("Date created");
 
-        if let Some(last_event) = release.last_event {
-            data_row.add(last_event);
-        }
+    if release.url.is_some() {
+        title_row.add("URL");
+    }
 
-        if matches.get_flag("show_projects") {
-            let project_slugs = release
-                .projects
-                .into_iter()
-                .map(|p| p.slug)
-                .collect::<Vec<_>>();
-            if !project_slugs.is_empty() {
-                data_row.add(project_slugs.join("\n"));
-            } else {
-                data_row.add("-");
-            }
-        }
+    if release.last_event.is_some() {
+        title_row.add("Last event");
+    }
+
+    if matches.get_flag("show_projects") {
+        title_row.add("Projects");
+    }
+
+    let data_row = tbl
+        .add_row()
+        .add(&release.version)
+        .add(release.date_created);
 
-        if matches.get_flag("show_commits") {
-            if let Ok(Some(commits)) =
-                authenticated_api.get_release_commits(&org, project.as_deref(), version)
-            {
-                if !commits.is_empty() {
-                    data_row.add(
-                        commits
-                            .into_iter()
-                            .map(|c| c.id)
-                            .collect::<Vec<String>>()
-                            .join("\n"),
-                    );
-                } else {
-                    data_row.add("-");
-                }
-            } else {
-                data_row.add("-");
-            }
+    if let Some(ref url) = release.url {
+        data_row.add(url);
+    }
+
+    if let Some(last_event) = release.last_event {
+        data_row.add(last_event);
+    }
+
+    if matches.get_flag("show_projects") {
+        let project_slugs = release
+            .projects
+            .iter()
+            .map(|p| p.slug.as_str())
+            .collect::<Vec<_>>();
+        if !project_slugs.is_empty() {
+            data_row.add(project_slugs.join("\n"));
+        } else {
+            data_row.add("-");
         }
+    }
 
-        tbl.print();
-    } else {
-        return Err(QuietExit(1).into());
+    tbl.print();
+
+    if let Some(ref commits) = commits {
+        print_commits(commits);
     }
+
+    if let Some(ref deploys) = deploys {
+        print_deploys(deploys);
+    }
+
     Ok(())
 }
@@ -1,8 +1,10 @@
+use std::io;
+
 use anyhow::Result;
 use chrono::Utc;
 use clap::{Arg, ArgAction, ArgMatches, Command};
 
-use crate::api::Api;
+use crate::api::{Api, ReleaseStatus};
 use crate::config::Config;
 use crate::utils::formatting::{HumanDuration, Table};
 
@@ -31,6 +33,29 @@ pub fn make_command(command: Command) -> Command {
                 .requires("raw")
                 .help("Delimiter for the --raw flag"),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .conflicts_with("raw")
+                .help("The output format of the list of releases."),
+        )
+        .arg(
+            Arg::new("status")
+                .long("status")
+                .value_name("STATUS")
+                .value_parser(["open", "archived"])
+                .help("Only list releases with this status. [defaults to open]"),
+        )
+        .arg(
+            Arg::new("show_archived")
+                .long("show-archived")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("status")
+                .help("List archived releases instead of open ones. Same as `--status archived`."),
+        )
         // Legacy flag that has no effect, left hidden for backward compatibility
         .arg(
             Arg::new("no_abbrev")
@@ -44,9 +69,20 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
     let project = config.get_project(matches).ok();
-    let releases = api
-        .authenticated()?
-        .list_releases(&config.get_org(matches)?, project.as_deref())?;
+    let status = if matches.get_flag("show_archived") {
+        Some(ReleaseStatus::Archived)
+    } else {
+        match matches.get_one::<String>("status").map(String::as_str) {
+            Some("archived") => Some(ReleaseStatus::Archived),
+            Some(_) => Some(ReleaseStatus::Open),
+            None => None,
+        }
+    };
+    let releases = api.authenticated()?.list_releases(
+        &config.get_org(matches)?,
+        project.as_deref(),
+        status,
+    )?;
 
     if matches.get_flag("raw") {
         let versions = releases
@@ -64,6 +100,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         return Ok(());
     }
 
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &releases)?;
+        println!();
+        return Ok(());
+    }
+
     let mut table = Table::new();
     let title_row = table.title_row();
     title_row.add("Released").add("Version");
@@ -5,9 +5,12 @@ use clap::{Arg, ArgAction, ArgMatches, Command};
 use crate::api::{Api, NewRelease};
 use crate::config::Config;
 use crate::utils::args::ArgExt;
+use crate::utils::logging::quiet_println;
+
+use super::set_commits::{commit_args, find_release_heads, set_release_heads, CommitOptions};
 
 pub fn make_command(command: Command) -> Command {
-    command
+    let command = command
         .about("Create a new release.")
         .allow_hyphen_values(true)
         .version_arg(false)
@@ -24,16 +27,27 @@ pub fn make_command(command: Command) -> Command {
                 .help("Immediately finalize the release. (sets it to released)"),
         )
         // Legacy flag that has no effect, left hidden for backward compatibility
-        .arg(Arg::new("ref").long("ref").hide(true))
+        .arg(Arg::new("ref").long("ref").hide(true));
+    commit_args(command)
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
-    let version = matches.get_one::<String>("version").unwrap();
+    let authenticated_api = api.authenticated()?;
+    let version = &config.get_release_version(matches)?;
+    let org = config.get_org(matches)?;
+
+    // Determine the commits before creating the release, so that a failure
+    // does not leave behind a release without commits.
+    let commit_options = CommitOptions::from_commit_args(matches);
+    let heads = commit_options
+        .as_ref()
+        .map(|options| find_release_heads(&authenticated_api, &org, options))
+        .transpose()?;
 
-    api.authenticated()?.new_release(
-        &config.get_org(matches)?,
+    authenticated_api.new_release(
+        &org,
         &NewRelease {
             version: version.to_owned(),
             projects: config.get_projects(matches)?,
@@ -47,6 +61,11 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         },
     )?;
 
-    println!("Created release {version}");
+    quiet_println!("Created release {version}");
+
+    if let (Some(options), Some(heads)) = (commit_options, heads) {
+        set_release_heads(&authenticated_api, &org, version, heads, &options)?;
+    }
+
     Ok(())
 }
@@ -4,6 +4,7 @@ use clap::{ArgMatches, Command};
 use crate::api::{Api, ReleaseStatus, UpdatedRelease};
 use crate::config::Config;
 use crate::utils::args::ArgExt;
+use crate::utils::logging::quiet_println;
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -15,19 +16,20 @@ pub fn make_command(command: Command) -> Command {
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
-    let version = matches.get_one::<String>("version").unwrap();
+    let version = &config.get_release_version(matches)?;
 
     let info_rv = api.authenticated()?.update_release(
         &config.get_org(matches)?,
         version,
         &UpdatedRelease {
-            projects: Some(vec![]),
-            version: Some(version.into()),
             status: Some(ReleaseStatus::Open),
             ..Default::default()
         },
     )?;
 
-    println!("Restored release {}", info_rv.version);
+    match info_rv.status {
+        Some(status) => quiet_println!("Restored release {} (status: {status})", info_rv.version),
+        None => quiet_println!("Restored release {}", info_rv.version),
+    }
     Ok(())
 }
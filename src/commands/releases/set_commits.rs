@@ -1,14 +1,22 @@
-use anyhow::{bail, Result};
+use std::fs;
+use std::io::{self, Read};
+
+use anyhow::{bail, Context, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::api::{Api, NewRelease, NoneReleaseInfo, OptionalReleaseInfo, UpdatedRelease};
+use crate::api::{
+    Api, AuthenticatedApi, NewRelease, NoneReleaseInfo, OptionalReleaseInfo, Ref, UpdatedRelease,
+};
 use crate::config::Config;
 use crate::utils::args::ArgExt;
-use crate::utils::formatting::Table;
+use crate::utils::formatting::{pluralize, Table};
+use crate::utils::logging::{is_quiet_mode, quiet_println};
 use crate::utils::vcs::{
-    find_heads, generate_patch_set, get_commits_from_git, get_repo_from_remote, CommitSpec,
+    find_heads, generate_patch_set, get_commits_from_git, get_remote_url, get_repo_from_remote,
+    merge_commit_refs, parse_commits_manifest, CommitSpec,
 };
 
 pub fn make_command(command: Command) -> Command {
@@ -32,7 +40,8 @@ pub fn make_command(command: Command) -> Command {
             .action(ArgAction::SetTrue)
             .help("When the flag is set and the previous release commit was not found in the repository, \
                     will create a release with the default commits count (or the one specified with `--initial-depth`) \
-                    instead of failing the command."))
+                    instead of failing the command.  Repositories given with `--commit` that are not \
+                    configured in Sentry are skipped with a warning instead of failing the command."))
         .arg(Arg::new("local")
             .conflicts_with_all(["auto", "clear", "commits"])
             .long("local")
@@ -64,6 +73,25 @@ pub fn make_command(command: Command) -> Command {
                     the current commit of the repository at the given PATH is \
                     assumed.  To override the revision `@REV` can be appended \
                     which will force the revision to a certain value."))
+        .arg(Arg::new("from_file")
+            .long("from-file")
+            .value_name("PATH")
+            .conflicts_with_all(["auto", "clear", "local"])
+            .help("Read the commits of the release from a JSON manifest file, or from \
+                    stdin if the path is `-`.  The manifest is an array of objects with \
+                    `repository`, `commit` and optionally `previousCommit` keys.  \
+                    Commits given with `--commit` take precedence over manifest entries \
+                    for the same repository."))
+        .arg(Arg::new("vcs_remote")
+            .long("vcs-remote")
+            .value_name("REMOTE")
+            .help("The name of the git remote used to discover the repository.  \
+                    Defaults to `origin`, or the `defaults.vcs_remote` setting."))
+        .arg(Arg::new("repo")
+            .long("repo")
+            .value_name("REPO")
+            .help("The repository name to use for commits read from the local git \
+                    tree, instead of deriving it from the URL of the git remote."))
         // Legacy flag that has no effect, left hidden for backward compatibility
         .arg(Arg::new("ignore-empty")
             .long("ignore-empty")
@@ -81,47 +109,179 @@ fn strip_sha(sha: &str) -> &str {
         sha
     }
 }
-pub fn execute(matches: &ArgMatches) -> Result<()> {
-    let config = Config::current();
-    let api = Api::current();
-    let authenticated_api = api.authenticated()?;
-    let version = matches.get_one::<String>("version").unwrap();
-    let org = config.get_org(matches)?;
-    let repos = authenticated_api.list_organization_repos(&org)?;
-    let mut commit_specs = vec![];
+/// Options controlling which commits are associated with a release.
+pub(super) struct CommitOptions<'a> {
+    pub auto: bool,
+    pub clear: bool,
+    pub local: bool,
+    pub ignore_missing: bool,
+    pub initial_depth: usize,
+    pub commits: Vec<&'a str>,
+    pub manifest: Vec<Ref>,
+    pub vcs_remote: String,
+    pub repo: Option<&'a str>,
+}
+
+/// Adds the `--set-commits-auto`, `--commit` and `--ignore-missing` arguments
+/// to commands which associate commits as part of another release operation.
+pub(super) fn commit_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("set_commits_auto")
+                .long("set-commits-auto")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("commits")
+                .help(
+                    "Also set the commits of the release in the same step, \
+                    like `set-commits --auto`.",
+                ),
+        )
+        .arg(
+            Arg::new("commits")
+                .long("commit")
+                .short('c')
+                .value_name("SPEC")
+                .action(ArgAction::Append)
+                .help(
+                    "Also set the given commit of the release in the same step. \
+                    The value is a commit spec like for `set-commits --commit`.",
+                ),
+        )
+        .arg(
+            Arg::new("ignore-missing")
+                .long("ignore-missing")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "When setting commits and the previous release commit was not found \
+                    in the repository, use the default commits count instead of failing \
+                    the command.  Repositories given with `--commit` that are not configured \
+                    in Sentry are skipped with a warning.",
+                ),
+        )
+}
+
+impl<'a> CommitOptions<'a> {
+    /// Returns the options requested with the arguments added by `commit_args`,
+    /// or `None` if commits should not be set.
+    pub fn from_commit_args(matches: &'a ArgMatches) -> Option<Self> {
+        let commits: Vec<_> = matches
+            .get_many::<String>("commits")
+            .map(|commits| commits.map(String::as_str).collect())
+            .unwrap_or_default();
+        let auto = matches.get_flag("set_commits_auto");
+        if !auto && commits.is_empty() {
+            return None;
+        }
+
+        Some(CommitOptions {
+            auto,
+            clear: false,
+            local: false,
+            ignore_missing: matches.get_flag("ignore-missing"),
+            initial_depth: 20,
+            commits,
+            manifest: vec![],
+            vcs_remote: Config::current().get_cached_vcs_remote(),
+            repo: None,
+        })
+    }
+}
+
+/// The commits to associate with a release, as determined by `find_release_heads`.
+///
+/// If no heads could be determined through a repository integration, commits
+/// are read from the local git tree instead.
+pub(super) struct ReleaseHeads {
+    heads: Option<Vec<Ref>>,
+    /// Repositories given with `--commit` that are not configured in Sentry.
+    skipped_repos: Vec<String>,
+}
+
+/// Determines the heads of all repositories for a release.
+///
+/// This does not modify the release, so that it can be called before a
+/// release is created or updated.
+pub(super) fn find_release_heads(
+    api: &AuthenticatedApi,
+    org: &str,
+    options: &CommitOptions,
+) -> Result<ReleaseHeads> {
+    let repos = api.list_organization_repos(org)?;
+    let mut skipped_repos = vec![];
+
+    for r in &options.manifest {
+        if !repos
+            .iter()
+            .any(|repo| repo.name.to_lowercase() == r.repo.to_lowercase())
+        {
+            bail!("Unknown repo '{}' in commits manifest", r.repo);
+        }
+    }
 
     let heads = if repos.is_empty() {
         None
-    } else if matches.get_flag("auto") {
-        let commits = find_heads(None, &repos, Some(config.get_cached_vcs_remote()))?;
+    } else if options.auto {
+        let commits = find_heads(None, &repos, Some(options.vcs_remote.clone()))?;
         if commits.is_empty() {
             None
         } else {
             Some(commits)
         }
-    } else if matches.get_flag("clear") {
+    } else if options.clear {
         Some(vec![])
-    } else if matches.get_flag("local") {
+    } else if options.local {
         None
     } else {
-        if let Some(commits) = matches.get_many::<String>("commits") {
-            for spec in commits {
-                let commit_spec = CommitSpec::parse(spec)?;
-                if repos
+        // Validate all repositories before anything is modified
+        let (commit_specs, unknown_specs): (Vec<_>, Vec<_>) = options
+            .commits
+            .iter()
+            .map(|spec| CommitSpec::parse(spec))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .partition(|spec| {
+                repos
                     .iter()
-                    .any(|r| r.name.to_lowercase() == commit_spec.repo.to_lowercase())
-                {
-                    commit_specs.push(commit_spec);
-                } else {
-                    bail!("Unknown repo '{}'", commit_spec.repo);
-                }
+                    .any(|r| r.name.to_lowercase() == spec.repo.to_lowercase())
+            });
+        skipped_repos = unknown_specs.into_iter().map(|spec| spec.repo).collect();
+
+        if !skipped_repos.is_empty() {
+            let names = skipped_repos
+                .iter()
+                .map(|repo| format!("'{repo}'"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if !options.ignore_missing {
+                bail!(
+                    "Unknown {} {names}. Configure {} in Sentry, or pass --ignore-missing \
+                     to set the commits of the other repositories.",
+                    pluralize(skipped_repos.len(), "repository", "repositories"),
+                    pluralize(skipped_repos.len(), "it", "them")
+                );
+            }
+            eprintln!(
+                "{}",
+                style(format!(
+                    "warning: Skipping unknown {} {names}",
+                    pluralize(skipped_repos.len(), "repository", "repositories")
+                ))
+                .red()
+            );
+            if commit_specs.is_empty() && options.manifest.is_empty() {
+                return Ok(ReleaseHeads {
+                    heads: None,
+                    skipped_repos,
+                });
             }
         }
-        let commits = find_heads(
-            Some(commit_specs),
-            &repos,
-            Some(config.get_cached_vcs_remote()),
-        )?;
+
+        let commits = if commit_specs.is_empty() && !options.manifest.is_empty() {
+            vec![]
+        } else {
+            find_heads(Some(commit_specs), &repos, Some(options.vcs_remote.clone()))?
+        };
+        let commits = merge_commit_refs(options.manifest.clone(), commits);
         if commits.is_empty() {
             None
         } else {
@@ -129,22 +289,41 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     };
 
-    // make sure the release exists if projects are given
-    if let Ok(projects) = config.get_projects(matches) {
-        authenticated_api.new_release(
-            &org,
-            &NewRelease {
-                version: version.into(),
-                projects,
-                ..Default::default()
-            },
-        )?;
+    Ok(ReleaseHeads {
+        heads,
+        skipped_repos,
+    })
+}
+
+/// Associates the previously determined heads with an existing release.
+pub(super) fn set_release_heads(
+    api: &AuthenticatedApi,
+    org: &str,
+    version: &str,
+    heads: ReleaseHeads,
+    options: &CommitOptions,
+) -> Result<()> {
+    let ReleaseHeads {
+        heads,
+        skipped_repos,
+    } = heads;
+
+    if heads.is_none() && !skipped_repos.is_empty() {
+        // All repositories given with `--commit` were skipped, so there is
+        // nothing to associate. Reading the local git tree instead would
+        // associate commits that were not asked for.
+        quiet_println!(
+            "No commits set for release {version}, skipped {} unknown {}.",
+            skipped_repos.len(),
+            pluralize(skipped_repos.len(), "repository", "repositories")
+        );
+        return Ok(());
     }
 
     if let Some(heads) = heads {
         if heads.is_empty() {
-            println!("Clearing commits for release.");
-        } else {
+            quiet_println!("Clearing commits for release.");
+        } else if !is_quiet_mode() {
             let mut table = Table::new();
             table.title_row().add("Repository").add("Revision");
             for commit in &heads {
@@ -162,20 +341,28 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             }
             table.print();
         }
-        authenticated_api.set_release_refs(&org, version, heads)?;
-    } else {
-        let default_count = matches
-            .get_one::<usize>("initial-depth")
-            .copied()
-            .unwrap_or(20);
+        let count = heads.len();
+        api.set_release_refs(org, version, heads)?;
 
-        if matches.get_flag("auto") {
-            println!("Could not determine any commits to be associated with a repo-based integration. Proceeding to find commits from local git tree.");
+        if !options.commits.is_empty() {
+            let skipped = match skipped_repos.len() {
+                0 => String::new(),
+                n => format!(
+                    ", skipped {n} unknown {}",
+                    pluralize(n, "repository", "repositories")
+                ),
+            };
+            quiet_println!(
+                "Set commits of {count} {} for release {version}{skipped}.",
+                pluralize(count, "repository", "repositories")
+            );
+        }
+    } else {
+        if options.auto {
+            quiet_println!("Could not determine any commits to be associated with a repo-based integration. Proceeding to find commits from local git tree.");
         }
         // Get the commit of the most recent release.
-        let prev_commit = match authenticated_api
-            .get_previous_release_with_commits(&org, version)?
-        {
+        let prev_commit = match api.get_previous_release_with_commits(org, version)? {
             OptionalReleaseInfo::Some(prev) => prev.last_commit.map(|c| c.id).unwrap_or_default(),
             OptionalReleaseInfo::None(NoneReleaseInfo {}) => String::new(),
         };
@@ -184,24 +371,29 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         let repo = git2::Repository::open_from_env()?;
 
         // Parse the git url.
-        let remote = config.get_cached_vcs_remote();
-        let parsed = get_repo_from_remote(&remote);
-        let ignore_missing = matches.get_flag("ignore-missing");
+        let parsed = match options.repo {
+            Some(repo) => repo.to_string(),
+            None => get_repo_from_remote(&get_remote_url(&repo, &options.vcs_remote)?)?,
+        };
         // Fetch all the commits upto the `prev_commit` or return the default (20).
         // Will return a tuple of Vec<GitCommits> and the `prev_commit` if it exists in the git tree.
-        let (commit_log, prev_commit) =
-            get_commits_from_git(&repo, &prev_commit, default_count, ignore_missing)?;
+        let (commit_log, prev_commit) = get_commits_from_git(
+            &repo,
+            &prev_commit,
+            options.initial_depth,
+            options.ignore_missing,
+        )?;
 
         // Calculate the diff for each commit in the Vec<GitCommit>.
         let commits = generate_patch_set(&repo, commit_log, prev_commit, &parsed)?;
 
         if commits.is_empty() {
-            println!("No commits found. Leaving release alone. If you believe there should be some, change commits range or initial depth and try again.");
+            quiet_println!("No commits found. Leaving release alone. If you believe there should be some, change commits range or initial depth and try again.");
             return Ok(());
         }
 
-        authenticated_api.update_release(
-            &config.get_org(matches)?,
+        api.update_release(
+            org,
             version,
             &UpdatedRelease {
                 commits: Some(commits),
@@ -209,8 +401,70 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             },
         )?;
 
-        println!("Success! Set commits for release {version}");
+        quiet_println!("Success! Set commits for release {version}");
     }
 
     Ok(())
 }
+
+/// Reads a commits manifest from the given path, or from stdin if the path is `-`.
+fn read_commits_manifest(path: &str) -> Result<Vec<Ref>> {
+    let contents = if path == "-" {
+        let mut contents = String::new();
+        io::stdin()
+            .read_to_string(&mut contents)
+            .context("Failed to read commits manifest from stdin")?;
+        contents
+    } else {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read commits manifest {path}"))?
+    };
+    parse_commits_manifest(&contents)
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+    let version = &config.get_release_version(matches)?;
+    let org = config.get_org(matches)?;
+    let options = CommitOptions {
+        auto: matches.get_flag("auto"),
+        clear: matches.get_flag("clear"),
+        local: matches.get_flag("local"),
+        ignore_missing: matches.get_flag("ignore-missing"),
+        initial_depth: matches
+            .get_one::<usize>("initial-depth")
+            .copied()
+            .unwrap_or(20),
+        commits: matches
+            .get_many::<String>("commits")
+            .map(|commits| commits.map(String::as_str).collect())
+            .unwrap_or_default(),
+        manifest: match matches.get_one::<String>("from_file") {
+            Some(path) => read_commits_manifest(path)?,
+            None => vec![],
+        },
+        vcs_remote: matches
+            .get_one::<String>("vcs_remote")
+            .cloned()
+            .unwrap_or_else(|| config.get_cached_vcs_remote()),
+        repo: matches.get_one::<String>("repo").map(String::as_str),
+    };
+
+    let heads = find_release_heads(&authenticated_api, &org, &options)?;
+
+    // make sure the release exists if projects are given
+    if let Ok(projects) = config.get_projects(matches) {
+        authenticated_api.new_release(
+            &org,
+            &NewRelease {
+                version: version.into(),
+                projects,
+                ..Default::default()
+            },
+        )?;
+    }
+
+    set_release_heads(&authenticated_api, &org, version, heads, &options)
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+
+use crate::api::{Api, NewRepo};
+use crate::config::Config;
+use crate::utils::logging::quiet_println;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Add a repository to your organization.")
+        .arg(
+            Arg::new("provider")
+                .long("provider")
+                .value_name("PROVIDER")
+                .required(true)
+                .help(
+                    "The provider of the repository, e.g. `integrations:github`.{n}\
+                     For integration providers, the repository is linked to the \
+                     installed integration.",
+                ),
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("NAME")
+                .required(true)
+                .help("The name of the repository, e.g. `getsentry/sentry-cli`."),
+        )
+        .arg(
+            Arg::new("url")
+                .long("url")
+                .value_name("URL")
+                .help("The URL of the repository."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let name = matches.get_one::<String>("name").unwrap();
+
+    let repo = Api::current().authenticated()?.create_organization_repo(
+        &org,
+        &NewRepo {
+            provider: matches.get_one::<String>("provider").unwrap(),
+            name,
+            url: matches.get_one::<String>("url").map(String::as_str),
+        },
+    )?;
+
+    match repo {
+        Some(repo) => quiet_println!(
+            "Added repository {} ({})",
+            style(&repo.name).cyan(),
+            repo.id
+        ),
+        None => quiet_println!(
+            "Repository {} already exists in organization {}",
+            style(name).cyan(),
+            org
+        ),
+    }
+
+    Ok(())
+}
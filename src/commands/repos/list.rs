@@ -1,12 +1,23 @@
+use std::io;
+
 use anyhow::Result;
-use clap::{ArgMatches, Command};
+use clap::{Arg, ArgMatches, Command};
 
 use crate::api::Api;
 use crate::config::Config;
 use crate::utils::formatting::Table;
 
 pub fn make_command(command: Command) -> Command {
-    command.about("List all repositories in your organization.")
+    command
+        .about("List all repositories in your organization.")
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of repositories."),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -16,12 +27,24 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let org = config.get_org(matches)?;
     let repos = api.authenticated()?.list_organization_repos(&org)?;
 
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &repos)?;
+        println!();
+        return Ok(());
+    }
+
     let mut table = Table::new();
-    table.title_row().add("Name").add("Provider").add("URL");
+    table
+        .title_row()
+        .add("ID")
+        .add("Name")
+        .add("Provider")
+        .add("URL");
 
     for repo in &repos {
         table
             .add_row()
+            .add(&repo.id)
             .add(&repo.name)
             .add(&repo.provider.name)
             .add(repo.url.as_deref().unwrap_or("-"));
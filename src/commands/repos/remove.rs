@@ -0,0 +1,33 @@
+use anyhow::{bail, Result};
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::logging::quiet_println;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Remove a repository from your organization.")
+        .arg(
+            Arg::new("id")
+                .value_name("ID")
+                .required(true)
+                .help("The ID of the repository, as shown by `repos list`."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let id = matches.get_one::<String>("id").unwrap();
+
+    if !Api::current()
+        .authenticated()?
+        .delete_organization_repo(&org, id)?
+    {
+        bail!("Repository {} not found in organization {}", id, org);
+    }
+
+    quiet_println!("Removed repository {id}");
+    Ok(())
+}
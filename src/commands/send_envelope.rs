@@ -1,12 +1,18 @@
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{bail, format_err, Context, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use glob::{glob_with, MatchOptions};
-use log::warn;
+use log::{debug, warn};
 use sentry::Envelope;
+use serde_json::Value;
 
-use crate::api::envelopes_api::EnvelopesApi;
+use crate::api::envelopes_api::{serialize_envelope, EnvelopesApi};
+use crate::commands::send_event;
+use crate::utils::envelope_queue::{enqueue_file, send_or_enqueue};
+use crate::utils::logging::quiet_println;
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -24,16 +30,20 @@ pub fn make_command(command: Command) -> Command {
                 .required(true)
                 .help("The path or glob to the file(s) in envelope format to send as envelope(s)."),
         )
-        .arg(
-            Arg::new("raw")
-                .long("raw")
-                .action(ArgAction::SetTrue)
-                .help("Send envelopes without attempting to parse their contents."),
-        )
+        .arg(Arg::new("raw").long("raw").action(ArgAction::SetTrue).help(
+            "Send envelopes without parsing their items. Only the framing \
+                     of the envelope, such as the item length headers, is validated \
+                     and the file is streamed to Sentry, which allows sending large \
+                     envelopes with attachments.",
+        ))
+        .arg(send_event::attachment_arg().conflicts_with("raw"))
+        .arg(send_event::queue_dir_arg())
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let raw = matches.get_flag("raw");
+    let queue_dir = matches.get_one::<String>("queue_dir").map(Path::new);
+    let attachments = send_event::read_attachments(matches)?;
 
     let path = matches.get_one::<String>("path").unwrap();
 
@@ -49,14 +59,142 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     for path in collected_paths {
         let p = path.as_path();
-        let envelope: Envelope = if raw {
-            Envelope::from_path_raw(p)
+        let queued = if raw {
+            send_raw_envelope(p, queue_dir)?
         } else {
-            Envelope::from_path(p)
-        }?;
-        EnvelopesApi::try_new()?.send_envelope(envelope)?;
-        println!("Envelope from file {} dispatched", p.display());
+            let mut envelope = Envelope::from_path(p)?;
+            for attachment in &attachments {
+                envelope.add_item(attachment.clone());
+            }
+            send_or_enqueue(serialize_envelope(envelope)?, queue_dir)?
+        };
+        match queued {
+            Some(queued) => quiet_println!(
+                "Envelope from file {} queued in {}",
+                p.display(),
+                queued.display()
+            ),
+            None => quiet_println!("Envelope from file {} dispatched", p.display()),
+        }
     }
 
     Ok(())
 }
+
+/// Validates the framing of the envelope in the given file and streams it to
+/// Sentry. Item payloads are never read into memory.
+///
+/// If a queue directory is given and Sentry cannot be reached, the file is
+/// copied into the queue instead, and the path of the copy is returned.
+fn send_raw_envelope(path: &Path, queue_dir: Option<&Path>) -> Result<Option<PathBuf>> {
+    let file = File::open(path)
+        .with_context(|| format!("Could not open envelope file {}", path.display()))?;
+    let len = file.metadata()?.len();
+    let items = validate_envelope(&file, len)
+        .with_context(|| format!("Invalid envelope in file {}", path.display()))?;
+
+    debug!("Streaming envelope with {} items ({} bytes)", items, len);
+    match EnvelopesApi::try_new()?.send_envelope_file(file) {
+        Ok(_) => Ok(None),
+        Err(err) if err.is_network_error() && queue_dir.is_some() => {
+            warn!(
+                "Could not reach Sentry, queuing the envelope: {:#}",
+                anyhow::Error::from(err)
+            );
+            enqueue_file(queue_dir.unwrap(), path).map(Some)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Checks the envelope header and the headers of all items, skipping over
+/// the item payloads. Returns the number of items in the envelope.
+///
+/// Items with a `length` header must be followed by exactly that many bytes
+/// and a newline or the end of the file. Items without it end at the next
+/// newline.
+fn validate_envelope(file: &File, len: u64) -> Result<usize> {
+    let mut reader = BufReader::new(file);
+
+    let header = read_line(&mut reader)?.ok_or_else(|| format_err!("The envelope is empty"))?;
+    if !serde_json::from_slice::<Value>(&header)
+        .context("Could not parse the envelope header")?
+        .is_object()
+    {
+        bail!("The envelope header is not a JSON object");
+    }
+
+    let mut items = 0;
+    while let Some(line) = read_line(&mut reader)? {
+        // Tolerate empty lines, e.g. a trailing newline at the end of the file
+        if line.is_empty() {
+            continue;
+        }
+
+        items += 1;
+        let header: Value = serde_json::from_slice(&line)
+            .with_context(|| format!("Could not parse the header of item {items}"))?;
+        if !header.get("type").map_or(false, Value::is_string) {
+            bail!("Item {items} has no type");
+        }
+
+        match header.get("length") {
+            None | Some(Value::Null) => skip_line(&mut reader)?,
+            Some(length) => {
+                let Some(length) = length.as_u64() else {
+                    bail!("Item {items} has an invalid length header: {length}");
+                };
+                let remaining = len - reader.stream_position()?;
+                if length > remaining {
+                    bail!(
+                        "Item {items} has a length of {length} bytes, \
+                         but only {remaining} bytes remain in the file"
+                    );
+                }
+                reader.seek(SeekFrom::Current(length as i64))?;
+                match reader.fill_buf()?.first() {
+                    None => {}
+                    Some(b'\n') => reader.consume(1),
+                    Some(_) => bail!("Item {items} is longer than its length of {length} bytes"),
+                }
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// Reads a line without the trailing newline, or `None` at the end of the file.
+fn read_line(reader: &mut impl BufRead) -> Result<Option<Vec<u8>>> {
+    let mut line = vec![];
+    if reader.read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+    if line.last() == Some(&b'\n') {
+        line.pop();
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Ok(Some(line))
+}
+
+/// Skips to the start of the next line without buffering the skipped bytes.
+fn skip_line(reader: &mut impl BufRead) -> Result<()> {
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(());
+        }
+        match buf.iter().position(|&b| b == b'\n') {
+            Some(pos) => {
+                reader.consume(pos + 1);
+                return Ok(());
+            }
+            None => {
+                let consumed = buf.len();
+                reader.consume(consumed);
+            }
+        }
+    }
+}
@@ -1,24 +1,31 @@
 use std::borrow::Cow;
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, Read};
+use std::iter;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-use anyhow::{anyhow, format_err, Result};
+use anyhow::{anyhow, bail, format_err, Context, Result};
 use chrono::{DateTime, Utc};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use glob::{glob_with, MatchOptions};
 use itertools::Itertools;
 use log::warn;
-use sentry::protocol::{Event, Level, LogEntry, User};
+use sentry::protocol::{Attachment, Event, Level, LogEntry, User};
 use sentry::types::Uuid;
 use sentry::{apply_defaults, Client, ClientOptions, Envelope};
 use serde_json::Value;
 use username::get_user_name;
 
-use crate::api::envelopes_api::EnvelopesApi;
+use crate::api::envelopes_api::{serialize_envelope, EnvelopesApi};
+use crate::api::Api;
+use crate::config::Config;
 use crate::constants::USER_AGENT;
 use crate::utils::args::{get_timestamp, validate_distribution};
-use crate::utils::event::{attach_logfile, get_sdk_info};
+use crate::utils::envelope_queue::send_or_enqueue;
+use crate::utils::event::{attach_logfile, get_sdk_info, read_attachment, AttachmentTooLarge};
+use crate::utils::logging::is_quiet_mode;
 use crate::utils::releases::detect_release_name;
 
 pub fn make_command(command: Command) -> Command {
@@ -42,6 +49,15 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Send events using an envelope without attempting to parse their contents."),
         )
+        .arg(
+            Arg::new("from_json")
+                .value_name("PATH")
+                .long("from-json")
+                .conflicts_with_all(["path", "raw"])
+                .help("Send a complete event payload read from the given JSON file, or from stdin \
+                       if '-' is given. Only --release and --dist are applied on top of it, \
+                       overriding the values in the payload."),
+        )
         .arg(
             Arg::new("level")
                 .value_name("LEVEL")
@@ -157,23 +173,199 @@ pub fn make_command(command: Command) -> Command {
                     eg. \"INFO: Something broke\" will be parsed as a breadcrumb \
                     \"{\"level\": \"info\", \"message\": \"Something broke\"}\"")
         )
+        .arg(attachment_arg())
+        .arg(
+            Arg::new("minidump")
+                .value_name("PATH")
+                .long("minidump")
+                .conflicts_with_all(["path", "raw", "from_json"])
+                .help(
+                    "Send the event with the given minidump to the minidump endpoint. \
+                     Attachments exceeding the maximum attachment size are dropped \
+                     with a warning instead of failing the upload. Minidump uploads are \
+                     not envelopes and cannot be queued with --queue-dir.",
+                ),
+        )
+        .arg(queue_dir_arg().conflicts_with("minidump"))
 }
 
 pub(super) fn send_raw_event(event: Event<'static>) -> Result<Uuid> {
+    send_event_with_attachments(event, &[], None).map(|(id, _)| id)
+}
+
+/// Applies the client defaults to an event before it is sent.
+fn prepare_event(event: Event<'static>) -> Result<Event<'static>> {
     let client = Client::from_config(apply_defaults(ClientOptions {
         user_agent: USER_AGENT.into(),
         ..Default::default()
     }));
-    let event = client
+    client
         .prepare_event(event, None)
-        .ok_or(anyhow!("Event dropped during preparation"))?;
+        .ok_or(anyhow!("Event dropped during preparation"))
+}
+
+/// Sends an event in an envelope, along with the given attachments.
+///
+/// If a queue directory is given and Sentry cannot be reached, the envelope is
+/// queued there instead, and its path is returned along with the event ID.
+fn send_event_with_attachments(
+    event: Event<'static>,
+    attachments: &[Attachment],
+    queue_dir: Option<&Path>,
+) -> Result<(Uuid, Option<PathBuf>)> {
+    let event = prepare_event(event)?;
+    let event_id = event.event_id;
+    let mut envelope = Envelope::from(event);
+    for attachment in attachments {
+        envelope.add_item(attachment.clone());
+    }
+    let queued = send_or_enqueue(serialize_envelope(envelope)?, queue_dir)?;
+    Ok((event_id, queued))
+}
+
+/// Reads the attachments given with `--attachment`.
+pub(super) fn read_attachments(matches: &ArgMatches) -> Result<Vec<Attachment>> {
+    let Some(specs) = matches.get_many::<String>("attachments") else {
+        return Ok(vec![]);
+    };
+    let max_size = max_attachment_size();
+    specs.map(|spec| read_attachment(spec, max_size)).collect()
+}
+
+/// Returns the maximum size of an attachment.
+///
+/// If an auth token and organization are configured, the maximum file size the
+/// server advertises for chunk uploads is used. Otherwise, or if the server does
+/// not advertise one, the `event.max_attachment_size` config value applies.
+fn max_attachment_size() -> u64 {
+    let config = Config::current();
+    let server_limit = || {
+        let (org, _) = config.get_org_and_project_defaults();
+        let api = Api::current();
+        let options = api
+            .authenticated()
+            .ok()?
+            .get_chunk_upload_options(&org?)
+            .ok()??;
+        options.max_file_size()
+    };
+    server_limit().unwrap_or_else(|| config.get_max_attachment_size())
+}
+
+/// Sends an event along with a minidump and the given attachments.
+fn send_event_with_minidump(
+    event: Event<'static>,
+    minidump: &Path,
+    attachments: &[Attachment],
+) -> Result<Uuid> {
+    if !minidump.is_file() {
+        bail!("Could not open minidump {}", minidump.display());
+    }
+    let event = prepare_event(event)?;
     let event_id = event.event_id;
-    EnvelopesApi::try_new()?.send_envelope(event)?;
+    let payload = serde_json::to_vec(&event)?;
+    EnvelopesApi::try_new()?.send_minidump(minidump, &payload, attachments)?;
     Ok(event_id)
 }
 
+/// Reads the attachments given with `--attachment`, dropping the ones that
+/// exceed the maximum attachment size with a warning.
+fn read_attachments_lenient(matches: &ArgMatches) -> Result<Vec<Attachment>> {
+    let Some(specs) = matches.get_many::<String>("attachments") else {
+        return Ok(vec![]);
+    };
+    let max_size = max_attachment_size();
+    let mut attachments = vec![];
+    for spec in specs {
+        match read_attachment(spec, max_size) {
+            Ok(attachment) => attachments.push(attachment),
+            Err(err) if err.is::<AttachmentTooLarge>() => warn!("{err}, dropping it"),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(attachments)
+}
+
+/// Returns the repeatable `--attachment` argument, which `send-envelope` shares.
+pub(super) fn attachment_arg() -> Arg {
+    Arg::new("attachments")
+        .value_name("PATH[:CONTENT_TYPE[:ATTACHMENT_TYPE]]")
+        .long("attachment")
+        .action(ArgAction::Append)
+        .help(
+            "Add a file as attachment, optionally with its content type and attachment \
+             type, e.g. `core.dmp:application/octet-stream:event.minidump`. \
+             Can be given multiple times.",
+        )
+}
+
+/// Returns the `--queue-dir` argument, which `send-envelope` shares.
+pub(super) fn queue_dir_arg() -> Arg {
+    Arg::new("queue_dir")
+        .value_name("PATH")
+        .long("queue-dir")
+        .help(
+            "Store the envelope in this directory instead of failing if Sentry cannot be \
+             reached. Queued envelopes can be sent later with `sentry-cli send-queued`.",
+        )
+}
+
+/// Parses a JSON event payload.
+///
+/// If the payload does not match the event schema, the error names the
+/// top-level field that failed to parse.
+fn parse_event(payload: &[u8]) -> Result<Event<'static>> {
+    let value: Value =
+        serde_json::from_slice(payload).context("Event payload is not valid JSON")?;
+
+    match serde_json::from_value(value.clone()) {
+        Ok(event) => Ok(event),
+        Err(err) => {
+            if let Value::Object(fields) = value {
+                for (key, field) in fields {
+                    let single = Value::Object(iter::once((key.clone(), field)).collect());
+                    if let Err(err) = serde_json::from_value::<Event>(single) {
+                        bail!("Invalid event payload: field `{}`: {}", key, err);
+                    }
+                }
+            }
+            bail!("Invalid event payload: {}", err);
+        }
+    }
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let raw = matches.get_flag("raw");
+    let minidump = matches.get_one::<String>("minidump").map(Path::new);
+    let queue_dir = matches.get_one::<String>("queue_dir").map(Path::new);
+    // Read attachments upfront, so that a missing or oversized file fails before anything is sent.
+    let attachments = if minidump.is_some() {
+        read_attachments_lenient(matches)?
+    } else {
+        read_attachments(matches)?
+    };
+
+    if let Some(path) = matches.get_one::<String>("from_json") {
+        let payload = if path == "-" {
+            let mut payload = Vec::new();
+            io::stdin().read_to_end(&mut payload)?;
+            payload
+        } else {
+            fs::read(path).with_context(|| format!("Failed to read event from {path}"))?
+        };
+
+        let mut event = parse_event(&payload)?;
+        if let Some(release) = matches.get_one::<String>("release") {
+            event.release = Some(Cow::Owned(release.clone()));
+        }
+        if let Some(dist) = matches.get_one::<String>("dist") {
+            event.dist = Some(Cow::Owned(dist.clone()));
+        }
+
+        let (id, queued) = send_event_with_attachments(event, &attachments, queue_dir)?;
+        print_event_id(id, queued.as_deref());
+        return Ok(());
+    }
 
     if let Some(path) = matches.get_one::<String>("path") {
         let collected_paths: Vec<PathBuf> = glob_with(path, MatchOptions::new())
@@ -189,7 +381,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         for path in collected_paths {
             let raw_event = std::fs::read(&path)?;
 
-            let id = if raw {
+            let (id, queued) = if raw {
                 use std::io::Write;
 
                 // Its a bit unfortunate that we still need to parse the whole JSON,
@@ -205,15 +397,32 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 writeln!(buf, r#"{{"event_id":"{id}"}}"#)?;
                 writeln!(buf, r#"{{"type":"event","length":{}}}"#, raw_event.len())?;
                 buf.extend(raw_event);
+                for attachment in &attachments {
+                    buf.push(b'\n');
+                    attachment.to_writer(&mut buf)?;
+                }
                 let envelope = Envelope::from_bytes_raw(buf)?;
-                EnvelopesApi::try_new()?.send_envelope(envelope)?;
-                id
+                (
+                    id,
+                    send_or_enqueue(serialize_envelope(envelope)?, queue_dir)?,
+                )
             } else {
                 let event: Event = serde_json::from_slice(&raw_event)?;
-                send_raw_event(event)?
+                send_event_with_attachments(event, &attachments, queue_dir)?
             };
 
-            println!("Event from file {} dispatched: {}", path.display(), id);
+            if is_quiet_mode() {
+                println!("{id}");
+            } else if let Some(queued) = queued {
+                println!(
+                    "Event from file {} queued in {}: {}",
+                    path.display(),
+                    queued.display(),
+                    id
+                );
+            } else {
+                println!("Event from file {} dispatched: {}", path.display(), id);
+            }
         }
 
         return Ok(());
@@ -325,8 +534,26 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         attach_logfile(&mut event, logfile, matches.get_flag("with_categories"))?;
     }
 
-    let id = send_raw_event(event)?;
-    println!("Event dispatched.\nEvent id: {id}");
+    let (id, queued) = match minidump {
+        Some(minidump) => (
+            send_event_with_minidump(event, minidump, &attachments)?,
+            None,
+        ),
+        None => send_event_with_attachments(event, &attachments, queue_dir)?,
+    };
+    print_event_id(id, queued.as_deref());
 
     Ok(())
 }
+
+/// Prints the ID of a dispatched or queued event, which is the only output in
+/// quiet mode.
+fn print_event_id(id: Uuid, queued: Option<&Path>) {
+    if is_quiet_mode() {
+        println!("{id}");
+    } else if let Some(queued) = queued {
+        println!("Event queued in {}.\nEvent id: {id}", queued.display());
+    } else {
+        println!("Event dispatched.\nEvent id: {id}");
+    }
+}
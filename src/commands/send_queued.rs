@@ -0,0 +1,119 @@
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+use log::warn;
+
+use crate::api::envelopes_api::EnvelopesApi;
+use crate::utils::envelope_queue::queued_envelopes;
+use crate::utils::formatting::pluralize;
+use crate::utils::logging::quiet_println;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Send the envelopes queued with --queue-dir to Sentry.")
+        .long_about(
+            "Send the envelopes queued with --queue-dir to Sentry.{n}{n}\
+             Envelopes accepted by Sentry are deleted from the queue directory, and envelopes \
+             that fail to send are kept for the next attempt. If Sentry cannot be reached or \
+             rate limits the envelopes, the remaining envelopes are kept without sending them.",
+        )
+        .arg(
+            Arg::new("dir")
+                .value_name("DIR")
+                .required(true)
+                .help("The queue directory given with --queue-dir."),
+        )
+        .arg(
+            Arg::new("max_age")
+                .long("max-age")
+                .value_name("SECS")
+                .value_parser(clap::value_parser!(u64))
+                .help("Drop envelopes queued more than this many seconds ago instead of sending them."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let dir = Path::new(matches.get_one::<String>("dir").unwrap());
+    let max_age = matches
+        .get_one::<u64>("max_age")
+        .copied()
+        .map(Duration::from_secs);
+
+    let envelopes = queued_envelopes(dir)?;
+    let api = EnvelopesApi::try_new()?;
+
+    let mut sent = 0;
+    let mut dropped = 0;
+    let mut kept = 0;
+    for (index, envelope) in envelopes.iter().enumerate() {
+        let path = &envelope.path;
+        let age = envelope.age();
+        if max_age.map_or(false, |max_age| age > max_age) {
+            warn!(
+                "Dropping envelope {}, which was queued {}s ago",
+                path.display(),
+                age.as_secs()
+            );
+            fs::remove_file(path)?;
+            dropped += 1;
+            continue;
+        }
+
+        let file = File::open(path)
+            .with_context(|| format!("Could not open envelope file {}", path.display()))?;
+        match api.send_envelope_file(file) {
+            Ok(_) => {
+                fs::remove_file(path)?;
+                quiet_println!("Envelope {} dispatched", path.display());
+                sent += 1;
+            }
+            Err(err) if err.is_network_error() || err.is_rate_limited() => {
+                // Sending the other envelopes would fail the same way
+                kept += envelopes.len() - index;
+                warn!(
+                    "Could not send envelope {}, keeping the remaining envelopes: {:#}",
+                    path.display(),
+                    anyhow::Error::from(err)
+                );
+                break;
+            }
+            Err(err) => {
+                warn!(
+                    "Could not send envelope {}, keeping it: {:#}",
+                    path.display(),
+                    anyhow::Error::from(err)
+                );
+                kept += 1;
+            }
+        }
+    }
+
+    quiet_println!(
+        "{} Sent {} queued {}",
+        style(">").dim(),
+        sent,
+        pluralize(sent, "envelope", "envelopes")
+    );
+    if kept > 0 {
+        quiet_println!(
+            "{} Kept {} {} in the queue",
+            style(">").dim(),
+            kept,
+            pluralize(kept, "envelope", "envelopes")
+        );
+    }
+    if dropped > 0 {
+        quiet_println!(
+            "{} Dropped {} expired {}",
+            style(">").dim(),
+            dropped,
+            pluralize(dropped, "envelope", "envelopes")
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use clap::{Arg, ArgMatches, Command};
+use console::style;
+
+use crate::utils::sourcemaps::bundle::read_bundle_info;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Print the contents and metadata of a local artifact bundle.")
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .required(true)
+                .help("The path to the artifact bundle."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let path = matches.get_one::<String>("path").unwrap();
+    let data = fs::read(path).with_context(|| format!("Could not read {path}"))?;
+    let info = read_bundle_info(&data)?;
+
+    println!("{}", style("Artifact Bundle Info").dim().bold());
+    println!("  Bundle ID: {}", style(info.debug_id).dim());
+    println!("  Files: {}", style(info.files).yellow());
+    for (label, value) in [
+        ("Organization", &info.org),
+        ("Project", &info.project),
+        ("Release", &info.release),
+        ("Dist", &info.dist),
+        ("Note", &info.metadata.note),
+    ] {
+        if let Some(value) = value {
+            println!("  {label}: {}", style(value).cyan());
+        }
+    }
+
+    if !info.metadata.meta.is_empty() {
+        println!("  Metadata:");
+        for (key, value) in &info.metadata.meta {
+            println!("    > {key}: {}", style(value).dim());
+        }
+    }
+
+    Ok(())
+}
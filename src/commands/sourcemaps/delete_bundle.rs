@@ -0,0 +1,50 @@
+use anyhow::{bail, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::ui::prompt_to_continue;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Delete an artifact bundle of a project.")
+        .arg(
+            Arg::new("bundle_id")
+                .value_name("BUNDLE_ID")
+                .required(true)
+                .help("The ID of the artifact bundle, as shown by `sourcemaps list-bundles`."),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .action(ArgAction::SetTrue)
+                .help("Delete the artifact bundle without asking for confirmation."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let bundle_id = matches.get_one::<String>("bundle_id").unwrap();
+
+    if !matches.get_flag("yes")
+        && !prompt_to_continue(&format!(
+            "Do you really want to delete artifact bundle {bundle_id}?"
+        ))?
+    {
+        println!("Aborted!");
+        return Ok(());
+    }
+
+    let api = Api::current();
+    if !api
+        .authenticated()?
+        .delete_artifact_bundle(&org, &project, bundle_id)?
+    {
+        bail!("Artifact bundle {bundle_id} does not exist.");
+    }
+    println!("Deleted artifact bundle {bundle_id}");
+
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use std::fs::{self, File};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, format_err, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use glob::Pattern;
+use log::warn;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::args::validate_distribution;
+use crate::utils::logging::quiet_println;
+
+/// The number of files downloaded at the same time.
+const DOWNLOAD_CONCURRENCY: usize = 8;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Download the sourcemaps and source files of a release or artifact bundle.")
+        .long_about(
+            "Download the sourcemaps and source files of a release or artifact bundle.{n}{n}\
+             Files are written to the given directory. URLs such as `~/static/app.js` or \
+             `https://example.com/static/app.js` are stored as `static/app.js` and \
+             `example.com/static/app.js` respectively.",
+        )
+        .arg(
+            Arg::new("path")
+                .value_name("PATH")
+                .required(true)
+                .help("The directory to download the files into."),
+        )
+        .arg(
+            Arg::new("dist")
+                .long("dist")
+                .short('d')
+                .value_name("DISTRIBUTION")
+                .value_parser(validate_distribution)
+                .conflicts_with("bundle_id")
+                .help("Only download release files of this distribution."),
+        )
+        .arg(
+            Arg::new("bundle_id")
+                .long("bundle-id")
+                .value_name("BUNDLE_ID")
+                .help("Download the files of this artifact bundle instead of a release."),
+        )
+        .arg(
+            Arg::new("url_filters")
+                .long("url-filter")
+                .value_name("PATTERN")
+                .action(ArgAction::Append)
+                .help(
+                    "Only download files whose URL matches this glob pattern, \
+                     e.g. `~/static/**` or `*.map`. Can be given multiple times.",
+                ),
+        )
+}
+
+/// A file to download, identified by its URL and server side ID.
+struct DownloadItem {
+    name: String,
+    id: String,
+}
+
+/// Maps the URL of an artifact to a path relative to the target directory.
+///
+/// The `~/` prefix and URL schemes are stripped, the host of absolute URLs
+/// becomes the first directory.  Windows path prefixes such as `C:` or `\\?\`
+/// and components that would escape the target directory are dropped.
+fn artifact_path(name: &str) -> Option<PathBuf> {
+    let name = strip_windows_prefix(name);
+    let name = name.split(['?', '#']).next().unwrap_or_default();
+    let name = if let Some(rest) = name.strip_prefix('~') {
+        rest
+    } else if let Some((_, rest)) = name.split_once("://") {
+        rest
+    } else {
+        name
+    };
+
+    let path: PathBuf = name
+        .split(['/', '\\'])
+        .flat_map(|c| Path::new(c).components())
+        .filter_map(|c| match c {
+            Component::Normal(c) => Some(c),
+            _ => None,
+        })
+        .collect();
+
+    if path.as_os_str().is_empty() {
+        None
+    } else {
+        Some(path)
+    }
+}
+
+/// Strips a Windows verbatim or device prefix (`\\?\`, `\\.\`) and a drive
+/// letter from the start of a path.
+fn strip_windows_prefix(name: &str) -> &str {
+    let is_separator = |c: u8| c == b'/' || c == b'\\';
+    let name = match name.as_bytes() {
+        [a, b, b'?' | b'.', c, ..] if is_separator(*a) && is_separator(*b) && is_separator(*c) => {
+            &name[4..]
+        }
+        _ => name,
+    };
+    match name.as_bytes() {
+        [drive, b':', ..] if drive.is_ascii_alphabetic() => &name[2..],
+        _ => name,
+    }
+}
+
+/// Where the files to download are stored on the server.
+enum DownloadSource<'a> {
+    Release(&'a str),
+    Bundle {
+        project: &'a str,
+        bundle_id: &'a str,
+    },
+}
+
+fn download_file(
+    org: &str,
+    project: Option<&str>,
+    source: &DownloadSource<'_>,
+    item: &DownloadItem,
+    target: &Path,
+) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = File::create(target)?;
+
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+    let result = match source {
+        DownloadSource::Release(release) => {
+            authenticated_api.get_release_file(org, project, release, &item.id, &mut file)
+        }
+        DownloadSource::Bundle { project, bundle_id } => {
+            authenticated_api.get_artifact_bundle_file(org, project, bundle_id, &item.id, &mut file)
+        }
+    };
+
+    if let Err(err) = result {
+        drop(file);
+        fs::remove_file(target).ok();
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let project = config.get_project(matches).ok();
+    let target_dir = Path::new(matches.get_one::<String>("path").unwrap());
+    let url_filters = matches
+        .get_many::<String>("url_filters")
+        .map(|patterns| {
+            patterns
+                .map(|pattern| {
+                    Pattern::new(pattern)
+                        .map_err(|err| format_err!("Invalid URL filter `{pattern}`: {err}"))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+
+    let release;
+    let (source, items) = if let Some(bundle_id) = matches.get_one::<String>("bundle_id") {
+        let Some(project) = project.as_deref() else {
+            bail!("A project is required to download the files of an artifact bundle.");
+        };
+        let items = authenticated_api
+            .list_artifact_bundle_files(&org, project, bundle_id)
+            .map_err(|err| format_err!("Could not list the files of the artifact bundle: {err}"))?
+            .into_iter()
+            .map(|file| DownloadItem {
+                name: file.file_path,
+                id: file.id,
+            })
+            .collect::<Vec<_>>();
+        (DownloadSource::Bundle { project, bundle_id }, items)
+    } else {
+        release = config.get_release(matches)?;
+        let dist = matches.get_one::<String>("dist");
+        let items = authenticated_api
+            .list_release_files(&org, project.as_deref(), &release)?
+            .into_iter()
+            .filter(|artifact| dist.map_or(true, |dist| artifact.dist.as_ref() == Some(dist)))
+            .map(|artifact| DownloadItem {
+                name: artifact.name,
+                id: artifact.id,
+            })
+            .collect::<Vec<_>>();
+        (DownloadSource::Release(&release), items)
+    };
+
+    let items: Vec<_> = items
+        .into_iter()
+        .filter(|item| {
+            url_filters.is_empty() || url_filters.iter().any(|filter| filter.matches(&item.name))
+        })
+        .filter_map(|item| match artifact_path(&item.name) {
+            Some(path) => Some((item, target_dir.join(path))),
+            None => {
+                warn!("Skipping file with unsupported URL: {}", item.name);
+                None
+            }
+        })
+        .collect();
+
+    if items.is_empty() {
+        quiet_println!("> No matching files found");
+        return Ok(());
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(DOWNLOAD_CONCURRENCY)
+        .build()?;
+    let failed = pool.install(|| {
+        items
+            .par_iter()
+            .filter(|(item, target)| {
+                match download_file(&org, project.as_deref(), &source, item, target) {
+                    Ok(()) => false,
+                    Err(err) => {
+                        warn!("Failed to download {}: {}", item.name, err);
+                        true
+                    }
+                }
+            })
+            .count()
+    });
+
+    quiet_println!(
+        "> Downloaded {} files to {}",
+        items.len() - failed,
+        target_dir.display()
+    );
+
+    if failed > 0 {
+        bail!("Failed to download {} of {} files.", failed, items.len());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_artifact_path() {
+    let cases = [
+        ("~/static/app.js", Some("static/app.js")),
+        (
+            "https://example.com/static/app.js?v=1",
+            Some("example.com/static/app.js"),
+        ),
+        ("../../etc/passwd", Some("etc/passwd")),
+        ("/static/./app.js", Some("static/app.js")),
+        ("C:\\static\\app.js", Some("static/app.js")),
+        ("C:app.js", Some("app.js")),
+        ("\\\\?\\C:\\static\\app.js", Some("static/app.js")),
+        ("//./C:/app.js", Some("app.js")),
+        ("~/", None),
+        ("C:\\..", None),
+    ];
+
+    for (name, expected) in cases {
+        assert_eq!(
+            artifact_path(name),
+            expected.map(|path| path.split('/').collect::<PathBuf>()),
+            "{name}"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::fs;
 use std::io::Read;
 use std::path::Path;
 
@@ -5,9 +7,11 @@ use anyhow::{bail, format_err, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use console::style;
 use sentry::protocol::{Frame, Stacktrace};
+use symbolic::common::DebugId;
+use symbolic::debuginfo::sourcebundle::{SourceBundle, SourceFileType};
 use url::Url;
 
-use crate::api::{Api, Artifact, ProcessedEvent};
+use crate::api::{Api, Artifact, ProcessedEvent, ProcessedEventDebugImage};
 use crate::config::Config;
 use crate::utils::fs::TempFile;
 use crate::utils::system::QuietExit;
@@ -275,11 +279,8 @@ fn discover_sourcemaps_location(
     Err(format_err!("Failed to discover source map url"))
 }
 
-fn print_sourcemap(file: &TempFile, line: u32, column: u32) -> Result<()> {
-    let mut f = file.open()?;
-    let mut buf = vec![];
-    f.read_to_end(&mut buf)?;
-    let sm = sourcemap::decode_slice(&buf)?;
+fn print_sourcemap(sourcemap: &[u8], line: u32, column: u32) -> Result<()> {
+    let sm = sourcemap::decode_slice(sourcemap)?;
 
     if let Some(token) = sm.lookup_token(line, column) {
         if let Some(view) = token.get_source_view() {
@@ -297,6 +298,18 @@ fn print_sourcemap(file: &TempFile, line: u32, column: u32) -> Result<()> {
     Ok(())
 }
 
+fn print_frame_sourcemap(sourcemap: &[u8], frame: &Frame) -> Result<()> {
+    print_sourcemap(
+        sourcemap,
+        frame.lineno.expect("Event frame is missing line number") as u32 - 1,
+        frame.colno.expect("Event frame is missing column number") as u32 - 1,
+    )
+    .map_err(|err| {
+        error(err);
+        QuietExit(1).into()
+    })
+}
+
 fn print_mapped_frame(frame: &Frame) {
     println!(
         "{}",
@@ -328,6 +341,191 @@ fn print_mapped_frame(frame: &Frame) {
     );
 }
 
+/// The outcome of looking up the source map of a frame by its debug ID.
+enum DebugIdLookup {
+    /// The file of the frame has no debug ID injected.
+    NoDebugId,
+    /// The debug ID reported by the SDK cannot be parsed.
+    InvalidDebugId(String),
+    /// No artifact bundle contains the debug ID. Holds the types of other
+    /// artifacts the lookup returned for it, if any.
+    MissingBundle(DebugId, Vec<String>),
+    /// Artifact bundles contain the debug ID, but no source map with it.
+    MissingSourceMap(DebugId, String),
+    /// The source map was found in an artifact bundle.
+    Found(DebugId, String, Vec<u8>),
+}
+
+/// Returns the source map debug images of the event.
+fn extract_debug_images(event: &ProcessedEvent) -> Vec<&ProcessedEventDebugImage> {
+    event
+        .debug_meta
+        .iter()
+        .flat_map(|meta| &meta.images)
+        .filter(|image| image.ty == "sourcemap" && image.debug_id.is_some())
+        .collect()
+}
+
+// The SDKs report the debug ID of each minified file as a debug image, with the
+// `abs_path` of the frames as its `code_file`.
+fn find_debug_id<'a>(images: &[&'a ProcessedEventDebugImage], abs_path: &str) -> Option<&'a str> {
+    images
+        .iter()
+        .find(|image| image.code_file.as_deref() == Some(abs_path))
+        .and_then(|image| image.debug_id.as_deref())
+}
+
+fn lookup_debug_id(org: &str, project: &str, debug_id: DebugId) -> Result<DebugIdLookup> {
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+    let (bundles, others): (Vec<_>, Vec<_>) = authenticated_api
+        .lookup_artifacts_by_debug_id(org, project, debug_id)?
+        .into_iter()
+        .partition(|artifact| artifact.ty == "bundle");
+
+    for bundle in &bundles {
+        let file = TempFile::create()?;
+        authenticated_api.get_artifact_lookup_file(org, project, &bundle.id, &mut file.open()?)?;
+        let data = fs::read(file.path())?;
+        let archive = SourceBundle::parse(&data)
+            .map_err(|err| format_err!("Could not read artifact bundle {}: {}", bundle.id, err))?;
+        let session = archive.debug_session()?;
+        if let Some(contents) = session
+            .source_by_debug_id(debug_id, SourceFileType::SourceMap)?
+            .and_then(|sourcemap| sourcemap.contents().map(str::to_owned))
+        {
+            return Ok(DebugIdLookup::Found(
+                debug_id,
+                bundle.id.clone(),
+                contents.into_bytes(),
+            ));
+        }
+    }
+
+    Ok(match bundles.first() {
+        Some(bundle) => DebugIdLookup::MissingSourceMap(debug_id, bundle.id.clone()),
+        None => {
+            let mut types: Vec<_> = others.into_iter().map(|artifact| artifact.ty).collect();
+            types.sort();
+            types.dedup();
+            DebugIdLookup::MissingBundle(debug_id, types)
+        }
+    })
+}
+
+/// The result of checking the frames of a stacktrace by debug ID.
+struct DebugIdReport {
+    /// The source map of the selected frame, if it was found.
+    selected: Option<Vec<u8>>,
+    /// Whether the source maps of all in-app frames were found.
+    all_resolved: bool,
+}
+
+/// Reports for each in-app frame whether its source map can be found by debug ID.
+fn explain_debug_ids(
+    org: &str,
+    project: &str,
+    stacktrace: &Stacktrace,
+    images: &[&ProcessedEventDebugImage],
+    position: usize,
+) -> Result<DebugIdReport> {
+    let mut lookups = HashMap::new();
+    let mut selected = None;
+    let mut all_resolved = true;
+    let (mut no_debug_id, mut missing_bundle, mut missing_sourcemap) = (false, false, false);
+
+    // Frames are in bottom-up order.
+    for (index, frame) in extract_in_app_frames(stacktrace).iter().rev().enumerate() {
+        let Some(abs_path) = frame.abs_path.as_deref() else {
+            continue;
+        };
+
+        let lookup: &DebugIdLookup = match find_debug_id(images, abs_path) {
+            None => &DebugIdLookup::NoDebugId,
+            Some(raw) => match raw.parse::<DebugId>() {
+                Err(_) => lookups
+                    .entry(raw.to_owned())
+                    .or_insert_with(|| DebugIdLookup::InvalidDebugId(raw.to_owned())),
+                Ok(debug_id) => {
+                    if !lookups.contains_key(raw) {
+                        lookups.insert(raw.to_owned(), lookup_debug_id(org, project, debug_id)?);
+                    }
+                    &lookups[raw]
+                }
+            },
+        };
+
+        let prefix = format!("Frame {index} ({abs_path})");
+        match lookup {
+            DebugIdLookup::NoDebugId => {
+                no_debug_id = true;
+                error(format!("{prefix}: no debug ID injected"));
+            }
+            DebugIdLookup::InvalidDebugId(raw) => {
+                error(format!("{prefix}: invalid debug ID {raw}"))
+            }
+            DebugIdLookup::MissingBundle(debug_id, types) if types.is_empty() => {
+                missing_bundle = true;
+                error(format!(
+                    "{prefix}: no artifact bundle contains debug ID {debug_id}"
+                ));
+            }
+            DebugIdLookup::MissingBundle(debug_id, types) => {
+                missing_bundle = true;
+                error(format!(
+                    "{prefix}: debug ID {debug_id} only matches artifacts of type {}, \
+                     which are not artifact bundles",
+                    types.join(", ")
+                ));
+            }
+            DebugIdLookup::MissingSourceMap(debug_id, bundle) => {
+                missing_sourcemap = true;
+                error(format!(
+                    "{prefix}: artifact bundle {bundle} has no source map with debug ID {debug_id}"
+                ));
+            }
+            DebugIdLookup::Found(debug_id, bundle, _) => success(format!(
+                "{prefix}: resolved with debug ID {debug_id} from artifact bundle {bundle}"
+            )),
+        }
+
+        match lookup {
+            DebugIdLookup::Found(_, _, sourcemap) if index == position => {
+                selected = Some(sourcemap.clone());
+            }
+            DebugIdLookup::Found(..) => {}
+            _ => all_resolved = false,
+        }
+    }
+
+    if no_debug_id {
+        tip("Run `sentry-cli sourcemaps inject` on the minified files before deploying them.");
+    }
+    if missing_bundle {
+        tip(
+            "Upload the source maps with `sentry-cli sourcemaps upload` after injecting debug IDs.",
+        );
+    }
+    if missing_sourcemap {
+        tip("Upload the source maps together with the minified files that reference them.");
+    }
+
+    Ok(DebugIdReport {
+        selected,
+        all_resolved,
+    })
+}
+
+/// Reports that source maps work, unless some frames failed to resolve by debug ID.
+fn finish(all_resolved: bool) -> Result<()> {
+    if !all_resolved {
+        warning("Not all frames resolve with debug IDs, see above.");
+        return Err(QuietExit(1).into());
+    }
+    success("Source Maps should be working fine. Have you tried turning it off and on again?");
+    Ok(())
+}
+
 fn extract_release(event: &ProcessedEvent) -> Result<String> {
     if let Some(release) = event.release.as_ref() {
         success(format!("Event has release name: {release}"));
@@ -377,7 +575,19 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let event_id = matches.get_one::<String>("event").unwrap();
 
     let event = fetch_event(&org, &project, event_id)?;
-    let release = extract_release(&event)?;
+    let debug_images = extract_debug_images(&event);
+
+    // Events with debug IDs do not need a release, so it is only checked when
+    // falling back to release artifacts.
+    let release = if debug_images.is_empty() {
+        Some(extract_release(&event)?)
+    } else {
+        success(format!(
+            "Event has debug IDs for {} source files",
+            debug_images.len()
+        ));
+        None
+    };
 
     if event.exception.values.is_empty() {
         warning("Event has no exception captured, there is no use for source maps");
@@ -392,11 +602,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     })?;
     success("Event has a valid stacktrace present");
 
-    let mut frame = extract_nth_frame(stacktrace, *matches.get_one::<usize>("frame").unwrap())
-        .map_err(|err| {
-            error(err);
-            QuietExit(1)
-        })?;
+    let position = *matches.get_one::<usize>("frame").unwrap();
+    let mut frame = extract_nth_frame(stacktrace, position).map_err(|err| {
+        error(err);
+        QuietExit(1)
+    })?;
+    let mut selected_stacktrace = stacktrace;
 
     if exception.raw_stacktrace.is_some() {
         if matches.get_flag("force") {
@@ -404,11 +615,11 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 "Exception is already source mapped, however 'force' flag was used. Moving along.",
             );
             let raw_stacktrace = exception.raw_stacktrace.as_ref().unwrap();
-            frame = extract_nth_frame(raw_stacktrace, *matches.get_one::<usize>("frame").unwrap())
-                .map_err(|err| {
-                    error(err);
-                    QuietExit(1)
-                })?;
+            frame = extract_nth_frame(raw_stacktrace, position).map_err(|err| {
+                error(err);
+                QuietExit(1)
+            })?;
+            selected_stacktrace = raw_stacktrace;
         } else {
             warning("Exception is already source mapped and first resolved frame points to:\n");
             if let Some(frame) = extract_in_app_frames(stacktrace)
@@ -424,6 +635,27 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
+    let mut all_resolved = true;
+    if debug_images.is_empty() {
+        tip("Event has no debug IDs, only release artifacts are checked.");
+    } else {
+        let report =
+            explain_debug_ids(&org, &project, selected_stacktrace, &debug_images, position)?;
+        if let Some(sourcemap) = report.selected {
+            print_frame_sourcemap(&sourcemap, frame)?;
+            return finish(report.all_resolved);
+        }
+        all_resolved = report.all_resolved;
+        warning(format!(
+            "Selected frame ({position}) cannot be resolved with debug IDs, \
+             checking release artifacts instead."
+        ));
+    }
+
+    let release = match release {
+        Some(release) => release,
+        None => extract_release(&event)?,
+    };
     let abs_path = frame.abs_path.as_ref().expect("Incorrect abs_path value");
     let artifacts = fetch_release_artifacts(&org, &project, &release)?;
     let matched_artifact = find_matching_artifact(&artifacts, &unify_artifact_url(abs_path)?)?;
@@ -450,19 +682,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     let sourcemap_file =
         fetch_release_artifact_file(&org, &project, &release, &sourcemap_artifact)?;
+    let mut sourcemap = vec![];
+    sourcemap_file.open()?.read_to_end(&mut sourcemap)?;
 
-    print_sourcemap(
-        &sourcemap_file,
-        frame.lineno.expect("Event frame is missing line number") as u32 - 1,
-        frame.colno.expect("Event frame is missing column number") as u32 - 1,
-    )
-    .map_err(|err| {
-        error(err);
-        QuietExit(1)
-    })?;
+    print_frame_sourcemap(&sourcemap, frame)?;
 
-    success("Source Maps should be working fine. Have you tried turning it off and on again?");
-    Ok(())
+    finish(all_resolved)
 }
 
 #[test]
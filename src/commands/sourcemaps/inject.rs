@@ -3,8 +3,10 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::{Arg, ArgAction, ArgMatches, Command};
 
+use crate::utils::args::ArgExt;
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::fs::path_as_url;
+use crate::utils::logging::quiet_println;
 use crate::utils::sourcemaps::SourceMapProcessor;
 
 pub fn make_command(command: Command) -> Command {
@@ -17,7 +19,13 @@ pub fn make_command(command: Command) -> Command {
             sourcemap and that sourcemap is locally available, \
             the debug id will be injected into it as well. \
             If the referenced sourcemap already contains a debug id, \
-            that id is used instead.",
+            that id is used instead.{n}{n}\
+            Source files that already contain a debug id are left untouched \
+            unless --force is given.{n}{n}\
+            Assets that cannot contain code, such as CSS files, are only \
+            considered when their extension is passed with --ext. They are \
+            never modified; instead, the debug id is only injected into \
+            their sourcemap.",
         )
         .arg(
             Arg::new("paths")
@@ -37,16 +45,7 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::Append)
                 .help("Ignores all files and folders matching the given glob"),
         )
-        .arg(
-            Arg::new("ignore_file")
-                .long("ignore-file")
-                .short('I')
-                .value_name("IGNORE_FILE")
-                .help(
-                    "Ignore all files and folders specified in the given \
-                    ignore file, e.g. .gitignore.",
-                ),
-        )
+        .ignore_file_args()
         .arg(
             Arg::new("extensions")
                 .long("ext")
@@ -57,7 +56,8 @@ pub fn make_command(command: Command) -> Command {
                     "Set the file extensions of JavaScript files that are considered \
                     for injection.  This overrides the default extensions (js, cjs, mjs).  \
                     To add an extension, all default extensions must be repeated.  Specify \
-                    once per extension.  Source maps are discovered via those files.",
+                    once per extension.  Source maps are discovered via those files.  \
+                    For CSS files (--ext css), only the sourcemaps are modified.",
                 ),
         )
         .arg(
@@ -66,6 +66,15 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Don't modify files on disk."),
         )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Replace the debug ids of source files that already contain one \
+                    with new ones, and update their sourcemaps accordingly.",
+                ),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -76,11 +85,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .unwrap()
         .map(PathBuf::from);
     let dry_run = matches.get_flag("dry_run");
+    let force = matches.get_flag("force");
 
-    let ignore_file = matches
-        .get_one::<String>("ignore_file")
-        .map(String::as_str)
-        .unwrap_or_default();
+    let ignore_files: Vec<_> = matches
+        .get_many::<String>("ignore_file")
+        .unwrap_or_default()
+        .collect();
     let ignores: Vec<_> = matches
         .get_many::<String>("ignore")
         .map(|ignores| ignores.map(|i| format!("!{i}")).collect())
@@ -95,9 +105,10 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     extensions.push("map");
 
     for path in paths {
-        println!("> Searching {}", path.display());
+        quiet_println!("> Searching {}", path.display());
         let sources = ReleaseFileSearch::new(path)
-            .ignore_file(ignore_file)
+            .ignore_files(ignore_files.clone())
+            .use_gitignore(matches.get_flag("use_gitignore"))
             .ignores(&ignores)
             .extensions(extensions.clone())
             .collect_files()?;
@@ -107,6 +118,6 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
-    processor.inject_debug_ids(dry_run, &extensions)?;
+    processor.inject_debug_ids(dry_run, force, &extensions)?;
     Ok(())
 }
@@ -0,0 +1,76 @@
+use std::io;
+
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use itertools::Itertools;
+
+use crate::api::{Api, ArtifactBundleAssociation};
+use crate::config::Config;
+use crate::utils::formatting::Table;
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("List the artifact bundles uploaded to a project.")
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .help("The output format of the list of artifact bundles."),
+        )
+}
+
+/// Formats an association as `release` or `release (dist)`.
+fn format_association(association: &ArtifactBundleAssociation) -> String {
+    match association.dist {
+        Some(ref dist) => format!("{} ({dist})", association.release),
+        None => association.release.clone(),
+    }
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let api = Api::current();
+    let bundles = api.authenticated()?.list_artifact_bundles(&org, &project)?;
+
+    if matches.get_one::<String>("format").map(String::as_str) == Some("json") {
+        serde_json::to_writer_pretty(&mut io::stdout(), &bundles)?;
+        println!();
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("Bundle ID")
+        .add("Date")
+        .add("Files")
+        .add("Releases");
+
+    for bundle in &bundles {
+        table
+            .add_row()
+            .add(&bundle.bundle_id)
+            .add(bundle.date.format("%F %T"))
+            .add(bundle.file_count)
+            .add(if bundle.associations.is_empty() {
+                "-".to_owned()
+            } else {
+                bundle
+                    .associations
+                    .iter()
+                    .map(format_association)
+                    .join(", ")
+            });
+    }
+
+    if table.is_empty() {
+        println!("No artifact bundles found");
+    } else {
+        table.print();
+    }
+
+    Ok(())
+}
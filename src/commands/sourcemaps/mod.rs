@@ -3,15 +3,23 @@ use clap::{ArgMatches, Command};
 
 use crate::utils::args::ArgExt;
 
+pub mod bundle_info;
+pub mod delete_bundle;
+pub mod download;
 pub mod explain;
 pub mod inject;
+pub mod list_bundles;
 pub mod resolve;
 pub mod upload;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
+        $mac!(bundle_info);
+        $mac!(delete_bundle);
+        $mac!(download);
         $mac!(explain);
         $mac!(inject);
+        $mac!(list_bundles);
         $mac!(resolve);
         $mac!(upload);
     };
@@ -6,14 +6,15 @@ use anyhow::{format_err, Result};
 use clap::{Arg, ArgMatches, Command};
 use sourcemap::{DecodedMap, SourceView, Token};
 
+use crate::utils::sourcemaps::bundle::resolve_from_bundle;
+
 pub fn make_command(command: Command) -> Command {
     command
         .about("Resolve sourcemap for a given line/column position.")
-        .arg(
-            Arg::new("path")
-                .value_name("PATH")
-                .help("The sourcemap to resolve."),
-        )
+        .arg(Arg::new("path").value_name("PATH").help(
+            "The sourcemap to resolve.{n}With --bundle, the URL or debug ID of the \
+                     minified file instead.",
+        ))
         .arg(
             Arg::new("line")
                 .long("line")
@@ -30,6 +31,10 @@ pub fn make_command(command: Command) -> Command {
                 .value_parser(clap::value_parser!(u32))
                 .help("Column number for minified source."),
         )
+        .arg(Arg::new("bundle").long("bundle").value_name("BUNDLE").help(
+            "Resolve offline from a local artifact bundle. The minified file is \
+                     looked up by URL or debug ID, the same way the server does.",
+        ))
 }
 
 /// Returns the zero indexed position from matches
@@ -136,7 +141,37 @@ fn print_token(token: &Token<'_>) {
     }
 }
 
+fn execute_bundle(matches: &ArgMatches, bundle_path: &str) -> Result<()> {
+    let reference = matches
+        .get_one::<String>("path")
+        .ok_or_else(|| format_err!("URL or debug ID of the minified file not provided"))?;
+
+    let resolved = resolve_from_bundle(&fs::read(bundle_path)?, reference)?;
+    println!("artifact bundle path: {bundle_path:?}");
+    println!("minified file: {}", resolved.minified_url);
+    println!("source map: {}", resolved.sourcemap_url);
+    println!();
+
+    let (line, column) = lookup_pos(matches).unwrap_or_default();
+    println!(
+        "Searching for token nearest to line {}, column {} in the minified file:\n",
+        line + 1,
+        column + 1
+    );
+    if let Some(token) = resolved.lookup_token(line, column)? {
+        print_token(&token);
+    } else {
+        println!("  - no token found!");
+    }
+
+    Ok(())
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
+    if let Some(bundle_path) = matches.get_one::<String>("bundle") {
+        return execute_bundle(matches, bundle_path);
+    }
+
     let sourcemap_path = matches
         .get_one::<String>("path")
         .ok_or_else(|| format_err!("Sourcemap not provided"))?;
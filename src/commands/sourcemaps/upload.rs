@@ -1,21 +1,30 @@
 use std::env;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{bail, format_err, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
 use glob::{glob_with, MatchOptions};
 use itertools::Itertools;
 use log::{debug, warn};
+use regex::Regex;
 
 use crate::api::{Api, ChunkUploadCapability};
 use crate::config::Config;
 use crate::constants::DEFAULT_MAX_WAIT;
-use crate::utils::args::validate_distribution;
-use crate::utils::file_search::ReleaseFileSearch;
+use crate::utils::args::{validate_distribution, ArgExt};
+use crate::utils::file_search::{ReleaseFileMatch, ReleaseFileSearch};
 use crate::utils::file_upload::UploadContext;
 use crate::utils::fs::path_as_url;
-use crate::utils::sourcemaps::SourceMapProcessor;
+use crate::utils::logging::{quiet_println, set_quiet_mode};
+use crate::utils::releases::detect_release_name_from_git;
+use crate::utils::sourcemaps::upload_state::UploadState;
+use crate::utils::sourcemaps::{
+    common_path_prefix, group_by_dist_from_path, SourceMapProcessor, ValidationLevel,
+};
+use crate::utils::upload_metadata::UploadMetadata;
+use crate::utils::upload_report::{UploadReport, UploadReportEntry};
 
 const DEFAULT_EXTENSIONS: &[&str] = &["js", "cjs", "mjs", "map", "jsbundle", "bundle"];
 
@@ -30,7 +39,10 @@ pub fn make_command(command: Command) -> Command {
                 .required_unless_present_any(["bundle", "bundle_sourcemap"])
                 .num_args(1..)
                 .action(ArgAction::Append)
-                .help("The files to upload."),
+                .help(
+                    "The files to upload. Directories as well as .zip, .tar and .tar.gz \
+                    archives are searched for files to upload.",
+                ),
         )
         .arg(
             Arg::new("url_prefix")
@@ -45,6 +57,16 @@ pub fn make_command(command: Command) -> Command {
                 .value_name("SUFFIX")
                 .help("The URL suffix to append to all filenames."),
         )
+        .arg(
+            Arg::new("url_strip_common_prefix")
+                .long("url-strip-common-prefix")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Strip the longest common directory of all matched files from their \
+                    paths before applying --url-prefix, instead of building URLs relative \
+                    to the given paths.",
+                ),
+        )
         .arg(
             Arg::new("dist")
                 .long("dist")
@@ -54,16 +76,33 @@ pub fn make_command(command: Command) -> Command {
                 .help("Optional distribution identifier for the sourcemaps."),
         )
         .arg(
-            Arg::new("note")
-                .long("note")
-                .value_name("NOTE")
-                .help("Adds an optional note to the uploaded artifact bundle."),
+            Arg::new("dist_from_path")
+                .long("dist-from-path")
+                .value_name("REGEX")
+                .conflicts_with("bundle")
+                .help(
+                    "Derive the distribution identifier of each file from its path, using \
+                     the first capture group of this regular expression, e.g. \
+                     `outputs/([^/]+)/`. Files are uploaded in one artifact bundle per \
+                     distribution. Files whose path does not match use --dist, or fail the \
+                     upload if it is not given.",
+                ),
         )
+        .upload_metadata_args()
         .arg(
             Arg::new("validate")
                 .long("validate")
-                .action(ArgAction::SetTrue)
-                .help("Enable basic sourcemap validation."),
+                .value_name("LEVEL")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("basic")
+                .value_parser(["basic", "strict"])
+                .help(
+                    "Enable sourcemap validation. This checks that source maps fit the \
+                     minified files referencing them. `--validate=strict` additionally \
+                     spot checks mappings against the embedded sources. Findings are \
+                     reported as warnings, which fail the upload with --strict.",
+                ),
         )
         .arg(
             Arg::new("decompress")
@@ -71,6 +110,13 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Enable files gzip decompression prior to upload."),
         )
+        .arg(
+            Arg::new("no_decompress")
+                .long("no-decompress")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("decompress")
+                .help("Do not decompress files ending in .gz or .br prior to upload."),
+        )
         .arg(
             Arg::new("wait")
                 .long("wait")
@@ -161,16 +207,8 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::Append)
                 .help("Ignores all files and folders matching the given glob"),
         )
-        .arg(
-            Arg::new("ignore_file")
-                .long("ignore-file")
-                .short('I')
-                .value_name("IGNORE_FILE")
-                .help(
-                    "Ignore all files and folders specified in the given \
-                    ignore file, e.g. .gitignore.",
-                ),
-        )
+        .ignore_file_args()
+        .follow_symlinks_args()
         .arg(
             Arg::new("bundle")
                 .long("bundle")
@@ -197,6 +235,27 @@ pub fn make_command(command: Command) -> Command {
                     no matter whether they are already present on the server.",
                 ),
         )
+        .arg(
+            Arg::new("compression")
+                .long("compression")
+                .value_name("ALGORITHM")
+                .value_parser(["gzip", "brotli", "zstd", "none"])
+                .help(
+                    "The compression used to upload files. If the server does not support \
+                    the given compression, gzip is used instead. [defaults to the best \
+                    compression supported by the server]",
+                ),
+        )
+        .arg(
+            Arg::new("max_file_size")
+                .long("max-file-size")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(u64))
+                .help(
+                    "Skip files larger than this many bytes instead of uploading them. \
+                    [defaults to the maximum file size supported by the server]",
+                ),
+        )
         .arg(
             Arg::new("extensions")
                 .long("ext")
@@ -221,19 +280,74 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help(
                     "Fail with a non-zero exit code if the specified source map file cannot be \
-                     uploaded.",
+                     uploaded, if source map references cannot be resolved, or if --validate \
+                     reports any warnings.",
                 ),
         )
-        // NOTE: Hidden until we decide to expose it publicly
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("strict")
+                .help(
+                    "Print the files that would be uploaded, without uploading them.{n}\
+                    This processes and bundles all files, but files on disk are never \
+                    modified.",
+                ),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["table", "json"])
+                .default_value("table")
+                .requires("dry_run")
+                .help("The output format of the dry run manifest."),
+        )
+        .arg(
+            Arg::new("report")
+                .long("report")
+                .value_name("PATH")
+                .conflicts_with("dry_run")
+                .help(
+                    "Write a JSON report to the given path.{n}The report lists every \
+                    file with its debug id, type, size, checksum and whether it was \
+                    uploaded, skipped or failed. It is also written if the upload fails.",
+                ),
+        )
+        .arg(
+            Arg::new("state_file")
+                .long("state-file")
+                .value_name("PATH")
+                .help(
+                    "Skip files that are unchanged since the last upload recorded in the \
+                    given state file.{n}The file records the checksum and debug id of every \
+                    uploaded file, and is updated after a successful upload. It is ignored \
+                    if it was recorded for a different organization, project or release.",
+                ),
+        )
+        .arg(
+            Arg::new("state_reset")
+                .long("state-reset")
+                .action(ArgAction::SetTrue)
+                .requires("state_file")
+                .help("Ignore the files recorded in the state file, and upload all files."),
+        )
         .arg(
             Arg::new("use_artifact_bundle")
                 .long("use-artifact-bundle")
-                .action(ArgAction::SetTrue)
+                .value_name("MODE")
+                .value_parser(["true", "false", "auto"])
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("true")
+                .default_value("auto")
                 .help(
-                    "Use new Artifact Bundles upload, that enables the use of Debug IDs \
-                    for Source Maps discovery.",
-                )
-                .hide(true),
+                    "Whether to upload an artifact bundle, which enables the use of Debug IDs \
+                    for Source Maps discovery. With `auto`, files are uploaded to the release \
+                    instead if the server does not support artifact bundles. This requires \
+                    `--release`.",
+                ),
         )
         // Legacy flag that has no effect, left hidden for backward compatibility
         .arg(
@@ -250,6 +364,7 @@ pub fn make_command(command: Command) -> Command {
                 .short('v')
                 .hide(true),
         )
+        .release_from_git_arg()
 }
 
 fn get_prefixes_from_args(matches: &ArgMatches) -> Vec<&str> {
@@ -333,15 +448,13 @@ fn process_sources_from_bundle(
     Ok(())
 }
 
-fn process_sources_from_paths(
-    matches: &ArgMatches,
-    processor: &mut SourceMapProcessor,
-) -> Result<()> {
+/// Collects the files to upload, along with the paths that their URLs are computed from.
+fn collect_sources_from_paths(matches: &ArgMatches) -> Result<Vec<(PathBuf, ReleaseFileMatch)>> {
     let paths = matches.get_many::<String>("paths").unwrap();
-    let ignore_file = matches
-        .get_one::<String>("ignore_file")
-        .map(String::as_str)
-        .unwrap_or_default();
+    let ignore_files: Vec<_> = matches
+        .get_many::<String>("ignore_file")
+        .unwrap_or_default()
+        .collect();
     let extensions = matches
         .get_many::<String>("extensions")
         .map(|extensions| extensions.map(|ext| ext.trim_start_matches('.')).collect())
@@ -354,50 +467,83 @@ fn process_sources_from_paths(
     let opts = MatchOptions::new();
     let collected_paths = paths.flat_map(|path| glob_with(path, opts).unwrap().flatten());
 
+    let mut collected = Vec::new();
     for path in collected_paths {
         // if we start walking over something that is an actual file then
         // the directory iterator yields that path and terminates.  We
         // handle that case here specifically to figure out what the path is
         // we should strip off.
         let path = path.as_path();
-        let (base_path, check_ignore) = if path.is_file() {
+        let (base_path, check_ignore) = if ReleaseFileSearch::is_archive(path) {
+            (path, true)
+        } else if path.is_file() {
             (path.parent().unwrap(), false)
         } else {
             (path, true)
         };
 
         let mut search = ReleaseFileSearch::new(path.to_path_buf());
-        search.decompress(matches.get_flag("decompress"));
+        search
+            .decompress(matches.get_flag("decompress"))
+            .decompress_suffixes(!matches.get_flag("no_decompress"))
+            .follow_symlinks(!matches.get_flag("no_follow_symlinks"));
 
         if check_ignore {
             search
-                .ignore_file(ignore_file)
+                .ignore_files(ignore_files.clone())
+                .use_gitignore(matches.get_flag("use_gitignore"))
                 .ignores(ignores.clone())
                 .extensions(extensions.clone());
         }
 
-        let sources = search.collect_files()?;
-
-        let url_suffix = matches
-            .get_one::<String>("url_suffix")
-            .map(String::as_str)
-            .unwrap_or_default();
-        let mut url_prefix = matches
-            .get_one::<String>("url_prefix")
-            .map(String::as_str)
-            .unwrap_or("~");
-        // remove a single slash from the end.  so ~/ becomes ~ and app:/// becomes app://
-        if url_prefix.ends_with('/') {
-            url_prefix = &url_prefix[..url_prefix.len() - 1];
+        for source in search.collect_files()? {
+            let local_path = source.path.strip_prefix(base_path).unwrap().to_path_buf();
+            collected.push((local_path, source));
         }
+    }
 
-        for source in sources {
-            let local_path = source.path.strip_prefix(base_path).unwrap();
-            let url = format!("{}/{}{}", url_prefix, path_as_url(local_path), url_suffix);
-            processor.add(&url, source)?;
+    if matches.get_flag("url_strip_common_prefix") {
+        // Make all paths absolute so that mixed relative and absolute inputs
+        // still share a common prefix.
+        let cwd = env::current_dir()?;
+        let absolute_paths: Vec<_> = collected
+            .iter()
+            .map(|(_, source)| cwd.join(&source.path))
+            .collect();
+        if let Some(prefix) = common_path_prefix(absolute_paths.iter().map(PathBuf::as_path)) {
+            debug!("Stripping common prefix: {}", prefix.display());
+            for ((local_path, _), absolute_path) in collected.iter_mut().zip(&absolute_paths) {
+                *local_path = absolute_path.strip_prefix(&prefix).unwrap().to_path_buf();
+            }
         }
     }
 
+    Ok(collected)
+}
+
+fn process_sources_from_paths(
+    matches: &ArgMatches,
+    processor: &mut SourceMapProcessor,
+    collected: Vec<(PathBuf, ReleaseFileMatch)>,
+) -> Result<()> {
+    let url_suffix = matches
+        .get_one::<String>("url_suffix")
+        .map(String::as_str)
+        .unwrap_or_default();
+    let mut url_prefix = matches
+        .get_one::<String>("url_prefix")
+        .map(String::as_str)
+        .unwrap_or("~");
+    // remove a single slash from the end.  so ~/ becomes ~ and app:/// becomes app://
+    if url_prefix.ends_with('/') {
+        url_prefix = &url_prefix[..url_prefix.len() - 1];
+    }
+
+    for (local_path, source) in collected {
+        let url = format!("{}/{}{}", url_prefix, path_as_url(&local_path), url_suffix);
+        processor.add(&url, source)?;
+    }
+
     if !matches.get_flag("no_rewrite") {
         let prefixes = get_prefixes_from_args(matches);
         processor.rewrite(&prefixes)?;
@@ -411,35 +557,83 @@ fn process_sources_from_paths(
         processor.add_debug_id_references()?;
     }
 
-    if matches.get_flag("validate") {
-        processor.validate_all()?;
+    processor.check_references(matches.get_flag("strict"))?;
+
+    if let Some(level) = matches.get_one::<String>("validate") {
+        let level = match level.as_str() {
+            "strict" => ValidationLevel::Strict,
+            _ => ValidationLevel::Basic,
+        };
+        processor.validate_all(level, matches.get_flag("strict"))?;
     }
 
     Ok(())
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let start = Instant::now();
     let config = Config::current();
-    let version = config.get_release_with_legacy_fallback(matches).ok();
+    let version = if matches.get_flag("release_from_git") {
+        Some(config.check_release(matches, &detect_release_name_from_git()?)?)
+    } else {
+        config.find_release_with_legacy_fallback(matches)?
+    };
     let (org, project) = config.get_org_and_project(matches)?;
+    let metadata = UploadMetadata::from_matches(matches)?;
     let api = Api::current();
-    let mut processor = SourceMapProcessor::new();
+    let dry_run_json = matches.get_flag("dry_run")
+        && matches.get_one::<String>("format").map(String::as_str) == Some("json");
+    if dry_run_json {
+        set_quiet_mode(true);
+    }
+
     let mut chunk_upload_options = api.authenticated()?.get_chunk_upload_options(&org)?;
 
-    if matches.get_flag("use_artifact_bundle")
-        || env::var("SENTRY_FORCE_ARTIFACT_BUNDLES").ok().as_deref() == Some("1")
+    let use_artifact_bundle = match matches
+        .get_one::<String>("use_artifact_bundle")
+        .map(String::as_str)
     {
-        if let Some(ref mut options) = chunk_upload_options {
-            if !options.supports(ChunkUploadCapability::ArtifactBundles) {
+        _ if env::var("SENTRY_FORCE_ARTIFACT_BUNDLES").ok().as_deref() == Some("1") => Some(true),
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        _ => None,
+    };
+    if let Some(ref mut options) = chunk_upload_options {
+        match use_artifact_bundle {
+            Some(true) if !options.supports(ChunkUploadCapability::ArtifactBundles) => {
                 options.accept.push(ChunkUploadCapability::ArtifactBundles);
             }
+            Some(false) => options.accept.retain(|capability| {
+                !matches!(
+                    capability,
+                    ChunkUploadCapability::ArtifactBundles
+                        | ChunkUploadCapability::ArtifactBundlesV2
+                )
+            }),
+            _ => (),
         }
     }
 
-    if matches.contains_id("bundle") && matches.contains_id("bundle_sourcemap") {
-        process_sources_from_bundle(matches, &mut processor)?;
-    } else {
-        process_sources_from_paths(matches, &mut processor)?;
+    let legacy_upload = !chunk_upload_options.as_ref().map_or(false, |options| {
+        options.supports(ChunkUploadCapability::ArtifactBundles)
+            || options.supports(ChunkUploadCapability::ArtifactBundlesV2)
+    });
+    if legacy_upload && !matches.get_flag("dry_run") {
+        if version.is_none() {
+            bail!(
+                "This version of Sentry does not support artifact bundles, or they were \
+                 disabled with --use-artifact-bundle=false. Uploading files to a release \
+                 requires a release slug (provide with --release)."
+            );
+        }
+        eprintln!(
+            "{}",
+            style(
+                "warning: Uploading the files to the release without an artifact bundle. \
+                 Debug IDs will not be used to associate the files with events."
+            )
+            .red()
+        );
     }
 
     let wait_for_secs = matches.get_one::<u64>("wait_for").copied();
@@ -450,18 +644,152 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         project: Some(&project),
         release: version.as_deref(),
         dist: matches.get_one::<String>("dist").map(String::as_str),
-        note: matches.get_one::<String>("note").map(String::as_str),
+        metadata: Some(&metadata),
         wait,
         max_wait,
         dedupe: !matches.get_flag("no_dedupe"),
         chunk_upload_options: chunk_upload_options.as_ref(),
+        compression: matches
+            .get_one::<String>("compression")
+            .map(|compression| compression.parse())
+            .transpose()?,
     };
 
-    if matches.get_flag("strict") {
-        processor.upload_strict(&upload_context)?;
+    let mut state = matches
+        .get_one::<String>("state_file")
+        .map(|path| {
+            UploadState::load(
+                Path::new(path),
+                matches.get_flag("state_reset"),
+                &upload_context,
+            )
+        })
+        .transpose()?;
+
+    // Write the report even if the upload fails
+    let mut report = Vec::new();
+    let result = upload_sources(
+        matches,
+        &upload_context,
+        dry_run_json,
+        state.as_mut(),
+        &mut report,
+    );
+    if let Some(path) = matches.get_one::<String>("report") {
+        UploadReport::new(report, start, result.as_ref().err())
+            .with_release(upload_context.release)
+            .with_metadata(&metadata)
+            .write(Path::new(path))?;
+    }
+    result?;
+
+    // The state is only updated once all files were uploaded
+    if let Some(state) = state {
+        if !matches.get_flag("dry_run") {
+            state.save()?;
+        }
+    }
+    Ok(())
+}
+
+fn upload_sources(
+    matches: &ArgMatches,
+    upload_context: &UploadContext,
+    dry_run_json: bool,
+    mut state: Option<&mut UploadState>,
+    report: &mut Vec<UploadReportEntry>,
+) -> Result<()> {
+    if matches.contains_id("bundle") && matches.contains_id("bundle_sourcemap") {
+        let mut processor = SourceMapProcessor::new();
+        process_sources_from_bundle(matches, &mut processor)?;
+        upload_processed_sources(
+            matches,
+            &mut processor,
+            upload_context,
+            dry_run_json,
+            state,
+            report,
+        )?;
+    } else if let Some(dist_pattern) = matches.get_one::<String>("dist_from_path") {
+        let dist_pattern = Regex::new(dist_pattern)
+            .map_err(|err| format_err!("Invalid --dist-from-path pattern: {err}"))?;
+        if dist_pattern.captures_len() < 2 {
+            bail!("The --dist-from-path pattern must contain a capture group for the dist.");
+        }
+
+        let collected = collect_sources_from_paths(matches)?;
+        let groups = group_by_dist_from_path(collected, &dist_pattern, upload_context.dist)?;
+        for (dist, collected) in groups {
+            quiet_println!("{} Uploading files of dist {}", style(">").dim(), dist);
+            let mut processor = SourceMapProcessor::new();
+            process_sources_from_paths(matches, &mut processor, collected)?;
+            let upload_context = UploadContext {
+                dist: Some(&dist),
+                ..upload_context.clone()
+            };
+            upload_processed_sources(
+                matches,
+                &mut processor,
+                &upload_context,
+                dry_run_json,
+                state.as_deref_mut(),
+                report,
+            )?;
+        }
     } else {
-        processor.upload(&upload_context)?;
+        let mut processor = SourceMapProcessor::new();
+        let collected = collect_sources_from_paths(matches)?;
+        process_sources_from_paths(matches, &mut processor, collected)?;
+        upload_processed_sources(
+            matches,
+            &mut processor,
+            upload_context,
+            dry_run_json,
+            state,
+            report,
+        )?;
     }
 
     Ok(())
 }
+
+fn upload_processed_sources(
+    matches: &ArgMatches,
+    processor: &mut SourceMapProcessor,
+    upload_context: &UploadContext,
+    dry_run_json: bool,
+    state: Option<&mut UploadState>,
+    report: &mut Vec<UploadReportEntry>,
+) -> Result<()> {
+    let max_file_size = matches
+        .get_one::<u64>("max_file_size")
+        .copied()
+        .or_else(|| {
+            upload_context
+                .chunk_upload_options
+                .map(|options| options.max_file_size)
+                .filter(|&size| size > 0)
+        });
+    if let Some(max_file_size) = max_file_size {
+        processor.skip_oversized_sources(max_file_size);
+    }
+    if let Some(state) = &state {
+        processor.skip_unchanged_sources(state, upload_context.dist);
+    }
+
+    if matches.get_flag("dry_run") {
+        processor.dry_run(upload_context, dry_run_json)?;
+        return Ok(());
+    }
+
+    let result = if matches.get_flag("strict") {
+        processor.upload_strict(upload_context)
+    } else {
+        processor.upload(upload_context)
+    };
+    report.extend(processor.report(result.as_ref().err()));
+    if let (Ok(_), Some(state)) = (&result, state) {
+        processor.record_upload_state(state, upload_context.dist);
+    }
+    result.map(|_| ())
+}
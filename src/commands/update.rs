@@ -1,18 +1,51 @@
 use std::env;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, format_err, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 
-use crate::utils::update::{assert_updatable, can_update_sentrycli, get_latest_sentrycli_release};
+use crate::config::Config;
+use crate::utils::system::{print_error, QuietExit};
+use crate::utils::update::{
+    assert_updatable, can_update_sentrycli, get_latest_sentrycli_release, UpdateChannel,
+};
+
+/// The exit code of `update --check` if a newer version is available.
+const UPDATE_AVAILABLE_EXIT_CODE: i32 = 10;
+/// The exit code of `update --check` if the latest version could not be determined.
+const CHECK_FAILED_EXIT_CODE: i32 = 11;
 
 pub fn make_command(command: Command) -> Command {
-    let command = command.about("Update the sentry-cli executable.").arg(
-        Arg::new("force")
-            .long("force")
-            .short('f')
-            .action(ArgAction::SetTrue)
-            .help("Force the update even if the latest version is already installed."),
-    );
+    let command = command
+        .about("Update the sentry-cli executable.")
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .short('f')
+                .action(ArgAction::SetTrue)
+                .conflicts_with("check")
+                .help("Force the update even if the latest version is already installed."),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Only check whether an update is available, without installing it. \
+                     Exits with 0 if sentry-cli is up to date, with 10 if an update is \
+                     available and with 11 if the latest version could not be determined, \
+                     for instance because the network is unavailable.",
+                ),
+        )
+        .arg(
+            Arg::new("channel")
+                .long("channel")
+                .value_name("CHANNEL")
+                .value_parser(["stable", "rc"])
+                .help(
+                    "The release channel to look for updates in. \
+                     [default: the `update.channel` config value, otherwise stable]",
+                ),
+        );
 
     if can_update_sentrycli() {
         command.hide(true)
@@ -25,11 +58,20 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     // Disable update check in case of errors
     env::set_var("SENTRY_DISABLE_UPDATE_CHECK", "true");
 
+    let channel = match matches.get_one::<String>("channel") {
+        Some(channel) => channel.parse::<UpdateChannel>()?,
+        None => Config::current().get_update_channel()?,
+    };
+
+    if matches.get_flag("check") {
+        return check_for_update(channel);
+    }
+
     // Aborts with an error if this installation is not updatable.
     assert_updatable()?;
 
     let exe = env::current_exe()?;
-    let update = get_latest_sentrycli_release()?;
+    let update = get_latest_sentrycli_release(channel)?;
     if !update.have_version_info() {
         bail!("Could not get the latest release version.");
     }
@@ -57,3 +99,33 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     println!("Updated to {}!", update.latest_version());
     Ok(())
 }
+
+fn check_for_update(channel: UpdateChannel) -> Result<()> {
+    let update = get_latest_sentrycli_release(channel).and_then(|update| {
+        if update.have_version_info() {
+            Ok(update)
+        } else {
+            Err(format_err!("Could not get the latest release version."))
+        }
+    });
+    let update = match update {
+        Ok(update) => update,
+        Err(err) => {
+            // Exit with a distinct code, so that scripts can tell a failed check
+            // apart from other errors.
+            print_error(&err);
+            return Err(QuietExit(CHECK_FAILED_EXIT_CODE).into());
+        }
+    };
+
+    println!("Current version: {}", update.current_version());
+    println!("Latest version ({channel}): {}", update.latest_version());
+
+    if update.is_update_available() {
+        println!("An update is available, run sentry-cli update to install it.");
+        return Err(QuietExit(UPDATE_AVAILABLE_EXIT_CODE).into());
+    }
+
+    println!("Already up to date!");
+    Ok(())
+}
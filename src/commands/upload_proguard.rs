@@ -19,6 +19,7 @@ use crate::config::Config;
 use crate::utils::android::dump_proguard_uuids_as_properties;
 use crate::utils::args::ArgExt;
 use crate::utils::fs::TempFile;
+use crate::utils::logging::quiet_println;
 use crate::utils::proguard_upload;
 use crate::utils::system::QuietExit;
 use crate::utils::ui::{copy_with_progress, make_byte_progress_bar};
@@ -136,6 +137,8 @@ pub fn make_command(command: Command) -> Command {
                 .value_parser(Uuid::parse_str)
                 .help(
                     "Explicitly override the UUID of the mapping file with another one.{n}\
+                     By default, the UUID is derived from the contents of the mapping file, \
+                     so uploading the same mapping file again yields the same UUID.{n}\
                      This should be used with caution as it means that you can upload \
                      multiple mapping files if you don't take care.  This however can \
                      be useful if you have a build process in which you need to know \
@@ -199,6 +202,15 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
+    for mapping in &mappings {
+        quiet_println!(
+            "{} found mapping {} ({})",
+            style(">").dim(),
+            style(mapping.uuid).yellow(),
+            mapping.path.display()
+        );
+    }
+
     let api = Api::current();
     let config = Config::current();
 
@@ -234,12 +246,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         proguard_upload::chunk_upload(&mappings, &chunk_upload_options, &org, &project)?;
     } else {
         if mappings.is_empty() && matches.get_flag("require_one") {
-            println!();
+            quiet_println!();
             eprintln!("{}", style("error: found no mapping files to upload").red());
             return Err(QuietExit(1).into());
         }
 
-        println!("{} compressing mappings", style(">").dim());
+        quiet_println!("{} compressing mappings", style(">").dim());
         let tf = TempFile::create()?;
         {
             let mut zip = zip::ZipWriter::new(tf.open()?);
@@ -261,11 +273,11 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
 
         if matches.get_flag("no_upload") {
-            println!("{} skipping upload.", style(">").dim());
+            quiet_println!("{} skipping upload.", style(">").dim());
             return Ok(());
         }
 
-        println!("{} uploading mappings", style(">").dim());
+        quiet_println!("{} uploading mappings", style(">").dim());
         (org, project) = config.get_org_and_project(matches)?;
 
         info!(
@@ -278,15 +290,15 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         let rv = authenticated_api
             .region_specific(&org)
             .upload_dif_archive(&project, tf.path())?;
-        println!(
+        quiet_println!(
             "{} Uploaded a total of {} new mapping files",
             style(">").dim(),
             style(rv.len()).yellow()
         );
         if !rv.is_empty() {
-            println!("Newly uploaded debug symbols:");
+            quiet_println!("Newly uploaded debug symbols:");
             for df in rv {
-                println!("  {}", style(&df.id()).dim());
+                quiet_println!("  {}", style(&df.id()).dim());
             }
         }
     }
@@ -17,9 +17,15 @@ pub const CONFIG_RC_FILE_NAME: &str = ".sentryclirc";
 /// The relative path of the configuration file in dirs::config_dir()
 pub const CONFIG_INI_FILE_PATH: &str = "sentry/sentrycli.ini";
 
-/// The release registry URL where the latest released version of sentry-cli can be found
-pub const RELEASE_REGISTRY_LATEST_URL: &str =
-    "https://release-registry.services.sentry.io/apps/sentry-cli/latest";
+/// The release registry URL where the released versions of sentry-cli can be found
+pub const RELEASE_REGISTRY_URL: &str =
+    "https://release-registry.services.sentry.io/apps/sentry-cli";
+
+/// The OAuth client id of sentry-cli, used for the device authorization flow.
+pub const OAUTH_CLIENT_ID: &str = "sentry-cli";
+
+/// The scopes requested for auth tokens issued through the device authorization flow.
+pub const OAUTH_SCOPES: &str = "org:read project:read project:write project:releases";
 
 /// The file extension of the binary (.exe or empty string)
 #[cfg(windows)]
@@ -45,7 +51,20 @@ pub const DEFAULT_MAX_DIF_SIZE: u64 = 2 * 1024 * 1024 * 1024; // 2GB
 pub const DEFAULT_MAX_DIF_ITEM_SIZE: u64 = 1024 * 1024; // 1MB
 /// Default maximum DIF upload size.
 pub const DEFAULT_MAX_DIF_UPLOAD_SIZE: u64 = 35 * 1024 * 1024; // 35MB
+/// Default maximum size of a single event attachment, the default limit of Sentry.
+pub const DEFAULT_MAX_ATTACHMENT_SIZE: u64 = 100 * 1024 * 1024; // 100MB
+/// Default time limit for establishing a connection to the server.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default time limit for small API requests, such as creating a release.
+pub const DEFAULT_API_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default time limit for requests transferring files, such as chunk uploads.
+pub const DEFAULT_TRANSFER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Size above which JSON request bodies are sent gzip compressed.
+pub const REQUEST_COMPRESSION_THRESHOLD: usize = 16 * 1024; // 16KB
 /// Default maximum time to wait for file assembly.
 pub const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(5 * 60);
+/// Maximum time to wait before retrying a rate limited request. Longer waits
+/// requested by the server are shortened to this.
+pub const MAX_RATE_LIMIT_DELAY: Duration = Duration::from_secs(60);
 
 include!(concat!(env!("OUT_DIR"), "/constants.gen.rs"));
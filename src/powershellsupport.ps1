@@ -0,0 +1,62 @@
+$_SENTRY_TRACEBACK_FILE = '___SENTRY_TRACEBACK_FILE___'
+$_SENTRY_LOG_FILE = '___SENTRY_LOG_FILE___'
+$_SENTRY_TRANSCRIPT_FILE = '___SENTRY_TRANSCRIPT_FILE___'
+
+function _sentry_write_lines($Path, $Lines) {
+  # Set-Content -Encoding UTF8 writes a byte order mark on PowerShell 5.1
+  [System.IO.File]::WriteAllLines($Path, [string[]]@($Lines))
+}
+
+function _sentry_err_trap {
+  param($ErrorRecord)
+  if ($null -eq $ErrorRecord) {
+    $ErrorRecord = $Error[0]
+  }
+
+  $_command = "unknown"
+  $_script = ""
+  $_info = $ErrorRecord.InvocationInfo
+  if ($null -ne $_info) {
+    if ($_info.Line) {
+      $_command = $_info.Line.Trim()
+    }
+    if ($_info.ScriptName) {
+      $_script = $_info.ScriptName
+    }
+  }
+  $_exit_code = 1
+  if ($global:LASTEXITCODE) {
+    $_exit_code = $global:LASTEXITCODE
+  }
+
+  $_traceback = @(
+    "@command:$_command",
+    "@exit_code:$_exit_code",
+    "@script:$_script",
+    "@error_type:$($ErrorRecord.Exception.GetType().FullName)",
+    "@error_message:$($ErrorRecord.Exception.Message -replace "`r?`n", ' ')"
+  )
+  if ($ErrorRecord.ScriptStackTrace) {
+    $_traceback += $ErrorRecord.ScriptStackTrace -split "`r?`n"
+  }
+  _sentry_write_lines $_SENTRY_TRACEBACK_FILE $_traceback
+
+  try { Stop-Transcript | Out-Null } catch { }
+  $_lines = @()
+  $_in_header = $false
+  if (Test-Path $_SENTRY_TRANSCRIPT_FILE) {
+    foreach ($_line in Get-Content $_SENTRY_TRANSCRIPT_FILE) {
+      if ($_line -match '^[*]{10,}$') {
+        $_in_header = -not $_in_header
+      } elseif (-not $_in_header -and $_line -notmatch '^Transcript started, output file is ') {
+        $_lines += $_line
+      }
+    }
+  }
+  _sentry_write_lines $_SENTRY_LOG_FILE @($_lines | Select-Object -Last 100)
+
+  $env:SENTRY_LAST_EVENT = & ___SENTRY_CLI___ powershell-hook --send-event --traceback $_SENTRY_TRACEBACK_FILE ___SENTRY_TAGS___ ___SENTRY_RELEASE___ --log $_SENTRY_LOG_FILE ___SENTRY_NO_ENVIRON___
+  Remove-Item -Force -ErrorAction SilentlyContinue $_SENTRY_TRACEBACK_FILE, $_SENTRY_LOG_FILE, $_SENTRY_TRANSCRIPT_FILE
+}
+
+Start-Transcript -Path $_SENTRY_TRANSCRIPT_FILE -Append | Out-Null
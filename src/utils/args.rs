@@ -2,6 +2,8 @@ use anyhow::{bail, Result};
 use chrono::{DateTime, TimeZone, Utc};
 use clap::{Arg, ArgAction, Command};
 
+use crate::utils::upload_metadata::meta_parser;
+
 fn validate_org(v: &str) -> Result<String, String> {
     if v.contains('/') || v == "." || v == ".." || v.contains(' ') {
         Err(
@@ -28,25 +30,26 @@ pub fn validate_project(v: &str) -> Result<String, String> {
     }
 }
 
-fn validate_release(v: &str) -> Result<String, String> {
-    if v.trim() != v {
-        Err(
-            "Invalid release version. Releases must not contain leading or trailing spaces."
-                .to_string(),
-        )
-    } else if v.is_empty()
-        || v == "."
-        || v == ".."
-        || v.find(&['\n', '\t', '\x0b', '\x0c', '\t', '/'][..])
-            .is_some()
+/// Adds the `--sanitize` flag for release versions, unless the command has it already.
+fn sanitize_release_arg(command: Command, global: bool) -> Command {
+    if command
+        .get_arguments()
+        .any(|arg| arg.get_id() == "sanitize_release")
     {
-        Err(
-            "Invalid release version. Slashes and certain whitespace characters are not permitted."
-                .to_string(),
-        )
-    } else {
-        Ok(v.to_owned())
+        return command;
     }
+
+    command.arg(
+        Arg::new("sanitize_release")
+            .long("sanitize")
+            .action(ArgAction::SetTrue)
+            .global(global)
+            .help(
+                "Trim the release version and replace slashes and whitespace in it, \
+                instead of rejecting versions that Sentry does not permit. Can also be \
+                enabled with the `defaults.sanitize_release` config.",
+            ),
+    )
 }
 
 pub fn validate_distribution(v: &str) -> Result<String, String> {
@@ -66,14 +69,16 @@ pub fn validate_distribution(v: &str) -> Result<String, String> {
 }
 
 pub fn get_timestamp(value: &str) -> Result<DateTime<Utc>> {
-    if let Ok(int) = value.parse::<i64>() {
+    if value == "now" {
+        Ok(Utc::now())
+    } else if let Ok(int) = value.parse::<i64>() {
         Ok(Utc.timestamp_opt(int, 0).single().unwrap())
     } else if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
         Ok(dt.with_timezone(&Utc))
     } else if let Ok(dt) = DateTime::parse_from_rfc2822(value) {
         Ok(dt.with_timezone(&Utc))
     } else {
-        bail!("Not in valid format. Unix timestamp or ISO 8601 date expected.");
+        bail!("Not in valid format. Unix timestamp, ISO 8601 date or `now` expected.");
     }
 }
 
@@ -81,7 +86,11 @@ pub trait ArgExt: Sized {
     fn org_arg(self) -> Self;
     fn project_arg(self, multiple: bool) -> Self;
     fn release_arg(self) -> Self;
+    fn release_from_git_arg(self) -> Self;
     fn version_arg(self, global: bool) -> Self;
+    fn ignore_file_args(self) -> Self;
+    fn follow_symlinks_args(self) -> Self;
+    fn upload_metadata_args(self) -> Self;
 }
 
 impl<'a: 'b, 'b> ArgExt for Command {
@@ -115,27 +124,141 @@ impl<'a: 'b, 'b> ArgExt for Command {
     }
 
     fn release_arg(self) -> Command {
-        self.arg(
+        let command = self.arg(
             Arg::new("release")
                 .value_name("RELEASE")
                 .long("release")
                 .short('r')
                 .global(true)
                 .allow_hyphen_values(true)
-                .value_parser(validate_release)
                 .help("The release slug."),
+        );
+        sanitize_release_arg(command, true)
+    }
+
+    fn release_from_git_arg(self) -> Command {
+        self.arg(
+            Arg::new("release_from_git")
+                .long("release-from-git")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("release")
+                .help(
+                    "Use the release name detected from the environment and version \
+                    control system, like `releases propose-version` does, instead of \
+                    --release.",
+                ),
         )
     }
 
     fn version_arg(self, global: bool) -> Command {
-        self.arg(
+        let command = self.arg(
             Arg::new("version")
                 .value_name("VERSION")
                 // either specified for subcommands (global=true) or for this command (required=true)
                 .required(!global)
                 .global(global)
-                .value_parser(validate_release)
                 .help("The version of the release"),
+        );
+        sanitize_release_arg(command, global)
+    }
+
+    fn ignore_file_args(self) -> Command {
+        self.arg(
+            Arg::new("ignore_file")
+                .long("ignore-file")
+                .short('I')
+                .value_name("IGNORE_FILE")
+                .action(ArgAction::Append)
+                .help(
+                    "Ignore all files and folders specified in the given \
+                    ignore file, e.g. .gitignore. Patterns are relative to each \
+                    searched path. Can be given multiple times.",
+                ),
+        )
+        .arg(
+            Arg::new("use_gitignore")
+                .long("use-gitignore")
+                .action(ArgAction::SetTrue)
+                .overrides_with("no_gitignore")
+                .help(
+                    "Respect .gitignore and .git/info/exclude files found in \
+                    the searched paths.",
+                ),
+        )
+        .arg(
+            Arg::new("no_gitignore")
+                .long("no-gitignore")
+                .action(ArgAction::SetTrue)
+                .overrides_with("use_gitignore")
+                .help("Do not respect .gitignore files. This is the default."),
+        )
+    }
+
+    fn follow_symlinks_args(self) -> Command {
+        self.arg(
+            Arg::new("follow_symlinks")
+                .long("follow-symlinks")
+                .action(ArgAction::SetTrue)
+                .overrides_with("no_follow_symlinks")
+                .help(
+                    "Follow symbolic links in the searched paths. This is the default. \
+                    Symlinks to a parent directory are skipped, and files reached \
+                    through several links are collected once.",
+                ),
+        )
+        .arg(
+            Arg::new("no_follow_symlinks")
+                .long("no-follow-symlinks")
+                .action(ArgAction::SetTrue)
+                .overrides_with("follow_symlinks")
+                .help("Skip symbolic links in the searched paths."),
         )
     }
+
+    fn upload_metadata_args(self) -> Command {
+        self.arg(Arg::new("note").long("note").value_name("NOTE").help(
+            "Adds an optional note to the uploaded bundles. The note is also \
+                    recorded in the upload report.",
+        ))
+        .arg(
+            Arg::new("meta")
+                .long("meta")
+                .value_name("KEY=VALUE")
+                .value_parser(meta_parser)
+                .action(ArgAction::Append)
+                .help(
+                    "Adds a metadata entry to the uploaded bundles, for instance the CI \
+                    pipeline that produced them. Keys must be printable ASCII. The \
+                    metadata is also recorded in the upload report. Can be given \
+                    multiple times.",
+                ),
+        )
+    }
+}
+
+#[test]
+fn test_get_timestamp() {
+    let expected = Utc.with_ymd_and_hms(2024, 1, 15, 8, 30, 0).unwrap();
+
+    assert_eq!(get_timestamp("1705307400").unwrap(), expected);
+    assert_eq!(get_timestamp("2024-01-15T08:30:00Z").unwrap(), expected);
+    assert_eq!(
+        get_timestamp("2024-01-15T10:30:00+02:00").unwrap(),
+        expected
+    );
+    assert_eq!(
+        get_timestamp("2024-01-15T03:00:00-05:30").unwrap(),
+        expected
+    );
+    assert_eq!(
+        get_timestamp("Mon, 15 Jan 2024 08:30:00 +0000").unwrap(),
+        expected
+    );
+
+    let before = Utc::now();
+    let now = get_timestamp("now").unwrap();
+    assert!(now >= before && now <= Utc::now());
+
+    assert!(get_timestamp("2024-01-15").is_err());
+    assert!(get_timestamp("yesterday").is_err());
 }
@@ -5,22 +5,35 @@
 //!
 //! See `BatchedSliceExt::batches` for more information.
 
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Result;
-use log::info;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use indicatif::HumanBytes;
+use log::{debug, info, warn};
 use parking_lot::RwLock;
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
-use sha1_smol::Digest;
+use serde::{Deserialize, Serialize};
+use sha1_smol::{Digest, Sha1};
 
-use crate::api::{Api, ChunkUploadOptions};
+use crate::api::{Api, ChunkCompression, ChunkUploadOptions};
 use crate::utils::progress::{ProgressBar, ProgressBarMode, ProgressStyle};
 
 /// Timeout for polling all assemble endpoints.
 pub const ASSEMBLE_POLL_INTERVAL: Duration = Duration::from_millis(1000);
 
+/// How long cached chunk upload options are reused before fetching them again.
+const CHUNK_UPLOAD_OPTIONS_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The size of the buffer used to read data while computing checksums.
+const CHECKSUM_BUFFER_SIZE: usize = 64 * 1024;
+
 /// A trait required by `BatchedSliceExt` to determine the logical size of a
 /// batch. Semantics and unit of the size depend on the batching use case,
 /// likely a number of bytes. See `BatchedSliceExt` for more information.
@@ -154,14 +167,103 @@ impl<'data> ItemSize for Chunk<'data> {
     }
 }
 
+/// Returns the SHA1 hash for the entire input, as well as each chunk of it. The
+/// `chunk_size` must be a power of two.
+///
+/// The input is read with a buffer of a fixed size, so that memory use does
+/// not depend on the size of the input or the chunk size. Memory mapped files
+/// can be passed as slices, which are only paged in while they are hashed.
+pub fn get_sha1_checksums<R: Read>(
+    mut reader: R,
+    chunk_size: u64,
+) -> Result<(Digest, Vec<Digest>)> {
+    if !chunk_size.is_power_of_two() {
+        bail!("Chunk size must be a power of two");
+    }
+
+    let mut buffer = vec![0u8; CHECKSUM_BUFFER_SIZE];
+    let mut total_sha = Sha1::new();
+    let mut chunk_sha = Sha1::new();
+    let mut chunk_len = 0;
+    let mut chunks = Vec::new();
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut data = &buffer[..read];
+        total_sha.update(data);
+
+        // A read can end in the middle of a chunk, or span several chunks if
+        // they are smaller than the buffer.
+        while !data.is_empty() {
+            let len = (chunk_size - chunk_len).min(data.len() as u64) as usize;
+            chunk_sha.update(&data[..len]);
+            chunk_len += len as u64;
+            data = &data[len..];
+
+            if chunk_len == chunk_size {
+                chunks.push(chunk_sha.digest());
+                chunk_sha.reset();
+                chunk_len = 0;
+            }
+        }
+    }
+
+    if chunk_len > 0 {
+        chunks.push(chunk_sha.digest());
+    }
+
+    Ok((total_sha.digest(), chunks))
+}
+
+/// Selects the compression used to upload chunks, given the compression requested by the user
+/// and the compression algorithms accepted by the server.
+///
+/// Without an explicit request, the best available compression mechanism is used. We assume that
+/// every compression algorithm has been implemented for uploading. In case the server does not
+/// support compression, we fall back to `Uncompressed`. If the server does not accept the
+/// requested compression, we fall back to gzip, if possible.
+pub fn negotiate_compression(
+    requested: Option<ChunkCompression>,
+    accepted: &[ChunkCompression],
+) -> ChunkCompression {
+    let requested = match requested {
+        Some(requested) => requested,
+        None => return accepted.iter().max().cloned().unwrap_or_default(),
+    };
+
+    if requested == ChunkCompression::Uncompressed || accepted.contains(&requested) {
+        return requested;
+    }
+
+    let fallback = if accepted.contains(&ChunkCompression::Gzip) {
+        ChunkCompression::Gzip
+    } else {
+        ChunkCompression::Uncompressed
+    };
+
+    warn!(
+        "The server does not support {} compression, falling back to {}.",
+        requested, fallback
+    );
+    fallback
+}
+
 /// Concurrently uploads chunks in batches. The batch size and number of concurrent requests is
-/// controlled by `chunk_options`.
+/// controlled by `chunk_options`. The chunks are compressed as negotiated by
+/// `negotiate_compression` for the requested `compression`.
 ///
 /// This function blocks until all chunks have been uploaded.
 pub fn upload_chunks(
     chunks: &[Chunk<'_>],
     chunk_options: &ChunkUploadOptions,
     progress_style: ProgressStyle,
+    compression: Option<ChunkCompression>,
 ) -> Result<()> {
     let total_bytes = chunks.iter().map(|&Chunk((_, data))| data.len()).sum();
 
@@ -169,19 +271,10 @@ pub fn upload_chunks(
     // multiple requests to simulate one continuous upload to the user. Since we
     // have to embed the progress bar into a ProgressBarMode and move it into
     // `Api::upload_chunks`, the progress bar is created in an Arc.
-    let pb = Arc::new(ProgressBar::new(total_bytes));
+    let pb = Arc::new(ProgressBar::new(total_bytes).with_phase("chunk_upload"));
     pb.set_style(progress_style);
 
-    // Select the best available compression mechanism. We assume that every
-    // compression algorithm has been implemented for uploading, except `Other`
-    // which is used for unknown compression algorithms. In case the server
-    // does not support compression, we fall back to `Uncompressed`.
-    let compression = chunk_options
-        .compression
-        .iter()
-        .max()
-        .cloned()
-        .unwrap_or_default();
+    let compression = negotiate_compression(compression, &chunk_options.compression);
 
     info!("using '{}' compression for chunk upload", compression);
 
@@ -191,17 +284,21 @@ pub fn upload_chunks(
     // occurs, all subsequent requests will be cancelled and the error returned.
     // Otherwise, the after every successful update, the overall progress is
     // updated and rendered.
-    let batches: Vec<_> = chunks
-        .batches(chunk_options.max_size, chunk_options.max_chunks)
-        .collect();
+    let batches = request_batches(chunks, chunk_options)?;
 
     // We count the progress of each batch separately to avoid synchronization
     // issues. For a more consistent progress bar in repeated uploads, we also
     // add the already uploaded bytes to the progress bar.
     let bytes = Arc::new(RwLock::new(vec![0u64; batches.len()]));
 
+    // The data of a batch is only read and compressed once its request is
+    // sent. We keep track of the chunk data held by requests in flight and log
+    // its peak for debugging.
+    let buffered = AtomicU64::new(0);
+    let peak_buffered = AtomicU64::new(0);
+
     let pool = ThreadPoolBuilder::new()
-        .num_threads(chunk_options.concurrency as usize)
+        .num_threads(chunk_options.concurrency())
         .build()?;
 
     pool.install(|| {
@@ -209,13 +306,410 @@ pub fn upload_chunks(
             .into_par_iter()
             .enumerate()
             .map(|(index, (batch, size))| {
+                debug_assert!(batch
+                    .iter()
+                    .all(|chunk| chunk.size() <= chunk_options.chunk_size));
+
+                let in_flight = buffered.fetch_add(size, Ordering::SeqCst) + size;
+                peak_buffered.fetch_max(in_flight, Ordering::SeqCst);
+
                 let mode = ProgressBarMode::Shared((pb.clone(), size, index, bytes.clone()));
-                Api::current().upload_chunks(&chunk_options.url, batch, mode, compression)
+                let result =
+                    Api::current().upload_chunks(&chunk_options.url, batch, mode, compression);
+                buffered.fetch_sub(size, Ordering::SeqCst);
+                result
             })
             .collect::<Result<(), _>>()
     })?;
 
+    debug!(
+        "requests in flight held at most {} bytes of chunk data",
+        peak_buffered.into_inner()
+    );
+
     pb.finish_with_duration("Uploading");
 
     Ok(())
 }
+
+/// Splits chunks into the batches sent in one request each, so that every
+/// request stays within the server's `maxRequestSize` and `chunksPerRequest`.
+///
+/// Fails before anything is sent if a single chunk exceeds the maximum
+/// request size, since the server would reject the request containing it.
+fn request_batches<'data, 'chunk>(
+    chunks: &'chunk [Chunk<'data>],
+    chunk_options: &ChunkUploadOptions,
+) -> Result<Vec<(&'chunk [Chunk<'data>], u64)>> {
+    let max_size = chunk_options.max_request_size();
+    if let Some(chunk) = chunks.iter().find(|chunk| chunk.size() > max_size) {
+        bail!(
+            "Cannot upload chunk {} of {}, since the server only accepts requests of up to {}",
+            (chunk.0).0,
+            HumanBytes(chunk.size()),
+            HumanBytes(max_size)
+        );
+    }
+
+    let batches: Vec<_> =
+        Batches::new(chunks, max_size, chunk_options.chunks_per_request()).collect();
+    debug!(
+        "uploading {} chunks in {} requests of at most {} chunks and {} each",
+        chunks.len(),
+        batches.len(),
+        chunk_options.chunks_per_request(),
+        HumanBytes(max_size)
+    );
+    Ok(batches)
+}
+
+/// A chunk upload options response stored in the cache directory.
+#[derive(Serialize, Deserialize)]
+struct CachedChunkUploadOptions {
+    fetched_at: DateTime<Utc>,
+    options: serde_json::Value,
+}
+
+fn chunk_upload_options_cache_path(cache_dir: &Path, base_url: &str, org: &str) -> PathBuf {
+    let key = Sha1::from(format!("{base_url}\n{org}")).digest();
+    cache_dir.join("chunk-upload").join(format!("{key}.json"))
+}
+
+/// Loads the cached chunk upload options of an organization on the given server.
+///
+/// Returns `None` if there is no cache entry, or if it is expired or cannot be
+/// read, in which case the options should be fetched from the server.
+pub fn load_cached_chunk_upload_options(
+    cache_dir: &Path,
+    base_url: &str,
+    org: &str,
+) -> Option<ChunkUploadOptions> {
+    let path = chunk_upload_options_cache_path(cache_dir, base_url, org);
+    let cached: CachedChunkUploadOptions = serde_json::from_slice(&fs::read(&path).ok()?)
+        .map_err(|err| debug!("Ignoring invalid cache entry {}: {}", path.display(), err))
+        .ok()?;
+
+    // Entries from the future are treated as expired.
+    let age = Utc::now()
+        .signed_duration_since(cached.fetched_at)
+        .to_std()
+        .ok()?;
+    if age > CHUNK_UPLOAD_OPTIONS_TTL {
+        debug!("Ignoring expired cache entry {}", path.display());
+        return None;
+    }
+
+    let options = serde_json::from_value(cached.options).ok()?;
+    info!("Using cached chunk upload options from {}", path.display());
+    Some(options)
+}
+
+/// Stores the chunk upload options response of an organization on the given
+/// server in the cache directory.
+///
+/// Failures are only logged, since the options can always be fetched again.
+pub fn store_cached_chunk_upload_options(
+    cache_dir: &Path,
+    base_url: &str,
+    org: &str,
+    options: &serde_json::Value,
+) {
+    let path = chunk_upload_options_cache_path(cache_dir, base_url, org);
+    let cached = CachedChunkUploadOptions {
+        fetched_at: Utc::now(),
+        options: options.clone(),
+    };
+
+    let result = fs::create_dir_all(cache_dir.join("chunk-upload"))
+        .map_err(anyhow::Error::from)
+        .and_then(|()| Ok(fs::write(&path, serde_json::to_vec(&cached)?)?));
+    if let Err(err) = result {
+        warn!(
+            "Failed to cache chunk upload options in {}: {}",
+            path.display(),
+            err
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ChunkCompression::{Brotli, Gzip, Uncompressed, Zstd};
+
+    /// Computes checksums by hashing the chunks of the entire input at once.
+    fn reference_sha1_checksums(data: &[u8], chunk_size: u64) -> (Digest, Vec<Digest>) {
+        let chunks = data
+            .chunks(chunk_size as usize)
+            .map(|chunk| Sha1::from(chunk).digest())
+            .collect();
+        (Sha1::from(data).digest(), chunks)
+    }
+
+    /// A reader returning at most a few bytes at a time.
+    struct ShortReader<'a>(&'a [u8]);
+
+    impl Read for ShortReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(1000);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_sha1_checksums_match_reference() {
+        let fixtures = [
+            "tests/integration/_fixtures/elf-Linux-ARMv7-ls",
+            "tests/integration/_fixtures/SrcGenSampleApp.pdb",
+            "tests/integration/_fixtures/proguard.txt",
+            "tests/integration/_fixtures/bundle.min.js.map",
+        ];
+
+        for fixture in fixtures {
+            let data = fs::read(fixture).unwrap();
+            // Chunks smaller than, equal to, and larger than the read buffer
+            for chunk_size in [1024, CHECKSUM_BUFFER_SIZE as u64, 1 << 23] {
+                let expected = reference_sha1_checksums(&data, chunk_size);
+                assert_eq!(
+                    get_sha1_checksums(&data[..], chunk_size).unwrap(),
+                    expected,
+                    "{fixture} with chunk size {chunk_size}"
+                );
+                assert_eq!(
+                    get_sha1_checksums(ShortReader(&data), chunk_size).unwrap(),
+                    expected,
+                    "{fixture} with short reads and chunk size {chunk_size}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sha1_checksums_exact_multiple_and_empty() {
+        let data = vec![7u8; 4 * 4096];
+        let (_, chunks) = get_sha1_checksums(&data[..], 4096).unwrap();
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(
+            get_sha1_checksums(&data[..], 4096).unwrap(),
+            reference_sha1_checksums(&data, 4096)
+        );
+
+        let (checksum, chunks) = get_sha1_checksums(&[][..], 4096).unwrap();
+        assert_eq!(checksum, Sha1::new().digest());
+        assert!(chunks.is_empty());
+    }
+
+    #[test]
+    fn test_sha1_checksums_chunk_size_power_of_two() {
+        assert!(get_sha1_checksums(&[0u8; 10][..], 1000).is_err());
+    }
+
+    #[test]
+    fn test_negotiate_compression_defaults_to_best() {
+        assert_eq!(negotiate_compression(None, &[]), Uncompressed);
+        assert_eq!(negotiate_compression(None, &[Gzip]), Gzip);
+        assert_eq!(negotiate_compression(None, &[Brotli, Gzip]), Brotli);
+        assert_eq!(negotiate_compression(None, &[Gzip, Zstd, Brotli]), Zstd);
+    }
+
+    #[test]
+    fn test_negotiate_compression_accepted() {
+        assert_eq!(negotiate_compression(Some(Gzip), &[Gzip, Brotli]), Gzip);
+        assert_eq!(negotiate_compression(Some(Brotli), &[Gzip, Brotli]), Brotli);
+        assert_eq!(negotiate_compression(Some(Zstd), &[Zstd]), Zstd);
+    }
+
+    #[test]
+    fn test_negotiate_compression_uncompressed_is_always_accepted() {
+        assert_eq!(negotiate_compression(Some(Uncompressed), &[]), Uncompressed);
+        assert_eq!(
+            negotiate_compression(Some(Uncompressed), &[Gzip, Brotli]),
+            Uncompressed
+        );
+    }
+
+    #[test]
+    fn test_negotiate_compression_falls_back() {
+        assert_eq!(negotiate_compression(Some(Zstd), &[Gzip, Brotli]), Gzip);
+        assert_eq!(negotiate_compression(Some(Brotli), &[Gzip]), Gzip);
+        assert_eq!(negotiate_compression(Some(Zstd), &[]), Uncompressed);
+        assert_eq!(negotiate_compression(Some(Gzip), &[Brotli]), Uncompressed);
+    }
+
+    fn chunk_upload_options_json() -> serde_json::Value {
+        serde_json::json!({
+            "url": "https://sentry.io/api/0/organizations/wat-org/chunk-upload/",
+            "chunkSize": 8388608,
+            "chunksPerRequest": 64,
+            "maxRequestSize": 33554432,
+            "concurrency": 8,
+            "hashAlgorithm": "sha1",
+        })
+    }
+
+    fn chunk_upload_options(limits: serde_json::Value) -> ChunkUploadOptions {
+        let mut options = serde_json::json!({
+            "url": "https://sentry.io/api/0/organizations/wat-org/chunk-upload/",
+            "chunkSize": 1024,
+            "hashAlgorithm": "sha1",
+        });
+        options
+            .as_object_mut()
+            .unwrap()
+            .extend(limits.as_object().unwrap().clone());
+        serde_json::from_value(options).unwrap()
+    }
+
+    /// Returns the number of chunks in each request.
+    fn batch_lengths(chunks: &[Chunk<'_>], options: &ChunkUploadOptions) -> Vec<usize> {
+        request_batches(chunks, options)
+            .unwrap()
+            .iter()
+            .map(|(batch, _)| batch.len())
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_upload_limits_fall_back_to_defaults() {
+        let missing = chunk_upload_options(serde_json::json!({}));
+        let zero = chunk_upload_options(serde_json::json!({
+            "chunksPerRequest": 0,
+            "maxRequestSize": 0,
+            "maxFileSize": 0,
+            "concurrency": 0,
+        }));
+
+        for options in [missing, zero] {
+            assert_eq!(options.chunks_per_request(), 64);
+            assert_eq!(options.max_request_size(), 32 * 1024 * 1024);
+            assert_eq!(options.concurrency(), 4);
+            assert_eq!(options.max_file_size(), None);
+
+            let data = vec![0u8; 100 * 1024];
+            let chunks: Vec<_> = data
+                .chunks(1024)
+                .map(|chunk| Chunk((Sha1::from(chunk).digest(), chunk)))
+                .collect();
+            assert_eq!(batch_lengths(&chunks, &options), [64, 36]);
+        }
+    }
+
+    #[test]
+    fn test_chunk_upload_limits_advertised() {
+        let options = chunk_upload_options(serde_json::json!({
+            "chunksPerRequest": 8,
+            "maxRequestSize": 4096,
+            "maxFileSize": 1_000_000,
+            "concurrency": 2,
+        }));
+        assert_eq!(options.chunks_per_request(), 8);
+        assert_eq!(options.max_request_size(), 4096);
+        assert_eq!(options.concurrency(), 2);
+        assert_eq!(options.max_file_size(), Some(1_000_000));
+    }
+
+    #[test]
+    fn test_request_batches_stay_within_limits() {
+        let data = vec![0u8; 10 * 1024 + 100];
+        let chunks: Vec<_> = data
+            .chunks(1024)
+            .map(|chunk| Chunk((Sha1::from(chunk).digest(), chunk)))
+            .collect();
+
+        // The request size allows two and a half chunks, so the short last
+        // chunk still fits into the last request
+        let options = chunk_upload_options(serde_json::json!({
+            "chunksPerRequest": 8,
+            "maxRequestSize": 2560,
+        }));
+        assert_eq!(batch_lengths(&chunks, &options), [2, 2, 2, 2, 3]);
+        for (_, size) in request_batches(&chunks, &options).unwrap() {
+            assert!(size <= 2560);
+        }
+
+        // The chunk count limits requests before their size does
+        let options = chunk_upload_options(serde_json::json!({
+            "chunksPerRequest": 3,
+            "maxRequestSize": 1024 * 1024,
+        }));
+        assert_eq!(batch_lengths(&chunks, &options), [3, 3, 3, 2]);
+
+        let options = chunk_upload_options(serde_json::json!({
+            "chunksPerRequest": 1,
+            "maxRequestSize": 1024,
+        }));
+        assert_eq!(batch_lengths(&chunks, &options), [1; 11]);
+    }
+
+    #[test]
+    fn test_request_batches_reject_chunks_exceeding_request_size() {
+        let data = vec![0u8; 2048];
+        let chunks: Vec<_> = data
+            .chunks(1024)
+            .map(|chunk| Chunk((Sha1::from(chunk).digest(), chunk)))
+            .collect();
+        let options = chunk_upload_options(serde_json::json!({ "maxRequestSize": 1000 }));
+
+        let err = request_batches(&chunks, &options).unwrap_err();
+        assert!(
+            err.to_string().ends_with(&format!(
+                "of {}, since the server only accepts requests of up to {}",
+                HumanBytes(1024),
+                HumanBytes(1000)
+            )),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_chunk_upload_options_cache_roundtrip() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let base_url = "https://sentry.io";
+        assert!(load_cached_chunk_upload_options(cache_dir.path(), base_url, "wat-org").is_none());
+
+        store_cached_chunk_upload_options(
+            cache_dir.path(),
+            base_url,
+            "wat-org",
+            &chunk_upload_options_json(),
+        );
+        let options = load_cached_chunk_upload_options(cache_dir.path(), base_url, "wat-org")
+            .expect("options are cached");
+        assert_eq!(options.chunk_size, 8388608);
+
+        // Entries are keyed by server and organization.
+        assert!(load_cached_chunk_upload_options(cache_dir.path(), base_url, "other").is_none());
+        assert!(
+            load_cached_chunk_upload_options(cache_dir.path(), "https://other.io", "wat-org")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_chunk_upload_options_cache_ignores_invalid_entries() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let base_url = "https://sentry.io";
+        let path = chunk_upload_options_cache_path(cache_dir.path(), base_url, "wat-org");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        fs::write(&path, b"{\"fetched_at\":").unwrap();
+        assert!(load_cached_chunk_upload_options(cache_dir.path(), base_url, "wat-org").is_none());
+
+        let expired = CachedChunkUploadOptions {
+            fetched_at: Utc::now() - chrono::Duration::minutes(6),
+            options: chunk_upload_options_json(),
+        };
+        fs::write(&path, serde_json::to_vec(&expired).unwrap()).unwrap();
+        assert!(load_cached_chunk_upload_options(cache_dir.path(), base_url, "wat-org").is_none());
+
+        let invalid_options = CachedChunkUploadOptions {
+            fetched_at: Utc::now(),
+            options: serde_json::json!({ "url": 42 }),
+        };
+        fs::write(&path, serde_json::to_vec(&invalid_options).unwrap()).unwrap();
+        assert!(load_cached_chunk_upload_options(cache_dir.path(), base_url, "wat-org").is_none());
+    }
+}
@@ -1,14 +1,31 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::fmt;
+use std::fs;
+use std::io::{Seek, Write};
 use std::path::Path;
 use std::str;
 
 use anyhow::{bail, Context, Error, Result};
+use glob::Pattern;
+use lazy_static::lazy_static;
+use log::{info, warn};
 use proguard::ProguardMapping;
-use serde::ser::{SerializeStruct, Serializer};
+use regex::Regex;
 use serde::Serialize;
-use symbolic::common::{ByteView, CodeId, DebugId, SelfCell};
+use symbolic::common::{Arch, ByteView, CodeId, DebugId, SelfCell};
+use symbolic::debuginfo::sourcebundle::{
+    SourceBundleErrorKind, SourceBundleWriter, SourceFileInfo, SourceFileType,
+};
 use symbolic::debuginfo::{Archive, FileFormat, Object, ObjectKind};
 
+use crate::utils::dif_upload::filter_bad_sources;
+
+lazy_static! {
+    // Sanitizes paths within source bundles, like `SourceBundleWriter` does.
+    static ref SANE_PATH_RE: Regex = Regex::new(r":?[/\\]+").unwrap();
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum DifType {
@@ -22,6 +39,7 @@ pub enum DifType {
     PortablePdb,
     Wasm,
     Jvm,
+    Dart,
 }
 
 impl DifType {
@@ -37,6 +55,7 @@ impl DifType {
             DifType::Proguard => "proguard",
             DifType::Wasm => "wasm",
             DifType::Jvm => "jvm",
+            DifType::Dart => "dart",
         }
     }
 
@@ -52,6 +71,7 @@ impl DifType {
             DifType::Proguard,
             DifType::Wasm,
             DifType::Jvm,
+            DifType::Dart,
         ]
     }
 
@@ -67,6 +87,7 @@ impl DifType {
             "proguard",
             "wasm",
             "jvm",
+            "dart",
         ]
     }
 }
@@ -92,13 +113,14 @@ impl str::FromStr for DifType {
             "proguard" => Ok(DifType::Proguard),
             "wasm" => Ok(DifType::Wasm),
             "jvm" => Ok(DifType::Jvm),
+            "dart" => Ok(DifType::Dart),
             _ => bail!("Invalid debug info file type"),
         }
     }
 }
 
 /// Declares which features an object may have to be uploaded.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize)]
 pub struct ObjectDifFeatures {
     /// Includes object files with debug information.
     pub debug: bool,
@@ -257,6 +279,9 @@ impl DifFile<'static> {
             Some(DifType::Breakpad) => DifFile::open_object(&path, FileFormat::Breakpad),
             Some(DifType::Proguard) => DifFile::open_proguard(&path),
             Some(DifType::Jvm) => DifFile::open_object(&path, FileFormat::SourceBundle),
+            // Flutter writes the debug information of Dart AOT snapshots as ELF
+            // files, regardless of the target platform.
+            Some(DifType::Dart) => DifFile::open_object(&path, FileFormat::Elf),
             None => DifFile::try_open(&path),
         };
 
@@ -264,6 +289,29 @@ impl DifFile<'static> {
     }
 }
 
+/// Validates the `MODULE <os> <arch> <id> <name>` record that starts every
+/// Breakpad symbol file.
+pub fn validate_breakpad_module(data: &[u8]) -> Result<()> {
+    let line = data.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = String::from_utf8_lossy(line);
+
+    let mut fields = line.split_whitespace();
+    if fields.next() != Some("MODULE") {
+        bail!("Invalid Breakpad MODULE record: missing MODULE header");
+    }
+    let (Some(_os), Some(arch), Some(id)) = (fields.next(), fields.next(), fields.next()) else {
+        bail!("Invalid Breakpad MODULE record: expected `MODULE <os> <arch> <id> <name>`");
+    };
+    if arch.parse::<Arch>().is_err() {
+        bail!("Invalid Breakpad MODULE record: unknown architecture {arch}");
+    }
+    if DebugId::from_breakpad(id).is_err() {
+        bail!("Invalid Breakpad MODULE record: invalid debug id {id}");
+    }
+
+    Ok(())
+}
+
 #[derive(Serialize)]
 pub struct DifVariant {
     pub debug_id: DebugId,
@@ -277,6 +325,12 @@ impl<'a> DifFile<'a> {
             bail!("Object file is empty");
         }
 
+        // Breakpad symbols are parsed lazily, which would otherwise hide a
+        // malformed `MODULE` record behind an object without identifiers.
+        if archive.get().file_format() == FileFormat::Breakpad {
+            validate_breakpad_module(archive.owner())?;
+        }
+
         Ok(DifFile::Archive(archive))
     }
 
@@ -297,6 +351,14 @@ impl<'a> DifFile<'a> {
         }
     }
 
+    /// Returns the name of the container format, e.g. `macho` or `elf`.
+    pub fn format_name(&self) -> String {
+        match self {
+            DifFile::Archive(archive) => archive.get().file_format().to_string(),
+            DifFile::Proguard(..) => DifType::Proguard.to_string(),
+        }
+    }
+
     pub fn kind(&self) -> Option<ObjectKind> {
         match self {
             DifFile::Archive(archive) => match archive.get().object_by_index(0) {
@@ -314,7 +376,7 @@ impl<'a> DifFile<'a> {
                 .objects()
                 .filter_map(Result::ok)
                 .map(|object| DifVariant {
-                    debug_id: object.debug_id(),
+                    debug_id: object_debug_id(&object, archive.owner()),
                     arch: Some(object.arch().name().to_string()),
                     code_id: object.code_id(),
                 })
@@ -333,7 +395,7 @@ impl<'a> DifFile<'a> {
                 .get()
                 .objects()
                 .filter_map(Result::ok)
-                .map(|object| object.debug_id())
+                .map(|object| object_debug_id(&object, archive.owner()))
                 .collect(),
             DifFile::Proguard(pg) => vec![pg.get().uuid().into()],
         }
@@ -401,6 +463,8 @@ impl<'a> DifFile<'a> {
     pub fn get_note(&self) -> Option<&'static str> {
         if self.has_hidden_symbols().unwrap_or(false) {
             Some("contains hidden symbols (needs BCSymbolMaps)")
+        } else if self.is_wasm_without_build_id() {
+            Some("missing build_id section, the debug id is derived from the file contents")
         } else {
             None
         }
@@ -409,21 +473,88 @@ impl<'a> DifFile<'a> {
     fn has_ids(&self) -> bool {
         self.ids().iter().any(|id| !id.is_nil())
     }
+
+    fn is_wasm_without_build_id(&self) -> bool {
+        match self {
+            DifFile::Archive(archive) => archive
+                .get()
+                .objects()
+                .filter_map(Result::ok)
+                .any(|object| is_wasm_without_build_id(&object)),
+            DifFile::Proguard(..) => false,
+        }
+    }
 }
 
-impl Serialize for DifFile<'_> {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let mut state = serializer.serialize_struct("DifFile", 6)?;
-        state.serialize_field("type", &self.ty())?;
-        state.serialize_field("variants", &self.variants())?;
-        state.serialize_field("features", &self.features().to_string())?;
-        state.serialize_field("is_usable", &self.is_usable())?;
-        state.serialize_field("problem", &self.get_problem())?;
-        state.serialize_field("note", &self.get_note())?;
-        state.end()
+/// Returns the debug id of an object, taking WebAssembly modules without
+/// `build_id` section into account.
+fn object_debug_id(object: &Object, data: &[u8]) -> DebugId {
+    if is_wasm_without_build_id(object) {
+        wasm_debug_id_from_contents(data)
+    } else {
+        object.debug_id()
+    }
+}
+
+fn is_wasm_without_build_id(object: &Object) -> bool {
+    object.file_format() == FileFormat::Wasm && object.code_id().is_none()
+}
+
+/// Computes a debug id for a WebAssembly module from its contents.
+///
+/// This is used for modules that do not carry a `build_id` custom section.
+pub fn wasm_debug_id_from_contents(data: &[u8]) -> DebugId {
+    let mut hash = sha1_smol::Sha1::new();
+    hash.update(data);
+    let mut sha1_bytes = [0u8; 16];
+    sha1_bytes.copy_from_slice(&hash.digest().bytes()[..16]);
+    DebugId::from_uuid(uuid::Builder::from_sha1_bytes(sha1_bytes).into_uuid())
+}
+
+/// Appends a `build_id` custom section to a WebAssembly module that lacks one.
+///
+/// The build id is derived from the module contents with
+/// [`wasm_debug_id_from_contents`], so that the returned module has the same
+/// debug id that `debug-files check` reports for the original one. Returns
+/// `None` if the data is not a WebAssembly module or already has a build id.
+pub fn add_wasm_build_id(data: &[u8]) -> Option<(DebugId, Vec<u8>)> {
+    if Archive::peek(data) != FileFormat::Wasm {
+        return None;
+    }
+
+    match Object::parse(data) {
+        Ok(object) if is_wasm_without_build_id(&object) => (),
+        _ => return None,
+    }
+
+    let debug_id = wasm_debug_id_from_contents(data);
+    let name = b"build_id";
+    let build_id = debug_id.uuid();
+    let build_id = build_id.as_bytes();
+
+    let mut payload = Vec::new();
+    write_leb128(&mut payload, name.len());
+    payload.extend_from_slice(name);
+    payload.extend_from_slice(build_id);
+
+    let mut rv = data.to_vec();
+    // Custom sections have the id 0 and may appear anywhere after the header.
+    rv.push(0);
+    write_leb128(&mut rv, payload.len());
+    rv.extend(payload);
+
+    Some((debug_id, rv))
+}
+
+fn write_leb128(buf: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            break;
+        }
+        buf.push(byte | 0x80);
     }
 }
 
@@ -461,3 +592,347 @@ impl DebuggingInformation for Archive<'_> {
         Ok(false)
     }
 }
+
+/// The text encoding a source file was read in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SourceEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl fmt::Display for SourceEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SourceEncoding::Utf8 => "UTF-8",
+            SourceEncoding::Utf16Le => "UTF-16LE",
+            SourceEncoding::Utf16Be => "UTF-16BE",
+            SourceEncoding::Latin1 => "Latin-1",
+        })
+    }
+}
+
+/// Decodes the contents of a source file to UTF-8.
+///
+/// A UTF-8 or UTF-16 byte order mark determines the encoding and is removed
+/// from the result. Files without one are read as UTF-8 if they are valid
+/// UTF-8, and as Latin-1 otherwise. Returns `None` for invalid UTF-16.
+pub fn decode_source(source: Vec<u8>) -> Option<(String, SourceEncoding)> {
+    let decode_utf16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        if bytes.len() % 2 != 0 {
+            return None;
+        }
+        let units: Vec<_> = bytes
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units).ok()
+    };
+
+    if let Some(rest) = source.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8(rest.to_vec())
+            .ok()
+            .map(|source| (source, SourceEncoding::Utf8));
+    }
+    if let Some(rest) = source.strip_prefix(b"\xFF\xFE") {
+        return decode_utf16(rest, u16::from_le_bytes)
+            .map(|source| (source, SourceEncoding::Utf16Le));
+    }
+    if let Some(rest) = source.strip_prefix(b"\xFE\xFF") {
+        return decode_utf16(rest, u16::from_be_bytes)
+            .map(|source| (source, SourceEncoding::Utf16Be));
+    }
+
+    match String::from_utf8(source) {
+        Ok(source) => Some((source, SourceEncoding::Utf8)),
+        // Every byte is a valid Latin-1 character, mapping to the same code point.
+        Err(err) => Some((
+            err.into_bytes().into_iter().map(char::from).collect(),
+            SourceEncoding::Latin1,
+        )),
+    }
+}
+
+/// Controls which source files are resolved when building a source bundle.
+#[derive(Debug, Default)]
+pub struct SourceBundleOptions {
+    /// Source files matching any of these patterns are not included.
+    pub exclude: Vec<Pattern>,
+    /// Prefix maps in the format of `-fdebug-prefix-map=OLD=NEW`.
+    pub prefix_map: Vec<(String, String)>,
+    /// Source files larger than this many bytes are not included.
+    pub max_source_size: Option<u64>,
+    /// Also include C# files referenced by il2cpp line mapping comments.
+    pub include_il2cpp_sources: bool,
+}
+
+/// The outcome of writing the source files of an object into a source bundle.
+#[derive(Debug, Default)]
+pub struct SourceBundleSummary {
+    /// The number of source files written into the bundle.
+    pub written: usize,
+    /// Source files skipped for exceeding the maximum source size, along with their size.
+    pub oversized: Vec<(String, u64)>,
+    /// Source files transcoded to UTF-8, along with their original encoding.
+    pub transcoded: Vec<(String, SourceEncoding)>,
+}
+
+impl SourceBundleOptions {
+    /// Returns the path on disk of a source file referenced by debug information.
+    ///
+    /// This reverts the compiler's prefix maps, so that a referenced path starting
+    /// with `NEW` is resolved under `OLD`. Like in the compiler, the last matching
+    /// prefix map wins.
+    pub fn local_path<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        for (old, new) in self.prefix_map.iter().rev() {
+            if let Some(rest) = path.strip_prefix(new.as_str()) {
+                return Cow::Owned(format!("{old}{rest}"));
+            }
+        }
+        Cow::Borrowed(path)
+    }
+
+    /// Returns whether the source file at the referenced or the local path is excluded.
+    pub fn is_excluded(&self, path: &str, local_path: &str) -> bool {
+        self.exclude
+            .iter()
+            .any(|pattern| pattern.matches(path) || pattern.matches(local_path))
+    }
+
+    /// Resolves the source files of an object and writes them into a source bundle.
+    ///
+    /// Source files are stored under the path referenced in the object, even if they
+    /// were read from a different local path. Sources that are not UTF-8 are transcoded.
+    /// The source bundle is finished in either case.
+    pub fn write_object<W>(
+        &self,
+        mut writer: SourceBundleWriter<W>,
+        object: &Object<'_>,
+        object_name: &str,
+    ) -> Result<SourceBundleSummary>
+    where
+        W: Seek + Write,
+    {
+        let session = object.debug_session()?;
+
+        writer.set_attribute("arch", object.arch().to_string());
+        writer.set_attribute("debug_id", object.debug_id().to_string());
+        writer.set_attribute("object_name", object_name);
+        if let Some(code_id) = object.code_id() {
+            writer.set_attribute("code_id", code_id.to_string());
+        }
+
+        let mut summary = SourceBundleSummary::default();
+        let mut files_handled = BTreeSet::new();
+        let mut referenced_files = BTreeSet::new();
+        for file in session.files() {
+            let file = file?;
+            let path = file.abs_path_str();
+
+            if (path.starts_with('<') && path.ends_with('>')) || !files_handled.insert(path.clone())
+            {
+                continue;
+            }
+
+            let local_path = self.local_path(&path);
+            if self.is_excluded(&path, &local_path) {
+                info!("Excluding source file: {}", path);
+                continue;
+            }
+
+            let embedded_source = session.source_by_path(&path)?;
+            if !filter_bad_sources(&file, &embedded_source) {
+                continue;
+            }
+
+            if let Some(source) = self.add_source(&mut writer, &path, &local_path, &mut summary)? {
+                if self.include_il2cpp_sources {
+                    collect_il2cpp_sources(&source, &mut referenced_files);
+                }
+            }
+        }
+
+        for path in referenced_files {
+            if files_handled.insert(path.clone()) {
+                self.add_source(&mut writer, &path, &path, &mut summary)?;
+            }
+        }
+
+        writer.finish()?;
+        Ok(summary)
+    }
+
+    /// Reads a source file from the local path and adds it to the source bundle.
+    ///
+    /// Returns the decoded source, or `None` if the file was skipped.
+    fn add_source<W>(
+        &self,
+        writer: &mut SourceBundleWriter<W>,
+        path: &str,
+        local_path: &str,
+        summary: &mut SourceBundleSummary,
+    ) -> Result<Option<String>>
+    where
+        W: Seek + Write,
+    {
+        // Files that are not available locally are silently skipped.
+        let Ok(meta) = fs::metadata(local_path) else {
+            return Ok(None);
+        };
+        if let Some(max_size) = self.max_source_size {
+            if meta.len() > max_size {
+                warn!(
+                    "Skipping source file exceeding the maximum source size ({} > {}): {}",
+                    meta.len(),
+                    max_size,
+                    path
+                );
+                summary.oversized.push((path.to_owned(), meta.len()));
+                return Ok(None);
+            }
+        }
+
+        let Ok(source) = fs::read(local_path) else {
+            return Ok(None);
+        };
+        let Some((source, encoding)) = decode_source(source) else {
+            info!("Skipped source file {}: invalid UTF-16", path);
+            return Ok(None);
+        };
+        if encoding != SourceEncoding::Utf8 {
+            info!("Transcoding source file from {}: {}", encoding, path);
+            summary.transcoded.push((path.to_owned(), encoding));
+        }
+
+        let mut info = SourceFileInfo::new();
+        info.set_ty(SourceFileType::Source);
+        info.set_path(path.to_owned());
+
+        let mut bundle_path = SANE_PATH_RE.replace_all(path, "/").into_owned();
+        if bundle_path.starts_with('/') {
+            bundle_path.remove(0);
+        }
+        match writer.add_file(bundle_path, source.as_bytes(), info) {
+            Err(err) if err.kind() == SourceBundleErrorKind::ReadFailed => {
+                info!("Skipped source file {}: {}", path, err);
+                return Ok(None);
+            }
+            result => result?,
+        }
+
+        summary.written += 1;
+        Ok(Some(source))
+    }
+}
+
+/// Collects the C# files referenced by il2cpp `//<source_info:` line mapping comments.
+fn collect_il2cpp_sources(source: &str, referenced_files: &mut BTreeSet<String>) {
+    for line in source.lines() {
+        if let Some(source_ref) = line.trim().strip_prefix("//<source_info:") {
+            if let Some((file, _line)) = source_ref.rsplit_once(':') {
+                referenced_files.insert(file.to_owned());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use symbolic::debuginfo::sourcebundle::SourceBundle;
+
+    use super::*;
+
+    const FIXTURES: &str = "tests/integration/_fixtures/source_encodings";
+    const GREETING: &str = "const char *greeting = \"Grüße, café\";\n";
+
+    fn bundle_sources(max_source_size: Option<u64>) -> (SourceBundleSummary, Vec<u8>) {
+        let options = SourceBundleOptions {
+            prefix_map: vec![(FIXTURES.into(), "/build".into())],
+            max_source_size,
+            ..Default::default()
+        };
+
+        let data = ByteView::open(format!("{FIXTURES}/encodings.debug")).unwrap();
+        let object = Object::parse(&data).unwrap();
+        let mut bundle = Vec::new();
+        let writer = SourceBundleWriter::start(Cursor::new(&mut bundle)).unwrap();
+        let summary = options.write_object(writer, &object, "encodings").unwrap();
+        (summary, bundle)
+    }
+
+    fn bundled_source(bundle: &[u8], path: &str) -> Option<String> {
+        let bundle = SourceBundle::parse(bundle).unwrap();
+        let session = bundle.debug_session().unwrap();
+        let source = session.source_by_path(path).unwrap()?;
+        source.contents().map(str::to_owned)
+    }
+
+    #[test]
+    fn test_decode_source() {
+        let decode = |bytes: &[u8]| decode_source(bytes.to_vec());
+
+        assert_eq!(
+            decode("café".as_bytes()),
+            Some(("café".into(), SourceEncoding::Utf8))
+        );
+        assert_eq!(
+            decode(b"\xEF\xBB\xBFcaf\xC3\xA9"),
+            Some(("café".into(), SourceEncoding::Utf8))
+        );
+        assert_eq!(
+            decode(b"caf\xE9"),
+            Some(("café".into(), SourceEncoding::Latin1))
+        );
+        assert_eq!(
+            decode(b"\xFF\xFEc\0a\0f\0\xE9\0"),
+            Some(("café".into(), SourceEncoding::Utf16Le))
+        );
+        assert_eq!(
+            decode(b"\xFE\xFF\0c\0a\0f\0\xE9"),
+            Some(("café".into(), SourceEncoding::Utf16Be))
+        );
+        // odd length and unpaired surrogate
+        assert_eq!(decode(b"\xFF\xFEc\0a"), None);
+        assert_eq!(decode(b"\xFF\xFE\x00\xD8"), None);
+    }
+
+    #[test]
+    fn test_write_object_transcodes_sources() {
+        let (summary, bundle) = bundle_sources(None);
+
+        assert_eq!(summary.written, 6);
+        assert!(summary.oversized.is_empty());
+        assert_eq!(
+            summary.transcoded,
+            [
+                ("/build/latin1.c".to_owned(), SourceEncoding::Latin1),
+                ("/build/utf16le.c".to_owned(), SourceEncoding::Utf16Le),
+                ("/build/utf16be.c".to_owned(), SourceEncoding::Utf16Be),
+            ]
+        );
+
+        for path in ["utf8.c", "latin1.c", "utf16le.c", "utf16be.c"] {
+            assert_eq!(
+                bundled_source(&bundle, &format!("/build/{path}")).as_deref(),
+                Some(GREETING),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_object_skips_oversized_sources() {
+        let (summary, bundle) = bundle_sources(Some(1024));
+
+        assert_eq!(summary.written, 5);
+        assert_eq!(summary.oversized, [("/build/oversized.c".to_owned(), 4096)]);
+        assert_eq!(bundled_source(&bundle, "/build/oversized.c"), None);
+        assert_eq!(
+            bundled_source(&bundle, "/build/utf8.c").as_deref(),
+            Some(GREETING)
+        );
+    }
+}
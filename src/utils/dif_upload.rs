@@ -1,50 +1,62 @@
 //! Searches, processes and uploads debug information files (DIFs). See
 //! `DifUpload` for more information.
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryInto;
 use std::ffi::{OsStr, OsString};
 use std::fmt::{self, Display};
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Seek, Write};
 use std::iter::IntoIterator;
-use std::mem::transmute;
+use std::mem::{self, transmute};
 use std::ops::Deref;
 use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 use std::slice::{Chunks, Iter};
 use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use anyhow::{bail, format_err, Error, Result};
+use anyhow::{bail, format_err, Context, Error, Result};
 use console::style;
+use ignore::WalkBuilder;
 use indicatif::HumanBytes;
 use log::{debug, info, warn};
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::Serialize;
 use sha1_smol::Digest;
 use symbolic::common::{Arch, AsSelf, ByteView, DebugId, SelfCell, Uuid};
 use symbolic::debuginfo::macho::{BcSymbolMap, UuidMapping};
 use symbolic::debuginfo::pe::PeObject;
 use symbolic::debuginfo::sourcebundle::{SourceBundleWriter, SourceFileDescriptor};
 use symbolic::debuginfo::{Archive, FileEntry, FileFormat, Object};
-use symbolic::il2cpp::ObjectLineMapping;
+use symbolic::il2cpp::{LineMapping, ObjectLineMapping};
 use walkdir::WalkDir;
 use which::which;
 use zip::result::ZipError;
 use zip::{write::FileOptions, ZipArchive, ZipWriter};
 
 use crate::api::{
-    Api, ChunkUploadCapability, ChunkUploadOptions, ChunkedDifRequest, ChunkedFileState,
+    Api, ApiError, ChunkCompression, ChunkUploadCapability, ChunkUploadOptions, ChunkedDifRequest,
+    ChunkedFileState,
 };
 use crate::config::Config;
 use crate::constants::{DEFAULT_MAX_DIF_SIZE, DEFAULT_MAX_WAIT};
-use crate::utils::chunks::{
-    upload_chunks, BatchedSliceExt, Chunk, ItemSize, ASSEMBLE_POLL_INTERVAL,
+use crate::utils::chunks::{get_sha1_checksums, upload_chunks, BatchedSliceExt, Chunk, ItemSize};
+use crate::utils::dif::{
+    add_wasm_build_id, validate_breakpad_module, ObjectDifFeatures, SourceBundleOptions,
 };
-use crate::utils::dif::ObjectDifFeatures;
-use crate::utils::fs::{get_sha1_checksum, get_sha1_checksums, TempDir, TempFile};
+use crate::utils::file_search::ignore_file_matcher;
+use crate::utils::fs::{get_sha1_checksum, TempDir, TempFile};
+use crate::utils::logging::quiet_println;
 use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::symbol_server::{PdbReference, SymbolServerClient};
 use crate::utils::ui::{copy_with_progress, make_byte_progress_bar};
+use crate::utils::upload_metadata::UploadMetadata;
+use crate::utils::upload_report::{UploadReportEntry, UploadStatus};
 
 /// A debug info file on the server.
 pub use crate::api::DebugInfoFile;
@@ -52,6 +64,13 @@ pub use crate::api::DebugInfoFile;
 /// Fallback maximum number of chunks in a batch for the legacy upload.
 static MAX_CHUNKS: u64 = 64;
 
+/// Initial interval between polls of the assemble endpoint while waiting for
+/// the server to process files. The interval doubles after every poll.
+const ASSEMBLE_POLL_INTERVAL_MIN: Duration = Duration::from_millis(500);
+
+/// Maximum interval between polls of the assemble endpoint.
+const ASSEMBLE_POLL_INTERVAL_MAX: Duration = Duration::from_secs(5);
+
 /// An iterator over chunks of data in a `ChunkedDifMatch` object.
 ///
 /// This struct is returned by `ChunkedDifMatch::chunks`.
@@ -81,6 +100,7 @@ enum ParsedDif<'a> {
     BcSymbolMap,
     UuidMap,
     Il2Cpp,
+    DartSymbolMap,
 }
 
 impl<'slf, 'data: 'slf> AsSelf<'slf> for ParsedDif<'data> {
@@ -152,6 +172,23 @@ impl<'data> DifMatch<'data> {
             attachments: None,
         })
     }
+
+    fn from_temp_dart_symbol_map<S>(temp_file: TempFile, name: S, debug_id: DebugId) -> Result<Self>
+    where
+        S: Into<String>,
+    {
+        let buffer = ByteView::open(temp_file.path()).map_err(Error::new)?;
+        let dif = SelfCell::try_new(buffer, |_| Ok::<_, anyhow::Error>(ParsedDif::DartSymbolMap))?;
+
+        Ok(DifMatch {
+            _backing: Some(DifBacking::Temp),
+            dif,
+            name: name.into(),
+            debug_id: Some(debug_id),
+            attachments: None,
+        })
+    }
+
     /// Creates a [`DifMatch`] from a `.bcsymbolmap` file.
     ///
     /// The `uuid` is the DebugID of the symbolmap while `name` is the filename of the file.
@@ -208,15 +245,28 @@ impl<'data> DifMatch<'data> {
             ParsedDif::BcSymbolMap => None,
             ParsedDif::UuidMap => None,
             ParsedDif::Il2Cpp => None,
+            ParsedDif::DartSymbolMap => None,
         }
     }
 
+    /// Returns the CPU architecture of this DIF, if it is an object file
+    /// with a known architecture.
+    ///
+    /// Each slice of a fat Mach-O binary is a separate DIF with its own
+    /// architecture.
+    pub fn arch(&self) -> Option<Arch> {
+        self.object()
+            .map(Object::arch)
+            .filter(|arch| *arch != Arch::Unknown)
+    }
+
     pub fn format(&self) -> DifFormat {
         match self.dif.get() {
             ParsedDif::Object(ref object) => DifFormat::Object(object.file_format()),
             ParsedDif::BcSymbolMap => DifFormat::BcSymbolMap,
             ParsedDif::UuidMap => DifFormat::PList,
             ParsedDif::Il2Cpp => DifFormat::Il2Cpp,
+            ParsedDif::DartSymbolMap => DifFormat::DartSymbolMap,
         }
     }
 
@@ -227,6 +277,7 @@ impl<'data> DifMatch<'data> {
             ParsedDif::BcSymbolMap => self.dif.owner(),
             ParsedDif::UuidMap => self.dif.owner(),
             ParsedDif::Il2Cpp => self.dif.owner(),
+            ParsedDif::DartSymbolMap => self.dif.owner(),
         }
     }
 
@@ -320,6 +371,43 @@ impl<'data> ItemSize for HashedDifMatch<'data> {
     }
 }
 
+/// A debug information file that would be uploaded, as reported by
+/// [`DifUpload::dry_run`].
+#[derive(Debug, Serialize)]
+pub struct DifManifestEntry {
+    pub name: String,
+    pub debug_id: Option<DebugId>,
+    pub format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    pub size: u64,
+    pub checksum: String,
+}
+
+impl From<&HashedDifMatch<'_>> for DifManifestEntry {
+    fn from(dif: &HashedDifMatch<'_>) -> Self {
+        DifManifestEntry {
+            name: dif.path().to_owned(),
+            debug_id: dif.debug_id.or_else(|| dif.object().map(Object::debug_id)),
+            format: format_name(dif.format()),
+            arch: dif.arch().map(|arch| arch.to_string()),
+            size: dif.size(),
+            checksum: dif.checksum().to_string(),
+        }
+    }
+}
+
+/// Returns the name of the given format, as shown in manifests and reports.
+fn format_name(format: DifFormat) -> String {
+    match format {
+        DifFormat::Object(format) => format.to_string(),
+        DifFormat::BcSymbolMap => "bcsymbolmap".into(),
+        DifFormat::PList => "plist".into(),
+        DifFormat::Il2Cpp => "il2cpp".into(),
+        DifFormat::DartSymbolMap => "dartsymbolmap".into(),
+    }
+}
+
 /// A chunked `DifMatch` with computed SHA1 checksums.
 #[derive(Debug)]
 struct ChunkedDifMatch<'data> {
@@ -388,6 +476,19 @@ impl<'data> ItemSize for ChunkedDifMatch<'data> {
 
 type ZipFileArchive = ZipArchive<BufReader<File>>;
 
+/// The name of the line mapping file emitted by Unity il2cpp builds.
+pub const IL2CPP_LINE_MAPPING_NAME: &str = "LineNumberMappings.json";
+
+/// The name of the il2cpp metadata file, which indicates a Unity il2cpp build.
+pub const IL2CPP_METADATA_NAME: &str = "global-metadata.dat";
+
+/// The name of the symbol map written by obfuscated Flutter builds, as given
+/// to `--save-obfuscation-map` in the Flutter documentation.
+pub const DART_SYMBOL_MAP_NAME: &str = "obfuscation.map.json";
+
+/// The extension of Dart symbol files written by `flutter build --split-debug-info`.
+pub const DART_SYMBOLS_EXTENSION: &str = "symbols";
+
 /// A handle to the source of a potential `DifMatch` used inside `search_difs`.
 ///
 /// The primary use of this handle is to resolve files relative to the debug
@@ -528,6 +629,15 @@ where
     Ok(())
 }
 
+/// A potential DIF discovered by `walk_difs_directory`.
+enum DifCandidate<'a> {
+    /// A file on the file system. It is opened and parsed later, in parallel
+    /// with other candidates.
+    File(PathBuf, String),
+    /// An entry of a ZIP archive, which has already been extracted into a buffer.
+    ZipEntry(DifSource<'a>, String, ByteView<'static>),
+}
+
 /// Recursively searches the given location for potential DIFs and passes them
 /// to the callback.
 ///
@@ -540,7 +650,7 @@ where
 fn walk_difs_directory<F, P>(location: P, options: &DifUpload, mut func: F) -> Result<()>
 where
     P: AsRef<Path>,
-    F: FnMut(DifSource<'_>, String, ByteView<'static>) -> Result<()>,
+    F: FnMut(DifCandidate<'_>) -> Result<()>,
 {
     let location = location.as_ref();
     let directory = if location.is_dir() {
@@ -549,14 +659,25 @@ where
         location.parent().unwrap_or_else(|| Path::new(""))
     };
 
-    debug!("searching location {}", location.display());
-    for entry in WalkDir::new(location)
+    let mut builder = WalkBuilder::new(location);
+    builder
+        .standard_filters(false)
         .follow_links(true)
-        .into_iter()
-        .filter_map(Result::ok)
-    {
+        .git_ignore(options.use_gitignore)
+        .git_exclude(options.use_gitignore)
+        .require_git(false);
+
+    if let Some(gitignore) = ignore_file_matcher(directory, &options.ignore_files)? {
+        builder.filter_entry(move |entry| {
+            let is_dir = entry.file_type().map_or(false, |t| t.is_dir());
+            !gitignore.matched(entry.path(), is_dir).is_ignore()
+        });
+    }
+
+    debug!("searching location {}", location.display());
+    for entry in builder.build().filter_map(Result::ok) {
         if !entry.metadata()?.is_file() {
-            // Walkdir recurses automatically into folders
+            // The walker recurses automatically into folders
             continue;
         }
 
@@ -565,7 +686,10 @@ where
             match try_open_zip(path) {
                 Ok(Some(zip)) => {
                     debug!("searching zip archive {}", path.display());
-                    if let Err(err) = walk_difs_zip(zip, options, &mut func) {
+                    let result = walk_difs_zip(zip, options, |source, name, buffer| {
+                        func(DifCandidate::ZipEntry(source, name, buffer))
+                    });
+                    if let Err(err) = result {
                         if let Some(e) = err.downcast_ref::<ZipError>() {
                             debug!("skipping zip archive {}", path.display());
                             debug!("error: {}", e);
@@ -591,14 +715,13 @@ where
             continue;
         }
 
-        let buffer = ByteView::open(path).map_err(Error::new)?;
         let name = path
             .strip_prefix(directory)
             .unwrap()
             .to_string_lossy()
             .into_owned();
 
-        func(DifSource::FileSystem(path), name, buffer)?;
+        func(DifCandidate::File(path.to_path_buf(), name))?;
     }
 
     debug!("finished location {}", directory.display());
@@ -644,6 +767,68 @@ fn find_uuid_plists(
     Some(plists)
 }
 
+/// Searches for an il2cpp line mapping next to the given `source` and wraps it
+/// into a [`DifMatch`] associated with the object's debug identifier.
+///
+/// Line mappings are only picked up if they were requested explicitly or if
+/// the directory also contains the il2cpp metadata of a Unity build.
+fn find_il2cpp_line_mapping<'a>(
+    object: &Object<'_>,
+    source: &mut DifSource<'_>,
+    name: &str,
+    options: &DifUpload,
+) -> Option<DifMatch<'a>> {
+    let wanted = options.upload_il2cpp_mappings
+        || options.il2cpp_mappings_allowed && source.get_relative(IL2CPP_METADATA_NAME).is_some();
+    if !wanted {
+        return None;
+    }
+
+    let buffer = source.get_relative(IL2CPP_LINE_MAPPING_NAME)?;
+    let mapping_name = Path::new(name)
+        .with_file_name(IL2CPP_LINE_MAPPING_NAME)
+        .to_string_lossy()
+        .into_owned();
+
+    match create_il2cpp_line_mapping(&buffer, object.debug_id(), mapping_name.clone()) {
+        Ok(dif) => Some(dif),
+        Err(err) => {
+            warn!("Skipping invalid il2cpp line mapping {mapping_name}: {err}");
+            None
+        }
+    }
+}
+
+/// Creates a line mapping [`DifMatch`] for the object with the given debug identifier.
+///
+/// The same mapping file usually applies to the binaries of all architectures. Just
+/// like [`ObjectLineMapping`], this adds a `__debug-id__` entry to make the uploaded
+/// file unique for every object.
+fn create_il2cpp_line_mapping<'a>(
+    buffer: &[u8],
+    debug_id: DebugId,
+    name: String,
+) -> Result<DifMatch<'a>> {
+    if LineMapping::parse(buffer).is_none() {
+        bail!("not a valid line mapping");
+    }
+
+    let mut mapping: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(buffer)?;
+    mapping.insert(
+        "__debug-id__".to_owned(),
+        serde_json::json!({ debug_id.to_string(): {} }),
+    );
+
+    let temp_file = TempFile::create()?;
+    {
+        let mut writer = BufWriter::new(temp_file.open()?);
+        serde_json::to_writer(&mut writer, &mapping)?;
+        writer.flush()?;
+    }
+
+    DifMatch::from_temp_line_mapping(temp_file, name, Some(debug_id))
+}
+
 /// Patch debug identifiers for PDBs where the corresponding PE specifies a different age.
 fn fix_pdb_ages(difs: &mut [DifMatch<'_>], age_overrides: &BTreeMap<Uuid, u32>) {
     for dif in difs {
@@ -674,8 +859,68 @@ fn fix_pdb_ages(difs: &mut [DifMatch<'_>], age_overrides: &BTreeMap<Uuid, u32>)
     }
 }
 
+/// DIFs collected from a single candidate, along with information on all PE
+/// files encountered in it. See `collect_object_dif`.
+struct ScannedDifs {
+    difs: Vec<DifMatch<'static>>,
+    /// The ages of PE files, used to fix up the ages of PDBs.
+    age_overrides: BTreeMap<Uuid, u32>,
+    /// The PDBs referenced by PE files, used to fetch them from symbol servers.
+    pdb_refs: BTreeSet<PdbReference>,
+}
+
+/// A candidate waiting to be scanned, or the result of an already scanned one.
+enum PendingScan {
+    File(PathBuf, String),
+    Scanned(ScannedDifs),
+}
+
+/// Inspects the contents of a potential DIF and collects all matching debug
+/// information files from it.
+fn scan_dif(
+    source: DifSource<'_>,
+    name: String,
+    buffer: ByteView<'static>,
+    options: &DifUpload,
+) -> ScannedDifs {
+    debug!("trying to process {}", name);
+
+    let mut age_overrides = BTreeMap::new();
+    let mut pdb_refs = BTreeSet::new();
+    let difs = if Archive::peek(&buffer) != FileFormat::Unknown {
+        collect_object_dif(
+            source,
+            name,
+            buffer,
+            options,
+            &mut age_overrides,
+            &mut pdb_refs,
+        )
+    } else if BcSymbolMap::test(&buffer) {
+        collect_auxdif(name, buffer, options, AuxDifKind::BcSymbolMap)
+            .into_iter()
+            .collect()
+    } else if buffer.starts_with(b"<?xml") {
+        collect_auxdif(name, buffer, options, AuxDifKind::UuidMap)
+            .into_iter()
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    ScannedDifs {
+        difs,
+        age_overrides,
+        pdb_refs,
+    }
+}
+
 /// Searches matching debug information files.
-fn search_difs(options: &DifUpload) -> Result<Vec<DifMatch<'static>>> {
+///
+/// The search paths are walked sequentially, while opening and parsing the
+/// discovered files is spread across the given thread pool. The resulting
+/// DIFs are returned in the order in which they were discovered.
+fn search_difs(options: &DifUpload, pool: &ThreadPool) -> Result<Vec<DifMatch<'static>>> {
     let progress_style = ProgressStyle::default_spinner().template(
         "{spinner} Searching for debug symbol files...\
          \n  found {prefix:.yellow} {msg:.dim}",
@@ -685,8 +930,7 @@ fn search_difs(options: &DifUpload) -> Result<Vec<DifMatch<'static>>> {
     pb.enable_steady_tick(100);
     pb.set_style(progress_style);
 
-    let mut age_overrides = BTreeMap::new();
-    let mut collected = Vec::new();
+    let mut pending = Vec::new();
     for base_path in &options.paths {
         if base_path == Path::new("") {
             warn!(
@@ -695,59 +939,228 @@ fn search_difs(options: &DifUpload) -> Result<Vec<DifMatch<'static>>> {
             );
             continue;
         }
-        walk_difs_directory(base_path, options, |source, name, buffer| {
-            debug!("trying to process {}", name);
-            pb.set_message(&name);
-
-            if Archive::peek(&buffer) != FileFormat::Unknown {
-                let mut difs =
-                    collect_object_dif(source, name, buffer, options, &mut age_overrides);
-                collected.append(difs.as_mut());
-            } else if BcSymbolMap::test(&buffer) {
-                if let Some(dif) = collect_auxdif(name, buffer, options, AuxDifKind::BcSymbolMap) {
-                    collected.push(dif);
+        walk_difs_directory(base_path, options, |candidate| {
+            match candidate {
+                DifCandidate::File(path, name) => pending.push(PendingScan::File(path, name)),
+                DifCandidate::ZipEntry(source, name, buffer) => {
+                    pb.set_message(&name);
+                    pending.push(PendingScan::Scanned(scan_dif(
+                        source, name, buffer, options,
+                    )));
                 }
-            } else if buffer.starts_with(b"<?xml") {
-                if let Some(dif) = collect_auxdif(name, buffer, options, AuxDifKind::UuidMap) {
-                    collected.push(dif);
-                }
-            };
-
-            pb.set_prefix(&collected.len().to_string());
+            }
             Ok(())
         })?;
     }
 
+    let found = AtomicUsize::new(0);
+    let scanned = pool.install(|| {
+        pending
+            .into_par_iter()
+            .map(|item| -> Result<ScannedDifs> {
+                let scanned = match item {
+                    PendingScan::File(path, name) => {
+                        pb.set_message(&name);
+                        let buffer = ByteView::open(&path).map_err(Error::new)?;
+                        scan_dif(DifSource::FileSystem(&path), name, buffer, options)
+                    }
+                    PendingScan::Scanned(scanned) => scanned,
+                };
+
+                let count =
+                    found.fetch_add(scanned.difs.len(), Ordering::Relaxed) + scanned.difs.len();
+                pb.set_prefix(&count.to_string());
+                Ok(scanned)
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    let mut age_overrides = BTreeMap::new();
+    let mut pdb_refs = BTreeSet::new();
+    let mut collected = Vec::new();
+    for mut scanned in scanned {
+        collected.append(&mut scanned.difs);
+        age_overrides.extend(scanned.age_overrides);
+        pdb_refs.extend(scanned.pdb_refs);
+    }
+
+    pb.finish_and_clear();
+
+    if !options.symbol_servers.is_empty() {
+        let fetched = fetch_referenced_pdbs(&collected, pdb_refs, options);
+        collected.extend(fetched);
+    }
+
     if !age_overrides.is_empty() {
         fix_pdb_ages(&mut collected, &age_overrides);
     }
 
-    pb.finish_and_clear();
+    let collected = dedupe_difs(collected);
+
+    let count_with_sources = collected
+        .iter()
+        .filter(|dif| match dif.object() {
+            Some(object) => object.has_sources(),
+            None => false,
+        })
+        .count();
 
-    print!(
-        "{} Found {} debug information {}",
+    quiet_println!(
+        "{} Found {} debug information {}{}",
         style(">").dim(),
         style(collected.len()).yellow(),
         match collected.len() {
             1 => "file",
             _ => "files",
+        },
+        match count_with_sources {
+            0 => String::new(),
+            _ => format!(" ({count_with_sources} with embedded sources)"),
         }
     );
 
-    let count_with_sources = collected
+    Ok(collected)
+}
+
+/// Removes copies of the same file from the discovered DIFs.
+///
+/// The same file can be found in multiple search paths, for instance dSYMs that
+/// Xcode copies into an archive.  Only the first occurrence of files with the same
+/// debug id, kind and contents is kept.
+fn dedupe_difs(difs: Vec<DifMatch<'static>>) -> Vec<DifMatch<'static>> {
+    let mut seen = HashMap::<_, Vec<usize>>::new();
+    let mut deduped: Vec<DifMatch<'static>> = Vec::with_capacity(difs.len());
+
+    for dif in difs {
+        let key = (
+            dif.debug_id,
+            dif.format(),
+            dif.object().map(|object| object.kind()),
+            dif.size(),
+        );
+        let copies = seen.entry(key).or_default();
+        if copies.iter().any(|&idx| deduped[idx].data() == dif.data()) {
+            debug!(
+                "skipping {}, which is a copy of a file found before",
+                dif.name
+            );
+            continue;
+        }
+        copies.push(deduped.len());
+        deduped.push(dif);
+    }
+
+    deduped
+}
+
+/// Fetches the PDBs referenced by PE files from the configured symbol servers.
+///
+/// PDBs that were found locally are not fetched again. Failures to fetch a PDB
+/// are reported as warnings, since all other files can still be uploaded.
+fn fetch_referenced_pdbs(
+    found: &[DifMatch<'static>],
+    pdb_refs: BTreeSet<PdbReference>,
+    options: &DifUpload,
+) -> Vec<DifMatch<'static>> {
+    if !options.valid_format(DifFormat::Object(FileFormat::Pdb))
+        && !options.valid_format(DifFormat::Object(FileFormat::PortablePdb))
+    {
+        return Vec::new();
+    }
+
+    // Ages of PDBs may differ from the PE file, so only compare GUIDs
+    let local: BTreeSet<_> = found
         .iter()
-        .filter(|dif| match dif.object() {
-            Some(object) => object.has_sources(),
-            None => false,
+        .filter(|dif| {
+            matches!(
+                dif.format(),
+                DifFormat::Object(FileFormat::Pdb | FileFormat::PortablePdb)
+            )
         })
-        .count();
+        .filter_map(|dif| dif.debug_id)
+        .map(|id| id.uuid())
+        .collect();
+    let missing: Vec<_> = pdb_refs
+        .into_iter()
+        .filter(|pdb| !local.contains(&pdb.debug_id.uuid()))
+        .collect();
+    if missing.is_empty() {
+        return Vec::new();
+    }
+
+    quiet_println!(
+        "{} Fetching {} {} from symbol servers",
+        style(">").dim(),
+        style(missing.len()).yellow(),
+        match missing.len() {
+            1 => "PDB",
+            _ => "PDBs",
+        }
+    );
+
+    let client = SymbolServerClient::new(&options.symbol_servers);
+    let mut fetched = Vec::new();
+    let mut failures = Vec::new();
+    for pdb in missing {
+        let result = match client.fetch(&pdb) {
+            Ok(Some(result)) => result,
+            Ok(None) => {
+                failures.push((pdb, "not found on any symbol server".to_owned()));
+                continue;
+            }
+            Err(err) => {
+                failures.push((pdb, format!("{err:#}")));
+                continue;
+            }
+        };
+
+        debug!("fetched {} from {}", pdb.name, result.url);
+        let path = result.path.unwrap_or_else(|| PathBuf::from(&result.url));
+        let scanned = scan_dif(
+            DifSource::FileSystem(&path),
+            pdb.server_path(),
+            result.data,
+            options,
+        );
+        let count = fetched.len();
+        fetched.extend(scanned.difs.into_iter().filter(|dif| {
+            dif.debug_id
+                .map_or(false, |id| id.uuid() == pdb.debug_id.uuid())
+        }));
+        if fetched.len() == count {
+            failures.push((pdb, format!("{} is not a matching PDB", result.url)));
+        }
+    }
+
+    quiet_println!(
+        "{} Fetched {} {} from symbol servers",
+        style(">").dim(),
+        style(fetched.len()).yellow(),
+        match fetched.len() {
+            1 => "PDB",
+            _ => "PDBs",
+        }
+    );
 
-    match count_with_sources {
-        0 => println!(),
-        _ => println!(" ({count_with_sources} with embedded sources)"),
+    if !failures.is_empty() {
+        eprintln!(
+            "{}",
+            style(format!(
+                "warning: Could not fetch {} {}:",
+                failures.len(),
+                match failures.len() {
+                    1 => "PDB",
+                    _ => "PDBs",
+                }
+            ))
+            .red()
+        );
+        for (pdb, reason) in &failures {
+            eprintln!("  {} ({}): {}", pdb.name, pdb.debug_id, reason);
+        }
     }
 
-    Ok(collected)
+    fetched
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -826,9 +1239,22 @@ fn collect_object_dif<'a>(
     buffer: ByteView<'static>,
     options: &DifUpload,
     age_overrides: &mut BTreeMap<Uuid, u32>,
+    pdb_refs: &mut BTreeSet<PdbReference>,
 ) -> Vec<DifMatch<'a>> {
     let mut collected = Vec::with_capacity(2);
 
+    // WebAssembly modules without a `build_id` section have no debug id. Assign one
+    // derived from the module contents and embed it into the uploaded module.
+    let buffer = match add_wasm_build_id(&buffer) {
+        Some((debug_id, data)) => {
+            warn!(
+                "{name} has no build_id section, using debug id {debug_id} derived from its contents"
+            );
+            ByteView::from_vec(data)
+        }
+        None => buffer,
+    };
+
     // Try to parse a potential object file. If this is not possible,
     // then we're not dealing with an object file, thus silently
     // skipping it.
@@ -844,6 +1270,13 @@ fn collect_object_dif<'a>(
         return collected;
     }
 
+    if format == FileFormat::Breakpad {
+        if let Err(e) = validate_breakpad_module(&buffer) {
+            warn!("Skipping invalid Breakpad symbol file {}: {}", name, e);
+            return collected;
+        }
+    }
+
     debug!("trying to parse dif {}", name);
     let archive = match Archive::parse(&buffer) {
         Ok(archive) => archive,
@@ -885,6 +1318,12 @@ fn collect_object_dif<'a>(
                     collected.push(ppdb_dif);
                 }
             }
+
+            // Remember the referenced PDB regardless of whether the PE file
+            // will be uploaded, since PE files often lack debug information.
+            if !options.symbol_servers.is_empty() {
+                pdb_refs.extend(PdbReference::from_pe(pe));
+            }
         };
 
         // Store a mapping of "age" values for all encountered PE files,
@@ -903,6 +1342,15 @@ fn collect_object_dif<'a>(
             _ => None,
         };
 
+        // Native binaries of Unity il2cpp builds may come with a line mapping
+        // from the generated C++ code back to C#.
+        let line_mapping = match object.file_format() {
+            FileFormat::Elf | FileFormat::MachO | FileFormat::Pdb => {
+                find_il2cpp_line_mapping(&object, &mut source, &name, options)
+            }
+            _ => None,
+        };
+
         // We retain the buffer and the borrowed object in a new SelfCell. This is
         // incredibly unsafe, but in our case it is fine, since the SelfCell owns the same
         // buffer that was used to retrieve the object.
@@ -928,6 +1376,7 @@ fn collect_object_dif<'a>(
         }
 
         collected.push(dif);
+        collected.extend(line_mapping);
     }
 
     collected
@@ -940,16 +1389,6 @@ fn collect_object_dif<'a>(
 /// Note that this process copies the file to a temporary location and might
 /// incur significant I/O for larger debug files.
 fn resolve_hidden_symbols<'a>(dif: DifMatch<'a>, symbol_map: &Path) -> Result<DifMatch<'a>> {
-    if dif.attachments.is_none() {
-        println!(
-            "{} {}: Could not locate UUID mapping for {}",
-            style(">").dim(),
-            style("Warning").red(),
-            style(dif.file_name()).yellow(),
-        );
-        return Ok(dif);
-    }
-
     // We need to rebuild the Resources folder of a dSYM structure in a temp
     // directory that is guaranteed to be deleted after this operation. The
     // Info.plist is not needed for this operation:
@@ -968,7 +1407,7 @@ fn resolve_hidden_symbols<'a>(dif: DifMatch<'a>, symbol_map: &Path) -> Result<Di
     temp_file.sync_data()?;
 
     // Copy the UUID plists
-    for (name, view) in dif.attachments().unwrap() {
+    for (name, view) in dif.attachments().into_iter().flatten() {
         let mut plist = File::create(temp_dir.path().join(name))?;
         plist.write_all(view)?;
         plist.sync_data()?;
@@ -993,15 +1432,21 @@ fn resolve_hidden_symbols<'a>(dif: DifMatch<'a>, symbol_map: &Path) -> Result<Di
     DifMatch::take_temp(temp_path, dif.path())
 }
 
-/// Runs all `DifMatch` objects through the provided callback and displays a
-/// progress bar while doing so.
+/// Runs all `DifMatch` objects through the provided callback on the given
+/// thread pool and displays a progress bar while doing so. The order of the
+/// items is retained.
 ///
 /// ```
-/// prepare_difs(processed, |m| HashedDifMatch::from(m))?
+/// prepare_difs(processed, &pool, |m| HashedDifMatch::from(m))?
 /// ```
-fn prepare_difs<'data, F, T>(items: Vec<DifMatch<'data>>, mut func: F) -> Result<Vec<T>>
+fn prepare_difs<'data, F, T>(
+    items: Vec<DifMatch<'data>>,
+    pool: &ThreadPool,
+    func: F,
+) -> Result<Vec<T>>
 where
-    F: FnMut(DifMatch<'data>) -> Result<T>,
+    F: Fn(DifMatch<'data>) -> Result<T> + Sync,
+    T: Send,
 {
     let progress_style = ProgressStyle::default_bar().template(
         "{prefix:.dim} Preparing for upload... {msg:.dim}\
@@ -1012,15 +1457,20 @@ where
     pb.set_style(progress_style);
     pb.set_prefix(">");
 
-    let mut calculated = Vec::new();
-    for item in items {
-        pb.inc(1);
-        pb.set_message(item.path());
-        calculated.push(func(item)?);
-    }
+    let calculated = pool.install(|| {
+        items
+            .into_par_iter()
+            .map(|item| {
+                pb.set_message(item.path());
+                let result = func(item);
+                pb.inc(1);
+                result
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
 
     pb.finish_and_clear();
-    println!(
+    quiet_println!(
         "{} Prepared debug information {} for upload",
         style(">").dim(),
         match calculated.len() {
@@ -1032,64 +1482,128 @@ where
     Ok(calculated)
 }
 
+/// Returns the file name of the BCSymbolMap needed to resolve the hidden
+/// symbols of the given dSYM.
+///
+/// dSYMs of builds recompiled by Apple come with a UUID plist, which maps them
+/// back to the original build.  In this case, the BCSymbolMap is named after the
+/// original UUID.
+fn expected_symbol_map_name(dif: &DifMatch<'_>) -> String {
+    let debug_id = dif.debug_id.unwrap_or_default();
+    let uuid = dif
+        .attachments()
+        .and_then(|plists| plists.values().next())
+        .and_then(|plist| UuidMapping::parse_plist(debug_id, plist).ok())
+        .map_or(debug_id, |mapping| mapping.original_uuid());
+
+    format!("{:X}.bcsymbolmap", uuid.uuid().as_hyphenated())
+}
+
+/// Checks whether `symbol_map`, a directory of BCSymbolMaps or a single file,
+/// provides the BCSymbolMap with the given file name.
+fn has_symbol_map(symbol_map: &Path, name: &str) -> bool {
+    if symbol_map.is_dir() {
+        symbol_map.join(name).is_file() || symbol_map.join(name.to_lowercase()).is_file()
+    } else {
+        symbol_map
+            .file_name()
+            .and_then(OsStr::to_str)
+            .map_or(false, |file_name| file_name.eq_ignore_ascii_case(name))
+    }
+}
+
 /// Resolves BCSymbolMaps for all debug files with hidden symbols. All other
 /// files are not touched. Note that this only applies to Apple dSYMs.
 ///
 /// If there are debug files with hidden symbols but no `symbol_map` path is
-/// given, a warning is emitted.
+/// given, a warning is emitted.  Otherwise, a warning is emitted for every
+/// dSYM whose BCSymbolMap is missing, which fails the upload if
+/// `require_symbol_maps` is set.
 fn process_symbol_maps<'a>(
     difs: Vec<DifMatch<'a>>,
     symbol_map: Option<&Path>,
+    require_symbol_maps: bool,
 ) -> Result<Vec<DifMatch<'a>>> {
     let (with_hidden, mut without_hidden): (Vec<_>, _) =
         difs.into_iter().partition(DifMatch::needs_symbol_map);
 
-    if with_hidden.is_empty() {
-        return Ok(without_hidden);
-    }
-
-    let symbol_map = match symbol_map {
-        Some(path) => path,
-        _ => {
-            println!(
+    let Some(symbol_map) = symbol_map else {
+        if !with_hidden.is_empty() {
+            quiet_println!(
                 "{} {}: Found {} symbol files with hidden symbols (need BCSymbolMaps)",
                 style(">").dim(),
                 style("Warning").red(),
                 style(with_hidden.len()).yellow()
             );
-
-            without_hidden.extend(with_hidden);
-            return Ok(without_hidden);
         }
+
+        without_hidden.extend(with_hidden);
+        return Ok(without_hidden);
     };
 
-    let len = with_hidden.len();
-    let progress_style = ProgressStyle::default_bar().template(
-        "{prefix:.dim} Resolving BCSymbolMaps... {msg:.dim}\
-         \n{wide_bar}  {pos}/{len}",
-    );
+    let no_map_needed = without_hidden
+        .iter()
+        .filter(|dif| dif.format() == DifFormat::Object(FileFormat::MachO))
+        .count();
 
-    let pb = ProgressBar::new(len);
-    pb.set_style(progress_style);
-    pb.set_prefix(">");
+    let (mapped, missing): (Vec<_>, Vec<_>) = with_hidden
+        .into_iter()
+        .partition(|dif| has_symbol_map(symbol_map, &expected_symbol_map_name(dif)));
 
-    for dif in with_hidden {
-        pb.inc(1);
-        pb.set_message(dif.path());
-        without_hidden.push(resolve_hidden_symbols(dif, symbol_map)?);
+    for dif in &missing {
+        quiet_println!(
+            "{} {}: No BCSymbolMap found for {} ({}), expected {}",
+            style(">").dim(),
+            style("Warning").red(),
+            dif.debug_id.unwrap_or_default(),
+            style(dif.path()).yellow(),
+            expected_symbol_map_name(dif),
+        );
     }
 
-    pb.finish_and_clear();
-    println!(
-        "{} Resolved BCSymbolMaps for {} debug information {}",
-        style(">").dim(),
-        style(len).yellow(),
-        match len {
-            1 => "file",
-            _ => "files",
+    if require_symbol_maps && !missing.is_empty() {
+        bail!(
+            "BCSymbolMaps are missing for {} debug information {}",
+            missing.len(),
+            match missing.len() {
+                1 => "file",
+                _ => "files",
+            }
+        );
+    }
+
+    let resolved = mapped.len();
+    if resolved > 0 {
+        which("dsymutil")
+            .map_err(|_| format_err!("Resolving BCSymbolMaps requires Apple dsymutil."))?;
+
+        let progress_style = ProgressStyle::default_bar().template(
+            "{prefix:.dim} Resolving BCSymbolMaps... {msg:.dim}\
+             \n{wide_bar}  {pos}/{len}",
+        );
+
+        let pb = ProgressBar::new(resolved);
+        pb.set_style(progress_style);
+        pb.set_prefix(">");
+
+        for dif in mapped {
+            pb.inc(1);
+            pb.set_message(dif.path());
+            without_hidden.push(resolve_hidden_symbols(dif, symbol_map)?);
         }
+
+        pb.finish_and_clear();
+    }
+
+    quiet_println!(
+        "{} BCSymbolMaps: {} resolved, {} no map needed, {} map missing",
+        style(">").dim(),
+        style(resolved).yellow(),
+        style(no_map_needed).yellow(),
+        style(missing.len()).yellow(),
     );
 
+    without_hidden.extend(missing);
     Ok(without_hidden)
 }
 
@@ -1113,11 +1627,12 @@ fn extract_embedded_ppdb<'a>(pe: &PeObject, pe_name: &str) -> Result<Option<DifM
 }
 
 /// Default filter function to skip over bad sources we do not want to include.
+///
+/// The size of source files is limited by `SourceBundleOptions::max_source_size`.
 pub fn filter_bad_sources(
     entry: &FileEntry,
     embedded_source: &Option<SourceFileDescriptor>,
 ) -> bool {
-    let max_size = Config::current().get_max_dif_item_size();
     let path = &entry.abs_path_str();
 
     // Ignore pch files.
@@ -1131,18 +1646,6 @@ pub fn filter_bad_sources(
         return false;
     }
 
-    // Ignore files larger than limit (defaults to `DEFAULT_MAX_DIF_ITEM_SIZE`).
-    if let Ok(meta) = fs::metadata(path) {
-        let item_size = meta.len();
-        if item_size > max_size {
-            warn!(
-                "Source exceeded maximum item size limit ({}). {}",
-                item_size, path
-            );
-            return false;
-        }
-    }
-
     // if a file metadata could not be read it will be skipped later.
     debug!("Trying to add source file: {}", path);
     true
@@ -1151,12 +1654,25 @@ pub fn filter_bad_sources(
 /// Creates a source bundle containing the source files referenced by the input DIFs.
 ///
 /// If `include_il2cpp_sources` is true, C# files referenced by il2cpp line mapping comments
-/// will also be included.
+/// will also be included. Source files larger than `max_source_size` are skipped, and
+/// sources that are not UTF-8 are transcoded. The upload metadata and release are stored in
+/// the manifest of every bundle.
 fn create_source_bundles<'a>(
     difs: &[DifMatch<'a>],
     include_il2cpp_sources: bool,
+    max_source_size: u64,
+    metadata: &UploadMetadata,
+    release: Option<&str>,
 ) -> Result<Vec<DifMatch<'a>>> {
     let mut source_bundles = Vec::new();
+    let mut oversized = BTreeMap::new();
+    let mut transcoded = BTreeMap::new();
+
+    let options = SourceBundleOptions {
+        max_source_size: Some(max_source_size),
+        include_il2cpp_sources,
+        ..Default::default()
+    };
 
     let progress_style = ProgressStyle::default_bar().template(
         "{prefix:.dim} Resolving source code... {msg:.dim}\
@@ -1180,15 +1696,20 @@ fn create_source_bundles<'a>(
 
         let temp_file = TempFile::create()?;
         let mut writer = SourceBundleWriter::start(BufWriter::new(temp_file.open()?))?;
-        writer.collect_il2cpp_sources(include_il2cpp_sources);
+        for (key, value) in metadata.attributes() {
+            writer.set_attribute(key, value);
+        }
+        if let Some(release) = release {
+            writer.set_attribute("release", release);
+        }
 
         // Resolve source files from the object and write their contents into the archive. Skip to
         // upload this bundle if no source could be written. This can happen if there is no file or
         // line information in the object file, or if none of the files could be resolved.
-        let written = writer
-            .with_skipped_file_callback(|skipped_info| info!("{skipped_info}"))
-            .write_object_with_filter(object, dif.file_name(), filter_bad_sources)?;
-        if !written {
+        let summary = options.write_object(writer, object, dif.file_name())?;
+        oversized.extend(summary.oversized);
+        transcoded.extend(summary.transcoded);
+        if summary.written == 0 {
             debug!("No sources found for {}", name);
             continue;
         }
@@ -1198,7 +1719,7 @@ fn create_source_bundles<'a>(
 
     let len = source_bundles.len();
     pb.finish_and_clear();
-    println!(
+    quiet_println!(
         "{} Resolved source code for {} debug information {}",
         style(">").dim(),
         style(len).yellow(),
@@ -1208,12 +1729,51 @@ fn create_source_bundles<'a>(
         }
     );
 
+    if !oversized.is_empty() {
+        quiet_println!(
+            "{} Skipped {} source {} larger than {}:",
+            style(">").dim(),
+            style(oversized.len()).yellow(),
+            match oversized.len() {
+                1 => "file",
+                _ => "files",
+            },
+            HumanBytes(max_source_size)
+        );
+        for (path, size) in &oversized {
+            quiet_println!("  {} ({})", path, HumanBytes(*size));
+        }
+    }
+
+    if !transcoded.is_empty() {
+        quiet_println!(
+            "{} Transcoded {} source {} to UTF-8:",
+            style(">").dim(),
+            style(transcoded.len()).yellow(),
+            match transcoded.len() {
+                1 => "file",
+                _ => "files",
+            }
+        );
+        for (path, encoding) in &transcoded {
+            quiet_println!("  {} (from {})", path, encoding);
+        }
+    }
+
     Ok(source_bundles)
 }
 
 fn create_il2cpp_mappings<'a>(difs: &[DifMatch<'a>]) -> Result<Vec<DifMatch<'a>>> {
     let mut line_mappings = Vec::new();
 
+    // Objects that already come with a line mapping from the build do not need
+    // to have one computed from their sources.
+    let mapped_ids = difs
+        .iter()
+        .filter(|dif| dif.format() == DifFormat::Il2Cpp)
+        .filter_map(|dif| dif.debug_id)
+        .collect::<BTreeSet<_>>();
+
     let progress_style = ProgressStyle::default_bar().template(
         "{prefix:.dim} Resolving il2cpp mappings... {msg:.dim}\
          \n{wide_bar}  {pos}/{len}",
@@ -1228,6 +1788,10 @@ fn create_il2cpp_mappings<'a>(difs: &[DifMatch<'a>]) -> Result<Vec<DifMatch<'a>>
         pb.set_message(dif.path());
 
         if let Some(object) = dif.object() {
+            if dif.debug_id.map_or(false, |id| mapped_ids.contains(&id)) {
+                continue;
+            }
+
             let temp_file = TempFile::create()?;
             let written = {
                 let mut writer = BufWriter::new(temp_file.open()?);
@@ -1249,7 +1813,7 @@ fn create_il2cpp_mappings<'a>(difs: &[DifMatch<'a>]) -> Result<Vec<DifMatch<'a>>
 
     let len = line_mappings.len();
     pb.finish_and_clear();
-    println!(
+    quiet_println!(
         "{} Resolved il2cpp mappings for {} debug information {}",
         style(">").dim(),
         style(len).yellow(),
@@ -1262,6 +1826,118 @@ fn create_il2cpp_mappings<'a>(difs: &[DifMatch<'a>]) -> Result<Vec<DifMatch<'a>>
     Ok(line_mappings)
 }
 
+/// Returns whether the DIF is a Dart symbol file written by `flutter build --split-debug-info`.
+fn is_dart_symbols(dif: &DifMatch<'_>) -> bool {
+    dif.format() == DifFormat::Object(FileFormat::Elf)
+        && Path::new(dif.path()).extension() == Some(OsStr::new(DART_SYMBOLS_EXTENSION))
+}
+
+/// Locates the Dart symbol map to upload, either as configured or by searching
+/// the search paths for a file named [`DART_SYMBOL_MAP_NAME`].
+fn find_dart_symbol_map(options: &DifUpload) -> Option<PathBuf> {
+    if let Some(ref path) = options.dart_symbol_map {
+        return Some(path.clone());
+    }
+
+    options
+        .paths
+        .iter()
+        .filter(|path| path.is_dir())
+        .flat_map(|path| WalkDir::new(path).follow_links(true).into_iter())
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_type().is_file() && entry.file_name() == DART_SYMBOL_MAP_NAME)
+        .map(|entry| entry.into_path())
+}
+
+/// Creates a Dart symbol map [`DifMatch`] for the object with the given debug identifier.
+///
+/// Flutter writes the symbol map as a JSON array of alternating obfuscated and
+/// original names, which applies to the symbol files of all architectures. It
+/// is wrapped into an object with a `__debug-id__` entry, like il2cpp line
+/// mappings, to associate it with the symbol file and make it unique for every
+/// object.
+fn create_dart_symbol_map<'a>(
+    mapping: &[String],
+    debug_id: DebugId,
+    name: String,
+) -> Result<DifMatch<'a>> {
+    let temp_file = TempFile::create()?;
+    {
+        let mut writer = BufWriter::new(temp_file.open()?);
+        serde_json::to_writer(
+            &mut writer,
+            &serde_json::json!({
+                "__debug-id__": { debug_id.to_string(): {} },
+                "mapping": mapping,
+            }),
+        )?;
+        writer.flush()?;
+    }
+
+    DifMatch::from_temp_dart_symbol_map(temp_file, name, debug_id)
+}
+
+/// Creates Dart symbol maps for all Dart symbol files among the given DIFs.
+fn create_dart_symbol_maps<'a>(
+    difs: &[DifMatch<'a>],
+    options: &DifUpload,
+) -> Result<Vec<DifMatch<'a>>> {
+    let debug_ids = difs
+        .iter()
+        .filter(|dif| is_dart_symbols(dif))
+        .filter_map(|dif| dif.debug_id)
+        .collect::<BTreeSet<_>>();
+    if debug_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let Some(path) = find_dart_symbol_map(options) else {
+        debug!(
+            "no Dart symbol map found for {} symbol files",
+            debug_ids.len()
+        );
+        return Ok(Vec::new());
+    };
+
+    let contents = fs::read(&path)
+        .with_context(|| format!("Failed to read Dart symbol map {}", path.display()))?;
+    let mapping = match serde_json::from_slice::<Vec<String>>(&contents) {
+        Ok(mapping) if mapping.len() % 2 == 0 => mapping,
+        _ => bail!(
+            "Invalid Dart symbol map {}: expected a JSON array of obfuscated and original names",
+            path.display()
+        ),
+    };
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| DART_SYMBOL_MAP_NAME.to_owned());
+
+    let mut symbol_maps = Vec::new();
+    for debug_id in debug_ids {
+        let dif = create_dart_symbol_map(&mapping, debug_id, name.clone())?;
+        if options.validate_dif(&dif) {
+            symbol_maps.push(dif);
+        }
+    }
+
+    if !symbol_maps.is_empty() {
+        quiet_println!(
+            "{} Associated Dart symbol map {} with {} debug information {}",
+            style(">").dim(),
+            style(path.display()).dim(),
+            style(symbol_maps.len()).yellow(),
+            match symbol_maps.len() {
+                1 => "file",
+                _ => "files",
+            }
+        );
+    }
+
+    Ok(symbol_maps)
+}
+
 /// Calls the assemble endpoint and returns the state for every `DifMatch` along
 /// with info on missing chunks.
 ///
@@ -1305,7 +1981,7 @@ fn try_assemble_difs<'data, 'm>(
                 // it shows up in the final report.
                 difs.push(chunked_match);
             }
-            ChunkedFileState::Assembling => {
+            ChunkedFileState::Created | ChunkedFileState::Assembling => {
                 // This file is currently assembling. The caller will have to poll this file later
                 // until it either resolves or errors.
                 difs.push(chunked_match);
@@ -1349,6 +2025,7 @@ fn try_assemble_difs<'data, 'm>(
 fn upload_missing_chunks(
     missing_info: &MissingDifsInfo<'_, '_>,
     chunk_options: &ChunkUploadOptions,
+    compression: Option<ChunkCompression>,
 ) -> Result<()> {
     let (difs, chunks) = missing_info;
 
@@ -1366,9 +2043,9 @@ fn upload_missing_chunks(
         if difs.len() == 1 { "" } else { "s" }
     ));
 
-    upload_chunks(chunks, chunk_options, progress_style)?;
+    upload_chunks(chunks, chunk_options, progress_style, compression)?;
 
-    println!(
+    quiet_println!(
         "{} Uploaded {} missing debug information {}",
         style(">").dim(),
         style(difs.len().to_string()).yellow(),
@@ -1381,6 +2058,42 @@ fn upload_missing_chunks(
     Ok(())
 }
 
+/// Uploads the chunks specified in `missing_info` like `upload_missing_chunks`,
+/// resuming the upload if it is interrupted.
+///
+/// If the chunks cannot be uploaded because of a network error, even after the
+/// failed requests were retried, the assemble endpoint is queried again. Only the
+/// chunks that the server still reports as missing are then sent again. The upload
+/// is resumed at most as often as failed requests are retried.
+fn upload_missing_chunks_resumable(
+    chunked: &[ChunkedDifMatch<'_>],
+    missing_info: &MissingDifsInfo<'_, '_>,
+    options: &DifUpload,
+    chunk_options: &ChunkUploadOptions,
+) -> Result<()> {
+    let max_resumes = Config::current().get_max_retry_count()?;
+    let mut result = upload_missing_chunks(missing_info, chunk_options, options.compression);
+
+    for resume in 1..=max_resumes {
+        match result {
+            Err(ref err) if is_network_error(err) => {
+                warn!("chunk upload failed ({err:#}), resuming upload ({resume}/{max_resumes})");
+            }
+            _ => break,
+        }
+
+        let remaining = try_assemble_difs(chunked, options)?;
+        result = upload_missing_chunks(&remaining, chunk_options, options.compression);
+    }
+
+    result
+}
+
+fn is_network_error(err: &Error) -> bool {
+    err.downcast_ref::<ApiError>()
+        .map_or(false, ApiError::is_network_error)
+}
+
 /// Renders the given detail string to the command line. If the `detail` is
 /// either missing or empty, the optional fallback will be used.
 fn render_detail(detail: &Option<String>, fallback: Option<&str>) {
@@ -1397,7 +2110,7 @@ fn render_detail(detail: &Option<String>, fallback: Option<&str>) {
 
     for line in string.lines() {
         if !line.is_empty() {
-            println!("        {}", style(line).dim());
+            quiet_println!("        {}", style(line).dim());
         }
     }
 }
@@ -1422,6 +2135,8 @@ fn poll_dif_assemble(
     pb.set_prefix(">");
 
     let assemble_start = Instant::now();
+    let mut poll_interval = ASSEMBLE_POLL_INTERVAL_MIN;
+    let mut timed_out = false;
 
     let request = difs
         .iter()
@@ -1450,10 +2165,6 @@ fn poll_dif_assemble(
             break response;
         }
 
-        if assemble_start.elapsed() > options.max_wait {
-            break response;
-        }
-
         let pending = response
             .iter()
             .filter(|&(_, r)| r.state.is_pending())
@@ -1465,32 +2176,53 @@ fn poll_dif_assemble(
             break response;
         }
 
-        thread::sleep(ASSEMBLE_POLL_INTERVAL);
+        let elapsed = assemble_start.elapsed();
+        if elapsed > options.max_wait {
+            timed_out = true;
+            break response;
+        }
+
+        thread::sleep(poll_interval.min(options.max_wait - elapsed));
+        poll_interval = (poll_interval * 2).min(ASSEMBLE_POLL_INTERVAL_MAX);
     };
 
     pb.finish_and_clear();
-    if response.values().any(|r| r.state.is_pending()) {
-        println!("{} File upload complete:\n", style(">").dim());
+    if timed_out {
+        quiet_println!(
+            "{} Timed out after {}s waiting for files to be processed:\n",
+            style(">").dim(),
+            options.max_wait.as_secs()
+        );
+    } else if response.values().any(|r| r.state.is_pending()) {
+        quiet_println!("{} File upload complete:\n", style(">").dim());
     } else {
-        println!("{} File processing complete:\n", style(">").dim());
+        quiet_println!("{} File processing complete:\n", style(">").dim());
     }
 
     let (errors, mut successes): (Vec<_>, _) = response
         .into_iter()
         .partition(|(_, r)| r.state.is_err() || options.wait && r.state.is_pending());
 
+    let difs_by_checksum: BTreeMap<_, _> = difs.iter().map(|m| (m.checksum, m)).collect();
+
     // Print a summary of all successes first, so that errors show up at the
-    // bottom for the user
-    successes.sort_by_key(|(_, success)| {
+    // bottom for the user. Entries without a "dif" record are sorted by their
+    // local name, so that the output does not depend on the response order.
+    successes.sort_by_key(|(checksum, success)| {
         success
             .dif
             .as_ref()
             .map(|x| x.object_name.as_str())
+            .or_else(|| difs_by_checksum.get(checksum).map(|dif| dif.path()))
             .unwrap_or("")
             .to_owned()
     });
 
-    let difs_by_checksum: BTreeMap<_, _> = difs.iter().map(|m| (m.checksum, m)).collect();
+    for &(checksum, _) in &successes {
+        if let Some(dif) = difs_by_checksum.get(&checksum) {
+            options.record(dif, Some(checksum), UploadStatus::Uploaded);
+        }
+    }
 
     for &(checksum, ref success) in &successes {
         // Silently skip all OK entries without a "dif" record since the server
@@ -1498,7 +2230,7 @@ fn poll_dif_assemble(
         if let Some(ref dif) = success.dif {
             // Files that have completed processing will contain a `dif` record
             // returned by the server. Use this to show detailed information.
-            println!(
+            quiet_println!(
                 "  {:>7} {} ({}; {}{})",
                 style("OK").green(),
                 style(&dif.id()).dim(),
@@ -1520,9 +2252,10 @@ fn poll_dif_assemble(
                 ParsedDif::BcSymbolMap => String::from("bcsymbolmap"),
                 ParsedDif::UuidMap => String::from("uuidmap"),
                 ParsedDif::Il2Cpp => String::from("il2cpp"),
+                ParsedDif::DartSymbolMap => String::from("dartsymbolmap"),
             };
 
-            println!(
+            quiet_println!(
                 "  {:>8} {} ({}; {}{})",
                 style("UPLOADED").yellow(),
                 style(dif.debug_id.map(|id| id.to_string()).unwrap_or_default()).dim(),
@@ -1555,13 +2288,25 @@ fn poll_dif_assemble(
     let has_errors = !errored.is_empty();
     for (dif, error) in errored {
         let fallback = match error.state {
-            ChunkedFileState::Assembling => Some("The file is still processing and not ready yet"),
+            ChunkedFileState::Created | ChunkedFileState::Assembling => {
+                Some("The file is still processing and not ready yet")
+            }
             ChunkedFileState::NotFound => Some("The file could not be saved"),
             _ => Some("An unknown error occurred"),
         };
 
-        println!("  {:>7} {}", style("ERROR").red(), dif.file_name());
+        if error.state.is_pending() {
+            quiet_println!("  {:>7} {}", style("PENDING").yellow(), dif.file_name());
+        } else {
+            quiet_println!("  {:>7} {}", style("ERROR").red(), dif.file_name());
+        }
         render_detail(&error.detail, fallback);
+
+        let message = match error.detail {
+            Some(ref detail) if !detail.is_empty() => detail.clone(),
+            _ => fallback.unwrap_or_default().to_owned(),
+        };
+        options.record(dif, Some(dif.checksum), UploadStatus::Errored { message });
     }
 
     // Return only successful uploads
@@ -1571,48 +2316,93 @@ fn poll_dif_assemble(
     ))
 }
 
-/// Uploads debug info files using the chunk-upload endpoint.
-fn upload_difs_chunked(
+/// Searches and processes all debug information files that should be uploaded.
+///
+/// Additional files like il2cpp line mappings and source bundles are only
+/// created for `chunked` uploads, since the legacy upload does not support them.
+fn collect_difs(
     options: &DifUpload,
-    chunk_options: &ChunkUploadOptions,
-) -> Result<(Vec<DebugInfoFile>, bool)> {
+    pool: &ThreadPool,
+    chunked: bool,
+) -> Result<Vec<DifMatch<'static>>> {
     // Search for debug files in the file system and ZIPs
-    let found = search_difs(options)?;
+    let found = search_difs(options, pool)?;
+    if found.is_empty() {
+        quiet_println!("{} No debug information files found", style(">").dim());
+        return Ok(Default::default());
+    }
+
+    // Skip files the server already has before hashing them
+    let found = filter_known_difs(found, options)?;
     if found.is_empty() {
-        println!("{} No debug information files found", style(">").dim());
         return Ok(Default::default());
     }
 
     // Try to resolve BCSymbolMaps
     let symbol_map = options.symbol_map.as_deref();
-    let mut processed = process_symbol_maps(found, symbol_map)?;
+    let mut processed = process_symbol_maps(found, symbol_map, options.require_symbol_maps)?;
+
+    if chunked {
+        let dart_symbol_maps = create_dart_symbol_maps(&processed, options)?;
+        processed.extend(dart_symbol_maps);
+    }
 
-    if options.upload_il2cpp_mappings {
+    if chunked && options.upload_il2cpp_mappings {
         let il2cpp_mappings = create_il2cpp_mappings(&processed)?;
         processed.extend(il2cpp_mappings);
     }
 
     // Resolve source code context if specified
-    if options.include_sources {
-        let source_bundles = create_source_bundles(&processed, options.upload_il2cpp_mappings)?;
+    if chunked && options.include_sources {
+        let max_source_size = options
+            .max_source_size
+            .unwrap_or_else(|| Config::current().get_max_dif_item_size());
+        let source_bundles = create_source_bundles(
+            &processed,
+            options.upload_il2cpp_mappings,
+            max_source_size,
+            &options.metadata,
+            options.release.as_deref(),
+        )?;
         processed.extend(source_bundles);
     }
 
+    Ok(processed)
+}
+
+/// Uploads debug info files using the chunk-upload endpoint.
+fn upload_difs_chunked(
+    options: &DifUpload,
+    chunk_options: &ChunkUploadOptions,
+) -> Result<(Vec<DebugInfoFile>, bool)> {
+    let pool = ThreadPoolBuilder::new().build()?;
+    let processed = collect_difs(options, &pool, true)?;
+    if processed.is_empty() {
+        return Ok(Default::default());
+    }
+
     // Calculate checksums and chunks
-    let chunked = prepare_difs(processed, |m| {
+    let chunked = prepare_difs(processed, &pool, |m| {
         ChunkedDifMatch::from(m, chunk_options.chunk_size)
     })?;
 
     // Upload missing chunks to the server and remember incomplete difs
     let missing_info = try_assemble_difs(&chunked, options)?;
-    upload_missing_chunks(&missing_info, chunk_options)?;
+    upload_missing_chunks_resumable(&chunked, &missing_info, options, chunk_options)?;
 
     // Only if DIFs were missing, poll until assembling is complete
     let (missing_difs, _) = missing_info;
+    let missing_checksums: BTreeSet<_> = missing_difs.iter().map(|dif| dif.checksum).collect();
+    for dif in &chunked {
+        if !missing_checksums.contains(&dif.checksum) {
+            options.record(dif, Some(dif.checksum), UploadStatus::SkippedKnown);
+        }
+    }
+
     if !missing_difs.is_empty() {
         poll_dif_assemble(&missing_difs, options)
     } else {
-        println!(
+        quiet_println!(
             "{} Nothing to upload, all files are on the server",
             style(">").dim()
         );
@@ -1621,6 +2411,81 @@ fn upload_difs_chunked(
     }
 }
 
+/// Removes object files from `difs` which are already known to the server.
+///
+/// An object file is known if the server has a debug information file with the
+/// same debug id, which provides at least all features of the local file. This
+/// only requires the debug ids of the files, so it is much cheaper than computing
+/// checksums. Unless `skip_known` is set, all files are returned unchanged.
+fn filter_known_difs<'data>(
+    difs: Vec<DifMatch<'data>>,
+    options: &DifUpload,
+) -> Result<Vec<DifMatch<'data>>> {
+    if !options.skip_known {
+        return Ok(difs);
+    }
+
+    let debug_ids: BTreeSet<_> = difs
+        .iter()
+        .filter(|dif| dif.object().is_some())
+        .filter_map(|dif| dif.debug_id)
+        .collect();
+
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+    let mut known = BTreeMap::new();
+    for debug_id in debug_ids {
+        let files =
+            authenticated_api.list_difs_by_debug_id(&options.org, &options.project, debug_id)?;
+        known.insert(debug_id, files);
+    }
+
+    let (skipped, remaining): (Vec<_>, Vec<_>) = difs.into_iter().partition(|dif| {
+        let (Some(object), Some(debug_id)) = (dif.object(), dif.debug_id) else {
+            return false;
+        };
+        let features = [
+            ("symtab", object.has_symbols()),
+            ("debug", object.has_debug_info()),
+            ("unwind", object.has_unwind_info()),
+            ("sources", object.has_sources()),
+        ];
+        known.get(&debug_id).map_or(false, |files| {
+            files.iter().any(|file| {
+                features
+                    .iter()
+                    .all(|(name, has)| !has || file.data.features.iter().any(|f| f == name))
+            })
+        })
+    });
+
+    for dif in &skipped {
+        info!("Skipping {} which is already known to the server", dif.name);
+        options.record(dif, None, UploadStatus::SkippedKnown);
+    }
+
+    if !skipped.is_empty() {
+        quiet_println!(
+            "{} Skipped {} debug information {} already known to the server",
+            style(">").dim(),
+            style(skipped.len()).yellow(),
+            match skipped.len() {
+                1 => "file",
+                _ => "files",
+            }
+        );
+    }
+
+    if remaining.is_empty() {
+        quiet_println!(
+            "{} Nothing to upload, all files are on the server",
+            style(">").dim()
+        );
+    }
+
+    Ok(remaining)
+}
+
 /// Returns debug files missing on the server.
 fn get_missing_difs<'data>(
     objects: Vec<HashedDifMatch<'data>>,
@@ -1641,10 +2506,13 @@ fn get_missing_difs<'data>(
         )?
     };
 
-    let missing = objects
+    let (missing, known): (Vec<_>, Vec<_>) = objects
         .into_iter()
-        .filter(|sym| missing_checksums.contains(&sym.checksum()))
-        .collect();
+        .partition(|sym| missing_checksums.contains(&sym.checksum()));
+
+    for dif in &known {
+        options.record(dif, Some(dif.checksum()), UploadStatus::SkippedKnown);
+    }
 
     info!("Missing debug information files: {:#?}", &missing);
     Ok(missing)
@@ -1658,9 +2526,18 @@ fn create_batch_archive(difs: &[HashedDifMatch<'_>]) -> Result<TempFile> {
 
     {
         let mut zip = ZipWriter::new(tf.open()?);
+        let mut names = HashSet::new();
 
         for symbol in difs {
-            zip.start_file(symbol.file_name(), FileOptions::default())?;
+            // The slices of a fat Mach-O binary share the name of the binary,
+            // but must not replace each other when the archive is extracted.
+            let mut name = symbol.file_name().to_owned();
+            if !names.insert(name.clone()) {
+                if let Some(debug_id) = symbol.debug_id {
+                    name = format!("{debug_id}/{name}");
+                }
+            }
+            zip.start_file(name, FileOptions::default())?;
             copy_with_progress(&pb, &mut symbol.data(), &mut zip)?;
         }
     }
@@ -1679,16 +2556,16 @@ fn upload_in_batches(
     let mut dsyms = Vec::new();
 
     for (i, (batch, _)) in objects.batches(max_size, MAX_CHUNKS).enumerate() {
-        println!("\n{}", style(format!("Batch {}", i + 1)).bold());
+        quiet_println!("\n{}", style(format!("Batch {}", i + 1)).bold());
 
-        println!(
+        quiet_println!(
             "{} Compressing {} debug symbol files",
             style(">").dim(),
             style(batch.len()).yellow()
         );
         let archive = create_batch_archive(batch)?;
 
-        println!("{} Uploading debug symbol files", style(">").dim());
+        quiet_println!("{} Uploading debug symbol files", style(">").dim());
         dsyms.extend(
             api.authenticated()?
                 .region_specific(&options.org)
@@ -1701,37 +2578,44 @@ fn upload_in_batches(
 
 /// Uploads debug info files using the legacy endpoint.
 fn upload_difs_batched(options: &DifUpload) -> Result<Vec<DebugInfoFile>> {
-    // Search for debug files in the file system and ZIPs
-    let found = search_difs(options)?;
-    if found.is_empty() {
-        println!("{} No debug information files found", style(">").dim());
+    let pool = ThreadPoolBuilder::new().build()?;
+    let processed = collect_difs(options, &pool, false)?;
+    if processed.is_empty() {
         return Ok(Default::default());
     }
 
-    // Try to resolve BCSymbolMaps
-    let symbol_map = options.symbol_map.as_deref();
-    let processed = process_symbol_maps(found, symbol_map)?;
-
     // Calculate checksums
-    let hashed = prepare_difs(processed, HashedDifMatch::from)?;
+    let hashed = prepare_difs(processed, &pool, HashedDifMatch::from)?;
 
     // Check which files are missing on the server
     let missing = get_missing_difs(hashed, options)?;
     if missing.is_empty() {
-        println!(
+        quiet_println!(
             "{} Nothing to upload, all files are on the server",
             style(">").dim()
         );
-        println!("{} Nothing to upload", style(">").dim());
+        quiet_println!("{} Nothing to upload", style(">").dim());
         return Ok(Default::default());
     }
 
     // Upload missing DIFs in batches
-    let uploaded = upload_in_batches(&missing, options)?;
+    let uploaded = match upload_in_batches(&missing, options) {
+        Ok(uploaded) => uploaded,
+        Err(err) => {
+            for dif in &missing {
+                let message = format!("{err:#}");
+                options.record(dif, Some(dif.checksum()), UploadStatus::Errored { message });
+            }
+            return Err(err);
+        }
+    };
+    for dif in &missing {
+        options.record(dif, Some(dif.checksum()), UploadStatus::Uploaded);
+    }
     if !uploaded.is_empty() {
-        println!("{} File upload complete:\n", style(">").dim());
+        quiet_println!("{} File upload complete:\n", style(">").dim());
         for dif in &uploaded {
-            println!(
+            quiet_println!(
                 "  {} ({}; {})",
                 style(&dif.id()).dim(),
                 &dif.object_name,
@@ -1763,6 +2647,8 @@ pub enum DifFormat {
     PList,
     /// A Unity il2cpp line mapping file.
     Il2Cpp,
+    /// A Dart symbol map of an obfuscated Flutter build.
+    DartSymbolMap,
 }
 
 /// Searches, processes and uploads debug information files (DIFs).
@@ -1803,17 +2689,29 @@ pub struct DifUpload {
     features: ObjectDifFeatures,
     extensions: BTreeSet<OsString>,
     symbol_map: Option<PathBuf>,
+    require_symbol_maps: bool,
     zips_allowed: bool,
+    ignore_files: Vec<PathBuf>,
+    use_gitignore: bool,
     max_file_size: u64,
     max_wait: Duration,
     pdbs_allowed: bool,
     portablepdbs_allowed: bool,
     sources_allowed: bool,
     include_sources: bool,
+    max_source_size: Option<u64>,
     bcsymbolmaps_allowed: bool,
     wait: bool,
     upload_il2cpp_mappings: bool,
     il2cpp_mappings_allowed: bool,
+    dart_symbol_map: Option<PathBuf>,
+    dart_symbol_maps_allowed: bool,
+    compression: Option<ChunkCompression>,
+    skip_known: bool,
+    symbol_servers: Vec<String>,
+    metadata: UploadMetadata,
+    release: Option<String>,
+    report: Mutex<Vec<UploadReportEntry>>,
 }
 
 impl DifUpload {
@@ -1843,17 +2741,29 @@ impl DifUpload {
             features: ObjectDifFeatures::all(),
             extensions: BTreeSet::new(),
             symbol_map: None,
+            require_symbol_maps: false,
             zips_allowed: true,
+            ignore_files: Vec::new(),
+            use_gitignore: false,
             max_file_size: DEFAULT_MAX_DIF_SIZE,
             max_wait: DEFAULT_MAX_WAIT,
             pdbs_allowed: false,
             portablepdbs_allowed: false,
             sources_allowed: false,
             include_sources: false,
+            max_source_size: None,
             bcsymbolmaps_allowed: false,
             wait: false,
             upload_il2cpp_mappings: false,
             il2cpp_mappings_allowed: false,
+            dart_symbol_map: None,
+            dart_symbol_maps_allowed: false,
+            compression: None,
+            skip_known: false,
+            symbol_servers: Vec::new(),
+            metadata: UploadMetadata::default(),
+            release: None,
+            report: Mutex::new(Vec::new()),
         }
     }
 
@@ -1866,6 +2776,11 @@ impl DifUpload {
         self
     }
 
+    /// Returns the paths to search for debug information files.
+    pub fn get_search_paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
     /// Adds paths to search for debug information files.
     pub fn search_paths<I>(&mut self, paths: I) -> &mut Self
     where
@@ -1901,6 +2816,17 @@ impl DifUpload {
         self
     }
 
+    /// Add a file extension to filter for.
+    ///
+    /// By default, files with any extension will be included.
+    pub fn filter_extension<S>(&mut self, extension: S) -> &mut Self
+    where
+        S: Into<OsString>,
+    {
+        self.extensions.insert(extension.into());
+        self
+    }
+
     /// Add an `ObjectFeature` to filter for.
     ///
     /// By default, all object features will be included.
@@ -1910,16 +2836,24 @@ impl DifUpload {
     }
 
     /// Set a path containing BCSymbolMaps to resolve hidden symbols in dSYMs
-    /// obtained from iTunes Connect. This requires the `dsymutil` command.
+    /// obtained from iTunes Connect. Resolving requires the `dsymutil` command.
     ///
     /// By default, hidden symbol resolution will be skipped.
-    pub fn symbol_map<P>(&mut self, path: P) -> Result<&mut Self>
+    pub fn symbol_map<P>(&mut self, path: P) -> &mut Self
     where
         P: Into<PathBuf>,
     {
-        which("dsymutil").map_err(|_| format_err!("Command `dsymutil` not found"))?;
         self.symbol_map = Some(path.into());
-        Ok(self)
+        self
+    }
+
+    /// Set whether dSYMs with hidden symbols but without a BCSymbolMap fail the
+    /// upload instead of being uploaded with unresolved symbols.
+    ///
+    /// Defaults to `false`.
+    pub fn require_symbol_maps(&mut self, require: bool) -> &mut Self {
+        self.require_symbol_maps = require;
+        self
     }
 
     /// Set whether opening and searching ZIPs for debug information files is
@@ -1931,6 +2865,26 @@ impl DifUpload {
         self
     }
 
+    /// Adds a file with gitignore rules to skip matching files and folders.
+    ///
+    /// The rules are applied relative to each search path.
+    pub fn ignore_file<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.ignore_files.push(path.into());
+        self
+    }
+
+    /// Set whether `.gitignore` and `.git/info/exclude` files found in the
+    /// search paths should be respected.
+    ///
+    /// Defaults to `false`.
+    pub fn use_gitignore(&mut self, use_gitignore: bool) -> &mut Self {
+        self.use_gitignore = use_gitignore;
+        self
+    }
+
     /// Set whether source files should be resolved during the scan process and
     /// uploaded as a separate archive.
     ///
@@ -1940,6 +2894,29 @@ impl DifUpload {
         self
     }
 
+    /// Set the note and metadata stored in the manifest of source bundles
+    /// created with `include_sources`.
+    pub fn metadata(&mut self, metadata: UploadMetadata) -> &mut Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Set the release stored in the manifest of source bundles created with
+    /// `include_sources`.
+    pub fn release(&mut self, release: Option<String>) -> &mut Self {
+        self.release = release;
+        self
+    }
+
+    /// Set the maximum size of a source file to include in source bundles.
+    /// Larger files are skipped with a warning.
+    ///
+    /// Defaults to the `dif.max_item_size` config value, or 1 MiB.
+    pub fn max_source_size(&mut self, size: u64) -> &mut Self {
+        self.max_source_size = Some(size);
+        self
+    }
+
     /// Set whether the upload should wait for the server to complete processing
     /// files or exit immediately after the upload.
     ///
@@ -1949,6 +2926,14 @@ impl DifUpload {
         self
     }
 
+    /// Sets the compression used for uploading chunks, if accepted by the server.
+    ///
+    /// Defaults to the best compression accepted by the server.
+    pub fn compression(&mut self, compression: ChunkCompression) -> &mut Self {
+        self.compression = Some(compression);
+        self
+    }
+
     /// Sets the maximum length of time the upload should wait for the server to complete processing.
     ///
     /// Defaults to [`DEFAULT_MAX_WAIT`].
@@ -1965,6 +2950,40 @@ impl DifUpload {
         self
     }
 
+    /// Set the Dart symbol map to upload along with Dart symbol files.
+    ///
+    /// By default, a file named [`DART_SYMBOL_MAP_NAME`] is searched in the
+    /// search paths, which covers the `build/` directory of Flutter projects.
+    pub fn dart_symbol_map<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.dart_symbol_map = Some(path.into());
+        self
+    }
+
+    /// Set whether object files whose debug id is already known to the server
+    /// should be skipped before computing checksums.
+    ///
+    /// Defaults to `false`.
+    pub fn skip_known(&mut self, skip_known: bool) -> &mut Self {
+        self.skip_known = skip_known;
+        self
+    }
+
+    /// Adds a symbol server to fetch PDBs from.
+    ///
+    /// PDBs referenced by PE files in the search paths are fetched from the
+    /// symbol servers in the order they were added, unless they were found
+    /// locally. The servers must use the SymStore layout.
+    pub fn symbol_server<S>(&mut self, url: S) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.symbol_servers.push(url.into());
+        self
+    }
+
     /// Performs the search for DIFs and uploads them.
     ///
     /// ```
@@ -1979,14 +2998,88 @@ impl DifUpload {
     /// latter can be used to indicate a fail state from the upload.
     pub fn upload(&mut self) -> Result<(Vec<DebugInfoFile>, bool)> {
         if self.paths.is_empty() {
-            println!("{}: No paths were provided.", style("Warning").yellow());
+            quiet_println!("{}: No paths were provided.", style("Warning").yellow());
             return Ok(Default::default());
         }
 
+        match self.fetch_chunk_upload_options()? {
+            Some(ref chunk_options)
+                if chunk_options.supports(ChunkUploadCapability::DebugFiles) =>
+            {
+                upload_difs_chunked(self, chunk_options)
+            }
+            _ => Ok((upload_difs_batched(self)?, false)),
+        }
+    }
+
+    /// Uploads the debug information files in the given paths, instead of the
+    /// configured search paths.
+    ///
+    /// All other options apply as in `upload`. This is used to upload files
+    /// that changed after an upload.
+    pub fn upload_paths(&mut self, paths: Vec<PathBuf>) -> Result<(Vec<DebugInfoFile>, bool)> {
+        let search_paths = mem::replace(&mut self.paths, paths);
+        let result = self.upload();
+        self.paths = search_paths;
+        result
+    }
+
+    /// Searches and processes debug information files like `upload`, but
+    /// returns the files that would be uploaded instead of uploading them.
+    ///
+    /// Apart from fetching the server's upload options, this does not
+    /// communicate with the server.
+    pub fn dry_run(&mut self) -> Result<Vec<DifManifestEntry>> {
+        if self.paths.is_empty() {
+            quiet_println!("{}: No paths were provided.", style("Warning").yellow());
+            return Ok(Default::default());
+        }
+
+        let chunked = self.fetch_chunk_upload_options()?.map_or(false, |options| {
+            options.supports(ChunkUploadCapability::DebugFiles)
+        });
+
+        let pool = ThreadPoolBuilder::new().build()?;
+        let processed = collect_difs(self, &pool, chunked)?;
+        let hashed = prepare_difs(processed, &pool, HashedDifMatch::from)?;
+
+        let mut entries: Vec<_> = hashed.iter().map(DifManifestEntry::from).collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Returns the outcome of every file considered by `upload` so far.
+    ///
+    /// This is also available if the upload failed, in which case it only
+    /// contains the files that were handled before the failure.
+    pub fn report(&self) -> Vec<UploadReportEntry> {
+        self.report.lock().clone()
+    }
+
+    /// Records the outcome of uploading the given file for the report.
+    fn record(&self, dif: &DifMatch<'_>, checksum: Option<Digest>, status: UploadStatus) {
+        self.report.lock().push(UploadReportEntry {
+            path: dif.path().to_owned(),
+            debug_id: dif
+                .debug_id
+                .or_else(|| dif.object().map(Object::debug_id))
+                .map(|id| id.to_string()),
+            ty: format_name(dif.format()),
+            size: dif.size(),
+            checksum: checksum.map(|c| c.to_string()),
+            status,
+        });
+    }
+
+    /// Fetches the chunk upload options of the server and applies the
+    /// server's limits and capabilities to this upload.
+    fn fetch_chunk_upload_options(&mut self) -> Result<Option<ChunkUploadOptions>> {
         let api = Api::current();
-        if let Some(ref chunk_options) = api.authenticated()?.get_chunk_upload_options(&self.org)? {
-            if chunk_options.max_file_size > 0 {
-                self.max_file_size = chunk_options.max_file_size;
+        let chunk_options = api.authenticated()?.get_chunk_upload_options(&self.org)?;
+
+        if let Some(ref chunk_options) = chunk_options {
+            if let Some(max_file_size) = chunk_options.max_file_size() {
+                self.max_file_size = max_file_size;
             }
             if chunk_options.max_wait > 0 {
                 self.max_wait = self
@@ -1999,15 +3092,12 @@ impl DifUpload {
             self.sources_allowed = chunk_options.supports(ChunkUploadCapability::Sources);
             self.bcsymbolmaps_allowed = chunk_options.supports(ChunkUploadCapability::BcSymbolmap);
             self.il2cpp_mappings_allowed = chunk_options.supports(ChunkUploadCapability::Il2Cpp);
-
-            if chunk_options.supports(ChunkUploadCapability::DebugFiles) {
-                self.validate_capabilities();
-                return upload_difs_chunked(self, chunk_options);
-            }
+            self.dart_symbol_maps_allowed =
+                chunk_options.supports(ChunkUploadCapability::DartSymbolMap);
         }
 
         self.validate_capabilities();
-        Ok((upload_difs_batched(self)?, false))
+        Ok(chunk_options)
     }
 
     /// Validate that the server supports all requested capabilities.
@@ -2053,6 +3143,12 @@ impl DifUpload {
         if self.upload_il2cpp_mappings && !self.il2cpp_mappings_allowed {
             warn!("il2cpp line mappings are not supported by the configured Sentry server");
         }
+
+        if (self.dart_symbol_map.is_some() || self.formats.contains(&DifFormat::DartSymbolMap))
+            && !self.dart_symbol_maps_allowed
+        {
+            warn!("Dart symbol maps are not supported by the configured Sentry server");
+        }
     }
 
     /// Determines if this `DebugId` matches the search criteria.
@@ -2074,6 +3170,7 @@ impl DifUpload {
             DifFormat::Object(FileFormat::SourceBundle) if !self.sources_allowed => false,
             DifFormat::Object(FileFormat::PortablePdb) if !self.portablepdbs_allowed => false,
             DifFormat::BcSymbolMap | DifFormat::PList if !self.bcsymbolmaps_allowed => false,
+            DifFormat::DartSymbolMap if !self.dart_symbol_maps_allowed => false,
             format => self.formats.is_empty() || self.formats.contains(&format),
         }
     }
@@ -2102,10 +3199,10 @@ impl DifUpload {
         };
         if too_large {
             warn!(
-                "Skipping debug file since it exceeds {}: {} ({})",
-                HumanBytes(self.max_file_size),
+                "Skipping debug file {} ({}), since it exceeds the maximum file size of {}",
                 name,
                 HumanBytes(file_size.unwrap_or(u64::MAX)),
+                HumanBytes(self.max_file_size),
             );
         }
         !too_large
@@ -2145,3 +3242,32 @@ impl DifUpload {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_difs_parallel_order_is_deterministic() {
+        let mut options = DifUpload::new("wat-org".into(), "wat-project".into());
+        options.search_path("tests/integration/_fixtures");
+        options.pdbs_allowed = true;
+        options.portablepdbs_allowed = true;
+
+        let search = |num_threads| {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            search_difs(&options, &pool)
+                .unwrap()
+                .iter()
+                .map(|dif| (dif.name.clone(), dif.debug_id))
+                .collect::<Vec<_>>()
+        };
+
+        let sequential = search(1);
+        assert!(sequential.len() > 1);
+        assert_eq!(search(8), sequential);
+    }
+}
@@ -0,0 +1,345 @@
+//! Parsing of dotenv-style files with environment variables.
+//!
+//! Lines have the form `KEY=VALUE` and may be prefixed with `export`. Empty
+//! lines and lines starting with `#` are ignored. Values can be quoted:
+//! single quoted values are taken literally, double quoted values support the
+//! escapes `\n`, `\r`, `\t`, `\"`, `\\` and `\$`. Both may span multiple lines.
+//! Unquoted values end at a `#` preceded by whitespace. Variables in values
+//! are not expanded.
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+/// Parses the environment variables in the file at `path`.
+///
+/// The variables are returned in the order they appear in the file.
+pub fn parse_env_file(path: &Path) -> Result<Vec<(String, String)>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read env file {}", path.display()))?;
+    match parse_env_str(&contents) {
+        Ok(vars) => Ok(vars),
+        Err((line, message)) => bail!("{}:{}: {}", path.display(), line, message),
+    }
+}
+
+/// Parses a single `KEY=VALUE` assignment, used as a value_parser for Clap
+/// arguments.
+///
+/// Unlike in env files, the value is taken literally.
+pub fn parse_env_var(s: &str) -> Result<(String, String)> {
+    let Some((key, value)) = s.split_once('=') else {
+        bail!("`{s}` is missing a `=`");
+    };
+    validate_key(key).map_err(anyhow::Error::msg)?;
+    Ok((key.to_owned(), value.to_owned()))
+}
+
+fn validate_key(key: &str) -> Result<(), String> {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid variable name `{key}`"))
+    }
+}
+
+/// Parses the contents of an env file.
+///
+/// On failure, returns the 1-based number of the offending line and a
+/// description of the problem.
+fn parse_env_str(contents: &str) -> Result<Vec<(String, String)>, (usize, String)> {
+    let mut vars = vec![];
+    let mut lines = contents.lines().enumerate().map(|(i, l)| (i + 1, l));
+
+    while let Some((number, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = match line.strip_prefix("export") {
+            Some(rest) if rest.starts_with(char::is_whitespace) => rest.trim_start(),
+            _ => line,
+        };
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err((number, format!("expected KEY=VALUE, got `{line}`")));
+        };
+        let key = key.trim_end();
+        validate_key(key).map_err(|message| (number, message))?;
+
+        let value = value.trim_start();
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                parse_quoted(&value[1..], quote, &mut lines).map_err(|message| (number, message))?
+            }
+            _ => parse_unquoted(value),
+        };
+
+        vars.push((key.to_owned(), value));
+    }
+
+    Ok(vars)
+}
+
+/// Strips a trailing comment and whitespace from an unquoted value.
+fn parse_unquoted(value: &str) -> String {
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| c == '#' && value[..i].ends_with(char::is_whitespace))
+        .map_or(value.len(), |(i, _)| i);
+    value[..end].trim_end().to_owned()
+}
+
+/// Parses a quoted value starting after the opening quote, consuming further
+/// lines until the closing quote is found.
+fn parse_quoted<'a>(
+    first: &str,
+    quote: char,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<String, String> {
+    let mut raw = first.to_owned();
+    loop {
+        if let Some((value, rest)) = find_closing_quote(&raw, quote) {
+            let rest = rest.trim_start();
+            if !rest.is_empty() && !rest.starts_with('#') {
+                return Err(format!("unexpected `{rest}` after quoted value"));
+            }
+            return Ok(value);
+        }
+
+        match lines.next() {
+            Some((_, line)) => {
+                raw.push('\n');
+                raw.push_str(line);
+            }
+            None => return Err(format!("missing closing {quote} for quoted value")),
+        }
+    }
+}
+
+/// Finds the closing quote in `raw`, returning the unescaped value and the
+/// remainder after the quote.
+fn find_closing_quote(raw: &str, quote: char) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = raw.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            return Some((value, &raw[i + 1..]));
+        }
+
+        if c == '\\' && quote == '"' {
+            match chars.next().map(|(_, c)| c) {
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some(c @ ('"' | '\\' | '$')) => value.push(c),
+                Some(c) => {
+                    value.push('\\');
+                    value.push(c);
+                }
+                None => value.push('\\'),
+            }
+        } else {
+            value.push(c);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> Vec<(String, String)> {
+        parse_env_str(contents).unwrap()
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_simple() {
+        assert_eq!(
+            parse("FOO=bar\nBAZ=qux\n"),
+            vars(&[("FOO", "bar"), ("BAZ", "qux")])
+        );
+    }
+
+    #[test]
+    fn test_empty_value() {
+        assert_eq!(parse("FOO=\nBAR= "), vars(&[("FOO", ""), ("BAR", "")]));
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines() {
+        let contents = "# a comment\n\n   \n  # indented comment\nFOO=bar\n";
+        assert_eq!(parse(contents), vars(&[("FOO", "bar")]));
+    }
+
+    #[test]
+    fn test_inline_comments() {
+        assert_eq!(
+            parse("FOO=bar # comment\nURL=http://example.com/#anchor\n"),
+            vars(&[("FOO", "bar"), ("URL", "http://example.com/#anchor")])
+        );
+    }
+
+    #[test]
+    fn test_whitespace_around_assignment() {
+        assert_eq!(parse("  FOO = bar baz  \n"), vars(&[("FOO", "bar baz")]));
+    }
+
+    #[test]
+    fn test_export_prefix() {
+        assert_eq!(
+            parse("export FOO=bar\nexport\tBAR=baz\nexported=1\n"),
+            vars(&[("FOO", "bar"), ("BAR", "baz"), ("exported", "1")])
+        );
+    }
+
+    #[test]
+    fn test_double_quoted() {
+        assert_eq!(
+            parse(r#"FOO="bar # not a comment" # comment"#),
+            vars(&[("FOO", "bar # not a comment")])
+        );
+    }
+
+    #[test]
+    fn test_double_quoted_escapes() {
+        assert_eq!(
+            parse(r#"FOO="a\nb\tc\"d\\e\$f\x""#),
+            vars(&[("FOO", "a\nb\tc\"d\\e$f\\x")])
+        );
+    }
+
+    #[test]
+    fn test_single_quoted_is_literal() {
+        assert_eq!(
+            parse(r#"FOO='a\nb "c" $HOME'"#),
+            vars(&[("FOO", r#"a\nb "c" $HOME"#)])
+        );
+    }
+
+    #[test]
+    fn test_no_expansion() {
+        assert_eq!(parse("FOO=$HOME/bin"), vars(&[("FOO", "$HOME/bin")]));
+    }
+
+    #[test]
+    fn test_multiline_quoted() {
+        let contents = "KEY=\"-----BEGIN-----\nabc\n-----END-----\"\nNEXT=1\n";
+        assert_eq!(
+            parse(contents),
+            vars(&[
+                ("KEY", "-----BEGIN-----\nabc\n-----END-----"),
+                ("NEXT", "1")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keys_keep_order() {
+        assert_eq!(parse("FOO=1\nFOO=2\n"), vars(&[("FOO", "1"), ("FOO", "2")]));
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        assert_eq!(
+            parse("FOO=bar\r\nBAR=\"baz\"\r\n"),
+            vars(&[("FOO", "bar"), ("BAR", "baz")])
+        );
+    }
+
+    #[test]
+    fn test_error_missing_equals() {
+        assert_eq!(
+            parse_env_str("FOO=bar\n\nnot an assignment\n"),
+            Err((3, "expected KEY=VALUE, got `not an assignment`".into()))
+        );
+    }
+
+    #[test]
+    fn test_error_invalid_key() {
+        assert_eq!(
+            parse_env_str("1FOO=bar"),
+            Err((1, "invalid variable name `1FOO`".into()))
+        );
+        assert_eq!(
+            parse_env_str("FOO-BAR=baz"),
+            Err((1, "invalid variable name `FOO-BAR`".into()))
+        );
+        assert_eq!(
+            parse_env_str("=baz"),
+            Err((1, "invalid variable name ``".into()))
+        );
+    }
+
+    #[test]
+    fn test_error_unterminated_quote() {
+        assert_eq!(
+            parse_env_str("A=1\nFOO=\"bar\nBAZ=1\n"),
+            Err((2, "missing closing \" for quoted value".into()))
+        );
+    }
+
+    #[test]
+    fn test_error_trailing_characters() {
+        assert_eq!(
+            parse_env_str("FOO='bar'baz"),
+            Err((1, "unexpected `baz` after quoted value".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_error_names_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.env");
+        fs::write(&path, "FOO=bar\nBAD\n").unwrap();
+
+        let err = parse_env_file(&path).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("{}:2: expected KEY=VALUE, got `BAD`", path.display())
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_missing() {
+        let err = parse_env_file(Path::new("does-not-exist.env")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to read env file does-not-exist.env"
+        );
+    }
+
+    #[test]
+    fn test_parse_env_var() {
+        assert_eq!(
+            parse_env_var("FOO=bar=baz").unwrap(),
+            ("FOO".to_owned(), "bar=baz".to_owned())
+        );
+        assert_eq!(
+            parse_env_var("FOO=").unwrap(),
+            ("FOO".to_owned(), String::new())
+        );
+        assert_eq!(
+            parse_env_var("FOO").unwrap_err().to_string(),
+            "`FOO` is missing a `=`"
+        );
+        assert_eq!(
+            parse_env_var("FO O=bar").unwrap_err().to_string(),
+            "invalid variable name `FO O`"
+        );
+    }
+}
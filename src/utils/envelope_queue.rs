@@ -0,0 +1,148 @@
+//! Queueing of envelopes on disk while Sentry cannot be reached, as used by
+//! `--queue-dir` and `send-queued`.
+//!
+//! Envelopes are stored as `<milliseconds since the epoch>-<uuid>.envelope`,
+//! so that they are sent in the order they were queued and their age does not
+//! depend on file times.
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use log::warn;
+use uuid::Uuid;
+
+use crate::api::envelopes_api::EnvelopesApi;
+
+/// The extension of queued envelopes. Other files in the queue are ignored.
+const EXTENSION: &str = "envelope";
+
+/// An envelope stored in a queue directory.
+pub struct QueuedEnvelope {
+    pub path: PathBuf,
+    pub queued_at: SystemTime,
+}
+
+impl QueuedEnvelope {
+    /// Returns how long ago this envelope was queued.
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.queued_at)
+            .unwrap_or_default()
+    }
+}
+
+/// Stores a serialized envelope in the queue directory, which is created if
+/// it does not exist yet. Returns the path of the queued envelope.
+pub fn enqueue(dir: &Path, body: &[u8]) -> Result<PathBuf> {
+    write_queued(dir, |path| fs::write(path, body))
+}
+
+/// Stores a copy of the envelope in the given file in the queue directory.
+pub fn enqueue_file(dir: &Path, source: &Path) -> Result<PathBuf> {
+    write_queued(dir, |path| fs::copy(source, path).map(|_| ()))
+}
+
+/// Writes a new envelope into the queue.
+///
+/// The envelope is first written under a different name and then renamed, so
+/// that `send-queued` never sees partially written envelopes.
+fn write_queued(dir: &Path, write: impl FnOnce(&Path) -> io::Result<()>) -> Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Could not create queue directory {}", dir.display()))?;
+
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+    let path = dir.join(format!("{millis}-{}.{EXTENSION}", Uuid::new_v4().simple()));
+    let partial = path.with_extension("partial");
+    write(&partial)
+        .and_then(|()| fs::rename(&partial, &path))
+        .with_context(|| format!("Could not queue envelope in {}", dir.display()))?;
+    Ok(path)
+}
+
+/// Returns the envelopes in the queue directory, oldest first.
+pub fn queued_envelopes(dir: &Path) -> Result<Vec<QueuedEnvelope>> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Could not read queue directory {}", dir.display()))?;
+
+    let mut envelopes = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension() != Some(OsStr::new(EXTENSION)) || !path.is_file() {
+            continue;
+        }
+        let queued_at = match queued_at_from_name(&path) {
+            Some(queued_at) => queued_at,
+            None => fs::metadata(&path)?.modified()?,
+        };
+        envelopes.push(QueuedEnvelope { path, queued_at });
+    }
+
+    envelopes.sort_by(|a, b| (a.queued_at, &a.path).cmp(&(b.queued_at, &b.path)));
+    Ok(envelopes)
+}
+
+/// Returns the time an envelope was queued from its file name.
+fn queued_at_from_name(path: &Path) -> Option<SystemTime> {
+    let (millis, _) = path.file_stem()?.to_str()?.split_once('-')?;
+    Some(UNIX_EPOCH + Duration::from_millis(millis.parse().ok()?))
+}
+
+/// Sends a serialized envelope. If Sentry cannot be reached and a queue
+/// directory is given, the envelope is stored there instead.
+///
+/// Returns the path of the queued envelope, if it was queued.
+pub fn send_or_enqueue(body: Vec<u8>, queue_dir: Option<&Path>) -> Result<Option<PathBuf>> {
+    let api = EnvelopesApi::try_new()?;
+    let Some(dir) = queue_dir else {
+        api.send_envelope_bytes(body)?;
+        return Ok(None);
+    };
+
+    match api.send_envelope_bytes(body.clone()) {
+        Ok(_) => Ok(None),
+        Err(err) if err.is_network_error() => {
+            warn!(
+                "Could not reach Sentry, queuing the envelope: {:#}",
+                anyhow::Error::from(err)
+            );
+            enqueue(dir, &body).map(Some)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queued_envelopes_are_sorted_by_age() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("1700000000000-b.envelope"), "{}\n").unwrap();
+        fs::write(dir.path().join("1600000000000-a.envelope"), "{}\n").unwrap();
+        fs::write(dir.path().join("1500000000000-c.partial"), "{}\n").unwrap();
+        let queued = enqueue(dir.path(), b"{}\n").unwrap();
+
+        let envelopes = queued_envelopes(dir.path()).unwrap();
+        let names: Vec<_> = envelopes
+            .iter()
+            .map(|envelope| envelope.path.file_name().unwrap().to_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                OsStr::new("1600000000000-a.envelope"),
+                OsStr::new("1700000000000-b.envelope"),
+                queued.file_name().unwrap(),
+            ]
+        );
+        assert_eq!(
+            envelopes[0].queued_at,
+            UNIX_EPOCH + Duration::from_secs(1_600_000_000)
+        );
+        assert!(envelopes[2].age() < Duration::from_secs(60));
+    }
+}
@@ -1,12 +1,15 @@
 use std::borrow::Cow;
 use std::fs;
 use std::io::{BufRead, BufReader};
+use std::path::Path;
 
-use anyhow::{Context, Result};
+use anyhow::{format_err, Context, Result};
 use chrono::Utc;
+use indicatif::HumanBytes;
 use lazy_static::lazy_static;
 use regex::Regex;
-use sentry::protocol::{Breadcrumb, ClientSdkInfo, Event};
+use sentry::protocol::{Attachment, AttachmentType, Breadcrumb, ClientSdkInfo, Event};
+use serde_json::Value;
 
 lazy_static! {
     static ref COMPONENT_RE: Regex = Regex::new(r#"^([^:]+): (.*)$"#).unwrap();
@@ -63,3 +66,74 @@ pub fn get_sdk_info() -> Cow<'static, ClientSdkInfo> {
         packages: Vec::new(),
     })
 }
+
+/// Indicates that an attachment exceeds the maximum attachment size.
+#[derive(thiserror::Error, Debug)]
+#[error("Attachment {path} is {size}, which exceeds the maximum attachment size of {max_size}")]
+pub struct AttachmentTooLarge {
+    pub path: String,
+    pub size: HumanBytes,
+    pub max_size: HumanBytes,
+}
+
+/// Reads an attachment given as `<path>[:content-type[:attachment-type]]`.
+///
+/// Since paths may contain colons themselves, the longest prefix that names an
+/// existing file is used as the path. Files larger than `max_size` are rejected
+/// with [`AttachmentTooLarge`] before they are read.
+pub fn read_attachment(spec: &str, max_size: u64) -> Result<Attachment> {
+    let mut path = spec;
+    let mut options = vec![];
+    while !Path::new(path).is_file() {
+        match path.rsplit_once(':') {
+            Some((rest, option)) if options.len() < 2 => {
+                options.insert(0, option);
+                path = rest;
+            }
+            // Report the missing file under the full name below.
+            _ => {
+                path = spec;
+                options.clear();
+                break;
+            }
+        }
+    }
+    let content_type = options.first().copied();
+    let ty = options
+        .get(1)
+        .copied()
+        .filter(|ty| !ty.is_empty())
+        .map(|ty| {
+            serde_json::from_value::<AttachmentType>(Value::String(ty.to_owned())).map_err(|_| {
+                format_err!(
+                    "Invalid attachment type `{ty}`, expected one of event.attachment, \
+                     event.minidump, event.applecrashreport, unreal.context or unreal.logs"
+                )
+            })
+        })
+        .transpose()?;
+
+    let size = fs::metadata(path)
+        .with_context(|| format!("Could not open attachment {path}"))?
+        .len();
+    if size > max_size {
+        return Err(AttachmentTooLarge {
+            path: path.to_owned(),
+            size: HumanBytes(size),
+            max_size: HumanBytes(max_size),
+        }
+        .into());
+    }
+
+    Ok(Attachment {
+        buffer: fs::read(path).with_context(|| format!("Could not read attachment {path}"))?,
+        filename: Path::new(path).file_name().map_or_else(
+            || path.to_owned(),
+            |name| name.to_string_lossy().into_owned(),
+        ),
+        content_type: content_type
+            .filter(|content_type| !content_type.is_empty())
+            .map(str::to_owned),
+        ty,
+    })
+}
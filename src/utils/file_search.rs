@@ -1,25 +1,60 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::io::Read;
-use std::path::PathBuf;
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
 
 use anyhow::Result;
 use console::style;
-use ignore::overrides::OverrideBuilder;
+use flate2::read::GzDecoder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
 use ignore::types::TypesBuilder;
 use ignore::WalkBuilder;
 use log::{info, warn};
+use zip::ZipArchive;
 
+use crate::utils::logging::quiet_println;
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 
-use super::fs::{decompress_gzip_content, is_gzip_compressed};
+use super::fs::{decompress_brotli_content, decompress_gzip_content, is_gzip_compressed};
+
+/// Suffixes of pre-compressed files which are decompressed and stripped from
+/// the file name when decompression by suffix is enabled.
+const COMPRESSION_SUFFIXES: &[&str] = &["gz", "br"];
 
 pub struct ReleaseFileSearch {
     path: PathBuf,
     extensions: BTreeSet<String>,
     ignores: BTreeSet<String>,
-    ignore_file: Option<String>,
+    ignore_files: Vec<String>,
+    use_gitignore: bool,
     decompress: bool,
+    decompress_suffixes: bool,
+    follow_symlinks: bool,
+}
+
+/// Archive formats which can be searched for release files without
+/// extracting them first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    fn from_path(path: &Path) -> Option<ArchiveKind> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Eq, PartialEq, Hash)]
@@ -27,6 +62,8 @@ pub struct ReleaseFileMatch {
     pub base_path: PathBuf,
     pub path: PathBuf,
     pub contents: Vec<u8>,
+    /// Whether the contents were decompressed from a `.gz` or `.br` file.
+    pub decompressed: bool,
 }
 
 impl ReleaseFileSearch {
@@ -34,17 +71,33 @@ impl ReleaseFileSearch {
         ReleaseFileSearch {
             path,
             extensions: BTreeSet::new(),
-            ignore_file: None,
+            ignore_files: Vec::new(),
+            use_gitignore: false,
             ignores: BTreeSet::new(),
             decompress: false,
+            decompress_suffixes: false,
+            follow_symlinks: true,
         }
     }
 
+    /// Returns `true` if the given path points to an archive (`.zip`, `.tar`,
+    /// `.tar.gz` or `.tgz`) whose entries are searched instead of the file itself.
+    pub fn is_archive(path: &Path) -> bool {
+        path.is_file() && ArchiveKind::from_path(path).is_some()
+    }
+
     pub fn decompress(&mut self, decompress: bool) -> &mut Self {
         self.decompress = decompress;
         self
     }
 
+    /// Decompresses files ending in `.gz` or `.br` and strips that suffix
+    /// from their path, so that `app.js.gz` is collected as `app.js`.
+    pub fn decompress_suffixes(&mut self, decompress_suffixes: bool) -> &mut Self {
+        self.decompress_suffixes = decompress_suffixes;
+        self
+    }
+
     pub fn extensions<E>(&mut self, extensions: E) -> &mut Self
     where
         E: IntoIterator,
@@ -67,17 +120,45 @@ impl ReleaseFileSearch {
         self
     }
 
+    /// Adds a file with gitignore rules, which are applied relative to the
+    /// search root. Empty paths are skipped.
     pub fn ignore_file<P>(&mut self, path: P) -> &mut Self
     where
         P: Into<String>,
     {
         let path = path.into();
         if !path.is_empty() {
-            self.ignore_file = Some(path);
+            self.ignore_files.push(path);
         }
         self
     }
 
+    pub fn ignore_files<I>(&mut self, paths: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        for path in paths {
+            self.ignore_file(path);
+        }
+        self
+    }
+
+    /// Respects `.gitignore` and `.git/info/exclude` files found while
+    /// walking directories, even outside of a git repository.
+    pub fn use_gitignore(&mut self, use_gitignore: bool) -> &mut Self {
+        self.use_gitignore = use_gitignore;
+        self
+    }
+
+    /// Follows symbolic links while walking directories. Symlinks pointing to
+    /// one of their parent directories are skipped. Defaults to `true`, if
+    /// disabled symlinks are skipped entirely.
+    pub fn follow_symlinks(&mut self, follow_symlinks: bool) -> &mut Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
     pub fn collect_file(path: PathBuf) -> Result<ReleaseFileMatch> {
         // NOTE: `collect_file` currently do not handle gzip decompression,
         // as its mostly used for 3rd tools like xcode, appcenter or gradle.
@@ -89,6 +170,7 @@ impl ReleaseFileSearch {
             base_path: path.clone(),
             path,
             contents,
+            decompressed: false,
         })
     }
 
@@ -102,13 +184,84 @@ impl ReleaseFileSearch {
         pb.enable_steady_tick(100);
         pb.set_style(progress_style);
 
+        let mut links = Vec::new();
+        let mut collected = match ArchiveKind::from_path(&self.path) {
+            Some(kind) if self.path.is_file() => self.collect_archive_files(kind, &pb)?,
+            _ => self.collect_directory_files(&pb, &mut links)?,
+        };
+
+        if self.decompress_suffixes {
+            // Build steps usually keep the original files next to the compressed
+            // ones, in which case we only upload the original.
+            let original_paths: BTreeSet<_> = collected
+                .iter()
+                .filter(|file| !file.decompressed)
+                .map(|file| file.path.clone())
+                .collect();
+            collected.retain(|file| {
+                let keep = !file.decompressed || !original_paths.contains(&file.path);
+                if !keep {
+                    info!(
+                        "skipping compressed copy of {} as the original was found",
+                        file.path.display()
+                    );
+                }
+                keep
+            });
+        }
+
+        pb.finish_and_clear();
+        quiet_println!(
+            "{} Found {} {}",
+            style(">").dim(),
+            style(collected.len()).yellow(),
+            match collected.len() {
+                1 => "file",
+                _ => "files",
+            }
+        );
+
+        if !links.is_empty() {
+            quiet_println!(
+                "{} Skipped {} {} to files already found:",
+                style(">").dim(),
+                style(links.len()).yellow(),
+                match links.len() {
+                    1 => "link",
+                    _ => "links",
+                }
+            );
+            for link in &links {
+                quiet_println!(
+                    "  {} (same as {})",
+                    link.path.display(),
+                    link.original.display()
+                );
+            }
+        }
+
+        Ok(collected)
+    }
+
+    /// Collects all matching files in the search directory.
+    ///
+    /// Files reached through several symlinks or hard links are collected once,
+    /// under the path they are found at first. The other paths are added to `links`.
+    fn collect_directory_files(
+        &self,
+        pb: &ProgressBar,
+        links: &mut Vec<LinkedFile>,
+    ) -> Result<Vec<ReleaseFileMatch>> {
         let mut collected = Vec::new();
+        let mut found = HashMap::<FileId, PathBuf>::new();
 
         let mut builder = WalkBuilder::new(&self.path);
         builder
-            .follow_links(true)
-            .git_exclude(false)
-            .git_ignore(false)
+            .follow_links(self.follow_symlinks)
+            .sort_by_file_path(Path::cmp)
+            .git_exclude(self.use_gitignore)
+            .git_ignore(self.use_gitignore)
+            .require_git(false)
             .ignore(false);
 
         if !&self.extensions.is_empty() {
@@ -116,14 +269,20 @@ impl ReleaseFileSearch {
             for ext in &self.extensions {
                 let ext_name = ext.replace('.', "");
                 types_builder.add(&ext_name, &format!("*.{ext}"))?;
+                if self.decompress_suffixes {
+                    for suffix in COMPRESSION_SUFFIXES {
+                        types_builder.add(&ext_name, &format!("*.{ext}.{suffix}"))?;
+                    }
+                }
             }
             builder.types(types_builder.select("all").build()?);
         }
 
-        if let Some(ignore_file) = &self.ignore_file {
-            // This could yield an optional partial error
-            // We ignore this error to match behavior of git
-            builder.add_ignore(ignore_file);
+        if let Some(gitignore) = ignore_file_matcher(&self.path, &self.ignore_files)? {
+            builder.filter_entry(move |entry| {
+                let is_dir = entry.file_type().map_or(false, |t| t.is_dir());
+                !gitignore.matched(entry.path(), is_dir).is_ignore()
+            });
         }
 
         if !&self.ignores.is_empty() {
@@ -135,12 +294,46 @@ impl ReleaseFileSearch {
         }
 
         for result in builder.build() {
-            let file = result?;
-            if file.file_type().map_or(false, |t| t.is_dir()) {
+            let file = match result {
+                Ok(file) => file,
+                Err(err) => match symlink_loop(&err) {
+                    Some((ancestor, child)) => {
+                        warn!(
+                            "Skipping symlink {}, which links to its parent directory {}",
+                            child.display(),
+                            ancestor.display()
+                        );
+                        continue;
+                    }
+                    None => return Err(err.into()),
+                },
+            };
+            let file_type = file.file_type();
+            if file_type.map_or(false, |t| t.is_dir()) {
+                continue;
+            }
+            if file_type.map_or(false, |t| t.is_symlink()) {
+                info!("skipping symlink {}", file.path().display());
                 continue;
             }
             pb.set_message(&format!("{}", file.path().display()));
 
+            if let Some(id) = FileId::of(file.path()) {
+                if let Some(original) = found.get(&id) {
+                    info!(
+                        "skipping {}, which links to {}",
+                        file.path().display(),
+                        original.display()
+                    );
+                    links.push(LinkedFile {
+                        path: file.path().to_path_buf(),
+                        original: original.clone(),
+                    });
+                    continue;
+                }
+                found.insert(id, file.path().to_path_buf());
+            }
+
             info!(
                 "found: {} ({} bytes)",
                 file.path().display(),
@@ -151,34 +344,486 @@ impl ReleaseFileSearch {
             let mut contents = Vec::new();
             f.read_to_end(&mut contents)?;
 
-            if self.decompress && is_gzip_compressed(&contents) {
-                contents = decompress_gzip_content(&contents).unwrap_or_else(|_| {
-                    warn!("Could not decompress: {}", file.path().display());
-                    contents
-                });
+            collected.push(self.make_match(file.path().to_path_buf(), contents));
+            pb.set_prefix(&collected.len().to_string());
+        }
+
+        Ok(collected)
+    }
+
+    /// Collects all matching files from the entries of an archive, without
+    /// extracting it. Entries are reported with paths below the archive path,
+    /// so that they map to the same URLs as if the archive had been extracted
+    /// into a directory of the same name.
+    fn collect_archive_files(
+        &self,
+        kind: ArchiveKind,
+        pb: &ProgressBar,
+    ) -> Result<Vec<ReleaseFileMatch>> {
+        let overrides = self.archive_overrides()?;
+        // Entry paths are relative to the archive, which acts as the search root.
+        let gitignore = ignore_file_matcher("", &self.ignore_files)?;
+        let mut collected = Vec::new();
+
+        let mut add_entry = |entry_path: &Path, contents: Vec<u8>| {
+            // Archives created with e.g. `tar -C dist .` prefix all entries with `./`
+            let entry_path = &entry_path
+                .components()
+                .filter(|component| *component != Component::CurDir)
+                .collect::<PathBuf>();
+
+            if !self.archive_entry_matches(entry_path, &overrides, gitignore.as_ref()) {
+                return;
+            }
+
+            pb.set_message(&format!("{}", entry_path.display()));
+            info!(
+                "found: {} in {} ({} bytes)",
+                entry_path.display(),
+                self.path.display(),
+                contents.len()
+            );
+
+            collected.push(self.make_match(self.path.join(entry_path), contents));
+            pb.set_prefix(&collected.len().to_string());
+        };
+
+        let file = BufReader::new(fs::File::open(&self.path)?);
+        match kind {
+            ArchiveKind::Zip => {
+                let mut archive = ZipArchive::new(file)?;
+                for index in 0..archive.len() {
+                    let mut entry = archive.by_index(index)?;
+                    if !entry.is_file() {
+                        continue;
+                    }
+                    let Some(entry_path) = entry.enclosed_name().map(Path::to_path_buf) else {
+                        warn!("Skipping archive entry with unsafe path: {}", entry.name());
+                        continue;
+                    };
+                    let mut contents = Vec::new();
+                    entry.read_to_end(&mut contents)?;
+                    add_entry(&entry_path, contents);
+                }
+            }
+            ArchiveKind::Tar => {
+                collect_tar_entries(tar::Archive::new(file), &mut add_entry)?;
+            }
+            ArchiveKind::TarGz => {
+                collect_tar_entries(tar::Archive::new(GzDecoder::new(file)), &mut add_entry)?;
+            }
+        }
+
+        Ok(collected)
+    }
+
+    fn archive_overrides(&self) -> Result<Override> {
+        let mut override_builder = OverrideBuilder::new("");
+        for ignore in &self.ignores {
+            override_builder.add(ignore)?;
+        }
+        Ok(override_builder.build()?)
+    }
+
+    /// Applies the extension and ignore rules to a path within an archive.
+    fn archive_entry_matches(
+        &self,
+        entry_path: &Path,
+        overrides: &Override,
+        gitignore: Option<&Gitignore>,
+    ) -> bool {
+        if !self.extensions.is_empty() {
+            let file_name = entry_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            let file_name = if self.decompress_suffixes {
+                strip_compression_suffix(file_name).map_or(file_name, |(name, _)| name)
+            } else {
+                file_name
+            };
+            if !self
+                .extensions
+                .iter()
+                .any(|ext| file_name.ends_with(&format!(".{ext}")))
+            {
+                return false;
             }
+        }
 
-            let file_match = ReleaseFileMatch {
+        if overrides.matched(entry_path, false).is_ignore() {
+            return false;
+        }
+
+        !gitignore.map_or(false, |gitignore| {
+            gitignore
+                .matched_path_or_any_parents(entry_path, false)
+                .is_ignore()
+        })
+    }
+
+    fn make_match(&self, path: PathBuf, contents: Vec<u8>) -> ReleaseFileMatch {
+        if self.decompress_suffixes {
+            if let Some(file) = self.make_decompressed_match(&path, &contents) {
+                return file;
+            }
+        }
+
+        let mut contents = contents;
+        if self.decompress && is_gzip_compressed(&contents) {
+            contents = decompress_gzip_content(&contents).unwrap_or_else(|_| {
+                warn!("Could not decompress: {}", path.display());
+                contents
+            });
+        }
+
+        ReleaseFileMatch {
+            base_path: self.path.clone(),
+            path,
+            contents,
+            decompressed: false,
+        }
+    }
+
+    /// Decompresses a file with a `.gz` or `.br` suffix, returning `None` if
+    /// the file has no such suffix or could not be decompressed.
+    fn make_decompressed_match(&self, path: &Path, contents: &[u8]) -> Option<ReleaseFileMatch> {
+        let (file_name, suffix) = strip_compression_suffix(path.file_name()?.to_str()?)?;
+        let decompressed = match suffix {
+            "gz" => decompress_gzip_content(contents),
+            _ => decompress_brotli_content(contents),
+        };
+
+        match decompressed {
+            Ok(contents) => Some(ReleaseFileMatch {
                 base_path: self.path.clone(),
-                path: file.path().to_path_buf(),
+                path: path.with_file_name(file_name),
                 contents,
-            };
-            collected.push(file_match);
+                decompressed: true,
+            }),
+            Err(_) => {
+                warn!("Could not decompress: {}", path.display());
+                None
+            }
+        }
+    }
+}
 
-            pb.set_prefix(&collected.len().to_string());
+/// A file which was skipped, since it links to a file that was already collected.
+struct LinkedFile {
+    path: PathBuf,
+    original: PathBuf,
+}
+
+/// Identifies a file independently of the path it is found at, so that
+/// symlinks and hard links to the same file can be detected.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum FileId {
+    /// The device and inode of the file, which also detects hard links.
+    #[cfg(unix)]
+    Inode(u64, u64),
+    /// The canonical path of the file, with symlinks and junctions resolved.
+    #[cfg(not(unix))]
+    Canonical(PathBuf),
+}
+
+impl FileId {
+    fn of(path: &Path) -> Option<FileId> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let metadata = fs::metadata(path).ok()?;
+            Some(FileId::Inode(metadata.dev(), metadata.ino()))
         }
 
-        pb.finish_and_clear();
-        println!(
-            "{} Found {} {}",
-            style(">").dim(),
-            style(collected.len()).yellow(),
-            match collected.len() {
-                1 => "file",
-                _ => "files",
+        #[cfg(not(unix))]
+        {
+            fs::canonicalize(path).ok().map(FileId::Canonical)
+        }
+    }
+}
+
+/// Returns the symlink and the ancestor directory it links to, if walking a
+/// directory failed because of a symlink cycle.
+fn symlink_loop(err: &ignore::Error) -> Option<(&Path, &Path)> {
+    match err {
+        ignore::Error::Loop { ancestor, child } => Some((ancestor, child)),
+        ignore::Error::WithPath { err, .. } | ignore::Error::WithDepth { err, .. } => {
+            symlink_loop(err)
+        }
+        _ => None,
+    }
+}
+
+/// Builds a matcher from the given gitignore files, whose patterns are
+/// interpreted relative to `root`. Returns `None` if no files are given.
+pub fn ignore_file_matcher<R, P>(root: R, ignore_files: &[P]) -> Result<Option<Gitignore>>
+where
+    R: AsRef<Path>,
+    P: AsRef<Path>,
+{
+    if ignore_files.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GitignoreBuilder::new(root);
+    for ignore_file in ignore_files {
+        // This could yield an optional partial error
+        // We ignore this error to match behavior of git
+        builder.add(ignore_file);
+    }
+    Ok(Some(builder.build()?))
+}
+
+/// Splits a `.gz` or `.br` suffix off a file name.
+fn strip_compression_suffix(file_name: &str) -> Option<(&str, &str)> {
+    let (name, suffix) = file_name.rsplit_once('.')?;
+    let suffix = COMPRESSION_SUFFIXES
+        .iter()
+        .find(|s| s.eq_ignore_ascii_case(suffix))
+        .copied()?;
+    (!name.is_empty()).then_some((name, suffix))
+}
+
+fn collect_tar_entries<R: Read>(
+    mut archive: tar::Archive<R>,
+    add_entry: &mut impl FnMut(&Path, Vec<u8>),
+) -> Result<()> {
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let Some(entry_path) = enclosed_tar_path(&entry.path()?) else {
+            warn!(
+                "Skipping archive entry with unsafe path: {}",
+                String::from_utf8_lossy(&entry.path_bytes())
+            );
+            continue;
+        };
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        add_entry(&entry_path, contents);
+    }
+    Ok(())
+}
+
+/// Returns the path of a tar entry if it stays within the archive, like
+/// `ZipFile::enclosed_name`.
+///
+/// Absolute paths and paths escaping the archive with `..` are rejected, and
+/// `..` components within the archive are resolved.
+fn enclosed_tar_path(path: &Path) -> Option<PathBuf> {
+    let mut enclosed = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => enclosed.push(name),
+            Component::CurDir => (),
+            Component::ParentDir => {
+                if !enclosed.pop() {
+                    return None;
+                }
             }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!enclosed.as_os_str().is_empty()).then_some(enclosed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    use tempfile::TempDir;
+
+    fn make_tree(files: &[(&str, &str)]) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, contents) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        dir
+    }
+
+    fn collected_paths(search: &ReleaseFileSearch, root: &Path) -> Vec<String> {
+        let mut paths: Vec<_> = search
+            .collect_files()
+            .unwrap()
+            .into_iter()
+            .map(|file| {
+                file.path
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_ignore_file_negation() {
+        let dir = make_tree(&[
+            (".sentryignore", "*.map\n!keep.map\n"),
+            ("app.js", ""),
+            ("app.js.map", ""),
+            ("keep.map", ""),
+            ("nested/keep.map", ""),
+            ("nested/other.map", ""),
+        ]);
+
+        let mut search = ReleaseFileSearch::new(dir.path().to_path_buf());
+        search.ignore_file(dir.path().join(".sentryignore").to_string_lossy());
+
+        assert_eq!(
+            collected_paths(&search, dir.path()),
+            ["app.js", "keep.map", "nested/keep.map"]
         );
+    }
 
-        Ok(collected)
+    #[test]
+    fn test_ignore_file_directory_pattern() {
+        let dir = make_tree(&[
+            (".sentryignore", "build/\n"),
+            ("app.js", ""),
+            ("build/app.js", ""),
+            ("nested/build/app.js", ""),
+            ("nested/build.js", ""),
+            ("lib/build", ""),
+        ]);
+
+        let mut search = ReleaseFileSearch::new(dir.path().to_path_buf());
+        search.ignore_file(dir.path().join(".sentryignore").to_string_lossy());
+
+        assert_eq!(
+            collected_paths(&search, dir.path()),
+            ["app.js", "lib/build", "nested/build.js"]
+        );
+    }
+
+    #[test]
+    fn test_ignore_file_relative_to_search_root() {
+        let dir = make_tree(&[
+            ("ignore", "/app.js\n"),
+            ("dist/app.js", ""),
+            ("dist/nested/app.js", ""),
+        ]);
+        let root = dir.path().join("dist");
+
+        let mut search = ReleaseFileSearch::new(root.clone());
+        search.ignore_file(dir.path().join("ignore").to_string_lossy());
+
+        assert_eq!(collected_paths(&search, &root), ["nested/app.js"]);
+    }
+
+    #[test]
+    fn test_multiple_ignore_files() {
+        let dir = make_tree(&[
+            ("ignore-maps", "*.map\n"),
+            ("ignore-vendor", "vendor/\n"),
+            ("dist/app.js", ""),
+            ("dist/app.js.map", ""),
+            ("dist/vendor/lib.js", ""),
+        ]);
+        let root = dir.path().join("dist");
+
+        let mut search = ReleaseFileSearch::new(root.clone());
+        search.ignore_files([
+            dir.path().join("ignore-maps").to_string_lossy(),
+            dir.path().join("ignore-vendor").to_string_lossy(),
+        ]);
+
+        assert_eq!(collected_paths(&search, &root), ["app.js"]);
+    }
+
+    #[test]
+    fn test_use_gitignore() {
+        let dir = make_tree(&[
+            (".gitignore", "*.map\n!keep.map\n"),
+            ("app.js", ""),
+            ("app.js.map", ""),
+            ("keep.map", ""),
+            ("nested/.gitignore", "generated/\n"),
+            ("nested/generated/app.js", ""),
+            ("nested/lib.js", ""),
+        ]);
+
+        let mut search = ReleaseFileSearch::new(dir.path().to_path_buf());
+        assert_eq!(
+            collected_paths(&search, dir.path()),
+            [
+                "app.js",
+                "app.js.map",
+                "keep.map",
+                "nested/generated/app.js",
+                "nested/lib.js"
+            ]
+        );
+
+        search.use_gitignore(true);
+        assert_eq!(
+            collected_paths(&search, dir.path()),
+            ["app.js", "keep.map", "nested/lib.js"]
+        );
+    }
+
+    #[test]
+    fn test_tar_entries_outside_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dist.tar");
+
+        let mut builder = tar::Builder::new(fs::File::create(&path).unwrap());
+        for name in ["../x.js", "/abs.js", "nested/../app.js", "./lib.js"] {
+            // `Header::set_path` rejects such paths, so the name is written directly
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(0);
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_cksum();
+            builder.append(&header, io::empty()).unwrap();
+        }
+        builder.finish().unwrap();
+
+        let search = ReleaseFileSearch::new(path.clone());
+        assert_eq!(collected_paths(&search, &path), ["app.js", "lib.js"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_cycle() {
+        let dir = make_tree(&[("app.js", ""), ("nested/lib.js", "")]);
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("nested/parent")).unwrap();
+
+        let search = ReleaseFileSearch::new(dir.path().to_path_buf());
+        assert_eq!(
+            collected_paths(&search, dir.path()),
+            ["app.js", "nested/lib.js"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_linked_files_are_collected_once() {
+        let dir = make_tree(&[("libfoo.so.1.2.3", "foo"), ("other.so", "foo")]);
+        let original = dir.path().join("libfoo.so.1.2.3");
+        std::os::unix::fs::symlink(&original, dir.path().join("libfoo.so.1")).unwrap();
+        fs::hard_link(&original, dir.path().join("libfoo.so")).unwrap();
+
+        // Copies with equal contents are separate files
+        let mut search = ReleaseFileSearch::new(dir.path().to_path_buf());
+        assert_eq!(
+            collected_paths(&search, dir.path()),
+            ["libfoo.so", "other.so"]
+        );
+
+        search.follow_symlinks(false);
+        assert_eq!(
+            collected_paths(&search, dir.path()),
+            ["libfoo.so", "other.so"]
+        );
     }
 }
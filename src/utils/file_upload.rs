@@ -1,7 +1,9 @@
 //! Searches, processes and uploads release files.
+use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::io::BufWriter;
+use std::mem;
 use std::path::PathBuf;
 use std::str;
 use std::sync::Arc;
@@ -9,6 +11,7 @@ use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, Result};
 use console::style;
+use indicatif::HumanBytes;
 use log::info;
 use parking_lot::RwLock;
 use rayon::prelude::*;
@@ -22,11 +25,13 @@ use symbolic::debuginfo::sourcebundle::{
 use url::Url;
 
 use crate::api::NewRelease;
-use crate::api::{Api, ChunkUploadCapability, ChunkUploadOptions};
+use crate::api::{Api, ChunkCompression, ChunkUploadCapability, ChunkUploadOptions};
 use crate::constants::DEFAULT_MAX_WAIT;
-use crate::utils::chunks::{upload_chunks, Chunk, ASSEMBLE_POLL_INTERVAL};
-use crate::utils::fs::{get_sha1_checksum, get_sha1_checksums, TempFile};
+use crate::utils::chunks::{get_sha1_checksums, upload_chunks, Chunk, ASSEMBLE_POLL_INTERVAL};
+use crate::utils::fs::{get_sha1_checksum, TempFile};
+use crate::utils::logging::quiet_println;
 use crate::utils::progress::{ProgressBar, ProgressBarMode, ProgressStyle};
+use crate::utils::upload_metadata::UploadMetadata;
 
 /// Fallback concurrency for release file uploads.
 static DEFAULT_CONCURRENCY: usize = 4;
@@ -64,6 +69,11 @@ pub fn initialize_legacy_release_upload(context: &UploadContext) -> Result<()> {
         );
     }
 
+    create_legacy_release(context)
+}
+
+/// Creates the release that files of legacy uploads are associated with.
+fn create_legacy_release(context: &UploadContext) -> Result<()> {
     if let Some(version) = context.release {
         let api = Api::current();
         api.authenticated()?.new_release(
@@ -80,17 +90,33 @@ pub fn initialize_legacy_release_upload(context: &UploadContext) -> Result<()> {
     Ok(())
 }
 
+/// Switches an artifact bundle upload over to the legacy release upload, for
+/// servers that advertise artifact bundles but do not know the assemble endpoint.
+fn fall_back_to_release_upload(context: &UploadContext) -> Result<()> {
+    eprintln!(
+        "{}",
+        style(
+            "warning: This version of Sentry does not support artifact bundles, \
+             uploading the files to the release instead. Debug IDs will not be used \
+             to associate the files with events."
+        )
+        .red()
+    );
+    create_legacy_release(context)
+}
+
 #[derive(Debug, Clone)]
 pub struct UploadContext<'a> {
     pub org: &'a str,
     pub project: Option<&'a str>,
     pub release: Option<&'a str>,
     pub dist: Option<&'a str>,
-    pub note: Option<&'a str>,
+    pub metadata: Option<&'a UploadMetadata>,
     pub wait: bool,
     pub max_wait: Duration,
     pub dedupe: bool,
     pub chunk_upload_options: Option<&'a ChunkUploadOptions>,
+    pub compression: Option<ChunkCompression>,
 }
 
 impl<'a> UploadContext<'a> {
@@ -197,7 +223,20 @@ impl<'a> FileUpload<'a> {
 
         if let Some(chunk_options) = self.context.chunk_upload_options {
             if chunk_options.supports(ChunkUploadCapability::ReleaseFiles) {
-                return upload_files_chunked(self.context, &self.files, chunk_options);
+                let bundles = split_into_bundles(&self.files, chunk_options.max_file_size);
+                if bundles.len() > 1 {
+                    quiet_println!(
+                        "{} Splitting {} files into {} bundles of at most {}",
+                        style(">").dim(),
+                        style(self.files.len()).yellow(),
+                        style(bundles.len()).yellow(),
+                        HumanBytes(chunk_options.max_file_size)
+                    );
+                }
+                for files in &bundles {
+                    upload_files_chunked(self.context, files, chunk_options)?;
+                }
+                return Ok(());
             }
         }
 
@@ -214,13 +253,61 @@ impl<'a> FileUpload<'a> {
         let concurrency = self
             .context
             .chunk_upload_options
-            .map_or(DEFAULT_CONCURRENCY, |o| usize::from(o.concurrency));
+            .map_or(DEFAULT_CONCURRENCY, |o| o.concurrency());
         upload_files_parallel(self.context, &self.files, concurrency)
     }
 
     pub fn build_jvm_bundle(&self, debug_id: Option<DebugId>) -> Result<TempFile> {
         build_artifact_bundle(self.context, &self.files, debug_id)
     }
+
+    /// Builds the artifact bundle that `upload` would upload, without uploading it.
+    ///
+    /// Returns `None` if the server does not support chunked uploads of release files,
+    /// in which case files are uploaded individually.
+    pub fn build_bundle(&self) -> Result<Option<(DebugId, TempFile)>> {
+        match self.context.chunk_upload_options {
+            Some(options) if options.supports(ChunkUploadCapability::ReleaseFiles) => {
+                let debug_id = build_debug_id(&self.files);
+                let archive = build_artifact_bundle(self.context, &self.files, Some(debug_id))?;
+                Ok(Some((debug_id, archive)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Splits files into groups whose total size does not exceed `max_size`, so that
+/// each group can be uploaded as a separate artifact bundle.
+///
+/// `max_size` is the server's maximum file size, which limits the size of the
+/// assembled bundle. It is compared against the uncompressed size of the files,
+/// which is an upper bound for the size of the compressed bundle except for
+/// archive overhead. `sourcemaps upload` skips files larger than the same limit;
+/// any other file larger than the limit ends up in a group of its own.
+///
+/// A `max_size` of zero means that the server has no limit. Files are only copied
+/// if they need to be split.
+fn split_into_bundles(files: &SourceFiles, max_size: u64) -> Vec<Cow<'_, SourceFiles>> {
+    let total_size: u64 = files.values().map(|file| file.contents.len() as u64).sum();
+    if max_size == 0 || total_size <= max_size {
+        return vec![Cow::Borrowed(files)];
+    }
+
+    let mut bundles = vec![];
+    let mut bundle = SourceFiles::new();
+    let mut bundle_size = 0;
+    for (url, file) in files {
+        let size = file.contents.len() as u64;
+        if !bundle.is_empty() && bundle_size + size > max_size {
+            bundles.push(Cow::Owned(mem::take(&mut bundle)));
+            bundle_size = 0;
+        }
+        bundle.insert(url.clone(), file.clone());
+        bundle_size += size;
+    }
+    bundles.push(Cow::Owned(bundle));
+    bundles
 }
 
 fn upload_files_parallel(
@@ -240,7 +327,7 @@ fn upload_files_parallel(
         .map(|artifact| ((artifact.dist, artifact.name), artifact.id))
         .collect();
 
-    println!(
+    quiet_println!(
         "{} Uploading source maps for release {}",
         style(">").dim(),
         style(release).cyan()
@@ -257,7 +344,7 @@ fn upload_files_parallel(
     let total_bytes = files.values().map(|file| file.contents.len()).sum();
     let files = files.iter().collect::<Vec<_>>();
 
-    let pb = Arc::new(ProgressBar::new(total_bytes));
+    let pb = Arc::new(ProgressBar::new(total_bytes).with_phase("artifact_upload"));
     pb.set_style(progress_style);
 
     let pool = ThreadPoolBuilder::new().num_threads(num_threads).build()?;
@@ -308,7 +395,13 @@ fn upload_files_parallel(
 
     pb.finish_and_clear();
 
-    print_upload_context_details(context);
+    print_upload_context_details(
+        context,
+        context.chunk_upload_options.map_or(false, |opts| {
+            opts.supports(ChunkUploadCapability::ArtifactBundles)
+                || opts.supports(ChunkUploadCapability::ArtifactBundlesV2)
+        }),
+    );
 
     Ok(())
 }
@@ -318,6 +411,7 @@ fn poll_assemble(
     chunks: &[Digest],
     context: &UploadContext,
     options: &ChunkUploadOptions,
+    mut use_artifact_bundle: bool,
 ) -> Result<()> {
     let progress_style = ProgressStyle::default_spinner().template("{spinner} Processing files...");
 
@@ -335,20 +429,24 @@ fn poll_assemble(
 
     let api = Api::current();
     let authenticated_api = api.authenticated()?;
-    let use_artifact_bundle = (options.supports(ChunkUploadCapability::ArtifactBundles)
-        || options.supports(ChunkUploadCapability::ArtifactBundlesV2))
-        && context.project.is_some();
     let response = loop {
         // prefer standalone artifact bundle upload over legacy release based upload
         let response = if use_artifact_bundle {
-            authenticated_api.assemble_artifact_bundle(
+            match authenticated_api.assemble_artifact_bundle(
                 context.org,
                 vec![context.project.unwrap().to_string()],
                 checksum,
                 chunks,
                 context.release,
                 context.dist,
-            )?
+            )? {
+                Some(response) => response,
+                None => {
+                    fall_back_to_release_upload(context)?;
+                    use_artifact_bundle = false;
+                    continue;
+                }
+            }
         } else {
             authenticated_api.assemble_release_artifacts(
                 context.org,
@@ -383,16 +481,16 @@ fn poll_assemble(
         if context.wait {
             bail!("Failed to process files in {}s", max_wait.as_secs());
         } else {
-            println!(
+            quiet_println!(
                 "{} File upload complete (processing pending on server)",
                 style(">").dim()
             );
         }
     } else {
-        println!("{} File processing complete", style(">").dim());
+        quiet_println!("{} File processing complete", style(">").dim());
     }
 
-    print_upload_context_details(context);
+    print_upload_context_details(context, use_artifact_bundle);
 
     Ok(())
 }
@@ -412,7 +510,7 @@ fn upload_files_chunked(
     pb.set_style(progress_style);
 
     let view = ByteView::open(archive.path())?;
-    let (checksum, checksums) = get_sha1_checksums(&view, options.chunk_size)?;
+    let (checksum, checksums) = get_sha1_checksums(&view[..], options.chunk_size)?;
     let mut chunks = view
         .chunks(options.chunk_size as usize)
         .zip(checksums.iter())
@@ -427,31 +525,42 @@ fn upload_files_chunked(
         style(">").dim(),
     ));
 
+    let mut use_artifact_bundle = (options.supports(ChunkUploadCapability::ArtifactBundles)
+        || options.supports(ChunkUploadCapability::ArtifactBundlesV2))
+        && context.project.is_some();
+
     // Filter out chunks that are already on the server. This only matters if the server supports
     // `ArtifactBundlesV2`, otherwise the `missing_chunks` field is meaningless.
-    if options.supports(ChunkUploadCapability::ArtifactBundlesV2) && context.project.is_some() {
+    if use_artifact_bundle && options.supports(ChunkUploadCapability::ArtifactBundlesV2) {
         let api = Api::current();
-        let response = api.authenticated()?.assemble_artifact_bundle(
+        match api.authenticated()?.assemble_artifact_bundle(
             context.org,
             vec![context.project.unwrap().to_string()],
             checksum,
             &checksums,
             context.release,
             context.dist,
-        )?;
-        chunks.retain(|Chunk((digest, _))| response.missing_chunks.contains(digest));
+        )? {
+            Some(response) => {
+                chunks.retain(|Chunk((digest, _))| response.missing_chunks.contains(digest))
+            }
+            None => {
+                fall_back_to_release_upload(context)?;
+                use_artifact_bundle = false;
+            }
+        }
     };
 
     if !chunks.is_empty() {
-        upload_chunks(&chunks, options, progress_style)?;
-        println!("{} Uploaded files to Sentry", style(">").dim());
+        upload_chunks(&chunks, options, progress_style, context.compression)?;
+        quiet_println!("{} Uploaded files to Sentry", style(">").dim());
     } else {
-        println!(
+        quiet_println!(
             "{} Nothing to upload, all files are on the server",
             style(">").dim()
         );
     }
-    poll_assemble(checksum, &checksums, context, options)
+    poll_assemble(checksum, &checksums, context, options, use_artifact_bundle)
 }
 
 /// Creates a debug id from a map of source files by hashing each file's
@@ -495,8 +604,12 @@ fn build_artifact_bundle(
     let debug_id = debug_id.unwrap_or_else(|| build_debug_id(files));
     bundle.set_attribute("debug_id", debug_id.to_string());
 
-    if let Some(note) = context.note {
-        bundle.set_attribute("note", note.to_owned());
+    for (key, value) in context
+        .metadata
+        .into_iter()
+        .flat_map(UploadMetadata::attributes)
+    {
+        bundle.set_attribute(key, value);
     }
 
     bundle.set_attribute("org".to_owned(), context.org.to_owned());
@@ -539,7 +652,7 @@ fn build_artifact_bundle(
 
     pb.finish_with_duration("Bundling");
 
-    println!(
+    quiet_println!(
         "{} Bundled {} {} for upload",
         style(">").dim(),
         style(files.len()).yellow(),
@@ -549,7 +662,7 @@ fn build_artifact_bundle(
         }
     );
 
-    println!(
+    quiet_println!(
         "{} Bundle ID: {}",
         style(">").dim(),
         style(debug_id).yellow(),
@@ -581,38 +694,33 @@ fn url_to_bundle_path(url: &str) -> Result<String> {
     })
 }
 
-fn print_upload_context_details(context: &UploadContext) {
-    println!(
+fn print_upload_context_details(context: &UploadContext, artifact_bundle: bool) {
+    quiet_println!(
         "{} {}",
         style("> Organization:").dim(),
         style(context.org).yellow()
     );
-    println!(
+    quiet_println!(
         "{} {}",
         style("> Project:").dim(),
         style(context.project.unwrap_or("None")).yellow()
     );
-    println!(
+    quiet_println!(
         "{} {}",
         style("> Release:").dim(),
         style(context.release.unwrap_or("None")).yellow()
     );
-    println!(
+    quiet_println!(
         "{} {}",
         style("> Dist:").dim(),
         style(context.dist.unwrap_or("None")).yellow()
     );
     let upload_type = match context.chunk_upload_options {
         None => "single file",
-        Some(opts)
-            if opts.supports(ChunkUploadCapability::ArtifactBundles)
-                || opts.supports(ChunkUploadCapability::ArtifactBundlesV2) =>
-        {
-            "artifact bundle"
-        }
+        Some(_) if artifact_bundle => "artifact bundle",
         _ => "release bundle",
     };
-    println!(
+    quiet_println!(
         "{} {}",
         style("> Upload type:").dim(),
         style(upload_type).yellow()
@@ -657,11 +765,12 @@ mod tests {
             project: Some("wat-project"),
             release: None,
             dist: None,
-            note: None,
+            metadata: None,
             wait: false,
             max_wait: DEFAULT_MAX_WAIT,
             dedupe: true,
             chunk_upload_options: None,
+            compression: None,
         };
 
         let source_files = ["bundle.min.js.map", "vendor.min.js.map"]
@@ -689,4 +798,55 @@ mod tests {
             "f0e25ae149b711c510148e022ebc883ad62c7c4c"
         );
     }
+
+    fn source_file(url: &str, size: usize) -> (String, SourceFile) {
+        let file = SourceFile {
+            url: url.to_owned(),
+            path: url.into(),
+            contents: vec![b'a'; size],
+            ty: SourceFileType::MinifiedSource,
+            headers: Default::default(),
+            messages: Default::default(),
+            already_uploaded: false,
+        };
+        (url.to_owned(), file)
+    }
+
+    fn bundle_urls<'a>(bundles: &'a [Cow<'_, SourceFiles>]) -> Vec<Vec<&'a str>> {
+        bundles
+            .iter()
+            .map(|bundle| bundle.keys().map(String::as_str).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_into_bundles_without_limit() {
+        let files = [source_file("~/a.js", 10), source_file("~/b.js", 20)]
+            .into_iter()
+            .collect();
+        let bundles = split_into_bundles(&files, 0);
+        assert_eq!(bundle_urls(&bundles), vec![vec!["~/a.js", "~/b.js"]]);
+        assert!(matches!(bundles[0], Cow::Borrowed(_)));
+
+        let bundles = split_into_bundles(&files, 30);
+        assert_eq!(bundle_urls(&bundles), vec![vec!["~/a.js", "~/b.js"]]);
+        assert!(matches!(bundles[0], Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_split_into_bundles_with_limit() {
+        let files = [
+            source_file("~/a.js", 10),
+            source_file("~/b.js", 20),
+            source_file("~/c.js", 50),
+            source_file("~/d.js", 5),
+        ]
+        .into_iter()
+        .collect();
+        let bundles = split_into_bundles(&files, 30);
+        assert_eq!(
+            bundle_urls(&bundles),
+            vec![vec!["~/a.js", "~/b.js"], vec!["~/c.js"], vec!["~/d.js"]]
+        );
+    }
 }
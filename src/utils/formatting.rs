@@ -1,5 +1,7 @@
 use std::fmt;
+use std::io;
 
+use anyhow::Result;
 use chrono::Duration;
 
 /// Helper for formatting durations.
@@ -24,6 +26,15 @@ impl fmt::Display for HumanDuration {
     }
 }
 
+/// Returns `singular` if `count` is one, and `plural` otherwise.
+pub fn pluralize<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
+    if count == 1 {
+        singular
+    } else {
+        plural
+    }
+}
+
 pub struct Table {
     title_row: Option<TableRow>,
     rows: Vec<TableRow>,
@@ -97,6 +108,19 @@ impl Table {
         }
         tbl.print_tty(false).ok();
     }
+
+    /// Prints the table as CSV, including the title row.
+    pub fn print_csv(&self) -> Result<()> {
+        let mut tbl = prettytable::Table::new();
+        if let Some(ref title_row) = self.title_row {
+            tbl.set_titles(title_row.make_row());
+        }
+        for row in &self.rows {
+            tbl.add_row(row.make_row());
+        }
+        tbl.to_csv(io::stdout())?;
+        Ok(())
+    }
 }
 
 impl Default for Table {
@@ -5,6 +5,7 @@ use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
+use brotli2::read::BrotliDecoder;
 use flate2::read::GzDecoder;
 use log::error;
 use sha1_smol::{Digest, Sha1};
@@ -154,6 +155,31 @@ pub fn set_executable_mode<P: AsRef<Path>>(path: P) -> Result<()> {
     Ok(())
 }
 
+/// Writes a file with the given function, which is called with a temporary file
+/// next to the target. Once it is written, the temporary file replaces the target,
+/// so that the target is never left half-written.
+pub fn write_atomically<F>(path: &Path, options: &fs::OpenOptions, write: F) -> Result<()>
+where
+    F: FnOnce(&mut fs::File) -> io::Result<()>,
+{
+    let Some(name) = path.file_name() else {
+        bail!("Invalid file path: {}", path.display());
+    };
+    let mut temp_name = name.to_owned();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = options.open(&temp_path)?;
+    let written = write(&mut file)
+        .and_then(|_| file.sync_all())
+        .and_then(|_| fs::rename(&temp_path, path));
+    if let Err(err) = written {
+        fs::remove_file(&temp_path).ok();
+        return Err(err.into());
+    }
+    Ok(())
+}
+
 /// Returns the SHA1 hash of the given input.
 pub fn get_sha1_checksum<R: Read>(rdr: R) -> Result<Digest> {
     let mut sha = Sha1::new();
@@ -169,26 +195,6 @@ pub fn get_sha1_checksum<R: Read>(rdr: R) -> Result<Digest> {
     Ok(sha.digest())
 }
 
-/// Returns the SHA1 hash for the entire input, as well as each chunk of it. The
-/// `chunk_size` must be a power of two.
-pub fn get_sha1_checksums(data: &[u8], chunk_size: u64) -> Result<(Digest, Vec<Digest>)> {
-    if !chunk_size.is_power_of_two() {
-        bail!("Chunk size must be a power of two");
-    }
-
-    let mut total_sha = Sha1::new();
-    let mut chunks = Vec::new();
-
-    for chunk in data.chunks(chunk_size as usize) {
-        let mut chunk_sha = Sha1::new();
-        chunk_sha.update(chunk);
-        total_sha.update(chunk);
-        chunks.push(chunk_sha.digest());
-    }
-
-    Ok((total_sha.digest(), chunks))
-}
-
 /// Checks if provided slice contains gzipped data.
 pub fn is_gzip_compressed(slice: &[u8]) -> bool {
     // Per https://www.ietf.org/rfc/rfc1952.txt
@@ -204,6 +210,14 @@ pub fn decompress_gzip_content(slice: &[u8]) -> Result<Vec<u8>> {
     Ok(decoded)
 }
 
+/// Gets brotli decompressed contents.
+pub fn decompress_brotli_content(slice: &[u8]) -> Result<Vec<u8>> {
+    let mut decoder = BrotliDecoder::new(slice);
+    let mut decoded = vec![];
+    decoder.read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
 #[cfg(windows)]
 pub fn path_as_url(path: &Path) -> String {
     path.display().to_string().replace('\\', "/")
@@ -234,6 +248,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn write_atomically_replaces_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("file.txt");
+        fs::write(&path, "old")?;
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).truncate(true).create(true);
+        write_atomically(&path, &options, |file| io::Write::write_all(file, b"new"))?;
+        assert_eq!(fs::read_to_string(&path)?, "new");
+
+        let failed = write_atomically(&path, &options, |_| Err(io::ErrorKind::Other.into()));
+        assert!(failed.is_err());
+        assert_eq!(fs::read_to_string(&path)?, "new");
+        assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn tempfile_goes_away_with_longer_living_handle() -> io::Result<()> {
         let tempfile = TempFile::create()?;
@@ -1,3 +1,6 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Weak,
@@ -7,8 +10,10 @@ use chrono::Local;
 use console::{style, Color};
 use indicatif::ProgressBar;
 use lazy_static::lazy_static;
-use log::max_level;
-use parking_lot::RwLock;
+use log::LevelFilter;
+use parking_lot::{Mutex, RwLock};
+
+use crate::utils::auth_token::redact_token_from_string;
 
 // Quiet mode decides whether to print something based on `--quiet` flag.
 lazy_static! {
@@ -23,8 +28,6 @@ pub fn set_quiet_mode(is_quiet: bool) {
     QUIET_MODE.store(is_quiet, Ordering::Relaxed);
 }
 
-// NOTE: Remove `allow`s after first use.
-#[allow(unused_macros)]
 macro_rules! quiet_println {
     ($($tt:tt)*) => {{
         if !crate::utils::logging::is_quiet_mode() {
@@ -32,11 +35,8 @@ macro_rules! quiet_println {
         }
     }};
 }
-#[allow(unused_imports)]
 pub(crate) use quiet_println;
 
-// NOTE: Remove `allow`s after first use.
-#[allow(unused_macros)]
 macro_rules! quiet_eprintln {
     ($($tt:tt)*) => {{
         if !crate::utils::logging::is_quiet_mode() {
@@ -44,7 +44,6 @@ macro_rules! quiet_eprintln {
         }
     }};
 }
-#[allow(unused_imports)]
 pub(crate) use quiet_eprintln;
 
 // Globally shared ProgressBar instance.
@@ -60,6 +59,62 @@ fn get_progress_bar() -> Option<Arc<ProgressBar>> {
     PROGRESS_BAR.read().as_ref()?.upgrade()
 }
 
+// The log level for the console, and the optional log file that receives
+// the debug log regardless of the console log level.
+lazy_static! {
+    static ref CONSOLE_LEVEL: RwLock<LevelFilter> = RwLock::new(LevelFilter::Warn);
+    static ref LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+}
+
+/// The level at which messages are written into the log file.
+const LOG_FILE_LEVEL: LevelFilter = LevelFilter::Debug;
+
+/// Sets the log level for the console output.
+///
+/// Use this instead of [`log::set_max_level`], so that a configured log file
+/// still receives debug messages.
+pub fn set_max_level(level: LevelFilter) {
+    *CONSOLE_LEVEL.write() = level;
+    update_max_level();
+}
+
+fn update_max_level() {
+    let mut level = *CONSOLE_LEVEL.read();
+    if LOG_FILE.lock().is_some() {
+        level = level.max(LOG_FILE_LEVEL);
+    }
+    log::set_max_level(level);
+}
+
+/// Writes the debug log into the file at the given path in addition to the console.
+///
+/// The file is opened in append mode, so that the logs of repeated invocations,
+/// for instance when retrying a failed CI step, are retained.
+pub fn set_log_file(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    *LOG_FILE.lock() = Some(file);
+    update_max_level();
+    Ok(())
+}
+
+fn write_to_log_file(level_name: &str, args: &std::fmt::Arguments, target: &str) {
+    let mut log_file = LOG_FILE.lock();
+    let Some(file) = log_file.as_mut() else {
+        return;
+    };
+
+    let msg = args.to_string();
+    let msg = redact_token_from_string(&msg, "[REDACTED]");
+    let target = if target != "sentry_cli" {
+        format!("  (from {target})")
+    } else {
+        "".to_string()
+    };
+
+    // Errors cannot be reported through the logger itself, so they are ignored.
+    writeln!(file, "{} {level_name} {msg}{target}", Local::now()).ok();
+}
+
 /// A simple logger.
 pub struct Logger;
 
@@ -78,7 +133,7 @@ impl Logger {
 
 impl log::Log for Logger {
     fn enabled(&self, metadata: &log::Metadata) -> bool {
-        self.get_actual_level(metadata) <= max_level()
+        self.get_actual_level(metadata) <= log::max_level()
     }
 
     fn log(&self, record: &log::Record) {
@@ -86,6 +141,10 @@ impl log::Log for Logger {
             return;
         }
 
+        if should_skip_log(record) {
+            return;
+        }
+
         let level = self.get_actual_level(record.metadata());
         let (level_name, level_color) = match level {
             log::Level::Error => ("ERROR", Color::Red),
@@ -95,6 +154,15 @@ impl log::Log for Logger {
             log::Level::Trace => ("TRACE", Color::Magenta),
         };
         let short_target = record.target().split("::").next().unwrap_or("");
+
+        if level <= LOG_FILE_LEVEL {
+            write_to_log_file(level_name, record.args(), short_target);
+        }
+
+        if level > *CONSOLE_LEVEL.read() {
+            return;
+        }
+
         let msg = format!(
             "{} {} {}{}",
             style(format!("  {level_name}  ")).bg(level_color).black(),
@@ -108,10 +176,6 @@ impl log::Log for Logger {
             .dim(),
         );
 
-        if should_skip_log(record) {
-            return;
-        }
-
         if let Some(pb) = get_progress_bar() {
             pb.println(msg);
         } else {
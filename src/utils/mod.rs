@@ -7,6 +7,8 @@ pub mod chunks;
 pub mod cordova;
 pub mod dif;
 pub mod dif_upload;
+pub mod env_file;
+pub mod envelope_queue;
 pub mod event;
 pub mod file_search;
 pub mod file_upload;
@@ -20,9 +22,13 @@ pub mod proguard_upload;
 pub mod releases;
 pub mod retry;
 pub mod sourcemaps;
+pub mod symbol_server;
 pub mod system;
 pub mod ui;
 pub mod update;
+pub mod upload_metadata;
+pub mod upload_report;
 pub mod value_parsers;
 pub mod vcs;
+pub mod watch;
 pub mod xcode;
@@ -1,33 +1,128 @@
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::env;
+use std::io::{self, Write};
 use std::ops::Deref;
+use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Error};
+use lazy_static::lazy_static;
+use serde::Serialize;
 
 use crate::utils::logging;
 
 pub use indicatif::ProgressStyle;
 
+/// The minimum interval between two JSON progress events of the same bar.
+const JSON_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How progress is reported to the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressFormat {
+    /// Render interactive progress bars.
+    Bar,
+    /// Emit newline-delimited JSON progress events to stderr.
+    Json,
+}
+
+impl FromStr for ProgressFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bar" => Ok(ProgressFormat::Bar),
+            "json" => Ok(ProgressFormat::Json),
+            _ => bail!("Unknown progress format: {}", s),
+        }
+    }
+}
+
+lazy_static! {
+    static ref PROGRESS_FORMAT: RwLock<ProgressFormat> = RwLock::new(ProgressFormat::Bar);
+}
+
+/// Sets the format in which progress is reported.
+pub fn set_progress_format(format: ProgressFormat) {
+    *PROGRESS_FORMAT.write() = format;
+}
+
+/// Returns the format in which progress is reported.
+pub fn progress_format() -> ProgressFormat {
+    *PROGRESS_FORMAT.read()
+}
+
 pub fn is_progress_bar_visible() -> bool {
-    env::var("SENTRY_NO_PROGRESS_BAR") != Ok("1".into())
+    env::var("SENTRY_NO_PROGRESS_BAR") != Ok("1".into()) && !logging::is_quiet_mode()
+}
+
+#[derive(Serialize)]
+struct ProgressEvent<'a> {
+    phase: &'a str,
+    sent: u64,
+    total: u64,
+}
+
+/// Emits progress of a bar as JSON events, instead of drawing it.
+struct JsonProgress {
+    phase: &'static str,
+    last_event: Mutex<Option<Instant>>,
+}
+
+impl JsonProgress {
+    fn emit(&self, sent: u64, total: u64, force: bool) {
+        let mut last_event = self.last_event.lock();
+        if !force && last_event.map_or(false, |last| last.elapsed() < JSON_EVENT_INTERVAL) {
+            return;
+        }
+        *last_event = Some(Instant::now());
+
+        let event = ProgressEvent {
+            phase: self.phase,
+            sent,
+            total,
+        };
+        if let Ok(mut line) = serde_json::to_vec(&event) {
+            line.push(b'\n');
+            io::stderr().write_all(&line).ok();
+        }
+    }
 }
 
 pub struct ProgressBar {
     inner: Arc<indicatif::ProgressBar>,
     start: Instant,
+    len: u64,
+    json: Option<JsonProgress>,
 }
 
 impl ProgressBar {
     pub fn new(len: usize) -> Self {
-        if is_progress_bar_visible() {
+        let mut pb = if is_progress_bar_visible() && progress_format() == ProgressFormat::Bar {
             indicatif::ProgressBar::new(len as u64).into()
         } else {
             Self::hidden()
+        };
+        pb.len = len as u64;
+        pb
+    }
+
+    /// Reports the progress of this bar as JSON events of the given phase
+    /// if JSON progress output was requested.
+    ///
+    /// Bars without a phase are hidden in JSON mode.
+    pub fn with_phase(mut self, phase: &'static str) -> Self {
+        if is_progress_bar_visible() && progress_format() == ProgressFormat::Json {
+            self.json = Some(JsonProgress {
+                phase,
+                last_event: Mutex::new(None),
+            });
         }
+        self
     }
 
     pub fn new_spinner() -> Self {
-        if is_progress_bar_visible() {
+        if is_progress_bar_visible() && progress_format() == ProgressFormat::Bar {
             indicatif::ProgressBar::new_spinner().into()
         } else {
             Self::hidden()
@@ -38,7 +133,27 @@ impl ProgressBar {
         indicatif::ProgressBar::hidden().into()
     }
 
+    pub fn set_position(&self, pos: u64) {
+        self.inner.set_position(pos);
+        self.emit_json(false);
+    }
+
+    pub fn inc(&self, delta: u64) {
+        self.inner.inc(delta);
+        self.emit_json(false);
+    }
+
+    fn emit_json(&self, force: bool) {
+        if let Some(ref json) = self.json {
+            json.emit(self.inner.position(), self.len, force);
+        }
+    }
+
     pub fn finish_with_duration(&self, op: &str) {
+        if let Some(ref json) = self.json {
+            json.emit(self.len, self.len, true);
+        }
+
         let dur = self.start.elapsed();
         // We could use `dur.as_secs_f64()`, but its unnecessarily precise (micros). Millis are enough for our purpose.
         let msg = format!("{} completed in {}s", op, dur.as_millis() as f64 / 1000.0);
@@ -50,6 +165,7 @@ impl ProgressBar {
     }
 
     pub fn finish_and_clear(&self) {
+        self.emit_json(true);
         self.inner.finish_and_clear();
         logging::set_progress_bar(None);
     }
@@ -62,6 +178,8 @@ impl From<indicatif::ProgressBar> for ProgressBar {
         ProgressBar {
             inner,
             start: Instant::now(),
+            len: 0,
+            json: None,
         }
     }
 }
@@ -4,18 +4,19 @@
 //! Proguard mappings, while we work on a more permanent solution, which will
 //! work for all different types of debug files.
 
+use std::thread;
 use std::time::{Duration, Instant};
-use std::{fs, thread};
 
 use anyhow::Result;
 use indicatif::ProgressStyle;
 use sha1_smol::Digest;
+use symbolic::common::ByteView;
 
 use super::chunks;
-use super::chunks::Chunk;
-use super::fs::get_sha1_checksums;
+use super::chunks::{get_sha1_checksums, Chunk};
 use crate::api::{Api, ChunkUploadOptions, ChunkedDifRequest, ChunkedFileState};
 use crate::commands::upload_proguard::MappingRef;
+use crate::utils::logging::quiet_println;
 
 /// How often to poll the server for the status of the assembled mappings.
 const ASSEMBLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
@@ -26,7 +27,8 @@ const ASSEMBLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
 const ASSEMBLE_POLL_TIMEOUT: Duration = Duration::from_secs(120);
 
 struct ChunkedMapping {
-    raw_data: Vec<u8>,
+    // Mappings are memory mapped, so that chunks are only read when sent.
+    raw_data: ByteView<'static>,
     hash: Digest,
     chunk_hashes: Vec<Digest>,
     file_name: String,
@@ -35,10 +37,10 @@ struct ChunkedMapping {
 
 impl ChunkedMapping {
     fn try_from_mapping(mapping: &MappingRef, chunk_size: u64) -> Result<Self> {
-        let raw_data = fs::read(mapping)?;
+        let raw_data = ByteView::open(mapping)?;
         let file_name = format!("/proguard/{}.txt", mapping.uuid);
 
-        let (hash, chunk_hashes) = get_sha1_checksums(&raw_data, chunk_size)?;
+        let (hash, chunk_hashes) = get_sha1_checksums(&raw_data[..], chunk_size)?;
         Ok(Self {
             raw_data,
             hash,
@@ -95,9 +97,10 @@ pub fn chunk_upload(
         &chunks.collect::<Vec<_>>(),
         chunk_upload_options,
         progress_style,
+        None,
     )?;
 
-    println!("Waiting for server to assemble uploaded mappings...");
+    quiet_println!("Waiting for server to assemble uploaded mappings...");
 
     let assemble_request = chunked_mappings.iter().map(to_assemble).collect();
     let start = Instant::now();
@@ -118,7 +121,7 @@ pub fn chunk_upload(
             .all(|response| matches!(response.state, ChunkedFileState::Ok));
 
         if all_assembled {
-            println!("Server finished assembling mappings.");
+            quiet_println!("Server finished assembling mappings.");
             return Ok(());
         }
 
@@ -4,15 +4,123 @@ use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+use console::style;
 use if_chain::if_chain;
 use lazy_static::lazy_static;
 use regex::Regex;
 
 use crate::utils::cordova::CordovaConfig;
+use crate::utils::logging::{quiet_eprintln, quiet_println};
 use crate::utils::vcs;
 use crate::utils::xcode::InfoPlist;
 
+/// The maximum length of a release version in characters.
+pub const MAX_RELEASE_LENGTH: usize = 250;
+
+/// Characters that Sentry does not permit anywhere in a release version.
+const FORBIDDEN_RELEASE_CHARS: &[char] = &['/', '\\', '\n', '\r', '\t', '\x0b', '\x0c'];
+
+/// Returns a human readable name of a character in a release version.
+fn describe_char(c: char) -> String {
+    match c {
+        '/' => "slash".to_owned(),
+        '\\' => "backslash".to_owned(),
+        ' ' => "space".to_owned(),
+        '\n' => "newline".to_owned(),
+        '\r' => "carriage return".to_owned(),
+        '\t' => "tab".to_owned(),
+        c => format!("character U+{:04X}", c as u32),
+    }
+}
+
+/// Checks that a release version is accepted by Sentry.
+///
+/// Sentry rejects versions that are empty, `.`, `..` or `latest`, have leading
+/// or trailing whitespace, contain slashes, backslashes or line breaks, or are
+/// longer than [`MAX_RELEASE_LENGTH`] characters.
+pub fn validate_release_version(version: &str) -> Result<()> {
+    if version.is_empty() {
+        bail!("Invalid release version. The release version must not be empty.");
+    }
+    if version == "." || version == ".." || version.eq_ignore_ascii_case("latest") {
+        bail!("Invalid release version `{version}`. This name is reserved by Sentry.");
+    }
+
+    let length = version.chars().count();
+    for (position, c) in version.chars().enumerate() {
+        let problem = if FORBIDDEN_RELEASE_CHARS.contains(&c) {
+            describe_char(c)
+        } else if c.is_whitespace() && position == 0 {
+            format!("leading {}", describe_char(c))
+        } else if c.is_whitespace() && position == length - 1 {
+            format!("trailing {}", describe_char(c))
+        } else {
+            continue;
+        };
+        bail!(
+            "Invalid release version `{}`: {problem} at position {} is not permitted.",
+            version.escape_debug(),
+            position + 1
+        );
+    }
+
+    if length > MAX_RELEASE_LENGTH {
+        bail!(
+            "Invalid release version. The release version is {length} characters long, \
+             but at most {MAX_RELEASE_LENGTH} characters are permitted."
+        );
+    }
+
+    Ok(())
+}
+
+/// Transforms a release version into one that Sentry accepts, by trimming it,
+/// replacing slashes, backslashes and whitespace with `-`, and capping its
+/// length.
+///
+/// Reserved names like `latest` cannot be sanitized and are returned as-is.
+pub fn sanitize_release_version(version: &str) -> String {
+    version
+        .trim()
+        .chars()
+        .map(|c| {
+            if c == '/' || c.is_whitespace() || FORBIDDEN_RELEASE_CHARS.contains(&c) {
+                '-'
+            } else {
+                c
+            }
+        })
+        .take(MAX_RELEASE_LENGTH)
+        .collect()
+}
+
+/// Validates a release version given by the user, or sanitizes it if `sanitize`
+/// is set.  The sanitized version is printed if it differs from the given one.
+pub fn resolve_release_version(version: &str, sanitize: bool) -> Result<String> {
+    if !sanitize {
+        if let Err(err) = validate_release_version(version) {
+            let sanitized = sanitize_release_version(version);
+            if validate_release_version(&sanitized).is_ok() {
+                bail!("{err} Use --sanitize to use `{sanitized}` instead.");
+            }
+            return Err(err);
+        }
+        return Ok(version.to_owned());
+    }
+
+    let sanitized = sanitize_release_version(version);
+    validate_release_version(&sanitized)?;
+    if sanitized != version {
+        quiet_eprintln!(
+            "{} Using sanitized release {}",
+            style(">").dim(),
+            style(&sanitized).cyan()
+        );
+    }
+    Ok(sanitized)
+}
+
 pub fn get_cordova_release_name(path: Option<PathBuf>) -> Result<Option<String>> {
     let here = path.unwrap_or(env::current_dir()?);
     let platform = match here.file_name().and_then(OsStr::to_str) {
@@ -165,3 +273,137 @@ pub fn detect_release_name() -> Result<String> {
         )),
     }
 }
+
+/// Detects the release name for `--release-from-git` and prints it.
+///
+/// This is the same release name that `releases propose-version` proposes.
+pub fn detect_release_name_from_git() -> Result<String> {
+    let release = detect_release_name()?;
+    quiet_println!(
+        "{} Using release {}",
+        style(">").dim(),
+        style(&release).cyan()
+    );
+    Ok(release)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_release_version() {
+        let valid = [
+            "1.0.0",
+            "my-app@1.0.0+1234",
+            "-hyphenated-release",
+            "release with spaces",
+            "a2f36b4e0c1d",
+            "ünïcödé",
+        ];
+        for version in valid {
+            assert!(
+                validate_release_version(version).is_ok(),
+                "`{version}` should be valid"
+            );
+        }
+
+        let invalid = [
+            ("", "Invalid release version. The release version must not be empty."),
+            (".", "Invalid release version `.`. This name is reserved by Sentry."),
+            ("..", "Invalid release version `..`. This name is reserved by Sentry."),
+            ("Latest", "Invalid release version `Latest`. This name is reserved by Sentry."),
+            (
+                "feature/foo",
+                "Invalid release version `feature/foo`: slash at position 8 is not permitted.",
+            ),
+            (
+                "1.0\n",
+                "Invalid release version `1.0\\n`: newline at position 4 is not permitted.",
+            ),
+            (
+                "1.0\t2",
+                "Invalid release version `1.0\\t2`: tab at position 4 is not permitted.",
+            ),
+            (
+                "1.0\x0b",
+                "Invalid release version `1.0\\u{b}`: character U+000B at position 4 is not permitted.",
+            ),
+            (
+                " 1.0",
+                "Invalid release version ` 1.0`: leading space at position 1 is not permitted.",
+            ),
+            (
+                "1.0 ",
+                "Invalid release version `1.0 `: trailing space at position 4 is not permitted.",
+            ),
+            (
+                "a\\b",
+                "Invalid release version `a\\\\b`: backslash at position 2 is not permitted.",
+            ),
+            (
+                "ä/b",
+                "Invalid release version `ä/b`: slash at position 2 is not permitted.",
+            ),
+        ];
+        for (version, message) in invalid {
+            let err = validate_release_version(version).unwrap_err();
+            assert_eq!(err.to_string(), message);
+        }
+
+        assert!(validate_release_version(&"a".repeat(MAX_RELEASE_LENGTH)).is_ok());
+        let err = validate_release_version(&"a".repeat(MAX_RELEASE_LENGTH + 1)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid release version. The release version is 251 characters long, \
+             but at most 250 characters are permitted."
+        );
+    }
+
+    #[test]
+    fn test_sanitize_release_version() {
+        let cases = [
+            ("1.0.0", "1.0.0"),
+            ("feature/foo", "feature-foo"),
+            ("  feature/foo\n", "feature-foo"),
+            ("release with spaces", "release-with-spaces"),
+            ("a/b\tc\r\nd", "a-b-c--d"),
+            ("windows\\path", "windows-path"),
+            ("latest", "latest"),
+        ];
+        for (version, sanitized) in cases {
+            assert_eq!(sanitize_release_version(version), sanitized);
+        }
+
+        let long = "a".repeat(MAX_RELEASE_LENGTH + 10);
+        assert_eq!(
+            sanitize_release_version(&long).chars().count(),
+            MAX_RELEASE_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_resolve_release_version() {
+        assert_eq!(resolve_release_version("1.0.0", false).unwrap(), "1.0.0");
+        assert_eq!(
+            resolve_release_version("feature/foo", true).unwrap(),
+            "feature-foo"
+        );
+
+        let err = resolve_release_version("feature/foo", false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid release version `feature/foo`: slash at position 8 is not permitted. \
+             Use --sanitize to use `feature-foo` instead."
+        );
+
+        // Reserved names cannot be sanitized, so there is nothing to suggest
+        let err = resolve_release_version("latest", false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid release version `latest`. This name is reserved by Sentry."
+        );
+        assert!(resolve_release_version("latest", true).is_err());
+        assert!(resolve_release_version(" \n ", true).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use std::time::{Duration, Instant};
 
 use backoff::backoff::Backoff;
 use backoff::ExponentialBackoff;
+use rand::Rng;
 
 use crate::constants::{
     DEFAULT_INITIAL_INTERVAL, DEFAULT_MAX_INTERVAL, DEFAULT_MULTIPLIER, DEFAULT_RANDOMIZATION,
@@ -23,6 +24,13 @@ pub fn get_default_backoff() -> ExponentialBackoff {
     eb
 }
 
+/// Adds a random jitter of up to a tenth of `delay` (but at least 100ms) so
+/// that many clients hitting a rate limit at once do not retry in lockstep.
+pub fn with_jitter(delay: Duration) -> Duration {
+    let max_jitter = (delay.as_millis() as f64 * DEFAULT_RANDOMIZATION).max(100.0) as u64;
+    delay + Duration::from_millis(rand::thread_rng().gen_range(0..=max_jitter))
+}
+
 /// Trait for displaying duration-like in milliseconds
 pub trait DurationAsMilliseconds {
     fn as_milliseconds(&self) -> u64;
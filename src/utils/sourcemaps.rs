@@ -1,7 +1,7 @@
 //! Provides sourcemap validation functionality.
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::OsStr;
-use std::io::Write;
+use std::io::{self, Write};
 use std::mem;
 use std::path::{Path, PathBuf};
 use std::str;
@@ -9,9 +9,13 @@ use std::str::FromStr;
 
 use anyhow::{anyhow, bail, Context, Error, Result};
 use console::style;
-use indicatif::ProgressStyle;
+use indicatif::{HumanBytes, ProgressStyle};
 use log::{debug, info, warn};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use regex::Regex;
 use sentry::types::DebugId;
+use serde::Serialize;
 use sha1_smol::Digest;
 use sourcemap::SourceMap;
 use symbolic::debuginfo::js::{
@@ -20,22 +24,46 @@ use symbolic::debuginfo::js::{
 use symbolic::debuginfo::sourcebundle::SourceFileType;
 use url::Url;
 
-use crate::api::Api;
+use crate::api::{Api, Artifact};
+use crate::utils::args::validate_distribution;
 use crate::utils::file_search::ReleaseFileMatch;
 use crate::utils::file_upload::{
-    initialize_legacy_release_upload, FileUpload, SourceFile, SourceFiles, UploadContext,
+    initialize_legacy_release_upload, FileUpload, LogLevel, SourceFile, SourceFiles, UploadContext,
 };
-use crate::utils::logging::is_quiet_mode;
+use crate::utils::fs::path_as_url;
+use crate::utils::logging::{is_quiet_mode, quiet_println};
 use crate::utils::progress::ProgressBar;
 use crate::utils::sourcemaps::inject::InjectReport;
+use crate::utils::sourcemaps::upload_state::UploadState;
+use crate::utils::upload_report::{UploadReportEntry, UploadStatus};
 
+pub mod bundle;
 pub mod inject;
+pub mod upload_state;
+
+/// The number of checksums queried per request when checking for already uploaded files.
+const CHECKSUM_BATCH_SIZE: usize = 100;
+
+/// The maximum number of concurrent requests when checking for already uploaded files.
+const CHECKSUM_QUERY_CONCURRENCY: usize = 8;
 
 /// The string prefix denoting a data URL.
 ///
 /// Data URLs are used to embed sourcemaps directly in javascript source files.
 const DATA_PREAMBLE: &str = "data:application/json;base64,";
 
+/// The maximum number of mappings spot checked per source map in strict validation.
+const MAX_SAMPLED_MAPPINGS: usize = 100;
+
+/// How thoroughly [`SourceMapProcessor::validate_all`] checks the sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationLevel {
+    /// Checks references and that source maps fit their minified files.
+    Basic,
+    /// Additionally spot checks mappings against the embedded sources.
+    Strict,
+}
+
 fn join_url(base_url: &str, url: &str) -> Result<String> {
     if base_url.starts_with("~/") {
         match Url::parse(&format!("http://{base_url}"))?.join(url) {
@@ -193,14 +221,35 @@ impl SourceMapReference {
     }
 }
 
+/// A file that would be uploaded, as printed by [`SourceMapProcessor::dry_run`].
+#[derive(Serialize)]
+struct SourceManifestEntry<'a> {
+    url: &'a str,
+    path: &'a Path,
+    #[serde(rename = "type")]
+    ty: SourceFileType,
+    debug_id: Option<&'a String>,
+    sourcemap: Option<String>,
+    size: u64,
+}
+
+/// The artifact bundle that would be uploaded by [`SourceMapProcessor::dry_run`].
+#[derive(Serialize)]
+struct BundleManifest {
+    debug_id: DebugId,
+    size: u64,
+}
+
 pub struct SourceMapProcessor {
     pending_sources: HashSet<(String, ReleaseFileMatch)>,
     sources: SourceFiles,
     sourcemap_references: HashMap<String, Option<SourceMapReference>>,
     debug_ids: HashMap<String, DebugId>,
+    oversized_sources: SourceFiles,
+    unchanged_sources: SourceFiles,
 }
 
-fn is_hermes_bytecode(slice: &[u8]) -> bool {
+pub fn is_hermes_bytecode(slice: &[u8]) -> bool {
     // The hermes bytecode format magic is defined here:
     // https://github.com/facebook/hermes/blob/5243222ef1d92b7393d00599fc5cff01d189a88a/include/hermes/BCGen/HBC/BytecodeFileFormat.h#L24-L25
     const HERMES_MAGIC: [u8; 8] = [0xC6, 0x1F, 0xBC, 0x03, 0xC1, 0x03, 0x19, 0x1F];
@@ -241,6 +290,62 @@ fn is_remote_sourcemap(url: &str) -> bool {
     is_remote_url(url) || is_url_path(url)
 }
 
+/// Returns the longest common directory of the given file paths.
+///
+/// For a single file, this is the directory containing the file. Returns
+/// `None` if no paths are given or the paths do not share a common root.
+pub fn common_path_prefix<'a, I>(paths: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = &'a Path>,
+{
+    let mut paths = paths.into_iter();
+    let mut prefix = paths.next()?.parent()?.to_path_buf();
+    for path in paths {
+        while !path.starts_with(&prefix) {
+            if !prefix.pop() {
+                return None;
+            }
+        }
+    }
+
+    if prefix.as_os_str().is_empty() {
+        None
+    } else {
+        Some(prefix)
+    }
+}
+
+/// Groups files by the dist derived from their path, which is the first
+/// capture group of `pattern`.
+///
+/// The files are given as pairs of the path that their URL is computed from
+/// and the file itself. The pattern is matched against the full path of the
+/// file with forward slashes. Files whose path does not match are assigned to
+/// `fallback_dist`, or fail the grouping if there is none.
+pub fn group_by_dist_from_path(
+    files: Vec<(PathBuf, ReleaseFileMatch)>,
+    pattern: &Regex,
+    fallback_dist: Option<&str>,
+) -> Result<BTreeMap<String, Vec<(PathBuf, ReleaseFileMatch)>>> {
+    let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (local_path, file) in files {
+        let path = path_as_url(&file.path);
+        let dist = match pattern.captures(&path).and_then(|captures| captures.get(1)) {
+            Some(dist) => validate_distribution(dist.as_str())
+                .map_err(|err| anyhow!("{err} (derived from {path})"))?,
+            None => match fallback_dist {
+                Some(dist) => dist.to_owned(),
+                None => bail!(
+                    "Could not derive a dist from {path}: the path does not match \
+                     --dist-from-path. Pass --dist to upload such files anyway."
+                ),
+            },
+        };
+        groups.entry(dist).or_default().push((local_path, file));
+    }
+    Ok(groups)
+}
+
 impl SourceMapProcessor {
     /// Creates a new sourcemap validator.
     pub fn new() -> SourceMapProcessor {
@@ -249,6 +354,8 @@ impl SourceMapProcessor {
             sources: SourceFiles::new(),
             sourcemap_references: HashMap::new(),
             debug_ids: HashMap::new(),
+            oversized_sources: SourceFiles::new(),
+            unchanged_sources: SourceFiles::new(),
         }
     }
 
@@ -270,7 +377,7 @@ impl SourceMapProcessor {
         let pb = ProgressBar::new(self.pending_sources.len());
         pb.set_style(progress_style);
 
-        println!(
+        quiet_println!(
             "{} Analyzing {} sources",
             style(">").dim(),
             style(self.pending_sources.len()).yellow()
@@ -375,8 +482,12 @@ impl SourceMapProcessor {
             // that can't be resolved to a source map file.
             // Instead, we pretend we failed to discover the location, and we fall back to
             // guessing the source map location based on the source location.
-            let location =
-                discover_sourcemaps_location(contents).filter(|loc| !is_remote_sourcemap(loc));
+            let location = if url_matches_extension(&source.url, inject::MAP_ONLY_EXTENSIONS) {
+                inject::discover_css_sourcemap_location(contents)
+            } else {
+                discover_sourcemaps_location(contents)
+            };
+            let location = location.filter(|loc| !is_remote_sourcemap(loc));
             let sourcemap_reference = match location {
                 Some(url) => SourceMapReference::from_url(url.to_string()),
                 None => match guess_sourcemap_reference(&sourcemaps, &source.url) {
@@ -405,8 +516,8 @@ impl SourceMapProcessor {
         let mut sources: Vec<_> = self.sources.values().collect();
         sources.sort_by_key(|&source| (source.ty, source.url.clone()));
 
-        println!();
-        println!("{}", style(title).dim().bold());
+        quiet_println!();
+        quiet_println!("{}", style(title).dim().bold());
         let mut current_section = None;
 
         for source in sources {
@@ -417,12 +528,12 @@ impl SourceMapProcessor {
             };
 
             if Some(section_title) != current_section {
-                println!("  {}", style(section_title).yellow().bold());
+                quiet_println!("  {}", style(section_title).yellow().bold());
                 current_section = Some(section_title);
             }
 
             if source.already_uploaded {
-                println!(
+                quiet_println!(
                     "    {}",
                     style(format!("{} (skipped; already uploaded)", &source.url)).yellow()
                 );
@@ -449,25 +560,29 @@ impl SourceMapProcessor {
             }
 
             if pieces.is_empty() {
-                println!("    {}", source.url);
+                quiet_println!("    {}", source.url);
             } else {
-                println!("    {} ({})", source.url, pieces.join(", "));
+                quiet_println!("    {} ({})", source.url, pieces.join(", "));
             }
 
             for msg in source.messages.iter() {
-                println!("      - {}: {}", style(&msg.0).red(), msg.1);
+                quiet_println!("      - {}: {}", style(&msg.0).red(), msg.1);
             }
         }
     }
 
     /// Validates all sources within.
-    pub fn validate_all(&mut self) -> Result<()> {
+    ///
+    /// Problems which prevent processing fail the validation, mismatches between
+    /// source maps and their minified files are reported as warnings. With
+    /// `fail_on_warnings`, those fail the validation as well.
+    pub fn validate_all(&mut self, level: ValidationLevel, fail_on_warnings: bool) -> Result<()> {
         self.flush_pending_sources();
         let source_urls = self.sources.keys().cloned().collect();
         let sources: Vec<&mut SourceFile> = self.sources.values_mut().collect();
         let mut failed = false;
 
-        println!("{} Validating sources", style(">").dim());
+        quiet_println!("{} Validating sources", style(">").dim());
 
         let progress_style = ProgressStyle::default_bar().template(&format!(
             "{} {{msg}}\n{{wide_bar}} {{pos}}/{{len}}",
@@ -497,12 +612,133 @@ impl SourceMapProcessor {
         }
         pb.finish_and_clear();
 
-        if !failed {
+        for (minified_url, sourcemap_url) in self.sourcemap_pairs() {
+            let findings = {
+                let minified = &self.sources[&minified_url];
+                let sourcemap = &self.sources[&sourcemap_url];
+                match sourcemap::decode_slice(&sourcemap.contents) {
+                    Ok(sourcemap::DecodedMap::Regular(sm)) => validate_sourcemap_pairing(
+                        &minified_url,
+                        Some(&String::from_utf8_lossy(&minified.contents)),
+                        &sm,
+                        level,
+                    ),
+                    _ => continue,
+                }
+            };
+
+            let sourcemap = self.sources.get_mut(&sourcemap_url).unwrap();
+            for finding in findings {
+                sourcemap.warn(finding);
+            }
+        }
+
+        let warned = self.sources.values().any(|source| {
+            source
+                .messages
+                .iter()
+                .any(|(level, _)| *level == LogLevel::Warning)
+        });
+
+        if !(failed || (fail_on_warnings && warned)) {
             return Ok(());
         }
 
         self.dump_log("Source Map Validation Report");
-        bail!("Encountered problems when validating source maps.");
+        if failed {
+            bail!("Encountered problems when validating source maps.");
+        }
+        bail!("Encountered warnings when validating source maps in strict mode.");
+    }
+
+    /// Checks that the `sourceMappingURL` of every minified file resolves to a
+    /// source map in the upload or to an absolute http(s) URL, and that the
+    /// `file` and `sources` references of all source maps are sane.
+    ///
+    /// Unresolved references are printed as a warning, grouped by the file
+    /// containing them. With `fail_on_unresolved`, they fail the check.
+    pub fn check_references(&mut self, fail_on_unresolved: bool) -> Result<()> {
+        self.flush_pending_sources();
+
+        let sourcemaps: Vec<_> = self
+            .sources
+            .values()
+            .filter(|source| source.ty == SourceFileType::SourceMap)
+            .map(|source| source.url.clone())
+            .collect();
+
+        let mut unresolved = BTreeMap::new();
+        for source in self.sources.values() {
+            let problems = match source.ty {
+                SourceFileType::MinifiedSource => {
+                    check_sourcemap_reference(source, &self.sources, &sourcemaps)
+                        .into_iter()
+                        .collect()
+                }
+                SourceFileType::SourceMap => check_sourcemap_file_references(source),
+                _ => continue,
+            };
+            if !problems.is_empty() {
+                unresolved.insert(source.url.as_str(), problems);
+            }
+        }
+
+        if unresolved.is_empty() {
+            return Ok(());
+        }
+
+        eprintln!(
+            "{}",
+            style("warning: Some source map references could not be resolved:").red()
+        );
+        for (url, problems) in &unresolved {
+            eprintln!("  {url}");
+            for problem in problems {
+                eprintln!("    - {problem}");
+            }
+        }
+
+        if fail_on_unresolved {
+            bail!("Found unresolved source map references in strict mode.");
+        }
+        Ok(())
+    }
+
+    /// Pairs minified sources with the external source maps they reference,
+    /// returning tuples of the minified source URL and the source map URL.
+    fn sourcemap_pairs(&self) -> Vec<(String, String)> {
+        let sourcemaps: Vec<_> = self
+            .sources
+            .values()
+            .filter(|source| source.ty == SourceFileType::SourceMap)
+            .map(|source| source.url.clone())
+            .collect();
+
+        self.sources
+            .values()
+            // Hermes bytecode bundles are flagged as empty minified sources
+            .filter(|source| {
+                source.ty == SourceFileType::MinifiedSource && !source.contents.is_empty()
+            })
+            .filter_map(|source| {
+                let reference_url = match self.sourcemap_references.get(&source.url) {
+                    Some(Some(reference)) => reference.url.clone(),
+                    _ => get_sourcemap_ref(source)?.get_url().to_owned(),
+                };
+                if reference_url.starts_with("data:") {
+                    return None;
+                }
+
+                let sourcemap_url = resolve_sourcemap_url(
+                    &source.url,
+                    &SourceMapReference::from_url(reference_url),
+                    &sourcemaps,
+                );
+                let sourcemap = self.sources.get(&sourcemap_url)?;
+                (sourcemap.ty == SourceFileType::SourceMap)
+                    .then(|| (source.url.clone(), sourcemap_url))
+            })
+            .collect()
     }
 
     /// Unpacks the given RAM bundle into a list of module sources and their sourcemaps
@@ -648,7 +884,7 @@ impl SourceMapProcessor {
     pub fn rewrite(&mut self, prefixes: &[&str]) -> Result<()> {
         self.flush_pending_sources();
 
-        println!("{} Rewriting sources", style(">").dim());
+        quiet_println!("{} Rewriting sources", style(">").dim());
 
         self.unpack_indexed_ram_bundles()?;
 
@@ -694,7 +930,13 @@ impl SourceMapProcessor {
         self.flush_pending_sources();
         self.collect_sourcemap_references();
 
-        println!("{} Adding source map references", style(">").dim());
+        let sourcemaps = self
+            .sources
+            .values()
+            .filter_map(|s| (s.ty == SourceFileType::SourceMap).then_some(s.url.clone()))
+            .collect::<Vec<_>>();
+
+        quiet_println!("{} Adding source map references", style(">").dim());
         for source in self.sources.values_mut() {
             if source.ty != SourceFileType::MinifiedSource {
                 continue;
@@ -702,6 +944,17 @@ impl SourceMapProcessor {
 
             if let Some(Some(sourcemap)) = self.sourcemap_references.get(&source.url) {
                 source.set_sourcemap_reference(sourcemap.url.to_string());
+
+                // Assets that cannot contain code are identified by the debug ids of their sourcemaps.
+                if url_matches_extension(&source.url, inject::MAP_ONLY_EXTENSIONS)
+                    && source.debug_id().is_none()
+                {
+                    let sourcemap_url = resolve_sourcemap_url(&source.url, sourcemap, &sourcemaps);
+                    if let Some(&debug_id) = self.debug_ids.get(&sourcemap_url) {
+                        source.set_debug_id(debug_id.to_string());
+                        self.debug_ids.insert(source.url.clone(), debug_id);
+                    }
+                }
             }
         }
         Ok(())
@@ -767,7 +1020,11 @@ impl SourceMapProcessor {
     }
 
     /// Flags the collected sources whether they have already been uploaded before
-    /// (based on their checksum), and returns the number of files that *do* need an upload.
+    /// (based on their checksum and dist), and returns the number of files that *do*
+    /// need an upload.
+    ///
+    /// Checksums are queried in batches with a bounded number of concurrent requests.
+    /// If the server cannot answer a query, all sources are uploaded.
     fn flag_uploaded_sources(&mut self, context: &UploadContext<'_>) -> usize {
         let mut files_needing_upload = self.sources.len();
 
@@ -788,28 +1045,29 @@ impl SourceMapProcessor {
 
         // Checksums need to be sorted in order to satisfy integration tests constraints.
         sources_checksums.sort();
+        sources_checksums.dedup();
 
-        let api = Api::current();
+        let artifacts = match query_release_files_by_checksum(context, release, &sources_checksums)
+        {
+            Ok(artifacts) => artifacts,
+            Err(err) => {
+                info!("Could not check for already uploaded files: {err:#}");
+                return files_needing_upload;
+            }
+        };
 
-        if let Ok(artifacts) = api.authenticated().and_then(|api| {
-            api.list_release_files_by_checksum(
-                context.org,
-                context.project,
-                release,
-                &sources_checksums,
-            )
-        }) {
-            let already_uploaded_checksums: HashSet<_> = artifacts
-                .into_iter()
-                .filter_map(|artifact| Digest::from_str(&artifact.sha1).ok())
-                .collect();
-
-            for source in self.sources.values_mut() {
-                if let Ok(checksum) = source.checksum() {
-                    if already_uploaded_checksums.contains(&checksum) {
-                        source.already_uploaded = true;
-                        files_needing_upload -= 1;
-                    }
+        let dist = context.dist.map(str::to_owned);
+        let already_uploaded_checksums: HashSet<_> = artifacts
+            .into_iter()
+            .filter(|artifact| artifact.dist == dist)
+            .filter_map(|artifact| Digest::from_str(&artifact.sha1).ok())
+            .collect();
+
+        for source in self.sources.values_mut() {
+            if let Ok(checksum) = source.checksum() {
+                if already_uploaded_checksums.contains(&checksum) {
+                    source.already_uploaded = true;
+                    files_needing_upload -= 1;
                 }
             }
         }
@@ -821,9 +1079,184 @@ impl SourceMapProcessor {
     pub fn upload(&mut self, context: &UploadContext<'_>) -> Result<usize> {
         initialize_legacy_release_upload(context)?;
         self.flush_pending_sources();
+        self.check_debug_ids(context)?;
 
-        // If there is no release, we have to check that the files at least
-        // contain debug ids.
+        let files_needing_upload = self.flag_uploaded_sources(context);
+        self.print_unchanged_sources();
+        if files_needing_upload > 0 {
+            let mut uploader = FileUpload::new(context);
+            uploader.files(&self.sources);
+            uploader.upload()?;
+
+            let skipped = self.sources.len() - files_needing_upload;
+            if skipped > 0 {
+                quiet_println!(
+                    "{} Uploaded {} {}, skipped {} already on the server",
+                    style(">").dim(),
+                    style(files_needing_upload).yellow(),
+                    match files_needing_upload {
+                        1 => "file",
+                        _ => "files",
+                    },
+                    style(skipped).yellow()
+                );
+            }
+            self.dump_log("Source Map Upload Report");
+        } else {
+            quiet_println!("{} Nothing to upload", style(">").dim());
+        }
+        Ok(files_needing_upload)
+    }
+
+    /// Removes all files larger than `max_size` bytes, so that they are not uploaded.
+    ///
+    /// The server rejects bundles containing such files, so each skipped file is
+    /// reported with a warning, and in the [`report`](Self::report).
+    pub fn skip_oversized_sources(&mut self, max_size: u64) {
+        self.flush_pending_sources();
+
+        let oversized = self
+            .sources
+            .iter()
+            .filter(|(_, source)| source.contents.len() as u64 > max_size)
+            .map(|(url, _)| url.clone())
+            .collect::<Vec<_>>();
+
+        for url in oversized {
+            let source = self.sources.remove(&url).unwrap();
+            eprintln!(
+                "{}",
+                style(format!(
+                    "warning: Skipping {} since it exceeds the maximum file size of {} ({})",
+                    source.path.display(),
+                    HumanBytes(max_size),
+                    HumanBytes(source.contents.len() as u64)
+                ))
+                .red()
+            );
+            self.oversized_sources.insert(url, source);
+        }
+    }
+
+    /// Removes all files that are unchanged since the upload recorded in `state`,
+    /// so that they are not bundled and uploaded again.
+    ///
+    /// The skipped files are counted in the summary, and listed in the
+    /// [`report`](Self::report).
+    pub fn skip_unchanged_sources(&mut self, state: &UploadState, dist: Option<&str>) {
+        self.flush_pending_sources();
+
+        let unchanged = self
+            .sources
+            .iter()
+            .filter(|(_, source)| state.is_unchanged(source, dist))
+            .map(|(url, _)| url.clone())
+            .collect::<Vec<_>>();
+
+        for url in unchanged {
+            let source = self.sources.remove(&url).unwrap();
+            self.unchanged_sources.insert(url, source);
+        }
+    }
+
+    /// Records all files of a successful `upload` in `state`, including the files
+    /// that were skipped because they are already on the server or unchanged.
+    pub fn record_upload_state(&self, state: &mut UploadState, dist: Option<&str>) {
+        for source in self.sources.values().chain(self.unchanged_sources.values()) {
+            state.record(source, dist);
+        }
+    }
+
+    fn print_unchanged_sources(&self) {
+        let unchanged = self.unchanged_sources.len();
+        if unchanged > 0 {
+            quiet_println!(
+                "{} {} {} unchanged (skipped via state)",
+                style(">").dim(),
+                style(unchanged).yellow(),
+                match unchanged {
+                    1 => "file",
+                    _ => "files",
+                }
+            );
+        }
+    }
+
+    /// Processes and bundles all files like `upload`, but prints a manifest of the
+    /// files that would be uploaded instead of uploading them.
+    ///
+    /// Neither the server nor the files on disk are modified. If `json` is set,
+    /// the manifest is printed as JSON. Returns the number of files in the manifest.
+    pub fn dry_run(&mut self, context: &UploadContext<'_>, json: bool) -> Result<usize> {
+        self.flush_pending_sources();
+        self.check_debug_ids(context)?;
+
+        let mut uploader = FileUpload::new(context);
+        uploader.files(&self.sources);
+        let bundle = uploader
+            .build_bundle()?
+            .map(|(debug_id, archive)| -> Result<_> {
+                Ok(BundleManifest {
+                    debug_id,
+                    size: archive.open()?.metadata()?.len(),
+                })
+            })
+            .transpose()?;
+
+        let mut files: Vec<_> = self
+            .sources
+            .values()
+            .map(|source| SourceManifestEntry {
+                url: &source.url,
+                path: &source.path,
+                ty: source.ty,
+                debug_id: source.debug_id(),
+                sourcemap: get_sourcemap_ref(source).map(|sm_ref| sm_ref.get_url().to_owned()),
+                size: source.contents.len() as u64,
+            })
+            .collect();
+        files.sort_by_key(|file| (file.ty, file.url));
+        let total_size = files.iter().map(|file| file.size).sum::<u64>();
+
+        if json {
+            serde_json::to_writer_pretty(
+                &mut io::stdout(),
+                &serde_json::json!({
+                    "files": files,
+                    "total_size": total_size,
+                    "bundle": bundle,
+                }),
+            )?;
+            quiet_println!();
+        } else {
+            self.dump_log("Source Map Dry Run Manifest");
+            quiet_println!();
+            self.print_unchanged_sources();
+            quiet_println!(
+                "{} {} {} ({}) would be uploaded",
+                style(">").dim(),
+                style(files.len()).yellow(),
+                match files.len() {
+                    1 => "file",
+                    _ => "files",
+                },
+                HumanBytes(total_size)
+            );
+            if let Some(bundle) = bundle {
+                quiet_println!(
+                    "{} Bundle size: {}",
+                    style(">").dim(),
+                    HumanBytes(bundle.size)
+                );
+            }
+        }
+
+        Ok(files.len())
+    }
+
+    /// Checks that files contain debug ids if no release is specified, since
+    /// they could not be associated otherwise.
+    fn check_debug_ids(&self, context: &UploadContext<'_>) -> Result<()> {
         if context.release.is_none() {
             let mut files_without_debug_id = BTreeSet::new();
             let mut files_with_debug_id = false;
@@ -855,48 +1288,92 @@ impl SourceMapProcessor {
             }
         }
 
-        let files_needing_upload = self.flag_uploaded_sources(context);
-        if files_needing_upload > 0 {
-            let mut uploader = FileUpload::new(context);
-            uploader.files(&self.sources);
-            uploader.upload()?;
-            self.dump_log("Source Map Upload Report");
-        } else {
-            println!("{} Nothing to upload", style(">").dim());
-        }
-        Ok(files_needing_upload)
+        Ok(())
     }
 
     /// Upload all files in "strict" mode. Strict mode differs from a normal upload
     /// only when there are no files to upload. In strict mode, having no files to
     /// upload results in an error, whereas such an upload is successful in normal
-    /// (non-strict) mode. On success, the number of uploaded files is returned in
-    /// an Ok()
+    /// (non-strict) mode. Files skipped because they are unchanged since the last
+    /// upload do not count as missing. On success, the number of uploaded files is
+    /// returned in an Ok()
     pub fn upload_strict(&mut self, context: &UploadContext<'_>) -> Result<usize> {
         match self.upload(context) {
-            Ok(0) => Err(anyhow!("No files to upload (strict mode).")),
+            Ok(0) if self.unchanged_sources.is_empty() => {
+                Err(anyhow!("No files to upload (strict mode)."))
+            }
             other => other,
         }
     }
 
+    /// Returns the outcome of every file after calling `upload`.
+    ///
+    /// Files are uploaded together, so if the upload failed with `error`, all
+    /// files that were not already on the server are reported with that error.
+    pub fn report(&self, error: Option<&Error>) -> Vec<UploadReportEntry> {
+        let entry = |source: &SourceFile, status| UploadReportEntry {
+            path: source.path.display().to_string(),
+            debug_id: source.debug_id().cloned(),
+            ty: match source.ty {
+                SourceFileType::Source => "source",
+                SourceFileType::MinifiedSource => "minified_source",
+                SourceFileType::SourceMap => "source_map",
+                SourceFileType::IndexedRamBundle => "indexed_ram_bundle",
+            }
+            .to_owned(),
+            size: source.contents.len() as u64,
+            checksum: source.checksum().ok().map(|c| c.to_string()),
+            status,
+        };
+
+        let uploaded = self.sources.values().map(|source| {
+            let status = match error {
+                _ if source.already_uploaded => UploadStatus::SkippedKnown,
+                Some(error) => UploadStatus::Errored {
+                    message: format!("{error:#}"),
+                },
+                None => UploadStatus::Uploaded,
+            };
+            entry(source, status)
+        });
+        let oversized = self
+            .oversized_sources
+            .values()
+            .map(|source| entry(source, UploadStatus::SkippedTooLarge));
+        let unchanged = self
+            .unchanged_sources
+            .values()
+            .map(|source| entry(source, UploadStatus::SkippedUnchanged));
+        uploaded.chain(oversized).chain(unchanged).collect()
+    }
+
     /// Injects debug ids into minified source files and sourcemaps.
     ///
     /// This iterates over contained minified source files and adds debug ids
-    /// to them. Files already containing debug ids will be untouched.
+    /// to them. Files already containing debug ids will be untouched, unless
+    /// `force` is set, in which case their debug id is replaced with a new one.
     ///
     /// If a source file refers to a sourcemap and that sourcemap is locally
     /// available, the debug id will be injected there as well so as to tie
     /// them together. If for whatever reason the sourcemap already contains
     /// a debug id, it will be reused for the source file.
     ///
+    /// Files that fail to process are reported and do not prevent the remaining
+    /// files from being processed. If any file failed, an error is returned at the end.
+    ///
     /// If `dry_run` is false, this will modify the source and sourcemap files on disk!
     ///
     /// The `js_extensions` is a list of file extensions that should be considered
     /// for JavaScript files.
-    pub fn inject_debug_ids(&mut self, dry_run: bool, js_extensions: &[&str]) -> Result<()> {
+    pub fn inject_debug_ids(
+        &mut self,
+        dry_run: bool,
+        force: bool,
+        js_extensions: &[&str],
+    ) -> Result<()> {
         self.flush_pending_sources();
         self.collect_sourcemap_references();
-        println!("{} Injecting debug ids", style(">").dim());
+        quiet_println!("{} Injecting debug ids", style(">").dim());
 
         let mut report = InjectReport::default();
 
@@ -917,20 +1394,280 @@ impl SourceMapProcessor {
                 continue;
             }
 
-            if let Some(debug_id) = self.debug_ids.get(source_url) {
+            if url_matches_extension(source_url, inject::MAP_ONLY_EXTENSIONS) {
+                let result = inject_debug_id_into_sourcemap(
+                    &mut self.sources,
+                    &sourcemaps,
+                    source_url,
+                    sourcemap_url,
+                    force,
+                    dry_run,
+                    &mut report,
+                );
+
+                let source_file = self.sources.get_mut(source_url).unwrap();
+                match result {
+                    Ok(Some(debug_id)) => {
+                        source_file.set_debug_id(debug_id.to_string());
+                        self.debug_ids.insert(source_url.clone(), debug_id);
+                    }
+                    Ok(None) => debug!("skipping {} because it has no sourcemap", source_url),
+                    Err(err) => {
+                        report
+                            .failed
+                            .push((source_file.path.clone(), format!("{err:#}")));
+                    }
+                }
+                continue;
+            }
+
+            let previous_debug_id = self.debug_ids.get(source_url).copied();
+            if let (Some(debug_id), false) = (previous_debug_id, force) {
                 report
                     .previously_injected
-                    .push((source_url.into(), *debug_id));
+                    .push((source_url.into(), debug_id));
                 continue;
             }
 
-            // Modify the source file and the sourcemap.
-            // There are several cases to consider according to whether we have a sourcemap for the source file and
-            // whether it's embedded or external.
-            let debug_id = match sourcemap_url {
-                None => {
-                    // Case 1: We have no sourcemap for the source file. Hash the file contents for the debug id.
-                    let source_file = self.sources.get_mut(source_url).unwrap();
+            let result = match previous_debug_id {
+                Some(previous_debug_id) => reinject_debug_id(
+                    &mut self.sources,
+                    &sourcemaps,
+                    source_url,
+                    sourcemap_url,
+                    previous_debug_id,
+                    dry_run,
+                    &mut report,
+                ),
+                None => inject_debug_id(
+                    &mut self.sources,
+                    &sourcemaps,
+                    source_url,
+                    sourcemap_url,
+                    dry_run,
+                    &mut report,
+                ),
+            };
+
+            // Finally, some housekeeping.
+            let source_file = self.sources.get_mut(source_url).unwrap();
+            let result = result.and_then(|debug_id| {
+                if !dry_run {
+                    let mut file = std::fs::File::create(&source_file.path)?;
+                    file.write_all(&source_file.contents).context(format!(
+                        "Failed to write source file {}",
+                        source_file.path.display()
+                    ))?;
+                }
+                Ok(debug_id)
+            });
+
+            match result {
+                Ok(debug_id) => {
+                    source_file.set_debug_id(debug_id.to_string());
+                    self.debug_ids.insert(source_url.clone(), debug_id);
+                    report.injected.push((source_file.path.clone(), debug_id));
+                }
+                Err(err) => {
+                    report
+                        .failed
+                        .push((source_file.path.clone(), format!("{err:#}")));
+                }
+            }
+        }
+
+        if report.is_empty() {
+            quiet_println!("> Nothing to inject");
+            return Ok(());
+        }
+
+        quiet_println!("{report}");
+        quiet_println!(
+            "{} {} {}, skipped {} (already injected), failed {}",
+            style(">").dim(),
+            if dry_run { "Would inject" } else { "Injected" },
+            report.injected.len() + report.assets.len(),
+            report.previously_injected.len(),
+            report.failed.len(),
+        );
+
+        if !report.failed.is_empty() {
+            bail!(
+                "Failed to inject debug ids into {} source files",
+                report.failed.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Queries the release files matching the given checksums, in batches of
+/// `CHECKSUM_BATCH_SIZE` with at most `CHECKSUM_QUERY_CONCURRENCY` requests in flight.
+fn query_release_files_by_checksum(
+    context: &UploadContext<'_>,
+    release: &str,
+    checksums: &[String],
+) -> Result<Vec<Artifact>> {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(CHECKSUM_QUERY_CONCURRENCY)
+        .build()?;
+
+    let batches = pool.install(|| {
+        checksums
+            .par_chunks(CHECKSUM_BATCH_SIZE)
+            .map(|batch| -> Result<Vec<Artifact>> {
+                let api = Api::current();
+                Ok(api.authenticated()?.list_release_files_by_checksum(
+                    context.org,
+                    context.project,
+                    release,
+                    batch,
+                )?)
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    Ok(batches.into_iter().flatten().collect())
+}
+
+/// Resolves the url of an external sourcemap referenced by a source file
+/// against the sourcemaps that are available locally.
+fn resolve_sourcemap_url(
+    source_url: &str,
+    sourcemap: &SourceMapReference,
+    sourcemaps: &[String],
+) -> String {
+    let normalized = inject::normalize_sourcemap_url(source_url, &sourcemap.url);
+    let matches = inject::find_matching_paths(sourcemaps, &normalized);
+
+    match &matches[..] {
+        [] => normalized,
+        [x] => x.to_string(),
+        _ => {
+            warn!("Ambiguous matches for sourcemap path {normalized}:");
+            for path in matches {
+                warn!("{path}");
+            }
+            normalized
+        }
+    }
+}
+
+/// Injects a debug id into a source file that doesn't contain one yet,
+/// as well as into its sourcemap if available.
+///
+/// Returns the injected debug id. The source file is modified in memory only,
+/// sourcemap files are written to disk unless `dry_run` is set.
+fn inject_debug_id(
+    sources: &mut SourceFiles,
+    sourcemaps: &[String],
+    source_url: &str,
+    sourcemap_url: &mut Option<SourceMapReference>,
+    dry_run: bool,
+    report: &mut InjectReport,
+) -> Result<DebugId> {
+    // Modify the source file and the sourcemap.
+    // There are several cases to consider according to whether we have a sourcemap for the source file and
+    // whether it's embedded or external.
+    let debug_id = match sourcemap_url {
+        None => {
+            // Case 1: We have no sourcemap for the source file. Hash the file contents for the debug id.
+            let source_file = sources.get_mut(source_url).unwrap();
+            let debug_id = inject::debug_id_from_bytes_hashed(&source_file.contents);
+
+            // If we don't have a sourcemap, it's not safe to inject the code snippet at the beginning,
+            // because that would throw off all the mappings. Instead, inject the snippet at the very end.
+            // This isn't ideal, but it's the best we can do in this case.
+            inject::fixup_js_file_end(&mut source_file.contents, debug_id)
+                .context(format!("Failed to process {}", source_file.path.display()))?;
+            debug_id
+        }
+        Some(sourcemap) => {
+            if let Some(encoded) = sourcemap.url.strip_prefix(DATA_PREAMBLE) {
+                // Case 2: The source file has an embedded sourcemap.
+
+                let Ok(mut decoded) = data_encoding::BASE64.decode(encoded.as_bytes()) else {
+                    bail!("Invalid embedded sourcemap in source file {source_url}");
+                };
+
+                let mut sourcemap = SourceMap::from_slice(&decoded).with_context(|| {
+                    format!("Invalid embedded sourcemap in source file {source_url}")
+                })?;
+
+                let debug_id = sourcemap
+                    .get_debug_id()
+                    .unwrap_or_else(|| inject::debug_id_from_bytes_hashed(&decoded));
+
+                let source_file = sources.get_mut(source_url).unwrap();
+                let adjustment_map = inject::fixup_js_file(&mut source_file.contents, debug_id)
+                    .context(format!("Failed to process {}", source_file.path.display()))?;
+
+                sourcemap.adjust_mappings(&adjustment_map);
+                sourcemap.set_debug_id(Some(debug_id));
+
+                decoded.clear();
+                sourcemap.to_writer(&mut decoded)?;
+
+                let encoded = data_encoding::BASE64.encode(&decoded);
+                let new_sourcemap_url = format!("{DATA_PREAMBLE}{encoded}");
+
+                inject::replace_sourcemap_url(&mut source_file.contents, &new_sourcemap_url)?;
+                *sourcemap_url = Some(SourceMapReference::from_url(new_sourcemap_url));
+
+                debug_id
+            } else {
+                // Handle external sourcemaps
+                let sourcemap_url = resolve_sourcemap_url(source_url, sourcemap, sourcemaps);
+
+                if sources.contains_key(&sourcemap_url) {
+                    // Case 3: We have an external sourcemap for the source file.
+
+                    // We need to do a bit of a dance here because we can't mutably
+                    // borrow the source file and the sourcemap at the same time.
+                    let (mut sourcemap, debug_id, debug_id_fresh) = {
+                        let sourcemap_file = &sources[&sourcemap_url];
+
+                        let sm = SourceMap::from_slice(&sourcemap_file.contents)
+                            .context(format!("Invalid sourcemap at {}", sourcemap_file.url))?;
+
+                        match sm.get_debug_id() {
+                            Some(debug_id) => (sm, debug_id, false),
+                            None => {
+                                let debug_id =
+                                    inject::debug_id_from_bytes_hashed(&sourcemap_file.contents);
+                                (sm, debug_id, true)
+                            }
+                        }
+                    };
+
+                    let source_file = sources.get_mut(source_url).unwrap();
+                    let adjustment_map = inject::fixup_js_file(&mut source_file.contents, debug_id)
+                        .context(format!("Failed to process {}", source_file.path.display()))?;
+
+                    sourcemap.adjust_mappings(&adjustment_map);
+                    sourcemap.set_debug_id(Some(debug_id));
+
+                    let sourcemap_file = sources.get_mut(&sourcemap_url).unwrap();
+                    write_sourcemap(sourcemap_file, &sourcemap, debug_id, dry_run)?;
+
+                    if debug_id_fresh {
+                        report
+                            .sourcemaps
+                            .push((sourcemap_file.path.clone(), debug_id));
+                    } else {
+                        report
+                            .skipped_sourcemaps
+                            .push((sourcemap_file.path.clone(), debug_id));
+                    }
+
+                    debug_id
+                } else {
+                    // Case 4: We have a URL for the external sourcemap, but we can't find it.
+                    // This is substantially the same as case 1.
+                    debug!("Sourcemap file {} not found", sourcemap_url);
+                    // source map cannot be found, fall back to hashing the contents.
+                    let source_file = sources.get_mut(source_url).unwrap();
                     let debug_id = inject::debug_id_from_bytes_hashed(&source_file.contents);
 
                     // If we don't have a sourcemap, it's not safe to inject the code snippet at the beginning,
@@ -938,174 +1675,146 @@ impl SourceMapProcessor {
                     // This isn't ideal, but it's the best we can do in this case.
                     inject::fixup_js_file_end(&mut source_file.contents, debug_id)
                         .context(format!("Failed to process {}", source_file.path.display()))?;
+
                     debug_id
                 }
-                Some(sourcemap) => {
-                    if let Some(encoded) = sourcemap.url.strip_prefix(DATA_PREAMBLE) {
-                        // Case 2: The source file has an embedded sourcemap.
-
-                        let Ok(mut decoded) = data_encoding::BASE64.decode(encoded.as_bytes())
-                        else {
-                            bail!("Invalid embedded sourcemap in source file {source_url}");
-                        };
+            }
+        }
+    };
 
-                        let mut sourcemap = SourceMap::from_slice(&decoded).with_context(|| {
-                            format!("Invalid embedded sourcemap in source file {source_url}")
-                        })?;
+    Ok(debug_id)
+}
 
-                        let debug_id = sourcemap
-                            .get_debug_id()
-                            .unwrap_or_else(|| inject::debug_id_from_bytes_hashed(&decoded));
+/// Replaces the debug id of a source file that was injected before with a
+/// new one, and updates its sourcemap accordingly if available.
+///
+/// The code snippet is not injected again, so the mappings stay valid.
+fn reinject_debug_id(
+    sources: &mut SourceFiles,
+    sourcemaps: &[String],
+    source_url: &str,
+    sourcemap_url: &mut Option<SourceMapReference>,
+    previous_debug_id: DebugId,
+    dry_run: bool,
+    report: &mut InjectReport,
+) -> Result<DebugId> {
+    let source_file = sources.get_mut(source_url).unwrap();
+    let debug_id = inject::debug_id_from_bytes_hashed(&source_file.contents);
+    inject::replace_debug_id(&mut source_file.contents, previous_debug_id, debug_id)
+        .context(format!("Failed to process {}", source_file.path.display()))?;
+
+    let Some(sourcemap) = sourcemap_url else {
+        return Ok(debug_id);
+    };
 
-                        let source_file = self.sources.get_mut(source_url).unwrap();
-                        let adjustment_map =
-                            inject::fixup_js_file(&mut source_file.contents, debug_id).context(
-                                format!("Failed to process {}", source_file.path.display()),
-                            )?;
+    if let Some(encoded) = sourcemap.url.strip_prefix(DATA_PREAMBLE) {
+        let Ok(mut decoded) = data_encoding::BASE64.decode(encoded.as_bytes()) else {
+            bail!("Invalid embedded sourcemap in source file {source_url}");
+        };
 
-                        sourcemap.adjust_mappings(&adjustment_map);
-                        sourcemap.set_debug_id(Some(debug_id));
+        let mut sourcemap = SourceMap::from_slice(&decoded)
+            .with_context(|| format!("Invalid embedded sourcemap in source file {source_url}"))?;
+        sourcemap.set_debug_id(Some(debug_id));
 
-                        decoded.clear();
-                        sourcemap.to_writer(&mut decoded)?;
+        decoded.clear();
+        sourcemap.to_writer(&mut decoded)?;
 
-                        let encoded = data_encoding::BASE64.encode(&decoded);
-                        let new_sourcemap_url = format!("{DATA_PREAMBLE}{encoded}");
+        let encoded = data_encoding::BASE64.encode(&decoded);
+        let new_sourcemap_url = format!("{DATA_PREAMBLE}{encoded}");
 
-                        inject::replace_sourcemap_url(
-                            &mut source_file.contents,
-                            &new_sourcemap_url,
-                        )?;
-                        *sourcemap_url = Some(SourceMapReference::from_url(new_sourcemap_url));
+        inject::replace_sourcemap_url(&mut source_file.contents, &new_sourcemap_url)?;
+        *sourcemap_url = Some(SourceMapReference::from_url(new_sourcemap_url));
+    } else {
+        let sourcemap_url = resolve_sourcemap_url(source_url, sourcemap, sourcemaps);
+        if let Some(sourcemap_file) = sources.get_mut(&sourcemap_url) {
+            let mut sourcemap = SourceMap::from_slice(&sourcemap_file.contents)
+                .context(format!("Invalid sourcemap at {}", sourcemap_file.url))?;
+            sourcemap.set_debug_id(Some(debug_id));
+
+            write_sourcemap(sourcemap_file, &sourcemap, debug_id, dry_run)?;
+            report
+                .sourcemaps
+                .push((sourcemap_file.path.clone(), debug_id));
+        }
+    }
 
-                        debug_id
-                    } else {
-                        // Handle external sourcemaps
-
-                        let normalized =
-                            inject::normalize_sourcemap_url(source_url, &sourcemap.url);
-                        let matches = inject::find_matching_paths(&sourcemaps, &normalized);
-
-                        let sourcemap_url = match &matches[..] {
-                            [] => normalized,
-                            [x] => x.to_string(),
-                            _ => {
-                                warn!("Ambiguous matches for sourcemap path {normalized}:");
-                                for path in matches {
-                                    warn!("{path}");
-                                }
-                                normalized
-                            }
-                        };
-
-                        if self.sources.contains_key(&sourcemap_url) {
-                            // Case 3: We have an external sourcemap for the source file.
-
-                            // We need to do a bit of a dance here because we can't mutably
-                            // borrow the source file and the sourcemap at the same time.
-                            let (mut sourcemap, debug_id, debug_id_fresh) = {
-                                let sourcemap_file = &self.sources[&sourcemap_url];
-
-                                let sm = SourceMap::from_slice(&sourcemap_file.contents).context(
-                                    format!("Invalid sourcemap at {}", sourcemap_file.url),
-                                )?;
-
-                                match sm.get_debug_id() {
-                                    Some(debug_id) => (sm, debug_id, false),
-                                    None => {
-                                        let debug_id = inject::debug_id_from_bytes_hashed(
-                                            &sourcemap_file.contents,
-                                        );
-                                        (sm, debug_id, true)
-                                    }
-                                }
-                            };
-
-                            let source_file = self.sources.get_mut(source_url).unwrap();
-                            let adjustment_map =
-                                inject::fixup_js_file(&mut source_file.contents, debug_id)
-                                    .context(format!(
-                                        "Failed to process {}",
-                                        source_file.path.display()
-                                    ))?;
-
-                            sourcemap.adjust_mappings(&adjustment_map);
-                            sourcemap.set_debug_id(Some(debug_id));
-
-                            let sourcemap_file = self.sources.get_mut(&sourcemap_url).unwrap();
-                            sourcemap_file.contents.clear();
-                            sourcemap.to_writer(&mut sourcemap_file.contents)?;
-
-                            sourcemap_file.set_debug_id(debug_id.to_string());
-
-                            if !dry_run {
-                                let mut file = std::fs::File::create(&sourcemap_file.path)?;
-                                file.write_all(&sourcemap_file.contents).context(format!(
-                                    "Failed to write sourcemap file {}",
-                                    sourcemap_file.path.display()
-                                ))?;
-                            }
+    Ok(debug_id)
+}
 
-                            if debug_id_fresh {
-                                report
-                                    .sourcemaps
-                                    .push((sourcemap_file.path.clone(), debug_id));
-                            } else {
-                                report
-                                    .skipped_sourcemaps
-                                    .push((sourcemap_file.path.clone(), debug_id));
-                            }
+/// Injects a debug id into the sourcemap of an asset that cannot contain code,
+/// such as a stylesheet.
+///
+/// The asset itself is left untouched, so it is only identified by the debug id
+/// of its sourcemap. Returns `None` if the asset has no external sourcemap that
+/// is available locally.
+fn inject_debug_id_into_sourcemap(
+    sources: &mut SourceFiles,
+    sourcemaps: &[String],
+    source_url: &str,
+    sourcemap_url: &Option<SourceMapReference>,
+    force: bool,
+    dry_run: bool,
+    report: &mut InjectReport,
+) -> Result<Option<DebugId>> {
+    // Embedded sourcemaps cannot be changed without modifying the asset.
+    let Some(sourcemap) = sourcemap_url
+        .as_ref()
+        .filter(|sm| !sm.url.starts_with(DATA_PREAMBLE))
+    else {
+        return Ok(None);
+    };
 
-                            debug_id
-                        } else {
-                            // Case 4: We have a URL for the external sourcemap, but we can't find it.
-                            // This is substantially the same as case 1.
-                            debug!("Sourcemap file {} not found", sourcemap_url);
-                            // source map cannot be found, fall back to hashing the contents.
-                            let source_file = self.sources.get_mut(source_url).unwrap();
-                            let debug_id =
-                                inject::debug_id_from_bytes_hashed(&source_file.contents);
-
-                            // If we don't have a sourcemap, it's not safe to inject the code snippet at the beginning,
-                            // because that would throw off all the mappings. Instead, inject the snippet at the very end.
-                            // This isn't ideal, but it's the best we can do in this case.
-                            inject::fixup_js_file_end(&mut source_file.contents, debug_id)
-                                .context(format!(
-                                    "Failed to process {}",
-                                    source_file.path.display()
-                                ))?;
-
-                            debug_id
-                        }
-                    }
-                }
-            };
+    let source_path = sources[source_url].path.clone();
+    let sourcemap_url = resolve_sourcemap_url(source_url, sourcemap, sourcemaps);
+    let Some(sourcemap_file) = sources.get_mut(&sourcemap_url) else {
+        debug!("Sourcemap file {} not found", sourcemap_url);
+        return Ok(None);
+    };
 
-            // Finally, some housekeeping.
-            let source_file = self.sources.get_mut(source_url).unwrap();
+    let mut sourcemap = SourceMap::from_slice(&sourcemap_file.contents)
+        .context(format!("Invalid sourcemap at {}", sourcemap_file.url))?;
 
-            source_file.set_debug_id(debug_id.to_string());
-            self.debug_ids.insert(source_url.clone(), debug_id);
+    if let (Some(debug_id), false) = (sourcemap.get_debug_id(), force) {
+        report.previously_injected.push((source_path, debug_id));
+        report
+            .skipped_sourcemaps
+            .push((sourcemap_file.path.clone(), debug_id));
+        return Ok(Some(debug_id));
+    }
 
-            if !dry_run {
-                let mut file = std::fs::File::create(&source_file.path)?;
-                file.write_all(&source_file.contents).context(format!(
-                    "Failed to write source file {}",
-                    source_file.path.display()
-                ))?;
-            }
+    let debug_id = inject::debug_id_from_bytes_hashed(&sourcemap_file.contents);
+    sourcemap.set_debug_id(Some(debug_id));
+    write_sourcemap(sourcemap_file, &sourcemap, debug_id, dry_run)?;
 
-            report.injected.push((source_file.path.clone(), debug_id));
-        }
+    report.assets.push((source_path, debug_id));
+    report
+        .sourcemaps
+        .push((sourcemap_file.path.clone(), debug_id));
 
-        if !report.is_empty() {
-            println!("{report}");
-        } else {
-            println!("> Nothing to inject")
-        }
+    Ok(Some(debug_id))
+}
 
-        Ok(())
+/// Serializes a modified sourcemap into its source file, writing it to disk unless `dry_run` is set.
+fn write_sourcemap(
+    sourcemap_file: &mut SourceFile,
+    sourcemap: &SourceMap,
+    debug_id: DebugId,
+    dry_run: bool,
+) -> Result<()> {
+    sourcemap_file.contents.clear();
+    sourcemap.to_writer(&mut sourcemap_file.contents)?;
+
+    sourcemap_file.set_debug_id(debug_id.to_string());
+
+    if !dry_run {
+        let mut file = std::fs::File::create(&sourcemap_file.path)?;
+        file.write_all(&sourcemap_file.contents).context(format!(
+            "Failed to write sourcemap file {}",
+            sourcemap_file.path.display()
+        ))?;
     }
+
+    Ok(())
 }
 
 fn validate_script(source: &mut SourceFile) -> Result<()> {
@@ -1158,6 +1867,212 @@ fn validate_sourcemap(source_urls: &HashSet<String>, source: &mut SourceFile) ->
     Ok(())
 }
 
+/// Checks the `sourceMappingURL` of a minified file, returning a description
+/// of the problem if it does not resolve.
+///
+/// Embedded source maps and absolute http(s) URLs are accepted as is, other
+/// references must resolve to a source map among `sourcemaps`.
+fn check_sourcemap_reference(
+    source: &SourceFile,
+    sources: &SourceFiles,
+    sourcemaps: &[String],
+) -> Option<String> {
+    // Hermes bytecode bundles are flagged as empty minified sources
+    let contents = str::from_utf8(&source.contents).ok()?;
+    let location = discover_sourcemaps_location(contents)?;
+
+    if location.starts_with("data:") {
+        return None;
+    }
+    if let Ok(url) = Url::parse(location) {
+        if url.scheme() == "http" || url.scheme() == "https" {
+            return None;
+        }
+    }
+
+    let path = location.split(['?', '#']).next().unwrap_or_default();
+    let target = match join_url(&source.url, path) {
+        Ok(target) => target,
+        Err(err) => return Some(format!("cannot resolve sourceMappingURL {location}: {err}")),
+    };
+
+    let resolved = match sources.get(&target) {
+        Some(sourcemap) => sourcemap.ty == SourceFileType::SourceMap,
+        None => !inject::find_matching_paths(sourcemaps, &target).is_empty(),
+    };
+
+    (!resolved).then(|| format!("sourceMappingURL {location} points to missing file {target}"))
+}
+
+/// Checks the `file` and `sources` references of a source map, returning a
+/// description of every problem.
+fn check_sourcemap_file_references(source: &SourceFile) -> Vec<String> {
+    let decoded = match sourcemap::decode_slice(&source.contents) {
+        Ok(decoded) => decoded,
+        Err(err) => return vec![format!("cannot parse source map: {err}")],
+    };
+    let sm: &sourcemap::SourceMap = match decoded {
+        sourcemap::DecodedMap::Regular(ref sm) => sm,
+        sourcemap::DecodedMap::Hermes(ref smh) => smh,
+        sourcemap::DecodedMap::Index(_) => return vec![],
+    };
+
+    let mut problems = vec![];
+    if sm.get_file() == Some("") {
+        problems.push("the file reference is empty".to_owned());
+    }
+    for (idx, name) in sm.sources().enumerate() {
+        if name.is_empty() {
+            problems.push(format!("the source reference at index {idx} is empty"));
+        }
+    }
+    problems
+}
+
+/// Returns the last path segment of a URL.
+fn url_basename(url: &str) -> &str {
+    url.rsplit(['/', '\\']).next().unwrap_or(url)
+}
+
+/// Returns the byte offset of the given UTF-16 column within a line, or `None`
+/// if the line is shorter than that.
+fn column_offset(line: &str, column: u32) -> Option<usize> {
+    let mut remaining = column as usize;
+    for (offset, c) in line.char_indices() {
+        if remaining == 0 {
+            return Some(offset);
+        }
+        remaining = remaining.checked_sub(c.len_utf16())?;
+    }
+    (remaining == 0).then_some(line.len())
+}
+
+/// Checks that a source map belongs to the bundle it is uploaded with, to keep
+/// mismatched build artifacts from being uploaded. Returns a description of
+/// every mismatch found.
+///
+/// Hermes bytecode bundles cannot be compared with the mappings, so only the
+/// `file` of their source map is checked.
+pub fn validate_bundle_sourcemap(
+    bundle_url: &str,
+    bundle: &[u8],
+    sourcemap: &[u8],
+) -> Result<Vec<String>> {
+    let sm = match sourcemap::decode_slice(sourcemap)? {
+        sourcemap::DecodedMap::Regular(sm) => sm,
+        sourcemap::DecodedMap::Hermes(smh) => (*smh).clone(),
+        sourcemap::DecodedMap::Index(smi) => smi.flatten()?,
+    };
+
+    let minified = if is_hermes_bytecode(bundle) {
+        None
+    } else {
+        Some(String::from_utf8_lossy(bundle))
+    };
+    Ok(validate_sourcemap_pairing(
+        bundle_url,
+        minified.as_deref(),
+        &sm,
+        ValidationLevel::Basic,
+    ))
+}
+
+/// Checks that a source map corresponds to the minified file which references
+/// it, to detect stale build artifacts. Returns a description of every
+/// mismatch found.
+///
+/// The mappings are only checked against the minified file if its contents are
+/// given. With [`ValidationLevel::Strict`], a sample of mappings is additionally
+/// checked to point to their names in the embedded sources.
+fn validate_sourcemap_pairing(
+    minified_url: &str,
+    minified: Option<&str>,
+    sm: &SourceMap,
+    level: ValidationLevel,
+) -> Vec<String> {
+    let mut findings = vec![];
+
+    if let Some(file) = sm.get_file() {
+        if url_basename(file) != url_basename(minified_url) {
+            findings.push(format!(
+                "the source map is for `{file}`, but it is referenced by {minified_url}"
+            ));
+        }
+    }
+
+    let last = minified.and_then(|minified| {
+        let last = sm.get_token((sm.get_token_count() as usize).saturating_sub(1))?;
+        Some((minified.lines().collect::<Vec<_>>(), last))
+    });
+    if let Some((lines, last)) = last {
+        let (line, column) = (last.get_dst_line(), last.get_dst_col());
+        match lines.get(line as usize) {
+            None => findings.push(format!(
+                "the last mapping points to line {}, but {minified_url} only has {} lines",
+                line + 1,
+                lines.len()
+            )),
+            Some(text) if column_offset(text, column).is_none() => findings.push(format!(
+                "the last mapping points to line {}, column {}, but that line of \
+                 {minified_url} only has {} columns",
+                line + 1,
+                column + 1,
+                text.encode_utf16().count()
+            )),
+            Some(_) => {}
+        }
+    }
+
+    if level == ValidationLevel::Strict {
+        let candidates: Vec<_> = sm
+            .tokens()
+            .filter(|token| {
+                token.has_source() && sm.get_source_contents(token.get_src_id()).is_some()
+            })
+            .collect();
+        let step = (candidates.len() / MAX_SAMPLED_MAPPINGS).max(1);
+
+        let mut sampled = 0;
+        let mut mismatches = vec![];
+        for token in candidates.iter().step_by(step) {
+            sampled += 1;
+            let contents = sm
+                .get_source_contents(token.get_src_id())
+                .unwrap_or_default();
+            let matches = contents
+                .lines()
+                .nth(token.get_src_line() as usize)
+                .and_then(|line| Some(&line[column_offset(line, token.get_src_col())?..]))
+                .map_or(false, |text| {
+                    token.get_name().map_or(true, |name| text.starts_with(name))
+                });
+            if !matches {
+                mismatches.push(token);
+            }
+        }
+
+        if let Some(example) = mismatches.first() {
+            let position = format!(
+                "{}:{}:{}",
+                example.get_source().unwrap_or("??"),
+                example.get_src_line() + 1,
+                example.get_src_col() + 1
+            );
+            let example = match example.get_name() {
+                Some(name) => format!("`{name}` expected at {position}"),
+                None => format!("no source text at {position}"),
+            };
+            findings.push(format!(
+                "{} of {} sampled mappings do not match the embedded sources, e.g. {example}",
+                mismatches.len(),
+                sampled,
+            ));
+        }
+    }
+
+    findings
+}
+
 impl Default for SourceMapProcessor {
     fn default() -> Self {
         SourceMapProcessor::new()
@@ -1168,6 +2083,68 @@ impl Default for SourceMapProcessor {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_common_path_prefix() {
+        let prefix = |paths: &[&str]| common_path_prefix(paths.iter().map(Path::new));
+
+        assert_eq!(prefix(&[]), None);
+        assert_eq!(
+            prefix(&["/dist/assets/app.js"]),
+            Some(PathBuf::from("/dist/assets"))
+        );
+        assert_eq!(
+            prefix(&["/dist/assets/app.js", "/dist/assets/app.js.map"]),
+            Some(PathBuf::from("/dist/assets"))
+        );
+        assert_eq!(
+            prefix(&["/dist/assets/app.js", "/dist/assets/chunks/vendor.js"]),
+            Some(PathBuf::from("/dist/assets"))
+        );
+        assert_eq!(
+            prefix(&["/dist/assets-old/app.js", "/dist/assets/app.js"]),
+            Some(PathBuf::from("/dist"))
+        );
+        assert_eq!(
+            prefix(&["/dist/app.js", "/other/app.js"]),
+            Some(PathBuf::from("/"))
+        );
+        assert_eq!(prefix(&["dist/app.js", "other/app.js"]), None);
+        assert_eq!(prefix(&["/dist/app.js", "dist/app.js"]), None);
+    }
+
+    #[test]
+    fn test_group_by_dist_from_path() {
+        let file = |path: &str| {
+            (
+                PathBuf::from(Path::new(path).file_name().unwrap()),
+                ReleaseFileMatch {
+                    base_path: PathBuf::from("outputs"),
+                    path: PathBuf::from(path),
+                    contents: vec![],
+                    decompressed: false,
+                },
+            )
+        };
+        let pattern = Regex::new("outputs/([^/]+)/").unwrap();
+        let files = || {
+            vec![
+                file("outputs/arm64-v8a/index.bundle"),
+                file("outputs/x86_64/index.bundle"),
+                file("outputs/arm64-v8a/index.bundle.map"),
+                file("index.bundle"),
+            ]
+        };
+
+        assert!(group_by_dist_from_path(files(), &pattern, None).is_err());
+
+        let groups = group_by_dist_from_path(files(), &pattern, Some("universal")).unwrap();
+        let dists: Vec<_> = groups
+            .iter()
+            .map(|(dist, files)| (dist.as_str(), files.len()))
+            .collect();
+        assert_eq!(dists, [("arm64-v8a", 2), ("universal", 1), ("x86_64", 1)]);
+    }
+
     #[test]
     fn test_split_url() {
         assert_eq!(split_url("/foo.js"), (Some(""), "foo", Some("js")));
@@ -1220,4 +2197,80 @@ mod tests {
         assert!(!url_matches_extension("js", &["js"][..]));
         assert!(url_matches_extension("foo.test.js", &["test.js"][..]));
     }
+
+    fn validate_fixture(name: &str, level: ValidationLevel) -> Vec<String> {
+        let dir = Path::new("tests/integration/_fixtures/validate_sourcemaps");
+        let minified = std::fs::read_to_string(dir.join(format!("{name}.min.js"))).unwrap();
+        let sm =
+            SourceMap::from_slice(&std::fs::read(dir.join(format!("{name}.min.js.map"))).unwrap())
+                .unwrap();
+        validate_sourcemap_pairing(&format!("~/{name}.min.js"), Some(&minified), &sm, level)
+    }
+
+    #[test]
+    fn test_validate_sourcemap_pairing_valid() {
+        assert!(validate_fixture("valid", ValidationLevel::Basic).is_empty());
+        assert!(validate_fixture("valid", ValidationLevel::Strict).is_empty());
+    }
+
+    #[test]
+    fn test_validate_sourcemap_pairing_file_mismatch() {
+        assert_eq!(
+            validate_fixture("renamed", ValidationLevel::Basic),
+            ["the source map is for `app.min.js`, but it is referenced by ~/renamed.min.js"]
+        );
+    }
+
+    #[test]
+    fn test_validate_sourcemap_pairing_line_out_of_bounds() {
+        assert_eq!(
+            validate_fixture("stale", ValidationLevel::Basic),
+            ["the last mapping points to line 6, but ~/stale.min.js only has 2 lines"]
+        );
+    }
+
+    #[test]
+    fn test_validate_sourcemap_pairing_column_out_of_bounds() {
+        let sm = SourceMap::from_slice(
+            &std::fs::read("tests/integration/_fixtures/validate_sourcemaps/valid.min.js.map")
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            validate_sourcemap_pairing(
+                "~/valid.min.js",
+                Some("function greet(n){}"),
+                &sm,
+                ValidationLevel::Basic
+            ),
+            [
+                "the last mapping points to line 1, column 36, but that line of \
+                 ~/valid.min.js only has 19 columns"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_sourcemap_pairing_outdated_sources() {
+        // The mappings look fine unless they are checked against the sources.
+        assert!(validate_fixture("outdated-sources", ValidationLevel::Basic).is_empty());
+        assert_eq!(
+            validate_fixture("outdated-sources", ValidationLevel::Strict),
+            [
+                "4 of 6 sampled mappings do not match the embedded sources, \
+                 e.g. `greet` expected at ../src/app.js:1:10"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_offset() {
+        assert_eq!(column_offset("abc", 0), Some(0));
+        assert_eq!(column_offset("abc", 3), Some(3));
+        assert_eq!(column_offset("abc", 4), None);
+        // `😀` takes two UTF-16 code units and four bytes
+        assert_eq!(column_offset("😀a", 2), Some(4));
+        assert_eq!(column_offset("😀a", 1), None);
+    }
 }
@@ -0,0 +1,344 @@
+//! Looks up minified files and their source maps in local artifact bundles,
+//! the same way the server does when processing a JavaScript event.
+
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+
+use anyhow::{bail, format_err, Context, Result};
+use sentry::types::DebugId;
+use serde::Deserialize;
+use sourcemap::{DecodedMap, Token};
+use symbolic::debuginfo::sourcebundle::{
+    SourceBundle, SourceBundleDebugSession, SourceFileDescriptor, SourceFileType,
+};
+use url::Url;
+use zip::ZipArchive;
+
+use crate::utils::upload_metadata::UploadMetadata;
+
+/// A minified file and its source map, resolved from an artifact bundle.
+pub struct BundledSourceMap {
+    pub minified_url: String,
+    pub minified_source: String,
+    pub sourcemap_url: String,
+    pub sourcemap: DecodedMap,
+}
+
+impl BundledSourceMap {
+    /// Looks up the token nearest to the given zero indexed position in the
+    /// minified file.
+    ///
+    /// Fails if the position lies outside of the minified file.
+    pub fn lookup_token(&self, line: u32, column: u32) -> Result<Option<Token<'_>>> {
+        let Some(src_line) = self.minified_source.lines().nth(line as usize) else {
+            bail!(
+                "Line {} is past the end of the minified file {}",
+                line + 1,
+                self.minified_url
+            );
+        };
+        let line_len = src_line.chars().count();
+        if column as usize >= line_len {
+            bail!(
+                "Column {} is past the end of line {} ({} characters) in the minified file {}",
+                column + 1,
+                line + 1,
+                line_len,
+                self.minified_url
+            );
+        }
+
+        Ok(self.sourcemap.lookup_token(line, column))
+    }
+}
+
+/// Returns the URLs under which a file referenced by `url` may be stored in a
+/// bundle, in the order they are tried.
+///
+/// Like on the server, the scheme and host of an absolute URL are replaced
+/// with `~`, first keeping and then dropping the query string.
+fn url_candidates(url: &str) -> Vec<String> {
+    let mut candidates = vec![url.to_owned()];
+
+    if url.starts_with("~/") {
+        return candidates;
+    }
+
+    if let Ok(parsed) = Url::parse(url) {
+        if parsed.has_host() {
+            if let Some(query) = parsed.query() {
+                candidates.push(format!("~{}?{}", parsed.path(), query));
+            }
+            candidates.push(format!("~{}", parsed.path()));
+        }
+    } else if url.starts_with('/') {
+        candidates.push(format!("~{url}"));
+    }
+
+    candidates.dedup();
+    candidates
+}
+
+/// Resolves `reference` relative to the URL of the file that contains it.
+fn join_url(base: &str, reference: &str) -> Result<String> {
+    if let Some(rest) = base.strip_prefix("~/") {
+        let joined = Url::parse("http://~/")?.join(rest)?.join(reference)?;
+        if joined.host_str() == Some("~") {
+            let mut url = format!("~{}", joined.path());
+            if let Some(query) = joined.query() {
+                url = format!("{url}?{query}");
+            }
+            return Ok(url);
+        }
+        return Ok(joined.to_string());
+    }
+
+    Ok(Url::parse(base)?.join(reference)?.to_string())
+}
+
+fn find_by_url<'a>(
+    session: &'a SourceBundleDebugSession<'_>,
+    url: &str,
+    ty: SourceFileType,
+) -> Result<Option<SourceFileDescriptor<'a>>> {
+    for candidate in url_candidates(url) {
+        if let Some(file) = session.source_by_url(&candidate)? {
+            if file.ty() == ty {
+                return Ok(Some(file));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Finds the minified file referenced by `reference`, which is either its
+/// URL or its debug ID, and its source map in the given artifact bundle.
+pub fn resolve_from_bundle(data: &[u8], reference: &str) -> Result<BundledSourceMap> {
+    let bundle = SourceBundle::parse(data)
+        .map_err(|e| format_err!("Could not read artifact bundle: {}", e))?;
+    let session = bundle.debug_session()?;
+
+    let minified = match reference.parse::<DebugId>() {
+        Ok(debug_id) => session.source_by_debug_id(debug_id, SourceFileType::MinifiedSource)?,
+        Err(_) => find_by_url(&session, reference, SourceFileType::MinifiedSource)?,
+    }
+    .ok_or_else(|| format_err!("Minified file {} not found in artifact bundle", reference))?;
+
+    let minified_url = minified.url().unwrap_or(reference).to_owned();
+    let minified_source = minified
+        .contents()
+        .ok_or_else(|| format_err!("Minified file {} has no contents", minified_url))?
+        .to_owned();
+
+    let by_debug_id = match minified.debug_id() {
+        Some(debug_id) => session.source_by_debug_id(debug_id, SourceFileType::SourceMap)?,
+        None => None,
+    };
+    let sourcemap = match by_debug_id {
+        Some(sourcemap) => sourcemap,
+        None => {
+            let Some(sourcemap_url) = minified.source_mapping_url() else {
+                bail!(
+                    "Minified file {} has neither a debug ID nor a sourceMappingURL",
+                    minified_url
+                );
+            };
+            let sourcemap_url = join_url(&minified_url, sourcemap_url)?;
+            find_by_url(&session, &sourcemap_url, SourceFileType::SourceMap)?.ok_or_else(|| {
+                format_err!("Source map {} not found in artifact bundle", sourcemap_url)
+            })?
+        }
+    };
+
+    let sourcemap_url = sourcemap.url().unwrap_or_default().to_owned();
+    let contents = sourcemap
+        .contents()
+        .ok_or_else(|| format_err!("Source map {} has no contents", sourcemap_url))?;
+
+    Ok(BundledSourceMap {
+        minified_url,
+        minified_source,
+        sourcemap_url,
+        sourcemap: sourcemap::decode_slice(contents.as_bytes())?,
+    })
+}
+
+/// The manifest of an artifact bundle, as far as it is needed for [`BundleInfo`].
+#[derive(Deserialize)]
+struct BundleManifest {
+    #[serde(default)]
+    files: BTreeMap<String, serde_json::Value>,
+    #[serde(flatten)]
+    attributes: BTreeMap<String, serde_json::Value>,
+}
+
+/// Information on a local artifact bundle, as shown by `sourcemaps bundle-info`.
+pub struct BundleInfo {
+    pub debug_id: DebugId,
+    pub files: usize,
+    pub org: Option<String>,
+    pub project: Option<String>,
+    pub release: Option<String>,
+    pub dist: Option<String>,
+    pub metadata: UploadMetadata,
+}
+
+/// Reads the manifest of the given artifact bundle.
+pub fn read_bundle_info(data: &[u8]) -> Result<BundleInfo> {
+    let bundle = SourceBundle::parse(data)
+        .map_err(|e| format_err!("Could not read artifact bundle: {}", e))?;
+
+    let mut archive = ZipArchive::new(Cursor::new(data))?;
+    let mut manifest = String::new();
+    archive
+        .by_name("manifest.json")?
+        .read_to_string(&mut manifest)?;
+    let manifest: BundleManifest = serde_json::from_str(&manifest)
+        .context("Could not read the manifest of the artifact bundle")?;
+
+    // Attributes are strings, other entries like source links are skipped
+    let attributes: BTreeMap<_, _> = manifest
+        .attributes
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::String(value) => Some((key, value)),
+            _ => None,
+        })
+        .collect();
+
+    let attribute = |key: &str| attributes.get(key).cloned();
+    Ok(BundleInfo {
+        debug_id: bundle.debug_id(),
+        files: manifest.files.len(),
+        org: attribute("org"),
+        project: attribute("project"),
+        release: attribute("release"),
+        dist: attribute("dist"),
+        metadata: UploadMetadata::from_attributes(&attributes),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use symbolic::debuginfo::sourcebundle::{SourceBundleWriter, SourceFileInfo};
+
+    use super::*;
+
+    const MINIFIED: &str =
+        include_str!("../../../tests/integration/_fixtures/resolve_bundle/app.min.js");
+    const SOURCEMAP: &str =
+        include_str!("../../../tests/integration/_fixtures/resolve_bundle/app.min.js.map");
+
+    fn build_bundle() -> Vec<u8> {
+        build_bundle_with_attributes(&[])
+    }
+
+    fn build_bundle_with_attributes(attributes: &[(&str, &str)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut writer = SourceBundleWriter::start(Cursor::new(&mut data)).unwrap();
+        for (key, value) in attributes {
+            writer.set_attribute(*key, *value);
+        }
+        for (path, url, ty, contents) in [
+            (
+                "_/_/static/app.min.js",
+                "~/static/app.min.js",
+                SourceFileType::MinifiedSource,
+                MINIFIED,
+            ),
+            (
+                "_/_/static/app.min.js.map",
+                "~/static/app.min.js.map",
+                SourceFileType::SourceMap,
+                SOURCEMAP,
+            ),
+        ] {
+            let mut info = SourceFileInfo::new();
+            info.set_ty(ty);
+            info.set_url(url.to_owned());
+            writer.add_file(path, contents.as_bytes(), info).unwrap();
+        }
+        writer.finish().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_url_candidates() {
+        assert_eq!(
+            url_candidates("https://example.com/static/app.min.js?v=1"),
+            vec![
+                "https://example.com/static/app.min.js?v=1",
+                "~/static/app.min.js?v=1",
+                "~/static/app.min.js",
+            ]
+        );
+        assert_eq!(
+            url_candidates("/static/app.min.js"),
+            vec!["/static/app.min.js", "~/static/app.min.js"]
+        );
+        assert_eq!(url_candidates("~/app.min.js"), vec!["~/app.min.js"]);
+    }
+
+    #[test]
+    fn test_resolve_from_bundle() {
+        let data = build_bundle();
+        let resolved = resolve_from_bundle(&data, "https://example.com/static/app.min.js").unwrap();
+        assert_eq!(resolved.minified_url, "~/static/app.min.js");
+        assert_eq!(resolved.sourcemap_url, "~/static/app.min.js.map");
+
+        let token = resolved.lookup_token(0, 71).unwrap().unwrap();
+        assert_eq!(token.get_source(), Some("../src/app.js"));
+        assert_eq!((token.get_src_line(), token.get_src_col()), (5, 18));
+        assert!(token.get_source_view().is_some());
+    }
+
+    #[test]
+    fn test_resolve_from_bundle_url_miss() {
+        let data = build_bundle();
+        let err = resolve_from_bundle(&data, "https://example.com/other.min.js")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Minified file https://example.com/other.min.js not found in artifact bundle"
+        );
+    }
+
+    #[test]
+    fn test_lookup_token_past_end_of_line() {
+        let data = build_bundle();
+        let resolved = resolve_from_bundle(&data, "~/static/app.min.js").unwrap();
+        let err = resolved.lookup_token(0, 200).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Column 201 is past the end of line 1 (76 characters) in the minified file \
+             ~/static/app.min.js"
+        );
+        assert!(resolved.lookup_token(5, 0).is_err());
+    }
+
+    #[test]
+    fn test_read_bundle_info() {
+        let data = build_bundle_with_attributes(&[
+            ("debug_id", "2f3c6a8e-1b2d-4e5f-8a9b-0c1d2e3f4a5b"),
+            ("org", "wat-org"),
+            ("release", "1.0.0"),
+            ("note", "pipeline 4812"),
+            ("meta.build", "4812"),
+        ]);
+        let info = read_bundle_info(&data).unwrap();
+        assert_eq!(
+            info.debug_id.to_string(),
+            "2f3c6a8e-1b2d-4e5f-8a9b-0c1d2e3f4a5b"
+        );
+        assert_eq!(info.files, 2);
+        assert_eq!(info.org.as_deref(), Some("wat-org"));
+        assert_eq!(info.project, None);
+        assert_eq!(info.release.as_deref(), Some("1.0.0"));
+        assert_eq!(info.metadata.note.as_deref(), Some("pipeline 4812"));
+        assert_eq!(
+            info.metadata.meta,
+            [("build".to_owned(), "4812".to_owned())].into()
+        );
+    }
+}
@@ -7,7 +7,7 @@ use std::fmt;
 use std::io::{BufRead, Write};
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use lazy_static::lazy_static;
 
 use magic_string::{GenerateDecodedMapOptions, MagicString};
@@ -18,6 +18,12 @@ const CODE_SNIPPET_TEMPLATE: &str = r#"!function(){try{var e="undefined"!=typeof
 const DEBUGID_PLACEHOLDER: &str = "__SENTRY_DEBUG_ID__";
 const DEBUGID_COMMENT_PREFIX: &str = "//# debugId";
 
+/// Extensions of assets that cannot contain code, such as stylesheets.
+///
+/// These assets are never modified. Instead, their debug ids are only injected
+/// into their sourcemaps.
+pub const MAP_ONLY_EXTENSIONS: &[&str] = &["css"];
+
 lazy_static! {
     // A regex that captures
     // 1. an optional initial hashbang,
@@ -50,7 +56,9 @@ pub struct InjectReport {
     pub injected: Vec<(PathBuf, DebugId)>,
     pub previously_injected: Vec<(PathBuf, DebugId)>,
     pub sourcemaps: Vec<(PathBuf, DebugId)>,
+    pub assets: Vec<(PathBuf, DebugId)>,
     pub skipped_sourcemaps: Vec<(PathBuf, DebugId)>,
+    pub failed: Vec<(PathBuf, String)>,
 }
 
 impl InjectReport {
@@ -58,7 +66,9 @@ impl InjectReport {
         self.injected.is_empty()
             && self.previously_injected.is_empty()
             && self.sourcemaps.is_empty()
+            && self.assets.is_empty()
             && self.skipped_sourcemaps.is_empty()
+            && self.failed.is_empty()
     }
 }
 
@@ -86,6 +96,14 @@ impl fmt::Display for InjectReport {
             )?;
         }
 
+        if !self.assets.is_empty() {
+            print_section_with_debugid(
+                f,
+                "Unchanged: The following assets are identified by the debug ids of their sourcemaps",
+                &self.assets,
+            )?;
+        }
+
         if !self.previously_injected.is_empty() {
             print_section_with_debugid(
                 f,
@@ -102,6 +120,16 @@ impl fmt::Display for InjectReport {
             )?;
         }
 
+        if !self.failed.is_empty() {
+            print_section_title(
+                f,
+                "Failed: The following source files could not be processed",
+            )?;
+            for (path, error) in self.failed.iter().sorted_by_key(|x| &x.0) {
+                writeln!(f, "    {} - {error}", path.display())?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -193,6 +221,22 @@ pub fn fixup_js_file_end(js_contents: &mut Vec<u8>, debug_id: DebugId) -> Result
     Ok(())
 }
 
+/// Replaces the debug id previously injected into a JS source file with a new one.
+///
+/// Both the code snippet and the `//# debugId=` comment are updated in place. Since
+/// all debug ids have the same length, this does not affect any mappings.
+pub fn replace_debug_id(js_contents: &mut Vec<u8>, old: DebugId, new: DebugId) -> Result<()> {
+    let contents = std::str::from_utf8(js_contents)?;
+    let old = old.to_string();
+
+    if !contents.contains(&old) {
+        bail!("Debug id {old} not found in source file");
+    }
+
+    *js_contents = contents.replace(&old, &new.to_string()).into_bytes();
+    Ok(())
+}
+
 /// Replaces a JS file's source mapping url with a new one.
 ///
 /// Only the bottommost source mapping url comment will be updated. If there
@@ -221,6 +265,21 @@ pub fn replace_sourcemap_url(js_contents: &mut Vec<u8>, new_url: &str) -> Result
     Ok(())
 }
 
+/// Returns the sourcemap url referenced by a CSS file.
+///
+/// CSS files reference their sourcemaps with a `/*# sourceMappingURL=<url> */`
+/// comment, the last one of which is used.
+pub fn discover_css_sourcemap_location(css_contents: &str) -> Option<&str> {
+    css_contents.lines().rev().find_map(|line| {
+        let url = line
+            .trim()
+            .strip_prefix("/*# sourceMappingURL=")?
+            .strip_suffix("*/")?
+            .trim();
+        (!url.is_empty()).then_some(url)
+    })
+}
+
 /// Generates a debug ID from bytes.
 pub fn debug_id_from_bytes_hashed(bytes: &[u8]) -> DebugId {
     let mut hash = sha1_smol::Sha1::new();
@@ -309,6 +368,7 @@ pub fn find_matching_paths(candidate_paths: &[String], expected_path: &str) -> V
 #[cfg(test)]
 mod tests {
     use sentry::types::DebugId;
+    use symbolic::debuginfo::js::discover_debug_id;
 
     use crate::utils::fs::TempFile;
 
@@ -479,6 +539,45 @@ something else
         assert_eq!(std::str::from_utf8(&source).unwrap(), expected);
     }
 
+    #[test]
+    fn test_replace_debug_id() {
+        let old = DebugId::default();
+        let new: DebugId = "2b8da676-53a3-4007-b0ec-2f4a6fe634c2".parse().unwrap();
+
+        let mut source = Vec::from("some line\n");
+        fixup_js_file_end(&mut source, old).unwrap();
+        let expected = String::from_utf8(source.clone())
+            .unwrap()
+            .replace(&old.to_string(), &new.to_string());
+
+        replace_debug_id(&mut source, old, new).unwrap();
+        assert_eq!(std::str::from_utf8(&source).unwrap(), expected);
+        assert_eq!(
+            discover_debug_id(std::str::from_utf8(&source).unwrap()),
+            Some(new)
+        );
+
+        assert!(replace_debug_id(&mut source, old, new).is_err());
+    }
+
+    #[test]
+    fn test_discover_css_sourcemap_location() {
+        let css_contents = r#"/*# sourceMappingURL=not-this-one.css.map */
+body{color:red}
+/*# sourceMappingURL=styles.css.map */
+"#;
+        assert_eq!(
+            discover_css_sourcemap_location(css_contents),
+            Some("styles.css.map")
+        );
+
+        assert_eq!(discover_css_sourcemap_location("body{color:red}\n"), None);
+        assert_eq!(
+            discover_css_sourcemap_location("/*# sourceMappingURL= */\n"),
+            None
+        );
+    }
+
     #[test]
     fn test_normalize_sourcemap_url() {
         assert_eq!(
@@ -0,0 +1,346 @@
+//! The state of previous uploads, as recorded with `sourcemaps upload --state-file`.
+//!
+//! The state file records the checksum and debug id of every file of the last
+//! successful upload, keyed by the file's dist and URL. Files that are unchanged
+//! since then are skipped without bundling them again. The state is only used for
+//! the organization, project and release it was recorded for.
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use console::style;
+use log::warn;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::utils::file_upload::{SourceFile, UploadContext};
+use crate::utils::fs::write_atomically;
+use crate::utils::logging::quiet_println;
+
+/// The contents of a state file.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct RecordedState {
+    org: String,
+    project: Option<String>,
+    release: Option<String>,
+    #[serde(
+        serialize_with = "serialize_files",
+        deserialize_with = "deserialize_files"
+    )]
+    files: BTreeMap<FileKey, RecordedFile>,
+}
+
+/// The dist and URL of a file, as the same URL may be uploaded for several dists.
+type FileKey = (Option<String>, String);
+
+/// A file of the last upload.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct RecordedFile {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dist: Option<String>,
+    checksum: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    debug_id: Option<String>,
+}
+
+impl RecordedFile {
+    fn key(&self) -> FileKey {
+        (self.dist.clone(), self.url.clone())
+    }
+}
+
+/// The files are stored as a list, as JSON objects can only be keyed by strings.
+fn serialize_files<S>(
+    files: &BTreeMap<FileKey, RecordedFile>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(files.values())
+}
+
+fn deserialize_files<'de, D>(deserializer: D) -> Result<BTreeMap<FileKey, RecordedFile>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let files = Vec::<RecordedFile>::deserialize(deserializer)?;
+    Ok(files.into_iter().map(|file| (file.key(), file)).collect())
+}
+
+impl RecordedState {
+    fn new(context: &UploadContext<'_>) -> Self {
+        RecordedState {
+            org: context.org.to_owned(),
+            project: context.project.map(str::to_owned),
+            release: context.release.map(str::to_owned),
+            files: BTreeMap::new(),
+        }
+    }
+
+    fn matches(&self, context: &UploadContext<'_>) -> bool {
+        self.org == context.org
+            && self.project.as_deref() == context.project
+            && self.release.as_deref() == context.release
+    }
+}
+
+/// The upload state of a single run.
+///
+/// Files are checked against the state of the previous run, while the files of
+/// this run are recorded separately, so that files no longer uploaded are dropped
+/// from the state file once it is saved.
+pub struct UploadState {
+    path: PathBuf,
+    previous: RecordedState,
+    current: RecordedState,
+}
+
+impl UploadState {
+    /// Loads the state file at `path` for an upload with the given context.
+    ///
+    /// A missing state file is treated as empty, as is the state file if `reset`
+    /// is set. An invalid state file, or one recorded for a different organization,
+    /// project or release is ignored.
+    pub fn load(path: &Path, reset: bool, context: &UploadContext<'_>) -> Result<Self> {
+        let previous = if reset {
+            RecordedState::new(context)
+        } else {
+            match fs::read(path) {
+                Ok(contents) => parse_state(path, &contents, context),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => RecordedState::new(context),
+                Err(err) => {
+                    return Err(err).with_context(|| {
+                        format!("Failed to read upload state file {}", path.display())
+                    })
+                }
+            }
+        };
+
+        Ok(UploadState {
+            path: path.to_owned(),
+            previous,
+            current: RecordedState::new(context),
+        })
+    }
+
+    /// Returns whether the file was uploaded for the same dist by the previous run
+    /// with the same contents and debug id.
+    pub fn is_unchanged(&self, source: &SourceFile, dist: Option<&str>) -> bool {
+        let key = (dist.map(str::to_owned), source.url.clone());
+        let Some(recorded) = self.previous.files.get(&key) else {
+            return false;
+        };
+        let Ok(checksum) = source.checksum() else {
+            return false;
+        };
+
+        recorded.checksum == checksum.to_string() && recorded.debug_id.as_ref() == source.debug_id()
+    }
+
+    /// Records a file as uploaded by this run.
+    pub fn record(&mut self, source: &SourceFile, dist: Option<&str>) {
+        let Ok(checksum) = source.checksum() else {
+            return;
+        };
+
+        let file = RecordedFile {
+            url: source.url.clone(),
+            dist: dist.map(str::to_owned),
+            checksum: checksum.to_string(),
+            debug_id: source.debug_id().cloned(),
+        };
+        self.current.files.insert(file.key(), file);
+    }
+
+    /// Writes the files recorded by this run to the state file, see `write_atomically`.
+    pub fn save(&self) -> Result<()> {
+        let json = serde_json::to_vec_pretty(&self.current)?;
+        let mut options = OpenOptions::new();
+        options.write(true).truncate(true).create(true);
+
+        write_atomically(&self.path, &options, |file| file.write_all(&json))
+            .with_context(|| format!("Failed to write upload state file {}", self.path.display()))
+    }
+}
+
+fn parse_state(path: &Path, contents: &[u8], context: &UploadContext<'_>) -> RecordedState {
+    let state: RecordedState = match serde_json::from_slice(contents) {
+        Ok(state) => state,
+        Err(err) => {
+            warn!(
+                "Ignoring invalid upload state file {}: {err}",
+                path.display()
+            );
+            return RecordedState::new(context);
+        }
+    };
+
+    if !state.matches(context) {
+        quiet_println!(
+            "{} Ignoring upload state recorded for a different organization, project or release",
+            style(">").dim()
+        );
+        return RecordedState::new(context);
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use symbolic::debuginfo::sourcebundle::SourceFileType;
+
+    use crate::constants::DEFAULT_MAX_WAIT;
+
+    use super::*;
+
+    fn context<'a>(release: Option<&'a str>) -> UploadContext<'a> {
+        UploadContext {
+            org: "wat-org",
+            project: Some("wat-project"),
+            release,
+            dist: None,
+            metadata: None,
+            wait: false,
+            max_wait: DEFAULT_MAX_WAIT,
+            dedupe: true,
+            chunk_upload_options: None,
+            compression: None,
+        }
+    }
+
+    fn source(url: &str, contents: &str) -> SourceFile {
+        SourceFile {
+            url: url.to_owned(),
+            path: url.into(),
+            contents: contents.as_bytes().to_vec(),
+            ty: SourceFileType::MinifiedSource,
+            headers: Default::default(),
+            messages: Default::default(),
+            already_uploaded: false,
+        }
+    }
+
+    fn saved_state(path: &Path, context: &UploadContext<'_>, sources: &[&SourceFile]) {
+        saved_state_with_dists(
+            path,
+            context,
+            &sources
+                .iter()
+                .map(|source| (*source, None))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    fn saved_state_with_dists(
+        path: &Path,
+        context: &UploadContext<'_>,
+        sources: &[(&SourceFile, Option<&str>)],
+    ) {
+        let mut state = UploadState::load(path, false, context).unwrap();
+        for (source, dist) in sources {
+            state.record(source, *dist);
+        }
+        state.save().unwrap();
+    }
+
+    #[test]
+    fn test_unchanged_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let context = context(Some("1.0"));
+        let app = source("~/app.js", "app");
+        saved_state(&path, &context, &[&app]);
+
+        let state = UploadState::load(&path, false, &context).unwrap();
+        assert!(state.is_unchanged(&app, None));
+        assert!(!state.is_unchanged(&app, Some("dist")));
+        assert!(!state.is_unchanged(&source("~/app.js", "changed"), None));
+        assert!(!state.is_unchanged(&source("~/other.js", "app"), None));
+
+        let mut with_debug_id = source("~/app.js", "app");
+        with_debug_id.set_debug_id("00000000-0000-0000-0000-000000000000".to_owned());
+        assert!(!state.is_unchanged(&with_debug_id, None));
+    }
+
+    #[test]
+    fn test_same_url_for_several_dists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let context = context(Some("1.0"));
+        let ios = source("~/app.js", "ios");
+        let android = source("~/app.js", "android");
+        saved_state_with_dists(
+            &path,
+            &context,
+            &[(&ios, Some("ios")), (&android, Some("android"))],
+        );
+
+        let state = UploadState::load(&path, false, &context).unwrap();
+        assert!(state.is_unchanged(&ios, Some("ios")));
+        assert!(state.is_unchanged(&android, Some("android")));
+        assert!(!state.is_unchanged(&ios, Some("android")));
+        assert!(!state.is_unchanged(&ios, None));
+    }
+
+    #[test]
+    fn test_missing_and_reset_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let context = context(Some("1.0"));
+        let app = source("~/app.js", "app");
+
+        let state = UploadState::load(&path, false, &context).unwrap();
+        assert!(!state.is_unchanged(&app, None));
+
+        saved_state(&path, &context, &[&app]);
+        let state = UploadState::load(&path, true, &context).unwrap();
+        assert!(!state.is_unchanged(&app, None));
+    }
+
+    #[test]
+    fn test_state_of_different_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let app = source("~/app.js", "app");
+        saved_state(&path, &context(Some("1.0")), &[&app]);
+
+        let state = UploadState::load(&path, false, &context(Some("2.0"))).unwrap();
+        assert!(!state.is_unchanged(&app, None));
+        let state = UploadState::load(&path, false, &context(None)).unwrap();
+        assert!(!state.is_unchanged(&app, None));
+    }
+
+    #[test]
+    fn test_invalid_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        fs::write(&path, "{not json").unwrap();
+
+        let state = UploadState::load(&path, false, &context(None)).unwrap();
+        assert!(!state.is_unchanged(&source("~/app.js", "app"), None));
+    }
+
+    #[test]
+    fn test_save_replaces_previous_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let context = context(None);
+        let app = source("~/app.js", "app");
+        let vendor = source("~/vendor.js", "vendor");
+        saved_state(&path, &context, &[&app, &vendor]);
+        saved_state(&path, &context, &[&vendor]);
+
+        let state = UploadState::load(&path, false, &context).unwrap();
+        assert!(!state.is_unchanged(&app, None));
+        assert!(state.is_unchanged(&vendor, None));
+        assert_eq!(
+            fs::read_dir(dir.path()).unwrap().count(),
+            1,
+            "the temporary file should be renamed"
+        );
+    }
+}
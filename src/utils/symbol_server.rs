@@ -0,0 +1,205 @@
+//! Fetching of PDBs from symbol servers.
+//!
+//! Symbol servers serve files in the layout of Microsoft's SymStore, which is
+//! also used by the Simple Symbol Query Protocol (SSQP): each file is stored at
+//! `<file name>/<lookup key>/<file name>`. For PDBs, the lookup key is the GUID
+//! and age of the PDB, as referenced from the debug directory of executables.
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use log::{debug, info, warn};
+use symbolic::common::{ByteView, DebugId};
+use symbolic::debuginfo::pe::PeObject;
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::fs::TempFile;
+
+/// A PDB referenced from the debug directory of a PE file.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PdbReference {
+    /// The file name of the PDB, without the directory it was built in.
+    pub name: String,
+    pub debug_id: DebugId,
+}
+
+impl PdbReference {
+    /// Returns the PDB referenced by the given PE file, if any.
+    pub fn from_pe(pe: &PeObject<'_>) -> Option<Self> {
+        let debug_id = pe.debug_id();
+        if debug_id.is_nil() {
+            return None;
+        }
+
+        // The debug directory contains the path of the PDB on the build machine
+        let path = pe.debug_file_name()?;
+        let name = path.rsplit(['\\', '/']).next()?;
+        if !name.to_lowercase().ends_with(".pdb") {
+            return None;
+        }
+
+        Some(PdbReference {
+            name: name.to_owned(),
+            debug_id,
+        })
+    }
+
+    /// Returns the path of this PDB on a symbol server.
+    pub fn server_path(&self) -> String {
+        let key = format!(
+            "{:X}{:X}",
+            self.debug_id.uuid().simple(),
+            self.debug_id.appendix()
+        );
+        format!("{0}/{key}/{0}", self.name)
+    }
+}
+
+/// A PDB downloaded from a symbol server.
+pub struct FetchedPdb {
+    /// The URL the PDB was downloaded from.
+    pub url: String,
+    /// The location of the PDB in the cache, if it was cached.
+    pub path: Option<PathBuf>,
+    pub data: ByteView<'static>,
+}
+
+/// Fetches PDBs from a list of symbol servers.
+///
+/// Servers are queried in order until one of them has the requested PDB.
+/// Downloaded PDBs are stored in the `symbols` folder in the cache directory,
+/// if caching is enabled, so that they are only downloaded once.
+pub struct SymbolServerClient {
+    servers: Vec<String>,
+    cache_dir: Option<PathBuf>,
+}
+
+impl SymbolServerClient {
+    pub fn new(servers: &[String]) -> Self {
+        let config = Config::current();
+        SymbolServerClient {
+            servers: servers
+                .iter()
+                .map(|server| server.trim_end_matches('/').to_owned())
+                .collect(),
+            cache_dir: config.get_cache_dir().map(|dir| dir.join("symbols")),
+        }
+    }
+
+    /// Fetches the given PDB.
+    ///
+    /// Returns `None` if none of the servers has the PDB. Errors of individual
+    /// servers are only returned if no other server has the PDB either.
+    pub fn fetch(&self, pdb: &PdbReference) -> Result<Option<FetchedPdb>> {
+        let server_path = pdb.server_path();
+
+        if let Some(ref cache_dir) = self.cache_dir {
+            let path = cache_dir.join(&server_path);
+            if path.is_file() {
+                debug!("using cached {}", path.display());
+                return Ok(Some(FetchedPdb {
+                    url: path.display().to_string(),
+                    data: ByteView::open(&path)?,
+                    path: Some(path),
+                }));
+            }
+        }
+
+        let mut error = None;
+        for server in &self.servers {
+            let url = format!("{server}/{server_path}");
+            match self.download(&url, &server_path) {
+                Ok(Some(fetched)) => return Ok(Some(fetched)),
+                Ok(None) => debug!("{url} not found"),
+                Err(err) => {
+                    info!("failed to fetch {url}: {err:#}");
+                    error = Some(err);
+                }
+            }
+        }
+
+        match error {
+            Some(err) => Err(err),
+            None => Ok(None),
+        }
+    }
+
+    fn download(&self, url: &str, server_path: &str) -> Result<Option<FetchedPdb>> {
+        let temp = TempFile::create()?;
+        let response = Api::current().download(url, &mut temp.open()?)?;
+        match response.status() {
+            200 => (),
+            404 => return Ok(None),
+            status => bail!("{url} responded with status {status}"),
+        }
+
+        if let Some(ref cache_dir) = self.cache_dir {
+            let path = cache_dir.join(server_path);
+            match store(temp.path(), &path) {
+                Ok(()) => {
+                    return Ok(Some(FetchedPdb {
+                        url: url.to_owned(),
+                        data: ByteView::open(&path)?,
+                        path: Some(path),
+                    }))
+                }
+                Err(err) => warn!("Failed to cache {}: {err}", path.display()),
+            }
+        }
+
+        // The temporary file is deleted once this returns
+        Ok(Some(FetchedPdb {
+            url: url.to_owned(),
+            path: None,
+            data: ByteView::from_vec(fs::read(temp.path())?),
+        }))
+    }
+}
+
+/// Copies a downloaded file into the cache.
+///
+/// The file is first written next to its final location and then renamed, so
+/// that concurrent runs never see partially written files.
+fn store(source: &Path, path: &Path) -> Result<()> {
+    let dir = path.parent().expect("cached file has a parent directory");
+    fs::create_dir_all(dir)?;
+    let partial = path.with_extension("partial");
+    std::io::copy(&mut File::open(source)?, &mut File::create(&partial)?)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_path() {
+        let pdb = PdbReference {
+            name: "Sentry.Samples.Console.Basic.pdb".into(),
+            debug_id: "54fdf14a-41a1-426a-a073-8185e11a89d6-1a".parse().unwrap(),
+        };
+
+        assert_eq!(
+            pdb.server_path(),
+            "Sentry.Samples.Console.Basic.pdb/54FDF14A41A1426AA0738185E11A89D61A/Sentry.Samples.Console.Basic.pdb"
+        );
+    }
+
+    #[test]
+    fn test_pdb_reference_from_pe() {
+        let data =
+            fs::read("tests/integration/_fixtures/symbol_server/Sentry.Samples.Console.Basic.exe")
+                .unwrap();
+        let pe = PeObject::parse(&data).unwrap();
+
+        assert_eq!(
+            PdbReference::from_pe(&pe),
+            Some(PdbReference {
+                name: "Sentry.Samples.Console.Basic.pdb".into(),
+                debug_id: "54fdf14a-41a1-426a-a073-8185e11a89d6-1".parse().unwrap(),
+            })
+        );
+    }
+}
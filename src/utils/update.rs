@@ -1,14 +1,16 @@
 #[cfg(not(feature = "managed"))]
 use std::env;
+use std::fmt;
 use std::fs;
 use std::io;
 use std::io::Write;
 #[cfg(not(feature = "managed"))]
 use std::path::Path;
+use std::str::FromStr;
 
 #[cfg(not(feature = "managed"))]
 use anyhow::bail;
-use anyhow::{format_err, Result};
+use anyhow::{format_err, Error, Result};
 use chrono::{DateTime, Duration, Utc};
 use console::{style, user_attended};
 use if_chain::if_chain;
@@ -21,6 +23,7 @@ use crate::config::Config;
 use crate::constants::{APP_NAME, VERSION};
 #[cfg(not(feature = "managed"))]
 use crate::utils::fs::{is_writable, set_executable_mode};
+use crate::utils::logging::is_quiet_mode;
 #[cfg(not(feature = "managed"))]
 use crate::utils::system::QuietExit;
 use crate::utils::system::{is_homebrew_install, is_npm_install};
@@ -71,24 +74,82 @@ fn rename_exe(exe: &Path, downloaded_path: &Path, elevate: bool) -> Result<()> {
     Ok(())
 }
 
+/// The release channel that updates of sentry-cli are looked up in.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum UpdateChannel {
+    /// Stable releases only.
+    #[default]
+    Stable,
+    /// Release candidates in addition to stable releases.
+    Rc,
+}
+
+impl UpdateChannel {
+    /// The name of the feed of this channel in the release registry.
+    pub fn registry_feed(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "latest",
+            UpdateChannel::Rc => "latest-rc",
+        }
+    }
+}
+
+impl fmt::Display for UpdateChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateChannel::Stable => write!(f, "stable"),
+            UpdateChannel::Rc => write!(f, "rc"),
+        }
+    }
+}
+
+impl FromStr for UpdateChannel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<UpdateChannel> {
+        match s {
+            "stable" => Ok(UpdateChannel::Stable),
+            "rc" => Ok(UpdateChannel::Rc),
+            _ => Err(format_err!(
+                "Invalid update channel `{s}`, expected `stable` or `rc`"
+            )),
+        }
+    }
+}
+
+/// Returns whether `latest` is a newer version than `current`.
+///
+/// Versions that are not valid semver are compared for equality only.
+fn is_newer_version(latest: &str, current: &str) -> bool {
+    match (Version::parse(latest), Version::parse(current)) {
+        (Ok(latest), Ok(current)) => latest > current,
+        _ => latest != current,
+    }
+}
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct LastUpdateCheck {
     pub last_check_timestamp: Option<DateTime<Utc>>,
     pub last_check_version: Option<String>,
     pub last_fetched_version: Option<String>,
+    #[serde(default)]
+    pub last_check_channel: Option<String>,
 }
 
 impl LastUpdateCheck {
-    pub fn update_for_info(&mut self, ui: &SentryCliUpdateInfo) {
+    pub fn update_for_info(&mut self, ui: &SentryCliUpdateInfo, channel: UpdateChannel) {
         self.last_check_timestamp = Some(Utc::now());
         self.last_check_version = Some(ui.current_version().to_string());
         self.last_fetched_version = Some(ui.latest_version().to_string());
+        self.last_check_channel = Some(channel.to_string());
     }
 
-    pub fn should_run_check(&self) -> bool {
+    pub fn should_run_check(&self, channel: UpdateChannel) -> bool {
         if_chain! {
             if let Some(ts) = self.last_check_timestamp;
             if let Some(ref ver) = self.last_check_version;
+            // checks from before channels existed were made against stable
+            if self.last_check_channel.as_deref().unwrap_or("stable") == channel.to_string();
             then {
                 ver.as_str() != VERSION || ts < Utc::now() - Duration::hours(12)
             } else {
@@ -102,8 +163,7 @@ impl LastUpdateCheck {
             if let Some(ref release_v) = self.last_fetched_version;
             if let Some(ref check_v) = self.last_check_version;
             then {
-                Version::parse(release_v.as_str()).unwrap() < Version::parse(VERSION).unwrap() &&
-                check_v.as_str() == VERSION
+                is_newer_version(release_v, VERSION) && check_v.as_str() == VERSION
             } else {
                 false
             }
@@ -126,7 +186,13 @@ impl SentryCliUpdateInfo {
 
     #[cfg(not(feature = "managed"))]
     pub fn is_latest_version(&self) -> bool {
-        self.latest_version() == VERSION
+        !self.is_update_available()
+    }
+
+    /// Returns whether the latest release is newer than the running version.
+    #[cfg(not(feature = "managed"))]
+    pub fn is_update_available(&self) -> bool {
+        self.have_version_info() && is_newer_version(self.latest_version(), VERSION)
     }
 
     pub fn current_version(&self) -> &str {
@@ -176,10 +242,10 @@ impl SentryCliUpdateInfo {
     }
 }
 
-pub fn get_latest_sentrycli_release() -> Result<SentryCliUpdateInfo> {
+pub fn get_latest_sentrycli_release(channel: UpdateChannel) -> Result<SentryCliUpdateInfo> {
     let api = Api::current();
     Ok(SentryCliUpdateInfo {
-        latest_release: api.get_latest_sentrycli_release().unwrap_or_default(),
+        latest_release: api.get_latest_sentrycli_release(channel)?,
     })
 }
 
@@ -208,7 +274,7 @@ pub fn assert_updatable() -> Result<()> {
     Ok(())
 }
 
-fn update_nagger_impl() -> Result<()> {
+fn update_nagger_impl(channel: UpdateChannel) -> Result<()> {
     let mut path = dirs::cache_dir().ok_or_else(|| format_err!("Could not get cache folder"))?;
 
     path.push(APP_NAME);
@@ -221,11 +287,11 @@ fn update_nagger_impl() -> Result<()> {
         Default::default()
     };
 
-    if check.should_run_check() {
+    if check.should_run_check(channel) {
         info!("Running update nagger update check");
-        let ui = get_latest_sentrycli_release()?;
+        let ui = get_latest_sentrycli_release(channel)?;
         if ui.have_version_info() {
-            check.update_for_info(&ui);
+            check.update_for_info(&ui, channel);
             let mut f = fs::File::create(&path)?;
             serde_json::to_writer_pretty(&mut f, &check)?;
             f.write_all(b"\n")?;
@@ -234,7 +300,7 @@ fn update_nagger_impl() -> Result<()> {
         info!("Skipping update nagger update check");
     }
 
-    if check.is_outdated() {
+    if check.is_outdated() && !is_quiet_mode() {
         info!("Update nagger determined outdated installation");
         eprintln!();
         eprintln!(
@@ -289,5 +355,7 @@ pub fn run_sentrycli_update_nagger() {
         return;
     }
 
-    update_nagger_impl().ok();
+    if let Ok(channel) = config.get_update_channel() {
+        update_nagger_impl(channel).ok();
+    }
 }
@@ -0,0 +1,186 @@
+//! Free-form metadata attached to uploads with `--note` and `--meta`.
+//!
+//! The metadata is stored as attributes in the manifest of uploaded bundles,
+//! where `--meta` keys are prefixed with `meta.` so that they cannot clash
+//! with the attributes set by sentry-cli itself.
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use clap::ArgMatches;
+use serde::Serialize;
+
+/// The maximum total size of the note and all metadata keys and values.
+pub const MAX_METADATA_SIZE: usize = 4 * 1024;
+
+/// The bundle attribute holding the note.
+const NOTE_ATTRIBUTE: &str = "note";
+
+/// The prefix of bundle attributes holding `--meta` values.
+const META_PREFIX: &str = "meta.";
+
+/// The metadata of an upload.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UploadMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, String>,
+}
+
+/// Parses a `KEY=VALUE` pair given with `--meta`, used as a value_parser for Clap arguments.
+///
+/// Keys must be printable ASCII and cannot contain `=`, while values may be
+/// arbitrary text.
+pub fn meta_parser(s: &str) -> Result<(String, String)> {
+    let Some((key, value)) = s.split_once('=') else {
+        bail!("`{s}` is missing a `=`");
+    };
+    if key.is_empty() {
+        bail!("`{s}` is missing a key before the `=`");
+    }
+    if !key.bytes().all(|b| b.is_ascii_graphic()) {
+        bail!("`{key}` is not a valid key, since keys must be printable ASCII without spaces");
+    }
+    Ok((key.to_owned(), value.to_owned()))
+}
+
+impl UploadMetadata {
+    /// Reads the metadata from the `note` and `meta` arguments.
+    pub fn from_matches(matches: &ArgMatches) -> Result<Self> {
+        let mut metadata = UploadMetadata {
+            note: matches.get_one::<String>("note").cloned(),
+            meta: BTreeMap::new(),
+        };
+
+        for (key, value) in matches
+            .get_many::<(String, String)>("meta")
+            .unwrap_or_default()
+        {
+            if metadata.meta.insert(key.clone(), value.clone()).is_some() {
+                bail!("The metadata key `{key}` was given more than once");
+            }
+        }
+
+        let size = metadata.size();
+        if size > MAX_METADATA_SIZE {
+            bail!(
+                "The upload metadata is {size} bytes large, \
+                 but at most {MAX_METADATA_SIZE} bytes are allowed"
+            );
+        }
+
+        Ok(metadata)
+    }
+
+    /// Reads the metadata from the attributes of a bundle manifest.
+    pub fn from_attributes(attributes: &BTreeMap<String, String>) -> Self {
+        UploadMetadata {
+            note: attributes.get(NOTE_ATTRIBUTE).cloned(),
+            meta: attributes
+                .iter()
+                .filter_map(|(key, value)| {
+                    let key = key.strip_prefix(META_PREFIX)?;
+                    Some((key.to_owned(), value.clone()))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the bundle manifest attributes storing this metadata.
+    pub fn attributes(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        let note = self
+            .note
+            .iter()
+            .map(|note| (NOTE_ATTRIBUTE.to_owned(), note.clone()));
+        let meta = self
+            .meta
+            .iter()
+            .map(|(key, value)| (format!("{META_PREFIX}{key}"), value.clone()));
+        note.chain(meta)
+    }
+
+    /// Returns the total size of the note and all keys and values.
+    fn size(&self) -> usize {
+        let note = self.note.as_ref().map_or(0, String::len);
+        let meta: usize = self
+            .meta
+            .iter()
+            .map(|(key, value)| key.len() + value.len())
+            .sum();
+        note + meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Command;
+
+    use crate::utils::args::ArgExt;
+
+    use super::*;
+
+    fn metadata_from_args(args: &[&str]) -> Result<UploadMetadata> {
+        let matches = Command::new("upload")
+            .upload_metadata_args()
+            .try_get_matches_from(std::iter::once("upload").chain(args.iter().copied()))?;
+        UploadMetadata::from_matches(&matches)
+    }
+
+    #[test]
+    fn test_meta_parser() {
+        assert_eq!(
+            meta_parser("pipeline=4812 (nightly)").unwrap(),
+            ("pipeline".to_owned(), "4812 (nightly)".to_owned())
+        );
+        assert_eq!(
+            meta_parser("url=https://ci.example.com/?a=b").unwrap(),
+            ("url".to_owned(), "https://ci.example.com/?a=b".to_owned())
+        );
+        assert_eq!(
+            meta_parser("empty=").unwrap(),
+            ("empty".to_owned(), String::new())
+        );
+
+        assert!(meta_parser("pipeline").is_err());
+        assert!(meta_parser("=4812").is_err());
+        assert!(meta_parser("build number=4812").is_err());
+        assert!(meta_parser("bäume=1").is_err());
+    }
+
+    #[test]
+    fn test_attributes_round_trip() {
+        let metadata = UploadMetadata {
+            note: Some("pipeline 4812".to_owned()),
+            meta: [("build".to_owned(), "4812".to_owned())].into(),
+        };
+
+        let mut attributes: BTreeMap<_, _> = metadata.attributes().collect();
+        assert_eq!(attributes["note"], "pipeline 4812");
+        assert_eq!(attributes["meta.build"], "4812");
+
+        attributes.insert("org".to_owned(), "wat-org".to_owned());
+        assert_eq!(UploadMetadata::from_attributes(&attributes), metadata);
+    }
+
+    #[test]
+    fn test_from_matches() {
+        let metadata =
+            metadata_from_args(&["--note", "pipeline 4812", "--meta", "build=4812"]).unwrap();
+        assert_eq!(metadata.note.as_deref(), Some("pipeline 4812"));
+        assert_eq!(metadata.meta["build"], "4812");
+
+        let err = metadata_from_args(&["--meta", "build=1", "--meta", "build=2"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The metadata key `build` was given more than once"
+        );
+
+        let note = "x".repeat(MAX_METADATA_SIZE);
+        assert!(metadata_from_args(&["--note", &note]).is_ok());
+        let err = metadata_from_args(&["--note", &note, "--meta", "a=b"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The upload metadata is 4098 bytes large, but at most 4096 bytes are allowed"
+        );
+    }
+}
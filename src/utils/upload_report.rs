@@ -0,0 +1,92 @@
+//! Machine-readable reports of uploads, as written with `--report`.
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::utils::upload_metadata::UploadMetadata;
+
+/// The outcome of uploading a single file.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum UploadStatus {
+    /// The file was uploaded to the server.
+    Uploaded,
+    /// The file was not uploaded, because the server already has it.
+    SkippedKnown,
+    /// The file was not uploaded, because it exceeds the maximum file size.
+    SkippedTooLarge,
+    /// The file was not uploaded, because it is unchanged since the upload
+    /// recorded in the state file.
+    SkippedUnchanged,
+    /// The file could not be uploaded or processed.
+    Errored { message: String },
+}
+
+/// A single file in an [`UploadReport`].
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadReportEntry {
+    pub path: String,
+    pub debug_id: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub size: u64,
+    pub checksum: Option<String>,
+    #[serde(flatten)]
+    pub status: UploadStatus,
+}
+
+/// A report of all files considered by an upload and their outcome.
+#[derive(Debug, Serialize)]
+pub struct UploadReport {
+    pub files: Vec<UploadReportEntry>,
+    pub duration_secs: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release: Option<String>,
+    #[serde(flatten)]
+    pub metadata: UploadMetadata,
+}
+
+impl UploadReport {
+    /// Creates a report for an upload that started at `start`.
+    ///
+    /// If the upload failed as a whole, pass the error so that it is included
+    /// in addition to the per-file outcomes.
+    pub fn new(
+        mut files: Vec<UploadReportEntry>,
+        start: Instant,
+        error: Option<&anyhow::Error>,
+    ) -> Self {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        UploadReport {
+            files,
+            duration_secs: start.elapsed().as_secs_f64(),
+            error: error.map(|err| format!("{err:#}")),
+            release: None,
+            metadata: UploadMetadata::default(),
+        }
+    }
+
+    /// Records the note and metadata given to the upload in the report.
+    pub fn with_metadata(mut self, metadata: &UploadMetadata) -> Self {
+        self.metadata = metadata.clone();
+        self
+    }
+
+    /// Records the release the files were uploaded for in the report.
+    pub fn with_release(mut self, release: Option<&str>) -> Self {
+        self.release = release.map(str::to_owned);
+        self
+    }
+
+    /// Writes the report as JSON to the given path.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self)?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write upload report to {}", path.display()))
+    }
+}
@@ -0,0 +1,198 @@
+//! Watching of files for changes, as used by `--watch` modes.
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use log::{debug, warn};
+use notify::{Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// How long to wait for further changes before reporting a batch of changes.
+///
+/// Linkers and build tools often write files in several steps, which should
+/// not result in several uploads.
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// The poll interval if filesystem notifications are not available.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often to check whether watching should stop while no changes happen.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+type EventReceiver = Receiver<notify::Result<Event>>;
+
+/// Watches paths recursively for new and modified files.
+pub struct FileWatcher {
+    events: EventReceiver,
+    // Dropping the watcher stops watching
+    _watcher: Box<dyn Watcher>,
+}
+
+impl FileWatcher {
+    /// Starts watching the given paths.
+    ///
+    /// With a `poll_interval`, the paths are polled for changes. Otherwise,
+    /// filesystem notifications are used, falling back to polling if they are
+    /// not available.
+    pub fn new(paths: &[PathBuf], poll_interval: Option<Duration>) -> Result<FileWatcher> {
+        if let Some(interval) = poll_interval {
+            return FileWatcher::polling(paths, interval);
+        }
+
+        match FileWatcher::native(paths) {
+            Ok(watcher) => Ok(watcher),
+            Err(err) => {
+                warn!("Cannot watch for filesystem notifications, polling for changes: {err}");
+                FileWatcher::polling(paths, DEFAULT_POLL_INTERVAL)
+            }
+        }
+    }
+
+    fn native(paths: &[PathBuf]) -> Result<FileWatcher> {
+        let (sender, events) = mpsc::channel();
+        let watcher = RecommendedWatcher::new(sender, Config::default())?;
+        FileWatcher::start(Box::new(watcher), events, paths)
+    }
+
+    fn polling(paths: &[PathBuf], interval: Duration) -> Result<FileWatcher> {
+        debug!("polling for changes every {}ms", interval.as_millis());
+        let (sender, events) = mpsc::channel();
+        let watcher = PollWatcher::new(sender, Config::default().with_poll_interval(interval))?;
+        FileWatcher::start(Box::new(watcher), events, paths)
+    }
+
+    fn start(
+        mut watcher: Box<dyn Watcher>,
+        events: EventReceiver,
+        paths: &[PathBuf],
+    ) -> Result<FileWatcher> {
+        for path in paths {
+            debug!("watching {}", path.display());
+            watcher.watch(path, RecursiveMode::Recursive)?;
+        }
+
+        Ok(FileWatcher {
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Blocks until files are created or modified, and returns them once no
+    /// further changes happened for a moment.
+    ///
+    /// New directories are returned as a whole, without the files in them.
+    /// Returns `None` as soon as `stop` is set.
+    pub fn wait_for_changes(&self, stop: &AtomicBool) -> Result<Option<BTreeSet<PathBuf>>> {
+        let mut changed = BTreeSet::new();
+        while changed.is_empty() {
+            if stop.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
+            match self.events.recv_timeout(STOP_CHECK_INTERVAL) {
+                Ok(event) => collect_changes(event, &mut changed),
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => bail!("Watching for changes failed"),
+            }
+        }
+
+        let mut deadline = Instant::now() + DEBOUNCE;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.events.recv_timeout(timeout) {
+                Ok(event) => {
+                    collect_changes(event, &mut changed);
+                    deadline = Instant::now() + DEBOUNCE;
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => bail!("Watching for changes failed"),
+            }
+        }
+
+        if stop.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+
+        Ok(Some(remove_nested(changed)))
+    }
+}
+
+/// Adds the existing paths of a creation or modification event to `changed`.
+fn collect_changes(event: notify::Result<Event>, changed: &mut BTreeSet<PathBuf>) {
+    match event {
+        Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+            // Paths of renamed or deleted files no longer exist
+            changed.extend(event.paths.into_iter().filter(|path| path.exists()));
+        }
+        Ok(_) => (),
+        Err(err) => warn!("Error while watching for changes: {err}"),
+    }
+}
+
+/// Removes paths contained in directories that are also in the set.
+fn remove_nested(paths: BTreeSet<PathBuf>) -> BTreeSet<PathBuf> {
+    let has_parent = |path: &Path| path.ancestors().skip(1).any(|dir| paths.contains(dir));
+    paths
+        .iter()
+        .filter(|path| !has_parent(path))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_remove_nested() {
+        let paths = ["a", "a/b", "a/b/c", "d/e", "d/f"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+
+        let expected: BTreeSet<_> = ["a", "d/e", "d/f"].iter().map(PathBuf::from).collect();
+        assert_eq!(remove_nested(paths), expected);
+    }
+
+    #[test]
+    fn test_polling_detects_modified_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path().canonicalize().unwrap();
+        let path = dir.join("file");
+        fs::write(&path, "a").unwrap();
+
+        let watcher = FileWatcher::new(&[dir], Some(Duration::from_millis(50))).unwrap();
+
+        // Make sure the modification time differs on filesystems with a coarse resolution
+        thread::sleep(Duration::from_millis(1100));
+        fs::write(&path, "bc").unwrap();
+
+        let stop = AtomicBool::new(false);
+        let changed = watcher.wait_for_changes(&stop).unwrap().unwrap();
+        assert_eq!(changed, BTreeSet::from([path]));
+    }
+
+    #[test]
+    fn test_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        let watcher =
+            FileWatcher::new(&[dir.path().to_owned()], Some(Duration::from_millis(50))).unwrap();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stop = stop.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(100));
+                stop.store(true, Ordering::SeqCst);
+            })
+        };
+
+        assert_eq!(watcher.wait_for_changes(&stop).unwrap(), None);
+        handle.join().unwrap();
+    }
+}
@@ -369,6 +369,48 @@ impl InfoPlist {
     }
 }
 
+/// Returns the folders of an Xcode build which contain dSYMs, in addition to
+/// the given search paths.
+///
+/// When invoked from a build phase, these are `DWARF_DSYM_FOLDER_PATH` and the
+/// framework dSYMs in `BUILT_PRODUCTS_DIR`, which include dSYMs of SwiftPM binary
+/// targets.  For every `.xcarchive` among the search paths, this is its `dSYMs`
+/// folder, which also contains the dSYMs of embedded frameworks.  Folders that
+/// do not exist are skipped.
+pub fn discover_dsym_folders<P: AsRef<Path>>(search_paths: &[P]) -> Vec<PathBuf> {
+    let mut folders = vec![];
+
+    if let Some(path) = env::var_os("DWARF_DSYM_FOLDER_PATH") {
+        folders.push(PathBuf::from(path));
+    }
+
+    if let Some(path) = env::var_os("BUILT_PRODUCTS_DIR") {
+        if let Ok(entries) = fs::read_dir(path) {
+            let mut dsyms: Vec<_> = entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .map_or(false, |name| name.ends_with(".framework.dSYM"))
+                })
+                .collect();
+            dsyms.sort();
+            folders.extend(dsyms);
+        }
+    }
+
+    for path in search_paths {
+        let path = path.as_ref();
+        if path.extension().map_or(false, |ext| ext == "xcarchive") {
+            folders.push(path.join("dSYMs"));
+        }
+    }
+
+    folders.retain(|folder| folder.is_dir());
+    folders
+}
+
 /// Returns true if we were invoked from xcode
 #[cfg(target_os = "macos")]
 pub fn launched_from_xcode() -> bool {
@@ -0,0 +1,4 @@
+#!/bin/sh
+# Fails like a secrets manager without access to the token.
+echo "vault: permission denied" >&2
+exit 1
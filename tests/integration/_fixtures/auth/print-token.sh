@@ -0,0 +1,3 @@
+#!/bin/sh
+# Prints an auth token, like a secrets manager would.
+echo "  fedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210  "
@@ -0,0 +1,5 @@
+#include "vendor/vendored.h"
+
+int main(void) {
+    return vendored_add(1, 2) - 3;
+}
@@ -0,0 +1,3 @@
+package io.sentry.extra
+
+fun String.shout(): String = uppercase() + "!"
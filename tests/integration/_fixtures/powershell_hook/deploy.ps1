@@ -0,0 +1,13 @@
+param($Target)
+
+function Publish-Build {
+  param($Path)
+  Copy-Item $Path -Destination \\server\share
+}
+
+function Invoke-Deploy {
+  Write-Output "Deploying to $Target"
+  Publish-Build -Path .\build
+}
+
+Invoke-Deploy
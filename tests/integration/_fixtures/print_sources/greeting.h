@@ -0,0 +1,3 @@
+static inline int greeting(void) {
+    return 42;
+}
@@ -0,0 +1,7 @@
+#include "greeting.h"
+
+int generated(void);
+
+int main(void) {
+    return greeting() + generated();
+}
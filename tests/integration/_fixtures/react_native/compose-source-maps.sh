@@ -0,0 +1,10 @@
+#!/bin/sh
+
+echo "compose-source-maps called with args: $@"
+
+while [ $# -gt 0 ]; do
+  if [ "$1" = "-o" ]; then
+    echo '{"version":3,"file":"react-native-xcode-hermes.jsbundle","sources":["index.js"],"names":[],"mappings":"AAAA","x_composed":true}' > "$2"
+  fi
+  shift
+done
@@ -0,0 +1,3 @@
+console.log("Fake bundle!");
+
+//# sourceMappingURL=react-native-xcode-hermes-packager.js.map
@@ -0,0 +1,11 @@
+#!/bin/sh
+
+echo "react-native-xcode-hermes.sh called with args: $@"
+
+echo '{
+  "packager_bundle_path": "tests/integration/_fixtures/react_native/react-native-xcode-hermes-packager.js",
+  "packager_sourcemap_path": "tests/integration/_fixtures/react_native/react-native-xcode-hermes-packager.js.map",
+  "hermes_bundle_path": "tests/integration/_fixtures/react_native/react-native-xcode-hermes.jsbundle",
+  "hermes_sourcemap_path": null,
+  "hermes_compiler_sourcemap_path": "tests/integration/_fixtures/react_native/react-native-xcode-hermes.jsbundle.map"
+}' > "$SENTRY_RN_SOURCEMAP_REPORT"
@@ -0,0 +1,2 @@
+function add(n,r){return n+r}function greet(n){throw new Error("Hello, "+n)}
+//# sourceMappingURL=app.min.js.map
@@ -0,0 +1 @@
+const char *greeting = "Gre, caf";
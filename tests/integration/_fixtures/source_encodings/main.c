@@ -0,0 +1,12 @@
+#line 1 "/build/utf8.c"
+int utf8(void) { return 1; }
+#line 1 "/build/latin1.c"
+int latin1(void) { return 2; }
+#line 1 "/build/utf16le.c"
+int utf16le(void) { return 3; }
+#line 1 "/build/utf16be.c"
+int utf16be(void) { return 4; }
+#line 1 "/build/oversized.c"
+int oversized(void) { return 5; }
+#line 12 "/build/main.c"
+int main(void) { return utf8() + latin1() + utf16le() + utf16be() + oversized(); }
@@ -0,0 +1,256 @@
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
+/* generated */
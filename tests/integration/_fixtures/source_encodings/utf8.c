@@ -0,0 +1 @@
+const char *greeting = "Grüße, café";
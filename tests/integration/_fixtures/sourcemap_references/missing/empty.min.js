@@ -0,0 +1,2 @@
+console.log(1);
+//# sourceMappingURL=empty.min.js.map
@@ -0,0 +1,2 @@
+console.log(1);
+//# sourceMappingURL=hashed.min.3f2a1b.js.map
@@ -0,0 +1,2 @@
+console.log(1);
+//# sourceMappingURL=data:application/json;base64,eyJ2ZXJzaW9uIjozLCJmaWxlIjoiaW5saW5lLm1pbi5qcyIsInNvdXJjZXMiOlsiaW5saW5lLmpzIl0sInNvdXJjZXNDb250ZW50IjpbImNvbnNvbGUubG9nKDEpO1xuIl0sIm5hbWVzIjpbXSwibWFwcGluZ3MiOiJBQUFBIn0=
@@ -0,0 +1,2 @@
+console.log(1);
+//# sourceMappingURL=relative.min.js.map
@@ -0,0 +1,2 @@
+console.log(1);
+//# sourceMappingURL=https://cdn.example.com/remote.min.js.map
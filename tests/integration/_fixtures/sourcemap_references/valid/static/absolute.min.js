@@ -0,0 +1,2 @@
+console.log(1);
+//# sourceMappingURL=/static/absolute.min.js.map
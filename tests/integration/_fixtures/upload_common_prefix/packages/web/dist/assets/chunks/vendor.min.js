@@ -0,0 +1 @@
+function sub(n,d){return n-d}
@@ -0,0 +1,2 @@
+function add(n,d){return n+d}
+//# sourceMappingURL=app.min.js.map
@@ -0,0 +1,2 @@
+function greet(n){return"Hello "+n}greet("world");
+//# sourceMappingURL=outdated-sources.min.js.map
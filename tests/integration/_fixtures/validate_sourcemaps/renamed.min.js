@@ -0,0 +1,2 @@
+function greet(n){return"Hello "+n}greet("world");
+//# sourceMappingURL=renamed.min.js.map
@@ -0,0 +1,107 @@
+use mockito::Matcher;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+const PROJECTS_ENDPOINT: &str = "/api/0/organizations/wat-org/projects/";
+
+#[test]
+fn command_api_help() {
+    TestManager::new().register_trycmd_test("api/api-help.trycmd");
+}
+
+#[test]
+fn command_api_get_paginate() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", PROJECTS_ENDPOINT)
+                .with_response_header(
+                    "link",
+                    "<http://localhost/api/0/organizations/wat-org/projects/?cursor=page-2>; \
+                     rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+                )
+                .with_response_body(r#"[{"slug":"wat-project"}]"#)
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/projects/?cursor=page-2",
+            )
+            .with_response_body(r#"[{"slug":"other-project"}]"#)
+            .expect(2),
+        )
+        .register_trycmd_test("api/api-get-paginate*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// The cursor of the next page replaces a cursor given in the path, and is encoded.
+#[test]
+fn command_api_paginate_replaces_cursor() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/projects/?query=wat&cursor=start",
+            )
+            .with_response_header(
+                "link",
+                "<http://localhost/api/0/organizations/wat-org/projects/?cursor=page+2>; \
+                 rel=\"next\"; results=\"true\"; cursor=\"page+2\"",
+            )
+            .with_response_body(r#"[{"slug":"wat-project"}]"#)
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/projects/?query=wat&cursor=page%2B2",
+            )
+            .with_response_body(r#"[{"slug":"other-project"}]"#)
+            .expect(1),
+        )
+        .register_trycmd_test("api/api-paginate-replaces-cursor.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_api_post_data() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/releases/")
+                .with_matcher(Matcher::Json(serde_json::json!({
+                    "version": "wat-release",
+                    "projects": ["wat-project"],
+                })))
+                .with_status(201)
+                .with_response_body(r#"{"version":"wat-release"}"#)
+                .expect(2),
+        )
+        .register_trycmd_test("api/api-post-data*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_api_forbidden() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", PROJECTS_ENDPOINT)
+                .with_status(403)
+                .with_response_body(
+                    r#"{"detail":"You do not have permission to perform this action."}"#,
+                )
+                .expect(1),
+        )
+        .register_trycmd_test("api/api-forbidden.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_api_invalid_path() {
+    TestManager::new()
+        .register_trycmd_test("api/api-invalid-path.trycmd")
+        .with_default_token();
+}
@@ -0,0 +1,85 @@
+use assert_cmd::Command;
+
+use crate::integration::TestManager;
+
+/// Subcommands that are hidden from `--help` and must not be completed.
+const HIDDEN_SUBCOMMANDS: &[&str] = &["bash-hook", "send-metric", "upload-dif", "upload-dsym"];
+
+fn completions(shell: &str) -> String {
+    let output = Command::cargo_bin("sentry-cli")
+        .expect("sentry-cli should be available")
+        .args(["completions", shell])
+        .output()
+        .expect("sentry-cli should run");
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).expect("completions should be valid UTF-8")
+}
+
+fn assert_no_hidden_subcommands(script: &str) {
+    for subcommand in HIDDEN_SUBCOMMANDS {
+        assert!(
+            !script.contains(subcommand),
+            "hidden subcommand {subcommand} should not be completed"
+        );
+    }
+}
+
+#[test]
+fn command_completions_help() {
+    TestManager::new().register_trycmd_test("completions/completions-help.trycmd");
+}
+
+#[test]
+fn command_completions_bash() {
+    let script = completions("bash");
+
+    let (_, upload) = script
+        .split_once("        sentry__cli__sourcemaps__upload)\n")
+        .expect("sourcemaps upload should be completed");
+    let opts = upload.lines().next().unwrap();
+    assert!(opts.contains(" --url-prefix "), "{opts}");
+    assert!(opts.contains(" --url-suffix "), "{opts}");
+
+    assert!(script.contains("sentry__cli__debug__files,bundle-jvm)"));
+    assert_no_hidden_subcommands(&script);
+}
+
+#[test]
+fn command_completions_zsh() {
+    let script = completions("zsh");
+
+    assert!(script.starts_with("#compdef sentry-cli\n"));
+    assert!(
+        script.contains("'--url-prefix=[The URL prefix to prepend to all filenames.]:PREFIX: '")
+    );
+    assert!(script.contains("'--url-suffix=[The URL suffix to append to all filenames.]:SUFFIX: '"));
+    assert!(script.contains("'sourcemaps:Manage sourcemaps for Sentry releases.'"));
+    assert_no_hidden_subcommands(&script);
+}
+
+#[test]
+fn command_completions_fish_and_powershell() {
+    for shell in ["fish", "powershell"] {
+        let script = completions(shell);
+        assert!(script.contains("url-prefix"), "{shell}");
+        assert_no_hidden_subcommands(&script);
+    }
+}
+
+#[test]
+fn command_completions_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("sentry-cli.bash");
+
+    let output = Command::cargo_bin("sentry-cli")
+        .expect("sentry-cli should be available")
+        .args(["completions", "bash", "--output"])
+        .arg(&path)
+        .output()
+        .expect("sentry-cli should run");
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stdout.is_empty());
+
+    let written = std::fs::read_to_string(&path).expect("completions should be written");
+    assert_eq!(written, completions("bash"));
+}
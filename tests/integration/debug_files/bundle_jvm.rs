@@ -1,5 +1,13 @@
-use crate::integration::{copy_recursively, ServerBehavior, TestManager};
+use crate::integration::{
+    copy_recursively, test_utils::env, MockEndpointBuilder, ServerBehavior, TestManager,
+};
+use assert_cmd::Command;
+use mockito::Matcher;
 use std::fs::{create_dir, remove_dir_all, write};
+use std::path::Path;
+use std::process::Output;
+use symbolic::common::{ByteView, DebugId};
+use symbolic::debuginfo::sourcebundle::{SourceBundle, SourceFileType};
 
 #[test]
 fn command_bundle_jvm_out_not_found_creates_dir() {
@@ -96,3 +104,152 @@ fn command_bundle_jvm() {
         .register_trycmd_test("debug_files/bundle_jvm/debug_files-bundle-jvm.trycmd")
         .with_default_token();
 }
+
+/// Bundles the given source roots into `output` and returns the output of the command.
+fn bundle_jvm(output: &Path, args: &[&str]) -> Output {
+    let manager = TestManager::new();
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command
+        .args(["debug-files", "bundle-jvm", "--output"])
+        .arg(output)
+        .args(args);
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    command.output().expect("sentry-cli should run")
+}
+
+#[test]
+fn command_bundle_jvm_multiple_roots() {
+    let output = tempfile::tempdir().unwrap();
+    let result = bundle_jvm(
+        output.path(),
+        &[
+            "--debug-id",
+            "4f5ed267-6e63-4a39-8ec5-ed36dd0ab6a2",
+            "tests/integration/_fixtures/jvm",
+            "tests/integration/_fixtures/jvm_kotlin",
+        ],
+    );
+    assert!(result.status.success(), "{result:?}");
+
+    let path = output
+        .path()
+        .join("4f5ed267-6e63-4a39-8ec5-ed36dd0ab6a2.zip");
+    let data = ByteView::open(&path).expect("bundle should be written");
+    let bundle = SourceBundle::parse(&data).expect("bundle should be a source bundle");
+    assert_eq!(
+        bundle.debug_id(),
+        "4f5ed267-6e63-4a39-8ec5-ed36dd0ab6a2".parse().unwrap()
+    );
+
+    let session = bundle.debug_session().unwrap();
+    assert_eq!(session.files().count(), 3);
+    for url in [
+        "~/io/sentry/sample/MainActivity.jvm",
+        "~/io/sentry/sample/SampleActivity.jvm",
+    ] {
+        assert!(
+            session.source_by_url(url).unwrap().is_some(),
+            "{url} should be bundled"
+        );
+    }
+
+    let source = session
+        .source_by_url("~/io/sentry/extra/Extensions.jvm")
+        .unwrap()
+        .expect("source should be bundled");
+    assert_eq!(source.ty(), SourceFileType::Source);
+    assert!(source
+        .contents()
+        .unwrap()
+        .starts_with("package io.sentry.extra"));
+}
+
+#[test]
+fn command_bundle_jvm_generated_debug_id() {
+    let output = tempfile::tempdir().unwrap();
+    let result = bundle_jvm(
+        output.path(),
+        &["--quiet", "tests/integration/_fixtures/jvm"],
+    );
+    assert!(result.status.success(), "{result:?}");
+
+    // In quiet mode, only the generated bundle ID is printed.
+    let stdout = String::from_utf8(result.stdout).unwrap();
+    let debug_id: DebugId = stdout
+        .trim()
+        .parse()
+        .expect("stdout should be the bundle ID");
+
+    let data = ByteView::open(output.path().join(format!("{debug_id}.zip")))
+        .expect("bundle should be named after the bundle ID");
+    let bundle = SourceBundle::parse(&data).unwrap();
+    assert_eq!(bundle.debug_id(), debug_id);
+}
+
+#[test]
+fn command_bundle_jvm_fails_duplicate_files() {
+    let output = tempfile::tempdir().unwrap();
+    let result = bundle_jvm(
+        output.path(),
+        &[
+            "tests/integration/_fixtures/jvm",
+            "tests/integration/_fixtures/jvm/",
+        ],
+    );
+    assert!(!result.status.success());
+
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert!(
+        stderr.contains("source roots must not contain the same file"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn command_debug_files_upload_jvm_bundle() {
+    let output = tempfile::tempdir().unwrap();
+    let result = bundle_jvm(
+        output.path(),
+        &[
+            "--debug-id",
+            "4f5ed267-6e63-4a39-8ec5-ed36dd0ab6a2",
+            "tests/integration/_fixtures/jvm",
+        ],
+    );
+    assert!(result.status.success(), "{result:?}");
+
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::AllOf(vec![
+                Matcher::Regex(r#""name":"4f5ed267-6e63-4a39-8ec5-ed36dd0ab6a2.zip""#.into()),
+                Matcher::Regex(r#""debug_id":"4f5ed267-6e63-4a39-8ec5-ed36dd0ab6a2""#.into()),
+            ]))
+            .with_response_body("{}")
+            .expect_at_least(1),
+        );
+
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command
+        .args(["debug-files", "upload", "--type", "jvm", "--no-wait"])
+        .arg(output.path());
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    let result = command.output().expect("sentry-cli should run");
+    manager.assert_mock_endpoints();
+    assert!(result.status.success(), "{result:?}");
+}
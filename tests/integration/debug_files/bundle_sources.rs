@@ -0,0 +1,64 @@
+use std::fs::File;
+
+use assert_cmd::Command;
+use zip::ZipArchive;
+
+/// Debug information file built with `-fdebug-prefix-map=<fixture dir>=/build`.
+const PREFIX_MAP_FIXTURE: &str = "tests/integration/_fixtures/bundle_sources/prefix-map.debug";
+
+/// Bundles the sources of the prefix map fixture and returns the files in the
+/// resulting source bundle, or `None` if no bundle was written.
+fn bundle_prefix_map_sources(args: &[&str]) -> Option<Vec<String>> {
+    let output = tempfile::tempdir().expect("Failed to create temp dir.");
+
+    Command::cargo_bin("sentry-cli")
+        .expect("sentry-cli should be available")
+        .args(["debug-files", "bundle-sources", "--output"])
+        .arg(output.path())
+        .args(args)
+        .arg(PREFIX_MAP_FIXTURE)
+        .assert()
+        .success();
+
+    let bundle = File::open(output.path().join("prefix-map.src.zip")).ok()?;
+    let archive = ZipArchive::new(bundle).expect("Failed to read source bundle.");
+    let mut files: Vec<_> = archive.file_names().map(str::to_owned).collect();
+    files.sort();
+    Some(files)
+}
+
+#[test]
+fn command_debug_files_bundle_sources_without_prefix_map() {
+    assert_eq!(bundle_prefix_map_sources(&[]), None);
+}
+
+#[test]
+fn command_debug_files_bundle_sources_prefix_map() {
+    assert_eq!(
+        bundle_prefix_map_sources(&[
+            "--prefix-map",
+            "tests/integration/_fixtures/bundle_sources=/build"
+        ]),
+        Some(vec![
+            "files/build/main.c".to_owned(),
+            "files/build/vendor/vendored.h".to_owned(),
+            "manifest.json".to_owned(),
+        ])
+    );
+}
+
+#[test]
+fn command_debug_files_bundle_sources_exclude() {
+    assert_eq!(
+        bundle_prefix_map_sources(&[
+            "--prefix-map",
+            "tests/integration/_fixtures/bundle_sources=/build",
+            "--exclude",
+            "**/vendor/**",
+        ]),
+        Some(vec![
+            "files/build/main.c".to_owned(),
+            "manifest.json".to_owned(),
+        ])
+    );
+}
@@ -0,0 +1,154 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+const LIST_ENDPOINT: &str = "/api/0/projects/wat-org/wat-project/files/dsyms/\
+                             ?debug_id=307a5402-9480-8ec2-25f1-a4adc744a991";
+
+#[test]
+fn command_debug_files_delete_by_id() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?id=1",
+            )
+            .with_status(204)
+            .expect(2),
+        )
+        .register_trycmd_test("debug_files/delete/debug_files-delete-yes.trycmd")
+        .register_trycmd_test("debug_files/delete/debug_files-delete-confirmed.toml")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_delete_not_found() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?id=42",
+            )
+            .with_status(404)
+            .with_response_body(r#"{"detail": "Not found"}"#)
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/delete/debug_files-delete-not-found.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_delete_by_debug_id() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", LIST_ENDPOINT)
+                .with_response_file("debug_files/get-difs-by-debug-id.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?id=1",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/delete/debug_files-delete-debug-id.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// A debug ID that only consists of digits is not mistaken for a file ID.
+#[test]
+fn command_debug_files_delete_by_numeric_debug_id() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/\
+                 ?debug_id=30715402-9480-8162-2511-145374474991",
+            )
+            .with_response_file("debug_files/get-difs-by-debug-id.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?id=1",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/delete/debug_files-delete-numeric-debug-id.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Deleting by a debug ID shared by multiple files requires `--all-matching`.
+#[test]
+fn command_debug_files_delete_by_debug_id_multiple() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", LIST_ENDPOINT)
+                .with_response_file("debug_files/get-difs-by-debug-id-multiple.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("DELETE", "/api/0/projects/wat-org/wat-project/files/dsyms/")
+                .expect(0),
+        )
+        .register_trycmd_test("debug_files/delete/debug_files-delete-debug-id-multiple.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_delete_all_matching() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", LIST_ENDPOINT)
+                .with_response_file("debug_files/get-difs-by-debug-id-multiple.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?id=1",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?id=2",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/delete/debug_files-delete-all-matching.toml")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_delete_all_matching_declined() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", LIST_ENDPOINT)
+                .with_response_file("debug_files/get-difs-by-debug-id-multiple.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("DELETE", "/api/0/projects/wat-org/wat-project/files/dsyms/")
+                .expect(0),
+        )
+        .register_trycmd_test("debug_files/delete/debug_files-delete-all-matching-declined.toml")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_delete_help() {
+    TestManager::new().register_trycmd_test("debug_files/delete/debug_files-delete-help.trycmd");
+}
@@ -0,0 +1,33 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_debug_files_find() {
+    TestManager::new()
+        .register_trycmd_test("debug_files/find/debug_files-find.trycmd")
+        .register_trycmd_test("debug_files/find/debug_files-find-missing.trycmd")
+        .register_trycmd_test("debug_files/find/debug_files-find-invalid-id.trycmd");
+}
+
+#[test]
+fn command_debug_files_find_check_server() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=307a5402-9480-8ec2-25f1-a4adc744a991",
+            )
+            .with_response_file("debug_files/get-difs-by-debug-id.json")
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=0000feeb-0000-0000-0000-000000000001",
+            )
+            .with_response_body("[]")
+            .expect(2),
+        )
+        .register_trycmd_test("debug_files/find/debug_files-find-check-server.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -0,0 +1,35 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+const LIST_ENDPOINT: &str = "/api/0/projects/wat-org/wat-project/files/dsyms/\
+                             ?debug_id=307a5402-9480-8ec2-25f1-a4adc744a991";
+
+#[test]
+fn command_debug_files_list() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", LIST_ENDPOINT)
+                .with_response_file("debug_files/get-difs-by-debug-id-multiple.json")
+                .expect(1),
+        )
+        .register_trycmd_test("debug_files/list/debug_files-list.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_list_empty() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", LIST_ENDPOINT)
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .register_trycmd_test("debug_files/list/debug_files-list-empty.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_list_help() {
+    TestManager::new().register_trycmd_test("debug_files/list/debug_files-list-help.trycmd");
+}
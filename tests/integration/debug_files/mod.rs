@@ -1,6 +1,10 @@
 use crate::integration::TestManager;
 
 mod bundle_jvm;
+mod bundle_sources;
+mod delete;
+mod find;
+mod list;
 mod upload;
 
 #[test]
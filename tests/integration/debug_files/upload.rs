@@ -1,6 +1,12 @@
+use std::fs::{self, File};
+
 use assert_cmd::Command;
+use mockito::Matcher;
+use sha1_smol::Sha1;
 
-use crate::integration::{test_utils::env, MockEndpointBuilder, TestManager};
+use crate::integration::{
+    copy_recursively, init_git_repo, test_utils::env, MockEndpointBuilder, TestManager,
+};
 
 #[test]
 fn command_debug_files_upload() {
@@ -43,6 +49,7 @@ fn command_debug_files_upload_pdb() {
         )
         .register_trycmd_test("debug_files/upload/debug_files-upload-pdb.trycmd")
         .register_trycmd_test("debug_files/upload/debug_files-upload-pdb-include-sources.trycmd")
+        .register_trycmd_test("debug_files/upload/debug_files-upload-pdb-type-filter.trycmd")
         .with_default_token();
 }
 
@@ -189,3 +196,1305 @@ fn ensure_correct_assemble_call() {
     manager.assert_mock_endpoints();
     command_result.success();
 }
+
+/// Returns the contents of a file in the `tests/integration/_responses` directory.
+fn response_file(path: &str) -> String {
+    fs::read_to_string(format!("tests/integration/_responses/{path}"))
+        .expect("response file should be readable")
+}
+
+/// Returns an assemble response for the test debug file in the given state.
+fn assemble_response(state: &str) -> String {
+    let missing_chunks = if state == "not_found" {
+        serde_json::json!(["4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8"])
+    } else {
+        serde_json::json!([])
+    };
+    serde_json::json!({
+        "4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8": {
+            "state": state,
+            "missingChunks": missing_chunks,
+        }
+    })
+    .to_string()
+}
+
+/// This test simulates a network interruption during the chunk upload. The first chunk upload
+/// request fails with a transport error, and the failed batch is expected to be sent again.
+#[test]
+fn command_debug_files_upload_retries_interrupted_chunks() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_interrupted_response()
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_sequence(vec![
+                (200, assemble_response("not_found")),
+                (200, response_file("debug_files/post-difs-assemble.json")),
+            ]),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-interrupted.trycmd")
+        .with_default_token()
+        .env("SENTRY_HTTP_MAX_RETRIES", "1")
+        .assert_mock_endpoints();
+}
+
+/// This test simulates a chunk upload that keeps failing after the failed request was
+/// retried. The assemble endpoint is expected to be queried again for the missing chunks,
+/// which are then uploaded once more before the file is processed.
+#[test]
+fn command_debug_files_upload_resumes_interrupted_upload() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_interrupted_response()
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_sequence(vec![
+                (200, assemble_response("not_found")),
+                (200, assemble_response("not_found")),
+                (200, response_file("debug_files/post-difs-assemble.json")),
+            ]),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-resumed.trycmd")
+        .with_default_token()
+        .env("SENTRY_HTTP_MAX_RETRIES", "1")
+        .assert_mock_endpoints();
+}
+
+/// This test ensures that with `--wait`, the assemble endpoint is polled until the file
+/// has finished processing on the server.
+#[test]
+fn command_debug_files_upload_wait_polls_until_processed() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_sequence(vec![
+                (200, assemble_response("not_found")),
+                (200, assemble_response("created")),
+                (200, assemble_response("created")),
+                (200, response_file("debug_files/post-difs-assemble.json")),
+            ]),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-wait.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// This test ensures that `--wait-for` gives up once the timeout is reached and reports
+/// the files which are still being processed.
+#[test]
+fn command_debug_files_upload_wait_for_timeout() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8": {
+                    "state": "not_found",
+                    "missingChunks": ["4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8"]
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8": {
+                    "state": "created",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect_at_least(2),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-wait-for-timeout.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Mocks the upload of a single missing debug information file, asserting that
+/// its chunk is uploaded with the form field of the given compression.
+fn compressed_upload_manager(chunk_upload_response: &str, field_name: &str) -> TestManager {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file(chunk_upload_response),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_matcher(Matcher::Regex(format!(r#"name="{field_name}""#)))
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8": {
+                    "state": "not_found",
+                    "missingChunks": ["4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8"]
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_file("debug_files/post-difs-assemble.json"),
+        )
+}
+
+#[test]
+fn command_debug_files_upload_compression() {
+    compressed_upload_manager("debug_files/get-chunk-upload-brotli.json", "file_brotli")
+        .register_trycmd_test("debug_files/upload/debug_files-upload-compression.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_compression_fallback() {
+    compressed_upload_manager("debug_files/get-chunk-upload.json", "file_gzip")
+        .register_trycmd_test("debug_files/upload/debug_files-upload-compression-fallback.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_il2cpp_line_mapping() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload-il2cpp.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_matcher(Matcher::Regex(
+                    r#""__debug-id__":\{"f21f9625-e2dc-b949-eac9-a722755805b4":\{\}\}"#.to_owned(),
+                ))
+                .with_response_body("[]")
+                .expect(1),
+        )
+        // chunks are uploaded one per request, this one receives the debug file
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJson(serde_json::json!({
+                "797b7804c026cd8103197781528e92bc8ceae074": {
+                    "name": "GameAssembly.debug",
+                    "debug_id": "f21f9625-e2dc-b949-eac9-a722755805b4",
+                },
+                "d6d90c41a054b5ad6487c1a93c5aa7f43e2eafdc": {
+                    "name": "LineNumberMappings.json",
+                    "debug_id": "f21f9625-e2dc-b949-eac9-a722755805b4",
+                },
+            })))
+            .with_response_body(
+                r#"{
+                "797b7804c026cd8103197781528e92bc8ceae074": {
+                    "state": "not_found",
+                    "missingChunks": ["797b7804c026cd8103197781528e92bc8ceae074"]
+                },
+                "d6d90c41a054b5ad6487c1a93c5aa7f43e2eafdc": {
+                    "state": "not_found",
+                    "missingChunks": ["d6d90c41a054b5ad6487c1a93c5aa7f43e2eafdc"]
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "797b7804c026cd8103197781528e92bc8ceae074": {
+                    "state": "ok",
+                    "missingChunks": []
+                },
+                "d6d90c41a054b5ad6487c1a93c5aa7f43e2eafdc": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-il2cpp-line-mapping.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_dart_symbol_map() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload-dart.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_matcher(Matcher::Regex(
+                    r#""__debug-id__":\{"db88f0be-f9b7-c378-6880-f303cfd08c5d":\{\}\},"mapping":\["MyApp","ex""#
+                        .to_owned(),
+                ))
+                .with_response_body("[]")
+                .expect(1),
+        )
+        // chunks are uploaded one per request, this one receives the symbol file
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJson(serde_json::json!({
+                "822c030ee543f9c2eceae2fee0c23f60c4dbeaea": {
+                    "name": "app.android-x64.symbols",
+                    "debug_id": "db88f0be-f9b7-c378-6880-f303cfd08c5d",
+                },
+                "9c7c8902cf5e0343d6e36ec7337b2849c82679a4": {
+                    "name": "obfuscation.map.json",
+                    "debug_id": "db88f0be-f9b7-c378-6880-f303cfd08c5d",
+                },
+            })))
+            .with_response_body(
+                r#"{
+                "822c030ee543f9c2eceae2fee0c23f60c4dbeaea": {
+                    "state": "not_found",
+                    "missingChunks": ["822c030ee543f9c2eceae2fee0c23f60c4dbeaea"]
+                },
+                "9c7c8902cf5e0343d6e36ec7337b2849c82679a4": {
+                    "state": "not_found",
+                    "missingChunks": ["9c7c8902cf5e0343d6e36ec7337b2849c82679a4"]
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "822c030ee543f9c2eceae2fee0c23f60c4dbeaea": {
+                    "state": "ok",
+                    "missingChunks": []
+                },
+                "9c7c8902cf5e0343d6e36ec7337b2849c82679a4": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-dart.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Without server support for Dart symbol maps, only the symbol files are uploaded.
+#[test]
+fn command_debug_files_upload_dart_symbol_map_unsupported() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload-il2cpp.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJson(serde_json::json!({
+                "822c030ee543f9c2eceae2fee0c23f60c4dbeaea": {
+                    "name": "app.android-x64.symbols",
+                },
+            })))
+            .with_response_body(
+                r#"{
+                "822c030ee543f9c2eceae2fee0c23f60c4dbeaea": {
+                    "state": "not_found",
+                    "missingChunks": ["822c030ee543f9c2eceae2fee0c23f60c4dbeaea"]
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "822c030ee543f9c2eceae2fee0c23f60c4dbeaea": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .register_trycmd_test(
+            "debug_files/upload/debug_files-upload-dart-symbol-map-unsupported.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// A WebAssembly module without `build_id` section is uploaded with a `build_id`
+/// section derived from its contents appended, which changes its checksum.
+#[test]
+fn command_debug_files_upload_wasm_without_build_id() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJsonString(
+                r#"{"182eec7bd6d240b3fa1b272d6b43159731a17bd5": {"name": "simple.wasm"}}"#.into(),
+            ))
+            .with_response_body(
+                r#"{
+                "182eec7bd6d240b3fa1b272d6b43159731a17bd5": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-wasm-no-build-id.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Breakpad symbol files are uploaded with `--type breakpad`, while files with a
+/// malformed `MODULE` record are skipped with a warning.
+#[test]
+fn command_debug_files_upload_breakpad() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJsonString(
+                r#"{
+                    "1c0fe10c2b9490c53322801c037ee5ab694e7036": {
+                        "name": "crash.sym",
+                        "debug_id": "3249d99d-0c40-4931-8610-f4e4fb0b6936-1"
+                    },
+                    "bbd4d93f08105050551b2cb1feca540ce7cb3fd5": {
+                        "name": "crash-lowercase.sym",
+                        "debug_id": "3249d99d-0c40-4931-8610-f4e4fb0b6936-1"
+                    }
+                }"#
+                .into(),
+            ))
+            .with_response_body(
+                r#"{
+                "1c0fe10c2b9490c53322801c037ee5ab694e7036": {
+                    "state": "ok",
+                    "missingChunks": []
+                },
+                "bbd4d93f08105050551b2cb1feca540ce7cb3fd5": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-breakpad.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// This test ensures that `--progress json` reports the chunk upload as newline-delimited
+/// JSON events on stderr.
+#[test]
+fn command_debug_files_upload_progress_json() {
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8": {
+                    "state": "not_found",
+                    "missingChunks": ["4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8"]
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_file("debug_files/post-difs-assemble.json"),
+        );
+
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+
+    command.args(
+        "debug-files upload --progress json tests/integration/_fixtures/elf-Linux-ARMv7-ls"
+            .split(' '),
+    );
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    let output = command.output().expect("sentry-cli should run");
+
+    manager.assert_mock_endpoints();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).expect("stderr should be valid UTF-8");
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).expect("progress event should be valid JSON"))
+        .filter(|event: &serde_json::Value| event["phase"] == "chunk_upload")
+        .collect();
+
+    assert!(
+        !events.is_empty(),
+        "no chunk upload progress events in {stderr}"
+    );
+    for event in &events {
+        let sent = event["sent"].as_u64().expect("sent should be a number");
+        let total = event["total"].as_u64().expect("total should be a number");
+        assert!(total > 0);
+        assert!(sent <= total);
+    }
+}
+
+/// Mocks the lookup of the ELF fixture, which is known to the server.
+fn mock_known_elf(manager: TestManager) -> TestManager {
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=307a5402-9480-8ec2-25f1-a4adc744a991",
+            )
+            .with_response_file("debug_files/get-difs-by-debug-id.json")
+            .expect(1),
+        )
+}
+
+/// This test ensures that with `--skip-known`, files which are already known to the
+/// server are not uploaded, while the chunks of the unknown file are.
+#[test]
+fn command_debug_files_upload_skip_known() {
+    mock_known_elf(TestManager::new())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=1f4d3b7a-9c2e-5f60-81a2-b3c4d5e6f708",
+            )
+            .with_response_body("[]")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJsonString(
+                r#"{"4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8": {}}"#.into(),
+            ))
+            .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJsonString(
+                r#"{"20e7a5516f17f313dd579221a92e74f7da5055f7": {"name": "simple-build-id.wasm"}}"#
+                    .into(),
+            ))
+            .with_response_body(
+                r#"{
+                "20e7a5516f17f313dd579221a92e74f7da5055f7": {
+                    "state": "not_found",
+                    "missingChunks": ["20e7a5516f17f313dd579221a92e74f7da5055f7"]
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJsonString(
+                r#"{"20e7a5516f17f313dd579221a92e74f7da5055f7": {"name": "simple-build-id.wasm"}}"#
+                    .into(),
+            ))
+            .with_response_body(
+                r#"{
+                "20e7a5516f17f313dd579221a92e74f7da5055f7": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-skip-known.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// This test ensures that no checksums are sent to the server with `--skip-known`
+/// if all files are already known.
+#[test]
+fn command_debug_files_upload_skip_known_all() {
+    mock_known_elf(TestManager::new())
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-skip-known-all.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_dry_run() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-dry-run.trycmd")
+        .register_trycmd_test("debug_files/upload/debug_files-upload-dry-run-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_dry_run_ignore_file() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-dry-run-ignore-file.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_xcode_auto_discover() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json")
+                .expect(3),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-xcode-auto-discover.trycmd")
+        .register_trycmd_test(
+            "debug_files/upload/debug_files-upload-xcode-auto-discover-no-paths.trycmd",
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-xcode-no-auto-discover.trycmd")
+        .with_default_token()
+        .env(
+            "DWARF_DSYM_FOLDER_PATH",
+            "tests/integration/_fixtures/xcode_dsyms/Build/dSYMs",
+        )
+        .env(
+            "BUILT_PRODUCTS_DIR",
+            "tests/integration/_fixtures/xcode_dsyms/Build/Products",
+        )
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_symbol_maps_missing() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-symbol-maps-missing.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_require_symbol_maps() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-require-symbol-maps.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// This test ensures that `--report` writes the outcome of every file, even if
+/// processing one of them fails on the server.
+#[test]
+fn command_debug_files_upload_report() {
+    let report_dir = tempfile::tempdir().unwrap();
+    let report_path = report_dir.path().join("report.json");
+
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "1c0fe10c2b9490c53322801c037ee5ab694e7036": {
+                    "state": "ok",
+                    "missingChunks": []
+                },
+                "bbd4d93f08105050551b2cb1feca540ce7cb3fd5": {
+                    "state": "error",
+                    "missingChunks": [],
+                    "detail": "Invalid symbol file"
+                }
+            }"#,
+            )
+            .expect(2),
+        );
+
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command
+        .args("debug-files upload --type breakpad tests/integration/_fixtures/breakpad".split(' '))
+        .args(["--note", "pipeline 4812", "--meta", "build=4812"])
+        .arg("--report")
+        .arg(&report_path);
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    let output = command.output().expect("sentry-cli should run");
+
+    manager.assert_mock_endpoints();
+    assert!(!output.status.success());
+
+    let report: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&report_path).expect("report should be written"))
+            .expect("report should be valid JSON");
+
+    assert!(report["duration_secs"].is_number());
+    assert!(report.get("error").is_none());
+    assert_eq!(report["note"], "pipeline 4812");
+    assert_eq!(report["meta"], serde_json::json!({ "build": "4812" }));
+
+    let files = report["files"].as_array().expect("files should be a list");
+    assert_eq!(files.len(), 2);
+
+    assert_eq!(
+        files[0],
+        serde_json::json!({
+            "path": "crash-lowercase.sym",
+            "debug_id": "3249d99d-0c40-4931-8610-f4e4fb0b6936-1",
+            "type": "breakpad",
+            "size": files[0]["size"],
+            "checksum": "bbd4d93f08105050551b2cb1feca540ce7cb3fd5",
+            "status": "errored",
+            "message": "Invalid symbol file",
+        })
+    );
+    assert_eq!(
+        files[1],
+        serde_json::json!({
+            "path": "crash.sym",
+            "debug_id": "3249d99d-0c40-4931-8610-f4e4fb0b6936-1",
+            "type": "breakpad",
+            "size": files[1]["size"],
+            "checksum": "1c0fe10c2b9490c53322801c037ee5ab694e7036",
+            "status": "skipped-known",
+        })
+    );
+    assert!(files.iter().all(|file| file["size"].as_u64() > Some(0)));
+}
+
+/// This test ensures that `--watch` uploads a modified file again, and that Ctrl-C
+/// stops watching without an error.
+#[test]
+#[cfg(unix)]
+fn command_debug_files_upload_watch() {
+    use std::io::{BufRead, BufReader};
+    use std::process::{self, Stdio};
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use std::{fs, thread};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("elf-Linux-ARMv7-ls");
+    fs::copy("tests/integration/_fixtures/elf-Linux-ARMv7-ls", &path).unwrap();
+
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_file("debug_files/post-difs-assemble.json")
+            .expect(2),
+        );
+
+    let mut command = process::Command::new(assert_cmd::cargo::cargo_bin("sentry-cli"));
+    command
+        .args("debug-files upload --watch --poll-interval 50".split(' '))
+        .arg(dir.path())
+        .stdout(Stdio::piped());
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    let mut child = command.spawn().expect("sentry-cli should run");
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in stdout.lines() {
+            sender.send(line.unwrap()).ok();
+        }
+    });
+    let wait_for_watching = || loop {
+        let line = lines
+            .recv_timeout(Duration::from_secs(30))
+            .expect("sentry-cli should watch for changes");
+        if line.contains("Watching for changes") {
+            break;
+        }
+    };
+
+    wait_for_watching();
+    // Make sure the modification time differs on filesystems with a coarse resolution
+    thread::sleep(Duration::from_millis(1100));
+    fs::write(&path, fs::read(&path).unwrap()).unwrap();
+    wait_for_watching();
+
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) };
+    let status = child.wait().unwrap();
+
+    manager.assert_mock_endpoints();
+    assert!(status.success());
+}
+
+/// The PDB of one of the PE files is fetched from the symbol server, while the
+/// other one is missing on the server, which only results in a warning.
+#[test]
+fn command_debug_files_upload_symbol_server() {
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/symbols/Sentry.Samples.Console.Basic.pdb/54FDF14A41A1426AA0738185E11A89D61/Sentry.Samples.Console.Basic.pdb",
+            )
+            .with_response_body(
+                std::fs::read("tests/integration/_fixtures/Sentry.Samples.Console.Basic.pdb")
+                    .unwrap(),
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/symbols/Missing.pdb/9A81B7A07C5A4D8EA8B164A0F3C2D5E72/Missing.pdb",
+            )
+            .with_status(404)
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJson(serde_json::json!({
+                "5f81d6becc51980870acc9f6636ab53d26160763": {
+                    "name": "Sentry.Samples.Console.Basic.pdb",
+                }
+            })))
+            .with_response_body(
+                r#"{
+                "5f81d6becc51980870acc9f6636ab53d26160763": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect(1),
+        );
+
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command
+        .args(["debug-files", "upload", "--symbol-server"])
+        .arg(format!("{}/symbols/", manager.server_url()))
+        .arg("tests/integration/_fixtures/symbol_server");
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    let output = command.output().expect("sentry-cli should run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    manager.assert_mock_endpoints();
+    assert!(output.status.success(), "{stderr}");
+    assert!(
+        stdout.contains("Fetching 2 PDBs from symbol servers"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Fetched 1 PDB from symbol servers"),
+        "{stdout}"
+    );
+    assert!(
+        stderr.contains("Could not fetch 1 PDB:")
+            && stderr.contains(
+                "Missing.pdb (9a81b7a0-7c5a-4d8e-a8b1-64a0f3c2d5e7-2): not found on any symbol server"
+            ),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn command_debug_files_upload_fat_macho() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            // Each slice is uploaded on its own, with its own debug id
+            .with_matcher(Matcher::Json(serde_json::json!({
+                "db0be4b705e55a62f1a2213f27d9b556f846138d": {
+                    "name": "libfat.dylib",
+                    "debug_id": "2e9d8e3a-0f5c-3c4b-9f35-7a0ac0c1b001",
+                    "chunks": ["db0be4b705e55a62f1a2213f27d9b556f846138d"],
+                },
+                "4a17a24d1f51e4795141b36defb408dda85f4335": {
+                    "name": "libfat.dylib",
+                    "debug_id": "7c1a9e54-3b8e-3e0e-a1b1-5b4c2d3e4f02",
+                    "chunks": ["4a17a24d1f51e4795141b36defb408dda85f4335"],
+                },
+            })))
+            .with_response_body("{}"),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-fat-macho.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_fat_macho_dry_run() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-fat-macho-dry-run.trycmd")
+        .with_default_token();
+}
+
+/// This test uploads a large sparse file in chunks and ensures that its checksums are computed
+/// correctly.
+#[test]
+fn command_debug_files_upload_large_file() {
+    const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+    const CONCURRENCY: u64 = 2;
+
+    let dir = tempfile::tempdir().expect("tempdir should be created");
+    let path = dir.path().join("large-ls");
+    fs::copy("tests/integration/_fixtures/elf-Linux-ARMv7-ls", &path)
+        .expect("fixture should be copied");
+    File::options()
+        .write(true)
+        .open(&path)
+        .and_then(|file| file.set_len(64 * 1024 * 1024))
+        .expect("file should be extended");
+
+    let data = fs::read(&path).expect("file should be readable");
+    let checksum = Sha1::from(&data).digest().to_string();
+    let chunks: Vec<_> = data
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| Sha1::from(chunk).digest().to_string())
+        .collect();
+    drop(data);
+
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(format!(
+                    r#"{{
+                        "url": "organizations/wat-org/chunk-upload/",
+                        "chunkSize": {CHUNK_SIZE},
+                        "chunksPerRequest": 1,
+                        "maxFileSize": 2147483648,
+                        "maxRequestSize": 33554432,
+                        "concurrency": {CONCURRENCY},
+                        "hashAlgorithm": "sha1",
+                        "compression": ["gzip"],
+                        "accept": ["debug_files"]
+                    }}"#
+                )),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(chunks.len()),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_matcher(Matcher::PartialJson(serde_json::json!({
+                checksum.clone(): { "chunks": chunks },
+            })))
+            .with_response_sequence(vec![
+                (
+                    200,
+                    serde_json::json!({
+                        checksum.clone(): { "state": "not_found", "missingChunks": chunks },
+                    })
+                    .to_string(),
+                ),
+                (
+                    200,
+                    serde_json::json!({
+                        checksum: { "state": "ok", "missingChunks": [] },
+                    })
+                    .to_string(),
+                ),
+            ]),
+        );
+
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command.args(["debug-files", "upload"]).arg(&path);
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    let command_result = command.assert();
+    manager.assert_mock_endpoints();
+    command_result.success();
+}
+
+#[test]
+fn command_debug_files_upload_print_limits() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json")
+                .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-print-limits.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Limits the server does not advertise, or advertises as zero, fall back to defaults.
+#[test]
+fn command_debug_files_upload_print_limits_defaults() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload-no-limits.json")
+                .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-print-limits-defaults.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Files exceeding the advertised `maxFileSize` are skipped before any chunk is uploaded.
+#[test]
+fn command_debug_files_upload_skips_files_over_max_file_size() {
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(
+                    r#"{
+                        "url": "organizations/wat-org/chunk-upload/",
+                        "chunkSize": 8388608,
+                        "chunksPerRequest": 64,
+                        "maxFileSize": 1024,
+                        "maxRequestSize": 33554432,
+                        "concurrency": 8,
+                        "hashAlgorithm": "sha1",
+                        "accept": ["debug_files"]
+                    }"#,
+                ),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .expect(0),
+        );
+
+    let dir = tempfile::tempdir().expect("tempdir should be created");
+    let log_file = dir.path().join("sentry-cli.log");
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command
+        .args(["debug-files", "upload", "--log-file"])
+        .arg(&log_file)
+        .arg("tests/integration/_fixtures/elf-Linux-ARMv7-ls");
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    let command_result = command.assert();
+    manager.assert_mock_endpoints();
+    command_result.success();
+
+    let log = fs::read_to_string(&log_file).expect("log file should be written");
+    assert!(
+        log.contains("Skipping debug file elf-Linux-ARMv7-ls (")
+            && log.contains("), since it exceeds the maximum file size of 1.00KB"),
+        "{log}"
+    );
+}
+
+/// Tests that `--release-from-git` records the release detected from the git
+/// repository in the upload report.
+#[test]
+fn command_debug_files_upload_release_from_git() {
+    let repo = tempfile::tempdir().unwrap();
+    copy_recursively("tests/integration/_fixtures/breakpad", repo.path()).unwrap();
+    let commit = init_git_repo(repo.path());
+    let report_path = repo.path().join("report.json");
+
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "1c0fe10c2b9490c53322801c037ee5ab694e7036": {
+                    "state": "ok",
+                    "missingChunks": []
+                },
+                "bbd4d93f08105050551b2cb1feca540ce7cb3fd5": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            ),
+        );
+
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command
+        .current_dir(repo.path())
+        .args([
+            "debug-files",
+            "upload",
+            "--type",
+            "breakpad",
+            "--release-from-git",
+        ])
+        .arg("--report")
+        .arg(&report_path)
+        .arg(".");
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+    for var in env::RELEASE_DETECTION_VARS {
+        command.env_remove(var);
+    }
+
+    let output = command.output().expect("sentry-cli should run");
+    manager.assert_mock_endpoints();
+    assert!(output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&format!("> Using release {commit}")));
+
+    let report: serde_json::Value =
+        serde_json::from_slice(&fs::read(&report_path).expect("report should be written"))
+            .expect("report should be valid JSON");
+    assert_eq!(report["release"], commit.as_str());
+}
@@ -13,3 +13,17 @@ fn command_deploys_list() {
         .register_trycmd_test("deploys/deploys-list.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_deploys_list_json() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .with_response_file("deploys/get-deploys.json"),
+        )
+        .register_trycmd_test("deploys/deploys-list-json.trycmd")
+        .with_default_token();
+}
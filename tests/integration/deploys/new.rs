@@ -1,7 +1,9 @@
+use std::fs::{self, remove_dir_all};
+
 use mockito::Matcher;
 use serde_json::json;
 
-use crate::integration::{MockEndpointBuilder, TestManager};
+use crate::integration::{init_git_repo, MockEndpointBuilder, TestManager};
 
 #[test]
 fn command_deploys_new() {
@@ -21,6 +23,62 @@ fn command_deploys_new() {
         .with_default_token();
 }
 
+#[test]
+fn command_deploys_new_sanitize() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/releases/feature-foo/deploys/",
+            )
+            .with_response_file("deploys/post-deploys.json")
+            .with_matcher(Matcher::PartialJson(json!({
+                "environment": "production",
+            })))
+            .expect(1),
+        )
+        .register_trycmd_test("deploys/deploys-new-sanitize.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_deploys_new_started_finished() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .with_response_file("deploys/post-deploys.json")
+            .with_matcher(Matcher::PartialJson(json!({
+                "environment": "production",
+                "dateStarted": "2024-01-15T08:00:00Z",
+                "dateFinished": "2024-01-15T08:30:00Z",
+            })))
+            .expect(1),
+        )
+        .register_trycmd_test("deploys/deploys-new-started-finished.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_deploys_new_finished_before_started() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("deploys/deploys-new-finished-before-started.trycmd")
+        .register_trycmd_test("deploys/deploys-new-time-conflicts.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
 #[test]
 fn command_releases_deploys_new() {
     TestManager::new()
@@ -38,3 +96,86 @@ fn command_releases_deploys_new() {
         .register_trycmd_test("releases/releases-deploys-new.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_deploys_new_idempotency_key_existing() {
+    // A retried step finds the deploy created by the first attempt.
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .with_response_file("deploys/get-deploys-idempotency-key.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("deploys/deploys-new-idempotency-key-existing.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_deploys_new_idempotency_key() {
+    // Deploys with the same name to other environments do not count.
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .with_response_file("deploys/get-deploys-idempotency-key.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .with_response_file("deploys/post-deploys-idempotency-key.json")
+            .with_header_matcher("idempotency-key", "run-43")
+            .with_matcher(Matcher::PartialJson(json!({
+                "environment": "production",
+                "name": "other-deploy",
+            })))
+            .expect(1),
+        )
+        .register_trycmd_test("deploys/deploys-new-idempotency-key.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_deploys_new_release_from_git() {
+    let testcase_cwd_path = "tests/integration/_cases/deploys/deploys-new-release-from-git.in/";
+    if std::path::Path::new(testcase_cwd_path).exists() {
+        remove_dir_all(testcase_cwd_path).unwrap();
+    }
+    fs::create_dir_all(testcase_cwd_path).unwrap();
+    fs::write(format!("{testcase_cwd_path}README.md"), "# Test\n").unwrap();
+    assert_eq!(
+        init_git_repo(testcase_cwd_path),
+        "8f35fa0dd5029d3a78b199c13460b1ccd1d3569a"
+    );
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/releases/8f35fa0dd5029d3a78b199c13460b1ccd1d3569a/deploys/",
+            )
+            .with_response_file("deploys/post-deploys.json")
+            .with_matcher(Matcher::PartialJson(json!({
+                "environment": "production",
+            }))),
+        )
+        .register_trycmd_test("deploys/deploys-new-release-from-git.toml")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -1,5 +1,28 @@
 use crate::integration::{MockEndpointBuilder, TestManager};
 
+/// Mocks both pages of the events list, with the first page linking to the second.
+fn paginated_events_manager() -> TestManager {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-project/events/?cursor=")
+                .with_response_header(
+                    "link",
+                    "<http://localhost/api/0/projects/wat-org/wat-project/events/?cursor=page-2>; \
+                     rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+                )
+                .with_response_file("events/get-events.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/events/?cursor=page-2",
+            )
+            .with_response_file("events/get-events-page-2.json")
+            .expect(1),
+        )
+}
+
 #[test]
 fn command_events() {
     TestManager::new()
@@ -12,3 +35,47 @@ fn command_events() {
         .register_trycmd_test("events/*.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_events_list_paginated() {
+    paginated_events_manager()
+        .register_trycmd_test("events/list/events-list-paginated.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_events_list_fields() {
+    paginated_events_manager()
+        .register_trycmd_test("events/list/events-list-fields.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_events_list_fields_csv() {
+    paginated_events_manager()
+        .register_trycmd_test("events/list/events-list-fields-csv.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_events_list_fields_json() {
+    paginated_events_manager()
+        .register_trycmd_test("events/list/events-list-fields-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_events_list_unknown_field() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-project/events/?cursor=")
+                .expect(0),
+        )
+        .register_trycmd_test("events/list/events-list-unknown-field.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -0,0 +1,84 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+const DEPLOYS_ENDPOINT: &str = "/api/0/organizations/wat-org/releases/wat-release/deploys/";
+
+#[test]
+fn command_exit_code_unauthorized() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", DEPLOYS_ENDPOINT)
+                .with_status(401)
+                .with_response_body(r#"{"detail":"Invalid token"}"#)
+                .expect(2),
+        )
+        .register_trycmd_test("exit_codes/unauthorized/*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_exit_code_forbidden() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(403)
+                .with_response_body(
+                    r#"{"detail":"You do not have permission to perform this action."}"#,
+                )
+                .expect(1),
+        )
+        .register_trycmd_test("exit_codes/forbidden/*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_exit_code_no_token() {
+    TestManager::new().register_trycmd_test("exit_codes/exit_codes-no-token.trycmd");
+}
+
+#[test]
+fn command_exit_code_invalid_argument_allow_failure() {
+    TestManager::new()
+        .register_trycmd_test("exit_codes/exit_codes-invalid-argument-allow-failure.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_exit_code_server_error() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", DEPLOYS_ENDPOINT)
+                .with_status(500)
+                .expect(2),
+        )
+        .register_trycmd_test("exit_codes/server_error/*.trycmd")
+        .with_default_token()
+        .env("SENTRY_HTTP_MAX_RETRIES", "0")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_exit_code_server_error_allow_failure_env() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", DEPLOYS_ENDPOINT)
+                .with_status(500)
+                .expect(1),
+        )
+        .register_trycmd_test("exit_codes/server_error_env/*.trycmd")
+        .with_default_token()
+        .env("SENTRY_HTTP_MAX_RETRIES", "0")
+        .env("SENTRY_ALLOW_FAILURE", "1")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_exit_code_connection_refused() {
+    TestManager::new()
+        .register_trycmd_test("exit_codes/connection_refused/*.trycmd")
+        .with_default_token()
+        // nothing listens on the discard port
+        .env("SENTRY_URL", "http://127.0.0.1:9")
+        .env("SENTRY_HTTP_MAX_RETRIES", "0");
+}
@@ -0,0 +1,173 @@
+use assert_cmd::Command;
+use mockito::Matcher;
+
+use crate::integration::{test_utils::env, MockEndpointBuilder, TestManager};
+
+const HEADER_VALUE: &str = "s3cr3t-proxy-credential";
+
+/// Runs `sentry-cli` with the default test environment and the given arguments.
+fn run_sentry_cli(manager: &TestManager, args: &[&str], envs: &[(&str, &str)]) -> Command {
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command.args(args);
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+    for (k, v) in envs {
+        command.env(k, v);
+    }
+
+    command
+}
+
+#[test]
+fn header_flag_is_sent_with_api_requests() {
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", "/api/0/")
+            .with_header_matcher("x-company-auth", Matcher::Exact(HEADER_VALUE.into()))
+            .with_response_file("info/get-info.json")
+            .expect(1),
+    );
+
+    run_sentry_cli(
+        &manager,
+        &[
+            "info",
+            "--header",
+            &format!("X-Company-Auth: {HEADER_VALUE}"),
+        ],
+        &[],
+    )
+    .assert()
+    .success();
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn config_headers_are_sent_with_envelopes() {
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+            .with_header_matcher("x-company-auth", Matcher::Exact("from-config".into()))
+            .expect(1),
+    );
+
+    run_sentry_cli(
+        &manager,
+        &["send-event", "-m", "hello"],
+        &[(
+            "SENTRY_PROPERTIES",
+            "tests/integration/_fixtures/headers/sentry.properties",
+        )],
+    )
+    .assert()
+    .success();
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn header_flag_takes_precedence_over_config_headers() {
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", "/api/0/")
+            .with_header_matcher("x-company-auth", Matcher::Exact(HEADER_VALUE.into()))
+            .with_response_file("info/get-info.json")
+            .expect(1),
+    );
+
+    run_sentry_cli(
+        &manager,
+        &[
+            "info",
+            "--header",
+            &format!("X-Company-Auth: {HEADER_VALUE}"),
+        ],
+        &[(
+            "SENTRY_PROPERTIES",
+            "tests/integration/_fixtures/headers/sentry.properties",
+        )],
+    )
+    .assert()
+    .success();
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn header_flag_cannot_override_authorization() {
+    let manager = TestManager::new();
+
+    let output = run_sentry_cli(
+        &manager,
+        &["info", "--header", "Authorization: Bearer other"],
+        &[],
+    )
+    .assert()
+    .failure()
+    .get_output()
+    .clone();
+    let stderr = String::from_utf8(output.stderr).expect("stderr should be valid UTF-8");
+
+    assert!(
+        stderr.contains("Refusing to override the Authorization header"),
+        "unexpected error output: {stderr}"
+    );
+}
+
+#[test]
+fn header_flag_overrides_authorization_when_allowed() {
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", "/api/0/")
+            .with_header_matcher("authorization", Matcher::Exact("Bearer other".into()))
+            .with_response_file("info/get-info.json")
+            .expect(1),
+    );
+
+    run_sentry_cli(
+        &manager,
+        &[
+            "info",
+            "--header",
+            "Authorization: Bearer other",
+            "--allow-header-override",
+        ],
+        &[],
+    )
+    .assert()
+    .success();
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn header_values_are_redacted_from_debug_log() {
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", "/api/0/")
+            .with_header_matcher("x-company-auth", Matcher::Exact(HEADER_VALUE.into()))
+            .with_response_file("info/get-info.json")
+            .expect(1),
+    );
+
+    let output = run_sentry_cli(
+        &manager,
+        &[
+            "info",
+            "--header",
+            &format!("X-Company-Auth: {HEADER_VALUE}"),
+            "--log-level",
+            "debug",
+        ],
+        &[],
+    )
+    .assert()
+    .success()
+    .get_output()
+    .clone();
+    manager.assert_mock_endpoints();
+    let stderr = String::from_utf8(output.stderr).expect("stderr should be valid UTF-8");
+
+    assert!(
+        stderr.contains("X-Company-Auth: [REDACTED]"),
+        "missing redacted header in {stderr}"
+    );
+    assert!(
+        !stderr.contains(HEADER_VALUE),
+        "header value leaked into the debug log: {stderr}"
+    );
+}
@@ -12,6 +12,7 @@ fn command_info_no_token() {
     // Special case where we don't want any env variables set, so we don't use `TestManager`.
     TestCases::new()
         .env("SENTRY_INTEGRATION_TEST", "1")
+        .env("RUST_BACKTRACE", "0")
         .case("tests/integration/_cases/info/info-no-token.trycmd");
 }
 
@@ -73,3 +74,41 @@ fn command_info_json_without_defaults() {
         .with_server_var()
         .expect("Failed to set server variable");
 }
+
+#[test]
+fn command_info_scopes_org_token() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/")
+                .with_response_file("info/get-info-org-token.json"),
+        )
+        .register_trycmd_test("info/info-scopes-org-token.trycmd")
+        .with_default_token()
+        .with_server_var()
+        .expect("Failed to set server variable");
+}
+
+#[test]
+fn command_info_scopes_admin() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/")
+                .with_response_file("info/get-info-admin.json"),
+        )
+        .register_trycmd_test("info/info-scopes-admin.trycmd")
+        .with_default_token()
+        .with_server_var()
+        .expect("Failed to set server variable");
+}
+
+#[test]
+fn command_info_format_json() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/").with_response_file("info/get-info.json"),
+        )
+        .register_trycmd_test("info/info-format-json.trycmd")
+        .with_default_token()
+        .with_server_var()
+        .expect("Failed to set server variable");
+}
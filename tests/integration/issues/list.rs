@@ -1,5 +1,43 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use mockito::Matcher;
+
 use crate::integration::{MockEndpointBuilder, TestManager};
 
+const ISSUES_PATH: &str = "/api/0/projects/wat-org/wat-project/issues/";
+const FIRST_PAGE: &str = "/api/0/projects/wat-org/wat-project/issues/?query=&cursor=";
+const SECOND_PAGE: &str = "/api/0/projects/wat-org/wat-project/issues/?query=&cursor=page-2";
+
+/// Mocks both pages of the issues list as a response sequence, with the first
+/// page linking to the second. The requested pages are recorded in `requested`.
+fn paginated_issues_manager(hits: usize, requested: &Arc<Mutex<Vec<String>>>) -> TestManager {
+    let page = |path| fs::read_to_string(format!("tests/integration/_responses/{path}")).unwrap();
+    let requested = requested.clone();
+
+    TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", ISSUES_PATH)
+            .with_query_matcher(Matcher::Any)
+            .with_response_sequence(vec![
+                (200, page("issues/get-issues.json")),
+                (200, page("issues/get-issues-page-2.json")),
+            ])
+            .with_sequence_response_header(
+                0,
+                "link",
+                "<http://localhost/api/0/projects/wat-org/wat-project/issues/?cursor=page-2>; \
+                 rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+            )
+            .with_request_inspector(move |request| {
+                requested
+                    .lock()
+                    .unwrap()
+                    .push(request.path_and_query().to_owned());
+            })
+            .expect(hits),
+    )
+}
+
 #[test]
 fn command_issues_list_help() {
     TestManager::new().register_trycmd_test("issues/issues-list-help.trycmd");
@@ -46,3 +84,41 @@ fn display_resolved_issues() {
         .register_trycmd_test("issues/issues-display-with-query.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn display_issues_from_all_pages() {
+    let requested = Arc::default();
+    paginated_issues_manager(2, &requested)
+        .register_trycmd_test("issues/issues-list-paginated.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+
+    assert_eq!(*requested.lock().unwrap(), [FIRST_PAGE, SECOND_PAGE]);
+}
+
+#[test]
+fn stops_paginating_at_max_rows() {
+    let requested = Arc::default();
+    paginated_issues_manager(1, &requested)
+        .register_trycmd_test("issues/issues-list-max-rows.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+
+    assert_eq!(*requested.lock().unwrap(), [FIRST_PAGE]);
+}
+
+#[test]
+fn display_issues_with_status_as_json() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/issues/?query=is:resolved&cursor=",
+            )
+            .with_response_file("issues/get-resolved-issues.json")
+            .expect(1),
+        )
+        .register_trycmd_test("issues/issues-list-status-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -1,6 +1,120 @@
-use crate::integration::TestManager;
+use mockito::Matcher;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
 
 #[test]
 fn command_login() {
     TestManager::new().register_trycmd_test("login/*.trycmd");
 }
+
+fn auth_check_manager(status: usize, response_file: &str) -> TestManager {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/")
+                .with_status(status)
+                .with_response_file(response_file)
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/users/me/regions/")
+                .with_status(404)
+                .expect(if status == 200 { 1 } else { 0 }),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/?cursor=")
+                .with_response_file("organizations/get-organizations.json")
+                .expect(if status == 200 { 1 } else { 0 }),
+        )
+}
+
+#[test]
+fn command_login_validate_only() {
+    auth_check_manager(200, "info/get-info.json")
+        .register_trycmd_test("login/validate/login-validate-only.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_login_validate_only_rejected() {
+    auth_check_manager(401, "login/get-auth-unauthorized.json")
+        .register_trycmd_test("login/validate/login-validate-only-rejected.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_login_validate_only_unreachable() {
+    TestManager::new()
+        .register_trycmd_test("login/validate/login-validate-only-unreachable.trycmd")
+        .with_default_token()
+        .env("SENTRY_URL", "http://127.0.0.1:1");
+}
+
+#[test]
+fn command_login_token_from_stdin() {
+    auth_check_manager(200, "info/get-info.json")
+        .register_trycmd_test("login/stdin/login-token-from-stdin.toml")
+        .assert_mock_endpoints();
+}
+
+fn device_code_manager(response_file: &str) -> TestManager {
+    TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("POST", "/oauth/device/code/")
+            .with_matcher(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("client_id".into(), "sentry-cli".into()),
+                Matcher::Regex("scope=".into()),
+            ]))
+            .with_response_file(response_file)
+            .expect(1),
+    )
+}
+
+#[test]
+fn command_login_device() {
+    device_code_manager("login/post-device-code.json")
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/oauth/token/")
+                .with_status(400)
+                .with_response_body(r#"{"error":"authorization_pending"}"#)
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/oauth/token/")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::UrlEncoded(
+                        "grant_type".into(),
+                        "urn:ietf:params:oauth:grant-type:device_code".into(),
+                    ),
+                    Matcher::UrlEncoded(
+                        "device_code".into(),
+                        "GmRhmhcxhwAzkoEqiMEg_DnyEysNkuNhszIySk9eS".into(),
+                    ),
+                ]))
+                .with_response_file("login/post-token.json")
+                .expect(1),
+        )
+        .register_trycmd_test("login/device/login-device.toml")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_login_device_denied() {
+    device_code_manager("login/post-device-code.json")
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/oauth/token/")
+                .with_status(400)
+                .with_response_body(r#"{"error":"access_denied"}"#)
+                .expect(1),
+        )
+        .register_trycmd_test("login/device/login-device-denied.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_login_device_expired() {
+    device_code_manager("login/post-device-code-expired.json")
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/oauth/token/").expect(0))
+        .register_trycmd_test("login/device/login-device-expired.trycmd")
+        .assert_mock_endpoints();
+}
@@ -1,24 +1,34 @@
+mod api;
+mod auth_token;
 mod bash_hook;
+mod completions;
 mod debug_files;
 mod deploys;
 mod events;
+mod exit_codes;
+mod headers;
 mod help;
 mod info;
 mod invalid_env;
 mod issues;
+mod log_file;
 mod login;
 mod monitors;
 mod org_tokens;
 mod organizations;
+mod powershell_hook;
 mod projects;
-#[cfg(target_os = "macos")]
+mod proxy;
 mod react_native;
 mod releases;
+mod repos;
 mod send_envelope;
 mod send_event;
 mod send_metric;
+mod send_queued;
 mod sourcemaps;
 mod test_utils;
+mod timeout;
 mod token_validation;
 mod uninstall;
 mod update;
@@ -51,6 +61,31 @@ pub fn copy_recursively(source: impl AsRef<Path>, destination: impl AsRef<Path>)
     Ok(())
 }
 
+/// Initializes a git repository in `path` and commits all files in it.
+///
+/// The commit has a fixed author and date, so its id is always the same for
+/// the same files. Returns the id of the commit.
+pub fn init_git_repo(path: impl AsRef<Path>) -> String {
+    let repo = git2::Repository::init(path).unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature =
+        git2::Signature::new("Test", "test@example.com", &git2::Time::new(1700000000, 0)).unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Initial commit",
+        &tree,
+        &[],
+    )
+    .unwrap()
+    .to_string()
+}
+
 #[test]
 pub fn token_redacted() {
     TestManager::new().register_trycmd_test("token-redacted.trycmd");
@@ -0,0 +1,54 @@
+use mockito::Matcher;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_monitors_check_in_help() {
+    TestManager::new().register_trycmd_test("monitors/check_in/monitors-check-in-help.trycmd");
+}
+
+#[test]
+fn command_monitors_check_in_in_progress() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::Regex(r#""type":"check_in""#.into()),
+                    Matcher::Regex(r#""check_in_id":"[0-9a-f]{32}""#.into()),
+                    Matcher::Regex(r#""monitor_slug":"foo-monitor""#.into()),
+                    Matcher::Regex(r#""status":"in_progress""#.into()),
+                    Matcher::Regex(r#""environment":"production""#.into()),
+                ]))
+                .expect(1),
+        )
+        .register_trycmd_test("monitors/check_in/monitors-check-in-in-progress.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_monitors_check_in_close_with_error_and_duration() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::Regex(r#""check_in_id":"4a7d9d9e0b0c4a479d5803e1f35ea2f3""#.into()),
+                    Matcher::Regex(r#""status":"error""#.into()),
+                    Matcher::Regex(r#""duration":12.5"#.into()),
+                    Matcher::Regex(r#""environment":"staging""#.into()),
+                ]))
+                .expect(1),
+        )
+        .register_trycmd_test("monitors/check_in/monitors-check-in-close.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_monitors_check_in_unknown_check_in() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_status(400)
+                .with_response_body(r#"{"detail":"unknown check-in"}"#),
+        )
+        .register_trycmd_test("monitors/check_in/monitors-check-in-unknown.trycmd");
+}
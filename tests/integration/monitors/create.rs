@@ -0,0 +1,63 @@
+use mockito::Matcher;
+use serde_json::json;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_monitors_create_help() {
+    TestManager::new().register_trycmd_test("monitors/create/monitors-create-help.trycmd");
+}
+
+#[test]
+fn command_monitors_create_updates_existing_monitor() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/monitors/foo-monitor/")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "project": "wat-project",
+                    "name": "foo-monitor",
+                    "slug": "foo-monitor",
+                    "type": "cron_job",
+                    "config": {
+                        "schedule_type": "crontab",
+                        "schedule": "*/5 * * * *",
+                        "checkin_margin": 5,
+                        "max_runtime": 30,
+                    },
+                })))
+                .with_response_file("monitors/put-monitor.json"),
+        )
+        .register_trycmd_test("monitors/create/monitors-create.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_monitors_create_new_monitor() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/monitors/baz-monitor/")
+                .with_status(404)
+                .with_response_body(r#"{"detail": "The requested resource does not exist"}"#),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/monitors/")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "name": "Baz Monitor",
+                    "slug": "baz-monitor",
+                    "config": {
+                        "schedule_type": "interval",
+                        "schedule": [10, "minute"],
+                    },
+                })))
+                .with_response_file("monitors/post-monitor.json"),
+        )
+        .register_trycmd_test("monitors/create/monitors-create-interval.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_monitors_create_invalid_interval() {
+    TestManager::new()
+        .register_trycmd_test("monitors/create/monitors-create-invalid-interval.trycmd")
+        .with_default_token();
+}
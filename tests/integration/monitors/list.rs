@@ -0,0 +1,41 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+/// Mocks both pages of the monitors list, with the first page linking to the second.
+fn paginated_monitors_manager() -> TestManager {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/monitors/?cursor=")
+                .with_response_header(
+                    "link",
+                    "<http://localhost/api/0/organizations/wat-org/monitors/?cursor=page-2>; \
+                     rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+                )
+                .with_response_file("monitors/get-monitors.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/monitors/?cursor=page-2",
+            )
+            .with_response_file("monitors/get-monitors-page-2.json"),
+        )
+}
+
+#[test]
+fn command_monitors_list_help() {
+    TestManager::new().register_trycmd_test("monitors/list/monitors-list-help.trycmd");
+}
+
+#[test]
+fn command_monitors_list() {
+    paginated_monitors_manager()
+        .register_trycmd_test("monitors/list/monitors-list.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_monitors_list_json() {
+    paginated_monitors_manager()
+        .register_trycmd_test("monitors/list/monitors-list-json.trycmd")
+        .with_default_token();
+}
@@ -0,0 +1,224 @@
+use std::sync::{Arc, Mutex};
+
+use mockito::Matcher;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+mod check_in;
+mod create;
+mod list;
+
+#[test]
+fn command_monitors() {
+    let manager = TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/"))
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/monitors/foo-monitor/checkins/")
+                .with_response_file("monitors/post-monitors.json"),
+        )
+        .register_trycmd_test("monitors/*.trycmd")
+        .with_default_token();
+
+    #[cfg(not(windows))]
+    manager.register_trycmd_test("monitors/not_windows/*.trycmd");
+
+    #[cfg(windows)]
+    manager.register_trycmd_test("monitors/windows/*.trycmd");
+}
+
+#[test]
+fn command_monitors_run_server_error() {
+    let manager = TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").with_status(500));
+
+    #[cfg(not(windows))]
+    manager.register_trycmd_test("monitors/server_error/monitors-run-server-error.trycmd");
+
+    #[cfg(windows)]
+    manager.register_trycmd_test("monitors/server_error/monitors-run-server-error-win.trycmd");
+}
+
+/// Mocks the envelope endpoint, expecting the two check-ins of a run.
+fn mock_check_in_envelopes(manager: TestManager) -> TestManager {
+    manager.mock_endpoint(
+        MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+            .with_matcher(Matcher::Regex(r#"\{"type":"check_in""#.into()))
+            .expect(2),
+    )
+}
+
+#[test]
+fn command_monitors_run_attach_log() {
+    let manager = mock_check_in_envelopes(TestManager::new()).mock_endpoint(
+        MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+            .with_matcher(Matcher::AllOf(vec![
+                Matcher::Regex(r#"\{"type":"event""#.into()),
+                Matcher::Regex(r#""message":"Command `[^`]*` exited with status 3""#.into()),
+                Matcher::Regex(r#""monitor":\{[^}]*"slug":"foo-monitor""#.into()),
+                Matcher::Regex(r#""message":"Connecting to database""#.into()),
+                Matcher::Regex(r#""message":"Error: connection refused""#.into()),
+            ]))
+            .expect(1),
+    );
+
+    #[cfg(not(windows))]
+    let manager =
+        manager.register_trycmd_test("monitors/attach_log/monitors-run-attach-log.trycmd");
+
+    #[cfg(windows)]
+    let manager =
+        manager.register_trycmd_test("monitors/attach_log/monitors-run-attach-log-win.trycmd");
+
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn command_monitors_run_attach_log_lines() {
+    let manager = mock_check_in_envelopes(TestManager::new()).mock_endpoint(
+        MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+            .with_matcher(Matcher::AllOf(vec![
+                Matcher::Regex(r#"\{"type":"event""#.into()),
+                Matcher::Regex(r#""output":"second\s*\\nthird\s*""#.into()),
+            ]))
+            .expect(1),
+    );
+
+    #[cfg(not(windows))]
+    let manager =
+        manager.register_trycmd_test("monitors/attach_log/monitors-run-attach-log-lines.trycmd");
+
+    #[cfg(windows)]
+    let manager = manager
+        .register_trycmd_test("monitors/attach_log/monitors-run-attach-log-lines-win.trycmd");
+
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn command_monitors_run_attach_log_success() {
+    let manager = mock_check_in_envelopes(TestManager::new());
+
+    #[cfg(not(windows))]
+    let manager =
+        manager.register_trycmd_test("monitors/attach_log/monitors-run-attach-log-success.trycmd");
+
+    #[cfg(windows)]
+    let manager = manager
+        .register_trycmd_test("monitors/attach_log/monitors-run-attach-log-success-win.trycmd");
+
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+#[cfg(not(windows))]
+fn command_monitors_run_env_file() {
+    let manager = mock_check_in_envelopes(TestManager::new())
+        .register_trycmd_test("monitors/env_file/monitors-run-env-file.trycmd");
+
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+#[cfg(not(windows))]
+fn command_monitors_run_env_override() {
+    // The check-ins must still be sent to the configured server, even though
+    // the command gets a different `SENTRY_DSN`.
+    let manager = mock_check_in_envelopes(TestManager::new())
+        .register_trycmd_test("monitors/env_file/monitors-run-env-override.trycmd");
+
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn command_monitors_run_env_file_invalid() {
+    TestManager::new()
+        .register_trycmd_test("monitors/env_file/monitors-run-env-file-invalid.trycmd");
+}
+
+#[test]
+#[cfg(not(windows))]
+fn command_monitors_run_create_if_missing() {
+    TestManager::new()
+        // the monitor config is only sent with the in-progress check-in
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::Regex(r#""status":"in_progress""#.into()),
+                    Matcher::Regex(r#""environment":"staging""#.into()),
+                    Matcher::Regex(
+                        r#""monitor_config":\{"schedule":\{"type":"crontab","value":"0 \* \* \* \*"\}"#
+                            .into(),
+                    ),
+                    Matcher::Regex(r#""timezone":"Europe/Vienna""#.into()),
+                ]))
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::Regex(r#""status":"ok""#.into()),
+                    Matcher::Regex(r#""environment":"staging""#.into()),
+                ]))
+                .expect(1),
+        )
+        .register_trycmd_test("monitors/upsert/monitors-run-create-if-missing.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+#[cfg(not(windows))]
+fn command_monitors_run_create_if_missing_interval() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::Regex(r#""environment":"production""#.into()),
+                    Matcher::Regex(
+                        r#""monitor_config":\{"schedule":\{"type":"interval","value":10,"unit":"minute"\}"#
+                            .into(),
+                    ),
+                ]))
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::Regex(r#""status":"ok""#.into()))
+                .expect(1),
+        )
+        .register_trycmd_test("monitors/upsert/monitors-run-create-if-missing-interval.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+#[cfg(not(windows))]
+fn command_monitors_run_schedule_without_create_if_missing() {
+    let envelopes = Arc::new(Mutex::new(vec![]));
+    let inspected = envelopes.clone();
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_request_inspector(move |request| {
+                    let body = request.body().unwrap();
+                    inspected.lock().unwrap().push(body.clone());
+                })
+                .expect(2),
+        )
+        .register_trycmd_test(
+            "monitors/upsert/monitors-run-schedule-without-create-if-missing.trycmd",
+        )
+        .assert_mock_endpoints();
+
+    let envelopes = envelopes.lock().unwrap();
+    assert!(envelopes
+        .iter()
+        .all(|body| !String::from_utf8_lossy(body).contains("monitor_config")));
+}
+
+#[test]
+fn command_monitors_run_invalid_schedule() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect(0))
+        .register_trycmd_test("monitors/upsert/monitors-run-invalid-crontab.trycmd")
+        .register_trycmd_test("monitors/upsert/monitors-run-create-if-missing-no-schedule.trycmd")
+        .assert_mock_endpoints();
+}
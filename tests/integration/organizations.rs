@@ -26,3 +26,93 @@ fn command_organizations() {
         .register_trycmd_test("organizations/*.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_organizations_list_paginated() {
+    let manager = TestManager::new();
+    let server_url = manager.server_url();
+
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/users/me/regions/")
+                .with_response_body(format!(
+                    r#"{{"regions": [{{"name": "monolith", "url": "{server_url}"}}]}}"#
+                ))
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/?cursor=")
+                .with_response_header(
+                    "link",
+                    "<http://localhost/api/0/organizations/?cursor=page-2>; \
+                     rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+                )
+                .with_response_file("organizations/get-organizations-page-1.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/?cursor=page-2")
+                .with_response_file("organizations/get-organizations-page-2.json")
+                .expect(2),
+        )
+        .register_trycmd_test("organizations/paginated/*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_organizations_tokens_create() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/org-auth-tokens/")
+                .with_status(201)
+                .with_matcher(r#"{"name":"CI deploys 2026-10","scopes":["org:ci"]}"#)
+                .with_response_file("organizations/post-org-auth-token.json"),
+        )
+        .register_trycmd_test("organizations/tokens/organizations-tokens-create.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_organizations_tokens_create_forbidden() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/org-auth-tokens/")
+                .with_status(403)
+                .with_response_body(
+                    r#"{"detail":"You do not have permission to perform this action."}"#,
+                ),
+        )
+        .register_trycmd_test("organizations/tokens/organizations-tokens-create-forbidden.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_organizations_tokens_list() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/org-auth-tokens/")
+                .with_response_file("organizations/get-org-auth-tokens.json"),
+        )
+        .register_trycmd_test("organizations/tokens/organizations-tokens-list.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_organizations_tokens_revoke_not_found() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/organizations/wat-org/org-auth-tokens/9999/",
+            )
+            .with_status(404)
+            .with_response_body(r#"{"detail":"The requested resource does not exist"}"#),
+        )
+        .register_trycmd_test("organizations/tokens/organizations-tokens-revoke-not-found.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
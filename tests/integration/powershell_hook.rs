@@ -0,0 +1,30 @@
+use mockito::Matcher;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_powershell_hook() {
+    TestManager::new().register_trycmd_test("powershell_hook/*.trycmd");
+}
+
+#[test]
+fn command_powershell_hook_send_event() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::Regex(
+                        r#""type":"System.Management.Automation.ItemNotFoundException","value":"Cannot find path 'build' because it does not exist.""#.into(),
+                    ),
+                    Matcher::Regex(
+                        r#""function":"<ScriptBlock>","lineno":1\}.*"function":"Invoke-Deploy".*"function":"Publish-Build","filename":"tests/integration/_fixtures/powershell_hook/deploy.ps1","abs_path":"[^"]+","lineno":5,"pre_context""#.into(),
+                    ),
+                    Matcher::Regex(r#""message":"Deploying to production""#.into()),
+                    Matcher::Regex(r#""tags":\{"stage":"deploy"\}"#.into()),
+                    Matcher::Regex(r#""release":"1.0.0""#.into()),
+                ]))
+                .expect(1),
+        )
+        .register_trycmd_test("powershell_hook/send_event/*.trycmd")
+        .assert_mock_endpoints();
+}
@@ -1,3 +1,8 @@
+use std::sync::{Arc, Mutex};
+
+use mockito::Matcher;
+use serde_json::json;
+
 use crate::integration::{MockEndpointBuilder, TestManager};
 
 #[test]
@@ -11,3 +16,215 @@ fn command_projects_list() {
         .register_trycmd_test("projects/*.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_projects_list_all_orgs() {
+    let manager = TestManager::new();
+    let server_url = manager.server_url();
+
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/users/me/regions/")
+                .with_response_body(format!(
+                    r#"{{"regions": [{{"name": "monolith", "url": "{server_url}"}}]}}"#
+                ))
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/?cursor=")
+                .with_response_header(
+                    "link",
+                    "<http://localhost/api/0/organizations/?cursor=page-2>; \
+                     rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+                )
+                .with_response_file("organizations/get-organizations-page-1.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/?cursor=page-2")
+                .with_response_file("organizations/get-organizations-page-2.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_response_file("projects/get-projects.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/other-org/projects/?cursor=")
+                .with_response_file("projects/get-projects-other-org.json")
+                .expect(2),
+        )
+        .register_trycmd_test("projects/all_orgs/*.trycmd")
+        .with_default_token()
+        // no organization is configured, so the projects of all organizations are listed
+        .env("SENTRY_ORG", "")
+        .env("SENTRY_DSN", "")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_projects_list_retries_rate_limited_requests() {
+    TestManager::new()
+        // the first two requests are rate limited, the third one succeeds
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_status(429)
+                .with_response_header("retry-after", "1")
+                .with_response_body("{\"detail\":\"Rate limit exceeded\"}")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_response_file("projects/get-projects.json")
+                .expect(1),
+        )
+        .register_trycmd_test("projects/rate_limited/*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_projects_list_invalid_max_retries() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .expect(0),
+        )
+        .register_trycmd_test(
+            "projects/invalid_max_retries/projects-list-invalid-max-retries.trycmd",
+        )
+        .env("SENTRY_HTTP_MAX_RETRIES", "many")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_projects_create() {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let created = requests.clone();
+    let keys = requests.clone();
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/teams/wat-org/wat-team/projects/")
+                .with_matcher(Matcher::Json(json!({
+                    "name": "Wat Backend",
+                    "platform": "python",
+                })))
+                .with_request_inspector(move |_| created.lock().unwrap().push("create"))
+                .with_status(201)
+                .with_response_file("projects/post-project.json")
+                .expect(1),
+        )
+        // the keys are looked up by the slug returned from the create request
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-backend/keys/")
+                .with_request_inspector(move |_| keys.lock().unwrap().push("keys"))
+                .with_response_file("projects/get-project-keys.json")
+                .expect(1),
+        )
+        .register_trycmd_test("projects/create/*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+
+    assert_eq!(*requests.lock().unwrap(), ["create", "keys"]);
+}
+
+#[test]
+fn command_projects_create_conflict() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/teams/wat-org/wat-team/projects/")
+                .with_matcher(Matcher::Json(json!({
+                    "name": "Wat Backend",
+                    "slug": "wat-backend",
+                })))
+                .with_status(409)
+                .with_response_body(r#"{"detail":"A project with this slug already exists."}"#)
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-backend/keys/")
+                .with_response_file("projects/get-project-keys.json")
+                .expect(0),
+        )
+        .register_trycmd_test("projects/create_conflict/*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_projects_list_team() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_response_file("projects/get-projects-teams.json")
+                .expect(1),
+        )
+        .register_trycmd_test("projects/team/*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_projects_keys() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-backend/keys/")
+                .with_response_file("projects/get-project-keys.json")
+                .expect(2),
+        )
+        .register_trycmd_test("projects/keys/projects-keys.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_projects_keys_create() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-backend/keys/")
+                .with_matcher(Matcher::Json(json!({ "name": "Rotated" })))
+                .with_status(201)
+                .with_response_file("projects/post-project-key.json")
+                .expect(1),
+        )
+        .register_trycmd_test("projects/keys/projects-keys-create.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_projects_keys_disable() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "PUT",
+                "/api/0/projects/wat-org/wat-backend/keys/a9f1fa5d1d0e41d4aa1a1c0b1dbd2d0c/",
+            )
+            .with_matcher(Matcher::Json(json!({ "isActive": false })))
+            .with_response_file("projects/put-project-key-disabled.json")
+            .expect(1),
+        )
+        .register_trycmd_test("projects/keys/projects-keys-disable.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_projects_keys_disable_default_project() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "PUT",
+                "/api/0/projects/wat-org/wat-project/keys/a9f1fa5d1d0e41d4aa1a1c0b1dbd2d0c/",
+            )
+            .with_matcher(Matcher::Json(json!({ "isActive": false })))
+            .with_response_file("projects/put-project-key-disabled.json")
+            .expect(1),
+        )
+        .register_trycmd_test("projects/keys/projects-keys-disable-default-project.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -0,0 +1,31 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+fn releases_manager() -> TestManager {
+    TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-project/releases/")
+            .with_response_file("releases/get-releases.json"),
+    )
+}
+
+#[test]
+fn command_fails_with_unreachable_proxy() {
+    releases_manager()
+        .register_trycmd_test("proxy/proxy-unreachable.trycmd")
+        .with_default_token()
+        .env(
+            "SENTRY_PROPERTIES",
+            "tests/integration/_fixtures/proxy/proxy.properties",
+        )
+        .env("SENTRY_HTTP_MAX_RETRIES", "0");
+}
+
+#[test]
+fn command_bypasses_proxy_for_no_proxy_hosts() {
+    releases_manager()
+        .register_trycmd_test("proxy/proxy-no-proxy-bypass.trycmd")
+        .with_default_token()
+        .env(
+            "SENTRY_PROPERTIES",
+            "tests/integration/_fixtures/proxy/no-proxy.properties",
+        );
+}
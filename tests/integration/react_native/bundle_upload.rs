@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::Path;
+
+use crate::integration::{copy_recursively, ServerBehavior, TestManager};
+
+/// Copies the bundle fixtures into the working directory of a test case, since
+/// the command injects debug IDs into them.
+fn prepare_fixtures(testcase_cwd_path: &str) {
+    if Path::new(testcase_cwd_path).exists() {
+        fs::remove_dir_all(testcase_cwd_path).unwrap();
+    }
+    copy_recursively(
+        "tests/integration/_fixtures/react_native_bundle/",
+        testcase_cwd_path,
+    )
+    .unwrap();
+}
+
+#[test]
+fn command_react_native_bundle_upload_help() {
+    TestManager::new().register_trycmd_test("react_native/bundle-upload-help.trycmd");
+}
+
+#[test]
+fn command_react_native_bundle_upload() {
+    let testcase_cwd_path = "tests/integration/_cases/react_native/bundle-upload.in/";
+    prepare_fixtures(testcase_cwd_path);
+
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("react_native/bundle-upload.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+
+    let bundle = fs::read_to_string(format!("{testcase_cwd_path}index.android.bundle")).unwrap();
+    let sourcemap =
+        fs::read_to_string(format!("{testcase_cwd_path}index.android.bundle.map")).unwrap();
+    let debug_id = bundle
+        .lines()
+        .find_map(|line| line.strip_prefix("//# debugId="))
+        .expect("bundle has no debug ID");
+    assert!(sourcemap.contains(&format!(r#""debug_id":"{debug_id}""#)));
+}
+
+#[test]
+fn command_react_native_bundle_upload_mismatched_sourcemap() {
+    let testcase_cwd_path =
+        "tests/integration/_cases/react_native/bundle-upload-mismatched-sourcemap.in/";
+    prepare_fixtures(testcase_cwd_path);
+
+    TestManager::new()
+        .register_trycmd_test("react_native/bundle-upload-mismatched-sourcemap.trycmd")
+        .with_default_token();
+
+    // Nothing was injected.
+    assert_eq!(
+        fs::read(format!("{testcase_cwd_path}index.android.bundle")).unwrap(),
+        fs::read("tests/integration/_fixtures/react_native_bundle/index.android.bundle").unwrap()
+    );
+}
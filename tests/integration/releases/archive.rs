@@ -0,0 +1,30 @@
+use mockito::Matcher;
+use serde_json::json;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn successfully_archives_a_release() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_matcher(Matcher::PartialJson(json!({ "status": "archived" })))
+                .with_response_file("releases/get-release-archived.json")
+                .expect(1),
+        )
+        .register_trycmd_test("releases/releases-archive.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn fails_for_nonexisting_release() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_status(404)
+                .with_response_body(r#"{"detail": "The requested resource does not exist"}"#),
+        )
+        .register_trycmd_test("releases/releases-archive-nonexisting.trycmd")
+        .with_default_token();
+}
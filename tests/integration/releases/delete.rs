@@ -56,3 +56,103 @@ fn doesnt_allow_to_delete_active_releases() {
         .register_trycmd_test("releases/releases-delete-active.trycmd")
         .with_default_token();
 }
+
+fn release_files_manager() -> TestManager {
+    TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new(
+            "GET",
+            "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+        )
+        .with_response_file("releases/get-release-files.json")
+        .expect(1),
+    )
+}
+
+#[test]
+fn informs_about_files_blocking_deletion() {
+    release_files_manager()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_status(400)
+            .with_response_file("releases/delete-release-with-files.json")
+            .expect(1),
+        )
+        .register_trycmd_test("releases/releases-delete-with-files.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn force_deletes_files_before_release() {
+    release_files_manager()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_status(400)
+            .with_response_file("releases/delete-release-with-files.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/6796495645/",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/6796495646/",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/files/source-maps/?name=wat-release",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .register_trycmd_test("releases/releases-delete-force.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn force_deletes_release_without_files() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_status(204)
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("releases/releases-delete-force-clean.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -0,0 +1,116 @@
+use mockito::Matcher;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+const FILES_ENDPOINT: &str = "/api/0/projects/wat-org/wat-project/releases/wat-release/files/";
+
+/// Matches the multipart field `name` of a release file upload with the given contents.
+fn form_field(name: &str, contents: &str) -> Matcher {
+    Matcher::Regex(format!(
+        "name=\"{name}\"\r\n\r\n{}\r\n",
+        regex::escape(contents)
+    ))
+}
+
+/// Mocks the endpoints for creating the release of a legacy release file upload.
+fn mock_legacy_upload_endpoints(manager: TestManager) -> TestManager {
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_status(404),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release.json"),
+        )
+}
+
+#[test]
+fn command_releases_files_upload_file_header() {
+    mock_legacy_upload_endpoints(TestManager::new())
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", FILES_ENDPOINT)
+                .with_matcher(Matcher::AllOf(vec![
+                    form_field("name", "~/templates/index.tmpl"),
+                    form_field("header", "Content-Type:text/html; charset=utf-8"),
+                ]))
+                .with_status(201)
+                .with_response_file("releases/post-release-file.json")
+                .expect(1),
+        )
+        .register_trycmd_test("releases/files/releases-files-upload-file-header.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_files_upload_header_manifest() {
+    mock_legacy_upload_endpoints(TestManager::new())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body("[]"),
+        )
+        // The manifest overrides the header of the matching file, with the
+        // later, more specific pattern taking precedence.
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", FILES_ENDPOINT)
+                .with_matcher(Matcher::AllOf(vec![
+                    form_field("name", "~/templates/index.tmpl"),
+                    form_field("header", "Content-Type:text/html; charset=utf-8"),
+                    form_field("header", "X-Test:yes"),
+                ]))
+                .with_status(201)
+                .with_response_file("releases/post-release-file.json")
+                .expect(1),
+        )
+        // Other files only get the headers given with --file-header.
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", FILES_ENDPOINT)
+                .with_matcher(Matcher::AllOf(vec![
+                    form_field("name", "~/static/app.css"),
+                    Matcher::Regex("name=\"header\"\r\n\r\nContent-Type:".into()),
+                ]))
+                .with_status(201)
+                .with_response_file("releases/post-release-file.json")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", FILES_ENDPOINT)
+                .with_matcher(Matcher::AllOf(vec![
+                    form_field("name", "~/static/app.css"),
+                    form_field("header", "X-Test:yes"),
+                ]))
+                .with_status(201)
+                .with_response_file("releases/post-release-file.json")
+                .expect(1),
+        )
+        .register_trycmd_test("releases/files/releases-files-upload-header-manifest.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_files_upload_header_manifest_file() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_status(404),
+        )
+        .register_trycmd_test("releases/files/releases-files-upload-header-manifest-file.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_releases_files_upload_header_manifest_invalid() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_status(404),
+        )
+        .register_trycmd_test("releases/files/releases-files-upload-header-manifest-invalid.trycmd")
+        .with_default_token();
+}
@@ -48,3 +48,56 @@ fn release_with_custom_dates() {
         .register_trycmd_test("releases/releases-finalize-dates.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn release_with_custom_url() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "PUT",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release-with-url.json")
+            .with_matcher(Matcher::PartialJson(json!({
+                "projects": ["wat-project"],
+                "url": "https://ci.example.com/runs/42"
+            }))),
+        )
+        .register_trycmd_test("releases/releases-finalize-url.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn finalizes_release_and_sets_commits() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+                .with_response_file("releases/get-repos.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "refs": [{
+                        "repository": "getsentry/sentry-cli",
+                        "commit": "0123456789abcdef0123456789abcdef01234567"
+                    }]
+                })))
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "PUT",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json")
+            .with_matcher(Matcher::PartialJson(json!({
+                "projects": ["wat-project"],
+            })))
+            .expect(1),
+        )
+        .register_trycmd_test("releases/releases-finalize-with-commit.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -14,6 +14,22 @@ fn shows_release_details() {
         .with_default_token();
 }
 
+#[test]
+fn shows_release_url() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release-with-url.json")
+            .expect(2),
+        )
+        .register_trycmd_test("releases/releases-info-url.trycmd")
+        .register_trycmd_test("releases/releases-info-url-json.trycmd")
+        .with_default_token();
+}
+
 #[test]
 fn shows_release_details_with_projects_and_commits() {
     TestManager::new()
@@ -90,3 +106,132 @@ fn exits_if_no_release_found() {
         .register_trycmd_test("releases/releases-info-not-found.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn shows_release_details_as_json() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json"),
+        )
+        .register_trycmd_test("releases/releases-info-json.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn shows_release_commits_and_deploys() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json")
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/",
+            )
+            .with_response_header(
+                "link",
+                "<http://localhost/api/0/projects/wat-org/wat-project/releases/wat-release/commits/?cursor=page-2>; \
+                 rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+            )
+            .with_response_file("releases/get-release-commits-page-1.json")
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/?cursor=page-2",
+            )
+            .with_response_file("releases/get-release-commits-page-2.json")
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .with_response_file("releases/get-release-deploys.json")
+            .expect(2),
+        )
+        .register_trycmd_test("releases/releases-info-commits-deploys.trycmd")
+        .register_trycmd_test("releases/releases-info-commits-deploys-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn stops_following_commit_pages_at_max_commits() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/",
+            )
+            .with_response_header(
+                "link",
+                "<http://localhost/api/0/projects/wat-org/wat-project/releases/wat-release/commits/?cursor=page-2>; \
+                 rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+            )
+            .with_response_file("releases/get-release-commits-page-1.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/?cursor=page-2",
+            )
+            .with_response_file("releases/get-release-commits-page-2.json")
+            .expect(0),
+        )
+        .register_trycmd_test("releases/releases-info-max-commits.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn shows_empty_release_commits_and_deploys() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json")
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/",
+            )
+            .with_response_body("[]")
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/releases/wat-release/deploys/",
+            )
+            .with_response_body("[]")
+            .expect(2),
+        )
+        .register_trycmd_test("releases/releases-info-empty-commits-deploys.trycmd")
+        .register_trycmd_test("releases/releases-info-empty-commits-deploys-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
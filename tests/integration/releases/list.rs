@@ -65,3 +65,46 @@ fn displays_releases_in_raw_mode_with_delimiter() {
         .register_trycmd_test("releases/releases-list-raw-delimiter.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn displays_releases_as_json() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_response_file("releases/get-releases.json"),
+        )
+        .register_trycmd_test("releases/releases-list-json.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn filters_by_status() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/?status=archived",
+            )
+            .with_response_file("releases/get-releases.json")
+            .expect(1),
+        )
+        .register_trycmd_test("releases/releases-list-status.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn shows_archived_releases() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/?status=archived",
+            )
+            .with_response_file("releases/get-releases.json")
+            .expect(1),
+        )
+        .register_trycmd_test("releases/releases-list-show-archived.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
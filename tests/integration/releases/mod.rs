@@ -1,10 +1,14 @@
 use crate::integration::TestManager;
 
+mod archive;
 mod delete;
+mod files;
 mod finalize;
 mod info;
 mod list;
 mod new;
+mod restore;
+mod set_commits;
 
 #[test]
 fn command_releases_help() {
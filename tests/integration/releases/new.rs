@@ -1,7 +1,9 @@
+use assert_cmd::assert::Assert;
+use assert_cmd::Command;
 use mockito::Matcher;
 use serde_json::json;
 
-use crate::integration::{MockEndpointBuilder, TestManager, UTC_DATE_FORMAT};
+use crate::integration::{test_utils::env, MockEndpointBuilder, TestManager, UTC_DATE_FORMAT};
 
 #[test]
 fn command_releases_new_help() {
@@ -24,6 +26,64 @@ fn creates_release() {
         .with_default_token();
 }
 
+#[test]
+fn creates_release_quietly() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .expect(2),
+        )
+        .register_trycmd_test("releases/releases-new-quiet.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn shows_validation_errors() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(400)
+                .with_response_body(
+                    r#"{"version": ["Release names cannot contain a slash."], "refs": [{"repository": ["This field is required."]}]}"#,
+                ),
+        )
+        .register_trycmd_test("releases/releases-new-validation-error.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn rejects_invalid_version() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .expect(0),
+        )
+        .register_trycmd_test("releases/releases-new-invalid-version.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn creates_release_with_sanitized_version() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "version": "feature-foo",
+                    "projects": ["wat-project"],
+                })))
+                .expect(1),
+        )
+        .register_trycmd_test("releases/releases-new-sanitize.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
 #[test]
 fn allows_for_release_to_start_with_hyphen() {
     TestManager::new()
@@ -123,3 +183,142 @@ fn creates_release_which_is_instantly_finalized() {
         .register_trycmd_test("releases/releases-new-finalize.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn creates_finalized_release_with_custom_url() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release-with-url.json")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::PartialJson(json!({
+                        "version": "wat-release",
+                        "projects": ["wat-project"],
+                        "url": "https://ci.example.com/runs/42"
+                    })),
+                    Matcher::Regex(format!(r#""dateStarted":"{UTC_DATE_FORMAT}""#)),
+                    Matcher::Regex(format!(r#""dateReleased":"{UTC_DATE_FORMAT}""#)),
+                ])),
+        )
+        .register_trycmd_test("releases/releases-new-finalize-url.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn creates_release_and_sets_commits() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "version": "new-release",
+                    "projects": ["wat-project"],
+                })))
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+                .with_response_file("releases/get-repos.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/new-release/")
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "refs": [{
+                        "repository": "getsentry/sentry-cli",
+                        "commit": "0123456789abcdef0123456789abcdef01234567"
+                    }]
+                })))
+                .expect(1),
+        )
+        .register_trycmd_test("releases/releases-new-with-commit.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Runs `sentry-cli releases new new-release` with only the DSN configured,
+/// which refers to project 1337.
+fn releases_new_from_dsn(manager: &TestManager) -> Assert {
+    let mut command = Command::cargo_bin("sentry-cli").unwrap();
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+    command
+        .env_remove("SENTRY_ORG")
+        .env_remove("SENTRY_PROJECT")
+        .args(["releases", "new", "new-release"])
+        .assert()
+}
+
+#[test]
+fn creates_release_with_org_and_project_from_dsn() {
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/?cursor=")
+                .with_response_file("projects/get-all-projects.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/dsn-org/dsn-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "version": "new-release",
+                    "projects": ["dsn-project"],
+                })))
+                .expect(1),
+        );
+
+    let assert = releases_new_from_dsn(&manager).success();
+    manager.assert_mock_endpoints();
+    assert_eq!(
+        String::from_utf8_lossy(&assert.get_output().stdout),
+        "Created release new-release\n"
+    );
+}
+
+#[test]
+fn creates_release_with_explicit_org_and_project_over_dsn() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/?cursor=")
+                .with_response_file("projects/get-all-projects.json")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "version": "new-release",
+                    "projects": ["wat-project"],
+                })))
+                .expect(1),
+        )
+        .register_trycmd_test("releases/releases-new-dsn-explicit.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn fails_to_create_release_if_dsn_project_is_not_accessible() {
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", "/api/0/projects/?cursor=")
+            .with_response_file("projects/get-all-projects-no-access.json")
+            .expect(1),
+    );
+
+    let assert = releases_new_from_dsn(&manager).failure();
+    manager.assert_mock_endpoints();
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(
+        stderr.contains(
+            "error: Could not resolve the project of the DSN in SENTRY_DSN (project id 1337). \
+             Make sure the auth token has access to it, or provide --org and --project."
+        ),
+        "unexpected output: {stderr}"
+    );
+}
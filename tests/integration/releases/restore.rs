@@ -0,0 +1,18 @@
+use mockito::Matcher;
+use serde_json::json;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn successfully_restores_a_release() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_matcher(Matcher::PartialJson(json!({ "status": "open" })))
+                .with_response_file("releases/get-release-open.json")
+                .expect(1),
+        )
+        .register_trycmd_test("releases/releases-restore.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
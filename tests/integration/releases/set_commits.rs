@@ -0,0 +1,306 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use assert_cmd::Command;
+use flate2::read::GzDecoder;
+use mockito::{Matcher, Request};
+use serde_json::{json, Value};
+
+use crate::integration::{test_utils::env, MockEndpointBuilder, TestManager};
+
+fn new_release_endpoint() -> MockEndpointBuilder {
+    MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+        .with_status(201)
+        .with_response_file("releases/get-release.json")
+}
+
+fn repos_endpoint() -> MockEndpointBuilder {
+    MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+        .with_response_file("releases/get-repos-multiple.json")
+        .expect(1)
+}
+
+fn manifest_refs() -> serde_json::Value {
+    json!({
+        "refs": [
+            {
+                "repository": "getsentry/sentry-cli",
+                "commit": "0123456789abcdef0123456789abcdef01234567",
+                "previousCommit": "89abcdef0123456789abcdef0123456789abcdef"
+            },
+            {
+                "repository": "getsentry/sentry",
+                "commit": "fedcba9876543210fedcba9876543210fedcba98"
+            }
+        ]
+    })
+}
+
+/// The number of repositories referenced by the large commits manifest, which
+/// is enough for the request body to be compressed.
+const MANY_REPOS: usize = 200;
+
+fn many_repos_endpoint() -> MockEndpointBuilder {
+    let repos: Vec<_> = (1..=MANY_REPOS)
+        .map(|i| {
+            json!({
+                "id": i.to_string(),
+                "name": format!("getsentry/repo-{i:03}"),
+                "url": format!("https://github.com/getsentry/repo-{i:03}"),
+                "provider": {"id": "integrations:github", "name": "GitHub"},
+                "status": "active",
+                "dateCreated": "2022-02-01T12:00:00.000000Z"
+            })
+        })
+        .collect();
+    MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+        .with_response_body(serde_json::to_vec(&repos).unwrap())
+        .expect(1)
+}
+
+fn large_manifest_refs() -> Value {
+    let refs: Vec<_> = (1..=MANY_REPOS)
+        .map(|i| {
+            json!({
+                "repository": format!("getsentry/repo-{i:03}"),
+                "commit": format!("{i:040x}"),
+                "previousCommit": format!("{:040x}", i + MANY_REPOS)
+            })
+        })
+        .collect();
+    json!({ "refs": refs })
+}
+
+/// Writes the large commits manifest to a file in the given directory.
+fn write_large_manifest(dir: &Path) -> PathBuf {
+    let path = dir.join("commits-manifest-large.json");
+    fs::write(
+        &path,
+        serde_json::to_vec(&large_manifest_refs()["refs"]).unwrap(),
+    )
+    .unwrap();
+    path
+}
+
+/// Runs `releases set-commits` with the large commits manifest.
+fn set_commits_from_large_manifest(manager: &TestManager, extra_env: &[(&str, &str)]) {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = write_large_manifest(dir.path());
+    let mut command = Command::cargo_bin("sentry-cli").unwrap();
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+    command.envs(extra_env.iter().copied());
+    let assert = command
+        .args(["releases", "set-commits", "wat-release", "--from-file"])
+        .arg(manifest)
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    assert!(
+        stdout.contains("| getsentry/repo-200 |"),
+        "unexpected output: {stdout}"
+    );
+}
+
+/// Checks that the request body is gzip compressed JSON containing the given value.
+fn is_gzip_json(request: &Request, expected: &Value) -> bool {
+    let Ok(body) = request.body() else {
+        return false;
+    };
+    let mut json = Vec::new();
+    if GzDecoder::new(body.as_slice())
+        .read_to_end(&mut json)
+        .is_err()
+    {
+        return false;
+    }
+    serde_json::from_slice::<Value>(&json).map_or(false, |body| body["refs"] == expected["refs"])
+}
+
+#[test]
+fn command_releases_set_commits_from_file() {
+    TestManager::new()
+        .mock_endpoint(repos_endpoint())
+        .mock_endpoint(new_release_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(manifest_refs()))
+                .with_header_matcher("content-encoding", Matcher::Missing)
+                .expect(1),
+        )
+        .register_trycmd_test("releases/set_commits/releases-set-commits-from-file.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_from_stdin() {
+    TestManager::new()
+        .mock_endpoint(repos_endpoint())
+        .mock_endpoint(new_release_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(manifest_refs()))
+                .expect(1),
+        )
+        .register_trycmd_test("releases/set_commits/releases-set-commits-from-file-stdin.toml")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_from_file_with_commit() {
+    TestManager::new()
+        .mock_endpoint(repos_endpoint())
+        .mock_endpoint(new_release_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "refs": [
+                        {
+                            "repository": "getsentry/sentry-cli",
+                            "commit": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+                        },
+                        {
+                            "repository": "getsentry/sentry",
+                            "commit": "fedcba9876543210fedcba9876543210fedcba98"
+                        }
+                    ]
+                })))
+                .expect(1),
+        )
+        .register_trycmd_test(
+            "releases/set_commits/releases-set-commits-from-file-with-commit.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_from_file_unknown_repo() {
+    TestManager::new()
+        .mock_endpoint(repos_endpoint())
+        .register_trycmd_test(
+            "releases/set_commits/releases-set-commits-from-file-unknown-repo.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_unknown_repo() {
+    TestManager::new()
+        .mock_endpoint(repos_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .expect(0),
+        )
+        .register_trycmd_test("releases/set_commits/releases-set-commits-unknown-repo.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_ignore_missing_repo() {
+    TestManager::new()
+        .mock_endpoint(repos_endpoint())
+        .mock_endpoint(new_release_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "refs": [
+                        {
+                            "repository": "getsentry/sentry-cli",
+                            "commit": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+                        }
+                    ]
+                })))
+                .expect(1),
+        )
+        .register_trycmd_test(
+            "releases/set_commits/releases-set-commits-ignore-missing-repo.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_all_unknown_repos() {
+    TestManager::new()
+        .mock_endpoint(repos_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .expect(0),
+        )
+        .register_trycmd_test("releases/set_commits/releases-set-commits-all-unknown-repos.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_ignore_missing_all_unknown_repos() {
+    TestManager::new()
+        .mock_endpoint(repos_endpoint())
+        .mock_endpoint(new_release_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .expect(0),
+        )
+        .register_trycmd_test(
+            "releases/set_commits/releases-set-commits-ignore-missing-all-unknown-repos.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_from_file_conflicts_with_auto() {
+    TestManager::new()
+        .register_trycmd_test("releases/set_commits/releases-set-commits-from-file-auto.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_releases_set_commits_compresses_large_payloads() {
+    let expected = large_manifest_refs();
+    let manager = TestManager::new()
+        .mock_endpoint(many_repos_endpoint())
+        .mock_endpoint(new_release_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_response_file("releases/get-release.json")
+                .with_header_matcher("content-encoding", "gzip")
+                .with_request_matcher(move |request| is_gzip_json(request, &expected))
+                .expect(1),
+        );
+    set_commits_from_large_manifest(&manager, &[]);
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_set_commits_compression_disabled() {
+    let manager = TestManager::new()
+        .mock_endpoint(many_repos_endpoint())
+        .mock_endpoint(new_release_endpoint())
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .with_response_file("releases/get-release.json")
+                .with_header_matcher("content-encoding", Matcher::Missing)
+                .with_matcher(Matcher::PartialJson(large_manifest_refs()))
+                .expect(1),
+        );
+    set_commits_from_large_manifest(
+        &manager,
+        &[(
+            "SENTRY_PROPERTIES",
+            "tests/integration/_fixtures/set_commits/no-compression.properties",
+        )],
+    );
+    manager.assert_mock_endpoints();
+}
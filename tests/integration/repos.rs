@@ -0,0 +1,87 @@
+use mockito::Matcher;
+use serde_json::json;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_repos_help() {
+    TestManager::new().register_trycmd_test("repos/repos-help.trycmd");
+}
+
+#[test]
+fn command_repos_list_empty() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+                .with_response_body("[]"),
+        )
+        .register_trycmd_test("repos/repos-list-empty.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_repos_list_paginated() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+                .with_response_header(
+                    "link",
+                    "<http://localhost/api/0/organizations/wat-org/repos/?cursor=page-2>; \
+                     rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+                )
+                .with_response_file("repos/get-repos.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=page-2")
+                .with_response_file("repos/get-repos-page-2.json")
+                .expect(2),
+        )
+        .register_trycmd_test("repos/repos-list.trycmd")
+        .register_trycmd_test("repos/repos-list-json.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_repos_add() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/repos/")
+                .with_matcher(Matcher::Json(json!({
+                    "provider": "integrations:github",
+                    "name": "getsentry/relay",
+                    "url": "https://github.com/getsentry/relay",
+                })))
+                .with_status(201)
+                .with_response_file("repos/post-repo.json"),
+        )
+        .register_trycmd_test("repos/repos-add.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_repos_add_duplicate() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/repos/")
+                .with_status(409)
+                .with_response_body(r#"{"detail": "A repository with that name already exists"}"#),
+        )
+        .register_trycmd_test("repos/repos-add-duplicate.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_repos_remove() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("DELETE", "/api/0/organizations/wat-org/repos/3/")
+                .with_status(204),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("DELETE", "/api/0/organizations/wat-org/repos/4/")
+                .with_status(404),
+        )
+        .register_trycmd_test("repos/repos-remove*.trycmd")
+        .with_default_token();
+}
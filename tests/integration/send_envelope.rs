@@ -1,4 +1,13 @@
-use crate::integration::{MockEndpointBuilder, TestManager};
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::Command;
+use mockito::Matcher;
+
+use crate::integration::{test_utils::env, MockEndpointBuilder, TestManager};
+
+/// Size of the generated binary attachment, large enough to require streaming.
+const ATTACHMENT_SIZE: usize = 16 * 1024 * 1024;
 
 #[test]
 fn command_send_envelope() {
@@ -6,3 +15,134 @@ fn command_send_envelope() {
         .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/"))
         .register_trycmd_test("send_envelope/*.trycmd");
 }
+
+/// Runs `sentry-cli send-envelope --raw` with the default test environment.
+fn send_raw_envelope(manager: &TestManager, path: &Path) -> Command {
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command.args(["send-envelope", "--raw"]).arg(path);
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    command
+}
+
+/// Creates an envelope with an event, a large binary attachment containing
+/// newlines and a trailing item without a length header.
+fn make_large_envelope() -> Vec<u8> {
+    let event = br#"{"event_id":"22d00b3fd1b14b5d8d2049d138cd8a9c","message":"large envelope"}"#;
+    let attachment: Vec<u8> = (0..ATTACHMENT_SIZE)
+        .map(|i| (i.wrapping_mul(31) % 251) as u8)
+        .collect();
+    let client_report = br#"{"timestamp":1700000000,"discarded_events":[]}"#;
+
+    let mut envelope = b"{\"event_id\":\"22d00b3f-d1b1-4b5d-8d20-49d138cd8a9c\"}\n".to_vec();
+    envelope.extend(format!("{{\"type\":\"event\",\"length\":{}}}\n", event.len()).as_bytes());
+    envelope.extend(event);
+    envelope.push(b'\n');
+    envelope.extend(
+        format!(
+            "{{\"type\":\"attachment\",\"length\":{},\"filename\":\"minidump.dmp\",\
+             \"attachment_type\":\"event.minidump\"}}\n",
+            attachment.len()
+        )
+        .as_bytes(),
+    );
+    envelope.extend(&attachment);
+    envelope.push(b'\n');
+    envelope.extend(b"{\"type\":\"client_report\"}\n");
+    envelope.extend(client_report);
+    envelope.push(b'\n');
+    envelope
+}
+
+#[test]
+fn command_send_envelope_raw_streams_large_envelope() {
+    let envelope = make_large_envelope();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("large.envelope");
+    fs::write(&path, &envelope).unwrap();
+
+    let expected_len = envelope.len();
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+            .with_header_matcher("content-length", Matcher::Exact(expected_len.to_string()))
+            .with_request_matcher(move |request| {
+                request.body().map_or(false, |body| {
+                    body.len() == expected_len && *body == envelope
+                })
+            })
+            .expect(1),
+    );
+
+    send_raw_envelope(&manager, &path)
+        .assert()
+        .success()
+        .stdout(format!(
+            "Envelope from file {} dispatched\n",
+            path.display()
+        ));
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_envelope_raw_rejects_invalid_length() {
+    let dir = tempfile::tempdir().unwrap();
+    let invalid_length = dir.path().join("invalid_length.envelope");
+    fs::write(
+        &invalid_length,
+        "{}\n{\"type\":\"event\",\"length\":\"a lot\"}\n{}\n",
+    )
+    .unwrap();
+    let truncated = dir.path().join("truncated.envelope");
+    fs::write(
+        &truncated,
+        "{}\n{\"type\":\"attachment\",\"length\":1000}\nsome content\n",
+    )
+    .unwrap();
+    let too_long = dir.path().join("too_long.envelope");
+    fs::write(
+        &too_long,
+        "{}\n{\"type\":\"attachment\",\"length\":4}\nsome content\n",
+    )
+    .unwrap();
+
+    let manager = TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect(0));
+
+    for (path, message) in [
+        (
+            invalid_length,
+            "Item 1 has an invalid length header: \"a lot\"",
+        ),
+        (
+            truncated,
+            "Item 1 has a length of 1000 bytes, but only 13 bytes remain in the file",
+        ),
+        (too_long, "Item 1 is longer than its length of 4 bytes"),
+    ] {
+        let output = send_raw_envelope(&manager, &path)
+            .assert()
+            .failure()
+            .get_output()
+            .clone();
+        let stderr = String::from_utf8(output.stderr).expect("stderr should be valid UTF-8");
+        assert!(stderr.contains(message), "missing `{message}` in {stderr}");
+    }
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_envelope_attachments() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::Regex(regex::escape(
+                    r#"{"type":"attachment","length":33,"filename":"config.ini","attachment_type":"event.attachment","content_type":"text/plain"}"#,
+                )))
+                .expect(1),
+        )
+        .register_trycmd_test("send_envelope/attachments/send_envelope-attachments.trycmd")
+        .assert_mock_endpoints();
+}
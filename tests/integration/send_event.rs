@@ -1,3 +1,5 @@
+use mockito::Matcher;
+
 use crate::integration::{MockEndpointBuilder, TestManager};
 
 // I have no idea why this is timing out on Windows.
@@ -17,3 +19,158 @@ fn command_send_event() {
         .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/"))
         .register_trycmd_test("send_event/*.trycmd");
 }
+
+#[test]
+fn command_send_event_from_json_file() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect(1))
+        .register_trycmd_test("send_event/from_json/send_event-from-json-file.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_event_from_json_file_quiet() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect(1))
+        .register_trycmd_test("send_event/from_json/send_event-from-json-file-quiet.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_event_from_json_stdin() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::Regex(
+                    r#""message":"replayed from another system""#.into(),
+                ))
+                .expect(1),
+        )
+        .register_trycmd_test("send_event/from_json/send_event-from-json-stdin.toml")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_event_from_json_overrides() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::AllOf(vec![
+                    Matcher::Regex(r#""release":"override-release""#.into()),
+                    Matcher::Regex(r#""dist":"override-dist""#.into()),
+                ]))
+                .expect(1),
+        )
+        .register_trycmd_test("send_event/from_json/send_event-from-json-overrides.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_event_from_json_invalid_field() {
+    TestManager::new()
+        .register_trycmd_test("send_event/from_json/send_event-from-json-invalid-field.trycmd");
+}
+
+/// Matches the header of an attachment item in an envelope.
+fn attachment_header(header: &str) -> Matcher {
+    Matcher::Regex(regex::escape(header))
+}
+
+#[test]
+fn command_send_event_attachments() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_matcher(Matcher::AllOf(vec![
+                    attachment_header(
+                        r#"{"type":"attachment","length":33,"filename":"config.ini","attachment_type":"event.attachment","content_type":"text/plain"}"#,
+                    ),
+                    attachment_header(
+                        r#"{"type":"attachment","length":28,"filename":"core.dmp","attachment_type":"event.minidump","content_type":"application/octet-stream"}"#,
+                    ),
+                    Matcher::Regex("MDMP fake minidump contents".into()),
+                ]))
+                .expect(1),
+        )
+        .register_trycmd_test("send_event/attachments/send_event-attachments.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_event_attachments_invalid() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect(0))
+        .register_trycmd_test("send_event/attachments/send_event-attachments-missing.trycmd")
+        .register_trycmd_test("send_event/attachments/send_event-attachments-invalid-type.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_event_attachments_server_limit() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(
+                    r#"{
+                        "url": "organizations/wat-org/chunk-upload/",
+                        "chunkSize": 8388608,
+                        "maxFileSize": 30,
+                        "hashAlgorithm": "sha1"
+                    }"#,
+                )
+                .expect(1),
+        )
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect(0))
+        .register_trycmd_test("send_event/attachments/send_event-attachments-server-limit.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+fn form_field(name: &str) -> Matcher {
+    Matcher::Regex(regex::escape(&format!(r#"name="{name}""#)))
+}
+
+#[test]
+fn command_send_event_minidump() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/minidump/")
+                .with_matcher(Matcher::AllOf(vec![
+                    form_field("upload_file_minidump"),
+                    Matcher::Regex("MDMP fake minidump contents".into()),
+                    form_field("sentry"),
+                    Matcher::Regex(regex::escape(r#""tags":{"channel":"beta","ci":"true"}"#)),
+                    Matcher::Regex(regex::escape(r#""release":"1.2.3""#)),
+                    Matcher::Regex(regex::escape(r#""environment":"staging""#)),
+                    form_field("config.ini"),
+                    Matcher::Regex("Content-Type: text/plain".into()),
+                ]))
+                .expect(1),
+        )
+        .register_trycmd_test("send_event/minidump/send_event-minidump.trycmd")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_event_minidump_drops_oversized_attachments() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/minidump/")
+                .with_matcher(form_field("upload_file_minidump"))
+                .expect(1),
+        )
+        .register_trycmd_test("send_event/minidump/send_event-minidump-oversized-attachment.trycmd")
+        .env(
+            "SENTRY_PROPERTIES",
+            "tests/integration/_fixtures/attachments/small-attachments.properties",
+        )
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_event_minidump_missing() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/minidump/").expect(0))
+        .register_trycmd_test("send_event/minidump/send_event-minidump-missing.trycmd")
+        .assert_mock_endpoints();
+}
@@ -35,6 +35,7 @@ fn command_send_metric_increment_no_dsn() {
 
     // Custom test case setup because we don't want the DSN to be set by the manager.
     TestCases::new()
+        .env("RUST_BACKTRACE", "0")
         .case("tests/integration/_cases/send_metric/individual_config/send_metric-increment-no-dsn.trycmd");
 }
 
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use assert_cmd::assert::Assert;
+use assert_cmd::Command;
+
+use crate::integration::{test_utils::env, MockEndpointBuilder, TestManager};
+
+/// Runs sentry-cli with the default test environment.
+fn sentry_cli(manager: &TestManager) -> Command {
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+    command
+}
+
+/// Returns the output of a command on the given stream.
+fn output(assert: &Assert, stderr: bool) -> String {
+    let output = assert.get_output();
+    String::from_utf8_lossy(if stderr {
+        &output.stderr
+    } else {
+        &output.stdout
+    })
+    .into_owned()
+}
+
+fn queued_files(dir: &Path) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn command_send_queued_flushes_unreachable_events() {
+    let queue = tempfile::tempdir().unwrap();
+
+    // Nothing listens on port 1, so the connection is refused
+    let manager = TestManager::new();
+    let assert = sentry_cli(&manager)
+        .env("SENTRY_DSN", "http://test@127.0.0.1:1/1337")
+        .args([
+            "send-event",
+            "-m",
+            "queued event",
+            "--no-environ",
+            "--queue-dir",
+        ])
+        .arg(queue.path())
+        .assert()
+        .success();
+    assert!(output(&assert, false).contains("Event queued in"));
+
+    let queued = queued_files(queue.path());
+    assert_eq!(queued.len(), 1, "expected one queued envelope: {queued:?}");
+    assert!(queued[0].ends_with(".envelope"));
+    let envelope = fs::read_to_string(queue.path().join(&queued[0])).unwrap();
+    assert!(envelope.contains("queued event"));
+
+    let sent = Arc::new(Mutex::new(vec![]));
+    let inspected = sent.clone();
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+            .with_request_inspector(move |request| {
+                let body = request.utf8_lossy_body().unwrap().into_owned();
+                inspected.lock().unwrap().push(body);
+            })
+            .expect(1),
+    );
+    let assert = sentry_cli(&manager)
+        .arg("send-queued")
+        .arg(queue.path())
+        .assert()
+        .success();
+    assert!(output(&assert, false).contains("> Sent 1 queued envelope\n"));
+
+    assert_eq!(queued_files(queue.path()), Vec::<String>::new());
+    manager.assert_mock_endpoints();
+    assert_eq!(*sent.lock().unwrap(), [envelope]);
+}
+
+#[test]
+fn command_send_envelope_queues_unreachable_envelopes() {
+    let queue = tempfile::tempdir().unwrap();
+
+    let manager = TestManager::new();
+    for raw in [false, true] {
+        let mut command = sentry_cli(&manager);
+        command
+            .env("SENTRY_DSN", "http://test@127.0.0.1:1/1337")
+            .arg("send-envelope")
+            .arg("tests/integration/_fixtures/envelope.dat")
+            .arg("--queue-dir")
+            .arg(queue.path());
+        if raw {
+            command.arg("--raw");
+        }
+        let assert = command.assert().success();
+        assert!(output(&assert, false)
+            .contains("Envelope from file tests/integration/_fixtures/envelope.dat queued in"));
+    }
+
+    assert_eq!(queued_files(queue.path()).len(), 2);
+}
+
+#[test]
+fn command_send_queued_keeps_failed_envelopes() {
+    let queue = tempfile::tempdir().unwrap();
+    fs::write(
+        queue.path().join("1700000000000-a.envelope"),
+        "{}\n{\"type\":\"event\"}\n{}\n",
+    )
+    .unwrap();
+
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+            .with_status(400)
+            .expect(1),
+    );
+    let assert = sentry_cli(&manager)
+        .arg("send-queued")
+        .arg(queue.path())
+        .assert()
+        .success();
+    assert!(output(&assert, false).contains("> Kept 1 envelope in the queue\n"));
+
+    assert_eq!(
+        queued_files(queue.path()),
+        ["1700000000000-a.envelope".to_owned()]
+    );
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_queued_drops_expired_envelopes() {
+    let queue = tempfile::tempdir().unwrap();
+    fs::write(
+        queue.path().join("1000-expired.envelope"),
+        "{}\n{\"type\":\"event\"}\n{}\n",
+    )
+    .unwrap();
+    fs::write(queue.path().join("notes.txt"), "not an envelope").unwrap();
+
+    let manager = TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect(0));
+    let assert = sentry_cli(&manager)
+        .args(["send-queued", "--max-age", "3600"])
+        .arg(queue.path())
+        .assert()
+        .success();
+    assert!(output(&assert, false).contains("> Dropped 1 expired envelope\n"));
+    assert!(output(&assert, true).contains("Dropping envelope"));
+
+    assert_eq!(queued_files(queue.path()), ["notes.txt".to_owned()]);
+    manager.assert_mock_endpoints();
+}
+
+#[test]
+fn command_send_queued_help() {
+    TestManager::new().register_trycmd_test("send_queued/*.trycmd");
+}
@@ -0,0 +1,123 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use assert_cmd::Command;
+
+use crate::integration::{test_utils::env, MockEndpointBuilder, TestManager};
+
+/// Extracts the artifact bundle from the body of a chunk upload request of a single chunk.
+fn bundle_from_chunk_upload(body: &[u8]) -> Vec<u8> {
+    let start = body
+        .windows(4)
+        .position(|window| window == b"SYSB")
+        .expect("chunk upload should contain an artifact bundle");
+    let end = body
+        .windows(4)
+        .rposition(|window| window == b"\r\n--")
+        .expect("chunk upload should be a multipart form");
+    body[start..end].to_vec()
+}
+
+#[test]
+fn command_sourcemaps_bundle_info_help() {
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-bundle-info-help.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_bundle_info_fixture() {
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-bundle-info.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_upload_invalid_meta() {
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-upload-invalid-meta.trycmd");
+}
+
+/// This test uploads an artifact bundle with a note and metadata, and ensures that
+/// `bundle-info` reads them back from the uploaded bundle and that they are reported.
+#[test]
+fn command_sourcemaps_upload_metadata_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let report_path = dir.path().join("report.json");
+    let bundle_path = dir.path().join("bundle.zip");
+
+    let uploaded = Arc::new(Mutex::new(vec![]));
+    let inspected = uploaded.clone();
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_request_inspector(move |request| {
+                    let body = request.body().unwrap();
+                    inspected.lock().unwrap().push(body.clone());
+                })
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .with_response_body(r#"{"state":"ok","missingChunks":[]}"#)
+            .expect(1),
+        );
+
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command
+        .args([
+            "sourcemaps",
+            "upload",
+            "tests/integration/_fixtures/bundle.min.js.map",
+            "--note",
+            "pipeline 4812",
+            "--meta",
+            "build=4812",
+            "--meta",
+            "url=https://ci.example.com/?pipeline=4812",
+            "--report",
+        ])
+        .arg(&report_path);
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+    command.assert().success();
+    manager.assert_mock_endpoints();
+
+    let report: serde_json::Value =
+        serde_json::from_slice(&fs::read(&report_path).expect("report should be written"))
+            .expect("report should be valid JSON");
+    assert_eq!(report["note"], "pipeline 4812");
+    assert_eq!(
+        report["meta"],
+        serde_json::json!({
+            "build": "4812",
+            "url": "https://ci.example.com/?pipeline=4812",
+        })
+    );
+
+    let uploaded = uploaded.lock().unwrap();
+    fs::write(&bundle_path, bundle_from_chunk_upload(&uploaded[0])).unwrap();
+
+    let output = Command::cargo_bin("sentry-cli")
+        .expect("sentry-cli should be available")
+        .args(["sourcemaps", "bundle-info"])
+        .arg(&bundle_path)
+        .output()
+        .expect("sentry-cli should run");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be valid UTF-8");
+    assert!(stdout.contains("  Files: 1\n"), "{stdout}");
+    assert!(stdout.contains("  Organization: wat-org\n"), "{stdout}");
+    assert!(stdout.contains("  Note: pipeline 4812\n"), "{stdout}");
+    assert!(
+        stdout.contains(
+            "  Metadata:\n    > build: 4812\n    > url: https://ci.example.com/?pipeline=4812\n"
+        ),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,73 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+const BUNDLE_ENDPOINT: &str = "/api/0/projects/wat-org/wat-project/files/artifact-bundles/\
+                               ?bundleId=2f3c6a8e-1b2d-4e5f-8a9b-0c1d2e3f4a5b";
+
+#[test]
+fn command_sourcemaps_list_bundles() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/artifact-bundles/?cursor=",
+            )
+            .with_response_header(
+                "link",
+                "<http://localhost/api/0/projects/wat-org/wat-project/files/artifact-bundles/\
+                 ?cursor=page-2>; rel=\"next\"; results=\"true\"; cursor=\"page-2\"",
+            )
+            .with_response_file("sourcemaps/get-artifact-bundles-page-1.json")
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/artifact-bundles/?cursor=page-2",
+            )
+            .with_response_file("sourcemaps/get-artifact-bundles-page-2.json")
+            .expect(2),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-list-bundles*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_delete_bundle() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("DELETE", BUNDLE_ENDPOINT)
+                .with_status(204)
+                .expect(2),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-delete-bundle-yes.trycmd")
+        .register_trycmd_test("sourcemaps/sourcemaps-delete-bundle-confirmed.toml")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_delete_bundle_declined() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("DELETE", BUNDLE_ENDPOINT).expect(0))
+        .register_trycmd_test("sourcemaps/sourcemaps-delete-bundle-declined.toml")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_delete_bundle_not_found() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/files/artifact-bundles/\
+                 ?bundleId=7d8e9f0a-3b4c-4d5e-9f6a-1b2c3d4e5f6a",
+            )
+            .with_status(404)
+            .with_response_body(r#"{"detail": "Not found"}"#),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-delete-bundle-not-found.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+/// Creates an empty working directory for the given test case and returns its path.
+fn prepare_cwd(name: &str) -> PathBuf {
+    let cwd = Path::new("tests/integration/_cases/sourcemaps/").join(format!("{name}.in"));
+    if cwd.exists() {
+        fs::remove_dir_all(&cwd).unwrap();
+    }
+    fs::create_dir_all(&cwd).unwrap();
+    cwd
+}
+
+#[test]
+fn command_sourcemaps_download_help() {
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-download-help.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_download_release() {
+    let cwd = prepare_cwd("sourcemaps-download-release");
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_file("sourcemaps/get-download-artifacts.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/1/?download=1",
+            )
+            .with_response_body("console.log('hello world');\n"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/2/?download=1",
+            )
+            .with_status(404)
+            .with_response_body(r#"{"detail": "The requested resource does not exist"}"#),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/3/?download=1",
+            )
+            .with_response_body("export const lib = 42;\n"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/4/?download=1",
+            )
+            .with_response_body("console.log('hello android');\n"),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-download-release.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+
+    assert_eq!(
+        fs::read_to_string(cwd.join("out/static/js/app.js")).unwrap(),
+        "console.log('hello world');\n"
+    );
+    assert_eq!(
+        fs::read_to_string(cwd.join("out/static/js/android/app.js")).unwrap(),
+        "console.log('hello android');\n"
+    );
+    assert_eq!(
+        fs::read_to_string(cwd.join("out/cdn.example.com/vendor/lib.js")).unwrap(),
+        "export const lib = 42;\n"
+    );
+    assert!(!cwd.join("out/static/js/app.js.map").exists());
+}
+
+#[test]
+fn command_sourcemaps_download_url_filter_and_dist() {
+    let cwd = prepare_cwd("sourcemaps-download-url-filter-and-dist");
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_file("sourcemaps/get-download-artifacts.json")
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/3/?download=1",
+            )
+            .with_response_body("export const lib = 42;\n"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/4/?download=1",
+            )
+            .with_response_body("console.log('hello android');\n"),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-download-url-filter-and-dist.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+
+    assert_eq!(
+        fs::read_to_string(cwd.join("out/cdn.example.com/vendor/lib.js")).unwrap(),
+        "export const lib = 42;\n"
+    );
+    assert!(!cwd.join("out/static").exists());
+    assert_eq!(
+        fs::read_to_string(cwd.join("out-android/static/js/android/app.js")).unwrap(),
+        "console.log('hello android');\n"
+    );
+}
+
+#[test]
+fn command_sourcemaps_download_bundle() {
+    let cwd = prepare_cwd("sourcemaps-download-bundle");
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-bundles/3e7c0a4b-d8c8-4ce1-8d8a-0c7d4e2f9a11/files/?cursor=",
+            )
+            .with_response_file("sourcemaps/get-artifact-bundle-files.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-bundles/3e7c0a4b-d8c8-4ce1-8d8a-0c7d4e2f9a11/files/fi4Ly9pbmRleC5qcw==/",
+            )
+            .with_response_body("import './lib/utils';\n"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-bundles/3e7c0a4b-d8c8-4ce1-8d8a-0c7d4e2f9a11/files/fi4Ly9saWIvdXRpbHMuanM=/",
+            )
+            .with_response_body("export default {};\n"),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-download-bundle.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+
+    assert_eq!(
+        fs::read_to_string(cwd.join("out/index.js")).unwrap(),
+        "import './lib/utils';\n"
+    );
+    assert_eq!(
+        fs::read_to_string(cwd.join("out/lib/utils.js")).unwrap(),
+        "export default {};\n"
+    );
+}
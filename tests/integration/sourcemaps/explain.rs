@@ -415,3 +415,83 @@ fn command_sourcemaps_explain_select_frame_out_of_range() {
         .register_trycmd_test("sourcemaps/sourcemaps-explain-select-frame-out-of-range.trycmd")
         .with_default_token();
 }
+
+fn mock_debug_id_lookups(manager: TestManager) -> TestManager {
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/events/43a57a55cd5a4207ac520c03e1dee1b4/json/",
+            )
+            .with_response_file("sourcemaps/get-event-debug-ids.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-lookup/?debug_id=5a10e1d4-5b7e-4c3f-a3b8-0e1f0e2a6f01",
+            )
+            .with_response_file("sourcemaps/get-artifact-lookup-app.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-lookup/?debug_id=3b2e8f4a-1c6d-4e7f-9a0b-c1d2e3f4a502",
+            )
+            .with_response_file("sourcemaps/get-artifact-lookup-empty.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-lookup/?debug_id=9c7f6e1b-2d3a-4b5c-8d9e-0f1a2b3c4d05",
+            )
+            .with_response_file("sourcemaps/get-artifact-lookup-chunk.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-lookup/?debug_id=d4e5f6a7-b8c9-4d0e-9f1a-2b3c4d5e6f06",
+            )
+            .with_response_file("sourcemaps/get-artifact-lookup-release-file.json")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-lookup/?download=1001",
+            )
+            .with_response_file("sourcemaps/get-artifact-lookup-bundle.zip"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/artifact-lookup/?download=1002",
+            )
+            .with_response_file("sourcemaps/get-artifact-lookup-bundle-no-sourcemap.zip"),
+        )
+}
+
+#[test]
+fn command_sourcemaps_explain_debug_id() {
+    mock_debug_id_lookups(TestManager::new())
+        .register_trycmd_test("sourcemaps/sourcemaps-explain-debug-id.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_explain_debug_id_fallback() {
+    mock_debug_id_lookups(TestManager::new())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/ytho-test/files/?cursor=",
+            )
+            .with_response_file("sourcemaps/get-artifacts-empty.json"),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-explain-debug-id-fallback.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -171,3 +171,128 @@ fn command_sourcemaps_inject_complex_extension() {
     TestManager::new()
         .register_trycmd_test("sourcemaps/sourcemaps-inject-complex-extension.trycmd");
 }
+
+/// Reads all files below `dir` into a map from relative path to contents.
+fn read_files(dir: &std::path::Path) -> std::collections::BTreeMap<std::path::PathBuf, Vec<u8>> {
+    let mut files = std::collections::BTreeMap::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        for entry in fs::read_dir(current).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                let contents = fs::read(&path).unwrap();
+                files.insert(path.strip_prefix(dir).unwrap().to_path_buf(), contents);
+            }
+        }
+    }
+    files
+}
+
+#[test]
+fn command_sourcemaps_inject_idempotent() {
+    let first_cwd_path = "tests/integration/_cases/sourcemaps/sourcemaps-inject-idempotent.in/";
+    let second_cwd_path =
+        "tests/integration/_cases/sourcemaps/sourcemaps-inject-idempotent-rerun.in/";
+    for path in [first_cwd_path, second_cwd_path] {
+        if std::path::Path::new(path).exists() {
+            remove_dir_all(path).unwrap();
+        }
+    }
+    copy_recursively("tests/integration/_fixtures/inject_split/", first_cwd_path).unwrap();
+
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-inject-idempotent.trycmd");
+
+    copy_recursively(first_cwd_path, second_cwd_path).unwrap();
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-inject-idempotent-rerun.trycmd");
+
+    // Running inject on already injected files must not change them.
+    let injected = read_files(std::path::Path::new(first_cwd_path));
+    let reinjected = read_files(std::path::Path::new(second_cwd_path));
+    assert_eq!(injected.len(), 4);
+    assert_eq!(injected, reinjected);
+}
+
+#[test]
+fn command_sourcemaps_inject_force() {
+    let testcase_cwd_path = "tests/integration/_cases/sourcemaps/sourcemaps-inject-force.in/";
+    if std::path::Path::new(testcase_cwd_path).exists() {
+        remove_dir_all(testcase_cwd_path).unwrap();
+    }
+    copy_recursively(
+        "tests/integration/_fixtures/inject_split/",
+        testcase_cwd_path,
+    )
+    .unwrap();
+
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-inject-force.trycmd");
+
+    // Both the source file and its sourcemap carry the replaced debug id, and the
+    // code snippet has not been injected a second time.
+    let code = fs::read_to_string(format!("{testcase_cwd_path}code/index.js")).unwrap();
+    let map = fs::read_to_string(format!("{testcase_cwd_path}maps/index.js.map")).unwrap();
+    let debug_id = code
+        .lines()
+        .find_map(|line| line.strip_prefix("//# debugId="))
+        .unwrap();
+    assert_eq!(code.matches(debug_id).count(), 2);
+    assert_eq!(code.matches("_sentryDebugIds").count(), 3);
+    assert!(map.contains(&format!("\"debug_id\":\"{debug_id}\"")));
+}
+
+#[test]
+fn command_sourcemaps_inject_failed() {
+    let testcase_cwd_path =
+        std::path::Path::new("tests/integration/_cases/sourcemaps/sourcemaps-inject-failed.in/");
+    if testcase_cwd_path.exists() {
+        remove_dir_all(testcase_cwd_path).unwrap();
+    }
+    fs::create_dir_all(testcase_cwd_path).unwrap();
+    fs::copy(
+        "tests/integration/_fixtures/inject/server/dummy_embedded.js",
+        testcase_cwd_path.join("dummy_embedded.js"),
+    )
+    .unwrap();
+    fs::write(
+        testcase_cwd_path.join("invalid_embedded.js"),
+        "console.log('hello');\n//# sourceMappingURL=data:application/json;base64,bm90IGpzb24=\n",
+    )
+    .unwrap();
+
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-inject-failed.trycmd");
+
+    // The valid file is still processed even though the other one failed.
+    let file_contents = fs::read_to_string(testcase_cwd_path.join("dummy_embedded.js")).unwrap();
+    assert!(file_contents.contains("//# debugId="));
+}
+
+#[test]
+fn command_sourcemaps_inject_css() {
+    let testcase_cwd_path = "tests/integration/_cases/sourcemaps/sourcemaps-inject-css.in/";
+    if std::path::Path::new(testcase_cwd_path).exists() {
+        remove_dir_all(testcase_cwd_path).unwrap();
+    }
+    copy_recursively("tests/integration/_fixtures/inject_css/", testcase_cwd_path).unwrap();
+    let fixture_files = read_files(std::path::Path::new(testcase_cwd_path));
+
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-inject-css.trycmd");
+
+    // Neither the stylesheet nor the JS files are modified.
+    let injected_files = read_files(std::path::Path::new(testcase_cwd_path));
+    for (path, contents) in &fixture_files {
+        if path.ends_with("styles.css.map") {
+            continue;
+        }
+        assert_eq!(
+            &injected_files[path],
+            contents,
+            "{} was modified",
+            path.display()
+        );
+    }
+
+    // The debug id only ends up in the sourcemap of the stylesheet.
+    let map = fs::read_to_string(format!("{testcase_cwd_path}static/styles.css.map")).unwrap();
+    assert!(map.contains("\"debug_id\":\""));
+}
@@ -1,5 +1,8 @@
 use crate::integration::TestManager;
 
+mod bundle_info;
+mod bundles;
+mod download;
 mod explain;
 mod inject;
 mod resolve;
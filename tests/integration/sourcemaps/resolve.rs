@@ -9,3 +9,8 @@ fn command_sourcemaps_resolve_help() {
 fn command_sourcemaps_resolve() {
     TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-resolve.trycmd");
 }
+
+#[test]
+fn command_sourcemaps_resolve_bundle() {
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-resolve-bundle.trycmd");
+}
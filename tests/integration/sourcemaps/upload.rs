@@ -1,4 +1,13 @@
-use crate::integration::{ChunkOptions, MockEndpointBuilder, ServerBehavior, TestManager};
+use std::fs::remove_dir_all;
+use std::sync::{Arc, Mutex};
+
+use assert_cmd::Command;
+use mockito::Matcher;
+
+use crate::integration::{
+    copy_recursively, init_git_repo, test_utils::env, ChunkOptions, MockEndpointBuilder,
+    ServerBehavior, TestManager,
+};
 
 #[test]
 fn command_sourcemaps_upload_help() {
@@ -10,6 +19,13 @@ fn command_sourcemaps_upload() {
     TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-upload.trycmd");
 }
 
+#[test]
+fn command_sourcemaps_upload_reserved_release() {
+    TestManager::new()
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-reserved-release.trycmd")
+        .with_default_token();
+}
+
 #[test]
 fn command_sourcemaps_upload_successfully_upload_file() {
     TestManager::new()
@@ -51,6 +67,62 @@ fn command_sourcemaps_upload_skip_already_uploaded() {
         .assert_mock_endpoints();
 }
 
+#[test]
+fn command_sourcemaps_upload_skip_already_uploaded_dist() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Legacy, Default::default())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=&checksum=38ed853073df85147960ea3a5bced6170ec389b0&checksum=f3673e2cea68bcb86bb74254a9efaa381d74929f",
+            )
+            .with_response_body(
+                r#"[{
+                    "id": "1336",
+                    "name": "~/bundle.min.js.map",
+                    "dist": "bar",
+                    "headers": {},
+                    "size": 1522,
+                    "sha1": "38ed853073df85147960ea3a5bced6170ec389b0",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                },
+                {
+                    "id": "1337",
+                    "name": "~/vendor.min.js.map",
+                    "dist": "foo",
+                    "headers": {},
+                    "size": 1522,
+                    "sha1": "f3673e2cea68bcb86bb74254a9efaa381d74929f",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                }]"#,
+            )
+            .expect(1),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-skip-already-uploaded-dist.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_skip_already_uploaded_unsupported() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Legacy, Default::default())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=&checksum=38ed853073df85147960ea3a5bced6170ec389b0&checksum=f3673e2cea68bcb86bb74254a9efaa381d74929f",
+            )
+            .with_status(400)
+            .with_response_body(r#"{"detail": "Invalid query"}"#)
+            .expect(1),
+        )
+        .register_trycmd_test(
+            "sourcemaps/sourcemaps-upload-skip-already-uploaded-unsupported.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
 #[test]
 fn command_sourcemaps_upload_no_dedupe() {
     TestManager::new()
@@ -183,3 +255,587 @@ fn command_sourcemaps_upload_complex_extension() {
         .register_trycmd_test("sourcemaps/sourcemaps-upload-complex-extension.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_sourcemaps_upload_from_archive() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-from-archive.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_decompress_suffixes() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-decompress-suffixes.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_strip_common_prefix() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-strip-common-prefix.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_dry_run() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .expect(0),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-dry-run.trycmd")
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-dry-run-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_references() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json")
+                .expect(3),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-references*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_validate() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-validate.trycmd")
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-validate-strict.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_caches_chunk_upload_options() {
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json")
+                .expect(1),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-cached-chunk-upload-options.trycmd")
+        .env("SENTRY_CACHE_DIR", cache_dir.path().to_str().unwrap())
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_no_cache() {
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json")
+                .expect(2),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-no-cache.trycmd")
+        .env("SENTRY_CACHE_DIR", cache_dir.path().to_str().unwrap())
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Mocks the endpoints of an upload to a release, instead of an artifact bundle.
+fn mock_legacy_upload_endpoints(manager: TestManager) -> TestManager {
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release.json")
+                .expect_at_least(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=&checksum=38ed853073df85147960ea3a5bced6170ec389b0",
+            )
+            .with_response_body("[]")
+            .expect_at_least(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/releases/wat-release/assemble/",
+            )
+            .with_response_body(r#"{"state":"created","missingChunks":[]}"#),
+        )
+}
+
+#[test]
+fn command_sourcemaps_upload_legacy_requires_release() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-legacy-no-release.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_artifact_bundle_disabled() {
+    mock_legacy_upload_endpoints(TestManager::new())
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-artifact-bundle-disabled.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_artifact_bundle_not_found() {
+    mock_legacy_upload_endpoints(TestManager::new())
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .with_status(404)
+            .with_response_body(r#"{"detail":"Not found"}"#),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-artifact-bundle-not-found.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_max_file_size() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .with_response_body(r#"{"state":"created","missingChunks":[]}"#)
+            .expect(1),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-max-file-size.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// The server only accepts files of up to 400 bytes, so the two source maps
+/// have to be uploaded in separate artifact bundles.
+#[test]
+fn command_sourcemaps_upload_split_bundles() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload-max-file-size.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .with_response_body(r#"{"state":"created","missingChunks":[]}"#)
+            .expect(2),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-split-bundles.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_dist_from_path() {
+    let assemble_dist = |dist| {
+        MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/artifactbundle/assemble/",
+        )
+        .with_matcher(Matcher::PartialJson(serde_json::json!({ "dist": dist })))
+        .with_response_body(r#"{"state":"created","missingChunks":[]}"#)
+    };
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(2),
+        )
+        .mock_endpoint(assemble_dist("arm64-v8a"))
+        .mock_endpoint(assemble_dist("x86_64"))
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-dist-from-path.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_dist_from_path_no_match() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-dist-from-path-no-match.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// This test ensures that `--report` is written if processing the bundle fails,
+/// with the failure recorded for every file.
+#[test]
+fn command_sourcemaps_upload_report() {
+    let report_dir = tempfile::tempdir().unwrap();
+    let report_path = report_dir.path().join("report.json");
+
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .with_response_body(r#"{"state":"error","missingChunks":[],"detail":"Invalid bundle"}"#)
+            .expect(1),
+        );
+
+    let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+    command
+        .args([
+            "sourcemaps",
+            "upload",
+            "tests/integration/_fixtures/bundle.min.js.map",
+        ])
+        .arg("--report")
+        .arg(&report_path);
+
+    env::set_all(manager.server_info(), |k, v| {
+        command.env(k, v.as_ref());
+    });
+
+    let output = command.output().expect("sentry-cli should run");
+
+    manager.assert_mock_endpoints();
+    assert!(!output.status.success());
+
+    let report: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&report_path).expect("report should be written"))
+            .expect("report should be valid JSON");
+
+    let message = "Failed to process uploaded files: Invalid bundle";
+    assert!(report["duration_secs"].is_number());
+    assert_eq!(report["error"], message);
+    assert_eq!(
+        report["files"],
+        serde_json::json!([{
+            "path": "tests/integration/_fixtures/bundle.min.js.map",
+            "debug_id": null,
+            "type": "source_map",
+            "size": report["files"][0]["size"],
+            "checksum": report["files"][0]["checksum"],
+            "status": "errored",
+            "message": message,
+        }])
+    );
+}
+
+#[test]
+fn command_sourcemaps_upload_css() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-css.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_release_from_git() {
+    let testcase_cwd_path =
+        "tests/integration/_cases/sourcemaps/sourcemaps-upload-release-from-git.in/";
+    if std::path::Path::new(testcase_cwd_path).exists() {
+        remove_dir_all(testcase_cwd_path).unwrap();
+    }
+    copy_recursively(
+        "tests/integration/_fixtures/upload_cjs_mjs/",
+        testcase_cwd_path,
+    )
+    .unwrap();
+    assert_eq!(
+        init_git_repo(testcase_cwd_path),
+        "81f9fe855eef72d9b30b067823c3499b79c1e9e9"
+    );
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .with_matcher(Matcher::PartialJson(serde_json::json!({
+                "version": "81f9fe855eef72d9b30b067823c3499b79c1e9e9",
+            })))
+            .with_response_body(r#"{"state":"created","missingChunks":[]}"#),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-release-from-git.toml")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// This test ensures that symlink cycles do not fail the upload, and that files reached
+/// through several symlinks are uploaded once.
+#[cfg(unix)]
+#[test]
+fn command_sourcemaps_upload_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let dir = tempfile::tempdir().unwrap();
+    let dist = dir.path().join("dist");
+    std::fs::create_dir_all(dist.join("nested")).unwrap();
+    std::fs::copy(
+        "tests/integration/_fixtures/bundle.min.js.map",
+        dist.join("bundle.min.js.map"),
+    )
+    .unwrap();
+    symlink(
+        dist.join("bundle.min.js.map"),
+        dist.join("nested/bundle.min.js.map"),
+    )
+    .unwrap();
+    symlink(&dist, dist.join("nested/parent")).unwrap();
+
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        );
+
+    for follow in ["--follow-symlinks", "--no-follow-symlinks"] {
+        let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+        command
+            .args(["sourcemaps", "upload", "--dry-run", follow])
+            .arg(&dist);
+        env::set_all(manager.server_info(), |k, v| {
+            command.env(k, v.as_ref());
+        });
+
+        let output = command.output().expect("sentry-cli should run");
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).expect("stdout should be valid UTF-8");
+        assert!(stdout.contains("> Found 1 file\n"), "{stdout}");
+
+        let skipped_link = format!(
+            "> Skipped 1 link to files already found:\n  {} (same as {})\n",
+            dist.join("nested/bundle.min.js.map").display(),
+            dist.join("bundle.min.js.map").display()
+        );
+        assert_eq!(
+            stdout.contains(&skipped_link),
+            follow == "--follow-symlinks",
+            "{stdout}"
+        );
+    }
+
+    manager.assert_mock_endpoints();
+}
+
+/// This test uploads two source maps twice with a state file, and ensures that the
+/// second upload only sends the source map modified in between.
+#[test]
+fn command_sourcemaps_upload_state_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let dist = dir.path().join("dist");
+    let state_path = dir.path().join("upload-state.json");
+    std::fs::create_dir(&dist).unwrap();
+    for name in ["bundle.min.js.map", "vendor.min.js.map"] {
+        std::fs::copy(
+            format!("tests/integration/_fixtures/{name}"),
+            dist.join(name),
+        )
+        .unwrap();
+    }
+
+    let uploaded = Arc::new(Mutex::new(vec![]));
+    let inspected = uploaded.clone();
+    let manager = TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("sourcemaps/get-chunk-upload.json")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_request_inspector(move |request| {
+                    let body = request.body().unwrap();
+                    inspected.lock().unwrap().push(body.clone());
+                })
+                .with_response_body("[]")
+                .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .with_response_body(r#"{"state":"ok","missingChunks":[]}"#)
+            .expect(2),
+        );
+
+    let upload = || {
+        let mut command = Command::cargo_bin("sentry-cli").expect("sentry-cli should be available");
+        command
+            .args(["sourcemaps", "upload", "--state-file"])
+            .arg(&state_path)
+            .arg(&dist);
+        env::set_all(manager.server_info(), |k, v| {
+            command.env(k, v.as_ref());
+        });
+        let output = command.output().expect("sentry-cli should run");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).expect("stdout should be valid UTF-8")
+    };
+
+    let stdout = upload();
+    assert!(!stdout.contains("skipped via state"), "{stdout}");
+    let state: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&state_path).expect("state should be written"))
+            .expect("state should be valid JSON");
+    assert_eq!(state["org"], "wat-org");
+    assert_eq!(state["project"], "wat-project");
+    let files = state["files"].as_array().expect("files should be a list");
+    for url in ["~/bundle.min.js.map", "~/vendor.min.js.map"] {
+        assert!(
+            files
+                .iter()
+                .any(|file| file["url"] == url && file["checksum"].is_string()),
+            "{url} should be recorded"
+        );
+    }
+
+    let vendor = std::fs::read_to_string(dist.join("vendor.min.js.map")).unwrap();
+    std::fs::write(
+        dist.join("vendor.min.js.map"),
+        vendor.replace("whoops", "oh no"),
+    )
+    .unwrap();
+
+    let stdout = upload();
+    assert!(
+        stdout.contains("> 1 file unchanged (skipped via state)\n"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("> Bundled 1 file for upload\n"), "{stdout}");
+    manager.assert_mock_endpoints();
+
+    let uploaded = uploaded.lock().unwrap();
+    let contains = |body: &[u8], name: &str| {
+        body.windows(name.len())
+            .any(|window| window == name.as_bytes())
+    };
+    assert!(contains(&uploaded[0], "bundle.min.js.map"));
+    assert!(contains(&uploaded[0], "vendor.min.js.map"));
+    assert!(!contains(&uploaded[1], "bundle.min.js.map"));
+    assert!(contains(&uploaded[1], "vendor.min.js.map"));
+}
@@ -4,6 +4,19 @@ use std::borrow::Cow;
 
 use mockito::ServerGuard;
 
+/// The environment variables that release detection reads before falling back to
+/// the git repository. CI systems set some of them, so tests of the detection from
+/// git remove them.
+pub const RELEASE_DETECTION_VARS: &[&str] = &[
+    "SENTRY_RELEASE",
+    "SOURCE_VERSION",
+    "HEROKU_SLUG_COMMIT",
+    "CODEBUILD_RESOLVED_SOURCE_VERSION",
+    "CIRCLE_SHA1",
+    "CF_PAGES_COMMIT_SHA",
+    "GAE_DEPLOYMENT_ID",
+];
+
 pub struct MockServerInfo {
     url: String,
     host_with_port: String,
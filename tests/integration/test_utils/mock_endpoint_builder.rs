@@ -1,4 +1,20 @@
-use mockito::{IntoHeaderName, Matcher, Mock, ServerGuard};
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use mockito::{IntoHeaderName, Matcher, Mock, Request, ServerGuard};
+
+type RequestMatcher = Arc<dyn Fn(&Request) -> bool + Send + Sync>;
+type RequestInspector = Arc<dyn Fn(&Request) + Send + Sync>;
+
+/// The number of hits expected for a mock endpoint.
+#[derive(Clone, Copy)]
+enum Hits {
+    Exactly(usize),
+    AtLeast(usize),
+}
 
 /// Builder for a mock endpoint.
 ///
@@ -9,8 +25,17 @@ use mockito::{IntoHeaderName, Matcher, Mock, ServerGuard};
 /// The mock is only created once `mock_endpoint()` is called with the builder.
 pub struct MockEndpointBuilder {
     /// Function which takes a mockito::ServerGuard and builds the configured mock
-    /// on that server.
-    builder: Box<dyn FnOnce(&mut ServerGuard) -> Mock>,
+    /// on that server. It is called once for every response of a sequence.
+    builder: Box<dyn Fn(&mut ServerGuard) -> Mock>,
+    /// mockito only supports one request matcher per mock, so they are combined
+    /// when the mock is created.
+    request_matchers: Vec<RequestMatcher>,
+    request_inspectors: Vec<RequestInspector>,
+    /// The status and body of the responses to successive requests.
+    sequence: Vec<(usize, String)>,
+    /// Headers which are only added to one response of the sequence, by its index.
+    sequence_headers: Vec<(usize, &'static str, &'static str)>,
+    hits: Option<Hits>,
 }
 
 impl MockEndpointBuilder {
@@ -22,6 +47,11 @@ impl MockEndpointBuilder {
                     .mock(method, endpoint)
                     .with_header("content-type", "application/json")
             }),
+            request_matchers: vec![],
+            request_inspectors: vec![],
+            sequence: vec![],
+            sequence_headers: vec![],
+            hits: None,
         }
     }
 
@@ -32,12 +62,21 @@ impl MockEndpointBuilder {
         self
     }
 
+    /// Add a header to the response of the mock endpoint.
+    pub fn with_response_header(mut self, key: impl IntoHeaderName, value: &'static str) -> Self {
+        let key = key.into_header_name();
+        self.builder =
+            Box::new(move |server| (self.builder)(server).with_header(key.clone(), value));
+        self
+    }
+
     /// Set the response body of the mock endpoint.
     pub fn with_response_body<T>(mut self, body: T) -> Self
     where
         T: AsRef<[u8]> + 'static,
     {
-        self.builder = Box::new(|server| (self.builder)(server).with_body(body));
+        let body = body.as_ref().to_vec();
+        self.builder = Box::new(move |server| (self.builder)(server).with_body(&body));
         self
     }
 
@@ -46,7 +85,70 @@ impl MockEndpointBuilder {
     pub fn with_response_file(mut self, path: &str) -> Self {
         let response_file = format!("tests/integration/_responses/{path}");
 
-        self.builder = Box::new(|server| (self.builder)(server).with_body_from_file(response_file));
+        self.builder =
+            Box::new(move |server| (self.builder)(server).with_body_from_file(&response_file));
+        self
+    }
+
+    /// Respond to successive requests with the given statuses and bodies, in order.
+    /// Once the sequence is exhausted, the last response is repeated.
+    ///
+    /// Unless `expect` or `expect_at_least` is called, every response of the sequence
+    /// is expected to be sent exactly once. Expectations apply to the total number of
+    /// hits of the endpoint.
+    pub fn with_response_sequence(mut self, responses: Vec<(usize, String)>) -> Self {
+        assert!(!responses.is_empty(), "response sequence must not be empty");
+        self.sequence = responses;
+        self
+    }
+
+    /// Add a header to the response at `index` of the response sequence only. Headers
+    /// added with `with_response_header` are sent with every response.
+    pub fn with_sequence_response_header(
+        mut self,
+        index: usize,
+        key: &'static str,
+        value: &'static str,
+    ) -> Self {
+        assert!(
+            index < self.sequence.len(),
+            "header must be added to a response of the sequence"
+        );
+        self.sequence_headers.push((index, key, value));
+        self
+    }
+
+    /// Set the matcher for the query of the mock endpoint. The endpoint path must not
+    /// contain a query then, as it is matched against the path of requests only.
+    pub fn with_query_matcher(mut self, matcher: impl Into<Matcher>) -> Self {
+        let matcher = matcher.into();
+        self.builder = Box::new(move |server| (self.builder)(server).match_query(matcher.clone()));
+        self
+    }
+
+    /// Abort the response body of the mock endpoint midway, simulating a connection
+    /// which is dropped while the response is being transferred.
+    pub fn with_interrupted_response(mut self) -> Self {
+        self.builder = Box::new(move |server| {
+            (self.builder)(server).with_chunked_body(|_| {
+                Err(io::Error::new(
+                    io::ErrorKind::ConnectionReset,
+                    "connection interrupted",
+                ))
+            })
+        });
+        self
+    }
+
+    /// Delay the response body of the mock endpoint by the given duration, simulating
+    /// a slow server.
+    pub fn with_delayed_response(mut self, delay: Duration) -> Self {
+        self.builder = Box::new(move |server| {
+            (self.builder)(server).with_chunked_body(move |writer| {
+                thread::sleep(delay);
+                writer.write_all(b"[]")
+            })
+        });
         self
     }
 
@@ -54,7 +156,32 @@ impl MockEndpointBuilder {
     /// respond to requests if the response body matches the matcher.
     pub fn with_matcher(mut self, matcher: impl Into<Matcher>) -> Self {
         let matcher = matcher.into();
-        self.builder = Box::new(|server| (self.builder)(server).match_body(matcher));
+        self.builder = Box::new(move |server| (self.builder)(server).match_body(matcher.clone()));
+        self
+    }
+
+    /// Set a function matching the requests of the mock endpoint. The endpoint is only
+    /// reached if the function returns `true` for the request.
+    ///
+    /// Can be called multiple times, in which case all functions must match.
+    pub fn with_request_matcher<F>(mut self, matcher: F) -> Self
+    where
+        F: Fn(&Request) -> bool + Send + Sync + 'static,
+    {
+        self.request_matchers.push(Arc::new(matcher));
+        self
+    }
+
+    /// Call a function with every request matching the mock endpoint, for instance to
+    /// capture the raw request bodies and assert on them once the command finished.
+    ///
+    /// The function is called when the request matches all other criteria of the
+    /// endpoint, even if another endpoint that also matches sends the response.
+    pub fn with_request_inspector<F>(mut self, inspector: F) -> Self
+    where
+        F: Fn(&Request) + Send + Sync + 'static,
+    {
+        self.request_inspectors.push(Arc::new(inspector));
         self
     }
 
@@ -67,7 +194,9 @@ impl MockEndpointBuilder {
     ) -> Self {
         let key = key.into_header_name();
         let matcher = matcher.into();
-        self.builder = Box::new(move |server| (self.builder)(server).match_header(key, matcher));
+        self.builder = Box::new(move |server| {
+            (self.builder)(server).match_header(key.clone(), matcher.clone())
+        });
         self
     }
 
@@ -75,7 +204,7 @@ impl MockEndpointBuilder {
     ///
     /// This expectation is only checked when the created mock is asserted.
     pub fn expect_at_least(mut self, hits: usize) -> Self {
-        self.builder = Box::new(move |server| (self.builder)(server).expect_at_least(hits));
+        self.hits = Some(Hits::AtLeast(hits));
         self
     }
 
@@ -83,12 +212,98 @@ impl MockEndpointBuilder {
     ///
     /// This expectation is only checked when the created mock is asserted.
     pub fn expect(mut self, hits: usize) -> Self {
-        self.builder = Box::new(move |server| (self.builder)(server).expect(hits));
+        self.hits = Some(Hits::Exactly(hits));
         self
     }
 
-    /// Create and return the mock endpoint on the given server.
-    pub(super) fn create(self, server: &mut ServerGuard) -> Mock {
-        (self.builder)(server).create()
+    /// Create and return the mocks of the endpoint on the given server.
+    ///
+    /// A response sequence is served by one mock per response. A shared counter of
+    /// the matched requests makes sure that only the mock of the next response
+    /// matches a request. The counter is advanced by the matcher itself, which
+    /// mockito calls for every mock in the order they were created. The mocks are
+    /// therefore created in reverse, so that the mock of a later response is never
+    /// checked right after the counter was advanced for the same request.
+    pub(super) fn create(self, server: &mut ServerGuard) -> Vec<Mock> {
+        if self.sequence.is_empty() {
+            let mock = (self.builder)(server).match_request(self.request_matcher(|_| true));
+            return vec![expect_hits(mock, self.hits).create()];
+        }
+
+        let matched = Arc::new(AtomicUsize::new(0));
+        let last = self.sequence.len() - 1;
+        let mut mocks: Vec<_> = self
+            .sequence
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, (status, body))| {
+                let matched = matched.clone();
+                let matcher = self.request_matcher(move |_| {
+                    if index == last {
+                        matched.load(Ordering::SeqCst) >= last
+                    } else {
+                        matched
+                            .compare_exchange(index, index + 1, Ordering::SeqCst, Ordering::SeqCst)
+                            .is_ok()
+                    }
+                });
+
+                let mock = self
+                    .sequence_headers
+                    .iter()
+                    .filter(|(header_index, _, _)| *header_index == index)
+                    .fold((self.builder)(server), |mock, (_, key, value)| {
+                        mock.with_header(*key, value)
+                    })
+                    .match_request(matcher)
+                    .with_status(*status)
+                    .with_body(body);
+
+                // All but the last response are sent at most once
+                let hits = match self.hits {
+                    Some(Hits::Exactly(hits)) if index < last => {
+                        Some(Hits::Exactly(usize::from(index < hits)))
+                    }
+                    Some(Hits::AtLeast(hits)) if index < last => {
+                        Some(Hits::AtLeast(usize::from(index < hits)))
+                    }
+                    Some(Hits::Exactly(hits)) => Some(Hits::Exactly(hits.saturating_sub(last))),
+                    Some(Hits::AtLeast(hits)) => Some(Hits::AtLeast(hits.saturating_sub(last))),
+                    None => None,
+                };
+                expect_hits(mock, hits).create()
+            })
+            .collect();
+        mocks.reverse();
+        mocks
+    }
+
+    /// Combines the request matchers and inspectors with the given function into the
+    /// request matcher of a mock.
+    fn request_matcher(
+        &self,
+        matcher: impl Fn(&Request) -> bool + Send + Sync + 'static,
+    ) -> impl Fn(&Request) -> bool + Send + Sync + 'static {
+        let matchers = self.request_matchers.clone();
+        let inspectors = self.request_inspectors.clone();
+        move |request| {
+            // The given function runs last, as it may advance a response sequence
+            let matches = matchers.iter().all(|matcher| matcher(request)) && matcher(request);
+            if matches {
+                for inspector in &inspectors {
+                    inspector(request);
+                }
+            }
+            matches
+        }
+    }
+}
+
+fn expect_hits(mock: Mock, hits: Option<Hits>) -> Mock {
+    match hits {
+        Some(Hits::Exactly(hits)) => mock.expect(hits),
+        Some(Hits::AtLeast(hits)) => mock.expect_at_least(hits),
+        None => mock,
     }
 }
@@ -33,10 +33,10 @@ impl TestManager {
         }
     }
 
-    /// Create a mock endpoint on the mockito test server with the given options.
+    /// Create the mocks of an endpoint on the mockito test server with the given options.
     /// Returns the updated `TestManager` with the new mock endpoint.
     pub fn mock_endpoint(mut self, opts: MockEndpointBuilder) -> Self {
-        self.mocks.push(opts.create(&mut self.server));
+        self.mocks.extend(opts.create(&mut self.server));
         self
     }
 
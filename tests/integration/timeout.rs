@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+fn slow_releases_manager() -> TestManager {
+    TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-project/releases/")
+            .with_delayed_response(Duration::from_secs(3)),
+    )
+}
+
+#[test]
+fn command_fails_when_exceeding_timeout() {
+    slow_releases_manager()
+        .register_trycmd_test("timeout/timeout-exceeded.trycmd")
+        .with_default_token()
+        .env("SENTRY_HTTP_MAX_RETRIES", "0");
+}
+
+#[test]
+fn command_fails_when_exceeding_configured_timeout() {
+    slow_releases_manager()
+        .register_trycmd_test("timeout/timeout-exceeded-config.trycmd")
+        .with_default_token()
+        .env(
+            "SENTRY_PROPERTIES",
+            "tests/integration/_fixtures/timeout/timeout.properties",
+        )
+        .env("SENTRY_HTTP_MAX_RETRIES", "0");
+}
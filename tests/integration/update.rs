@@ -1,6 +1,85 @@
-use crate::integration::TestManager;
+use crate::integration::{MockEndpointBuilder, TestManager, VERSION};
 
 #[test]
 fn command_update() {
     TestManager::new().register_trycmd_test("update/*.trycmd");
 }
+
+/// Returns a release registry response for the given version.
+fn registry_release(version: &str) -> String {
+    format!(
+        r#"{{
+            "version": "{version}",
+            "file_urls": {{
+                "sentry-cli-Darwin-universal": "https://example.com/sentry-cli-Darwin-universal",
+                "sentry-cli-Linux-aarch64": "https://example.com/sentry-cli-Linux-aarch64",
+                "sentry-cli-Linux-x86_64": "https://example.com/sentry-cli-Linux-x86_64",
+                "sentry-cli-Windows-x86_64.exe": "https://example.com/sentry-cli-Windows-x86_64.exe"
+            }}
+        }}"#
+    )
+}
+
+/// Mocks the stable and rc feeds of the release registry.
+fn mock_release_registry(manager: TestManager) -> TestManager {
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/latest")
+                .with_response_body(registry_release(VERSION))
+                .expect_at_least(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/latest-rc")
+                .with_response_body(registry_release("99.0.0-rc.1"))
+                .expect_at_least(0),
+        )
+}
+
+#[test]
+fn command_update_check_up_to_date() {
+    let manager = mock_release_registry(TestManager::new());
+    let registry_url = manager.server_url();
+    manager
+        .register_trycmd_test("update/check/update-check-up-to-date.trycmd")
+        .env("SENTRY_RELEASE_REGISTRY_URL", registry_url);
+}
+
+#[test]
+fn command_update_check_rc_available() {
+    let manager = mock_release_registry(TestManager::new());
+    let registry_url = manager.server_url();
+    manager
+        .register_trycmd_test("update/check/update-check-rc-available.trycmd")
+        .env("SENTRY_RELEASE_REGISTRY_URL", registry_url);
+}
+
+#[test]
+fn command_update_check_config_channel() {
+    let manager = mock_release_registry(TestManager::new());
+    let registry_url = manager.server_url();
+    manager
+        .register_trycmd_test("update/check/update-check-config-channel.trycmd")
+        .env("SENTRY_RELEASE_REGISTRY_URL", registry_url)
+        .env("SENTRY_UPDATE_CHANNEL", "rc");
+}
+
+#[test]
+fn command_update_check_registry_error() {
+    let manager = TestManager::new().mock_endpoint(
+        MockEndpointBuilder::new("GET", "/latest")
+            .with_status(404)
+            .with_response_body("{}"),
+    );
+    let registry_url = manager.server_url();
+    manager
+        .register_trycmd_test("update/check/update-check-registry-error.trycmd")
+        .env("SENTRY_RELEASE_REGISTRY_URL", registry_url)
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_update_check_network_error() {
+    TestManager::new()
+        .register_trycmd_test("update/check/update-check-network-error.trycmd")
+        .env("SENTRY_RELEASE_REGISTRY_URL", "http://127.0.0.1:1");
+}
@@ -15,3 +15,15 @@ fn command_upload_proguard() {
 fn command_upload_proguard_no_upload_no_auth_token() {
     TestManager::new().register_trycmd_test("upload_proguard/upload_proguard-no-upload.trycmd");
 }
+
+#[test]
+fn command_upload_proguard_uuid_matches_debug_files_check() {
+    TestManager::new()
+        .register_trycmd_test("upload_proguard/uuid/upload_proguard-deterministic-uuid.trycmd");
+}
+
+#[test]
+fn command_upload_proguard_explicit_uuid_wins() {
+    TestManager::new()
+        .register_trycmd_test("upload_proguard/uuid/upload_proguard-forced-uuid.trycmd");
+}